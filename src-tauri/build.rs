@@ -1,17 +1,42 @@
 use std::env;
 use std::fs;
 use std::path::Path;
-use std::process::Command;
+
+// Shared with the runtime `update_sidecar` command
+#[allow(dead_code)]
+#[path = "src/sidecar_download.rs"]
+mod sidecar_download;
 
 fn main() {
     // Get the target triple for the current build
     let target = env::var("TARGET")
         .unwrap_or_else(|_| env::var("HOST").unwrap_or_else(|_| String::from("unknown")));
+    // Lets `update_sidecar` pick the matching release asset at runtime
+    println!("cargo:rustc-env=PROXYPAL_TARGET={}", target);
+    println!("cargo:rerun-if-env-changed=CLIPROXY_BINARY_PATH");
 
     let binary_name = get_binary_name(&target);
     let binaries_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("binaries");
     let binary_path = binaries_dir.join(&binary_name);
 
+    // Offline builds: use a binary the caller already has
+    if let Some(source) = env::var_os("CLIPROXY_BINARY_PATH").filter(|p| !p.is_empty()) {
+        let source = Path::new(&source);
+        println!("cargo:rerun-if-changed={}", source.display());
+        let result = fs::read(source)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| sidecar_download::install_binary(&bytes, &binary_path));
+        if let Err(e) = result {
+            panic!(
+                "CLIPROXY_BINARY_PATH={} is not a usable CLIProxyAPI binary: {}",
+                source.display(),
+                e
+            );
+        }
+        tauri_build::build();
+        return;
+    }
+
     let is_ci = env::var("CI").is_ok();
     // CARGO_PRIMARY_PACKAGE is set during check/build of the workspace root package.
     // For `cargo check`, Tauri doesn't bundle sidecars, so we can skip validation.
@@ -52,7 +77,14 @@ fn main() {
             );
         } else {
             println!("cargo:warning=Downloading sidecar from CLIProxyAPI releases...");
-            download_binary(&binary_name, &binaries_dir);
+            if let Err(e) = download_binary(&target, &binary_path) {
+                panic!(
+                    "Failed to download sidecar binary {}: {}\n\
+                    Download it manually and build with CLIPROXY_BINARY_PATH=/path/to/cli-proxy-api,\n\
+                    or run: pnpm update-sidecar --force",
+                    binary_name, e
+                );
+            }
         }
     }
 
@@ -60,123 +92,23 @@ fn main() {
 }
 
 /// Validate that the file is a real executable, not a gzip archive or other invalid format.
-/// Checks the file's magic bytes:
-///   - gzip: starts with 0x1f 0x8b
-///   - Mach-O 64-bit: starts with 0xcf 0xfa 0xed 0xfe
-///   - ELF: starts with 0x7f 'E' 'L' 'F'
-///   - PE (Windows): starts with 'M' 'Z'
 fn is_valid_binary(path: &Path) -> bool {
-    let Ok(bytes) = fs::read(path) else {
-        return false;
-    };
-    if bytes.len() < 4 {
-        return false;
-    }
-
-    // Reject gzip archives (0x1f 0x8b)
-    if bytes[0] == 0x1f && bytes[1] == 0x8b {
-        return false;
-    }
-
-    // Accept known executable formats
-    let is_macho = bytes[0] == 0xcf && bytes[1] == 0xfa && bytes[2] == 0xed && bytes[3] == 0xfe;
-    let is_elf = bytes[0] == 0x7f && bytes[1] == b'E' && bytes[2] == b'L' && bytes[3] == b'F';
-    let is_pe = bytes[0] == b'M' && bytes[1] == b'Z';
-
-    is_macho || is_elf || is_pe
+    fs::read(path)
+        .map(|bytes| sidecar_download::is_executable(&bytes))
+        .unwrap_or(false)
 }
 
-/// Download the sidecar binary using the cross-platform Node.js script.
-/// Falls back to platform-specific shell scripts if Node is unavailable.
-fn download_binary(binary_name: &str, binaries_dir: &Path) {
-    let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
-    let node_script = project_root.join("scripts").join("update-sidecar.mjs");
-
-    // Prefer the cross-platform Node.js script
-    if node_script.exists() {
-        let status = Command::new("node")
-            .arg(&node_script)
-            .arg("--force")
-            .status();
-
-        match status {
-            Ok(s) if s.success() => {
-                println!("cargo:warning=Sidecar binary downloaded successfully");
-                return;
-            }
-            Ok(s) => {
-                println!(
-                    "cargo:warning=Node script exited with code {:?}, trying shell fallback",
-                    s.code()
-                );
-            }
-            Err(e) => {
-                println!(
-                    "cargo:warning=Failed to run node ({}), trying shell fallback",
-                    e
-                );
-            }
-        }
-    }
-
-    // Fallback to platform-specific shell scripts in src-tauri/scripts/
-    let scripts_dir = binaries_dir.parent().unwrap().join("scripts");
-
-    #[cfg(not(windows))]
-    {
-        let script_path = scripts_dir.join("download-binaries.sh");
-        if script_path.exists() {
-            let status = Command::new("bash")
-                .arg(&script_path)
-                .arg(binary_name)
-                .status()
-                .expect("Failed to execute download script");
-
-            if !status.success() {
-                panic!(
-                    "Failed to download sidecar binary: {}.\n\
-                    Run manually: pnpm update-sidecar --force\n\
-                    Or: bash src-tauri/scripts/download-binaries.sh {}",
-                    binary_name, binary_name
-                );
-            }
-        } else {
-            panic!(
-                "Sidecar binary missing: {}.\n\
-                Run: pnpm update-sidecar --force",
-                binary_name
-            );
-        }
-    }
-
-    #[cfg(windows)]
-    {
-        let script_path = scripts_dir.join("download-binaries.ps1");
-        if script_path.exists() {
-            let status = Command::new("powershell")
-                .arg("-ExecutionPolicy")
-                .arg("Bypass")
-                .arg("-File")
-                .arg(&script_path)
-                .arg(binary_name)
-                .status()
-                .expect("Failed to execute download script");
-
-            if !status.success() {
-                panic!(
-                    "Failed to download sidecar binary: {}.\n\
-                    Run manually: pnpm update-sidecar --force",
-                    binary_name
-                );
-            }
-        } else {
-            panic!(
-                "Sidecar binary missing: {}.\n\
-                Run: pnpm update-sidecar --force",
-                binary_name
-            );
-        }
-    }
+/// Download the latest release for `target`, verified against its published checksum.
+fn download_binary(target: &str, binary_path: &Path) -> Result<(), String> {
+    let client = sidecar_download::client(reqwest::blocking::Client::builder())?;
+    let repo = sidecar_download::release_repo();
+    let version = sidecar_download::latest_version(&client, &repo)?;
+    sidecar_download::download_verified(&client, &repo, &version, target, binary_path)?;
+    println!(
+        "cargo:warning=Sidecar binary downloaded and verified: {} v{}",
+        repo, version
+    );
+    Ok(())
 }
 
 fn get_binary_name(target: &str) -> String {
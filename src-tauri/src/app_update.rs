@@ -0,0 +1,317 @@
+//! Checks for new ProxyPal releases.
+//!
+//! Where the updater plugin can replace the install (macOS, Windows, AppImage)
+//! it is asked first, so "available" means a signed update is ready to install.
+//! Elsewhere, or when the updater manifest can't be fetched, the GitHub
+//! releases API is read instead and the user is sent to the release page.
+//!
+//! With `auto_check_app_updates` on, a background task checks shortly after
+//! launch and then once a day. Startup never waits on it, and a failed check
+//! (offline, rate limited) is only logged and retried on a later pass.
+//! `app-update-available` is emitted for releases the user hasn't dismissed.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_updater::UpdaterExt;
+
+use crate::error::{CommandError, ErrorCode};
+use crate::http::HttpClients;
+use crate::state::AppState;
+use crate::types::AppUpdateInfo;
+
+const RELEASES_REPO: &str = "heyhuynhgiabuu/proxypal";
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Leave launch to the proxy and the UI before touching the network
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(30);
+
+/// How often the background task wakes to see whether a check is due
+const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const CHECK_INTERVAL_SECS: i64 = 24 * 60 * 60;
+
+/// Whether the updater can install updates in place, and why not if it can't
+pub(crate) fn auto_install_support() -> (bool, &'static str) {
+    #[cfg(target_os = "linux")]
+    {
+        // Only an AppImage can be replaced; .deb/.rpm installs belong to the package manager
+        if std::env::var("APPIMAGE").is_ok() {
+            (true, "AppImage supports auto-update")
+        } else {
+            (false, "Auto-update is only supported for AppImage installations. Please download the new version manually from GitHub Releases.")
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        (true, "Auto-update supported")
+    }
+}
+
+/// Numeric parts of a dotted version; a leading "v" and any pre-release or
+/// build suffix are ignored
+fn version_parts(version: &str) -> Vec<u64> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    core.split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Whether `latest` is a later version than `current`
+pub(crate) fn is_newer(latest: &str, current: &str) -> bool {
+    let (mut latest, mut current) = (version_parts(latest), version_parts(current));
+    let len = latest.len().max(current.len());
+    latest.resize(len, 0);
+    current.resize(len, 0);
+    latest > current
+}
+
+/// Whether a background check is due, given the last successful one
+pub(crate) fn check_due(last_check: Option<i64>, now: i64) -> bool {
+    // A last check in the future means the clock was moved back
+    last_check.is_none_or(|last| now - last >= CHECK_INTERVAL_SECS || last > now)
+}
+
+fn release_page(version: &str) -> String {
+    format!(
+        "https://github.com/{}/releases/tag/v{}",
+        RELEASES_REPO, version
+    )
+}
+
+pub(crate) fn latest_release_page() -> String {
+    format!("https://github.com/{}/releases/latest", RELEASES_REPO)
+}
+
+/// Newest published release
+#[derive(Debug, Clone)]
+struct Release {
+    version: String,
+    notes: Option<String>,
+    url: String,
+    installable: bool,
+}
+
+fn update_info(
+    current: &str,
+    release: Option<Release>,
+    dismissed_version: Option<&str>,
+    now: i64,
+) -> AppUpdateInfo {
+    // No release from the updater means the running version is the newest
+    let release = release.unwrap_or_else(|| Release {
+        version: current.to_string(),
+        notes: None,
+        url: release_page(current),
+        installable: false,
+    });
+    let available = is_newer(&release.version, current);
+    AppUpdateInfo {
+        current: current.to_string(),
+        dismissed: available && dismissed_version == Some(release.version.as_str()),
+        can_install: available && release.installable,
+        latest: release.version,
+        available,
+        notes: release.notes,
+        url: release.url,
+        checked_at: now,
+    }
+}
+
+// Ok(None) when the updater has nothing newer
+async fn from_updater(app: &AppHandle) -> Result<Option<Release>, String> {
+    let update = app
+        .updater_builder()
+        .timeout(CHECK_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(update.map(|update| Release {
+        url: release_page(&update.version),
+        notes: update.body.filter(|notes| !notes.trim().is_empty()),
+        version: update.version,
+        installable: true,
+    }))
+}
+
+async fn from_github(http: &HttpClients) -> Result<Release, String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/releases/latest",
+        RELEASES_REPO
+    );
+    let response = http
+        .external()
+        .get(&url)
+        .header("User-Agent", "ProxyPal")
+        .header("Accept", "application/vnd.github+json")
+        .timeout(CHECK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Could not reach GitHub: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("GitHub releases returned {}", response.status()));
+    }
+    let release: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid release info: {}", e))?;
+    let version = release["tag_name"]
+        .as_str()
+        .map(|tag| tag.trim_start_matches('v').to_string())
+        .ok_or_else(|| format!("No tag_name in the latest release of {}", RELEASES_REPO))?;
+    Ok(Release {
+        notes: release["body"]
+            .as_str()
+            .filter(|notes| !notes.trim().is_empty())
+            .map(str::to_string),
+        url: release["html_url"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| release_page(&version)),
+        version,
+        installable: false,
+    })
+}
+
+// GitHub couldn't be asked, or its answer didn't make sense
+fn upstream(message: String) -> CommandError {
+    CommandError::new(ErrorCode::Upstream, message)
+}
+
+/// Look for a newer release. A successful check is remembered for
+/// `get_app_update` and recorded in the config.
+pub(crate) async fn check(app: &AppHandle) -> Result<AppUpdateInfo, CommandError> {
+    let state = app.state::<AppState>();
+    // The result is written to the config, which must be loaded first
+    state.ensure_hydrated()?;
+    let current = app.package_info().version.to_string();
+
+    let release = if auto_install_support().0 {
+        match from_updater(app).await {
+            Ok(release) => release,
+            Err(e) => {
+                // Unsigned builds and missing manifests still get the release page
+                eprintln!("[ProxyPal] Updater check failed, asking GitHub: {}", e);
+                Some(from_github(&state.http).await.map_err(upstream)?)
+            }
+        }
+    } else {
+        Some(from_github(&state.http).await.map_err(upstream)?)
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let (info, config) = {
+        let mut config = state.config.lock();
+        let info = update_info(
+            &current,
+            release,
+            config.dismissed_app_version.as_deref(),
+            now,
+        );
+        config.last_app_update_check = Some(now);
+        (info, config.clone())
+    };
+    let saved =
+        tauri::async_runtime::spawn_blocking(move || crate::config::save_config_to_file(&config))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|saved| saved);
+    if let Err(e) = saved {
+        eprintln!("[ProxyPal] Failed to record the update check: {}", e);
+    }
+    *state.app_update.lock() = Some(info.clone());
+    Ok(info)
+}
+
+/// Check in the background shortly after launch and then daily, while
+/// `auto_check_app_updates` is on
+pub(crate) fn start_update_checker(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_CHECK_DELAY).await;
+        let mut first = true;
+        loop {
+            let due = {
+                let state = app.state::<AppState>();
+                let config = state.config.lock();
+                // Every launch checks once, so a release found yesterday is
+                // announced again until it is installed or dismissed
+                config.auto_check_app_updates
+                    && (first
+                        || check_due(config.last_app_update_check, chrono::Utc::now().timestamp()))
+            };
+            if due {
+                match check(&app).await {
+                    Ok(info) => {
+                        first = false;
+                        if info.available && !info.dismissed {
+                            let _ = app.emit("app-update-available", &info);
+                        }
+                    }
+                    Err(e) => eprintln!("[ProxyPal] App update check failed: {}", e),
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str) -> Release {
+        Release {
+            version: version.to_string(),
+            notes: Some("Fixes".to_string()),
+            url: release_page(version),
+            installable: true,
+        }
+    }
+
+    #[test]
+    fn compares_versions_numerically() {
+        assert!(is_newer("0.4.10", "0.4.9"));
+        assert!(is_newer("v1.0.0", "0.9.12"));
+        assert!(is_newer("0.5", "0.4.9"));
+        assert!(!is_newer("0.4.9", "0.4.9"));
+        assert!(!is_newer("0.4.0", "0.4"));
+        assert!(!is_newer("0.4.8", "0.4.9"));
+        // Pre-release suffixes don't make an older version look newer
+        assert!(!is_newer("0.4.9-beta.2", "0.4.9"));
+    }
+
+    #[test]
+    fn daily_checks_are_due_after_a_day() {
+        let now = 1_750_000_000;
+        assert!(check_due(None, now));
+        assert!(!check_due(Some(now - 3600), now));
+        assert!(check_due(Some(now - CHECK_INTERVAL_SECS), now));
+        assert!(check_due(Some(now + 3600), now));
+    }
+
+    #[test]
+    fn dismissing_a_version_only_hides_that_version() {
+        let info = update_info("0.4.9", Some(release("0.5.0")), Some("0.4.12"), 1);
+        assert!(info.available && !info.dismissed && info.can_install);
+        assert_eq!(info.notes.as_deref(), Some("Fixes"));
+
+        let info = update_info("0.4.9", Some(release("0.5.0")), Some("0.5.0"), 1);
+        assert!(info.available && info.dismissed);
+    }
+
+    #[test]
+    fn up_to_date_installs_report_nothing_to_install() {
+        let info = update_info("0.4.9", None, None, 1);
+        assert_eq!(info.latest, "0.4.9");
+        assert!(!info.available && !info.can_install && !info.dismissed);
+
+        // A GitHub release older than a local build
+        let info = update_info("0.5.1", Some(release("0.5.0")), None, 1);
+        assert!(!info.available && !info.can_install);
+    }
+}
@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 use tokio::process::Command;
 use tokio::sync::Notify;
+use parking_lot::Mutex;
 
 use crate::types::cloudflare::CloudflareConfig;
 
@@ -305,14 +306,17 @@ impl CloudflareManager {
             }
         });
 
-        tunnels.lock().unwrap().insert(config_id, RunningTunnel {
-            notify_stop,
-            handle,
-        });
+        tunnels.lock().insert(
+            config_id,
+            RunningTunnel {
+                notify_stop,
+                handle,
+            },
+        );
     }
 
     pub fn disconnect(&self, id: &str) {
-        let mut tunnels = self.tunnels.lock().unwrap();
+        let mut tunnels = self.tunnels.lock();
         if let Some(tunnel) = tunnels.remove(id) {
             tunnel.notify_stop.notify_one();
         }
@@ -321,7 +325,7 @@ impl CloudflareManager {
     #[allow(dead_code)]
     pub fn disconnect_all(&self) {
         println!("[Cloudflare Manager] Shutting down all tunnels...");
-        let mut tunnels = self.tunnels.lock().unwrap();
+        let mut tunnels = self.tunnels.lock();
         for (id, tunnel) in tunnels.iter() {
             println!("[Cloudflare Manager] Stopping tunnel: {}", id);
             tunnel.notify_stop.notify_one();
@@ -331,11 +335,11 @@ impl CloudflareManager {
 
     #[allow(dead_code)]
     pub fn get_status(&self, id: &str) -> String {
-       let tunnels = self.tunnels.lock().unwrap();
-       if tunnels.contains_key(id) {
-           "active".to_string()
-       } else {
-           "inactive".to_string()
-       }
+        let tunnels = self.tunnels.lock();
+        if tunnels.contains_key(id) {
+            "active".to_string()
+        } else {
+            "inactive".to_string()
+        }
     }
 }
@@ -0,0 +1,345 @@
+//! Agent setup bundles ("team setup").
+//!
+//! A bundle is a JSON file with the parts of an agent setup a team wants to
+//! share: per-agent model preferences, client key labels, Amp model mappings
+//! and which agents to configure. Key values, the proxy API key and provider
+//! credentials are never written to it. On import the local endpoint and keys
+//! are used, and any client key named in the bundle is issued fresh.
+
+use std::collections::BTreeMap;
+
+use tauri::State;
+
+use crate::config::AppConfig;
+use crate::error::CommandError;
+use crate::helpers::config_writer::unified_diff;
+use crate::state::AppState;
+use crate::types::{
+    AgentBundle, AgentBundleImport, AgentModelPrefs, BundleClientKey, ClientKey, FilePreview,
+};
+
+const BUNDLE_VERSION: u32 = 1;
+
+fn build_bundle(config: &AppConfig, agents: Vec<String>) -> AgentBundle {
+    AgentBundle {
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().timestamp_millis() as u64,
+        agents,
+        agent_model_preferences: config
+            .agent_model_preferences
+            .iter()
+            .map(|(id, prefs)| (id.clone(), prefs.clone()))
+            .collect(),
+        client_keys: config
+            .client_keys
+            .iter()
+            .map(|key| BundleClientKey {
+                name: key.name.clone(),
+                agent_id: key.agent_id.clone(),
+            })
+            .collect(),
+        model_mappings: config.amp_model_mappings.clone(),
+    }
+}
+
+/// The shared settings as pretty JSON, for the import diff
+fn shared_settings(config: &AppConfig) -> String {
+    let prefs: BTreeMap<_, _> = config.agent_model_preferences.iter().collect();
+    let labels: Vec<BundleClientKey> = build_bundle(config, Vec::new()).client_keys;
+    let value = serde_json::json!({
+        "agentModelPreferences": prefs,
+        "ampModelMappings": config.amp_model_mappings,
+        "clientKeys": labels,
+    });
+    serde_json::to_string_pretty(&value).unwrap_or_default() + "\n"
+}
+
+// Apply a bundle to a copy of `config`. Bundle entries win over local ones
+// with the same agent or mapping name; everything else local is kept. Client
+// keys are only issued for labels with no local match, and never for an
+// agent that already has its own key.
+fn merge_bundle(config: &AppConfig, bundle: &AgentBundle) -> (AppConfig, Vec<ClientKey>) {
+    let mut merged = config.clone();
+    for (agent_id, prefs) in &bundle.agent_model_preferences {
+        let previous = merged
+            .agent_model_preferences
+            .get(agent_id)
+            .cloned()
+            .unwrap_or_default();
+        if previous == *prefs {
+            continue;
+        }
+        if !merged.stale_agent_configs.contains(agent_id) {
+            merged.stale_agent_configs.push(agent_id.clone());
+        }
+        if *prefs == AgentModelPrefs::default() {
+            merged.agent_model_preferences.remove(agent_id);
+        } else {
+            merged
+                .agent_model_preferences
+                .insert(agent_id.clone(), prefs.clone());
+        }
+    }
+
+    for mapping in &bundle.model_mappings {
+        match merged
+            .amp_model_mappings
+            .iter_mut()
+            .find(|m| m.name == mapping.name)
+        {
+            Some(existing) => *existing = mapping.clone(),
+            None => merged.amp_model_mappings.push(mapping.clone()),
+        }
+    }
+
+    let mut created = Vec::new();
+    for label in &bundle.client_keys {
+        let name = label.name.trim();
+        let agent_id = label.agent_id.clone().filter(|id| !id.is_empty());
+        let exists = merged.client_keys.iter().any(|k| match &agent_id {
+            Some(agent_id) => k.agent_id.as_ref() == Some(agent_id),
+            None => k.agent_id.is_none() && k.name == name,
+        });
+        if name.is_empty() || exists {
+            continue;
+        }
+        let key = crate::commands::proxy_keys::new_client_key(name.to_string(), agent_id);
+        merged.client_keys.push(key.clone());
+        created.push(key);
+    }
+    (merged, created)
+}
+
+fn read_bundle(path: &str) -> Result<AgentBundle, CommandError> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| CommandError::io(format!("Failed to read bundle: {}", e)))?;
+    let bundle: AgentBundle = serde_json::from_str(&data)
+        .map_err(|e| CommandError::invalid_input(format!("Invalid agent bundle: {}", e)))?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(CommandError::invalid_input(format!(
+            "This bundle was made by a newer ProxyPal (format {}); update to import it",
+            bundle.version
+        )));
+    }
+    Ok(bundle)
+}
+
+// Write the agent setup to `path` as a shareable bundle. Agents currently
+// configured for ProxyPal are the ones the bundle asks to configure.
+#[tauri::command]
+pub fn export_agent_bundle(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<AgentBundle, CommandError> {
+    let agents = crate::commands::agents::detect_cli_agents(state.clone())
+        .into_iter()
+        .filter(|agent| agent.configured)
+        .map(|agent| agent.id)
+        .collect();
+    let bundle = build_bundle(&state.config.lock(), agents);
+    let data = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize bundle: {}", e))?;
+    std::fs::write(&path, data)
+        .map_err(|e| CommandError::io(format!("Failed to write bundle: {}", e)))?;
+    Ok(bundle)
+}
+
+// Apply a bundle from `path` and configure the agents it lists with the local
+// endpoint and keys. Runs as a dry run unless `dry_run` is false: the result
+// then carries a diff of the settings and a preview of each agent config file.
+#[tauri::command]
+pub async fn import_agent_bundle(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    dry_run: Option<bool>,
+) -> Result<AgentBundleImport, CommandError> {
+    let bundle = read_bundle(&path)?;
+    let dry_run = dry_run.unwrap_or(true);
+
+    let installed: Vec<String> = crate::commands::agents::detect_cli_agents(state.clone())
+        .into_iter()
+        .filter(|agent| agent.installed)
+        .map(|agent| agent.id)
+        .collect();
+    let (agents, skipped): (Vec<String>, Vec<String>) = bundle
+        .agents
+        .iter()
+        .cloned()
+        .partition(|id| installed.contains(id));
+
+    let current = state.config.lock().clone();
+    let (merged, created) = merge_bundle(&current, &bundle);
+    let (before, after) = (shared_settings(&current), shared_settings(&merged));
+    let config_diff = if before == after {
+        String::new()
+    } else {
+        unified_diff(&crate::config::get_config_path(), &before, &after)
+    };
+    let mut report = AgentBundleImport {
+        dry_run,
+        config_diff,
+        client_keys_created: created.iter().map(|k| k.name.clone()).collect(),
+        skipped,
+        ..AgentBundleImport::default()
+    };
+
+    if dry_run {
+        // Keys not issued yet show as the main proxy key in these previews
+        for agent_id in agents {
+            let prefs = merged
+                .agent_model_preferences
+                .get(&agent_id)
+                .cloned()
+                .unwrap_or_default();
+            let result = crate::commands::agents::write_cli_agent_config_with(
+                state.clone(),
+                agent_id.clone(),
+                Vec::new(),
+                Some(true),
+                Some(prefs),
+            )
+            .await;
+            match result {
+                Ok(value) => report.previews.extend(
+                    serde_json::from_value::<Vec<FilePreview>>(value["previews"].clone())
+                        .unwrap_or_default(),
+                ),
+                Err(e) => report.errors.push(format!("{}: {}", agent_id, e)),
+            }
+        }
+        return Ok(report);
+    }
+
+    let mut changes = vec![format!("bundle: {}", path)];
+    changes.extend(
+        report
+            .client_keys_created
+            .iter()
+            .map(|name| format!("clientKeys: {} added", name)),
+    );
+    let stored = crate::commands::config::store_config(&state, merged).map_err(CommandError::io);
+    crate::helpers::audit::record("import_agent_bundle", changes, &stored);
+    stored?;
+    if !created.is_empty() {
+        crate::commands::proxy_keys::apply_keys_to_proxy(&app, state.clone()).await;
+    }
+
+    for agent_id in agents {
+        let changes = vec![format!("agent: {}", agent_id)];
+        let result = crate::commands::agents::write_cli_agent_config(
+            state.clone(),
+            agent_id.clone(),
+            Vec::new(),
+            None,
+        )
+        .await;
+        crate::helpers::audit::record("configure_cli_agent", changes, &result);
+        match result {
+            Ok(_) => report.configured.push(agent_id),
+            Err(e) => report.errors.push(format!("{}: {}", agent_id, e)),
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AmpModelMapping;
+
+    fn client_key(name: &str, agent_id: Option<&str>, key: &str) -> ClientKey {
+        let mut client = crate::commands::proxy_keys::new_client_key(
+            name.to_string(),
+            agent_id.map(String::from),
+        );
+        client.key = key.to_string();
+        client
+    }
+
+    fn mapping(name: &str, alias: &str) -> AmpModelMapping {
+        AmpModelMapping {
+            name: name.to_string(),
+            alias: alias.to_string(),
+            enabled: true,
+            fork: false,
+        }
+    }
+
+    #[test]
+    fn bundle_never_contains_secrets() {
+        let mut config = AppConfig {
+            proxy_api_key: "pp-main-secret".to_string(),
+            client_keys: vec![client_key(
+                "laptop",
+                Some("claude-code"),
+                "pp-client-secret",
+            )],
+            ..AppConfig::default()
+        };
+        config.agent_model_preferences.insert(
+            "claude-code".to_string(),
+            AgentModelPrefs {
+                primary_model: Some("claude-sonnet-4-5".to_string()),
+                ..AgentModelPrefs::default()
+            },
+        );
+
+        let bundle = build_bundle(&config, vec!["claude-code".to_string()]);
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains("pp-main-secret"));
+        assert!(!json.contains("pp-client-secret"));
+        assert!(json.contains("laptop"));
+        assert!(json.contains("claude-sonnet-4-5"));
+    }
+
+    #[test]
+    fn merge_keeps_local_keys_and_issues_missing_ones() {
+        let config = AppConfig {
+            client_keys: vec![client_key("mine", Some("codex"), "pp-local")],
+            amp_model_mappings: vec![mapping("gpt-5", "old"), mapping("local-only", "x")],
+            ..AppConfig::default()
+        };
+        let bundle = AgentBundle {
+            version: BUNDLE_VERSION,
+            agent_model_preferences: BTreeMap::from([(
+                "codex".to_string(),
+                AgentModelPrefs {
+                    reasoning_effort: Some("high".to_string()),
+                    ..AgentModelPrefs::default()
+                },
+            )]),
+            client_keys: vec![
+                BundleClientKey {
+                    name: "team codex".to_string(),
+                    agent_id: Some("codex".to_string()),
+                },
+                BundleClientKey {
+                    name: "team opencode".to_string(),
+                    agent_id: Some("opencode".to_string()),
+                },
+            ],
+            model_mappings: vec![mapping("gpt-5", "new")],
+            ..AgentBundle::default()
+        };
+
+        let (merged, created) = merge_bundle(&config, &bundle);
+        // codex keeps its own key; opencode gets a fresh one
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].agent_id.as_deref(), Some("opencode"));
+        assert!(created[0].key.starts_with("pp-"));
+        assert_eq!(merged.client_keys.len(), 2);
+        assert_eq!(merged.client_keys[0].key, "pp-local");
+
+        assert_eq!(merged.amp_model_mappings.len(), 2);
+        assert_eq!(merged.amp_model_mappings[0].alias, "new");
+        assert_eq!(
+            merged.agent_model_preferences["codex"]
+                .reasoning_effort
+                .as_deref(),
+            Some("high")
+        );
+        assert!(merged.stale_agent_configs.contains(&"codex".to_string()));
+        assert_ne!(shared_settings(&config), shared_settings(&merged));
+    }
+}
@@ -0,0 +1,293 @@
+//! Agent endpoint and environment conflict commands.
+
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::commands::proxy::is_loopback_bind;
+use crate::commands::proxy_keys::active_proxy_api_keys;
+use crate::error::CommandError;
+use crate::helpers::agent_endpoints::{self, ENVIRONMENT};
+use crate::helpers::config_writer::ConfigWriter;
+use crate::helpers::env_conflicts;
+use crate::state::AppState;
+use crate::types::{
+    AgentEndpointAudit, AgentEndpointFix, EndpointConflictKind, EnvConflict, EnvConflictFix,
+};
+
+fn run_audit(state: &AppState) -> AgentEndpointAudit {
+    let home = dirs::home_dir().unwrap_or_default();
+    let profile = crate::commands::agents::get_shell_profile_path().ok();
+    let (_, port) = crate::proxy::lifecycle::client_address(state);
+    let keys = {
+        let config = state.config.lock();
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        active_proxy_api_keys(&config, now)
+    };
+    let found =
+        agent_endpoints::collect(&home, profile.as_deref().map(std::path::Path::new), |var| {
+            std::env::var(var).ok()
+        });
+    agent_endpoints::audit(found, port, &keys)
+}
+
+// Where every detected agent sends its requests, grouped by endpoint, with
+// the agents that point at another port or host, or use a stale key
+#[tauri::command]
+pub fn audit_agent_endpoints(state: State<AppState>) -> AgentEndpointAudit {
+    run_audit(&state)
+}
+
+fn fix(agent_id: &str, source: &str, status: &str, detail: Option<String>) -> AgentEndpointFix {
+    AgentEndpointFix {
+        agent_id: agent_id.to_string(),
+        source: source.to_string(),
+        status: status.to_string(),
+        detail,
+    }
+}
+
+// Point the outliers of a fresh audit (or just `agent_ids`) back at this
+// proxy: agent config files are rewritten as by `configure_cli_agent`, local
+// URLs in the shell profile get the current port. Variables set elsewhere are
+// reported for the user to change.
+#[tauri::command]
+pub async fn fix_agent_endpoints(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    agent_ids: Option<Vec<String>>,
+) -> Result<Vec<AgentEndpointFix>, CommandError> {
+    // The audit reads every agent's config files
+    let handle = app.clone();
+    let audit =
+        tauri::async_runtime::spawn_blocking(move || run_audit(&handle.state::<AppState>()))
+            .await
+            .map_err(|e| CommandError::from(e.to_string()))?;
+    let (_, port) = crate::proxy::lifecycle::client_address(&state);
+    let profile = crate::commands::agents::get_shell_profile_path().ok();
+    let conflicts: Vec<_> = audit
+        .conflicts
+        .into_iter()
+        .filter(|c| {
+            agent_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&c.agent_id))
+        })
+        .collect();
+
+    let mut fixes = Vec::new();
+    let mut rewritten: Vec<String> = Vec::new();
+    let mut profile_conflicts = Vec::new();
+    for conflict in conflicts {
+        let id = conflict.agent_id.as_str();
+        if conflict.source == ENVIRONMENT {
+            fixes.push(fix(
+                id,
+                ENVIRONMENT,
+                "manual",
+                Some(format!(
+                    "Set outside ProxyPal's files; point it at {} wherever you export it and restart ProxyPal",
+                    conflict.expected
+                )),
+            ));
+        } else if profile.as_deref() == Some(conflict.source.as_str()) {
+            profile_conflicts.push(conflict);
+        } else if !rewritten.iter().any(|r| r == id) {
+            rewritten.push(id.to_string());
+            let result = crate::commands::agents::write_cli_agent_config(
+                state.clone(),
+                id.to_string(),
+                Vec::new(),
+                None,
+            )
+            .await;
+            fixes.push(match result {
+                Ok(_) => fix(id, &conflict.source, "updated", None),
+                Err(e) => fix(id, &conflict.source, "failed", Some(e)),
+            });
+        }
+    }
+
+    if let Some(path) = profile.filter(|_| !profile_conflicts.is_empty()) {
+        let profile_path = path.clone();
+        let written = tauri::async_runtime::spawn_blocking(move || {
+            let content = std::fs::read_to_string(&profile_path)
+                .map_err(|e| CommandError::io(format!("Failed to read {}: {}", profile_path, e)))?;
+            Ok::<_, CommandError>(match agent_endpoints::retarget_profile(&content, port) {
+                Some((updated, _)) => ConfigWriter::new(false)
+                    .write(std::path::Path::new(&profile_path), &updated)
+                    .map(|_| true),
+                None => Ok(false),
+            })
+        })
+        .await
+        .map_err(|e| CommandError::from(e.to_string()))??;
+        for conflict in &profile_conflicts {
+            let id = conflict.agent_id.as_str();
+            fixes.push(match (&written, conflict.kind) {
+                (Err(e), _) => fix(id, &path, "failed", Some(e.clone())),
+                (Ok(true), EndpointConflictKind::Port) => fix(
+                    id,
+                    &path,
+                    "updated",
+                    Some("Open a new terminal to pick it up".to_string()),
+                ),
+                _ => fix(
+                    id,
+                    &path,
+                    "manual",
+                    Some(format!(
+                        "Edit the export in your shell profile to use {}",
+                        conflict.expected
+                    )),
+                ),
+            });
+        }
+    }
+
+    let changes = fixes
+        .iter()
+        .filter(|f| f.status == "updated")
+        .map(|f| format!("agent: {} ({})", f.agent_id, f.source))
+        .collect();
+    let result = Ok(fixes);
+    crate::helpers::audit::record("fix_agent_endpoints", changes, &result);
+    result
+}
+
+// This proxy's endpoint as agents should reach it
+fn expected_endpoint(state: &AppState) -> String {
+    let (host, port) = crate::proxy::lifecycle::client_address(state);
+    let host = if is_loopback_bind(&host) {
+        "127.0.0.1".to_string()
+    } else {
+        host
+    };
+    format!("http://{}:{}", host, port)
+}
+
+fn run_env_scan(state: &AppState) -> Vec<EnvConflict> {
+    let expected = expected_endpoint(state);
+    let home = dirs::home_dir().unwrap_or_default();
+    let profile = crate::commands::agents::get_shell_profile_path().ok();
+    let mut conflicts = env_conflicts::scan_env(std::env::vars(), &expected);
+    for path in env_conflicts::profile_files(&home, profile.as_deref().map(std::path::Path::new)) {
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            let source = path.to_string_lossy();
+            conflicts.extend(env_conflicts::scan_profile(&source, &content, &expected));
+        }
+    }
+    conflicts
+}
+
+// Agent env vars (ANTHROPIC_*, OPENAI_BASE_URL, GOOGLE_GEMINI_BASE_URL,
+// CODE_ASSIST_ENDPOINT, AMP_URL) set to a URL other than this proxy's, in
+// ProxyPal's environment or a shell profile
+#[tauri::command]
+pub fn scan_environment_conflicts(state: State<AppState>) -> Vec<EnvConflict> {
+    run_env_scan(&state)
+}
+
+fn env_fix(conflict: &EnvConflict, status: &str, detail: Option<String>) -> EnvConflictFix {
+    EnvConflictFix {
+        variable: conflict.variable.clone(),
+        source: conflict.source.clone(),
+        line: conflict.line,
+        status: status.to_string(),
+        detail,
+    }
+}
+
+// Comment out the conflicting exports of a fresh scan. Only lines inside
+// blocks ProxyPal wrote are touched unless `outside_managed` is set; the rest,
+// and variables in ProxyPal's own environment, are reported for the user.
+#[tauri::command]
+pub fn fix_environment_conflicts(
+    state: State<AppState>,
+    outside_managed: Option<bool>,
+) -> Result<Vec<EnvConflictFix>, CommandError> {
+    let outside_managed = outside_managed.unwrap_or(false);
+    let conflicts = run_env_scan(&state);
+
+    let mut sources: Vec<&str> = Vec::new();
+    for conflict in conflicts.iter().filter(|c| c.source != ENVIRONMENT) {
+        if !sources.contains(&conflict.source.as_str()) {
+            sources.push(&conflict.source);
+        }
+    }
+
+    let mut fixes: Vec<EnvConflictFix> = conflicts
+        .iter()
+        .filter(|c| c.source == ENVIRONMENT)
+        .map(|c| {
+            env_fix(
+                c,
+                "manual",
+                Some(format!(
+                    "Set outside ProxyPal's files; unset {} wherever you export it and restart ProxyPal",
+                    c.variable
+                )),
+            )
+        })
+        .collect();
+    for source in sources {
+        let in_source: Vec<&EnvConflict> =
+            conflicts.iter().filter(|c| c.source == source).collect();
+        let lines: Vec<u32> = in_source.iter().filter_map(|c| c.line).collect();
+        let path = std::path::Path::new(source);
+        let written = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", source, e))
+            .and_then(|content| {
+                let (updated, changed) =
+                    env_conflicts::comment_out(&content, &lines, outside_managed);
+                if !changed.is_empty() {
+                    ConfigWriter::new(false).write(path, &updated)?;
+                }
+                Ok(changed)
+            });
+        for conflict in in_source {
+            fixes.push(match &written {
+                Err(e) => env_fix(conflict, "failed", Some(e.clone())),
+                Ok(changed) if conflict.line.is_some_and(|l| changed.contains(&l)) => env_fix(
+                    conflict,
+                    "updated",
+                    Some("Commented out; open a new terminal to pick it up".to_string()),
+                ),
+                Ok(_) => env_fix(
+                    conflict,
+                    "manual",
+                    Some(format!(
+                        "Outside ProxyPal's block; comment out line {} yourself or allow ProxyPal to",
+                        conflict.line.unwrap_or_default()
+                    )),
+                ),
+            });
+        }
+    }
+
+    let changes = fixes
+        .iter()
+        .filter(|f| f.status == "updated")
+        .map(|f| {
+            format!(
+                "env: {} ({}:{})",
+                f.variable,
+                f.source,
+                f.line.unwrap_or_default()
+            )
+        })
+        .collect();
+    let result = Ok(fixes);
+    crate::helpers::audit::record("fix_environment_conflicts", changes, &result);
+    result
+}
+
+/// Audit in the background and emit `agent-endpoint-conflicts` when some
+/// agents don't point at this proxy. Run after the port changes.
+pub(crate) fn warn_on_conflicts(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let audit = run_audit(&app.state::<AppState>());
+        if !audit.conflicts.is_empty() {
+            let _ = app.emit("agent-endpoint-conflicts", &audit);
+        }
+    });
+}
@@ -3,8 +3,16 @@
 //! Extracted from lib.rs — handles detection and configuration of CLI agents
 //! (Claude Code, Codex, Gemini CLI, etc.) and IDE tools (Cursor, Continue, etc.).
 
+use crate::error::CommandError;
+use crate::helpers::config_writer::ConfigWriter;
+use crate::helpers::docker;
+use crate::proxy::lifecycle;
 use crate::state::AppState;
-use crate::types::{AgentStatus, AvailableModel, DetectedTool};
+use crate::types::{
+    AgentModelPrefs, AgentStatus, ApiProtocol, AvailableModel, ConnectionInfo, ConnectionTarget,
+    DetectedTool, DockerHost, EnvVar, GeminiAuthMode,
+};
+use std::collections::HashMap;
 use tauri::State;
 
 /// Generate a shell environment variable export line using platform-appropriate syntax.
@@ -23,12 +31,171 @@ fn env_export_line_commented(key: &str, value: &str) -> String {
     format!("# {}", env_export_line(key, value))
 }
 
+/// Like `connection_info`, for a client in a container reaching the proxy
+/// through `docker.host`. The snippet is POSIX shell whatever the host OS.
+pub(crate) fn docker_connection_info(
+    protocol: ApiProtocol,
+    docker: &DockerHost,
+    port: u16,
+    api_key: &str,
+) -> ConnectionInfo {
+    let mut info = connection_info(protocol, &docker.host, port, api_key);
+    info.snippet = info
+        .env
+        .iter()
+        .map(|var| format!("export {}=\"{}\"", var.name, var.value))
+        .collect::<Vec<_>>()
+        .join("\n");
+    info.docker = Some(docker.clone());
+    info
+}
+
+/// Base URL, auth header and env vars for a client speaking `protocol` that
+/// reaches the proxy at `host`. `port` is always the proxy port, never the
+/// management port.
+pub(crate) fn connection_info(
+    protocol: ApiProtocol,
+    host: &str,
+    port: u16,
+    api_key: &str,
+) -> ConnectionInfo {
+    let root = format!("http://{}:{}", host, port);
+    let (base_url, example_path, auth_header, env) = match protocol {
+        ApiProtocol::OpenAI => (
+            format!("{}/v1", root),
+            "/chat/completions",
+            "Authorization: Bearer",
+            [
+                ("OPENAI_BASE_URL", format!("{}/v1", root)),
+                ("OPENAI_API_KEY", api_key.to_string()),
+            ],
+        ),
+        // Anthropic SDKs and Claude Code append /v1/messages themselves
+        ApiProtocol::Anthropic => (
+            root.clone(),
+            "/v1/messages",
+            "x-api-key",
+            [
+                ("ANTHROPIC_BASE_URL", root),
+                ("ANTHROPIC_API_KEY", api_key.to_string()),
+            ],
+        ),
+        ApiProtocol::Gemini => (
+            root.clone(),
+            "/v1beta/models/{model}:generateContent",
+            "x-goog-api-key",
+            [
+                ("GOOGLE_GEMINI_BASE_URL", root),
+                ("GEMINI_API_KEY", api_key.to_string()),
+            ],
+        ),
+    };
+    let env: Vec<EnvVar> = env
+        .into_iter()
+        .map(|(name, value)| EnvVar {
+            name: name.to_string(),
+            value,
+        })
+        .collect();
+    let snippet = env
+        .iter()
+        .map(|var| env_export_line(&var.name, &var.value))
+        .collect::<Vec<_>>()
+        .join("\n");
+    ConnectionInfo {
+        protocol,
+        base_url,
+        example_path: example_path.to_string(),
+        auth_header: auth_header.to_string(),
+        api_key: api_key.to_string(),
+        env,
+        snippet,
+        docker: None,
+    }
+}
+
+/// devcontainer.json fragment pointing every protocol's env vars at the proxy,
+/// with the `runArgs` Linux needs for host.docker.internal
+pub(crate) fn devcontainer_snippet(docker: &DockerHost, port: u16, api_key: &str) -> String {
+    let mut remote_env = serde_json::Map::new();
+    for protocol in [
+        ApiProtocol::OpenAI,
+        ApiProtocol::Anthropic,
+        ApiProtocol::Gemini,
+    ] {
+        for var in connection_info(protocol, &docker.host, port, api_key).env {
+            remote_env.insert(var.name, serde_json::Value::String(var.value));
+        }
+    }
+    let mut snippet = serde_json::Map::new();
+    if let Some(flag) = &docker.add_host_flag {
+        snippet.insert("runArgs".to_string(), serde_json::json!([flag]));
+    }
+    snippet.insert(
+        "remoteEnv".to_string(),
+        serde_json::Value::Object(remote_env),
+    );
+    serde_json::to_string_pretty(&serde_json::Value::Object(snippet)).unwrap_or_default()
+}
+
+// Connection details per protocol (all three when `protocol` is omitted), for
+// a client on this machine or, with the Docker target, in a container
+#[tauri::command]
+pub fn get_connection_info(
+    protocol: Option<ApiProtocol>,
+    target: Option<ConnectionTarget>,
+    state: State<AppState>,
+) -> Vec<ConnectionInfo> {
+    let (host, port) = lifecycle::client_address(&state);
+    let config = state.config.lock().clone();
+    let docker =
+        (target == Some(ConnectionTarget::Docker)).then(|| docker::docker_host(&config.proxy_bind));
+    let protocols = match protocol {
+        Some(protocol) => vec![protocol],
+        None => vec![
+            ApiProtocol::OpenAI,
+            ApiProtocol::Anthropic,
+            ApiProtocol::Gemini,
+        ],
+    };
+    protocols
+        .into_iter()
+        .map(|p| match &docker {
+            Some(docker) => docker_connection_info(p, docker, port, &config.proxy_api_key),
+            None => connection_info(p, &host, port, &config.proxy_api_key),
+        })
+        .collect()
+}
+
+// How containers reach the proxy; `installed` tells whether to offer container
+// snippets at all
+#[tauri::command]
+pub fn get_docker_host(state: State<AppState>) -> DockerHost {
+    let bind = state.config.lock().proxy_bind.clone();
+    docker::docker_host(&bind)
+}
+
+// devcontainer.json fragment (remoteEnv, plus runArgs on Linux) for reaching
+// the proxy from a devcontainer, with the agent's own key when it has one
+#[tauri::command]
+pub fn get_devcontainer_snippet(agent_id: Option<String>, state: State<AppState>) -> String {
+    let (_, port) = lifecycle::client_address(&state);
+    let config = state.config.lock().clone();
+    let api_key = match &agent_id {
+        Some(agent_id) => crate::commands::proxy_keys::agent_api_key(&config, agent_id),
+        None => config.proxy_api_key.clone(),
+    };
+    let docker = docker::docker_host(&config.proxy_bind);
+    devcontainer_snippet(&docker, port, &api_key)
+}
+
 // Detect installed CLI agents
 #[tauri::command]
 pub fn detect_cli_agents(state: State<AppState>) -> Vec<AgentStatus> {
     let home = dirs::home_dir().unwrap_or_default();
-    let config = state.config.lock().unwrap();
-    let endpoint = format!("http://127.0.0.1:{}", config.port);
+    let (host, port) = lifecycle::client_address(&state);
+    let endpoint = format!("http://{}:{}", host, port);
+    let config = state.config.lock();
     let mut agents = Vec::new();
 
     // 1. Claude Code - uses environment variables
@@ -44,8 +211,10 @@ pub fn detect_cli_agents(state: State<AppState>) -> Vec<AgentStatus> {
         configured: claude_configured,
         config_type: "env".to_string(),
         config_path: None,
-        logo: "/logos/claude.svg".to_string(),
+        logo: crate::providers::logo("claude").to_string(),
         docs_url: "https://help.router-for.me/agent-client/claude-code.html".to_string(),
+        configured_stale: false,
+        protocol: ApiProtocol::Anthropic,
     });
 
     // 2. Codex - uses ~/.codex/config.toml and ~/.codex/auth.json
@@ -67,8 +236,10 @@ pub fn detect_cli_agents(state: State<AppState>) -> Vec<AgentStatus> {
         configured: codex_configured,
         config_type: "file".to_string(),
         config_path: Some(codex_config.to_string_lossy().to_string()),
-        logo: "/logos/openai.svg".to_string(),
+        logo: crate::providers::logo("openai").to_string(),
         docs_url: "https://help.router-for.me/agent-client/codex.html".to_string(),
+        configured_stale: false,
+        protocol: ApiProtocol::OpenAI,
     });
 
     // 3. Gemini CLI - uses environment variables
@@ -84,8 +255,10 @@ pub fn detect_cli_agents(state: State<AppState>) -> Vec<AgentStatus> {
         configured: gemini_configured,
         config_type: "env".to_string(),
         config_path: None,
-        logo: "/logos/gemini.svg".to_string(),
+        logo: crate::providers::logo("gemini").to_string(),
         docs_url: "https://help.router-for.me/agent-client/gemini-cli.html".to_string(),
+        configured_stale: false,
+        protocol: ApiProtocol::Gemini,
     });
 
     // 4. Factory Droid - uses ~/.factory/config.json
@@ -93,7 +266,7 @@ pub fn detect_cli_agents(state: State<AppState>) -> Vec<AgentStatus> {
     let droid_config = home.join(".factory/config.json");
     let droid_configured = if droid_config.exists() {
         std::fs::read_to_string(&droid_config)
-            .map(|c| c.contains(&endpoint) || c.contains("127.0.0.1:8317"))
+            .map(|c| points_at_proxy(&c, &endpoint, port))
             .unwrap_or(false)
     } else {
         false
@@ -109,6 +282,8 @@ pub fn detect_cli_agents(state: State<AppState>) -> Vec<AgentStatus> {
         config_path: Some(droid_config.to_string_lossy().to_string()),
         logo: "/logos/droid.svg".to_string(),
         docs_url: "https://help.router-for.me/agent-client/droid.html".to_string(),
+        configured_stale: false,
+        protocol: ApiProtocol::OpenAI,
     });
 
     // 5. Amp CLI - uses ~/.config/amp/settings.json or AMP_URL env
@@ -117,7 +292,7 @@ pub fn detect_cli_agents(state: State<AppState>) -> Vec<AgentStatus> {
     let amp_configured = check_env_configured("AMP_URL", &endpoint) || {
         if amp_config.exists() {
             std::fs::read_to_string(&amp_config)
-                .map(|c| c.contains(&endpoint) || c.contains("localhost:8317"))
+                .map(|c| points_at_proxy(&c, &endpoint, port))
                 .unwrap_or(false)
         } else {
             false
@@ -134,20 +309,15 @@ pub fn detect_cli_agents(state: State<AppState>) -> Vec<AgentStatus> {
         config_path: Some(amp_config.to_string_lossy().to_string()),
         logo: "/logos/amp.svg".to_string(),
         docs_url: "https://help.router-for.me/agent-client/amp-cli.html".to_string(),
+        configured_stale: false,
+        protocol: ApiProtocol::OpenAI,
     });
 
     // 6. OpenCode - uses opencode.json config file with custom provider
     let opencode_installed = which_exists("opencode");
     // Check for global opencode.json in ~/.config/opencode/opencode.json
     let opencode_global_config = home.join(".config/opencode/opencode.json");
-    let opencode_configured = if opencode_global_config.exists() {
-        // Check if our proxypal provider is configured
-        std::fs::read_to_string(&opencode_global_config)
-            .map(|content| content.contains("proxypal") && content.contains(&endpoint))
-            .unwrap_or(false)
-    } else {
-        false
-    };
+    let opencode_configured = opencode_config_has_proxypal(&opencode_global_config, &endpoint);
 
     agents.push(AgentStatus {
         id: "opencode".to_string(),
@@ -159,15 +329,78 @@ pub fn detect_cli_agents(state: State<AppState>) -> Vec<AgentStatus> {
         config_path: Some(opencode_global_config.to_string_lossy().to_string()),
         logo: "/logos/opencode.svg".to_string(),
         docs_url: "https://opencode.ai/docs/providers/".to_string(),
+        configured_stale: false,
+        protocol: ApiProtocol::OpenAI,
     });
 
+    // 7. Goose - uses ~/.config/goose/config.yaml (OpenAI provider keys)
+    let goose_installed = which_exists("goose");
+    let goose_config = home.join(".config/goose/config.yaml");
+    let goose_configured = std::fs::read_to_string(&goose_config)
+        .map(|c| {
+            c.lines().any(|line| {
+                line.trim_start().starts_with("OPENAI_HOST:") && line.contains(&endpoint)
+            })
+        })
+        .unwrap_or(false);
+
+    agents.push(AgentStatus {
+        id: "goose".to_string(),
+        name: "Goose".to_string(),
+        description: "Block's open-source on-machine AI agent".to_string(),
+        installed: goose_installed,
+        configured: goose_configured,
+        config_type: "both".to_string(),
+        config_path: Some(goose_config.to_string_lossy().to_string()),
+        logo: "/logos/goose.svg".to_string(),
+        docs_url: "https://block.github.io/goose/docs/getting-started/providers".to_string(),
+        configured_stale: false,
+        protocol: ApiProtocol::OpenAI,
+    });
+
+    // 8. Crush - uses ~/.config/crush/crush.json with a custom provider
+    let crush_installed = which_exists("crush");
+    let crush_config = home.join(".config/crush/crush.json");
+    let crush_configured = std::fs::read_to_string(&crush_config)
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .and_then(|json| {
+            json.pointer("/providers/proxypal/base_url")
+                .and_then(|v| v.as_str())
+                .map(|url| url.starts_with(&endpoint))
+        })
+        .unwrap_or(false);
+
+    agents.push(AgentStatus {
+        id: "crush".to_string(),
+        name: "Crush".to_string(),
+        description: "Charm's glamorous terminal coding agent".to_string(),
+        installed: crush_installed,
+        configured: crush_configured,
+        config_type: "both".to_string(),
+        config_path: Some(crush_config.to_string_lossy().to_string()),
+        logo: "/logos/crush.svg".to_string(),
+        docs_url: "https://github.com/charmbracelet/crush#custom-providers".to_string(),
+        configured_stale: false,
+        protocol: ApiProtocol::OpenAI,
+    });
+
+    for agent in agents.iter_mut() {
+        agent.configured_stale = agent.configured && config.stale_agent_configs.contains(&agent.id);
+    }
+
     agents
 }
 
 // Helper to check if a command exists by checking common installation paths
 // Note: Using `which` command doesn't work in production builds (sandboxed macOS app)
 // so we check common binary locations directly
-fn which_exists(cmd: &str) -> bool {
+pub(crate) fn which_exists(cmd: &str) -> bool {
+    find_binary(cmd).is_some()
+}
+
+// Resolve a command to its full path using the same search paths as `which_exists`
+fn find_binary(cmd: &str) -> Option<std::path::PathBuf> {
     let home = dirs::home_dir().unwrap_or_default();
 
     // Common binary installation paths (static)
@@ -306,21 +539,53 @@ fn which_exists(cmd: &str) -> bool {
     // Check all paths
     for path in &paths {
         // Check base command (no extension)
-        if path.join(cmd).exists() {
-            return true;
+        let candidate = path.join(cmd);
+        if candidate.exists() {
+            return Some(candidate);
         }
         // On Windows, also check common executable extensions
         #[cfg(target_os = "windows")]
         {
             for ext in &[".cmd", ".exe", ".bat", ".ps1"] {
-                if path.join(format!("{}{}", cmd, ext)).exists() {
-                    return true;
+                let candidate = path.join(format!("{}{}", cmd, ext));
+                if candidate.exists() {
+                    return Some(candidate);
                 }
             }
         }
     }
 
-    false
+    None
+}
+
+// Check whether opencode.json has a ProxyPal provider pointing at our endpoint
+fn opencode_config_has_proxypal(config_path: &std::path::Path, endpoint: &str) -> bool {
+    let Ok(content) = std::fs::read_to_string(config_path) else {
+        return false;
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return false;
+    };
+    json.pointer("/provider/proxypal/options/baseURL")
+        .and_then(|v| v.as_str())
+        .map(|url| url.starts_with(endpoint))
+        .unwrap_or(false)
+}
+
+// Whether an agent config file points at the proxy: the endpoint in use, or
+// the configured port under either loopback name
+fn points_at_proxy(content: &str, endpoint: &str, port: u16) -> bool {
+    if content.contains(endpoint) {
+        return true;
+    }
+    ["127.0.0.1", "localhost"].iter().any(|host| {
+        let address = format!("{}:{}", host, port);
+        // "localhost:84" must not match "localhost:8440"
+        let port_ends = |at: usize| !content[at..].starts_with(|c: char| c.is_ascii_digit());
+        content
+            .match_indices(&address)
+            .any(|(at, _)| port_ends(at + address.len()))
+    })
 }
 
 // Helper to check if env var is set to expected value
@@ -330,25 +595,167 @@ fn check_env_configured(var: &str, expected_prefix: &str) -> bool {
         .unwrap_or(false)
 }
 
-// Configure a CLI agent with ProxyPal
+/// Pick the model an agent config should use.
+///
+/// An explicit preference wins; otherwise the first live model matching one of
+/// `patterns` (in priority order), then any live model. Returns `None` only when
+/// no preference is set and the proxy reported no models.
+fn pick_model(
+    preferred: Option<&String>,
+    models: &[AvailableModel],
+    patterns: &[&str],
+) -> Option<String> {
+    if let Some(model) = preferred.filter(|m| !m.trim().is_empty()) {
+        return Some(model.trim().to_string());
+    }
+    for pattern in patterns {
+        if let Some(m) = models.iter().find(|m| m.id.contains(pattern)) {
+            return Some(m.id.clone());
+        }
+    }
+    models.first().map(|m| m.id.clone())
+}
+
+// Drop an agent from the stale list once its config has been rewritten
+fn clear_stale_agent_config(state: &State<'_, AppState>, agent_id: &str) {
+    let mut config = state.config.lock();
+    if !config.stale_agent_configs.iter().any(|id| id == agent_id) {
+        return;
+    }
+    config.stale_agent_configs.retain(|id| id != agent_id);
+    if let Err(e) = crate::config::save_config_to_file(&config) {
+        eprintln!("[ProxyPal] Failed to persist stale agent configs: {}", e);
+    }
+}
+
+// Get per-agent model preferences
+#[tauri::command]
+pub fn get_agent_model_preferences(state: State<AppState>) -> HashMap<String, AgentModelPrefs> {
+    state.config.lock().agent_model_preferences.clone()
+}
+
+// Set model preferences for an agent. If the agent was already configured with
+// different preferences, its config is flagged stale until it is regenerated.
+#[tauri::command]
+pub fn set_agent_model_preferences(
+    state: State<AppState>,
+    agent_id: String,
+    prefs: AgentModelPrefs,
+) -> Result<(), CommandError> {
+    if agent_id == "gemini-cli" && prefs.gemini_auth_mode == Some(GeminiAuthMode::ApiKey) {
+        let has_keys = !state.config.lock().gemini_api_keys.is_empty();
+        check_gemini_api_key_mode(has_keys, state.auth_status.lock().gemini)
+            .map_err(CommandError::invalid_input)?;
+    }
+    let config_to_save = {
+        let mut config = state.config.lock();
+        let previous = config
+            .agent_model_preferences
+            .get(&agent_id)
+            .cloned()
+            .unwrap_or_default();
+        if previous != prefs && !config.stale_agent_configs.contains(&agent_id) {
+            config.stale_agent_configs.push(agent_id.clone());
+        }
+        if prefs == AgentModelPrefs::default() {
+            config.agent_model_preferences.remove(&agent_id);
+        } else {
+            config.agent_model_preferences.insert(agent_id, prefs);
+        }
+        config.clone()
+    };
+    crate::commands::config::store_config(&state, config_to_save).map_err(CommandError::io)
+}
+
+// Configure a CLI agent with ProxyPal. With the Docker target nothing is
+// written: the result previews the files to put inside the container.
 #[tauri::command]
 pub async fn configure_cli_agent(
     state: State<'_, AppState>,
     agent_id: String,
     models: Vec<AvailableModel>,
+    dry_run: Option<bool>,
+    target: Option<ConnectionTarget>,
+) -> Result<serde_json::Value, CommandError> {
+    let changes = vec![format!("agent: {}", agent_id)];
+    let result = write_cli_agent_config_for(state, agent_id, models, dry_run, None, target)
+        .await
+        .map_err(CommandError::from);
+    if !dry_run.unwrap_or(false) && target != Some(ConnectionTarget::Docker) {
+        crate::helpers::audit::record("configure_cli_agent", changes, &result);
+    }
+    result
+}
+
+pub(crate) async fn write_cli_agent_config(
+    state: State<'_, AppState>,
+    agent_id: String,
+    models: Vec<AvailableModel>,
+    dry_run: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    write_cli_agent_config_with(state, agent_id, models, dry_run, None).await
+}
+
+// Like `write_cli_agent_config`, but with `prefs` in place of the saved
+// preferences, so a dry run can preview preferences that are not saved yet
+pub(crate) async fn write_cli_agent_config_with(
+    state: State<'_, AppState>,
+    agent_id: String,
+    models: Vec<AvailableModel>,
+    dry_run: Option<bool>,
+    prefs: Option<AgentModelPrefs>,
+) -> Result<serde_json::Value, String> {
+    write_cli_agent_config_for(state, agent_id, models, dry_run, prefs, None).await
+}
+
+// Like `write_cli_agent_config_with`, for an agent running on `target`
+pub(crate) async fn write_cli_agent_config_for(
+    state: State<'_, AppState>,
+    agent_id: String,
+    models: Vec<AvailableModel>,
+    dry_run: Option<bool>,
+    prefs: Option<AgentModelPrefs>,
+    target: Option<ConnectionTarget>,
 ) -> Result<serde_json::Value, String> {
-    let (port, endpoint, endpoint_v1) = {
-        let config = state.config.lock().unwrap();
-        let port = config.port;
-        let endpoint = format!("http://127.0.0.1:{}", port);
+    let docker = (target == Some(ConnectionTarget::Docker)).then(|| {
+        let bind = state.config.lock().proxy_bind.clone();
+        docker::docker_host(&bind)
+    });
+    // In dry-run mode nothing is written; the result carries a diff per file instead.
+    // Container configs are always previewed, never written over the host's own.
+    let mut writer = ConfigWriter::new(dry_run.unwrap_or(false) || docker.is_some());
+
+    // Fall back to the last model list fetched from the proxy
+    let models = if models.is_empty() {
+        crate::commands::models::cached_models(&state)
+    } else {
+        models
+    };
+    let (local_host, port) = lifecycle::client_address(&state);
+    let (port, endpoint, endpoint_v1, api_key) = {
+        let config = state.config.lock();
+        let host = docker
+            .as_ref()
+            .map_or(local_host.as_str(), |d| d.host.as_str());
+        let endpoint = format!("http://{}:{}", host, port);
         let endpoint_v1 = format!("{}/v1", endpoint);
-        (port, endpoint, endpoint_v1)
+        let api_key = crate::commands::proxy_keys::agent_api_key(&config, &agent_id);
+        (port, endpoint, endpoint_v1, api_key)
     }; // Mutex guard dropped here
     let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    let prefs = prefs.unwrap_or_else(|| {
+        state
+            .config
+            .lock()
+            .agent_model_preferences
+            .get(&agent_id)
+            .cloned()
+            .unwrap_or_default()
+    });
 
     // Precompute thinking/reasoning config for opencode
     let (thinking_budget, reasoning_effort) = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         let mode = if config.thinking_budget_mode.is_empty() {
             "medium"
         } else {
@@ -366,46 +773,58 @@ pub async fn configure_cli_agent(
             "custom" => custom as u64,
             _ => 8192,
         };
-        let effort = if config.reasoning_effort_level.is_empty() {
-            "medium".to_string()
-        } else {
-            config.reasoning_effort_level.clone()
+        let effort = match prefs.reasoning_effort.as_deref() {
+            Some(effort) if !effort.is_empty() => effort.to_string(),
+            _ if config.reasoning_effort_level.is_empty() => "medium".to_string(),
+            _ => config.reasoning_effort_level.clone(),
         };
         (budget, effort)
     };
 
-    match agent_id.as_str() {
-        "claude-code" => configure_claude_code_agent(&home, &endpoint, &models),
+    let result = match agent_id.as_str() {
+        "claude-code" => {
+            configure_claude_code_agent(&mut writer, &home, &endpoint, &api_key, &models, &prefs)
+        }
 
         "codex" => {
-            // Create ~/.codex directory
             let codex_dir = home.join(".codex");
-            std::fs::create_dir_all(&codex_dir).map_err(|e| e.to_string())?;
+
+            let model = pick_model(
+                prefs.primary_model.as_ref(),
+                &models,
+                &["gpt-5-codex", "gpt-5"],
+            )
+            .unwrap_or_else(|| "gpt-5-codex".to_string());
+            let effort = prefs
+                .reasoning_effort
+                .clone()
+                .filter(|e| !e.is_empty())
+                .unwrap_or_else(|| "high".to_string());
 
             // Write config.toml
             let config_content = format!(
                 r#"# ProxyPal - Codex Configuration
 model_provider = "cliproxyapi"
-model = "gpt-5-codex"
-model_reasoning_effort = "high"
+model = "{}"
+model_reasoning_effort = "{}"
 
 [model_providers.cliproxyapi]
 name = "cliproxyapi"
 base_url = "{}/v1"
 wire_api = "responses"
 "#,
-                endpoint
+                model, effort, endpoint
             );
 
             let config_path = codex_dir.join("config.toml");
-            std::fs::write(&config_path, &config_content).map_err(|e| e.to_string())?;
+            writer.write(&config_path, &config_content)?;
 
             // Write auth.json
-            let auth_content = r#"{
-  "OPENAI_API_KEY": "proxypal-local"
-}"#;
+            let auth_content =
+                serde_json::to_string_pretty(&serde_json::json!({ "OPENAI_API_KEY": api_key }))
+                    .map_err(|e| e.to_string())?;
             let auth_path = codex_dir.join("auth.json");
-            std::fs::write(&auth_path, auth_content).map_err(|e| e.to_string())?;
+            writer.write(&auth_path, &auth_content)?;
 
             Ok(serde_json::json!({
                 "success": true,
@@ -417,21 +836,12 @@ wire_api = "responses"
         }
 
         "gemini-cli" => {
-            // Generate shell config for Gemini CLI using platform-appropriate syntax.
-            // On Windows: $env:VAR = "value" (PowerShell)
-            // On Unix:    export VAR="value"
-            let shell_config = format!(
-                "# ProxyPal - Gemini CLI Configuration\n\
-                 # Option 1: OAuth mode (local only)\n\
-                 {code_assist}\n\
-                 \n\
-                 # Option 2: API Key mode (works with any IP/domain)\n\
-                 {gemini_url}\n\
-                 {gemini_key}\n",
-                code_assist = env_export_line("CODE_ASSIST_ENDPOINT", &endpoint),
-                gemini_url = env_export_line_commented("GOOGLE_GEMINI_BASE_URL", &endpoint),
-                gemini_key = env_export_line_commented("GEMINI_API_KEY", "proxypal-local"),
-            );
+            let mode = prefs.gemini_auth_mode.unwrap_or_default();
+            if mode == GeminiAuthMode::ApiKey {
+                let has_keys = !state.config.lock().gemini_api_keys.is_empty();
+                check_gemini_api_key_mode(has_keys, state.auth_status.lock().gemini)?;
+            }
+            let shell_config = gemini_cli_shell_config(mode, &endpoint, &api_key);
 
             let profile_hint = if cfg!(target_os = "windows") {
                 "Documents\\PowerShell\\Microsoft.PowerShell_profile.ps1"
@@ -447,67 +857,143 @@ wire_api = "responses"
             }))
         }
 
-        "factory-droid" => configure_factory_droid_agent(&home, &endpoint, &models),
+        "factory-droid" => {
+            configure_factory_droid_agent(&mut writer, &home, &endpoint, &api_key, &models, &prefs)
+        }
 
-        "amp-cli" => configure_amp_cli_agent(&home, port),
+        "amp-cli" => {
+            // Amp wants "localhost" for a proxy on loopback
+            let local = match local_host.as_str() {
+                "127.0.0.1" => "localhost",
+                other => other,
+            };
+            let host = docker.as_ref().map_or(local, |d| d.host.as_str());
+            configure_amp_cli_agent(&mut writer, &home, host, port, &api_key)
+        }
 
         "opencode" => configure_opencode_agent(
+            &mut writer,
             &home,
-            &endpoint,
             &endpoint_v1,
+            &api_key,
             &models,
+            &prefs,
             thinking_budget,
             &reasoning_effort,
         ),
 
+        "goose" => configure_goose_agent(&mut writer, &home, &endpoint, &api_key, &models, &prefs),
+
+        "crush" => {
+            configure_crush_agent(&mut writer, &home, &endpoint_v1, &api_key, &models, &prefs)
+        }
+
         _ => Err(format!("Unknown agent: {}", agent_id)),
+    };
+
+    if writer.is_dry_run() {
+        return result.map(|mut value| {
+            value["dryRun"] = serde_json::json!(true);
+            value["previews"] = serde_json::json!(writer.into_previews());
+            if let Some(docker) = &docker {
+                value["docker"] = serde_json::json!(docker);
+            }
+            value
+        });
+    }
+
+    if result.is_ok() {
+        clear_stale_agent_config(&state, &agent_id);
+    }
+
+    result
+}
+
+// Shell lines for Gemini CLI. The mode not chosen is included commented out,
+// so switching later is a matter of editing the profile.
+// On Windows: $env:VAR = "value" (PowerShell); on Unix: export VAR="value"
+fn gemini_cli_shell_config(mode: GeminiAuthMode, endpoint: &str, api_key: &str) -> String {
+    let export: fn(&str, &str) -> String = env_export_line;
+    let commented: fn(&str, &str) -> String = env_export_line_commented;
+    let (oauth, api) = match mode {
+        GeminiAuthMode::OAuth => (export, commented),
+        GeminiAuthMode::ApiKey => (commented, export),
+    };
+    format!(
+        "# ProxyPal - Gemini CLI Configuration\n\
+         # Option 1: OAuth mode (local only)\n\
+         {code_assist}\n\
+         \n\
+         # Option 2: API Key mode (works with any IP/domain)\n\
+         {gemini_url}\n\
+         {gemini_key}\n",
+        code_assist = oauth("CODE_ASSIST_ENDPOINT", endpoint),
+        gemini_url = api("GOOGLE_GEMINI_BASE_URL", endpoint),
+        gemini_key = api("GEMINI_API_KEY", api_key),
+    )
+}
+
+// In API key mode Gemini CLI requests go to the proxy's Gemini backends: a
+// key from the gemini-api-key list or a connected Gemini account
+fn check_gemini_api_key_mode(
+    has_gemini_api_keys: bool,
+    gemini_accounts: u32,
+) -> Result<(), String> {
+    if has_gemini_api_keys || gemini_accounts > 0 {
+        return Ok(());
     }
+    Err("Gemini API key mode needs a Gemini API key (Settings > API Keys) or a connected Gemini account for the proxy to forward requests to".to_string())
 }
 
 fn configure_claude_code_agent(
+    writer: &mut ConfigWriter,
     home: &std::path::Path,
     endpoint: &str,
+    api_key: &str,
     models: &[AvailableModel],
+    prefs: &AgentModelPrefs,
 ) -> Result<serde_json::Value, String> {
     // Write config to ~/.claude/settings.json (Claude Code's config file)
     let config_dir = home.join(".claude");
-    std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
     let config_path = config_dir.join("settings.json");
 
-    // Find best models for each tier from available models
-    // Priority: Claude > Gemini-Claude > Gemini > GPT
-    let find_model = |patterns: &[&str]| -> Option<String> {
-        for pattern in patterns {
-            if let Some(m) = models.iter().find(|m| m.id.contains(pattern)) {
-                return Some(m.id.clone());
-            }
-        }
-        None
-    };
-
+    // Find best models for each tier from available models, unless the user set
+    // preferences. Priority: Claude > Gemini-Claude > Gemini > GPT
     // Opus tier: claude-opus > gpt-5(high)
-    let opus_model = find_model(&["claude-opus-4", "claude-opus", "gpt-5"])
+    let opus_model = pick_model(None, models, &["claude-opus-4", "claude-opus", "gpt-5"])
         .unwrap_or_else(|| "claude-opus-4-1-20250805".to_string());
 
-    // Sonnet tier: claude-sonnet-4-5 > gpt-5
-    let sonnet_model =
-        find_model(&["claude-sonnet-4-5", "claude-sonnet-4", "claude-sonnet", "gpt-5"])
-            .unwrap_or_else(|| "claude-sonnet-4-5-20250929".to_string());
-
-    // Haiku tier: claude-haiku > gemini-claude-sonnet > gemini-2.5-flash > gpt-5(minimal)
-    let haiku_model = find_model(&[
-        "claude-3-5-haiku",
-        "claude-haiku",
-        "gemini-claude-sonnet-4-5",
-        "gemini-2.5-flash",
-        "gpt-5",
-    ])
+    // Sonnet tier (primary): claude-sonnet-4-5 > gpt-5
+    let sonnet_model = pick_model(
+        prefs.primary_model.as_ref(),
+        models,
+        &[
+            "claude-sonnet-4-5",
+            "claude-sonnet-4",
+            "claude-sonnet",
+            "gpt-5",
+        ],
+    )
+    .unwrap_or_else(|| "claude-sonnet-4-5-20250929".to_string());
+
+    // Haiku tier (small/fast): claude-haiku > gemini-claude-sonnet > gemini-2.5-flash > gpt-5(minimal)
+    let haiku_model = pick_model(
+        prefs.small_model.as_ref(),
+        models,
+        &[
+            "claude-3-5-haiku",
+            "claude-haiku",
+            "gemini-claude-sonnet-4-5",
+            "gemini-2.5-flash",
+            "gpt-5",
+        ],
+    )
     .unwrap_or_else(|| "claude-3-5-haiku-20241022".to_string());
 
     // Build env config for Claude Code settings.json
     let env_config = serde_json::json!({
         "ANTHROPIC_BASE_URL": endpoint,
-        "ANTHROPIC_AUTH_TOKEN": "proxypal-local",
+        "ANTHROPIC_AUTH_TOKEN": api_key,
         "ANTHROPIC_MODEL": sonnet_model,
         "ANTHROPIC_DEFAULT_OPUS_MODEL": opus_model,
         "ANTHROPIC_DEFAULT_SONNET_MODEL": sonnet_model,
@@ -562,7 +1048,7 @@ fn configure_claude_code_agent(
     };
 
     let config_str = serde_json::to_string_pretty(&final_config).map_err(|e| e.to_string())?;
-    std::fs::write(&config_path, &config_str).map_err(|e| e.to_string())?;
+    writer.write(&config_path, &config_str)?;
 
     // Create a reference file with all available model options from each provider
     let reference_path = config_dir.join("proxypal-models.md");
@@ -574,7 +1060,7 @@ Edit your `~/.claude/settings.json` and replace the model values in the `env` se
 ## Current Configuration
 ```json
 "ANTHROPIC_BASE_URL": "{}",
-"ANTHROPIC_AUTH_TOKEN": "proxypal-local",
+"ANTHROPIC_AUTH_TOKEN": "{}",
 "ANTHROPIC_MODEL": "{}",
 "ANTHROPIC_DEFAULT_OPUS_MODEL": "{}",
 "ANTHROPIC_DEFAULT_SONNET_MODEL": "{}",
@@ -661,10 +1147,10 @@ Edit your `~/.claude/settings.json` and replace the model values in the `env` se
 ---
 Generated by ProxyPal. Run `claude` to start using Claude Code.
 "#,
-        endpoint, sonnet_model, opus_model, sonnet_model, haiku_model
+        endpoint, api_key, sonnet_model, opus_model, sonnet_model, haiku_model
     );
 
-    std::fs::write(&reference_path, &reference_content).map_err(|e| e.to_string())?;
+    writer.write(&reference_path, &reference_content)?;
 
     Ok(serde_json::json!({
         "success": true,
@@ -676,17 +1162,31 @@ Generated by ProxyPal. Run `claude` to start using Claude Code.
 }
 
 fn configure_factory_droid_agent(
+    writer: &mut ConfigWriter,
     home: &std::path::Path,
     endpoint: &str,
+    api_key: &str,
     models: &[AvailableModel],
+    prefs: &AgentModelPrefs,
 ) -> Result<serde_json::Value, String> {
-    // Create ~/.factory directory
     let factory_dir = home.join(".factory");
-    std::fs::create_dir_all(&factory_dir).map_err(|e| e.to_string())?;
+
+    // Droid lists custom models in config order, so preferred models go first
+    let preferred: Vec<&String> = [prefs.primary_model.as_ref(), prefs.small_model.as_ref()]
+        .into_iter()
+        .flatten()
+        .collect();
+    let mut ordered: Vec<&AvailableModel> = models.iter().collect();
+    ordered.sort_by_key(|m| {
+        preferred
+            .iter()
+            .position(|p| **p == m.id)
+            .unwrap_or(preferred.len())
+    });
 
     // Build dynamic custom_models array from available models
-    let proxypal_models: Vec<serde_json::Value> = models
-        .iter()
+    let proxypal_models: Vec<serde_json::Value> = ordered
+        .into_iter()
         .map(|m| {
             let (base_url, provider) = match m.owned_by.as_str() {
                 "anthropic" => (endpoint.to_string(), "anthropic"),
@@ -705,7 +1205,7 @@ fn configure_factory_droid_agent(
                 "model": m.id,
                 "model_display_name": display_name,
                 "base_url": base_url,
-                "api_key": "proxypal-local",
+                "api_key": api_key,
                 "provider": provider
             })
         })
@@ -720,16 +1220,23 @@ fn configure_factory_droid_agent(
                 // Get existing custom_models, filter out proxypal entries, then add new ones
                 let mut merged_models: Vec<serde_json::Value> = Vec::new();
 
-                // Keep existing models that are NOT from proxypal (don't have proxypal-local api_key)
+                // Keep existing models that are NOT from proxypal (pointing at this proxy,
+                // or carrying the pre-rotation default key)
                 if let Some(existing_models) =
                     existing_json.get("custom_models").and_then(|v| v.as_array())
                 {
                     for model in existing_models {
-                        let is_proxypal = model
-                            .get("api_key")
+                        let points_here = model
+                            .get("base_url")
                             .and_then(|v| v.as_str())
-                            .map(|s| s == "proxypal-local")
+                            .map(|s| s.starts_with(endpoint))
                             .unwrap_or(false);
+                        let is_proxypal = points_here
+                            || model
+                                .get("api_key")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s == "proxypal-local" || s == api_key)
+                                .unwrap_or(false);
                         if !is_proxypal {
                             merged_models.push(model.clone());
                         }
@@ -756,7 +1263,7 @@ fn configure_factory_droid_agent(
     };
 
     let config_str = serde_json::to_string_pretty(&final_config).map_err(|e| e.to_string())?;
-    std::fs::write(&config_path, &config_str).map_err(|e| e.to_string())?;
+    writer.write(&config_path, &config_str)?;
 
     Ok(serde_json::json!({
         "success": true,
@@ -768,16 +1275,17 @@ fn configure_factory_droid_agent(
 }
 
 fn configure_amp_cli_agent(
+    writer: &mut ConfigWriter,
     home: &std::path::Path,
+    host: &str,
     port: u16,
+    api_key: &str,
 ) -> Result<serde_json::Value, String> {
-    // Create ~/.config/amp directory
     let amp_dir = home.join(".config/amp");
-    std::fs::create_dir_all(&amp_dir).map_err(|e| e.to_string())?;
 
     // Amp CLI requires localhost URL (not 127.0.0.1) per CLIProxyAPI docs
     // See: https://help.router-for.me/agent-client/amp-cli.html
-    let amp_endpoint = format!("http://localhost:{}", port);
+    let amp_endpoint = format!("http://{}:{}", host, port);
 
     // NOTE: Model mappings are configured in CLIProxyAPI's config.yaml (proxy-config.yaml),
     // NOT in Amp's settings.json. Amp CLI doesn't support amp.modelMapping setting.
@@ -791,7 +1299,7 @@ fn configure_amp_cli_agent(
 
         // API key for authentication with the proxy
         // This matches the api-keys in CLIProxyAPI config
-        "amp.apiKey": "proxypal-local",
+        "amp.apiKey": api_key,
 
         // Enable extended thinking for Claude models
         "amp.anthropic.thinking.enabled": true,
@@ -842,7 +1350,7 @@ fn configure_amp_cli_agent(
     };
 
     let settings_content = serde_json::to_string_pretty(&final_config).map_err(|e| e.to_string())?;
-    std::fs::write(&config_path, &settings_content).map_err(|e| e.to_string())?;
+    writer.write(&config_path, &settings_content)?;
 
     // Also provide env var option and API key instructions.
     // Use platform-appropriate syntax (PowerShell on Windows, export on Unix).
@@ -854,7 +1362,7 @@ fn configure_amp_cli_agent(
          # For Amp cloud features, get your API key from https://ampcode.com/settings\n\
          # and add it to ProxyPal Settings > Amp CLI Integration > Amp API Key\n",
         amp_url = env_export_line("AMP_URL", &amp_endpoint),
-        amp_key = env_export_line("AMP_API_KEY", "proxypal-local"),
+        amp_key = env_export_line("AMP_API_KEY", api_key),
     );
 
     Ok(serde_json::json!({
@@ -862,20 +1370,22 @@ fn configure_amp_cli_agent(
         "configType": "both",
         "configPath": config_path.to_string_lossy(),
         "shellConfig": shell_config,
-        "instructions": "Amp CLI has been configured. Run 'amp' to start using it. The API key 'proxypal-local' is pre-configured for local proxy access."
+        "instructions": "Amp CLI has been configured. Run 'amp' to start using it. The ProxyPal API key is pre-configured for local proxy access."
     }))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn configure_opencode_agent(
+    writer: &mut ConfigWriter,
     home: &std::path::Path,
-    _endpoint: &str,
     endpoint_v1: &str,
+    api_key: &str,
     models: &[AvailableModel],
+    prefs: &AgentModelPrefs,
     thinking_budget: u64,
     reasoning_effort: &str,
 ) -> Result<serde_json::Value, String> {
     let config_dir = home.join(".config/opencode");
-    std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
     let config_path = config_dir.join("opencode.json");
 
     // Build dynamic models object from available models
@@ -1016,9 +1526,10 @@ fn configure_opencode_agent(
         models_obj.insert(m.id.clone(), model_config);
     }
 
-    // Create or update opencode.json with proxypal provider
+    // Create or update opencode.json with proxypal provider.
+    // The "proxypal" provider key is the marker detection and re-runs look for.
     // Use @ai-sdk/anthropic for native Anthropic API (better for Claude models with thinking)
-    let opencode_config = serde_json::json!({
+    let mut opencode_config = serde_json::json!({
         "$schema": "https://opencode.ai/config.json",
         "provider": {
             "proxypal": {
@@ -1026,7 +1537,7 @@ fn configure_opencode_agent(
                 "name": "ProxyPal",
                 "options": {
                     "baseURL": endpoint_v1,
-                    "apiKey": "proxypal-local",
+                    "apiKey": api_key,
                     "includeUsage": true
                 },
                 "models": models_obj
@@ -1034,6 +1545,15 @@ fn configure_opencode_agent(
         }
     });
 
+    // Default model selection ("provider/model"). Only written when the user set a
+    // preference so an existing OpenCode default isn't overridden.
+    if let Some(model) = prefs.primary_model.as_ref().filter(|m| !m.is_empty()) {
+        opencode_config["model"] = serde_json::json!(format!("proxypal/{}", model));
+    }
+    if let Some(small_model) = prefs.small_model.as_ref().filter(|m| !m.is_empty()) {
+        opencode_config["small_model"] = serde_json::json!(format!("proxypal/{}", small_model));
+    }
+
     // If config exists, merge with existing
     let final_config = if config_path.exists() {
         if let Ok(existing) = std::fs::read_to_string(&config_path) {
@@ -1049,6 +1569,12 @@ fn configure_opencode_agent(
                 } else {
                     existing_json["provider"] = opencode_config["provider"].clone();
                 }
+                if let Some(model) = opencode_config.get("model") {
+                    existing_json["model"] = model.clone();
+                }
+                if let Some(small_model) = opencode_config.get("small_model") {
+                    existing_json["small_model"] = small_model.clone();
+                }
                 existing_json
             } else {
                 opencode_config
@@ -1060,21 +1586,176 @@ fn configure_opencode_agent(
         opencode_config
     };
 
+    let backup_path = writer.backup(&config_path);
     let config_str = serde_json::to_string_pretty(&final_config).map_err(|e| e.to_string())?;
-    std::fs::write(&config_path, &config_str).map_err(|e| e.to_string())?;
+    writer.write(&config_path, &config_str)?;
 
     Ok(serde_json::json!({
         "success": true,
         "configType": "config",
         "configPath": config_path.to_string_lossy(),
+        "backupPath": backup_path.map(|p| p.to_string_lossy().to_string()),
         "modelsConfigured": models.len(),
         "instructions": "ProxyPal provider added to OpenCode. Run 'opencode' and use /models to select a model (e.g., proxypal/gemini-2.5-pro). OpenCode uses AI SDK (ai-sdk.dev) and models.dev registry."
     }))
 }
 
+// Set top-level `KEY: value` entries in a flat YAML file, replacing existing keys
+// in place and appending missing ones. Other lines (comments, nested maps) are kept.
+fn upsert_yaml_top_level_keys(existing: &str, entries: &[(&str, String)]) -> String {
+    let mut lines: Vec<String> = existing.lines().map(|l| l.to_string()).collect();
+    for (key, value) in entries {
+        let prefix = format!("{}:", key);
+        let line = format!("{}: {}", key, value);
+        match lines.iter().position(|l| l.starts_with(&prefix)) {
+            Some(idx) => lines[idx] = line,
+            None => lines.push(line),
+        }
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+fn configure_goose_agent(
+    writer: &mut ConfigWriter,
+    home: &std::path::Path,
+    endpoint: &str,
+    api_key: &str,
+    models: &[AvailableModel],
+    prefs: &AgentModelPrefs,
+) -> Result<serde_json::Value, String> {
+    let config_path = home.join(".config/goose/config.yaml");
+    let model = pick_model(
+        prefs.primary_model.as_ref(),
+        models,
+        &["claude-sonnet", "gpt-5", "gemini-2.5-pro"],
+    )
+    .unwrap_or_else(|| "gpt-5".to_string());
+
+    // Goose's OpenAI provider reads host and path separately. Values are quoted
+    // JSON strings, which YAML accepts as double-quoted scalars.
+    let quote = |v: &str| serde_json::to_string(v).unwrap_or_default();
+    let mut existing = std::fs::read_to_string(&config_path).unwrap_or_default();
+    if !existing.contains("# Managed by ProxyPal") {
+        existing = format!(
+            "# Managed by ProxyPal (GOOSE_PROVIDER / OPENAI_*)\n{}",
+            existing
+        );
+    }
+    let content = upsert_yaml_top_level_keys(
+        &existing,
+        &[
+            ("GOOSE_PROVIDER", quote("openai")),
+            ("GOOSE_MODEL", quote(&model)),
+            ("OPENAI_HOST", quote(endpoint)),
+            ("OPENAI_BASE_PATH", quote("v1/chat/completions")),
+        ],
+    );
+
+    let backup_path = writer.backup(&config_path);
+    writer.write(&config_path, &content)?;
+
+    // The API key lives in the keyring or environment, never in config.yaml
+    let shell_config = format!(
+        "# ProxyPal - Goose Configuration\n{}\n{}\n",
+        env_export_line("OPENAI_API_KEY", api_key),
+        env_export_line_commented("OPENAI_HOST", endpoint),
+    );
+
+    Ok(serde_json::json!({
+        "success": true,
+        "configType": "both",
+        "configPath": config_path.to_string_lossy(),
+        "backupPath": backup_path.map(|p| p.to_string_lossy().to_string()),
+        "shellConfig": shell_config,
+        "modelsConfigured": 1,
+        "instructions": "Goose has been configured to use ProxyPal. Add OPENAI_API_KEY to your shell profile (or run 'goose configure' to store it in the keyring), then run 'goose session'."
+    }))
+}
+
+fn configure_crush_agent(
+    writer: &mut ConfigWriter,
+    home: &std::path::Path,
+    endpoint_v1: &str,
+    api_key: &str,
+    models: &[AvailableModel],
+    prefs: &AgentModelPrefs,
+) -> Result<serde_json::Value, String> {
+    let config_path = home.join(".config/crush/crush.json");
+
+    let crush_models: Vec<serde_json::Value> = models
+        .iter()
+        .map(|m| {
+            let (context_limit, output_limit) =
+                crate::commands::models::get_model_limits(&m.id, &m.owned_by, &m.source);
+            serde_json::json!({
+                "id": m.id,
+                "name": crate::commands::models::get_model_display_name(&m.id, &m.owned_by, &m.source),
+                "context_window": context_limit,
+                "default_max_tokens": output_limit
+            })
+        })
+        .collect();
+
+    // The "proxypal" provider key is the marker detection and re-runs look for
+    let provider = serde_json::json!({
+        "name": "ProxyPal",
+        "type": "openai",
+        "base_url": endpoint_v1,
+        "api_key": api_key,
+        "models": crush_models
+    });
+
+    let mut final_config = std::fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .filter(|v| v.is_object())
+        .unwrap_or_else(|| serde_json::json!({ "$schema": "https://charm.land/crush.json" }));
+    if !final_config["providers"].is_object() {
+        final_config["providers"] = serde_json::json!({});
+    }
+    final_config["providers"]["proxypal"] = provider;
+
+    // Point the large/small model slots at ProxyPal only when the user chose models
+    for (slot, model) in [
+        ("large", &prefs.primary_model),
+        ("small", &prefs.small_model),
+    ] {
+        let Some(model) = model.as_ref().filter(|m| !m.is_empty()) else {
+            continue;
+        };
+        if !final_config["models"].is_object() {
+            final_config["models"] = serde_json::json!({});
+        }
+        final_config["models"][slot] =
+            serde_json::json!({ "model": model, "provider": "proxypal" });
+    }
+
+    let backup_path = writer.backup(&config_path);
+    let config_str = serde_json::to_string_pretty(&final_config).map_err(|e| e.to_string())?;
+    writer.write(&config_path, &config_str)?;
+
+    let shell_config = format!(
+        "# ProxyPal - Crush Configuration (alternative to crush.json)\n{}\n{}\n",
+        env_export_line("OPENAI_API_KEY", api_key),
+        env_export_line("OPENAI_API_BASE", endpoint_v1),
+    );
+
+    Ok(serde_json::json!({
+        "success": true,
+        "configType": "both",
+        "configPath": config_path.to_string_lossy(),
+        "backupPath": backup_path.map(|p| p.to_string_lossy().to_string()),
+        "shellConfig": shell_config,
+        "modelsConfigured": models.len(),
+        "instructions": "ProxyPal provider added to Crush. Run 'crush' and pick a ProxyPal model from the model switcher."
+    }))
+}
+
 // Get shell profile path
 #[tauri::command]
-pub fn get_shell_profile_path() -> Result<String, String> {
+pub fn get_shell_profile_path() -> Result<String, CommandError> {
     let home = dirs::home_dir().ok_or("Could not find home directory")?;
 
     // On Windows we target the PowerShell profile — the standard location for persistent
@@ -1113,39 +1794,167 @@ pub fn get_shell_profile_path() -> Result<String, String> {
     }
 }
 
-// Append environment config to shell profile
+// Append environment config to shell profile.
+// With `dry_run`, returns a unified diff of the change instead of the profile path.
 #[tauri::command]
-pub fn append_to_shell_profile(content: String) -> Result<String, String> {
+pub fn append_to_shell_profile(
+    content: String,
+    dry_run: Option<bool>,
+) -> Result<String, CommandError> {
+    let result = write_shell_profile(content, dry_run);
+    if !dry_run.unwrap_or(false) {
+        let changes = match &result {
+            Ok(path) => vec![format!("profile: {}", path)],
+            Err(_) => Vec::new(),
+        };
+        crate::helpers::audit::record("append_to_shell_profile", changes, &result);
+    }
+    result
+}
+
+fn write_shell_profile(content: String, dry_run: Option<bool>) -> Result<String, CommandError> {
     let profile_path = get_shell_profile_path()?;
     let path = std::path::Path::new(&profile_path);
-
-    // Create parent directories if they don't exist.
-    // This is required on Windows where `Documents\PowerShell` may not exist
-    // if the user has never opened a PowerShell session.
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| {
-            format!("Failed to create profile directory '{}': {}", parent.display(), e)
-        })?;
-    }
+    // The writer creates parent directories if they don't exist. This is required
+    // on Windows where `Documents\PowerShell` may not exist if the user has never
+    // opened a PowerShell session.
+    let mut writer = ConfigWriter::new(dry_run.unwrap_or(false));
 
     // Read existing content
     let existing = std::fs::read_to_string(path).unwrap_or_default();
 
     // Check if ProxyPal config already exists
     if existing.contains("# ProxyPal") {
-        return Err(
-            "ProxyPal configuration already exists in shell profile. Please remove it first or update manually."
-                .to_string(),
-        );
+        return Err(CommandError::invalid_input(
+            "ProxyPal configuration already exists in shell profile. Please remove it first or update manually.",
+        ));
     }
 
     // Append new config
     let new_content = format!("{}\n\n{}", existing.trim_end(), content);
-    std::fs::write(path, new_content).map_err(|e| e.to_string())?;
+    writer.write(path, &new_content).map_err(CommandError::io)?;
 
+    if writer.is_dry_run() {
+        return Ok(preview_diffs(writer));
+    }
     Ok(profile_path)
 }
 
+// Concatenate the diffs recorded by a dry-run writer
+fn preview_diffs(writer: ConfigWriter) -> String {
+    writer
+        .into_previews()
+        .into_iter()
+        .map(|p| p.diff)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// JetBrains IDEs by config folder prefix and product name
+const JETBRAINS_IDES: &[(&str, &str)] = &[
+    ("IntelliJIdea", "IntelliJ IDEA"),
+    ("IdeaIC", "IntelliJ IDEA CE"),
+    ("PyCharm", "PyCharm"),
+    ("PyCharmCE", "PyCharm CE"),
+    ("WebStorm", "WebStorm"),
+    ("GoLand", "GoLand"),
+    ("PhpStorm", "PhpStorm"),
+    ("CLion", "CLion"),
+    ("Rider", "Rider"),
+    ("RubyMine", "RubyMine"),
+    ("RustRover", "RustRover"),
+    ("DataGrip", "DataGrip"),
+];
+
+// Product and version of a config folder such as "GoLand2024.3"
+fn jetbrains_config_version(folder: &str) -> Option<(&'static str, Vec<u32>)> {
+    JETBRAINS_IDES.iter().find_map(|(prefix, name)| {
+        let version = folder.strip_prefix(prefix)?;
+        if !version.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let parts = version
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u32>>>()?;
+        Some((*name, parts))
+    })
+}
+
+/// Config folders of the JetBrains IDEs under `root`, newest version first
+fn jetbrains_config_dirs_in(root: &std::path::Path) -> Vec<(&'static str, std::path::PathBuf)> {
+    let mut found: Vec<_> = std::fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let (name, version) =
+                        jetbrains_config_version(&entry.file_name().to_string_lossy())?;
+                    Some((name, version, entry.path()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    found.sort_by(|a, b| b.1.cmp(&a.1));
+    found
+        .into_iter()
+        .map(|(name, _, path)| (name, path))
+        .collect()
+}
+
+// The IDEs that have been run at least once; the config root is
+// ~/Library/Application Support, %APPDATA% or ~/.config plus "JetBrains"
+fn jetbrains_config_dirs() -> Vec<(&'static str, std::path::PathBuf)> {
+    dirs::config_dir()
+        .map(|dir| jetbrains_config_dirs_in(&dir.join("JetBrains")))
+        .unwrap_or_default()
+}
+
+// Whether an app bundle, install folder or snap is named after a JetBrains IDE
+fn is_jetbrains_ide_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    let name = name.trim_start_matches("jetbrains ");
+    // Tarball installs unpack to "idea-IU-243.21565.193"
+    name.starts_with("idea-")
+        || JETBRAINS_IDES.iter().any(|(_, product)| {
+            let first_word = product.split(' ').next().unwrap_or(product);
+            name.starts_with(&first_word.to_lowercase())
+        })
+}
+
+// Check Toolbox's apps folder, then the usual standalone install locations
+fn jetbrains_ide_installed(home: &std::path::Path) -> bool {
+    let toolbox_apps = dirs::data_local_dir().map(|dir| dir.join("JetBrains/Toolbox/apps"));
+    if toolbox_apps.is_some_and(|apps| apps.read_dir().is_ok_and(|mut e| e.next().is_some())) {
+        return true;
+    }
+
+    #[cfg(target_os = "macos")]
+    let install_dirs = [
+        std::path::PathBuf::from("/Applications"),
+        home.join("Applications"), // Toolbox 2 links its apps here
+    ];
+    #[cfg(target_os = "windows")]
+    let install_dirs = [
+        std::path::PathBuf::from(r"C:\Program Files\JetBrains"),
+        home.join(r"AppData\Local\Programs"),
+    ];
+    #[cfg(target_os = "linux")]
+    let install_dirs = [
+        std::path::PathBuf::from("/opt"),
+        std::path::PathBuf::from("/snap"),
+        home.join(".local/share/JetBrains"),
+    ];
+
+    install_dirs.iter().any(|dir| {
+        std::fs::read_dir(dir).is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|entry| is_jetbrains_ide_name(&entry.file_name().to_string_lossy()))
+        })
+    })
+}
+
 // Detect installed AI coding tools
 #[tauri::command]
 pub fn detect_ai_tools() -> Vec<DetectedTool> {
@@ -1243,21 +2052,57 @@ pub fn detect_ai_tools() -> Vec<DetectedTool> {
         can_auto_configure: false,
     });
 
+    // Check for JetBrains IDEs (AI Assistant and Junie run inside them)
+    let jetbrains_configs = jetbrains_config_dirs();
+    tools.push(DetectedTool {
+        id: "jetbrains".to_string(),
+        name: "JetBrains AI Assistant".to_string(),
+        installed: !jetbrains_configs.is_empty() || jetbrains_ide_installed(&home),
+        config_path: jetbrains_configs
+            .first()
+            .map(|(_, path)| path.to_string_lossy().to_string()),
+        can_auto_configure: false, // Provider settings live in the IDE's settings store
+    });
+
     tools
 }
 
 // Configure Continue extension with ProxyPal endpoint
 #[tauri::command]
-pub fn configure_continue(state: State<AppState>) -> Result<String, String> {
-    let config = state.config.lock().unwrap();
-    let endpoint = format!("http://localhost:{}/v1", config.port);
+pub fn configure_continue(
+    state: State<AppState>,
+    models: Option<Vec<AvailableModel>>,
+    dry_run: Option<bool>,
+) -> Result<String, CommandError> {
+    let result = write_continue_config(&state, models, dry_run).map_err(CommandError::from);
+    if !dry_run.unwrap_or(false) {
+        let changes = vec!["agent: continue".to_string()];
+        crate::helpers::audit::record("configure_continue", changes, &result);
+    }
+    result
+}
+
+fn write_continue_config(
+    state: &AppState,
+    models: Option<Vec<AvailableModel>>,
+    dry_run: Option<bool>,
+) -> Result<String, String> {
+    let (host, port) = lifecycle::client_address(state);
+    let endpoint = lifecycle::proxy_endpoint(&host, port);
+    let config = state.config.lock();
+    let model = pick_model(
+        config
+            .agent_model_preferences
+            .get("continue")
+            .and_then(|p| p.primary_model.as_ref()),
+        &models.unwrap_or_else(|| crate::commands::models::cached_models(state)),
+        &["claude-sonnet", "gpt-5", "gemini-2.5-pro"],
+    )
+    .unwrap_or_else(|| "gpt-4".to_string());
 
     let home = dirs::home_dir().ok_or("Could not find home directory")?;
     let continue_dir = home.join(".continue");
 
-    // Create directory if it doesn't exist
-    std::fs::create_dir_all(&continue_dir).map_err(|e| e.to_string())?;
-
     let config_path = continue_dir.join("config.yaml");
 
     // Check if config already exists
@@ -1279,15 +2124,15 @@ schema: v1
 models:
   - name: ProxyPal (Auto-routed)
     provider: openai
-    model: gpt-4
-    apiKey: proxypal-local
+    model: {}
+    apiKey: {}
     apiBase: {}
     roles:
       - chat
       - edit
       - apply
 "#,
-            endpoint
+            model, config.proxy_api_key, endpoint
         )
     } else {
         // Append ProxyPal model to existing config
@@ -1296,8 +2141,8 @@ models:
   # Added by ProxyPal
   - name: ProxyPal (Auto-routed)
     provider: openai
-    model: gpt-4
-    apiKey: proxypal-local
+    model: {}
+    apiKey: {}
     apiBase: {}
     roles:
       - chat
@@ -1305,24 +2150,32 @@ models:
       - apply
 "#,
             existing_content.trim_end(),
+            model,
+            config.proxy_api_key,
             endpoint
         )
     };
 
-    std::fs::write(&config_path, new_config).map_err(|e| e.to_string())?;
+    let mut writer = ConfigWriter::new(dry_run.unwrap_or(false));
+    writer.write(&config_path, &new_config)?;
 
+    if writer.is_dry_run() {
+        return Ok(preview_diffs(writer));
+    }
     Ok(config_path.to_string_lossy().to_string())
 }
 
 // Get setup instructions for a specific tool
 #[tauri::command]
-pub fn get_tool_setup_info(tool_id: String, state: State<AppState>) -> Result<serde_json::Value, String> {
-    let config = state.config.lock().unwrap();
-    let endpoint = format!("http://localhost:{}/v1", config.port);
-    
-    let info = match tool_id.as_str() {
+pub fn get_tool_setup_info(tool_id: String, state: State<AppState>) -> Result<serde_json::Value, CommandError> {
+    let (host, port) = lifecycle::client_address(&state);
+    let config = state.config.lock();
+    let endpoint = connection_info(ApiProtocol::OpenAI, &host, port, &config.proxy_api_key).base_url;
+
+    let mut info = match tool_id.as_str() {
         "cursor" => serde_json::json!({
             "name": "Cursor",
+            "protocol": ApiProtocol::OpenAI,
             "logo": "/logos/cursor.svg",
             "canAutoConfigure": false,
             "note": "Cursor doesn't support custom API base URLs. Use your connected providers' API keys directly in Cursor settings.",
@@ -1339,6 +2192,7 @@ pub fn get_tool_setup_info(tool_id: String, state: State<AppState>) -> Result<se
         }),
         "continue" => serde_json::json!({
             "name": "Continue",
+            "protocol": ApiProtocol::OpenAI,
             "logo": "/logos/continue.svg",
             "canAutoConfigure": true,
             "steps": [
@@ -1355,12 +2209,13 @@ pub fn get_tool_setup_info(tool_id: String, state: State<AppState>) -> Result<se
   - name: ProxyPal
     provider: openai
     model: gpt-4
-    apiKey: proxypal-local
-    apiBase: {}"#, endpoint),
+    apiKey: {}
+    apiBase: {}"#, config.proxy_api_key, endpoint),
             "endpoint": endpoint
         }),
         "cline" => serde_json::json!({
             "name": "Cline",
+            "protocol": ApiProtocol::OpenAI,
             "logo": "/logos/cline.svg",
             "canAutoConfigure": false,
             "steps": [
@@ -1379,8 +2234,8 @@ pub fn get_tool_setup_info(tool_id: String, state: State<AppState>) -> Result<se
                 },
                 {
                     "title": "Set API Key",
-                    "description": "Enter: proxypal-local",
-                    "copyable": "proxypal-local".to_string()
+                    "description": format!("Enter: {}", config.proxy_api_key),
+                    "copyable": config.proxy_api_key.clone()
                 },
                 {
                     "title": "Select Model",
@@ -1391,6 +2246,7 @@ pub fn get_tool_setup_info(tool_id: String, state: State<AppState>) -> Result<se
         }),
         "windsurf" => serde_json::json!({
             "name": "Windsurf",
+            "protocol": ApiProtocol::OpenAI,
             "logo": "/logos/windsurf.svg",
             "canAutoConfigure": false,
             "note": "Windsurf doesn't support custom API endpoints. It only supports direct API keys for Claude models.",
@@ -1401,9 +2257,71 @@ pub fn get_tool_setup_info(tool_id: String, state: State<AppState>) -> Result<se
                 }
             ]
         }),
-        _ => return Err(format!("Unknown tool: {}", tool_id)),
+        "jetbrains" => {
+            let models = crate::commands::models::cached_models(&state);
+            let model = pick_model(
+                config
+                    .agent_model_preferences
+                    .get("jetbrains")
+                    .and_then(|p| p.primary_model.as_ref()),
+                &models,
+                &["claude-sonnet", "gpt-5", "gemini-2.5-pro"],
+            )
+            .unwrap_or_else(|| "gpt-4".to_string());
+            let ide = jetbrains_config_dirs().into_iter().next();
+            serde_json::json!({
+                "name": "JetBrains AI Assistant",
+                "protocol": ApiProtocol::OpenAI,
+                "logo": "/logos/jetbrains.svg",
+                "canAutoConfigure": false,
+                "note": "AI Assistant keeps third-party provider settings in the IDE's own settings store, so ProxyPal can't write them. Enter them once in each IDE.",
+                "steps": [
+                    {
+                        "title": "Open AI Assistant Settings",
+                        "description": format!(
+                            "In {}, open Settings | Tools | AI Assistant | Models",
+                            ide.as_ref().map(|(name, _)| *name).unwrap_or("your JetBrains IDE")
+                        )
+                    },
+                    {
+                        "title": "Add an OpenAI-Compatible Provider",
+                        "description": "Under 'Third-party AI providers', pick 'OpenAI API compatible' and enter the ProxyPal endpoint:",
+                        "copyable": endpoint.clone()
+                    },
+                    {
+                        "title": "Set API Key",
+                        "description": format!("Enter: {}", config.proxy_api_key),
+                        "copyable": config.proxy_api_key.clone()
+                    },
+                    {
+                        "title": "Test the Connection",
+                        "description": "Click 'Test Connection'; the model lists then offer the models ProxyPal serves"
+                    },
+                    {
+                        "title": "Select Models",
+                        "description": format!("Choose a model for chat and for instant helpers, e.g. {}", model),
+                        "copyable": model
+                    }
+                ],
+                "ide": ide.as_ref().map(|(name, _)| *name),
+                "settingsPath": ide.as_ref().map(|(_, path)| path.to_string_lossy().to_string()),
+                "models": models.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+                "endpoint": endpoint
+            })
+        }
+        _ => {
+            return Err(CommandError::invalid_input(format!(
+                "Unknown tool: {}",
+                tool_id
+            )))
+        }
     };
-    
+
+    // Snippet for the protocol the tool declared above
+    let protocol: ApiProtocol =
+        serde_json::from_value(info["protocol"].clone()).unwrap_or_default();
+    let connection = connection_info(protocol, &host, port, &config.proxy_api_key);
+    info["connection"] = serde_json::json!(connection);
     Ok(info)
 }
 
@@ -1435,6 +2353,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn finds_jetbrains_config_folders_newest_first() {
+        let root =
+            std::env::temp_dir().join(format!("proxypal-jetbrains-{}", uuid::Uuid::new_v4()));
+        for folder in [
+            "GoLand2024.3",
+            "PyCharmCE2023.2",
+            "GoLand2025.1",
+            "Toolbox",
+            "consentOptions",
+        ] {
+            std::fs::create_dir_all(root.join(folder)).unwrap();
+        }
+        let found = jetbrains_config_dirs_in(&root);
+        let names: Vec<_> = found.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["GoLand", "GoLand", "PyCharm CE"]);
+        assert!(found[0].1.ends_with("GoLand2025.1"));
+        assert!(jetbrains_config_dirs_in(&root.join("missing")).is_empty());
+
+        assert!(is_jetbrains_ide_name("IntelliJ IDEA CE.app"));
+        assert!(is_jetbrains_ide_name("JetBrains Rider 2024.3"));
+        assert!(is_jetbrains_ide_name("idea-IU-243.21565.193"));
+        assert!(is_jetbrains_ide_name("pycharm-professional"));
+        assert!(!is_jetbrains_ide_name("Visual Studio Code.app"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn agent_checks_compare_the_configured_port() {
+        let droid = r#"{"custom_models":[{"base_url":"http://127.0.0.1:8440/v1"}]}"#;
+        assert!(points_at_proxy(droid, "http://localhost:8440", 8440));
+        assert!(!points_at_proxy(droid, "http://localhost:844", 844));
+        assert!(!points_at_proxy(droid, "http://127.0.0.1:8441", 8441));
+        let amp = r#"{"amp.url":"http://localhost:8440"}"#;
+        assert!(points_at_proxy(amp, "http://127.0.0.1:8440", 8440));
+    }
+
     #[test]
     fn env_export_line_generates_correct_syntax() {
         let line = env_export_line("FOO", "bar");
@@ -1451,4 +2407,165 @@ mod tests {
         assert!(line.contains("BAZ"), "Commented line should contain the key");
         assert!(line.contains("qux"), "Commented line should contain the value");
     }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn gemini_cli_exports_the_chosen_mode() {
+        let endpoint = "http://127.0.0.1:8317";
+        let oauth = gemini_cli_shell_config(GeminiAuthMode::OAuth, endpoint, "pp-key");
+        assert!(oauth.contains("\nexport CODE_ASSIST_ENDPOINT=\"http://127.0.0.1:8317\"\n"));
+        assert!(oauth.contains("# export GEMINI_API_KEY=\"pp-key\""));
+
+        let api_key = gemini_cli_shell_config(GeminiAuthMode::ApiKey, endpoint, "pp-key");
+        assert!(api_key.contains("# export CODE_ASSIST_ENDPOINT="));
+        assert!(api_key.contains("\nexport GOOGLE_GEMINI_BASE_URL=\"http://127.0.0.1:8317\"\n"));
+        assert!(api_key.contains("\nexport GEMINI_API_KEY=\"pp-key\"\n"));
+    }
+
+    #[test]
+    fn gemini_api_key_mode_needs_a_gemini_backend() {
+        assert!(check_gemini_api_key_mode(false, 0).is_err());
+        assert!(check_gemini_api_key_mode(true, 0).is_ok());
+        assert!(check_gemini_api_key_mode(false, 2).is_ok());
+        let prefs: AgentModelPrefs =
+            serde_json::from_str(r#"{"geminiAuthMode":"api-key"}"#).unwrap();
+        assert_eq!(prefs.gemini_auth_mode, Some(GeminiAuthMode::ApiKey));
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn connection_info_snippets_per_protocol() {
+        let openai = connection_info(ApiProtocol::OpenAI, "127.0.0.1", 9123, "key-1");
+        assert_eq!(openai.base_url, "http://127.0.0.1:9123/v1");
+        assert_eq!(openai.auth_header, "Authorization: Bearer");
+        assert_eq!(
+            openai.snippet,
+            "export OPENAI_BASE_URL=\"http://127.0.0.1:9123/v1\"\nexport OPENAI_API_KEY=\"key-1\""
+        );
+
+        let anthropic = connection_info(ApiProtocol::Anthropic, "127.0.0.1", 9123, "key-1");
+        assert_eq!(anthropic.base_url, "http://127.0.0.1:9123");
+        assert_eq!(anthropic.example_path, "/v1/messages");
+        assert_eq!(anthropic.auth_header, "x-api-key");
+        assert_eq!(
+            anthropic.snippet,
+            "export ANTHROPIC_BASE_URL=\"http://127.0.0.1:9123\"\nexport ANTHROPIC_API_KEY=\"key-1\""
+        );
+
+        let gemini = connection_info(ApiProtocol::Gemini, "127.0.0.1", 9123, "key-1");
+        assert_eq!(gemini.base_url, "http://127.0.0.1:9123");
+        assert!(gemini.example_path.starts_with("/v1beta/"));
+        assert_eq!(gemini.auth_header, "x-goog-api-key");
+        assert_eq!(
+            gemini.snippet,
+            "export GOOGLE_GEMINI_BASE_URL=\"http://127.0.0.1:9123\"\nexport GEMINI_API_KEY=\"key-1\""
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn connection_info_snippets_use_powershell_on_windows() {
+        assert_eq!(
+            connection_info(ApiProtocol::Anthropic, "127.0.0.1", 9123, "key-1").snippet,
+            "$env:ANTHROPIC_BASE_URL = \"http://127.0.0.1:9123\"\n$env:ANTHROPIC_API_KEY = \"key-1\""
+        );
+    }
+
+    #[test]
+    fn docker_snippets_point_at_the_host_gateway() {
+        let docker = DockerHost {
+            installed: true,
+            host: "172.17.0.1".to_string(),
+            add_host_flag: Some(docker::ADD_HOST_FLAG.to_string()),
+            warning: None,
+        };
+        // POSIX on every OS: the shell runs inside the container
+        let info = docker_connection_info(ApiProtocol::Anthropic, &docker, 9123, "key-1");
+        assert_eq!(info.base_url, "http://172.17.0.1:9123");
+        assert_eq!(
+            info.snippet,
+            "export ANTHROPIC_BASE_URL=\"http://172.17.0.1:9123\"\nexport ANTHROPIC_API_KEY=\"key-1\""
+        );
+        assert_eq!(info.docker.as_ref(), Some(&docker));
+
+        let snippet: serde_json::Value =
+            serde_json::from_str(&devcontainer_snippet(&docker, 9123, "key-1")).unwrap();
+        assert_eq!(snippet["runArgs"][0], docker::ADD_HOST_FLAG);
+        assert_eq!(
+            snippet["remoteEnv"]["OPENAI_BASE_URL"],
+            "http://172.17.0.1:9123/v1"
+        );
+        assert_eq!(snippet["remoteEnv"]["GEMINI_API_KEY"], "key-1");
+
+        let desktop = DockerHost {
+            host: docker::DOCKER_DESKTOP_HOST.to_string(),
+            add_host_flag: None,
+            ..docker
+        };
+        let snippet: serde_json::Value =
+            serde_json::from_str(&devcontainer_snippet(&desktop, 9123, "key-1")).unwrap();
+        assert!(snippet.get("runArgs").is_none());
+        assert_eq!(
+            snippet["remoteEnv"]["ANTHROPIC_BASE_URL"],
+            "http://host.docker.internal:9123"
+        );
+    }
+
+    #[test]
+    fn protocol_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_string(&ApiProtocol::OpenAI).unwrap(),
+            "\"openai\""
+        );
+        assert_eq!(
+            serde_json::from_str::<ApiProtocol>("\"gemini\"").unwrap(),
+            ApiProtocol::Gemini
+        );
+    }
+
+    fn model(id: &str) -> AvailableModel {
+        AvailableModel {
+            id: id.to_string(),
+            owned_by: "test".to_string(),
+            source: "test".to_string(),
+            provider: "test".to_string(),
+            connected: true,
+        }
+    }
+
+    #[test]
+    fn upsert_yaml_top_level_keys_replaces_and_appends() {
+        let existing = "# comment\nGOOSE_PROVIDER: anthropic\nextensions:\n  developer:\n    enabled: true\n";
+        let updated = upsert_yaml_top_level_keys(
+            existing,
+            &[
+                ("GOOSE_PROVIDER", "\"openai\"".to_string()),
+                ("OPENAI_HOST", "\"http://127.0.0.1:8317\"".to_string()),
+            ],
+        );
+        assert!(updated.contains("GOOSE_PROVIDER: \"openai\"\n"));
+        assert!(!updated.contains("anthropic"));
+        assert!(updated.contains("  developer:\n    enabled: true\n"));
+        assert!(updated.ends_with("OPENAI_HOST: \"http://127.0.0.1:8317\"\n"));
+    }
+
+    #[test]
+    fn pick_model_prefers_explicit_preference_then_patterns() {
+        let models = vec![model("gemini-2.5-pro"), model("gpt-5-codex")];
+        let preferred = "claude-opus-4-1".to_string();
+
+        assert_eq!(
+            pick_model(Some(&preferred), &models, &["gpt-5"]).as_deref(),
+            Some("claude-opus-4-1")
+        );
+        assert_eq!(
+            pick_model(None, &models, &["gpt-5"]).as_deref(),
+            Some("gpt-5-codex")
+        );
+        assert_eq!(
+            pick_model(None, &models, &["missing"]).as_deref(),
+            Some("gemini-2.5-pro")
+        );
+        assert_eq!(pick_model(None, &[], &["gpt-5"]), None);
+    }
 }
@@ -0,0 +1,111 @@
+//! Amp onboarding commands: checking the Amp API key through the proxy and
+//! setting up Amp and the proxy's Amp routes in one step.
+
+use std::time::{Duration, Instant};
+
+use tauri::State;
+
+use crate::error::{CommandError, ErrorCode};
+use crate::helpers::amp_setup::{key_check_result, validate_routing, KEY_CHECK_ENDPOINT};
+use crate::proxy::lifecycle;
+use crate::state::AppState;
+use crate::types::ProviderTestResult;
+
+/// How long Amp gets to answer the key check
+const KEY_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Ask Amp, through the running proxy's `ampcode` upstream, whether it
+/// accepts the configured Amp API key. The proxy swaps in the key, so this
+/// checks the one it was last given.
+#[tauri::command]
+pub async fn validate_amp_api_key(
+    state: State<'_, AppState>,
+) -> Result<ProviderTestResult, CommandError> {
+    if state.config.lock().amp_api_key.trim().is_empty() {
+        return Err(CommandError::invalid_input(
+            "Enter your Amp API key from ampcode.com/settings first",
+        ));
+    }
+    if !state.proxy_status.lock().running {
+        return Err(CommandError::new(
+            ErrorCode::ProxyNotRunning,
+            "Start the proxy to check the Amp API key",
+        ));
+    }
+    let (host, port) = lifecycle::client_address(&state);
+    let client_key = {
+        let config = state.config.lock();
+        crate::commands::proxy_keys::agent_api_key(&config, "amp-cli")
+    };
+
+    let start = Instant::now();
+    let response = state
+        .http
+        .local()
+        .get(format!("http://{}:{}/{}", host, port, KEY_CHECK_ENDPOINT))
+        .bearer_auth(client_key)
+        .timeout(KEY_CHECK_TIMEOUT)
+        .send()
+        .await;
+    let latency = start.elapsed().as_millis() as u64;
+    Ok(match response {
+        Ok(response) => key_check_result(response.status().as_u16(), latency),
+        Err(e) => ProviderTestResult {
+            success: false,
+            message: format!("Could not reach Amp through the proxy: {}", e),
+            latency_ms: Some(latency),
+            models_found: None,
+        },
+    })
+}
+
+/// Set up Amp in one step: save the Amp API key and routing mode, write the
+/// proxy config with Amp's key, model mappings and pools, and point Amp's
+/// settings.json at the proxy. A routing mode that leaves Amp's models
+/// without a route is refused before anything is written.
+#[tauri::command]
+pub async fn configure_amp(
+    state: State<'_, AppState>,
+    amp_api_key: Option<String>,
+    routing_mode: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    state.ensure_hydrated()?;
+    let old = state.config.lock().clone();
+    let mut config = old.clone();
+    if let Some(key) = amp_api_key {
+        config.amp_api_key = key.trim().to_string();
+    }
+    if let Some(mode) = routing_mode {
+        config.amp_routing_mode = mode.trim().to_string();
+    }
+    let mut changes = crate::helpers::audit::config_changes(&old, &config);
+    changes.push("agent: amp-cli".to_string());
+
+    let result = write_amp_setup(&state, config).await;
+    crate::helpers::audit::record("configure_amp", changes, &result);
+    result
+}
+
+async fn write_amp_setup(
+    state: &State<'_, AppState>,
+    config: crate::config::AppConfig,
+) -> Result<serde_json::Value, CommandError> {
+    if config.amp_api_key.is_empty() {
+        return Err(CommandError::invalid_input(
+            "Enter your Amp API key from ampcode.com/settings",
+        ));
+    }
+    validate_routing(&config).map_err(CommandError::invalid_input)?;
+    crate::commands::config::store_config(state, config.clone()).map_err(CommandError::io)?;
+    let proxy_config_path = lifecycle::write_proxy_config(&config).map_err(CommandError::io)?;
+
+    let mut result = crate::commands::agents::write_cli_agent_config(
+        state.clone(),
+        "amp-cli".to_string(),
+        Vec::new(),
+        None,
+    )
+    .await?;
+    result["proxyConfigPath"] = serde_json::json!(proxy_config_path.to_string_lossy());
+    Ok(result)
+}
@@ -0,0 +1,69 @@
+//! Amp provider pool commands. Pool changes reach the proxy when it next
+//! starts, since members are written into its generated config.
+
+use tauri::State;
+
+use crate::config::save_config_to_file;
+use crate::error::CommandError;
+use crate::helpers::amp_pools::{usage_by_pool, validate_pools};
+use crate::state::AppState;
+use crate::types::{AmpPool, AmpPoolUsage};
+
+#[tauri::command]
+pub fn get_amp_pools(state: State<AppState>) -> Vec<AmpPool> {
+    state.config.lock().amp_pools.clone()
+}
+
+// Add a pool, or replace the one with the same id. Refused if a member names
+// a custom provider that doesn't exist or a provider with no connected
+// account, or if an Amp model would be routed by two pools.
+#[tauri::command]
+pub fn save_amp_pool(
+    state: State<AppState>,
+    mut pool: AmpPool,
+) -> Result<Vec<AmpPool>, CommandError> {
+    state.ensure_hydrated()?;
+    if pool.id.is_empty() {
+        pool.id = uuid::Uuid::new_v4().to_string();
+    }
+    pool.name = pool.name.trim().to_string();
+    pool.routes = pool
+        .routes
+        .iter()
+        .map(|route| route.trim().to_string())
+        .filter(|route| !route.is_empty())
+        .collect();
+
+    let auth = state.auth_status.lock().clone();
+    let mut config = state.config.lock();
+    let mut pools = config.amp_pools.clone();
+    if let Some(existing) = pools.iter_mut().find(|p| p.id == pool.id) {
+        *existing = pool;
+    } else {
+        pools.push(pool);
+    }
+    validate_pools(&pools, &config.amp_openai_providers, &auth)
+        .map_err(CommandError::invalid_input)?;
+    config.amp_pools = pools;
+    save_config_to_file(&config).map_err(CommandError::io)?;
+    Ok(config.amp_pools.clone())
+}
+
+#[tauri::command]
+pub fn delete_amp_pool(state: State<AppState>, id: String) -> Result<Vec<AmpPool>, CommandError> {
+    state.ensure_hydrated()?;
+    let mut config = state.config.lock();
+    config.amp_pools.retain(|p| p.id != id);
+    save_config_to_file(&config).map_err(CommandError::io)?;
+    Ok(config.amp_pools.clone())
+}
+
+// Requests in history that Amp sent through each pool, matched by Amp's
+// provider routes and the pool's models
+#[tauri::command]
+pub fn get_amp_pool_usage(state: State<AppState>) -> Vec<AmpPoolUsage> {
+    let pools = state.config.lock().amp_pools.clone();
+    state
+        .history
+        .read(|history| usage_by_pool(&history.requests, &pools))
+}
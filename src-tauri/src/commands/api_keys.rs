@@ -1,26 +1,34 @@
 //! API Keys Management - CRUD operations via Management API.
 
-use tauri::State;
+use crate::config::{save_config_to_file, AppConfig};
+use crate::error::{CommandError, ErrorCode};
+use crate::helpers::amp_pools;
+use crate::http::{management_json, send_management, ManagementError};
+use crate::providers::AuthKind;
 use crate::state::AppState;
-use crate::config::save_config_to_file;
-use crate::types::{GeminiApiKey, ClaudeApiKey, CodexApiKey, VertexApiKey, OpenAICompatibleProvider};
+use crate::types::{
+    ClaudeApiKey, CodexApiKey, GeminiApiKey, ModelMapping, OpenAICompatibleApiKeyEntry,
+    OpenAICompatibleProvider, VertexApiKey,
+};
+use reqwest::Method;
+use tauri::{AppHandle, Emitter, State};
 
 // Convert Management API kebab-case keys to camelCase for frontend
 // The Management API returns data wrapped in an object like: { "gemini-api-key": [...] }
 // It may also return null for empty lists: { "gemini-api-key": null }
-fn convert_api_key_response<T: serde::de::DeserializeOwned>(json: serde_json::Value, wrapper_key: &str) -> Result<Vec<T>, String> {
+fn convert_api_key_response<T: serde::de::DeserializeOwned>(json: serde_json::Value, wrapper_key: &str) -> Result<Vec<T>, CommandError> {
     // Extract the array from the wrapper object
     let array_value = match &json {
         serde_json::Value::Object(obj) => {
             match obj.get(wrapper_key) {
                 Some(serde_json::Value::Array(arr)) => serde_json::Value::Array(arr.clone()),
                 Some(serde_json::Value::Null) | None => serde_json::Value::Array(vec![]), // null or missing = empty array
-                Some(other) => return Err(format!("Expected array or null for key '{}', got: {:?}", wrapper_key, other)),
+                Some(other) => return Err(CommandError::new(ErrorCode::Upstream, format!("Expected array or null for key '{}', got: {:?}", wrapper_key, other))),
             }
         }
         serde_json::Value::Array(_) => json.clone(), // Already an array, use as-is
         serde_json::Value::Null => serde_json::Value::Array(vec![]), // Top-level null = empty array
-        _ => return Err(format!("Unexpected response format: expected object with key '{}' or array", wrapper_key)),
+        _ => return Err(CommandError::new(ErrorCode::Upstream, format!("Unexpected response format: expected object with key '{}' or array", wrapper_key))),
     };
     
     // The Management API returns kebab-case, we need to convert
@@ -32,7 +40,7 @@ fn convert_api_key_response<T: serde::de::DeserializeOwned>(json: serde_json::Va
         .replace("\"proxy-url\"", "\"proxyUrl\"")
         .replace("\"excluded-models\"", "\"excludedModels\"")
         .replace("\"api-key-entries\"", "\"apiKeyEntries\"");
-    serde_json::from_str(&converted).map_err(|e| e.to_string())
+    serde_json::from_str(&converted).map_err(|e| CommandError::new(ErrorCode::Upstream, e.to_string()))
 }
 
 // Convert camelCase to kebab-case for Management API
@@ -52,73 +60,73 @@ fn convert_to_management_format<T: serde::Serialize>(data: &T) -> Result<serde_j
 // ============================================
 
 #[tauri::command]
-pub async fn get_gemini_api_keys(state: State<'_, AppState>) -> Result<Vec<GeminiApiKey>, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "gemini-api-key");
-    
-    let client = crate::build_management_client();
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch Gemini API keys: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Ok(Vec::new());
-    }
-    
-    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+pub async fn get_gemini_api_keys(state: State<'_, AppState>) -> Result<Vec<GeminiApiKey>, CommandError> {
+    let port = state.config.lock().port;
+    let request = state.http.management(Method::GET, port, "gemini-api-key");
+    let json: serde_json::Value = match management_json(request).await {
+        Ok(json) => json,
+        Err(ManagementError::Status { .. }) => return Ok(Vec::new()),
+        Err(e) => return Err(CommandError::management("fetch Gemini API keys", e)),
+    };
     convert_api_key_response(json, "gemini-api-key")
 }
 
 #[tauri::command]
-pub async fn set_gemini_api_keys(state: State<'_, AppState>, keys: Vec<GeminiApiKey>) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "gemini-api-key");
-    
-    let client = crate::build_management_client();
+pub async fn set_gemini_api_keys(state: State<'_, AppState>, keys: Vec<GeminiApiKey>) -> Result<(), CommandError> {
+    let before = state.config.lock().gemini_api_keys.len();
+    let changes = vec![format!("geminiApiKeys: {} → {} entries", before, keys.len())];
+    let result = put_gemini_api_keys(state, keys).await;
+    crate::helpers::audit::record("set_gemini_api_keys", changes, &result);
+    result
+}
+
+async fn put_gemini_api_keys(state: State<'_, AppState>, keys: Vec<GeminiApiKey>) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
     let body = convert_to_management_format(&keys)?;
-    
-    let response = client
-        .put(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .json(&body)
-        .send()
+    let request = state
+        .http
+        .management(Method::PUT, port, "gemini-api-key")
+        .json(&body);
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to set Gemini API keys: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to set Gemini API keys: {} - {}", status, text));
-    }
-    
+        .map_err(|e| CommandError::management("set Gemini API keys", e))?;
+
     // Persist to ProxyPal config for restart persistence
     {
-        let mut config = state.config.lock().unwrap();
+        let mut config = state.config.lock();
         config.gemini_api_keys = keys;
-        save_config_to_file(&config)?;
+        save_config_to_file(&config).map_err(CommandError::io)?;
     }
     
     Ok(())
 }
 
 #[tauri::command]
-pub async fn add_gemini_api_key(state: State<'_, AppState>, key: GeminiApiKey) -> Result<(), String> {
-    let mut keys = get_gemini_api_keys(state.clone()).await?;
-    keys.push(key);
-    set_gemini_api_keys(state, keys).await
+pub async fn add_gemini_api_key(state: State<'_, AppState>, key: GeminiApiKey) -> Result<(), CommandError> {
+    let result = async {
+        let mut keys = get_gemini_api_keys(state.clone()).await?;
+        keys.push(key);
+        put_gemini_api_keys(state, keys).await
+    }
+    .await;
+    crate::helpers::audit::record("add_gemini_api_key", vec!["geminiApiKeys: entry added".to_string()], &result);
+    result
 }
 
 #[tauri::command]
-pub async fn delete_gemini_api_key(state: State<'_, AppState>, index: usize) -> Result<(), String> {
-    let mut keys = get_gemini_api_keys(state.clone()).await?;
-    if index >= keys.len() {
-        return Err("Index out of bounds".to_string());
+pub async fn delete_gemini_api_key(state: State<'_, AppState>, index: usize) -> Result<(), CommandError> {
+    let result = async {
+        let mut keys = get_gemini_api_keys(state.clone()).await?;
+        if index >= keys.len() {
+            return Err(CommandError::invalid_input("Index out of bounds"));
+        }
+        keys.remove(index);
+        put_gemini_api_keys(state, keys).await
     }
-    keys.remove(index);
-    set_gemini_api_keys(state, keys).await
+    .await;
+    let changes = vec![format!("geminiApiKeys: entry {} removed", index)];
+    crate::helpers::audit::record("delete_gemini_api_key", changes, &result);
+    result
 }
 
 // ============================================
@@ -126,73 +134,73 @@ pub async fn delete_gemini_api_key(state: State<'_, AppState>, index: usize) ->
 // ============================================
 
 #[tauri::command]
-pub async fn get_claude_api_keys(state: State<'_, AppState>) -> Result<Vec<ClaudeApiKey>, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "claude-api-key");
-    
-    let client = crate::build_management_client();
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch Claude API keys: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Ok(Vec::new());
-    }
-    
-    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+pub async fn get_claude_api_keys(state: State<'_, AppState>) -> Result<Vec<ClaudeApiKey>, CommandError> {
+    let port = state.config.lock().port;
+    let request = state.http.management(Method::GET, port, "claude-api-key");
+    let json: serde_json::Value = match management_json(request).await {
+        Ok(json) => json,
+        Err(ManagementError::Status { .. }) => return Ok(Vec::new()),
+        Err(e) => return Err(CommandError::management("fetch Claude API keys", e)),
+    };
     convert_api_key_response(json, "claude-api-key")
 }
 
 #[tauri::command]
-pub async fn set_claude_api_keys(state: State<'_, AppState>, keys: Vec<ClaudeApiKey>) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "claude-api-key");
-    
-    let client = crate::build_management_client();
+pub async fn set_claude_api_keys(state: State<'_, AppState>, keys: Vec<ClaudeApiKey>) -> Result<(), CommandError> {
+    let before = state.config.lock().claude_api_keys.len();
+    let changes = vec![format!("claudeApiKeys: {} → {} entries", before, keys.len())];
+    let result = put_claude_api_keys(state, keys).await;
+    crate::helpers::audit::record("set_claude_api_keys", changes, &result);
+    result
+}
+
+async fn put_claude_api_keys(state: State<'_, AppState>, keys: Vec<ClaudeApiKey>) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
     let body = convert_to_management_format(&keys)?;
-    
-    let response = client
-        .put(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .json(&body)
-        .send()
+    let request = state
+        .http
+        .management(Method::PUT, port, "claude-api-key")
+        .json(&body);
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to set Claude API keys: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to set Claude API keys: {} - {}", status, text));
-    }
-    
+        .map_err(|e| CommandError::management("set Claude API keys", e))?;
+
     // Persist to ProxyPal config for restart persistence
     {
-        let mut config = state.config.lock().unwrap();
+        let mut config = state.config.lock();
         config.claude_api_keys = keys;
-        save_config_to_file(&config)?;
+        save_config_to_file(&config).map_err(CommandError::io)?;
     }
     
     Ok(())
 }
 
 #[tauri::command]
-pub async fn add_claude_api_key(state: State<'_, AppState>, key: ClaudeApiKey) -> Result<(), String> {
-    let mut keys = get_claude_api_keys(state.clone()).await?;
-    keys.push(key);
-    set_claude_api_keys(state, keys).await
+pub async fn add_claude_api_key(state: State<'_, AppState>, key: ClaudeApiKey) -> Result<(), CommandError> {
+    let result = async {
+        let mut keys = get_claude_api_keys(state.clone()).await?;
+        keys.push(key);
+        put_claude_api_keys(state, keys).await
+    }
+    .await;
+    crate::helpers::audit::record("add_claude_api_key", vec!["claudeApiKeys: entry added".to_string()], &result);
+    result
 }
 
 #[tauri::command]
-pub async fn delete_claude_api_key(state: State<'_, AppState>, index: usize) -> Result<(), String> {
-    let mut keys = get_claude_api_keys(state.clone()).await?;
-    if index >= keys.len() {
-        return Err("Index out of bounds".to_string());
+pub async fn delete_claude_api_key(state: State<'_, AppState>, index: usize) -> Result<(), CommandError> {
+    let result = async {
+        let mut keys = get_claude_api_keys(state.clone()).await?;
+        if index >= keys.len() {
+            return Err(CommandError::invalid_input("Index out of bounds"));
+        }
+        keys.remove(index);
+        put_claude_api_keys(state, keys).await
     }
-    keys.remove(index);
-    set_claude_api_keys(state, keys).await
+    .await;
+    let changes = vec![format!("claudeApiKeys: entry {} removed", index)];
+    crate::helpers::audit::record("delete_claude_api_key", changes, &result);
+    result
 }
 
 // ============================================
@@ -200,73 +208,73 @@ pub async fn delete_claude_api_key(state: State<'_, AppState>, index: usize) ->
 // ============================================
 
 #[tauri::command]
-pub async fn get_codex_api_keys(state: State<'_, AppState>) -> Result<Vec<CodexApiKey>, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "codex-api-key");
-    
-    let client = crate::build_management_client();
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch Codex API keys: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Ok(Vec::new());
-    }
-    
-    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+pub async fn get_codex_api_keys(state: State<'_, AppState>) -> Result<Vec<CodexApiKey>, CommandError> {
+    let port = state.config.lock().port;
+    let request = state.http.management(Method::GET, port, "codex-api-key");
+    let json: serde_json::Value = match management_json(request).await {
+        Ok(json) => json,
+        Err(ManagementError::Status { .. }) => return Ok(Vec::new()),
+        Err(e) => return Err(CommandError::management("fetch Codex API keys", e)),
+    };
     convert_api_key_response(json, "codex-api-key")
 }
 
 #[tauri::command]
-pub async fn set_codex_api_keys(state: State<'_, AppState>, keys: Vec<CodexApiKey>) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "codex-api-key");
-    
-    let client = crate::build_management_client();
+pub async fn set_codex_api_keys(state: State<'_, AppState>, keys: Vec<CodexApiKey>) -> Result<(), CommandError> {
+    let before = state.config.lock().codex_api_keys.len();
+    let changes = vec![format!("codexApiKeys: {} → {} entries", before, keys.len())];
+    let result = put_codex_api_keys(state, keys).await;
+    crate::helpers::audit::record("set_codex_api_keys", changes, &result);
+    result
+}
+
+async fn put_codex_api_keys(state: State<'_, AppState>, keys: Vec<CodexApiKey>) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
     let body = convert_to_management_format(&keys)?;
-    
-    let response = client
-        .put(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .json(&body)
-        .send()
+    let request = state
+        .http
+        .management(Method::PUT, port, "codex-api-key")
+        .json(&body);
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to set Codex API keys: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to set Codex API keys: {} - {}", status, text));
-    }
-    
+        .map_err(|e| CommandError::management("set Codex API keys", e))?;
+
     // Persist to ProxyPal config for restart persistence
     {
-        let mut config = state.config.lock().unwrap();
+        let mut config = state.config.lock();
         config.codex_api_keys = keys;
-        save_config_to_file(&config)?;
+        save_config_to_file(&config).map_err(CommandError::io)?;
     }
     
     Ok(())
 }
 
 #[tauri::command]
-pub async fn add_codex_api_key(state: State<'_, AppState>, key: CodexApiKey) -> Result<(), String> {
-    let mut keys = get_codex_api_keys(state.clone()).await?;
-    keys.push(key);
-    set_codex_api_keys(state, keys).await
+pub async fn add_codex_api_key(state: State<'_, AppState>, key: CodexApiKey) -> Result<(), CommandError> {
+    let result = async {
+        let mut keys = get_codex_api_keys(state.clone()).await?;
+        keys.push(key);
+        put_codex_api_keys(state, keys).await
+    }
+    .await;
+    crate::helpers::audit::record("add_codex_api_key", vec!["codexApiKeys: entry added".to_string()], &result);
+    result
 }
 
 #[tauri::command]
-pub async fn delete_codex_api_key(state: State<'_, AppState>, index: usize) -> Result<(), String> {
-    let mut keys = get_codex_api_keys(state.clone()).await?;
-    if index >= keys.len() {
-        return Err("Index out of bounds".to_string());
+pub async fn delete_codex_api_key(state: State<'_, AppState>, index: usize) -> Result<(), CommandError> {
+    let result = async {
+        let mut keys = get_codex_api_keys(state.clone()).await?;
+        if index >= keys.len() {
+            return Err(CommandError::invalid_input("Index out of bounds"));
+        }
+        keys.remove(index);
+        put_codex_api_keys(state, keys).await
     }
-    keys.remove(index);
-    set_codex_api_keys(state, keys).await
+    .await;
+    let changes = vec![format!("codexApiKeys: entry {} removed", index)];
+    crate::helpers::audit::record("delete_codex_api_key", changes, &result);
+    result
 }
 
 // ============================================
@@ -274,159 +282,331 @@ pub async fn delete_codex_api_key(state: State<'_, AppState>, index: usize) -> R
 // ============================================
 
 #[tauri::command]
-pub async fn get_vertex_api_keys(state: State<'_, AppState>) -> Result<Vec<VertexApiKey>, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "vertex-api-key");
-    
-    let client = crate::build_management_client();
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch Vertex API keys: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Ok(Vec::new());
-    }
-    
-    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+pub async fn get_vertex_api_keys(state: State<'_, AppState>) -> Result<Vec<VertexApiKey>, CommandError> {
+    let port = state.config.lock().port;
+    let request = state.http.management(Method::GET, port, "vertex-api-key");
+    let json: serde_json::Value = match management_json(request).await {
+        Ok(json) => json,
+        Err(ManagementError::Status { .. }) => return Ok(Vec::new()),
+        Err(e) => return Err(CommandError::management("fetch Vertex API keys", e)),
+    };
     convert_api_key_response(json, "vertex-api-key")
 }
 
 #[tauri::command]
-pub async fn set_vertex_api_keys(state: State<'_, AppState>, keys: Vec<VertexApiKey>) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "vertex-api-key");
-    
-    let client = crate::build_management_client();
+pub async fn set_vertex_api_keys(state: State<'_, AppState>, keys: Vec<VertexApiKey>) -> Result<(), CommandError> {
+    let before = state.config.lock().vertex_api_keys.len();
+    let changes = vec![format!("vertexApiKeys: {} → {} entries", before, keys.len())];
+    let result = put_vertex_api_keys(state, keys).await;
+    crate::helpers::audit::record("set_vertex_api_keys", changes, &result);
+    result
+}
+
+async fn put_vertex_api_keys(state: State<'_, AppState>, keys: Vec<VertexApiKey>) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
     let body = convert_to_management_format(&keys)?;
-    
-    let response = client
-        .put(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .json(&body)
-        .send()
+    let request = state
+        .http
+        .management(Method::PUT, port, "vertex-api-key")
+        .json(&body);
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to set Vertex API keys: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to set Vertex API keys: {} - {}", status, text));
-    }
-    
+        .map_err(|e| CommandError::management("set Vertex API keys", e))?;
+
     // Persist to ProxyPal config for restart persistence
     {
-        let mut config = state.config.lock().unwrap();
+        let mut config = state.config.lock();
         config.vertex_api_keys = keys;
-        save_config_to_file(&config)?;
+        save_config_to_file(&config).map_err(CommandError::io)?;
     }
     
     Ok(())
 }
 
 #[tauri::command]
-pub async fn add_vertex_api_key(state: State<'_, AppState>, key: VertexApiKey) -> Result<(), String> {
-    let mut keys = get_vertex_api_keys(state.clone()).await?;
-    keys.push(key);
-    set_vertex_api_keys(state, keys).await
+pub async fn add_vertex_api_key(state: State<'_, AppState>, key: VertexApiKey) -> Result<(), CommandError> {
+    let result = async {
+        let mut keys = get_vertex_api_keys(state.clone()).await?;
+        keys.push(key);
+        put_vertex_api_keys(state, keys).await
+    }
+    .await;
+    crate::helpers::audit::record("add_vertex_api_key", vec!["vertexApiKeys: entry added".to_string()], &result);
+    result
 }
 
 #[tauri::command]
-pub async fn delete_vertex_api_key(state: State<'_, AppState>, index: usize) -> Result<(), String> {
-    let mut keys = get_vertex_api_keys(state.clone()).await?;
-    if index >= keys.len() {
-        return Err("Index out of bounds".to_string());
+pub async fn delete_vertex_api_key(state: State<'_, AppState>, index: usize) -> Result<(), CommandError> {
+    let result = async {
+        let mut keys = get_vertex_api_keys(state.clone()).await?;
+        if index >= keys.len() {
+            return Err(CommandError::invalid_input("Index out of bounds"));
+        }
+        keys.remove(index);
+        put_vertex_api_keys(state, keys).await
     }
-    keys.remove(index);
-    set_vertex_api_keys(state, keys).await
+    .await;
+    let changes = vec![format!("vertexApiKeys: entry {} removed", index)];
+    crate::helpers::audit::record("delete_vertex_api_key", changes, &result);
+    result
 }
 
 // ============================================
-// OpenAI-Compatible Providers
+// Key-only registry providers (DeepSeek, Zhipu GLM)
 // ============================================
 
-#[tauri::command]
-pub async fn get_openai_compatible_providers(state: State<'_, AppState>) -> Result<Vec<OpenAICompatibleProvider>, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "openai-compatibility");
-    
-    let client = crate::build_management_client();
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch OpenAI-compatible providers: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Ok(Vec::new());
+// CLIProxyAPI has no section of its own for these providers, so each becomes
+// an OpenAI-compatible entry named after its registry id, at the registry's
+// base URL, serving the provider's well-known models.
+
+fn api_key_provider(provider: &str) -> Result<&'static crate::providers::ProviderMeta, CommandError> {
+    let meta = crate::providers::lookup(provider)?;
+    if meta.auth_kind != AuthKind::ApiKey {
+        return Err(CommandError::invalid_input(format!(
+            "{} is not connected with API keys",
+            meta.display_name
+        )));
     }
-    
-    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
-    convert_api_key_response(json, "openai-compatibility")
+    Ok(meta)
+}
+
+/// Whether an `openai-compatibility` entry was generated for a key-only provider
+pub(crate) fn is_api_key_provider_entry(name: &str) -> bool {
+    crate::providers::get(name).is_some_and(|p| p.auth_kind == AuthKind::ApiKey)
+}
+
+/// `openai-compatibility` entries for the key-only providers that have keys
+pub(crate) fn api_key_provider_entries(config: &AppConfig) -> Vec<OpenAICompatibleProvider> {
+    crate::providers::api_key_providers()
+        .filter_map(|meta| {
+            let keys: Vec<OpenAICompatibleApiKeyEntry> = config
+                .provider_api_keys(meta.id)?
+                .iter()
+                .filter(|k| !k.api_key.is_empty())
+                .cloned()
+                .collect();
+            if keys.is_empty() {
+                return None;
+            }
+            let models = crate::commands::models::fallback_model_ids(meta.id)
+                .iter()
+                .map(|id| ModelMapping {
+                    name: id.to_string(),
+                    alias: Some(id.to_string()),
+                })
+                .collect();
+            Some(OpenAICompatibleProvider {
+                name: meta.id.to_string(),
+                base_url: meta.api_base_url?.to_string(),
+                api_key_entries: keys,
+                models: Some(models),
+                headers: None,
+                prefix: None,
+            })
+        })
+        .collect()
 }
 
 #[tauri::command]
-pub async fn set_openai_compatible_providers(state: State<'_, AppState>, providers: Vec<OpenAICompatibleProvider>) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "openai-compatibility");
-    
-    let client = crate::build_management_client();
-    let body = convert_to_management_format(&providers)?;
-    
-    let response = client
-        .put(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to set OpenAI-compatible providers: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to set OpenAI-compatible providers: {} - {}", status, text));
+pub fn get_provider_api_keys(
+    state: State<'_, AppState>,
+    provider: String,
+) -> Result<Vec<OpenAICompatibleApiKeyEntry>, CommandError> {
+    api_key_provider(&provider)?;
+    Ok(state
+        .config
+        .lock()
+        .provider_api_keys(&provider)
+        .cloned()
+        .unwrap_or_default())
+}
+
+// Replace a key-only provider's keys. A running proxy gets them at once
+// through its OpenAI-compatible providers; otherwise they are written into
+// the config it next starts with.
+#[tauri::command]
+pub async fn set_provider_api_keys(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    provider: String,
+    keys: Vec<OpenAICompatibleApiKeyEntry>,
+) -> Result<(), CommandError> {
+    let before = state
+        .config
+        .lock()
+        .provider_api_keys(&provider)
+        .map_or(0, Vec::len);
+    let changes = vec![format!(
+        "{}ApiKeys: {} → {} entries",
+        provider,
+        before,
+        keys.len()
+    )];
+    let result = put_provider_api_keys(&app, state, &provider, keys).await;
+    crate::helpers::audit::record("set_provider_api_keys", changes, &result);
+    result
+}
+
+async fn put_provider_api_keys(
+    app: &AppHandle,
+    state: State<'_, AppState>,
+    provider: &str,
+    keys: Vec<OpenAICompatibleApiKeyEntry>,
+) -> Result<(), CommandError> {
+    let meta = api_key_provider(provider)?;
+    let keys: Vec<OpenAICompatibleApiKeyEntry> = keys
+        .into_iter()
+        .map(|key| OpenAICompatibleApiKeyEntry {
+            api_key: key.api_key.trim().to_string(),
+            proxy_url: key.proxy_url.filter(|url| !url.trim().is_empty()),
+        })
+        .collect();
+    if keys.iter().any(|k| k.api_key.is_empty()) {
+        return Err(CommandError::invalid_input(format!(
+            "{} API keys can't be empty",
+            meta.display_name
+        )));
     }
-    
-    // Persist to local config for restart persistence
+
     {
-        let mut config = state.config.lock().unwrap();
-        config.amp_openai_providers = providers.iter().map(|p| {
-            crate::types::amp::AmpOpenAIProvider {
-                id: uuid::Uuid::new_v4().to_string(),
-                name: p.name.clone(),
-                base_url: p.base_url.clone(),
-                api_key: p.api_key_entries.first().map(|e| e.api_key.clone()).unwrap_or_default(),
-                models: p.models.as_ref().map(|m| {
-                    m.iter().map(|model| crate::types::amp::AmpOpenAIModel {
-                        name: model.name.clone(),
-                        alias: model.alias.clone().unwrap_or_default(),
-                    }).collect()
-                }).unwrap_or_default(),
-            }
-        }).collect();
+        let mut config = state.config.lock();
+        if let Some(stored) = config.provider_api_keys_mut(provider) {
+            *stored = keys;
+        }
+        save_config_to_file(&config).map_err(CommandError::io)?;
+    }
+
+    // Re-send the proxy's list, which now carries the new keys
+    if state.proxy_status.lock().running {
+        let providers = get_openai_compatible_providers(state.clone()).await?;
+        put_openai_compatible_providers(state.clone(), providers).await?;
     }
-    let config_to_save = state.config.lock().unwrap().clone();
-    crate::config::save_config_to_file(&config_to_save)?;
+
+    let auth = {
+        let config = state.config.lock().clone();
+        let mut auth = state.auth_status.lock();
+        crate::commands::auth::count_api_keys(&mut auth, &config);
+        crate::config::save_auth_to_file(&auth).map_err(CommandError::io)?;
+        auth.clone()
+    };
+    let _ = app.emit("auth-status-changed", auth);
+    Ok(())
+}
+
+// ============================================
+// OpenAI-Compatible Providers
+// ============================================
+
+#[tauri::command]
+pub async fn get_openai_compatible_providers(state: State<'_, AppState>) -> Result<Vec<OpenAICompatibleProvider>, CommandError> {
+    let port = state.config.lock().port;
+    let request = state
+        .http
+        .management(Method::GET, port, "openai-compatibility");
+    let json: serde_json::Value = match management_json(request).await {
+        Ok(json) => json,
+        Err(ManagementError::Status { .. }) => return Ok(Vec::new()),
+        Err(e) => return Err(CommandError::management("fetch OpenAI-compatible providers", e)),
+    };
+    // Entries generated for Amp pools and key-only providers are managed
+    // through the pools and the provider's keys
+    let providers: Vec<OpenAICompatibleProvider> =
+        convert_api_key_response(json, "openai-compatibility")?;
+    Ok(providers
+        .into_iter()
+        .filter(|p| !amp_pools::is_pool_entry(&p.name) && !is_api_key_provider_entry(&p.name))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn set_openai_compatible_providers(state: State<'_, AppState>, providers: Vec<OpenAICompatibleProvider>) -> Result<(), CommandError> {
+    let before = state.config.lock().amp_openai_providers.len();
+    let changes = vec![format!("ampOpenaiProviders: {} → {} entries", before, providers.len())];
+    let result = put_openai_compatible_providers(state, providers).await;
+    crate::helpers::audit::record("set_openai_compatible_providers", changes, &result);
+    result
+}
+
+async fn put_openai_compatible_providers(state: State<'_, AppState>, providers: Vec<OpenAICompatibleProvider>) -> Result<(), CommandError> {
+    let (port, previous, pools, key_providers) = {
+        let config = state.config.lock();
+        (
+            config.port,
+            config.amp_openai_providers.clone(),
+            config.amp_pools.clone(),
+            api_key_provider_entries(&config),
+        )
+    };
+    let providers: Vec<OpenAICompatibleProvider> = providers
+        .into_iter()
+        .filter(|p| !amp_pools::is_pool_entry(&p.name) && !is_api_key_provider_entry(&p.name))
+        .collect();
+    // Keep ids by name, so Amp pools still find their members
+    let amp_providers: Vec<crate::types::amp::AmpOpenAIProvider> = providers.iter().map(|p| {
+        crate::types::amp::AmpOpenAIProvider {
+            id: previous
+                .iter()
+                .find(|old| old.name == p.name)
+                .map(|old| old.id.clone())
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            name: p.name.clone(),
+            base_url: p.base_url.clone(),
+            api_key: p.api_key_entries.first().map(|e| e.api_key.clone()).unwrap_or_default(),
+            models: p.models.as_ref().map(|m| {
+                m.iter().map(|model| crate::types::amp::AmpOpenAIModel {
+                    name: model.name.clone(),
+                    alias: model.alias.clone().unwrap_or_default(),
+                }).collect()
+            }).unwrap_or_default(),
+        }
+    }).collect();
+
+    // The proxy's list replaces the generated config's, pool members and
+    // key-only providers included
+    let mut live = providers;
+    live.extend(amp_pools::pool_providers(&pools, &amp_providers));
+    live.extend(key_providers);
+    let body = convert_to_management_format(&live)?;
+    let request = state
+        .http
+        .management(Method::PUT, port, "openai-compatibility")
+        .json(&body);
+    send_management(request)
+        .await
+        .map_err(|e| CommandError::management("set OpenAI-compatible providers", e))?;
+
+    // Persist to local config for restart persistence
+    state.config.lock().amp_openai_providers = amp_providers;
+    let config_to_save = state.config.lock().clone();
+    crate::config::save_config_to_file(&config_to_save).map_err(CommandError::io)?;
     
     Ok(())
 }
 
 #[tauri::command]
-pub async fn add_openai_compatible_provider(state: State<'_, AppState>, provider: OpenAICompatibleProvider) -> Result<(), String> {
-    let mut providers = get_openai_compatible_providers(state.clone()).await?;
-    providers.push(provider);
-    set_openai_compatible_providers(state, providers).await
+pub async fn add_openai_compatible_provider(state: State<'_, AppState>, provider: OpenAICompatibleProvider) -> Result<(), CommandError> {
+    let result = async {
+        let mut providers = get_openai_compatible_providers(state.clone()).await?;
+        providers.push(provider);
+        put_openai_compatible_providers(state, providers).await
+    }
+    .await;
+    crate::helpers::audit::record("add_openai_compatible_provider", vec!["ampOpenaiProviders: entry added".to_string()], &result);
+    result
 }
 
 #[tauri::command]
-pub async fn delete_openai_compatible_provider(state: State<'_, AppState>, index: usize) -> Result<(), String> {
-    let mut providers = get_openai_compatible_providers(state.clone()).await?;
-    if index >= providers.len() {
-        return Err("Index out of bounds".to_string());
+pub async fn delete_openai_compatible_provider(state: State<'_, AppState>, index: usize) -> Result<(), CommandError> {
+    let result = async {
+        let mut providers = get_openai_compatible_providers(state.clone()).await?;
+        if index >= providers.len() {
+            return Err(CommandError::invalid_input("Index out of bounds"));
+        }
+        providers.remove(index);
+        put_openai_compatible_providers(state, providers).await
     }
-    providers.remove(index);
-    set_openai_compatible_providers(state, providers).await
+    .await;
+    let changes = vec![format!("ampOpenaiProviders: entry {} removed", index)];
+    crate::helpers::audit::record("delete_openai_compatible_provider", changes, &result);
+    result
 }
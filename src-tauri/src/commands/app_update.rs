@@ -0,0 +1,61 @@
+//! ProxyPal release checks, separate from the CLIProxyAPI sidecar updates.
+
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::config::save_config_to_file;
+use crate::error::CommandError;
+use crate::state::AppState;
+use crate::types::AppUpdateInfo;
+
+// Ask for the newest release now. Errors (offline, rate limited) are for
+// manual checks to show; the daily background check only logs them.
+#[tauri::command]
+pub async fn check_app_update(app: AppHandle) -> Result<AppUpdateInfo, CommandError> {
+    crate::app_update::check(&app).await
+}
+
+// Whether the updater can install releases on this platform/install type.
+// Linux .deb installations do NOT support auto-update (only AppImage does).
+#[tauri::command]
+pub fn is_updater_supported() -> serde_json::Value {
+    let (supported, reason) = crate::app_update::auto_install_support();
+    serde_json::json!({ "supported": supported, "reason": reason })
+}
+
+// Result of the last successful check this session, without the network
+#[tauri::command]
+pub fn get_app_update(state: State<AppState>) -> Option<AppUpdateInfo> {
+    state.app_update.lock().clone()
+}
+
+// Stop announcing `version`; a later release is announced as usual
+#[tauri::command]
+pub fn dismiss_app_update(state: State<AppState>, version: String) -> Result<(), CommandError> {
+    state.ensure_hydrated()?;
+    {
+        let mut config = state.config.lock();
+        config.dismissed_app_version = Some(version.clone());
+        save_config_to_file(&config).map_err(CommandError::io)?;
+    }
+    if let Some(info) = state.app_update.lock().as_mut() {
+        info.dismissed = info.available && info.latest == version;
+    }
+    Ok(())
+}
+
+// Open the release page of the last found update (or the latest release) in
+// the browser, for installs the updater can't replace
+#[tauri::command]
+pub fn open_app_release_page(app: AppHandle, state: State<AppState>) -> Result<(), CommandError> {
+    let url = state
+        .app_update
+        .lock()
+        .as_ref()
+        .filter(|info| info.available)
+        .map(|info| info.url.clone())
+        .unwrap_or_else(crate::app_update::latest_release_page);
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| CommandError::from(format!("Failed to open the release page: {}", e)))
+}
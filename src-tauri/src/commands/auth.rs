@@ -3,11 +3,23 @@
 //! Extracted from lib.rs — handles authentication status, OAuth flows,
 //! provider connection/disconnection, and credential management.
 
+use crate::commands::onboarding::mark_onboarding_step;
+use crate::config::{save_config_to_file, AppConfig};
+use crate::error::{CommandError, ErrorCode};
+use crate::helpers::credential_refresh::{self, PendingReauth};
+use crate::helpers::oauth_flows::FLOW_TIMEOUT_MS;
+use crate::helpers::provider_checks;
+use crate::http::{management_json, HttpClients, ManagementError};
+use crate::providers::{AuthKind, ProviderMeta};
 use crate::state::AppState;
-use crate::types::{AuthStatus, OAuthState};
-use crate::utils::provider_filename_prefixes;
+use crate::types::{
+    AuthStatus, CredentialRefresh, OAuthFlowStatus, OAuthState, ProviderVerification,
+    STEP_PROVIDER_CONNECTED,
+};
+use crate::utils::{detect_provider_from_filename, provider_filename_prefixes};
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
-use tauri::{Emitter, State};
+use tauri::{Emitter, Manager, State};
 use tauri_plugin_opener::OpenerExt;
 
 /// OAuth URL response for frontend modal
@@ -29,7 +41,13 @@ pub struct DeviceCodeResponse {
 
 #[tauri::command]
 pub fn get_auth_status(state: State<AppState>) -> AuthStatus {
-    state.auth_status.lock().unwrap().clone()
+    state.auth_status.lock().clone()
+}
+
+/// Provider registry (display names, logos, auth kinds) for the frontend
+#[tauri::command]
+pub fn get_providers() -> Vec<ProviderMeta> {
+    crate::providers::PROVIDERS.to_vec()
 }
 
 /// Get OAuth URL without opening browser (for modal flow)
@@ -37,101 +55,29 @@ pub fn get_auth_status(state: State<AppState>) -> AuthStatus {
 pub async fn get_oauth_url(
     state: State<'_, AppState>,
     provider: String,
-) -> Result<OAuthUrlResponse, String> {
+) -> Result<OAuthUrlResponse, CommandError> {
     // Get proxy port from config
     let port = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         config.port
     };
 
-    // Kiro uses a web UI page directly, not a JSON API endpoint
+    // Providers like Kiro use a web UI page directly, not a JSON API endpoint
     // Return the URL directly without making an HTTP request
-    if provider == "kiro" {
-        let kiro_url = format!("http://127.0.0.1:{}/v0/oauth/kiro", port);
+    if let Some(login_url) = login_page_url(&provider, port)? {
         return Ok(OAuthUrlResponse {
-            url: kiro_url,
+            url: login_url,
             state: String::new(),
         });
     }
 
-    // Get the OAuth URL from CLIProxyAPI's Management API
-    // Add is_webui=true to use the embedded callback forwarder
-    // Use 127.0.0.1 consistently (not localhost) to avoid access control issues
-    let endpoint = match provider.as_str() {
-        "claude" => format!(
-            "http://127.0.0.1:{}/v0/management/anthropic-auth-url?is_webui=true",
-            port
-        ),
-        "openai" => format!(
-            "http://127.0.0.1:{}/v0/management/codex-auth-url?is_webui=true",
-            port
-        ),
-        "gemini" => format!(
-            "http://127.0.0.1:{}/v0/management/gemini-cli-auth-url?is_webui=true",
-            port
-        ),
-        "qwen" => format!(
-            "http://127.0.0.1:{}/v0/management/qwen-auth-url?is_webui=true",
-            port
-        ),
-        "iflow" => format!(
-            "http://127.0.0.1:{}/v0/management/iflow-auth-url?is_webui=true",
-            port
-        ),
-        "antigravity" => format!(
-            "http://127.0.0.1:{}/v0/management/antigravity-auth-url?is_webui=true",
-            port
-        ),
-        "kimi" => format!(
-            "http://127.0.0.1:{}/v0/management/kimi-auth-url?is_webui=true",
-            port
-        ),
-        "vertex" => {
-            return Err(
-                "Vertex uses service account import, not OAuth. Use import_vertex_credential instead."
-                    .to_string(),
-            )
-        }
-        _ => return Err(format!("Unknown provider: {}", provider)),
-    };
-
-    // Make HTTP request to get OAuth URL
-    let client = crate::build_management_client();
-    let response = client
-        .get(&endpoint)
-        .header("X-Management-Key", &crate::get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to get OAuth URL: {}. Is the proxy running?", e))?;
+    let (oauth_url, oauth_state) = fetch_oauth_url(&state.http, port, &provider).await?;
 
-    if !response.status().is_success() {
-        return Err(format!(
-            "Management API returned error: {}",
-            response.status()
-        ));
-    }
-
-    // Parse response to get URL and state
-    let body: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    let oauth_url = body["url"]
-        .as_str()
-        .ok_or("No URL in response")?
-        .to_string();
-
-    let oauth_state = body["state"].as_str().unwrap_or("").to_string();
-
-    // Store pending OAuth state
-    {
-        let mut pending = state.pending_oauth.lock().unwrap();
-        *pending = Some(OAuthState {
-            provider: provider.clone(),
-            state: oauth_state.clone(),
-        });
-    }
+    // Track the flow alongside any others in progress
+    state
+        .oauth_flows
+        .lock()
+        .start(&provider, &oauth_state, now_ms(), FLOW_TIMEOUT_MS);
 
     Ok(OAuthUrlResponse {
         url: oauth_url,
@@ -143,53 +89,55 @@ pub async fn get_oauth_url(
 pub async fn get_device_code(
     state: State<'_, AppState>,
     provider: String,
-) -> Result<DeviceCodeResponse, String> {
+) -> Result<DeviceCodeResponse, CommandError> {
     // Get the proxy port from config
     let port = {
-        let config = state.config.lock().map_err(|e| e.to_string())?;
+        let config = state.config.lock();
         config.port
     };
 
     // Build endpoint WITHOUT ?is_webui=true to trigger device-code flow
-    let endpoint = match provider.as_str() {
-        "openai" => format!(
-            "http://127.0.0.1:{}/v0/management/codex-auth-url",
-            port
-        ),
-        "qwen" => format!(
-            "http://127.0.0.1:{}/v0/management/qwen-auth-url",
-            port
-        ),
-        _ => return Err(format!("Device code flow not supported for provider: {}", provider)),
+    let meta = crate::providers::lookup(&provider)?;
+    let endpoint = match meta.management_auth_endpoint {
+        Some(endpoint) if meta.device_code => endpoint,
+        _ => {
+            return Err(CommandError::invalid_input(format!(
+                "Device code flow not supported for provider: {}",
+                provider
+            )))
+        }
     };
 
-    let client = crate::build_management_client();
-    let response = client
-        .get(&endpoint)
-        .header("X-Management-Key", &crate::get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to get device code: {}. Is the proxy running?", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!(
-            "Device code request failed ({}): {}",
-            status, body
-        ));
-    }
-
-    let body: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse device code response: {}", e))?;
+    let request = state.http.management(Method::GET, port, endpoint);
+    let body: serde_json::Value = match management_json(request).await {
+        Ok(body) => body,
+        Err(ManagementError::Status { status, body }) => {
+            return Err(CommandError::new(
+                ErrorCode::Upstream,
+                format!("Device code request failed ({}): {}", status, body),
+            )
+            .with_details(serde_json::json!({ "status": status.as_u16() })))
+        }
+        Err(e @ ManagementError::Unreachable(_)) => {
+            return Err(
+                CommandError::from(e).map_message(|m| format!("Failed to get device code: {}", m))
+            )
+        }
+        Err(e) => {
+            return Err(CommandError::new(
+                ErrorCode::Upstream,
+                format!("Failed to parse device code response: {}", e),
+            ))
+        }
+    };
 
     let verification_uri = body["verification_uri"]
         .as_str()
         .or_else(|| body["verification_url"].as_str())
         .or_else(|| body["url"].as_str())
-        .ok_or("Missing verification_uri in response")?
+        .ok_or_else(|| {
+            CommandError::new(ErrorCode::Upstream, "Missing verification_uri in response")
+        })?
         .to_string();
 
     let user_code = body["user_code"].as_str().unwrap_or("").to_string();
@@ -203,14 +151,11 @@ pub async fn get_device_code(
     let expires_in = body["expires_in"].as_u64().unwrap_or(900);
     let interval = body["interval"].as_u64().unwrap_or(5);
 
-    // Store pending OAuth state for callback matching
-    {
-        let mut pending = state.pending_oauth.lock().map_err(|e| e.to_string())?;
-        *pending = Some(OAuthState {
-            provider: provider.clone(),
-            state: oauth_state.clone(),
-        });
-    }
+    // Track the flow for callback matching; it lasts as long as the code
+    state
+        .oauth_flows
+        .lock()
+        .start(&provider, &oauth_state, now_ms(), expires_in * 1000);
 
     Ok(DeviceCodeResponse {
         verification_uri,
@@ -223,169 +168,219 @@ pub async fn get_device_code(
 
 /// Open a URL in the default browser
 #[tauri::command]
-pub async fn open_url_in_browser(app: tauri::AppHandle, url: String) -> Result<(), String> {
+pub async fn open_url_in_browser(app: tauri::AppHandle, url: String) -> Result<(), CommandError> {
     app.opener()
         .open_url(&url, None::<&str>)
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| CommandError::invalid_input(e.to_string()))?;
     Ok(())
 }
 
+/// The proxy's own login page for providers that use one instead of the
+/// Management API (Kiro), None for the others
+fn login_page_url(provider: &str, port: u16) -> Result<Option<String>, CommandError> {
+    let meta = crate::providers::lookup(provider)?;
+    Ok(meta
+        .login_page
+        .map(|page| format!("http://127.0.0.1:{}{}", port, page)))
+}
+
+/// Ask CLIProxyAPI's Management API for a provider's OAuth URL.
+/// Returns the URL to open and the state to poll with. Providers with a
+/// `login_page` are not covered: they use the proxy's own web UI instead.
+pub(crate) async fn fetch_oauth_url(
+    http: &HttpClients,
+    port: u16,
+    provider: &str,
+) -> Result<(String, String), CommandError> {
+    // Get the OAuth URL from CLIProxyAPI's Management API
+    // Add is_webui=true to use the embedded callback forwarder
+    let meta = crate::providers::lookup(provider)?;
+    let endpoint = match meta.management_auth_endpoint {
+        Some(endpoint) => endpoint,
+        None if meta.auth_kind == AuthKind::ServiceAccount => {
+            return Err(CommandError::invalid_input(format!(
+                "{} uses service account import, not OAuth. Use import_vertex_credential instead.",
+                meta.display_name
+            )))
+        }
+        None if meta.auth_kind == AuthKind::ApiKey => {
+            return Err(CommandError::invalid_input(format!(
+                "{} is connected with an API key, not OAuth. Add one with set_provider_api_keys instead.",
+                meta.display_name
+            )))
+        }
+        None => {
+            return Err(CommandError::invalid_input(format!(
+                "{} has no OAuth URL to fetch",
+                meta.display_name
+            )))
+        }
+    };
+
+    // Make HTTP request to get OAuth URL
+    let request = http
+        .management(Method::GET, port, endpoint)
+        .query(&[("is_webui", "true")]);
+    let body: serde_json::Value = match management_json(request).await {
+        Ok(body) => body,
+        Err(ManagementError::Status { status, .. }) => {
+            return Err(CommandError::new(
+                ErrorCode::Upstream,
+                format!("Management API returned error: {}", status),
+            )
+            .with_details(serde_json::json!({ "status": status.as_u16() })))
+        }
+        Err(e @ ManagementError::Unreachable(_)) => {
+            return Err(
+                CommandError::from(e).map_message(|m| format!("Failed to get OAuth URL: {}", m))
+            )
+        }
+        Err(e) => {
+            return Err(CommandError::new(
+                ErrorCode::Upstream,
+                format!("Failed to parse response: {}", e),
+            ))
+        }
+    };
+
+    let oauth_url = body["url"]
+        .as_str()
+        .ok_or_else(|| CommandError::new(ErrorCode::Upstream, "No URL in response"))?
+        .to_string();
+
+    let oauth_state = body["state"].as_str().unwrap_or("").to_string();
+
+    Ok((oauth_url, oauth_state))
+}
+
 #[tauri::command]
 pub async fn open_oauth(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     provider: String,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     // Get proxy port from config
     let port = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         config.port
     };
 
     // For Kiro, open the Web OAuth UI directly in CLIProxyAPIPlus
-    if provider == "kiro" {
-        let oauth_url = format!("http://127.0.0.1:{}/v0/oauth/kiro", port);
+    if let Some(oauth_url) = login_page_url(&provider, port)? {
         app.opener()
             .open_url(&oauth_url, None::<&str>)
-            .map_err(|e| format!("Failed to open URL: {}", e))?;
+            .map_err(|e| CommandError::from(format!("Failed to open URL: {}", e)))?;
         return Ok(String::new()); // No specific state needed for direct Web UI
     }
 
-    // Get the OAuth URL from CLIProxyAPI's Management API
-    // Add is_webui=true to use the embedded callback forwarder
-    // Use 127.0.0.1 consistently (not localhost) to avoid access control issues
-    let endpoint = match provider.as_str() {
-        "claude" => format!(
-            "http://127.0.0.1:{}/v0/management/anthropic-auth-url?is_webui=true",
-            port
-        ),
-        "openai" => format!(
-            "http://127.0.0.1:{}/v0/management/codex-auth-url?is_webui=true",
-            port
-        ),
-        "gemini" => format!(
-            "http://127.0.0.1:{}/v0/management/gemini-cli-auth-url?is_webui=true",
-            port
-        ),
-        "qwen" => format!(
-            "http://127.0.0.1:{}/v0/management/qwen-auth-url?is_webui=true",
-            port
-        ),
-        "iflow" => format!(
-            "http://127.0.0.1:{}/v0/management/iflow-auth-url?is_webui=true",
-            port
-        ),
-        "antigravity" => format!(
-            "http://127.0.0.1:{}/v0/management/antigravity-auth-url?is_webui=true",
-            port
-        ),
-        "kimi" => format!(
-            "http://127.0.0.1:{}/v0/management/kimi-auth-url?is_webui=true",
-            port
-        ),
-        "vertex" => {
-            return Err(
-                "Vertex uses service account import, not OAuth. Use import_vertex_credential instead."
-                    .to_string(),
-            )
-        }
-        // Note: Kiro is handled above with direct Web UI
-        _ => return Err(format!("Unknown provider: {}", provider)),
-    };
+    let (oauth_url, oauth_state) = fetch_oauth_url(&state.http, port, &provider).await?;
 
-    // Make HTTP request to get OAuth URL
-    let client = crate::build_management_client();
-    let response = client
-        .get(&endpoint)
-        .header("X-Management-Key", &crate::get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to get OAuth URL: {}. Is the proxy running?", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "Management API returned error: {}",
-            response.status()
-        ));
-    }
-
-    // Parse response to get URL and state
-    let body: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    let oauth_url = body["url"]
-        .as_str()
-        .ok_or("No URL in response")?
-        .to_string();
-
-    let oauth_state = body["state"].as_str().unwrap_or("").to_string();
-
-    // Store pending OAuth state
-    {
-        let mut pending = state.pending_oauth.lock().unwrap();
-        *pending = Some(OAuthState {
-            provider: provider.clone(),
-            state: oauth_state.clone(),
-        });
-    }
+    // Track the flow alongside any others in progress
+    state
+        .oauth_flows
+        .lock()
+        .start(&provider, &oauth_state, now_ms(), FLOW_TIMEOUT_MS);
 
     // Open the OAuth URL in the default browser
     app.opener()
         .open_url(&oauth_url, None::<&str>)
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| CommandError::from(e.to_string()))?;
 
     // Return the state so frontend can poll for completion
     Ok(oauth_state)
 }
 
+/// Whether the OAuth flow started with `oauth_state` has completed
+pub(crate) async fn check_oauth_status(
+    http: &HttpClients,
+    port: u16,
+    oauth_state: &str,
+) -> Result<bool, CommandError> {
+    let request = http
+        .management(Method::GET, port, "get-auth-status")
+        .query(&[("state", oauth_state)]);
+    let body: serde_json::Value = match management_json(request).await {
+        Ok(body) => body,
+        Err(ManagementError::Status { .. }) => return Ok(false), // Not ready yet
+        Err(e @ ManagementError::Unreachable(_)) => {
+            return Err(CommandError::from(e)
+                .map_message(|m| format!("Failed to poll OAuth status: {}", m)))
+        }
+        Err(e) => {
+            return Err(CommandError::new(
+                ErrorCode::Upstream,
+                format!("Failed to parse response: {}", e),
+            ))
+        }
+    };
+
+    // Check if auth is complete - CLIProxyAPI returns { "status": "ok" } when done
+    let status = body["status"].as_str().unwrap_or("wait");
+    Ok(status == "ok")
+}
+
 #[tauri::command]
 pub async fn poll_oauth_status(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     oauth_state: String,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
+    let flow = state.oauth_flows.lock().get(&oauth_state, now_ms());
+    let flow = flow.ok_or_else(|| {
+        CommandError::not_found("This sign-in expired or was cancelled; start it again")
+    })?;
+    if flow.status == OAuthFlowStatus::Completed {
+        return Ok(true);
+    }
+
     let port = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         config.port
     };
 
-    let endpoint = format!(
-        "http://localhost:{}/v0/management/get-auth-status?state={}",
-        port, oauth_state
-    );
-
-    let client = crate::build_management_client();
-    let response = client
-        .get(&endpoint)
-        .header("X-Management-Key", &crate::get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to poll OAuth status: {}", e))?;
-
-    if !response.status().is_success() {
-        return Ok(false); // Not ready yet
+    let completed = check_oauth_status(&state.http, port, &oauth_state).await?;
+    if completed {
+        let reauth = {
+            let mut flows = state.oauth_flows.lock();
+            flows.set_status(&oauth_state, OAuthFlowStatus::Completed, now_ms());
+            flows.take_reauth(&oauth_state)
+        };
+        // A sign-in from refresh_provider_token replaces its credential
+        if let Some(reauth) = reauth {
+            let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
+            credential_refresh::finish_reauth(&auth_dir, &reauth).map_err(CommandError::io)?;
+            verify_in_background(&app, vec![reauth.provider]);
+        }
     }
+    Ok(completed)
+}
 
-    let body: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
-
-    // Check if auth is complete - CLIProxyAPI returns { "status": "ok" } when done
-    let status = body["status"].as_str().unwrap_or("wait");
-    Ok(status == "ok")
+/// Sign-ins still in progress, oldest first, so the UI can offer to cancel them
+#[tauri::command]
+pub fn list_pending_oauth(state: State<AppState>) -> Vec<OAuthState> {
+    state.oauth_flows.lock().in_progress(now_ms())
 }
 
+/// Stop tracking one sign-in; the others carry on
 #[tauri::command]
-pub async fn refresh_auth_status(
-    app: tauri::AppHandle,
-    state: State<'_, AppState>,
-) -> Result<AuthStatus, String> {
+pub fn cancel_oauth(state: State<AppState>, oauth_state: String) -> Result<(), CommandError> {
+    state
+        .oauth_flows
+        .lock()
+        .cancel(&oauth_state)
+        .map(|_| ())
+        .ok_or_else(|| CommandError::not_found("No sign-in in progress with that state"))
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Count credential files per provider in CLIProxyAPI's auth directory, and
+/// the keys entered for key-only providers
+pub(crate) fn scan_auth_dir(config: &AppConfig) -> AuthStatus {
     // Check CLIProxyAPI's auth directory for credentials
-    let auth_dir = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".cli-proxy-api");
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
 
     let mut new_auth = AuthStatus::default();
 
@@ -394,50 +389,60 @@ pub async fn refresh_auth_status(
         for entry in entries.flatten() {
             let filename = entry.file_name().to_string_lossy().to_lowercase();
 
-            // CLIProxyAPI naming patterns:
-            // - claude-{email}.json or anthropic-*.json
-            // - codex-{email}.json
-            // - gemini-{email}-{project}.json
-            // - qwen-{email}.json
-            // - iflow-{email}.json
-            // - vertex-{project_id}.json
-            // - antigravity-{email}.json
-
-            if filename.ends_with(".json") {
-                if filename.starts_with("claude-") || filename.starts_with("anthropic-") {
-                    new_auth.claude += 1;
-                } else if filename.starts_with("codex-") {
-                    new_auth.openai += 1;
-                } else if filename.starts_with("gemini-") {
-                    new_auth.gemini += 1;
-                } else if filename.starts_with("qwen-") {
-                    new_auth.qwen += 1;
-                } else if filename.starts_with("iflow-") {
-                    new_auth.iflow += 1;
-                } else if filename.starts_with("vertex-") {
-                    new_auth.vertex += 1;
-                } else if filename.starts_with("kiro-") {
-                    new_auth.kiro += 1;
-                } else if filename.starts_with("antigravity-") {
-                    new_auth.antigravity += 1;
-                } else if filename.starts_with("kimi-") {
-                    new_auth.kimi += 1;
-                }
+            // CLIProxyAPI names files {prefix}{email or project}.json, e.g.
+            // claude-{email}.json or gemini-{email}-{project}.json
+            if !filename.ends_with(".json") {
+                continue;
+            }
+            if let Some(count) = new_auth.count_mut(detect_provider_from_filename(&filename)) {
+                *count += 1;
             }
         }
     }
+    count_api_keys(&mut new_auth, config);
+
+    new_auth
+}
+
+/// Set the counts of key-only providers from the keys in `config`
+pub(crate) fn count_api_keys(auth: &mut AuthStatus, config: &AppConfig) {
+    for provider in crate::providers::api_key_providers() {
+        let keys = config.provider_api_keys(provider.id).map_or(0, Vec::len);
+        if let Some(count) = auth.count_mut(provider.id) {
+            *count = keys as u32;
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn refresh_auth_status(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AuthStatus, CommandError> {
+    let config = state.config.lock().clone();
+    let mut new_auth = scan_auth_dir(&config);
 
     // Update state
-    {
-        let mut auth = state.auth_status.lock().unwrap();
+    let added: Vec<String> = {
+        let mut auth = state.auth_status.lock();
+        new_auth.keep_verification(&auth);
+        let added = crate::providers::ids()
+            .filter(|id| new_auth.count(id) > auth.count(id))
+            .map(|id| id.to_string())
+            .collect();
         *auth = new_auth.clone();
-    }
+        added
+    };
 
     // Save to our config
-    crate::save_auth_to_file(&new_auth)?;
+    crate::config::save_auth_to_file(&new_auth)?;
 
     // Emit auth status update
     let _ = app.emit("auth-status-changed", new_auth.clone());
+    if new_auth.any_connected() {
+        mark_onboarding_step(&app, STEP_PROVIDER_CONNECTED);
+    }
+    verify_in_background(&app, added);
 
     Ok(new_auth)
 }
@@ -448,7 +453,7 @@ pub async fn complete_oauth(
     state: State<'_, AppState>,
     provider: String,
     code: String,
-) -> Result<AuthStatus, String> {
+) -> Result<AuthStatus, CommandError> {
     // In a real implementation, we would:
     // 1. Exchange the code for tokens
     // 2. Store the tokens securely (keychain/credential manager)
@@ -457,51 +462,169 @@ pub async fn complete_oauth(
 
     // For now, just increment the account count
     {
-        let mut auth = state.auth_status.lock().unwrap();
-        match provider.as_str() {
-            "claude" => auth.claude += 1,
-            "openai" => auth.openai += 1,
-            "gemini" => auth.gemini += 1,
-            "qwen" => auth.qwen += 1,
-            "iflow" => auth.iflow += 1,
-            "vertex" => auth.vertex += 1,
-            "kiro" => auth.kiro += 1,
-            "antigravity" => auth.antigravity += 1,
-            "kimi" => auth.kimi += 1,
-            _ => return Err(format!("Unknown provider: {}", provider)),
+        let mut auth = state.auth_status.lock();
+        match auth.count_mut(&provider) {
+            Some(count) => *count += 1,
+            None => {
+                return Err(CommandError::invalid_input(format!(
+                    "Unknown provider: {}",
+                    provider
+                )))
+            }
         }
 
         // Save to file
-        crate::save_auth_to_file(&auth)?;
+        crate::config::save_auth_to_file(&auth)?;
 
-        // Clear pending OAuth
-        let mut pending = state.pending_oauth.lock().unwrap();
-        *pending = None;
+        // Finish this provider's flow only; other sign-ins stay pending
+        {
+            let mut flows = state.oauth_flows.lock();
+            if let Some(oauth_state) = flows.awaiting_exchange(&provider, now_ms()) {
+                flows.set_status(&oauth_state, OAuthFlowStatus::Completed, now_ms());
+            }
+        }
 
         // Emit auth status update
         let _ = app.emit("auth-status-changed", auth.clone());
+        mark_onboarding_step(&app, STEP_PROVIDER_CONNECTED);
+        verify_in_background(&app, vec![provider]);
 
         Ok(auth.clone())
     }
 }
 
+// Send a one-token request through the proxy with a model the provider
+// serves, and record whether its accounts can actually be used. Other
+// providers serving the same model may answer instead, so this is best
+// checked right after the provider's first account is added.
+async fn run_provider_check(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    provider: &str,
+) -> Result<ProviderVerification, CommandError> {
+    if state.auth_status.lock().count(provider) == 0 {
+        return Err(CommandError::new(
+            ErrorCode::NotFound,
+            format!("No {} account is connected", provider),
+        ));
+    }
+    let (port, api_key) = {
+        let config = state.config.lock();
+        (config.port, config.proxy_api_key.clone())
+    };
+    if !state.proxy_status.lock().running {
+        return Err(CommandError::new(
+            ErrorCode::ProxyNotRunning,
+            "Start the proxy to verify the account",
+        ));
+    }
+    let models = crate::commands::models::cached_models(state);
+    let model = provider_checks::check_model(provider, &models).ok_or_else(|| {
+        CommandError::not_found(format!("No model known to check {} with", provider))
+    })?;
+
+    let body = serde_json::json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": "Reply with OK" }],
+        "max_tokens": 1,
+    });
+    let response = state
+        .http
+        .local()
+        .post(format!("http://127.0.0.1:{}/v1/chat/completions", port))
+        .bearer_auth(&api_key)
+        .timeout(std::time::Duration::from_secs(60))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| {
+            CommandError::new(ErrorCode::Upstream, format!("Check request failed: {}", e))
+        })?;
+    let status = response.status().as_u16();
+    let text = response.text().await.unwrap_or_default();
+    let diagnosis = provider_checks::diagnose(provider, status, &text);
+
+    let verification = ProviderVerification {
+        provider: provider.to_string(),
+        verified: diagnosis.is_none(),
+        model,
+        status: Some(status),
+        issue: diagnosis.as_ref().map(|d| d.issue.to_string()),
+        guidance: diagnosis.as_ref().map(|d| d.guidance.clone()),
+        link: diagnosis.and_then(|d| d.link).map(String::from),
+        checked_at: chrono::Utc::now().timestamp_millis() as u64,
+    };
+    let auth = {
+        let mut auth = state.auth_status.lock();
+        auth.verified
+            .insert(provider.to_string(), verification.verified);
+        auth.clone()
+    };
+    crate::config::save_auth_to_file(&auth)?;
+    let _ = app.emit("auth-status-changed", auth);
+    Ok(verification)
+}
+
+// Check that a connected provider's accounts can serve requests, e.g. that a
+// Gemini account has a Cloud project with Code Assist enabled. The account
+// stays connected either way; `verified` in the auth status records the result.
+#[tauri::command]
+pub async fn verify_provider(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    provider: String,
+) -> Result<ProviderVerification, CommandError> {
+    run_provider_check(&app, &state, &provider).await
+}
+
+/// Check newly connected providers without holding up the caller
+fn verify_in_background(app: &tauri::AppHandle, providers: Vec<String>) {
+    if providers.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        for provider in providers {
+            match run_provider_check(&app, &state, &provider).await {
+                Ok(check) if !check.verified => eprintln!(
+                    "[ProxyPal] {} check failed: {}",
+                    provider,
+                    check.issue.unwrap_or_default()
+                ),
+                Ok(_) => {}
+                Err(e) => eprintln!("[ProxyPal] Could not verify {}: {}", provider, e),
+            }
+        }
+    });
+}
+
 #[tauri::command]
 pub async fn disconnect_provider(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     provider: String,
-) -> Result<AuthStatus, String> {
-    // Delete credential files from ~/.cli-proxy-api/ for this provider
-    let auth_dir = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".cli-proxy-api");
+) -> Result<AuthStatus, CommandError> {
+    let changes = vec![format!("provider: {}", provider)];
+    let result = remove_provider_credentials(app, state, provider).await;
+    crate::helpers::audit::record("disconnect_provider", changes, &result);
+    result
+}
+
+async fn remove_provider_credentials(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    provider: String,
+) -> Result<AuthStatus, CommandError> {
+    // Delete credential files from the auth directory for this provider
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
 
     if auth_dir.exists() {
         if let Ok(entries) = std::fs::read_dir(&auth_dir) {
             for entry in entries.flatten() {
                 let filename = entry.file_name().to_string_lossy().to_lowercase();
 
-                // Match credential files by provider prefix (single source of truth in utils.rs)
+                // Match credential files by provider prefix (from the provider registry)
                 let prefixes = provider_filename_prefixes(provider.as_str());
                 let should_delete = prefixes.iter().any(|p| filename.starts_with(p));
 
@@ -514,26 +637,214 @@ pub async fn disconnect_provider(
         }
     }
 
-    let mut auth = state.auth_status.lock().unwrap();
-
-    match provider.as_str() {
-        "claude" => auth.claude = 0,
-        "openai" => auth.openai = 0,
-        "gemini" => auth.gemini = 0,
-        "qwen" => auth.qwen = 0,
-        "iflow" => auth.iflow = 0,
-        "vertex" => auth.vertex = 0,
-        "kiro" => auth.kiro = 0,
-        "antigravity" => auth.antigravity = 0,
-        "kimi" => auth.kimi = 0,
-        _ => return Err(format!("Unknown provider: {}", provider)),
+    // Key-only providers have no files; their keys go from the config instead
+    {
+        let mut config = state.config.lock();
+        if let Some(keys) = config.provider_api_keys_mut(&provider) {
+            if !keys.is_empty() {
+                keys.clear();
+                save_config_to_file(&config)?;
+            }
+        }
+    }
+
+    let mut auth = state.auth_status.lock();
+
+    match auth.count_mut(&provider) {
+        Some(count) => *count = 0,
+        None => {
+            return Err(CommandError::invalid_input(format!(
+                "Unknown provider: {}",
+                provider
+            )))
+        }
     }
 
     // Save to file
-    crate::save_auth_to_file(&auth)?;
+    crate::config::save_auth_to_file(&auth)?;
 
     // Emit auth status update
     let _ = app.emit("auth-status-changed", auth.clone());
 
     Ok(auth.clone())
 }
+
+// Ask the Management API to refresh one credential's token now; returns the
+// new expiry when the reply has one. Errors carry the status to report:
+// "reauth-needed" only when the proxy has no refresh endpoint at all.
+async fn refresh_via_management(
+    http: &HttpClients,
+    port: u16,
+    file: &str,
+) -> Result<Option<String>, (&'static str, String)> {
+    let request = http
+        .management(Method::POST, port, "auth-files/refresh")
+        .json(&serde_json::json!({ "name": file }));
+    match management_json::<serde_json::Value>(request).await {
+        Ok(body) => Ok(["expired", "expires_at"]
+            .iter()
+            .find_map(|key| body[key].as_str())
+            .map(str::to_string)),
+        Err(ManagementError::Status { status, .. })
+            if matches!(status.as_u16(), 404 | 405 | 501) =>
+        {
+            Err((
+                "reauth-needed",
+                "this CLIProxyAPI version can't refresh tokens".to_string(),
+            ))
+        }
+        Err(e) => Err(("failed", e.to_string())),
+    }
+}
+
+// Sign a credential in again; `poll_oauth_status` writes the result over it
+async fn start_reauth(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    port: u16,
+    file: &str,
+    provider: &str,
+) -> Result<CredentialRefresh, CommandError> {
+    if !state.proxy_status.lock().running {
+        return Err(CommandError::new(
+            ErrorCode::ProxyNotRunning,
+            "Start the proxy to sign in again",
+        ));
+    }
+    let path = crate::helpers::permissions::cli_proxy_auth_dir().join(file);
+    let previous = tauri::async_runtime::spawn_blocking(move || std::fs::read_to_string(path))
+        .await
+        .map_err(|e| CommandError::from(e.to_string()))?
+        .map_err(|e| CommandError::io(format!("Failed to read {}: {}", file, e)))?;
+    let started = std::time::SystemTime::now();
+    let (oauth_url, oauth_state) = fetch_oauth_url(&state.http, port, provider).await?;
+    if oauth_state.is_empty() {
+        return Err(CommandError::new(
+            ErrorCode::Upstream,
+            "The proxy returned a sign-in that can't be tracked",
+        ));
+    }
+    {
+        let mut flows = state.oauth_flows.lock();
+        flows.start(provider, &oauth_state, now_ms(), FLOW_TIMEOUT_MS);
+        flows.replace_on_completion(
+            &oauth_state,
+            PendingReauth {
+                provider: provider.to_string(),
+                file: file.to_string(),
+                previous,
+                started,
+            },
+        );
+    }
+    app.opener()
+        .open_url(&oauth_url, None::<&str>)
+        .map_err(|e| CommandError::from(e.to_string()))?;
+    Ok(CredentialRefresh {
+        file: file.to_string(),
+        provider: provider.to_string(),
+        status: "reauth-started".to_string(),
+        expires_at: None,
+        oauth_state: Some(oauth_state),
+        error: None,
+    })
+}
+
+async fn refresh_credentials(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    target: &str,
+) -> Result<Vec<CredentialRefresh>, CommandError> {
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
+    let files =
+        credential_refresh::resolve_targets(&auth_dir, target).map_err(CommandError::not_found)?;
+    let port = state.config.lock().port;
+    let running = state.proxy_status.lock().running;
+
+    let mut results = Vec::new();
+    let mut refreshed = Vec::new();
+    for file in &files {
+        let provider = detect_provider_from_filename(file);
+        let outcome = if !credential_refresh::is_refreshable(provider) {
+            Err((
+                "failed",
+                format!("{} credentials can't be refreshed in place", provider),
+            ))
+        } else if !running {
+            Err(("failed", "the proxy is not running".to_string()))
+        } else {
+            refresh_via_management(&state.http, port, file).await
+        };
+        let (status, expires_at, error) = match outcome {
+            Ok(expires_at) => {
+                if !refreshed.iter().any(|p| p == provider) {
+                    refreshed.push(provider.to_string());
+                }
+                let expires_at = expires_at.or_else(|| {
+                    crate::helpers::credential_meta::read(provider, &auth_dir.join(file)).expires_at
+                });
+                ("refreshed", expires_at, None)
+            }
+            Err((status, reason)) => (status, None, Some(reason)),
+        };
+        results.push(CredentialRefresh {
+            file: file.clone(),
+            provider: provider.to_string(),
+            status: status.to_string(),
+            expires_at,
+            oauth_state: None,
+            error,
+        });
+    }
+    // Refreshed accounts get checked again, which updates `verified`
+    verify_in_background(app, refreshed);
+    Ok(results)
+}
+
+/// Renew credentials without disconnecting them. `target` is an auth file
+/// (or its name without `.json`), or a provider for all of its files. Each
+/// is refreshed through the Management API. Files the proxy has no way to
+/// refresh come back as "reauth-needed"; `reauth_credential` signs them in
+/// again once the user agrees.
+#[tauri::command]
+pub async fn refresh_provider_token(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    target: String,
+) -> Result<Vec<CredentialRefresh>, CommandError> {
+    let changes = vec![format!("credential: {}", target)];
+    let result = refresh_credentials(&app, &state, &target).await;
+    crate::helpers::audit::record("refresh_provider_token", changes, &result);
+    result
+}
+
+/// Sign one credential file in again, for a "reauth-needed" refresh the user
+/// agreed to. The sign-in's result replaces the file, labels and other fields
+/// kept, once `poll_oauth_status` reports it complete.
+#[tauri::command]
+pub async fn reauth_credential(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    file: String,
+) -> Result<CredentialRefresh, CommandError> {
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
+    let files =
+        credential_refresh::resolve_targets(&auth_dir, &file).map_err(CommandError::not_found)?;
+    let [file] = files.as_slice() else {
+        return Err(CommandError::invalid_input(
+            "Pick a single credential file to sign in again",
+        ));
+    };
+    let provider = detect_provider_from_filename(file);
+    if !credential_refresh::is_refreshable(provider) {
+        return Err(CommandError::invalid_input(format!(
+            "{} credentials can't be signed in again in place",
+            provider
+        )));
+    }
+    let port = state.config.lock().port;
+    let changes = vec![format!("credential: {}", file)];
+    let result = start_reauth(&app, &state, port, file, provider).await;
+    crate::helpers::audit::record("reauth_credential", changes, &result);
+    result
+}
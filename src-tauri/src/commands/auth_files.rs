@@ -1,27 +1,33 @@
 //! Auth Files Management - via Management API
 
+use crate::error::{CommandError, ErrorCode};
+use crate::helpers::{credential_meta, credential_refresh};
+use crate::http::{management_json, send_management, ManagementError};
 use crate::state::AppState;
 use crate::types::{self, AuthFile};
 use crate::utils::detect_provider_from_filename;
-use crate::{build_management_client, get_management_key, get_management_url};
+use reqwest::Method;
 use tauri::State;
 
 // Get all auth files
 #[tauri::command]
-pub async fn get_auth_files(state: State<'_, AppState>) -> Result<Vec<AuthFile>, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = get_management_url(port, "auth-files");
+pub async fn get_auth_files(state: State<'_, AppState>) -> Result<Vec<AuthFile>, CommandError> {
+    list_auth_files(&state).await
+}
+
+// get_auth_files for callers without a Tauri State, such as headless mode
+pub(crate) async fn list_auth_files(state: &AppState) -> Result<Vec<AuthFile>, CommandError> {
+    let port = state.config.lock().port;
     
     // 1. Fetch active files from Management API
     let mut files: Vec<AuthFile> = Vec::new();
     
     // Only try to fetch if proxy is running
-    let proxy_running = state.proxy_status.lock().unwrap().running;
+    let proxy_running = state.proxy_status.lock().running;
     if proxy_running {
-        let client = build_management_client();
-        match client
-            .get(&url)
-            .header("X-Management-Key", &get_management_key())
+        match state
+            .http
+            .management(Method::GET, port, "auth-files")
             .send()
             .await 
         {
@@ -63,9 +69,7 @@ pub async fn get_auth_files(state: State<'_, AppState>) -> Result<Vec<AuthFile>,
     
     // 2. Scan auth directory on the filesystem for any files the Management API may have missed
     // (e.g. proxy not running, or disabled files never returned by the API).
-    let auth_dir = dirs::home_dir()
-        .ok_or("Could not find home directory")?
-        .join(".cli-proxy-api");
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
         
     if auth_dir.exists() {
         if let Ok(entries) = std::fs::read_dir(&auth_dir) {
@@ -107,8 +111,11 @@ pub async fn get_auth_files(state: State<'_, AppState>) -> Result<Vec<AuthFile>,
                             created_at: None,
                             updated_at: None,
                             last_refresh: None,
+                            expires_at: None,
+                            plan: None,
                             success_count: None,
                             failure_count: None,
+                            refreshable: false,
                         });
                     }
                 }
@@ -148,10 +155,13 @@ pub async fn get_auth_files(state: State<'_, AppState>) -> Result<Vec<AuthFile>,
                                 created_at: None,
                                 updated_at: None,
                                 last_refresh: None,
+                                expires_at: None,
+                                plan: None,
                                 success_count: None,
                                 failure_count: None,
                                 label: None,
                                 status_message: None,
+                                refreshable: false,
                             };
                             
                             files.push(disabled_file);
@@ -161,19 +171,43 @@ pub async fn get_auth_files(state: State<'_, AppState>) -> Result<Vec<AuthFile>,
             }
         }
     }
-    
+
+    // 3. Fill in account details the Management API leaves out (or all of
+    // them when the proxy is down) from the credential files themselves
+    for file in files.iter_mut() {
+        // The Management API's provider names differ from the registry's
+        file.refreshable = !file.disabled
+            && !file.runtime_only
+            && credential_refresh::is_refreshable(detect_provider_from_filename(&file.name));
+        if file.email.is_some() && file.expires_at.is_some() && file.plan.is_some() {
+            continue;
+        }
+        let path = file
+            .path
+            .clone()
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|| auth_dir.join(&file.name));
+        let provider = match file.provider.as_str() {
+            "unknown" | "" => detect_provider_from_filename(&file.name),
+            provider => provider,
+        };
+        let meta = credential_meta::read(provider, &path);
+        file.email = file.email.take().or(meta.email);
+        file.expires_at = file.expires_at.take().or(meta.expires_at);
+        file.plan = file.plan.take().or(meta.plan);
+    }
+
     Ok(files)
 }
 
 // Upload auth file
 #[tauri::command]
-pub async fn upload_auth_file(state: State<'_, AppState>, file_path: String, provider: String) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = get_management_url(port, "auth-files");
+pub async fn upload_auth_file(state: State<'_, AppState>, file_path: String, provider: String) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
     
     // Read file content
     let content = std::fs::read(&file_path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+        .map_err(|e| CommandError::io(format!("Failed to read file: {}", e)))?;
     
     // Get filename from path
     let filename = std::path::Path::new(&file_path)
@@ -181,9 +215,7 @@ pub async fn upload_auth_file(state: State<'_, AppState>, file_path: String, pro
         .and_then(|n| n.to_str())
         .unwrap_or("auth.json")
         .to_string();
-    
-    let client = build_management_client();
-    
+
     // Create multipart form
     let part = reqwest::multipart::Part::bytes(content)
         .file_name(filename.clone())
@@ -195,56 +227,40 @@ pub async fn upload_auth_file(state: State<'_, AppState>, file_path: String, pro
         .text("filename", filename)
         .part("file", part);
     
-    let response = client
-        .post(&url)
-        .header("X-Management-Key", &get_management_key())
-        .multipart(form)
-        .send()
+    let request = state
+        .http
+        .management(Method::POST, port, "auth-files")
+        .multipart(form);
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to upload auth file: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to upload auth file: {} - {}", status, text));
-    }
-    
+        .map_err(|e| CommandError::management("upload auth file", e))?;
+
     Ok(())
 }
 
 // Delete auth file
 #[tauri::command]
-pub async fn delete_auth_file(state: State<'_, AppState>, file_id: String) -> Result<(), String> {
+pub async fn delete_auth_file(state: State<'_, AppState>, file_id: String) -> Result<(), CommandError> {
     // Check if it's a disabled file first (file_id matches filename without extension usually)
-    let auth_dir = dirs::home_dir()
-        .ok_or("Could not find home directory")?
-        .join(".cli-proxy-api");
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
         
     let disabled_path = auth_dir.join(format!("{}.json.disabled", file_id));
     if disabled_path.exists() {
         std::fs::remove_file(disabled_path)
-            .map_err(|e| format!("Failed to delete disabled file: {}", e))?;
+            .map_err(|e| CommandError::io(format!("Failed to delete disabled file: {}", e)))?;
         return Ok(());
     }
 
     // Otherwise try to delete via API
-    let port = state.config.lock().unwrap().port;
-    let url = format!("{}?name={}", get_management_url(port, "auth-files"), file_id);
-    
-    let client = build_management_client();
-    let response = client
-        .delete(&url)
-        .header("X-Management-Key", &get_management_key())
-        .send()
+    let port = state.config.lock().port;
+    let request = state
+        .http
+        .management(Method::DELETE, port, "auth-files")
+        .query(&[("name", &file_id)]);
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to delete auth file: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to delete auth file: {} - {}", status, text));
-    }
-    
+        .map_err(|e| CommandError::management("delete auth file", e))?;
+
     Ok(())
 }
 
@@ -255,34 +271,28 @@ pub async fn toggle_auth_file(
 	state: State<'_, AppState>,
 	file_name: String,
 	disabled: bool,
-) -> Result<(), String> {
-	let port = {
-		let config = state.config.lock().unwrap();
-		config.port
-	};
-
-	// Use the new PATCH endpoint from CLIProxyAPI v6.7.18
-	// Endpoint: PATCH /v0/management/auth-files/status
-	// Body: { "name": "filename.json", "disabled": true/false }
-	let url = get_management_url(port, "auth-files/status");
+) -> Result<(), CommandError> {
+    let port = {
+        let config = state.config.lock();
+        config.port
+    };
 
-	let client = build_management_client();
-	let response_res = client
-		.patch(&url)
-		.header("X-Management-Key", &get_management_key())
-		.json(&serde_json::json!({
-			"name": file_name,
-			"disabled": disabled
-		}))
-		.send()
-		.await;
+    // Use the new PATCH endpoint from CLIProxyAPI v6.7.18
+    // Endpoint: PATCH /v0/management/auth-files/status
+    // Body: { "name": "filename.json", "disabled": true/false }
+    let request = state
+        .http
+        .management(Method::PATCH, port, "auth-files/status")
+        .json(&serde_json::json!({
+            "name": file_name,
+            "disabled": disabled
+        }));
 
-	match response_res {
-		Ok(response) if response.status().is_success() => Ok(()),
-		Ok(response) if response.status().as_u16() == 404 => {
-			// API not found (old version), fallback to manual file renaming
-			let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
-			let auth_dir = home_dir.join(".cli-proxy-api");
+    match send_management(request).await {
+        Ok(_) => Ok(()),
+        Err(ManagementError::Status { status, .. }) if status.as_u16() == 404 => {
+            // API not found (old version), fallback to manual file renaming
+            let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
 
 			let current_name = if !disabled {
 				format!("{}.disabled", file_name)
@@ -299,46 +309,30 @@ pub async fn toggle_auth_file(
 			let current_path = auth_dir.join(&current_name);
 			let new_path = auth_dir.join(&new_name);
 
-			if current_path.exists() {
-				std::fs::rename(&current_path, &new_path)
-					.map_err(|e| format!("Manual toggle failed: {}", e))?;
-				Ok(())
-			} else {
-				Err(format!("Auth file not found: {:?}", current_path))
-			}
-		}
-		Ok(response) => {
-			let status = response.status();
-			let error_text = response.text().await.unwrap_or_default();
-			Err(format!(
-				"Failed to toggle auth file: {} - {}",
-				status, error_text
-			))
-		}
-		Err(e) => Err(format!("Failed to toggle auth file: {}", e)),
-	}
+            if current_path.exists() {
+                std::fs::rename(&current_path, &new_path)
+                    .map_err(|e| CommandError::io(format!("Manual toggle failed: {}", e)))?;
+                Ok(())
+            } else {
+                Err(CommandError::not_found(format!("Auth file not found: {:?}", current_path)))
+            }
+        }
+        Err(e) => Err(CommandError::management("toggle auth file", e)),
+    }
 }
 
 // Download auth file - returns path to temp file
 #[tauri::command]
-pub async fn download_auth_file(state: State<'_, AppState>, _file_id: String, filename: String) -> Result<String, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = format!("{}?name={}", get_management_url(port, "auth-files/download"), filename);
-    
-    let client = build_management_client();
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &get_management_key())
-        .send()
+pub async fn download_auth_file(state: State<'_, AppState>, _file_id: String, filename: String) -> Result<String, CommandError> {
+    let port = state.config.lock().port;
+    let request = state
+        .http
+        .management(Method::GET, port, "auth-files/download")
+        .query(&[("name", &filename)]);
+    let response = send_management(request)
         .await
-        .map_err(|e| format!("Failed to download auth file: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to download auth file: {} - {}", status, text));
-    }
-    
+        .map_err(|e| CommandError::management("download auth file", e))?;
+
     let bytes = response.bytes().await.map_err(|e| e.to_string())?;
     
     // Save to downloads directory
@@ -347,31 +341,25 @@ pub async fn download_auth_file(state: State<'_, AppState>, _file_id: String, fi
     
     let dest_path = downloads_dir.join(&filename);
     std::fs::write(&dest_path, &bytes)
-        .map_err(|e| format!("Failed to save file: {}", e))?;
+        .map_err(|e| CommandError::io(format!("Failed to save file: {}", e)))?;
+    // An exported credential is as sensitive in Downloads as in the auth dir
+    crate::helpers::permissions::restrict_after_write(&dest_path);
     
     Ok(dest_path.to_string_lossy().to_string())
 }
 
 // Delete all auth files
 #[tauri::command]
-pub async fn delete_all_auth_files(state: State<'_, AppState>) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = format!("{}?all=true", get_management_url(port, "auth-files"));
-    
-    let client = build_management_client();
-    let response = client
-        .delete(&url)
-        .header("X-Management-Key", &get_management_key())
-        .send()
+pub async fn delete_all_auth_files(state: State<'_, AppState>) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
+    let request = state
+        .http
+        .management(Method::DELETE, port, "auth-files")
+        .query(&[("all", "true")]);
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to delete all auth files: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to delete all auth files: {} - {}", status, text));
-    }
-    
+        .map_err(|e| CommandError::management("delete all auth files", e))?;
+
     Ok(())
 }
 
@@ -381,11 +369,11 @@ pub async fn delete_all_auth_files(state: State<'_, AppState>) -> Result<(), Str
 
 // Verify auth status from CLIProxyAPI's /api/auth/status endpoint
 #[tauri::command]
-pub async fn verify_proxy_auth_status(state: State<'_, AppState>) -> Result<types::ProxyAuthStatus, String> {
-    let port = state.config.lock().unwrap().port;
+pub async fn verify_proxy_auth_status(state: State<'_, AppState>) -> Result<types::ProxyAuthStatus, CommandError> {
+    let port = state.config.lock().port;
     
     // Check if proxy is running first
-    let proxy_running = state.proxy_status.lock().unwrap().running;
+    let proxy_running = state.proxy_status.lock().running;
     if !proxy_running {
         return Ok(types::ProxyAuthStatus::default());
     }
@@ -393,23 +381,22 @@ pub async fn verify_proxy_auth_status(state: State<'_, AppState>) -> Result<type
     // The new endpoint in CLIProxyAPI v6.6.72+ is /api/auth/status
     let url = format!("http://127.0.0.1:{}/api/auth/status", port);
     
-    let client = build_management_client();
-    let response = client
+    let request = state
+        .http
+        .local()
         .get(&url)
-        .header("X-Management-Key", &get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to verify auth status: {}", e))?;
-    
-    if !response.status().is_success() {
+        .header("X-Management-Key", crate::http::get_management_key());
+    let json: serde_json::Value = match management_json(request).await {
+        Ok(json) => json,
         // Fallback: endpoint might not exist in older CLIProxyAPI versions
-        return Ok(types::ProxyAuthStatus {
-            status: "unsupported".to_string(),
-            providers: types::ProxyAuthProviders::default(),
-        });
-    }
-    
-    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+        Err(ManagementError::Status { .. }) => {
+            return Ok(types::ProxyAuthStatus {
+                status: "unsupported".to_string(),
+                providers: types::ProxyAuthProviders::default(),
+            })
+        }
+        Err(e) => return Err(CommandError::management("verify auth status", e)),
+    };
     
     // Convert snake_case to camelCase if needed
     let json_str = serde_json::to_string(&json).map_err(|e| e.to_string())?;
@@ -417,5 +404,7 @@ pub async fn verify_proxy_auth_status(state: State<'_, AppState>) -> Result<type
         .replace("\"account_count\"", "\"accounts\"")
         .replace("\"error_message\"", "\"error\"");
     
-    serde_json::from_str(&converted).map_err(|e| format!("Failed to parse auth status: {}", e))
+    serde_json::from_str(&converted).map_err(|e| {
+        CommandError::new(ErrorCode::Upstream, format!("Failed to parse auth status: {}", e))
+    })
 }
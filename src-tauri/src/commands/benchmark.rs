@@ -0,0 +1,323 @@
+//! Provider comparison benchmark.
+//!
+//! `run_provider_benchmark` sends one short prompt to each selected model
+//! through the proxy, all at once, streaming so the first byte can be timed.
+//! Runs are kept in `<config_dir>/benchmarks/`, newest [`MAX_SAVED_RUNS`].
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use futures_util::future::join_all;
+use tauri::State;
+
+use crate::error::{CommandError, ErrorCode};
+use crate::state::AppState;
+use crate::types::{BenchmarkResult, BenchmarkRun, CostTier, ModelCatalog};
+use crate::utils::{detect_provider_from_model, estimate_request_cost};
+
+const DEFAULT_PROMPT: &str = "In one sentence, explain what a reverse proxy does.";
+const DEFAULT_MAX_TOKENS: u32 = 64;
+
+/// Above either limit the caller has to pass `confirmed`
+const CONFIRM_ABOVE_MODELS: usize = 5;
+const CONFIRM_ABOVE_MAX_TOKENS: u32 = 256;
+
+const MAX_TOKENS_LIMIT: u32 = 4096;
+const BENCHMARK_TIMEOUT: Duration = Duration::from_secs(120);
+const RESPONSE_PREVIEW_CHARS: usize = 500;
+const MAX_SAVED_RUNS: usize = 50;
+
+fn benchmarks_dir() -> PathBuf {
+    crate::config::get_data_dir().join("benchmarks")
+}
+
+// Refuse runs that could cost real money unless the user said yes
+fn check_expense(models: usize, max_tokens: u32, confirmed: bool) -> Result<(), String> {
+    if models == 0 {
+        return Err("Select at least one model".to_string());
+    }
+    if max_tokens == 0 || max_tokens > MAX_TOKENS_LIMIT {
+        return Err(format!(
+            "max_tokens must be between 1 and {}",
+            MAX_TOKENS_LIMIT
+        ));
+    }
+    if confirmed {
+        return Ok(());
+    }
+    if models > CONFIRM_ABOVE_MODELS {
+        return Err(format!(
+            "Benchmarking {} models needs confirmation (more than {})",
+            models, CONFIRM_ABOVE_MODELS
+        ));
+    }
+    if max_tokens > CONFIRM_ABOVE_MAX_TOKENS {
+        return Err(format!(
+            "A max_tokens of {} needs confirmation (more than {})",
+            max_tokens, CONFIRM_ABOVE_MAX_TOKENS
+        ));
+    }
+    Ok(())
+}
+
+// Premium-tier models are refused without confirmation even in a small run
+fn check_cost_tiers(
+    models: &[String],
+    catalog: &ModelCatalog,
+    confirmed: bool,
+) -> Result<(), String> {
+    if confirmed {
+        return Ok(());
+    }
+    let premium: Vec<&str> = models
+        .iter()
+        .filter(|m| crate::model_catalog::cost_tier(catalog, m) == Some(CostTier::Premium))
+        .map(String::as_str)
+        .collect();
+    if premium.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Benchmarking premium-tier models ({}) needs confirmation",
+        premium.join(", ")
+    ))
+}
+
+/// Reply text and usage from an OpenAI-style SSE stream
+fn parse_stream(body: &str) -> (String, Option<u32>, Option<u32>) {
+    let mut text = String::new();
+    let (mut tokens_in, mut tokens_out) = (None, None);
+    for line in body.lines() {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            continue;
+        };
+        let Ok(chunk) = serde_json::from_str::<serde_json::Value>(data) else {
+            continue;
+        };
+        if let Some(content) = chunk["choices"][0]["delta"]["content"].as_str() {
+            text.push_str(content);
+        }
+        if let Some(usage) = chunk.get("usage").filter(|u| u.is_object()) {
+            tokens_in = usage["prompt_tokens"].as_u64().map(|t| t as u32);
+            tokens_out = usage["completion_tokens"].as_u64().map(|t| t as u32);
+        }
+    }
+    (text, tokens_in, tokens_out)
+}
+
+fn preview(text: &str) -> String {
+    match text.char_indices().nth(RESPONSE_PREVIEW_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+async fn benchmark_model(
+    client: &reqwest::Client,
+    url: &str,
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+    max_tokens: u32,
+) -> BenchmarkResult {
+    let mut result = BenchmarkResult {
+        model: model.to_string(),
+        provider: detect_provider_from_model(model),
+        ..BenchmarkResult::default()
+    };
+    let body = serde_json::json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": prompt }],
+        "max_tokens": max_tokens,
+        "stream": true,
+        "stream_options": { "include_usage": true },
+    });
+
+    let start = Instant::now();
+    let mut response = match client
+        .post(url)
+        .bearer_auth(api_key)
+        .timeout(BENCHMARK_TIMEOUT)
+        .json(&body)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+    result.status = Some(response.status().as_u16());
+
+    let mut raw = Vec::new();
+    loop {
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                if result.ttfb_ms.is_none() {
+                    result.ttfb_ms = Some(start.elapsed().as_millis() as u64);
+                }
+                raw.extend_from_slice(&chunk);
+            }
+            Ok(None) => break,
+            Err(e) => {
+                result.error = Some(e.to_string());
+                break;
+            }
+        }
+    }
+    result.total_ms = Some(start.elapsed().as_millis() as u64);
+
+    let raw = String::from_utf8_lossy(&raw);
+    if !result.status.is_some_and(|s| (200..300).contains(&s)) {
+        result.error = Some(preview(raw.trim()));
+        return result;
+    }
+    let (text, tokens_in, tokens_out) = parse_stream(&raw);
+    result.tokens_in = tokens_in;
+    result.tokens_out = tokens_out;
+    if tokens_in.is_some() || tokens_out.is_some() {
+        result.estimated_cost = Some(estimate_request_cost(
+            model,
+            tokens_in.unwrap_or(0),
+            tokens_out.unwrap_or(0),
+        ));
+    }
+    result.response = Some(preview(&text));
+    result
+}
+
+fn save_run(run: &BenchmarkRun) -> Result<(), String> {
+    let dir = benchmarks_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create benchmarks directory: {}", e))?;
+    let data = serde_json::to_string_pretty(run)
+        .map_err(|e| format!("Failed to serialize benchmark: {}", e))?;
+    std::fs::write(dir.join(format!("{}-{}.json", run.timestamp, run.id)), data)
+        .map_err(|e| format!("Failed to save benchmark: {}", e))?;
+
+    // File names start with the timestamp, so they sort oldest first
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    files.retain(|p| p.extension().is_some_and(|ext| ext == "json"));
+    if files.len() > MAX_SAVED_RUNS {
+        files.sort();
+        for path in &files[..files.len() - MAX_SAVED_RUNS] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    Ok(())
+}
+
+// Send the same prompt to every model in `models` and compare the replies.
+// More than 5 models or a max_tokens above 256 needs `confirmed`.
+#[tauri::command]
+pub async fn run_provider_benchmark(
+    state: State<'_, AppState>,
+    prompt: Option<String>,
+    mut models: Vec<String>,
+    max_tokens: Option<u32>,
+    confirmed: Option<bool>,
+) -> Result<BenchmarkRun, CommandError> {
+    let prompt = prompt
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| DEFAULT_PROMPT.to_string());
+    let max_tokens = max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+    let mut seen = std::collections::HashSet::new();
+    models.retain(|m| seen.insert(m.clone()));
+    check_expense(models.len(), max_tokens, confirmed.unwrap_or(false))
+        .map_err(CommandError::invalid_input)?;
+    check_cost_tiers(
+        &models,
+        &crate::model_catalog::load(),
+        confirmed.unwrap_or(false),
+    )
+    .map_err(CommandError::invalid_input)?;
+
+    let (port, api_key) = {
+        let config = state.config.lock();
+        (config.port, config.proxy_api_key.clone())
+    };
+    if !state.proxy_status.lock().running {
+        return Err(CommandError::new(
+            ErrorCode::ProxyNotRunning,
+            "Start the proxy to run a benchmark",
+        ));
+    }
+
+    let client = state.http.local();
+    let url = format!("http://127.0.0.1:{}/v1/chat/completions", port);
+    let results = join_all(
+        models
+            .iter()
+            .map(|model| benchmark_model(client, &url, &api_key, model, &prompt, max_tokens)),
+    )
+    .await;
+
+    let run = BenchmarkRun {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        prompt,
+        max_tokens,
+        results,
+    };
+    save_run(&run).map_err(CommandError::io)?;
+    Ok(run)
+}
+
+// Saved runs, newest first
+#[tauri::command]
+pub fn get_benchmark_history(limit: Option<usize>) -> Vec<BenchmarkRun> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(benchmarks_dir())
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    files.retain(|p| p.extension().is_some_and(|ext| ext == "json"));
+    files.sort_by(|a, b| b.cmp(a));
+    files
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .filter_map(|data| serde_json::from_str(&data).ok())
+        .take(limit.unwrap_or(MAX_SAVED_RUNS))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_runs_need_confirmation() {
+        assert!(check_expense(3, 64, false).is_ok());
+        assert!(check_expense(6, 64, false).is_err());
+        assert!(check_expense(6, 64, true).is_ok());
+        assert!(check_expense(2, 1024, false).is_err());
+        assert!(check_expense(2, 1024, true).is_ok());
+        // Never allowed
+        assert!(check_expense(0, 64, true).is_err());
+        assert!(check_expense(2, 100_000, true).is_err());
+    }
+
+    #[test]
+    fn premium_models_need_confirmation() {
+        let catalog = crate::model_catalog::builtin();
+        let models = ["gpt-5-mini".to_string(), "claude-opus-4-1".to_string()];
+        let err = check_cost_tiers(&models, catalog, false).unwrap_err();
+        assert!(err.contains("claude-opus-4-1") && !err.contains("gpt-5-mini"));
+        assert!(check_cost_tiers(&models, catalog, true).is_ok());
+        assert!(check_cost_tiers(&models[..1], catalog, false).is_ok());
+    }
+
+    #[test]
+    fn reads_text_and_usage_from_the_stream() {
+        let body = "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n\
+                    data: {\"choices\":[{\"delta\":{\"content\":\"It forwards \"}}]}\n\n\
+                    data: {\"choices\":[{\"delta\":{\"content\":\"requests.\"}}],\"usage\":null}\n\n\
+                    data: {\"choices\":[],\"usage\":{\"prompt_tokens\":14,\"completion_tokens\":9}}\n\n\
+                    data: [DONE]\n";
+        let (text, tokens_in, tokens_out) = parse_stream(body);
+        assert_eq!(text, "It forwards requests.");
+        assert_eq!((tokens_in, tokens_out), (Some(14), Some(9)));
+    }
+}
@@ -0,0 +1,119 @@
+//! Captured request listing and replay.
+
+use std::time::{Duration, Instant};
+
+use reqwest::Method;
+use tauri::State;
+
+use crate::error::{CommandError, ErrorCode};
+use crate::helpers::captures;
+use crate::state::AppState;
+use crate::types::{Capture, CaptureSummary, ReplayOverrides, ReplayResult};
+
+// Long enough for a slow non-streaming completion
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[tauri::command]
+pub fn list_captures(limit: Option<usize>) -> Vec<CaptureSummary> {
+    captures::list(limit.unwrap_or(100))
+}
+
+#[tauri::command]
+pub fn get_capture(id: String) -> Result<Capture, CommandError> {
+    captures::load(&id).map_err(CommandError::not_found)
+}
+
+// Re-send a captured request through the local proxy with the configured key,
+// optionally with another model or token limit. The exchange is saved as a
+// new capture linked to the original.
+#[tauri::command]
+pub async fn replay_request(
+    state: State<'_, AppState>,
+    capture_id: String,
+    overrides: Option<ReplayOverrides>,
+) -> Result<ReplayResult, CommandError> {
+    // Capture files can be large; read and write them off the async workers
+    let id = capture_id.clone();
+    let original = tauri::async_runtime::spawn_blocking(move || captures::load(&id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(CommandError::not_found)?;
+    let overrides = overrides.unwrap_or_default();
+    let (path, body) = captures::apply_overrides(&original.path, &original.body, &overrides)
+        .map_err(CommandError::invalid_input)?;
+    let method = Method::from_bytes(original.method.as_bytes()).map_err(|_| {
+        CommandError::invalid_input(format!("Unsupported method: {}", original.method))
+    })?;
+
+    let (port, api_key, running) = {
+        let config = state.config.lock();
+        let running = state.proxy_status.lock().running;
+        (config.port, config.proxy_api_key.clone(), running)
+    };
+    if !running {
+        return Err(CommandError::new(
+            ErrorCode::ProxyNotRunning,
+            "Start the proxy to replay requests",
+        ));
+    }
+
+    let headers = captures::forwarded_headers(&original);
+    let mut request = state
+        .http
+        .local()
+        .request(method, format!("http://127.0.0.1:{}{}", port, path))
+        .bearer_auth(&api_key)
+        .timeout(REPLAY_TIMEOUT)
+        .body(body.clone());
+    for (name, value) in &headers {
+        request = request.header(name, value);
+    }
+
+    let started = Instant::now();
+    let response = request
+        .send()
+        .await
+        .map_err(|e| CommandError::new(ErrorCode::Upstream, format!("Replay failed: {}", e)))?;
+    let status = response.status().as_u16();
+    // Streamed responses are read to the end before they are stored
+    let response_body = response.text().await.map_err(|e| {
+        CommandError::new(
+            ErrorCode::Upstream,
+            format!("Replay failed while reading the response: {}", e),
+        )
+    })?;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let diff = captures::diff_summary(
+        original.response_status,
+        &original.response_body,
+        status,
+        &response_body,
+    );
+    let replay = Capture {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        method: original.method.clone(),
+        path,
+        headers,
+        body,
+        response_status: Some(status),
+        response_body,
+        duration_ms: Some(latency_ms),
+        replay_of: Some(capture_id.clone()),
+        overrides: (overrides != ReplayOverrides::default()).then_some(overrides),
+    };
+    let replay_id = replay.id.clone();
+    tauri::async_runtime::spawn_blocking(move || captures::save(&replay))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(CommandError::io)?;
+
+    Ok(ReplayResult {
+        capture_id: replay_id,
+        replay_of: capture_id,
+        status,
+        latency_ms,
+        diff,
+    })
+}
@@ -1,16 +1,17 @@
 use tauri::{AppHandle, State, command};
 use crate::cloudflare_manager::CloudflareManager;
 use crate::config::{save_config_to_file, load_config};
+use crate::error::CommandError;
 use crate::types::cloudflare::CloudflareConfig;
 
 #[command]
-pub async fn get_cloudflare_configs() -> Result<Vec<CloudflareConfig>, String> {
+pub async fn get_cloudflare_configs() -> Result<Vec<CloudflareConfig>, CommandError> {
     let config = load_config();
     Ok(config.cloudflare_configs)
 }
 
 #[command]
-pub async fn save_cloudflare_config(cf_config: CloudflareConfig) -> Result<Vec<CloudflareConfig>, String> {
+pub async fn save_cloudflare_config(cf_config: CloudflareConfig) -> Result<Vec<CloudflareConfig>, CommandError> {
     let mut current_config = load_config();
     
     if let Some(idx) = current_config.cloudflare_configs.iter().position(|c| c.id == cf_config.id) {
@@ -19,19 +20,19 @@ pub async fn save_cloudflare_config(cf_config: CloudflareConfig) -> Result<Vec<C
         current_config.cloudflare_configs.push(cf_config);
     }
     
-    save_config_to_file(&current_config)?;
+    save_config_to_file(&current_config).map_err(CommandError::io)?;
     Ok(current_config.cloudflare_configs)
 }
 
 #[command]
-pub async fn delete_cloudflare_config(_app: AppHandle, state: State<'_, CloudflareManager>, id: String) -> Result<Vec<CloudflareConfig>, String> {
+pub async fn delete_cloudflare_config(_app: AppHandle, state: State<'_, CloudflareManager>, id: String) -> Result<Vec<CloudflareConfig>, CommandError> {
     let mut current_config = load_config();
     
     // Stop if running
     state.disconnect(&id);
     
     current_config.cloudflare_configs.retain(|c| c.id != id);
-    save_config_to_file(&current_config)?;
+    save_config_to_file(&current_config).map_err(CommandError::io)?;
     Ok(current_config.cloudflare_configs)
 }
 
@@ -41,14 +42,14 @@ pub async fn set_cloudflare_connection(
     state: State<'_, CloudflareManager>,
     id: String,
     enable: bool
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let mut config = load_config();
     if let Some(c) = config.cloudflare_configs.iter_mut().find(|c| c.id == id) {
         c.enabled = enable;
         let target_config = c.clone();
         
         // Save persistent state
-        save_config_to_file(&config)?;
+        save_config_to_file(&config).map_err(CommandError::io)?;
         
         if enable {
             state.connect(app, target_config);
@@ -57,6 +58,6 @@ pub async fn set_cloudflare_connection(
         }
         Ok(())
     } else {
-        Err("Config not found".to_string())
+        Err(CommandError::not_found("Config not found"))
     }
 }
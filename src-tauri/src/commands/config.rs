@@ -3,14 +3,20 @@
 #[cfg(test)]
 use crate::config::save_config_to_path;
 use crate::config::{save_config_to_file, AppConfig};
+use crate::error::CommandError;
+use crate::helpers::autostart::set_launch_at_login;
 use crate::state::AppState;
+use crate::types::{CliproxyConfigImport, StartupState, StorageInfo};
 use std::fs;
 use std::path::{Path, PathBuf};
-use tauri::State;
+use tauri::{AppHandle, State};
 
+// Fails with a `loading` error until startup has read the saved config, so
+// defaults are never shown (and saved back) as the user's settings
 #[tauri::command]
-pub fn get_config(state: State<AppState>) -> AppConfig {
-    let config = state.config.lock().unwrap().clone();
+pub fn get_config(state: State<AppState>) -> Result<AppConfig, CommandError> {
+    state.ensure_hydrated()?;
+    let config = state.config.lock().clone();
     eprintln!(
         "[ProxyPal Debug] Loading {} custom providers",
         config.amp_openai_providers.len()
@@ -26,11 +32,222 @@ pub fn get_config(state: State<AppState>) -> AppConfig {
             eprintln!("[ProxyPal Debug]   Model {}: {}", j, model.name);
         }
     }
-    config
+    Ok(config)
 }
 
+/// The config and auth loaded at startup, or None while they are still being
+/// read; the same snapshot is sent with `state-hydrated`
 #[tauri::command]
-pub fn save_config(state: State<AppState>, config: AppConfig) -> Result<(), String> {
+pub fn get_startup_state(state: State<AppState>) -> Option<StartupState> {
+    state.ensure_hydrated().ok()?;
+    Some(StartupState {
+        config: state.config.lock().clone(),
+        auth_status: state.auth_status.lock().clone(),
+    })
+}
+
+/// Paths, free space and writability of ProxyPal's storage
+#[tauri::command]
+pub fn get_storage_info() -> StorageInfo {
+    crate::helpers::storage::storage_info()
+}
+
+// Copy the data to `to`, switch to `config`, then delete the originals; on
+// failure the copies go and `previous` stays in effect
+fn move_data(
+    state: &AppState,
+    config: AppConfig,
+    previous: &AppConfig,
+    from: &Path,
+    to: &Path,
+) -> Result<StorageInfo, CommandError> {
+    state
+        .history
+        .flush()
+        .map_err(|e| CommandError::io(format!("Failed to save history: {}", e)))?;
+    let copied = crate::helpers::storage::copy_data(from, to).map_err(CommandError::io)?;
+    if let Err(e) = store_config(state, config) {
+        crate::helpers::storage::remove_data(to, &copied);
+        crate::helpers::paths::set_data_dir(previous);
+        return Err(CommandError::io(e));
+    }
+    crate::helpers::storage::remove_data(from, &copied);
+    Ok(crate::helpers::storage::storage_info())
+}
+
+/// Move history, logs, captures and benchmarks to `new_path` (back to the
+/// config dir when empty): copy and verify them, switch `data_dir`, then
+/// delete the old copies. The proxy has to be stopped so nothing writes to
+/// them meanwhile.
+#[tauri::command]
+pub fn migrate_data_dir(
+    state: State<AppState>,
+    new_path: Option<String>,
+) -> Result<StorageInfo, CommandError> {
+    state.ensure_hydrated()?;
+    if state.proxy_status.lock().running {
+        return Err(CommandError::invalid_input(
+            "Stop the proxy before moving the data folder",
+        ));
+    }
+    let mut config = state.config.lock().clone();
+    let previous = config.clone();
+    config.data_dir = new_path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    let from = crate::config::get_data_dir();
+    let to = crate::helpers::paths::data_dir_for(&config);
+    if to == from {
+        return Err(CommandError::invalid_input(format!(
+            "The data folder is already {}",
+            to.display()
+        )));
+    }
+    let inside_data = crate::helpers::paths::DATA_ENTRIES
+        .iter()
+        .any(|entry| to.starts_with(from.join(entry)));
+    if inside_data {
+        return Err(CommandError::invalid_input(
+            "The new data folder can't be inside the current one's data",
+        ));
+    }
+
+    let result = move_data(&state, config, &previous, &from, &to);
+    crate::helpers::audit::record(
+        "migrate_data_dir",
+        vec![format!("dataDir: {} -> {}", from.display(), to.display())],
+        &result,
+    );
+    result
+}
+
+#[tauri::command]
+pub fn save_config(
+    app: AppHandle,
+    state: State<AppState>,
+    config: AppConfig,
+) -> Result<(), CommandError> {
+    // Saving over defaults before startup loaded the real config would lose it
+    state.ensure_hydrated()?;
+    let old = state.config.lock().clone();
+    // Window geometry is saved as windows move, update checks are recorded in
+    // the background and Amp pools, request watches and provider rate limits
+    // have their own commands; the page's copy of them may be stale. The page
+    // has no config version at all.
+    let config = AppConfig {
+        config_version: old.config_version,
+        amp_pools: old.amp_pools.clone(),
+        request_watches: old.request_watches.clone(),
+        provider_rate_limits: old.provider_rate_limits.clone(),
+        main_window: old.main_window,
+        mini_monitor_window: old.mini_monitor_window,
+        last_app_update_check: old.last_app_update_check,
+        dismissed_app_version: old.dismissed_app_version.clone(),
+        ..config
+    };
+    let changes = crate::helpers::audit::config_changes(&old, &config);
+    let result = apply_config(&app, &state, config, old.launch_at_login);
+    crate::helpers::audit::record("save_config", changes, &result);
+    result
+}
+
+// Copy the settings of a standalone CLIProxyAPI config.yaml into the config.
+// Keys without a ProxyPal setting are listed in the result; they take effect
+// in proxy-config-custom.yaml. Restart the proxy to use the imported values.
+#[tauri::command]
+pub fn import_cliproxy_config(
+    app: AppHandle,
+    state: State<AppState>,
+    path: String,
+) -> Result<CliproxyConfigImport, CommandError> {
+    state.ensure_hydrated()?;
+    let yaml = fs::read_to_string(&path)
+        .map_err(|e| CommandError::io(format!("Failed to read {}: {}", path, e)))?;
+    let old = state.config.lock().clone();
+    let mut config = old.clone();
+    let report = crate::helpers::cliproxy_config::import_config(&yaml, &path, &mut config)
+        .map_err(CommandError::invalid_input)?;
+    let changes = crate::helpers::audit::config_changes(&old, &config);
+    let result = apply_config(&app, &state, config, old.launch_at_login);
+    crate::helpers::audit::record("import_cliproxy_config", changes, &result);
+    result?;
+    Ok(report)
+}
+
+/// Starts, stops or moves one listener to match the current config
+type ApplyListener = fn(&AppHandle) -> Result<(), String>;
+
+fn apply_config(
+    app: &AppHandle,
+    state: &AppState,
+    config: AppConfig,
+    was_enabled: bool,
+) -> Result<(), CommandError> {
+    crate::failover::validate_rules(&config.failover_rules).map_err(CommandError::invalid_input)?;
+    crate::model_caps::validate_caps(&config.model_caps).map_err(CommandError::invalid_input)?;
+    crate::management_gateway::validate_management_port(&config)
+        .map_err(CommandError::invalid_input)?;
+    crate::http::validate_network_proxy(&config.network_proxy)
+        .map_err(CommandError::invalid_input)?;
+    if let Some(schedule) = &config.schedule {
+        crate::scheduler::validate_schedule(schedule).map_err(CommandError::invalid_input)?;
+    }
+    // Only migrate_data_dir moves the data folder, since the files go with it
+    let mut config = config;
+    config.data_dir = state.config.lock().data_dir.clone();
+    let schedule_changed = config.schedule != state.config.lock().schedule;
+    let caps_changed = config.model_caps != state.config.lock().model_caps;
+    let icon_style_changed = config.tray_icon_style != state.config.lock().tray_icon_style;
+    let port_changed = config.port != state.config.lock().port;
+
+    // Register/unregister with the OS first so a refusal leaves the old value in place
+    if config.launch_at_login != was_enabled {
+        set_launch_at_login(app, config.launch_at_login)?;
+    }
+
+    store_config(state, config).map_err(CommandError::io)?;
+    if schedule_changed {
+        crate::scheduler::reschedule(state);
+    }
+    if caps_changed {
+        crate::model_caps::refresh(app);
+    }
+    // Agents configured for the old port now miss the proxy
+    if port_changed {
+        crate::commands::agent_endpoints::warn_on_conflicts(app);
+    }
+
+    // Pick up tray stats settings right away rather than on the next refresh tick
+    crate::tray::refresh_tray_tooltip(app);
+    if icon_style_changed {
+        crate::tray::refresh_tray_icon(app);
+    }
+    crate::status_file::refresh_status_file(app);
+    crate::proxy::pause::apply_sleep_watcher_config(app);
+
+    // Start/stop/move the metrics, control API, control socket, management and
+    // event stream listeners; the config is saved even if binding fails. Each
+    // is applied on its own, so one bad port doesn't leave the others stale.
+    let listeners: [ApplyListener; 5] = [
+        crate::metrics::apply_metrics_config,
+        crate::control_api::apply_control_api_config,
+        crate::control_socket::apply_control_socket_config,
+        crate::management_gateway::apply_management_gateway_config,
+        crate::event_stream::apply_event_stream_config,
+    ];
+    let errors: Vec<String> = listeners
+        .iter()
+        .filter_map(|apply| apply(app).err())
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Settings saved, but:\n{}", errors.join("\n")).into())
+    }
+}
+
+/// Persist `config` and make it the in-memory config (no OS side effects).
+pub(crate) fn store_config(state: &AppState, config: AppConfig) -> Result<(), String> {
     // Debug: Log provider models before save
     eprintln!(
         "[ProxyPal Debug] Saving {} custom providers",
@@ -50,7 +267,8 @@ pub fn save_config(state: State<AppState>, config: AppConfig) -> Result<(), Stri
 
     persist_config(&config)?;
 
-    let mut current_config = state.config.lock().unwrap();
+    state.http.set_network_proxy(&config.network_proxy);
+    let mut current_config = state.config.lock();
     *current_config = config.clone();
 
     eprintln!("[ProxyPal Debug] Config saved successfully");
@@ -58,10 +276,7 @@ pub fn save_config(state: State<AppState>, config: AppConfig) -> Result<(), Stri
 }
 
 fn proxy_config_path() -> PathBuf {
-    dirs::config_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("proxypal")
-        .join("proxy-config.yaml")
+    crate::config::get_proxypal_config_dir().join("proxy-config.yaml")
 }
 
 fn persist_config(config: &AppConfig) -> Result<(), String> {
@@ -104,7 +319,9 @@ fn update_proxy_config_yaml_at_path(
     existing_yaml = set_routing_strategy(&existing_yaml, &app_config.routing_strategy);
 
     std::fs::write(proxy_config_path, existing_yaml)
-        .map_err(|e| format!("Failed to write proxy config: {}", e))
+        .map_err(|e| format!("Failed to write proxy config: {}", e))?;
+    crate::helpers::permissions::restrict_after_write(proxy_config_path);
+    Ok(())
 }
 
 fn set_routing_strategy(existing_yaml: &str, strategy: &str) -> String {
@@ -146,16 +363,14 @@ fn set_routing_strategy(existing_yaml: &str, strategy: &str) -> String {
 }
 
 #[tauri::command]
-pub fn get_config_yaml() -> Result<String, String> {
-    let config_dir = dirs::config_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("proxypal");
+pub fn get_config_yaml() -> Result<String, CommandError> {
+    let config_dir = crate::config::get_proxypal_config_dir();
 
     // Read the main generated config
     let config_path = config_dir.join("proxy-config.yaml");
     if config_path.exists() {
         return fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read config YAML: {}", e));
+            .map_err(|e| CommandError::io(format!("Failed to read config YAML: {}", e)));
     }
 
     // Config doesn't exist yet
@@ -163,25 +378,53 @@ pub fn get_config_yaml() -> Result<String, String> {
 }
 
 #[tauri::command]
-pub fn save_config_yaml(yaml: String) -> Result<(), String> {
-    let config_dir = dirs::config_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("proxypal");
-    fs::create_dir_all(&config_dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+pub fn save_config_yaml(yaml: String) -> Result<(), CommandError> {
+    let config_dir = crate::config::get_proxypal_config_dir();
+    fs::create_dir_all(&config_dir)
+        .map_err(|e| CommandError::io(format!("Failed to create config dir: {}", e)))?;
+
+    if crate::commands::proxy::yaml_exposes_default_key(&yaml) {
+        return Err(CommandError::invalid_input(
+            "Refusing to save: allow-remote is true while secret-key is still the default \
+             management key. Rotate the key first.",
+        ));
+    }
 
     // Save directly to main config file
     // Note: This will be overwritten on next proxy restart
     let config_path = config_dir.join("proxy-config.yaml");
-    fs::write(&config_path, yaml).map_err(|e| format!("Failed to save config YAML: {}", e))
+    fs::write(&config_path, yaml)
+        .map_err(|e| CommandError::io(format!("Failed to save config YAML: {}", e)))?;
+    crate::helpers::permissions::restrict_after_write(&config_path);
+    Ok(())
+}
+
+/// Warnings about the config that don't stop it from saving. Checks `config`
+/// if given, the current config otherwise.
+#[tauri::command]
+pub fn validate_config(state: State<AppState>, config: Option<AppConfig>) -> Vec<String> {
+    let config = config.unwrap_or_else(|| state.config.lock().clone());
+    let custom_yaml =
+        crate::commands::proxy::read_custom_proxy_yaml(&crate::config::get_proxypal_config_dir());
+    let mut warnings = crate::commands::proxy::management_warnings(&config, custom_yaml.as_deref());
+    warnings.extend(crate::model_caps::fallback_tier_warnings(
+        &config.model_caps,
+        &crate::model_catalog::load(),
+    ));
+    if let Err(e) = crate::helpers::amp_setup::validate_routing(&config) {
+        warnings.push(e);
+    }
+    warnings
 }
 
 #[tauri::command]
-pub fn reload_config(state: State<AppState>) -> Result<AppConfig, String> {
+pub fn reload_config(state: State<AppState>) -> Result<AppConfig, CommandError> {
     // Reload config from disk
     let fresh_config = crate::config::load_config();
 
     // Update the in-memory state
-    let mut current_config = state.config.lock().unwrap();
+    state.http.set_network_proxy(&fresh_config.network_proxy);
+    let mut current_config = state.config.lock();
     *current_config = fresh_config.clone();
 
     eprintln!("[ProxyPal Debug] Config reloaded from disk");
@@ -1,3 +1,4 @@
+use crate::error::CommandError;
 use crate::state::AppState;
 use crate::types::{CopilotApiDetection, CopilotApiInstallResult, CopilotStatus};
 use tauri::{Emitter, Manager, State};
@@ -9,24 +10,24 @@ use tauri_plugin_shell::ShellExt;
 
 #[tauri::command]
 pub fn get_copilot_status(state: State<AppState>) -> CopilotStatus {
-    state.copilot_status.lock().unwrap().clone()
+    state.copilot_status.lock().clone()
 }
 
 #[tauri::command]
 pub async fn start_copilot(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<CopilotStatus, String> {
-    let config = state.config.lock().unwrap().clone();
+) -> Result<CopilotStatus, CommandError> {
+    let config = state.config.lock().clone();
     let port = config.copilot.port;
     
     // Check if copilot is enabled
     if !config.copilot.enabled {
-        return Err("Copilot is not enabled in settings".to_string());
+        return Err(CommandError::invalid_input("Copilot is not enabled in settings"));
     }
     
     // First, check if copilot-api is already running on this port (maybe externally)
-    let client = crate::build_management_client();
+    let client = state.http.local();
     let health_url = format!("http://127.0.0.1:{}/v1/models", port);
     if let Ok(response) = client
         .get(&health_url)
@@ -37,7 +38,7 @@ pub async fn start_copilot(
         if response.status().is_success() {
             // Already running and healthy - just update status
             let new_status = {
-                let mut status = state.copilot_status.lock().unwrap();
+                let mut status = state.copilot_status.lock();
                 status.running = true;
                 status.port = port;
                 status.endpoint = format!("http://localhost:{}", port);
@@ -51,7 +52,7 @@ pub async fn start_copilot(
     
     // Kill any existing copilot process we're tracking
     {
-        let mut process = state.copilot_process.lock().unwrap();
+        let mut process = state.copilot_process.lock();
         if let Some(child) = process.take() {
             let _ = child.kill(); // Ignore errors, process might already be dead
         }
@@ -65,12 +66,12 @@ pub async fn start_copilot(
     
     if !detection.node_available {
         let checked = detection.checked_node_paths.join(", ");
-        return Err(format!(
+        return Err(CommandError::not_found(format!(
             "Node.js is required for GitHub Copilot support.\n\n\
             Checked paths: {}\n\n\
             Please install Node.js from https://nodejs.org/ or via a version manager (nvm, volta, fnm) and restart ProxyPal.",
             if checked.is_empty() { "none".to_string() } else { checked }
-        ));
+        )));
     }
     
     // Check Node.js version >= 20.16.0 (required for process.getBuiltinModule)
@@ -84,7 +85,7 @@ pub async fn start_copilot(
             
             // Require Node.js >= 20.16.0
             if major < 20 || (major == 20 && minor < 16) {
-                return Err(format!(
+                return Err(CommandError::invalid_input(format!(
                     "Node.js version {} is too old for GitHub Copilot support.\n\n\
                     The copilot-api package requires Node.js 20.16.0 or later.\n\
                     Your current version: {}\n\n\
@@ -93,7 +94,7 @@ pub async fn start_copilot(
                     • Or use a version manager: nvm install 22 / volta install node@22\n\n\
                     After upgrading, restart ProxyPal.",
                     version_str, version_str
-                ));
+                )));
             }
         }
     }
@@ -102,11 +103,11 @@ pub async fn start_copilot(
     let (bin_path, mut args) = if detection.installed {
         // Use copilot-api directly
         let copilot_bin = detection.copilot_bin.clone()
-            .ok_or_else(|| format!(
+            .ok_or_else(|| CommandError::not_found(format!(
                 "copilot-api binary path not found.\n\n\
                 Checked paths: {}",
                 detection.checked_copilot_paths.join(", ")
-            ))?;
+            )))?;
         println!("[copilot] Using globally installed copilot-api: {}{}", 
             copilot_bin,
             detection.version.as_ref().map(|v| format!(" v{}", v)).unwrap_or_default());
@@ -120,7 +121,7 @@ pub async fn start_copilot(
         println!("[copilot] Using npx: {} copilot-api@latest", npx_bin);
         (npx_bin, vec!["copilot-api@latest".to_string()])
     } else {
-        return Err(
+        return Err(CommandError::not_found(
             "Could not start GitHub Copilot bridge.\n\n\
             The copilot-api package now requires Bun (recommended) or Node.js.\n\n\
             Option 1 - Install Bun (recommended):\n\
@@ -129,8 +130,8 @@ pub async fn start_copilot(
             Option 2 - Run manually in terminal:\n\
             • bunx copilot-api start --port 4141\n\
             • Or: npx copilot-api@latest start --port 4141\n\n\
-            For more info: https://github.com/ericc-ch/copilot-api".to_string()
-        );
+            For more info: https://github.com/ericc-ch/copilot-api"
+        ));
     };
     
     // Add common arguments
@@ -165,17 +166,17 @@ pub async fn start_copilot(
     
     let command = app.shell().command(&bin_path).args(&args);
     
-    let (mut rx, child) = command.spawn().map_err(|e| format!("Failed to spawn copilot-api: {}. Make sure Node.js is installed.", e))?;
+    let (mut rx, child) = command.spawn().map_err(|e| CommandError::io(format!("Failed to spawn copilot-api: {}. Make sure Node.js is installed.", e)))?;
     
     // Store the child process
     {
-        let mut process = state.copilot_process.lock().unwrap();
+        let mut process = state.copilot_process.lock();
         *process = Some(child);
     }
     
     // Update status to running (but not yet authenticated)
     {
-        let mut status = state.copilot_status.lock().unwrap();
+        let mut status = state.copilot_status.lock();
         status.running = true;
         status.port = port;
         status.endpoint = format!("http://localhost:{}", port);
@@ -193,7 +194,7 @@ pub async fn start_copilot(
             match event {
                 CommandEvent::Stdout(line) => {
                     let text = String::from_utf8_lossy(&line);
-                    println!("[copilot-api] {}", text);
+                    println!("[copilot-api] {}", crate::redact::redact(&text));
                     
                     // Check for successful login message
                     // copilot-api outputs "Listening on: http://localhost:PORT/" when ready
@@ -201,7 +202,7 @@ pub async fn start_copilot(
                     if text_lower.contains("listening on") || text.contains("Logged in as") || text.contains("Server running") {
                         // Update authenticated status
                         if let Some(state) = app_handle.try_state::<AppState>() {
-                            let mut status = state.copilot_status.lock().unwrap();
+                            let mut status = state.copilot_status.lock();
                             status.authenticated = true;
                             let _ = app_handle.emit("copilot-status-changed", status.clone());
                             println!("[copilot] ✓ Authenticated via stdout detection");
@@ -217,14 +218,14 @@ pub async fn start_copilot(
                 }
                 CommandEvent::Stderr(line) => {
                     let text = String::from_utf8_lossy(&line);
-                    eprintln!("[copilot-api ERROR] {}", text);
+                    eprintln!("[copilot-api ERROR] {}", crate::redact::redact(&text));
                     
                     // Some processes log to stderr even for non-errors
                     // Check if it's actually a login/running message
                     let text_lower = text.to_lowercase();
                     if text_lower.contains("listening on") || text.contains("Logged in as") || text.contains("Server running") {
                         if let Some(state) = app_handle.try_state::<AppState>() {
-                            let mut status = state.copilot_status.lock().unwrap();
+                            let mut status = state.copilot_status.lock();
                             status.authenticated = true;
                             let _ = app_handle.emit("copilot-status-changed", status.clone());
                             println!("[copilot] ✓ Authenticated via stderr detection");
@@ -235,7 +236,7 @@ pub async fn start_copilot(
                     println!("[copilot-api] Process terminated: {:?}", payload);
                     // Update status when process dies
                     if let Some(state) = app_handle.try_state::<AppState>() {
-                        let mut status = state.copilot_status.lock().unwrap();
+                        let mut status = state.copilot_status.lock();
                         status.running = false;
                         status.authenticated = false;
                         let _ = app_handle.emit("copilot-status-changed", status.clone());
@@ -249,7 +250,7 @@ pub async fn start_copilot(
     
     // Wait for copilot-api to be ready (up to 8 seconds)
     // bunx/npx may need to download packages on first run, which takes ~5s
-    let client = crate::build_management_client();
+    let client = state.http.local();
     let health_url = format!("http://127.0.0.1:{}/v1/models", port);
     
     for i in 0..16 {
@@ -257,7 +258,7 @@ pub async fn start_copilot(
         
         // Check if stdout listener already detected authentication
         {
-            let status = state.copilot_status.lock().unwrap();
+            let status = state.copilot_status.lock();
             if status.authenticated {
                 println!("[copilot] ✓ Ready via stdout detection at {:.1}s", (i + 1) as f32 * 0.5);
                 let status_clone = status.clone();
@@ -265,7 +266,7 @@ pub async fn start_copilot(
                 return Ok(status_clone);
             }
             if !status.running {
-                return Err("Copilot process stopped unexpectedly".to_string());
+                return Err("Copilot process stopped unexpectedly".into());
             }
         }
         
@@ -279,7 +280,7 @@ pub async fn start_copilot(
             if response.status().is_success() {
                 println!("[copilot] ✓ Ready via health check at {:.1}s", (i + 1) as f32 * 0.5);
                 let new_status = {
-                    let mut status = state.copilot_status.lock().unwrap();
+                    let mut status = state.copilot_status.lock();
                     status.authenticated = true;
                     status.clone()
                 };
@@ -291,15 +292,15 @@ pub async fn start_copilot(
     
     // Return with "running but not authenticated" status after timeout
     // The background task will continue polling and emit status updates
-    let initial_status = state.copilot_status.lock().unwrap().clone();
+    let initial_status = state.copilot_status.lock().clone();
     println!("[copilot] Returning after 8s wait: running={}, authenticated={}", initial_status.running, initial_status.authenticated);
     let _ = app.emit("copilot-status-changed", initial_status.clone());
     
     // Spawn background task to poll for authentication
     // This runs independently and emits status updates as authentication completes
     let app_handle = app.clone();
+    let client = state.http.local().clone();
     tauri::async_runtime::spawn(async move {
-        let client = crate::build_management_client();
         let health_url = format!("http://127.0.0.1:{}/v1/models", port);
         
         // Poll for up to 60 seconds to catch slower authentication (especially on first run)
@@ -308,7 +309,7 @@ pub async fn start_copilot(
             
             // Check if stdout listener already detected authentication
             if let Some(state) = app_handle.try_state::<AppState>() {
-                let status = state.copilot_status.lock().unwrap();
+                let status = state.copilot_status.lock();
                 if status.authenticated {
                     println!("✓ Copilot authenticated via stdout detection at {:.1}s", i as f32 * 0.5);
                     return;
@@ -332,7 +333,7 @@ pub async fn start_copilot(
                     // Update status
                     if let Some(state) = app_handle.try_state::<AppState>() {
                         let new_status = {
-                            let mut status = state.copilot_status.lock().unwrap();
+                            let mut status = state.copilot_status.lock();
                             status.authenticated = true;
                             status.clone()
                         };
@@ -358,10 +359,10 @@ pub async fn start_copilot(
 pub async fn stop_copilot(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<CopilotStatus, String> {
+) -> Result<CopilotStatus, CommandError> {
     // Check if running
     {
-        let status = state.copilot_status.lock().unwrap();
+        let status = state.copilot_status.lock();
         if !status.running {
             return Ok(status.clone());
         }
@@ -369,15 +370,15 @@ pub async fn stop_copilot(
     
     // Kill the child process
     {
-        let mut process = state.copilot_process.lock().unwrap();
+        let mut process = state.copilot_process.lock();
         if let Some(child) = process.take() {
-            child.kill().map_err(|e| format!("Failed to kill copilot-api: {}", e))?;
+            child.kill().map_err(|e| CommandError::io(format!("Failed to kill copilot-api: {}", e)))?;
         }
     }
     
     // Update status
     let new_status = {
-        let mut status = state.copilot_status.lock().unwrap();
+        let mut status = state.copilot_status.lock();
         status.running = false;
         status.authenticated = false;
         status.clone()
@@ -390,11 +391,11 @@ pub async fn stop_copilot(
 }
 
 #[tauri::command]
-pub async fn check_copilot_health(state: State<'_, AppState>) -> Result<CopilotStatus, String> {
-    let config = state.config.lock().unwrap().clone();
+pub async fn check_copilot_health(state: State<'_, AppState>) -> Result<CopilotStatus, CommandError> {
+    let config = state.config.lock().clone();
     let port = config.copilot.port;
     
-    let client = crate::build_management_client();
+    let client = state.http.local();
     let health_url = format!("http://127.0.0.1:{}/v1/models", port);
     
     let (running, authenticated) = match client
@@ -409,7 +410,7 @@ pub async fn check_copilot_health(state: State<'_, AppState>) -> Result<CopilotS
     
     // Update status
     let new_status = {
-        let mut status = state.copilot_status.lock().unwrap();
+        let mut status = state.copilot_status.lock();
         status.running = running;
         status.authenticated = authenticated;
         if running {
@@ -423,7 +424,7 @@ pub async fn check_copilot_health(state: State<'_, AppState>) -> Result<CopilotS
 }
 
 #[tauri::command]
-pub async fn detect_copilot_api(app: tauri::AppHandle) -> Result<CopilotApiDetection, String> {
+pub async fn detect_copilot_api(app: tauri::AppHandle) -> Result<CopilotApiDetection, CommandError> {
     // Common Node.js installation paths on macOS/Linux
     // GUI apps don't inherit shell PATH, so we need to check common locations
     // Including version managers: Volta, nvm, fnm, asdf
@@ -852,7 +853,7 @@ pub async fn detect_copilot_api(app: tauri::AppHandle) -> Result<CopilotApiDetec
 }
 
 #[tauri::command]
-pub async fn install_copilot_api(app: tauri::AppHandle) -> Result<CopilotApiInstallResult, String> {
+pub async fn install_copilot_api(app: tauri::AppHandle) -> Result<CopilotApiInstallResult, CommandError> {
     // Find npm binary - GUI apps don't inherit shell PATH on macOS
     // Including version managers: Volta, nvm, fnm, asdf
     let home = dirs::home_dir().unwrap_or_else(|| std::path::PathBuf::from("~"));
@@ -934,7 +935,7 @@ pub async fn install_copilot_api(app: tauri::AppHandle) -> Result<CopilotApiInst
         .args(["install", "-g", "copilot-api"])
         .output()
         .await
-        .map_err(|e| format!("Failed to run npm install: {}", e))?;
+        .map_err(|e| CommandError::io(format!("Failed to run npm install: {}", e)))?;
     
     if !install_output.status.success() {
         let stderr = String::from_utf8_lossy(&install_output.stderr);
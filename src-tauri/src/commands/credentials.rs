@@ -0,0 +1,90 @@
+//! Encrypted export and import of the OAuth credentials in the auth directory.
+//! The passphrase is asked for through the frontend's dialog, never passed in.
+
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+use crate::error::CommandError;
+use crate::helpers::credential_export::{self, MIN_PASSPHRASE_LEN};
+use crate::helpers::permissions::{cli_proxy_auth_dir, restrict_after_write};
+use crate::secrets::{self, PURPOSE_EXPORT_CREDENTIALS, PURPOSE_IMPORT_CREDENTIALS};
+use crate::state::AppState;
+use crate::types::CredentialTransfer;
+
+// The passphrase dialog's answer; None when the user cancelled it
+#[tauri::command]
+pub fn answer_passphrase_request(
+    state: State<AppState>,
+    id: String,
+    passphrase: Option<String>,
+) -> Result<(), CommandError> {
+    if state
+        .passphrase_prompts
+        .answer(&id, passphrase.map(Zeroizing::new))
+    {
+        Ok(())
+    } else {
+        Err(CommandError::not_found(
+            "This passphrase prompt has expired",
+        ))
+    }
+}
+
+// Write every auth file, encrypted under a passphrase the user chooses, to `path`
+#[tauri::command]
+pub async fn export_credentials(
+    app: AppHandle,
+    path: String,
+) -> Result<CredentialTransfer, CommandError> {
+    let files =
+        credential_export::read_auth_files(&cli_proxy_auth_dir()).map_err(CommandError::io)?;
+    if files.is_empty() {
+        return Err(CommandError::not_found(
+            "There are no credentials to export",
+        ));
+    }
+    let passphrase = secrets::request_passphrase(&app, PURPOSE_EXPORT_CREDENTIALS, true).await?;
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(CommandError::invalid_input(format!(
+            "The passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        )));
+    }
+
+    let names = files.iter().map(|f| f.name.clone()).collect();
+    // Key derivation is deliberately slow; keep it off the async workers
+    let sealed = tauri::async_runtime::spawn_blocking(move || {
+        credential_export::encrypt(&files, &passphrase)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    std::fs::write(&path, sealed)
+        .map_err(|e| CommandError::io(format!("Failed to save {}: {}", path, e)))?;
+    restrict_after_write(std::path::Path::new(&path));
+    Ok(CredentialTransfer {
+        files: names,
+        skipped: Vec::new(),
+    })
+}
+
+// Restore auth files from an export. Files already in the auth directory are
+// left alone; the proxy picks up the new ones from the directory.
+#[tauri::command]
+pub async fn import_credentials(
+    app: AppHandle,
+    path: String,
+) -> Result<CredentialTransfer, CommandError> {
+    let data = std::fs::read(&path)
+        .map_err(|e| CommandError::io(format!("Failed to read {}: {}", path, e)))?;
+    let passphrase = secrets::request_passphrase(&app, PURPOSE_IMPORT_CREDENTIALS, false).await?;
+    // A wrong passphrase and a damaged file look the same here
+    let files = tauri::async_runtime::spawn_blocking(move || {
+        credential_export::decrypt(&data, &passphrase)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(CommandError::invalid_input)?;
+    let (files, skipped) = credential_export::write_auth_files(&cli_proxy_auth_dir(), &files)
+        .map_err(CommandError::io)?;
+    Ok(CredentialTransfer { files, skipped })
+}
@@ -0,0 +1,102 @@
+//! Demo mode: synthetic request traffic for UI development and demos.
+//!
+//! Generated requests go through `log_watcher::process_batch` like parsed ones,
+//! so the request feed, history and event stream see them; `demo-mode-changed`
+//! reports when a run starts and ends.
+
+use std::time::Duration;
+
+use rand::SeedableRng;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::watch;
+
+use crate::error::CommandError;
+use crate::helpers::demo;
+use crate::helpers::log_watcher::process_batch;
+use crate::state::AppState;
+
+// Generate synthetic requests at `rate` per second (default 1) for
+// `duration_secs` (default 5 minutes, at most an hour). Replaces a run in progress.
+#[tauri::command]
+pub async fn start_demo_mode(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    rate: Option<f64>,
+    duration_secs: Option<u64>,
+) -> Result<(), CommandError> {
+    if !demo::allowed(state.config.lock().demo_mode_enabled) {
+        return Err(CommandError::invalid_input(
+            "Demo mode is only available in debug builds or with demoModeEnabled in the config",
+        ));
+    }
+    let rate = rate.unwrap_or(demo::DEFAULT_RATE);
+    if !(demo::MIN_RATE..=demo::MAX_RATE).contains(&rate) {
+        return Err(CommandError::invalid_input(format!(
+            "Rate must be between {} and {} requests per second",
+            demo::MIN_RATE,
+            demo::MAX_RATE
+        )));
+    }
+    let duration = Duration::from_secs(
+        duration_secs
+            .unwrap_or(demo::DEFAULT_DURATION_SECS)
+            .clamp(1, demo::MAX_DURATION_SECS),
+    );
+
+    let (sender, mut stop) = watch::channel(false);
+    if let Some(previous) = state.demo_mode.lock().replace(sender) {
+        let _ = previous.send(true);
+    }
+    let _ = app.emit("demo-mode-changed", true);
+
+    let history = state.history.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut rng = rand::rngs::StdRng::from_entropy();
+        let deadline = tokio::time::Instant::now() + duration;
+        let mut last_timestamp = 0;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(demo::next_delay(&mut rng, rate)) => {}
+                _ = stop.changed() => break,
+            }
+            if tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            // History dedupes on timestamp and path
+            let timestamp = (chrono::Utc::now().timestamp_millis() as u64).max(last_timestamp + 1);
+            last_timestamp = timestamp;
+            let log = demo::synthesize(&mut rng, timestamp);
+            process_batch(Some(&app), &history, &[log]);
+        }
+
+        // A stopped or replaced run was already reported by whoever stopped it
+        let state = app.state::<AppState>();
+        let finished = {
+            let mut run = state.demo_mode.lock();
+            let finished = !*stop.borrow();
+            if finished {
+                *run = None;
+            }
+            finished
+        };
+        if finished {
+            let _ = app.emit("demo-mode-changed", false);
+        }
+    });
+    Ok(())
+}
+
+// Stop generating synthetic requests; those already in history stay until
+// cleared with `clear_request_history(syntheticOnly)`
+#[tauri::command]
+pub fn stop_demo_mode(app: AppHandle, state: State<AppState>) {
+    if let Some(run) = state.demo_mode.lock().take() {
+        let _ = run.send(true);
+        let _ = app.emit("demo-mode-changed", false);
+    }
+}
+
+#[tauri::command]
+pub fn is_demo_mode_running(state: State<AppState>) -> bool {
+    state.demo_mode.lock().is_some()
+}
@@ -0,0 +1,127 @@
+//! Shareable diagnostic report.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+
+use crate::error::CommandError;
+use crate::helpers::diagnostics::{self, DiagnosticInputs, LOG_LINES};
+use crate::proxy::lifecycle;
+use crate::state::AppState;
+use crate::types::{BinarySource, DiagnosticReport, ProviderRateLimit};
+
+async fn sidecar_description() -> String {
+    let binary = match lifecycle::resolve_sidecar_binary().await {
+        Ok(binary) => binary,
+        Err(e) => return format!("not found ({})", e),
+    };
+    let source = match binary.source {
+        BinarySource::Bundled => "bundled",
+        BinarySource::System => "system",
+        BinarySource::Custom => "updated",
+    };
+    let version = lifecycle::binary_version(&binary.path)
+        .await
+        .map(|(a, b, c)| format!("{}.{}.{}", a, b, c))
+        .unwrap_or_else(|| "unknown version".to_string());
+    format!("{} ({}, {})", version, source, binary.path.display())
+}
+
+// The config the sidecar was started with, else the one the next start writes
+fn proxy_yaml(state: &AppState, config_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(config_dir.join("proxy-config.yaml"))
+        .ok()
+        .or_else(|| {
+            let config = state.config.lock().clone();
+            let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
+            crate::commands::proxy::build_proxy_config_yaml(&config, config_dir, &auth_dir).ok()
+        })
+}
+
+fn rate_limits(state: &AppState) -> Vec<(ProviderRateLimit, usize)> {
+    let limits = state.config.lock().provider_rate_limits.clone();
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let measured = state.rate_limits.lock();
+    limits
+        .into_iter()
+        .map(|limit| {
+            let count = measured.measured(&limit.provider, now);
+            (limit, count)
+        })
+        .collect()
+}
+
+// Zip holding the report and the masked proxy log
+fn save_zip(report: &str, log_path: &Path, config_dir: &Path) -> Result<PathBuf, String> {
+    let dir = config_dir.join("diagnostics");
+    crate::helpers::permissions::create_private_dir(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!(
+        "proxypal-diagnostics-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let file = std::fs::File::create(&path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    let add = |zip: &mut zip::ZipWriter<std::fs::File>, name: &str, contents: &str| {
+        zip.start_file(name, options)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+        zip.write_all(contents.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", name, e))
+    };
+    add(&mut zip, "report.md", report)?;
+    if let Ok(log) = std::fs::read_to_string(log_path) {
+        add(&mut zip, "main.log", &diagnostics::scrub(&log))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish {}: {}", path.display(), e))?;
+    crate::helpers::permissions::restrict_after_write(&path);
+    Ok(path)
+}
+
+// Markdown report for asking for help: versions, the proxy config, accounts,
+// agents, provider rate limits, recent errors and the end of the proxy log, with keys, tokens and
+// emails masked. With `write_zip` the report and the masked full log are also
+// saved to a zip under the config directory.
+#[tauri::command]
+pub async fn generate_diagnostic_report(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    write_zip: Option<bool>,
+) -> Result<DiagnosticReport, CommandError> {
+    let config_dir = crate::config::get_proxypal_config_dir();
+    let log_path = crate::config::get_data_dir().join("logs").join("main.log");
+
+    let sidecar = sidecar_description().await;
+    let accounts = crate::commands::auth_files::get_auth_files(state.clone())
+        .await
+        .unwrap_or_default();
+    let inputs = DiagnosticInputs {
+        app_version: app.package_info().version.to_string(),
+        sidecar,
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        proxy_running: state.proxy_status.lock().running,
+        proxy_yaml: proxy_yaml(&state, &config_dir),
+        auth: state.auth_status.lock().clone(),
+        accounts,
+        log_lines: diagnostics::tail_lines(&log_path, LOG_LINES),
+        errors: state.recent_errors.lock().entries.iter().cloned().collect(),
+        agents: crate::commands::agents::detect_cli_agents(state.clone()),
+        rate_limits: rate_limits(&state),
+    };
+    let text = diagnostics::render(&inputs);
+
+    let path = if write_zip.unwrap_or(false) {
+        Some(save_zip(&text, &log_path, &config_dir).map_err(CommandError::io)?)
+    } else {
+        None
+    };
+    Ok(DiagnosticReport {
+        text,
+        path: path.map(|p| p.display().to_string()),
+    })
+}
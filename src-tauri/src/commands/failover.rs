@@ -0,0 +1,78 @@
+//! Provider failover rule commands.
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::config::save_config_to_file;
+use crate::error::CommandError;
+use crate::state::AppState;
+use crate::types::{FailoverActivation, FailoverRule};
+
+// Re-check applied rules against the latest health result after an edit
+fn reconcile_in_background(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let health = app
+            .state::<AppState>()
+            .health_cache
+            .lock()
+            .as_ref()
+            .map(|(_, health)| health.clone());
+        crate::failover::reconcile(&app, health.as_ref()).await;
+    });
+}
+
+#[tauri::command]
+pub fn get_failover_rules(state: State<AppState>) -> Vec<FailoverRule> {
+    state.config.lock().failover_rules.clone()
+}
+
+// Add a rule, or replace the one with the same id. Refused if the enabled
+// rules would then loop.
+#[tauri::command]
+pub fn save_failover_rule(
+    app: AppHandle,
+    state: State<AppState>,
+    mut rule: FailoverRule,
+) -> Result<Vec<FailoverRule>, CommandError> {
+    if rule.id.is_empty() {
+        rule.id = uuid::Uuid::new_v4().to_string();
+    }
+
+    let rules = {
+        let mut config = state.config.lock();
+        let mut rules = config.failover_rules.clone();
+        if let Some(existing) = rules.iter_mut().find(|r| r.id == rule.id) {
+            *existing = rule;
+        } else {
+            rules.push(rule);
+        }
+        crate::failover::validate_rules(&rules).map_err(CommandError::invalid_input)?;
+        config.failover_rules = rules;
+        save_config_to_file(&config).map_err(CommandError::io)?;
+        config.failover_rules.clone()
+    };
+    reconcile_in_background(&app);
+    Ok(rules)
+}
+
+#[tauri::command]
+pub fn delete_failover_rule(
+    app: AppHandle,
+    state: State<AppState>,
+    id: String,
+) -> Result<Vec<FailoverRule>, CommandError> {
+    let rules = {
+        let mut config = state.config.lock();
+        config.failover_rules.retain(|r| r.id != id);
+        save_config_to_file(&config).map_err(CommandError::io)?;
+        config.failover_rules.clone()
+    };
+    reconcile_in_background(&app);
+    Ok(rules)
+}
+
+// Rules currently applied to the running proxy
+#[tauri::command]
+pub fn get_active_failovers(state: State<AppState>) -> Vec<FailoverActivation> {
+    state.failover.active()
+}
@@ -1,99 +1,372 @@
 //! Provider health check commands.
+//!
+//! How each provider is judged depends on `AppConfig.probe_mode`:
+//! - "passive" (default): one /v1/models call shows whether the proxy answers,
+//!   then each provider's recent requests in history decide its status
+//! - "active": a one-token completion per authenticated provider, all sent at
+//!   once with their own timeout, so latency and errors are per provider
 
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use futures_util::future::join_all;
 use tauri::State;
 
+use crate::error::CommandError;
+use crate::helpers::latency::median_ttfb;
 use crate::state::AppState;
-use crate::types::{ProviderHealth, HealthStatus};
+use crate::types::{AvailableModel, HealthStatus, ProviderHealth, RequestLog};
 
-#[tauri::command]
-pub async fn check_provider_health(state: State<'_, AppState>) -> Result<ProviderHealth, String> {
-    let (port, proxy_running, proxy_api_key) = {
-        let config = state.config.lock().unwrap();
-        let status = state.proxy_status.lock().unwrap();
-        (config.port, status.running, config.proxy_api_key.clone())
+const MODELS_TIMEOUT: Duration = Duration::from_secs(5);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A /v1/models reply slower than this marks providers degraded
+const SLOW_MODELS_MS: u64 = 2000;
+
+/// A probe completion slower than this marks its provider degraded
+const SLOW_PROBE_MS: u64 = 8000;
+
+/// A median time to first token above this marks a provider degraded.
+/// Total duration isn't used: it grows with the length of the answer.
+const SLOW_TTFB_MS: u64 = 10_000;
+
+/// How far back passive mode looks at request outcomes
+const PASSIVE_WINDOW_MS: u64 = 15 * 60 * 1000;
+
+/// Active results are reused this long, so several open views polling at
+/// once don't each spend tokens
+const ACTIVE_RESULT_TTL: Duration = Duration::from_secs(30);
+
+/// Model id segments (between dashes) that mark a cheap model to probe with
+const CHEAP_MODEL_HINTS: &[&str] = &["flash", "haiku", "mini", "lite"];
+
+fn health(status: &str, latency_ms: Option<u64>, now: u64) -> HealthStatus {
+    HealthStatus {
+        status: status.to_string(),
+        latency_ms,
+        last_checked: now,
+    }
+}
+
+// Providers missing from `statuses` are unconfigured
+fn assemble(mut statuses: HashMap<&str, HealthStatus>, now: u64) -> ProviderHealth {
+    let mut take = |provider: &str| {
+        statuses
+            .remove(provider)
+            .unwrap_or_else(|| health("unconfigured", None, now))
+    };
+    ProviderHealth {
+        claude: take("claude"),
+        openai: take("openai"),
+        gemini: take("gemini"),
+        qwen: take("qwen"),
+        iflow: take("iflow"),
+        vertex: take("vertex"),
+        kiro: take("kiro"),
+        antigravity: take("antigravity"),
+        kimi: take("kimi"),
+        deepseek: take("deepseek"),
+        zhipu: take("zhipu"),
+    }
+}
+
+// A cheap model the proxy reports for the provider, else a well-known one.
+// Gemini-family ids are shared by gemini, vertex and antigravity, so the
+// proxy may route their probes to any of them.
+fn probe_model(provider: &str, models: &[AvailableModel]) -> Option<String> {
+    let pick = |ids: &[&str]| {
+        ids.iter()
+            .find(|id| id.split('-').any(|part| CHEAP_MODEL_HINTS.contains(&part)))
+            .or_else(|| ids.first())
+            .map(|id| id.to_string())
     };
-    
-    let auth_status = state.auth_status.lock().unwrap().clone();
+    let reported: Vec<&str> = models
+        .iter()
+        .filter(|m| m.provider == provider)
+        .map(|m| m.id.as_str())
+        .collect();
+    pick(&reported).or_else(|| pick(crate::commands::models::fallback_model_ids(provider)))
+}
+
+// Status for a probe reply; None means no reply at all
+fn classify_probe(status: Option<u16>, latency_ms: u64) -> &'static str {
+    match status {
+        None => "offline",
+        Some(200..=299) if latency_ms > SLOW_PROBE_MS => "degraded",
+        Some(200..=299) => "healthy",
+        Some(401) | Some(403) => "auth_expired",
+        Some(429) => "rate_limited",
+        Some(_) => "degraded",
+    }
+}
+
+// Status from the provider's requests since `since` (Unix millis); None
+// without recent traffic
+fn passive_status(requests: &[RequestLog], provider: &str, since: u64) -> Option<&'static str> {
+    let recent: Vec<&RequestLog> = requests
+        .iter()
+        .filter(|r| !r.synthetic && r.provider == provider && r.timestamp >= since)
+        .collect();
+    let last = recent.last()?;
+    let server_errors = recent.iter().filter(|r| r.status >= 500).count();
+    Some(match last.status {
+        401 | 403 => "auth_expired",
+        429 => "rate_limited",
+        s if s >= 500 => "degraded",
+        _ if server_errors * 2 > recent.len() => "degraded",
+        _ if median_ttfb(recent.iter().copied()).is_some_and(|t| t > SLOW_TTFB_MS) => "degraded",
+        _ => "healthy",
+    })
+}
+
+async fn passive_statuses(
+    state: &AppState,
+    port: u16,
+    proxy_api_key: &str,
+    providers: &[&'static str],
+    now: u64,
+) -> HashMap<&'static str, HealthStatus> {
+    let start = Instant::now();
+    let (proxy_healthy, latency) = match state
+        .http
+        .local()
+        .get(format!("http://127.0.0.1:{}/v1/models", port))
+        .bearer_auth(proxy_api_key)
+        .timeout(MODELS_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) => (
+            response.status().is_success(),
+            Some(start.elapsed().as_millis() as u64),
+        ),
+        Err(_) => (false, None),
+    };
+
+    let since = (now * 1000).saturating_sub(PASSIVE_WINDOW_MS);
+    state.history.read(|history| {
+        providers
+            .iter()
+            .map(|&provider| {
+                // Time to first token where the log shows it, else the proxy's reply time
+                let latency = median_ttfb(
+                    history
+                        .requests
+                        .iter()
+                        .filter(|r| r.provider == provider && r.timestamp >= since),
+                )
+                .or(latency);
+                let status = if !proxy_healthy {
+                    "offline"
+                } else if let Some(status) = passive_status(&history.requests, provider, since) {
+                    status
+                } else if latency.is_some_and(|l| l > SLOW_MODELS_MS) {
+                    "degraded"
+                } else {
+                    "healthy"
+                };
+                (provider, health(status, latency, now))
+            })
+            .collect()
+    })
+}
+
+async fn active_statuses(
+    state: &AppState,
+    port: u16,
+    proxy_api_key: &str,
+    providers: &[&'static str],
+    now: u64,
+) -> HashMap<&'static str, HealthStatus> {
+    let models = crate::commands::models::cached_models(state);
+    let client = state.http.local();
+    let url = format!("http://127.0.0.1:{}/v1/chat/completions", port);
+    let probes = providers.iter().map(|&provider| {
+        let model = probe_model(provider, &models);
+        let url = &url;
+        async move {
+            let Some(model) = model else {
+                return (provider, health("degraded", None, now));
+            };
+            let body = serde_json::json!({
+                "model": model,
+                "messages": [{ "role": "user", "content": "ping" }],
+                "max_tokens": 1,
+                "stream": false,
+            });
+            let start = Instant::now();
+            let reply = client
+                .post(url)
+                .bearer_auth(proxy_api_key)
+                .timeout(PROBE_TIMEOUT)
+                .json(&body)
+                .send()
+                .await;
+            let latency = start.elapsed().as_millis() as u64;
+            let status = reply.ok().map(|r| r.status().as_u16());
+            let latency = status.map(|_| latency);
+            (
+                provider,
+                health(classify_probe(status, latency.unwrap_or(0)), latency, now),
+            )
+        }
+    });
+    join_all(probes).await.into_iter().collect()
+}
+
+/// Check every provider and keep the result as the latest health. Runs are
+/// serialized, so the background monitor and the command never probe at once.
+pub(crate) async fn check_health(state: &AppState) -> ProviderHealth {
+    let _running = state.health_check_lock.lock().await;
+    let (port, proxy_running, proxy_api_key, probe_mode) = {
+        let config = state.config.lock();
+        let status = state.proxy_status.lock();
+        (
+            config.port,
+            status.running,
+            config.proxy_api_key.clone(),
+            config.probe_mode.clone(),
+        )
+    };
+
+    let auth_status = state.auth_status.lock().clone();
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
         .as_secs();
-    
+
     // If proxy is not running, all providers are offline
     if !proxy_running {
-        let offline_status = HealthStatus {
-            status: "offline".to_string(),
-            latency_ms: None,
-            last_checked: now,
-        };
-        return Ok(ProviderHealth {
-            claude: offline_status.clone(),
-            openai: offline_status.clone(),
-            gemini: offline_status.clone(),
-            qwen: offline_status.clone(),
-            iflow: offline_status.clone(),
-            vertex: offline_status.clone(),
-            kiro: offline_status.clone(),
-            antigravity: offline_status,
-        });
+        let offline = crate::providers::ids()
+            .map(|provider| (provider, health("offline", None, now)))
+            .collect();
+        return assemble(offline, now);
     }
-    
-    let client = reqwest::Client::builder()
-        .no_proxy()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .unwrap_or_else(|_| reqwest::Client::new());
-    
-    // Check health for each provider based on auth status
-    // Note: We use a single /v1/models call since the proxy handles routing
-    // For now, if the proxy is responsive, all configured providers are healthy
-    let models_url = format!("http://127.0.0.1:{}/v1/models", port);
-    let start = std::time::Instant::now();
-    let (proxy_healthy, latency) = match client
-        .get(&models_url)
-        .header("Authorization", format!("Bearer {}", proxy_api_key))
-        .send()
-        .await
-    {
-        Ok(response) => {
-            let latency = start.elapsed().as_millis() as u64;
-            (response.status().is_success(), Some(latency))
+
+    let configured: Vec<&'static str> = crate::providers::ids()
+        .filter(|provider| auth_status.count(provider) > 0)
+        .collect();
+
+    let statuses = if probe_mode == "active" {
+        let cached = state
+            .health_cache
+            .lock()
+            .as_ref()
+            .filter(|(checked_at, _)| checked_at.elapsed() < ACTIVE_RESULT_TTL)
+            .map(|(_, cached)| cached.clone());
+        if let Some(cached) = cached {
+            return cached;
         }
-        Err(_) => (false, None),
+        active_statuses(state, port, &proxy_api_key, &configured, now).await
+    } else {
+        passive_statuses(state, port, &proxy_api_key, &configured, now).await
     };
-    
-    // Build health status for each provider
-    let make_status = |is_configured: bool| -> HealthStatus {
-        if !is_configured {
-            HealthStatus {
-                status: "unconfigured".to_string(),
-                latency_ms: None,
-                last_checked: now,
-            }
-        } else if !proxy_healthy {
-            HealthStatus {
-                status: "offline".to_string(),
-                latency_ms: latency,
-                last_checked: now,
-            }
-        } else {
-            let is_degraded = latency.map(|l| l > 2000).unwrap_or(false);
-            HealthStatus {
-                status: if is_degraded { "degraded" } else { "healthy" }.to_string(),
-                latency_ms: latency,
-                last_checked: now,
-            }
+    let result = assemble(statuses, now);
+    *state.health_cache.lock() = Some((Instant::now(), result.clone()));
+    result
+}
+
+#[tauri::command]
+pub async fn check_provider_health(
+    state: State<'_, AppState>,
+) -> Result<ProviderHealth, CommandError> {
+    Ok(check_health(&state).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(provider: &str, status: u16, timestamp: u64) -> RequestLog {
+        RequestLog {
+            id: format!("req_{}", timestamp),
+            timestamp,
+            provider: provider.to_string(),
+            model: "m".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/chat/completions".to_string(),
+            status,
+            duration_ms: 900,
+            ttfb_ms: None,
+            tokens_in: None,
+            tokens_out: None,
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
         }
-    };
-    
-    Ok(ProviderHealth {
-        claude: make_status(auth_status.claude > 0),
-        openai: make_status(auth_status.openai > 0),
-        gemini: make_status(auth_status.gemini > 0),
-        qwen: make_status(auth_status.qwen > 0),
-        iflow: make_status(auth_status.iflow > 0),
-        vertex: make_status(auth_status.vertex > 0),
-        kiro: make_status(auth_status.kiro > 0),
-        antigravity: make_status(auth_status.antigravity > 0),
-    })
+    }
+
+    #[test]
+    fn classifies_probe_replies() {
+        assert_eq!(classify_probe(Some(200), 300), "healthy");
+        assert_eq!(classify_probe(Some(200), SLOW_PROBE_MS + 1), "degraded");
+        assert_eq!(classify_probe(Some(401), 100), "auth_expired");
+        assert_eq!(classify_probe(Some(429), 100), "rate_limited");
+        assert_eq!(classify_probe(Some(502), 100), "degraded");
+        assert_eq!(classify_probe(None, 20_000), "offline");
+    }
+
+    #[test]
+    fn derives_passive_status_from_recent_requests() {
+        let requests = [
+            request("claude", 200, 1_000),
+            request("claude", 429, 5_000),
+            request("openai", 500, 5_000),
+            request("openai", 200, 6_000),
+            request("openai", 502, 6_500),
+            request("gemini", 401, 100),
+        ];
+        assert_eq!(
+            passive_status(&requests, "claude", 2_000),
+            Some("rate_limited")
+        );
+        assert_eq!(passive_status(&requests, "openai", 2_000), Some("degraded"));
+        // A slow first token degrades; a long answer doesn't
+        let slow = |ttfb_ms: u64| RequestLog {
+            ttfb_ms: Some(ttfb_ms),
+            duration_ms: 120_000,
+            ..request("vertex", 200, 5_000)
+        };
+        let mut requests = requests.to_vec();
+        requests.extend([slow(400), slow(600), slow(500)]);
+        assert_eq!(passive_status(&requests, "vertex", 2_000), Some("healthy"));
+        requests.extend([slow(12_000), slow(15_000), slow(14_000), slow(11_000)]);
+        assert_eq!(passive_status(&requests, "vertex", 2_000), Some("degraded"));
+        // Outcomes older than the window are ignored
+        assert_eq!(passive_status(&requests, "gemini", 2_000), None);
+        assert_eq!(passive_status(&requests, "qwen", 0), None);
+    }
+
+    #[test]
+    fn probes_with_a_cheap_model() {
+        let model = |id: &str, provider: &str| AvailableModel {
+            id: id.to_string(),
+            owned_by: provider.to_string(),
+            source: "oauth".to_string(),
+            provider: provider.to_string(),
+            connected: true,
+        };
+        let models = [
+            model("claude-opus-4-1", "claude"),
+            model("claude-haiku-4-5", "claude"),
+            model("gpt-5", "openai"),
+        ];
+        assert_eq!(
+            probe_model("claude", &models).as_deref(),
+            Some("claude-haiku-4-5")
+        );
+        assert_eq!(probe_model("openai", &models).as_deref(), Some("gpt-5"));
+        // Nothing reported: fall back to the static list
+        assert_eq!(
+            probe_model("gemini", &models).as_deref(),
+            Some("gemini-2.5-flash")
+        );
+    }
 }
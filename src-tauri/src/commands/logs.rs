@@ -1,10 +1,14 @@
 //! Log viewer commands and helpers.
 
+use crate::error::{CommandError, ErrorCode};
+use crate::helpers::log_watcher::{emit_request_logs, BATCH_INTERVAL, REQUEST_QUEUE_CAPACITY};
+use crate::http::send_management;
 use crate::state::AppState;
-use crate::types::LogEntry;
-use crate::{build_management_client, get_management_key, get_management_url};
+use crate::types::{LogEntry, ProxyErrorEntry, ProxyLogDiagnostics, RequestLog};
+use reqwest::Method;
 use serde::Deserialize;
-use tauri::State;
+use std::sync::atomic::Ordering;
+use tauri::{Emitter, State};
 
 // API response structure for logs
 #[derive(Debug, Clone, Deserialize)]
@@ -25,15 +29,14 @@ struct LogsApiResponse {
 pub async fn get_logs(
     state: State<'_, AppState>,
     lines: Option<u32>,
-) -> Result<Vec<LogEntry>, String> {
-    let port = state.config.lock().unwrap().port;
+) -> Result<Vec<LogEntry>, CommandError> {
+    let port = state.config.lock().port;
     let lines_param = lines.unwrap_or(500);
-    let url = format!("{}?lines={}", get_management_url(port, "logs"), lines_param);
+    let endpoint = format!("logs?lines={}", lines_param);
 
-    let client = build_management_client();
-    let response = match client
-        .get(&url)
-        .header("X-Management-Key", &get_management_key())
+    let response = match state
+        .http
+        .management(Method::GET, port, &endpoint)
         .send()
         .await
     {
@@ -43,21 +46,29 @@ pub async fn get_logs(
             if e.is_connect() || e.is_timeout() {
                 return Ok(vec![]);
             }
-            return Err(format!("Failed to get logs: {}", e));
+            return Err(CommandError::new(
+                ErrorCode::Upstream,
+                format!("Failed to get logs: {}", e),
+            ));
         }
     };
 
     if !response.status().is_success() {
         let status = response.status();
         let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to get logs: {} - {}", status, text));
+        return Err(CommandError::new(
+            ErrorCode::Upstream,
+            format!("Failed to get logs: {} - {}", status, text),
+        ));
     }
 
     // Parse JSON response
-    let api_response: LogsApiResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse logs response: {}", e))?;
+    let api_response: LogsApiResponse = response.json().await.map_err(|e| {
+        CommandError::new(
+            ErrorCode::Upstream,
+            format!("Failed to parse logs response: {}", e),
+        )
+    })?;
 
     // Parse each line into a LogEntry
     let entries: Vec<LogEntry> = api_response
@@ -165,23 +176,52 @@ fn normalize_log_level(level: &str) -> String {
 
 // Clear all logs
 #[tauri::command]
-pub async fn clear_logs(state: State<'_, AppState>) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = get_management_url(port, "logs");
-
-    let client = build_management_client();
-    let response = client
-        .delete(&url)
-        .header("X-Management-Key", &get_management_key())
-        .send()
+pub async fn clear_logs(state: State<'_, AppState>) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
+    let request = state.http.management(Method::DELETE, port, "logs");
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to clear logs: {}", e))?;
+        .map_err(|e| CommandError::management("clear logs", e))?;
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to clear logs: {} - {}", status, text));
+    Ok(())
+}
+
+// Recent classified proxy errors (newest last), as shown in the tray submenu
+#[tauri::command]
+pub fn get_recent_errors(state: State<'_, AppState>) -> Vec<ProxyErrorEntry> {
+    state.recent_errors.lock().entries.iter().cloned().collect()
+}
+
+// Counters of the request-log pipeline, to spot requests dropped under load
+#[tauri::command]
+pub fn get_proxy_log_diagnostics(state: State<'_, AppState>) -> ProxyLogDiagnostics {
+    let counters = &state.request_log_counters;
+    ProxyLogDiagnostics {
+        processed_requests: counters.processed.load(Ordering::Relaxed),
+        dropped_requests: counters.dropped.load(Ordering::Relaxed),
+        queue_capacity: REQUEST_QUEUE_CAPACITY,
+        batch_interval_ms: BATCH_INTERVAL.as_millis() as u64,
     }
+}
 
-    Ok(())
+// Stop or restart live request events. History keeps recording while paused;
+// resuming sends what was held back as one batch, then `feed-resumed`.
+#[tauri::command]
+pub fn set_live_feed_paused(app: tauri::AppHandle, state: State<'_, AppState>, paused: bool) {
+    let resumed = state.live_feed.lock().set_paused(paused);
+    if let Some(resumed) = resumed {
+        if !resumed.batch.is_empty() {
+            emit_request_logs(&app, &resumed.batch);
+        }
+        let _ = app.emit(
+            "feed-resumed",
+            serde_json::json!({ "missed": resumed.missed }),
+        );
+    }
+}
+
+// Recent requests, oldest first, so the monitor can render before the next event
+#[tauri::command]
+pub fn snapshot_live_feed(state: State<'_, AppState>) -> Vec<RequestLog> {
+    state.live_feed.lock().snapshot()
 }
@@ -0,0 +1,31 @@
+//! MCP server toggle commands.
+
+use tauri::{AppHandle, State};
+
+use crate::config::save_config_to_file;
+use crate::error::CommandError;
+use crate::state::AppState;
+
+fn set_mcp_enabled(state: &AppState, enabled: bool) -> Result<(), CommandError> {
+    let mut config = state.config.lock();
+    if config.mcp_server_enabled != enabled {
+        config.mcp_server_enabled = enabled;
+        save_config_to_file(&config).map_err(CommandError::io)?;
+    }
+    Ok(())
+}
+
+/// Start the MCP server and remember it for the next launch. Returns the
+/// socket path (or pipe name) it listens on.
+#[tauri::command]
+pub fn start_mcp_server(app: AppHandle, state: State<AppState>) -> Result<String, CommandError> {
+    let endpoint = crate::mcp::start_mcp_server(&app).map_err(CommandError::io)?;
+    set_mcp_enabled(&state, true)?;
+    Ok(endpoint)
+}
+
+#[tauri::command]
+pub fn stop_mcp_server(app: AppHandle, state: State<AppState>) -> Result<(), CommandError> {
+    crate::mcp::stop_mcp_server(&app);
+    set_mcp_enabled(&state, false)
+}
@@ -1,17 +1,42 @@
 //! Command modules for Tauri IPC.
 
+pub mod agent_bundle;
+pub mod agent_endpoints;
 pub mod agents;
+pub mod amp;
+pub mod amp_pools;
 pub mod api_keys;
+pub mod app_update;
 pub mod auth;
 pub mod auth_files;
+pub mod benchmark;
+pub mod captures;
 pub mod config;
 pub mod cloudflare;
 pub mod copilot;
+pub mod credentials;
+pub mod demo;
+pub mod diagnostics;
+pub mod failover;
 pub mod health;
 pub mod logs;
+pub mod mcp;
+pub mod model_caps;
+pub mod model_catalog;
 pub mod models;
+pub mod onboarding;
 pub mod proxy;
+pub mod proxy_keys;
 pub mod quota;
+pub mod rate_limits;
+pub mod request_watches;
+pub mod schedule;
+pub mod self_test;
+pub mod session;
 pub mod settings;
+pub mod shutdown;
+pub mod sidecar;
 pub mod ssh;
 pub mod usage;
+pub mod vertex;
+pub mod webhooks;
@@ -0,0 +1,65 @@
+//! Per-model daily usage cap commands.
+
+use tauri::{AppHandle, State};
+
+use crate::config::save_config_to_file;
+use crate::error::CommandError;
+use crate::state::AppState;
+use crate::types::{ModelCap, ModelCapStatus};
+
+#[tauri::command]
+pub fn get_model_caps(state: State<AppState>) -> Vec<ModelCap> {
+    state.config.lock().model_caps.clone()
+}
+
+// Add a cap, or replace the one with the same id. Takes effect against
+// today's counts straight away.
+#[tauri::command]
+pub fn save_model_cap(
+    app: AppHandle,
+    state: State<AppState>,
+    mut cap: ModelCap,
+) -> Result<Vec<ModelCap>, CommandError> {
+    if cap.id.is_empty() {
+        cap.id = uuid::Uuid::new_v4().to_string();
+    }
+    cap.model_pattern = cap.model_pattern.trim().to_string();
+
+    let caps = {
+        let mut config = state.config.lock();
+        let mut caps = config.model_caps.clone();
+        if let Some(existing) = caps.iter_mut().find(|c| c.id == cap.id) {
+            *existing = cap;
+        } else {
+            caps.push(cap);
+        }
+        crate::model_caps::validate_caps(&caps).map_err(CommandError::invalid_input)?;
+        config.model_caps = caps;
+        save_config_to_file(&config).map_err(CommandError::io)?;
+        config.model_caps.clone()
+    };
+    crate::model_caps::refresh(&app);
+    Ok(caps)
+}
+
+#[tauri::command]
+pub fn delete_model_cap(
+    app: AppHandle,
+    state: State<AppState>,
+    id: String,
+) -> Result<Vec<ModelCap>, CommandError> {
+    let caps = {
+        let mut config = state.config.lock();
+        config.model_caps.retain(|c| c.id != id);
+        save_config_to_file(&config).map_err(CommandError::io)?;
+        config.model_caps.clone()
+    };
+    crate::model_caps::refresh(&app);
+    Ok(caps)
+}
+
+// Today's consumption against each cap
+#[tauri::command]
+pub fn get_model_cap_status(state: State<AppState>) -> Vec<ModelCapStatus> {
+    crate::model_caps::status(&state)
+}
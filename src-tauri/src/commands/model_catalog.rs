@@ -0,0 +1,78 @@
+//! Model catalog commands.
+
+use tauri::{AppHandle, State};
+
+use crate::error::{CommandError, ErrorCode};
+use crate::state::AppState;
+use crate::types::{CatalogModel, ModelCatalog};
+
+/// Available models with their catalog metadata, narrowed to those matching
+/// every word of `query` (id, provider, cost tier, "vision" or "tools")
+#[tauri::command]
+pub async fn get_model_catalog(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    query: Option<String>,
+) -> Result<Vec<CatalogModel>, CommandError> {
+    let models = crate::commands::models::get_available_models(app, state).await?;
+    let annotated = crate::model_catalog::annotate(models, &crate::model_catalog::load());
+    Ok(match query.as_deref().map(str::trim) {
+        Some(query) if !query.is_empty() => crate::model_catalog::search(annotated, query),
+        _ => annotated,
+    })
+}
+
+async fn fetch_text(client: &reqwest::Client, url: &str) -> Result<String, CommandError> {
+    let upstream = |message: String| CommandError::new(ErrorCode::Upstream, message);
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| upstream(format!("Failed to download {}: {}", url, e)))?;
+    if !response.status().is_success() {
+        return Err(upstream(format!(
+            "Failed to download {}: HTTP {}",
+            url,
+            response.status()
+        )));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| upstream(format!("Failed to read {}: {}", url, e)))
+}
+
+/// Download the catalog from `url` (or `model_catalog_url`), check it against
+/// `<url>.sha256` and keep it as the override file
+#[tauri::command]
+pub async fn refresh_model_catalog(
+    state: State<'_, AppState>,
+    url: Option<String>,
+) -> Result<ModelCatalog, CommandError> {
+    let url = url
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+        .or_else(|| state.config.lock().model_catalog_url.clone())
+        .ok_or_else(|| CommandError::invalid_input("No model catalog URL is configured"))?;
+    let file_name = url
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(crate::model_catalog::CATALOG_FILE)
+        .to_string();
+
+    let client = &state.http.external();
+    let result = async {
+        let body = fetch_text(client, &url).await?;
+        let checksums = fetch_text(client, &format!("{}.sha256", url)).await?;
+        crate::model_catalog::install(body.as_bytes(), &checksums, &file_name)
+            .map_err(CommandError::from)
+    }
+    .await;
+    crate::helpers::audit::record(
+        "refresh_model_catalog",
+        vec![format!("modelCatalog: {}", url)],
+        &result,
+    );
+    result
+}
@@ -1,8 +1,55 @@
 use crate::config::save_config_to_file;
-use crate::state::AppState;
-use crate::types::{AvailableModel, ProviderTestResult};
+use crate::error::{CommandError, ErrorCode};
+use crate::http::{management_json, send_management, ManagementError};
+use crate::providers::AuthKind;
+use crate::state::{AppState, ModelsCache};
+use crate::types::{AuthStatus, AvailableModel, ProviderTestResult};
+use crate::utils::detect_provider_from_model;
+use reqwest::Method;
 use serde::Deserialize;
-use tauri::State;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+// How long a /v1/models result is reused before querying the proxy again
+const MODELS_CACHE_TTL: Duration = Duration::from_secs(30);
+
+// Well-known models per provider, offered when the proxy is not running.
+// (provider, owned_by, model ids)
+const FALLBACK_MODELS: &[(&str, &str, &[&str])] = &[
+    (
+        "claude",
+        "anthropic",
+        &[
+            "claude-sonnet-4-5-20250929",
+            "claude-opus-4-1-20250805",
+            "claude-3-5-haiku-20241022",
+        ],
+    ),
+    ("openai", "openai", &["gpt-5", "gpt-5-codex"]),
+    ("gemini", "google", &["gemini-2.5-pro", "gemini-2.5-flash"]),
+    ("vertex", "google", &["gemini-2.5-pro", "gemini-2.5-flash"]),
+    ("qwen", "qwen", &["qwen3-coder-plus", "qwen3-coder-flash"]),
+    ("iflow", "iflow", &["qwen3-coder-plus", "kimi-k2", "glm-4.6"]),
+    ("kiro", "kiro", &["claude-sonnet-4-5"]),
+    (
+        "antigravity",
+        "antigravity",
+        &[
+            "gemini-3-pro-high",
+            "gemini-3-flash",
+            "gemini-claude-sonnet-4-5",
+            "gemini-claude-sonnet-4-5-thinking",
+            "gemini-claude-opus-4-5-thinking",
+        ],
+    ),
+    ("kimi", "kimi", &["kimi-k2"]),
+    (
+        "deepseek",
+        "deepseek",
+        &["deepseek-chat", "deepseek-reasoner"],
+    ),
+    ("zhipu", "zhipu", &["glm-4.6", "glm-4.5-air"]),
+];
 
 // Internal types for model API responses
 #[derive(Debug, Deserialize)]
@@ -21,27 +68,83 @@ pub fn get_gpt_reasoning_models() -> Vec<String> {
     crate::GPT5_BASE_MODELS.iter().map(|s| s.to_string()).collect()
 }
 
+// Well-known model ids for one provider
+pub(crate) fn fallback_model_ids(provider: &str) -> &'static [&'static str] {
+    FALLBACK_MODELS
+        .iter()
+        .find(|(p, _, _)| *p == provider)
+        .map(|(_, _, ids)| *ids)
+        .unwrap_or(&[])
+}
+
+// Static model list for the providers that have credentials, used while the proxy is down
+fn fallback_models(auth: &AuthStatus) -> Vec<AvailableModel> {
+    let mut models: Vec<AvailableModel> = Vec::new();
+    for (provider, owned_by, ids) in FALLBACK_MODELS {
+        if auth.count(provider) == 0 {
+            continue;
+        }
+        let source = match crate::providers::get(provider) {
+            Some(meta) if meta.auth_kind == AuthKind::ApiKey => "api-key",
+            _ => "oauth",
+        };
+        for id in ids.iter() {
+            if models.iter().any(|m| m.id == *id) {
+                continue;
+            }
+            models.push(AvailableModel {
+                id: id.to_string(),
+                owned_by: owned_by.to_string(),
+                source: source.to_string(),
+                provider: provider.to_string(),
+                connected: false,
+            });
+        }
+    }
+    models
+}
+
+// Cached models from the last successful /v1/models fetch (ignores TTL)
+pub(crate) fn cached_models(state: &AppState) -> Vec<AvailableModel> {
+    state
+        .models_cache
+        .lock()
+        .as_ref()
+        .map(|c| c.models.clone())
+        .unwrap_or_default()
+}
+
 #[tauri::command]
-pub async fn get_available_models(state: State<'_, AppState>) -> Result<Vec<AvailableModel>, String> {
-    let config = state.config.lock().unwrap().clone();
-    let proxy_running = state.proxy_status.lock().unwrap().running;
+pub async fn get_available_models(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<AvailableModel>, CommandError> {
+    let config = state.config.lock().clone();
+    let proxy_running = state.proxy_status.lock().running;
     
     if !proxy_running {
-        return Ok(vec![]);
+        let auth_status = state.auth_status.lock().clone();
+        return Ok(fallback_models(&auth_status));
+    }
+
+    let fresh = state
+        .models_cache
+        .lock()
+        .as_ref()
+        .filter(|cache| cache.fetched_at.elapsed() < MODELS_CACHE_TTL)
+        .map(|cache| cache.models.clone());
+    if let Some(models) = fresh {
+        return Ok(models);
     }
     
     // Get auth status to determine model sources
-    let auth_status = state.auth_status.lock().unwrap().clone();
+    let auth_status = state.auth_status.lock().clone();
     let has_vertex = auth_status.vertex > 0;
     let has_gemini_api = !config.gemini_api_keys.is_empty();
     let has_copilot = config.copilot.enabled;
     let has_openai = auth_status.openai > 0;
     
-    let client = reqwest::Client::builder()
-        .no_proxy()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| e.to_string())?;
+    let client = state.http.local();
     
     let endpoint = format!("http://localhost:{}/v1/models", config.port);
     
@@ -54,22 +157,27 @@ pub async fn get_available_models(state: State<'_, AppState>) -> Result<Vec<Avai
         Err(e) => {
             // Connection error - proxy might have crashed
             // Update state to reflect proxy is not running
-            {
-                let mut status = state.proxy_status.lock().unwrap();
-                status.running = false;
-            }
-            return Err(format!("Proxy not responding. Please restart the proxy. ({})", e));
+            state.proxy_status.lock().set_stopped();
+            return Err(CommandError::new(
+                ErrorCode::ProxyNotRunning,
+                format!("Proxy not responding. Please restart the proxy. ({})", e),
+            ));
         }
     };
     
     if !response.status().is_success() {
-        return Err(format!("API returned status {}", response.status()));
+        return Err(CommandError::new(
+            ErrorCode::Upstream,
+            format!("API returned status {}", response.status()),
+        ));
     }
     
-    let api_response: ModelsApiResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse models response: {}", e))?;
+    let api_response: ModelsApiResponse = response.json().await.map_err(|e| {
+        CommandError::new(
+            ErrorCode::Upstream,
+            format!("Failed to parse models response: {}", e),
+        )
+    })?;
     
     let models: Vec<AvailableModel> = api_response.data
         .into_iter()
@@ -115,12 +223,29 @@ pub async fn get_available_models(state: State<'_, AppState>) -> Result<Vec<Avai
             };
             
             AvailableModel {
+                provider: detect_provider_from_model(&m.id),
                 id: m.id,
                 owned_by: m.owned_by,
                 source,
+                connected: true,
             }
         })
         .collect();
+
+    // Notify listeners when the model set changed (e.g. a provider was connected)
+    let previous = state.models_cache.lock().replace(ModelsCache {
+        models: models.clone(),
+        fetched_at: Instant::now(),
+    });
+    if let Some(previous) = previous {
+        let mut old_ids: Vec<&str> = previous.models.iter().map(|m| m.id.as_str()).collect();
+        let mut new_ids: Vec<&str> = models.iter().map(|m| m.id.as_str()).collect();
+        old_ids.sort_unstable();
+        new_ids.sort_unstable();
+        if old_ids != new_ids {
+            let _ = app.emit("models-changed", &models);
+        }
+    }
     
     Ok(models)
 }
@@ -129,17 +254,13 @@ pub async fn get_available_models(state: State<'_, AppState>) -> Result<Vec<Avai
 pub async fn test_provider_connection(
     model_id: String,
     state: State<'_, AppState>,
-) -> Result<ProviderTestResult, String> {
+) -> Result<ProviderTestResult, CommandError> {
     let (port, api_key) = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         (config.port, config.proxy_api_key.clone())
     };
 
-    let client = reqwest::Client::builder()
-        .no_proxy()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .map_err(|e| e.to_string())?;
+    let client = state.http.local();
 
     let endpoint = format!("http://localhost:{}/v1/chat/completions", port);
     
@@ -158,6 +279,7 @@ pub async fn test_provider_connection(
     let response = client.post(&endpoint)
         .header("Authorization", format!("Bearer {}", api_key))
         .json(&payload)
+        .timeout(Duration::from_secs(30))
         .send()
         .await;
     
@@ -195,7 +317,11 @@ pub async fn test_provider_connection(
 }
 
 #[tauri::command]
-pub async fn test_openai_provider(base_url: String, api_key: String) -> Result<ProviderTestResult, String> {
+pub async fn test_openai_provider(
+    state: State<'_, AppState>,
+    base_url: String,
+    api_key: String,
+) -> Result<ProviderTestResult, CommandError> {
     if base_url.is_empty() || api_key.is_empty() {
         return Ok(ProviderTestResult {
             success: false,
@@ -205,10 +331,7 @@ pub async fn test_openai_provider(base_url: String, api_key: String) -> Result<P
         });
     }
     
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| e.to_string())?;
+    let client = state.http.external();
     
     // Normalize base URL - remove trailing slash
     let base_url = base_url.trim_end_matches('/');
@@ -226,6 +349,7 @@ pub async fn test_openai_provider(base_url: String, api_key: String) -> Result<P
     for endpoint in &endpoints {
         let response = client.get(endpoint)
             .header("Authorization", format!("Bearer {}", api_key))
+            .timeout(Duration::from_secs(10))
             .send()
             .await;
         let latency = start.elapsed().as_millis() as u64;
@@ -292,7 +416,7 @@ pub async fn test_openai_provider(base_url: String, api_key: String) -> Result<P
 
 // Fetch models from all configured OpenAI-compatible providers
 #[tauri::command]
-pub async fn fetch_openai_compatible_models(state: State<'_, AppState>) -> Result<Vec<crate::types::OpenAICompatibleProviderModels>, String> {
+pub async fn fetch_openai_compatible_models(state: State<'_, AppState>) -> Result<Vec<crate::types::OpenAICompatibleProviderModels>, CommandError> {
     // Get all configured OpenAI-compatible providers
     let providers = crate::commands::api_keys::get_openai_compatible_providers(state.clone()).await?;
     
@@ -300,10 +424,7 @@ pub async fn fetch_openai_compatible_models(state: State<'_, AppState>) -> Resul
         return Ok(Vec::new());
     }
     
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| e.to_string())?;
+    let client = state.http.external();
     
     let mut results = Vec::new();
     
@@ -334,6 +455,7 @@ pub async fn fetch_openai_compatible_models(state: State<'_, AppState>) -> Resul
         for endpoint in &endpoints {
             let response = client.get(endpoint)
                 .header("Authorization", format!("Bearer {}", api_key))
+                .timeout(Duration::from_secs(10))
                 .send()
                 .await;
             
@@ -557,15 +679,20 @@ pub(crate) fn get_model_display_name(model_id: &str, owned_by: &str, source: &st
 }
 
 #[tauri::command]
-pub async fn set_claude_code_model(model_type: String, model_name: String) -> Result<(), String> {
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+pub async fn set_claude_code_model(
+    model_type: String,
+    model_name: String,
+) -> Result<(), CommandError> {
+    let home =
+        dirs::home_dir().ok_or_else(|| CommandError::not_found("Could not find home directory"))?;
     let config_dir = home.join(".claude");
-    std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&config_dir).map_err(|e| CommandError::io(e.to_string()))?;
     let config_path = config_dir.join("settings.json");
     
     // Read existing config or create new
     let mut json: serde_json::Value = if config_path.exists() {
-        let content = std::fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
+        let content =
+            std::fs::read_to_string(&config_path).map_err(|e| CommandError::io(e.to_string()))?;
         serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
     } else {
         serde_json::json!({})
@@ -581,7 +708,12 @@ pub async fn set_claude_code_model(model_type: String, model_name: String) -> Re
         "haiku" => "ANTHROPIC_DEFAULT_HAIKU_MODEL",
         "opus" => "ANTHROPIC_DEFAULT_OPUS_MODEL",
         "sonnet" => "ANTHROPIC_DEFAULT_SONNET_MODEL",
-        _ => return Err(format!("Unknown model type: {}", model_type)),
+        _ => {
+            return Err(CommandError::invalid_input(format!(
+                "Unknown model type: {}",
+                model_type
+            )))
+        }
     };
     
     // Update the model
@@ -591,58 +723,242 @@ pub async fn set_claude_code_model(model_type: String, model_name: String) -> Re
     
     // Write back
     let config_str = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
-    std::fs::write(&config_path, config_str).map_err(|e| e.to_string())?;
+    std::fs::write(&config_path, config_str).map_err(|e| CommandError::io(e.to_string()))?;
     
     Ok(())
 }
 
 // Get force model mappings from Management API
 #[tauri::command]
-pub async fn get_force_model_mappings(state: State<'_, AppState>) -> Result<bool, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "ampcode/force-model-mappings");
-    
-    let client = crate::build_management_client();
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to get force model mappings: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Ok(false); // Default to false
-    }
-    
-    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+pub async fn get_force_model_mappings(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    let port = state.config.lock().port;
+    let request = state
+        .http
+        .management(Method::GET, port, "ampcode/force-model-mappings");
+    let json: serde_json::Value = match management_json(request).await {
+        Ok(json) => json,
+        Err(ManagementError::Status { .. }) => return Ok(false), // Default to false
+        Err(e) => return Err(CommandError::management("get force model mappings", e)),
+    };
     Ok(json.get("force-model-mappings").and_then(|v| v.as_bool()).unwrap_or(false))
 }
 
 // Set force model mappings via Management API
 #[tauri::command]
-pub async fn set_force_model_mappings(state: State<'_, AppState>, value: bool) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = crate::get_management_url(port, "ampcode/force-model-mappings");
-    
-    let client = crate::build_management_client();
-    let response = client
-        .put(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .json(&serde_json::json!({ "value": value }))
-        .send()
+pub async fn set_force_model_mappings(state: State<'_, AppState>, value: bool) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
+    let request = state
+        .http
+        .management(Method::PUT, port, "ampcode/force-model-mappings")
+        .json(&serde_json::json!({ "value": value }));
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to set force model mappings: {}", e))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to set force model mappings: {} - {}", status, text));
-    }
-    
+        .map_err(|e| CommandError::management("set force model mappings", e))?;
+
     // Persist to Tauri config so it survives restart
-    let mut config = state.config.lock().unwrap();
+    let mut config = state.config.lock();
     config.force_model_mappings = value;
-    save_config_to_file(&config).map_err(|e| format!("Failed to save config: {}", e))?;
+    save_config_to_file(&config)
+        .map_err(|e| CommandError::io(format!("Failed to save config: {}", e)))?;
     
     Ok(())
 }
+
+// ============================================================================
+// Model alias export (LiteLLM / JSON)
+// ============================================================================
+
+// Build alias -> proxied model id pairs. Every advertised model maps to itself;
+// enabled Amp model mappings add an alias when their target is advertised.
+fn build_model_alias_map(
+    models: &[AvailableModel],
+    mappings: &[crate::types::AmpModelMapping],
+) -> Vec<(String, String)> {
+    let mut aliases: Vec<(String, String)> =
+        models.iter().map(|m| (m.id.clone(), m.id.clone())).collect();
+
+    for mapping in mappings.iter().filter(|m| m.enabled) {
+        let advertised = models.iter().any(|m| m.id == mapping.alias);
+        let taken = aliases.iter().any(|(alias, _)| *alias == mapping.name);
+        if advertised && !taken {
+            aliases.push((mapping.name.clone(), mapping.alias.clone()));
+        }
+    }
+
+    aliases
+}
+
+// LiteLLM proxy config: one model_list entry per alias, routed to ProxyPal's
+// OpenAI-compatible endpoint. Scalars are emitted as JSON strings, which are
+// valid double-quoted YAML.
+fn render_litellm_config(aliases: &[(String, String)], endpoint_v1: &str, api_key: &str) -> String {
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_else(|_| format!("\"{}\"", s));
+    let mut yaml = String::from("# LiteLLM model aliases generated by ProxyPal\nmodel_list:\n");
+    for (alias, model) in aliases {
+        yaml.push_str(&format!(
+            "  - model_name: {}\n    litellm_params:\n      model: {}\n      api_base: {}\n      api_key: {}\n",
+            quote(alias),
+            quote(&format!("openai/{}", model)),
+            quote(endpoint_v1),
+            quote(api_key),
+        ));
+    }
+    yaml.push_str("router_settings:\n  routing_strategy: \"simple-shuffle\"\n");
+    yaml
+}
+
+fn render_json_alias_map(aliases: &[(String, String)]) -> Result<String, String> {
+    let map: serde_json::Map<String, serde_json::Value> = aliases
+        .iter()
+        .map(|(alias, model)| (alias.clone(), serde_json::Value::String(model.clone())))
+        .collect();
+    serde_json::to_string_pretty(&map).map_err(|e| e.to_string())
+}
+
+// Export model aliases as a LiteLLM config ("litellm") or a plain JSON map ("json").
+// Writes to `path` when given; the content is returned either way for copying.
+#[tauri::command]
+pub async fn export_model_aliases(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    format: String,
+    path: Option<String>,
+) -> Result<String, CommandError> {
+    let (host, port) = crate::proxy::lifecycle::client_address(&state);
+    let (endpoint_v1, api_key, mappings) = {
+        let config = state.config.lock();
+        (
+            crate::proxy::lifecycle::proxy_endpoint(&host, port),
+            config.proxy_api_key.clone(),
+            config.amp_model_mappings.clone(),
+        )
+    };
+    let models = get_available_models(app, state).await?;
+    let aliases = build_model_alias_map(&models, &mappings);
+
+    let content = match format.as_str() {
+        "litellm" => render_litellm_config(&aliases, &endpoint_v1, &api_key),
+        "json" => render_json_alias_map(&aliases)?,
+        _ => {
+            return Err(CommandError::invalid_input(format!(
+                "Unknown alias format: {}",
+                format
+            )))
+        }
+    };
+
+    if let Some(path) = path {
+        let written = content.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            std::fs::write(&path, written).map_err(|e| {
+                CommandError::io(format!("Failed to write aliases to {}: {}", path, e))
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+    }
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AmpModelMapping;
+
+    fn model(id: &str) -> AvailableModel {
+        AvailableModel {
+            id: id.to_string(),
+            owned_by: "test".to_string(),
+            source: "test".to_string(),
+            provider: detect_provider_from_model(id),
+            connected: true,
+        }
+    }
+
+    fn mapping(name: &str, alias: &str, enabled: bool) -> AmpModelMapping {
+        AmpModelMapping {
+            name: name.to_string(),
+            alias: alias.to_string(),
+            enabled,
+            fork: false,
+        }
+    }
+
+    #[test]
+    fn litellm_aliases_round_trip_and_reference_advertised_models() {
+        let models = vec![model("gpt-5-codex"), model("gemini-2.5-pro")];
+        let mappings = vec![
+            mapping("claude-opus-4-6", "gemini-2.5-pro", true),
+            mapping("claude-haiku-4-5", "missing-model", true),
+            mapping("claude-sonnet-4-5", "gpt-5-codex", false),
+        ];
+        let aliases = build_model_alias_map(&models, &mappings);
+        let yaml = render_litellm_config(&aliases, "http://127.0.0.1:8317/v1", "proxypal-local");
+
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).expect("valid YAML");
+        let entries = parsed["model_list"].as_sequence().expect("model_list");
+        assert_eq!(entries.len(), 3, "2 models + 1 enabled, advertised mapping");
+
+        for entry in entries {
+            let target = entry["litellm_params"]["model"].as_str().unwrap();
+            let id = target.strip_prefix("openai/").expect("openai/ prefix");
+            assert!(
+                models.iter().any(|m| m.id == id),
+                "alias points at unadvertised model {}",
+                id
+            );
+        }
+        assert!(entries
+            .iter()
+            .any(|e| e["model_name"].as_str() == Some("claude-opus-4-6")));
+    }
+
+    #[test]
+    fn antigravity_fallback_lists_gemini_claude_models() {
+        for id in fallback_model_ids("antigravity") {
+            assert_eq!(detect_provider_from_model(id), "antigravity", "{}", id);
+        }
+        let auth = AuthStatus {
+            antigravity: 1,
+            ..AuthStatus::default()
+        };
+        let models = fallback_models(&auth);
+        assert!(models
+            .iter()
+            .any(|m| m.id == "gemini-claude-sonnet-4-5" && m.provider == "antigravity"));
+        assert!(models.iter().all(|m| m.provider == "antigravity"));
+    }
+
+    #[test]
+    fn key_only_providers_fall_back_to_their_own_models() {
+        let auth = AuthStatus {
+            deepseek: 1,
+            zhipu: 2,
+            ..AuthStatus::default()
+        };
+        let models = fallback_models(&auth);
+        for provider in ["deepseek", "zhipu"] {
+            let ids: Vec<&str> = models
+                .iter()
+                .filter(|m| m.provider == provider)
+                .map(|m| m.id.as_str())
+                .collect();
+            assert_eq!(ids, fallback_model_ids(provider), "{}", provider);
+            for id in ids {
+                assert_eq!(detect_provider_from_model(id), provider, "{}", id);
+            }
+        }
+        assert!(models.iter().all(|m| m.source == "api-key"));
+    }
+
+    #[test]
+    fn json_alias_map_contains_mapping_targets() {
+        let models = vec![model("gemini-2.5-pro")];
+        let mappings = vec![mapping("claude-opus-4-6", "gemini-2.5-pro", true)];
+        let json = render_json_alias_map(&build_model_alias_map(&models, &mappings)).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["claude-opus-4-6"], "gemini-2.5-pro");
+        assert_eq!(parsed["gemini-2.5-pro"], "gemini-2.5-pro");
+    }
+}
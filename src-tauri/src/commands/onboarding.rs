@@ -0,0 +1,103 @@
+//! First-run onboarding progress.
+//!
+//! Steps are marked automatically when the app observes them (proxy started,
+//! provider connected, agent configured, first request), so users who set
+//! things up by hand aren't asked to do them again.
+
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::CommandError;
+use crate::state::AppState;
+use crate::types::{
+    OnboardingState, ONBOARDING_STEPS, STEP_AGENT_CONFIGURED, STEP_PROVIDER_CONNECTED,
+    STEP_PROXY_STARTED,
+};
+
+// Update the persisted onboarding state, emitting onboarding-changed if it changed
+fn update_step(app: &AppHandle, step: &str, skipped: bool) -> Result<OnboardingState, String> {
+    let state = app.state::<AppState>();
+    let (onboarding, changed) = {
+        let mut config = state.config.lock();
+        let changed = config.onboarding.mark(step, skipped);
+        if changed {
+            crate::config::save_config_to_file(&config)?;
+        }
+        (config.onboarding.clone(), changed)
+    };
+    if changed {
+        let _ = app.emit("onboarding-changed", onboarding.clone());
+    }
+    Ok(onboarding)
+}
+
+/// Mark a step as done. Cheap when the step is already recorded, so it can be
+/// called from hot paths like the log watcher.
+pub(crate) fn mark_onboarding_step(app: &AppHandle, step: &str) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    if state
+        .config
+        .lock()
+        .onboarding
+        .completed_steps
+        .iter()
+        .any(|s| s == step)
+    {
+        return;
+    }
+    if let Err(e) = update_step(app, step, false) {
+        eprintln!("[ProxyPal] Failed to save onboarding state: {}", e);
+    }
+}
+
+// Get onboarding progress, first picking up anything done outside the wizard
+#[tauri::command]
+pub fn get_onboarding_state(app: AppHandle, state: State<AppState>) -> OnboardingState {
+    if state.proxy_status.lock().running {
+        mark_onboarding_step(&app, STEP_PROXY_STARTED);
+    }
+    if state.auth_status.lock().any_connected() {
+        mark_onboarding_step(&app, STEP_PROVIDER_CONNECTED);
+    }
+    let agent_configured = crate::commands::agents::detect_cli_agents(state.clone())
+        .iter()
+        .any(|agent| agent.configured);
+    if agent_configured {
+        mark_onboarding_step(&app, STEP_AGENT_CONFIGURED);
+    }
+    let onboarding = state.config.lock().onboarding.clone();
+    onboarding
+}
+
+// Mark an onboarding step as done, or as skipped
+#[tauri::command]
+pub fn complete_onboarding_step(
+    app: AppHandle,
+    step: String,
+    skipped: Option<bool>,
+) -> Result<OnboardingState, CommandError> {
+    if !ONBOARDING_STEPS.contains(&step.as_str()) {
+        return Err(CommandError::invalid_input(format!(
+            "Unknown onboarding step: {}",
+            step
+        )));
+    }
+    update_step(&app, &step, skipped.unwrap_or(false)).map_err(CommandError::io)
+}
+
+// Start onboarding over
+#[tauri::command]
+pub fn reset_onboarding(
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<OnboardingState, CommandError> {
+    {
+        let mut config = state.config.lock();
+        config.onboarding = OnboardingState::default();
+        crate::config::save_config_to_file(&config).map_err(CommandError::io)?;
+    }
+    let onboarding = OnboardingState::default();
+    let _ = app.emit("onboarding-changed", onboarding.clone());
+    Ok(onboarding)
+}
@@ -2,25 +2,29 @@ use std::sync::atomic::Ordering;
 use tauri::{Emitter, Manager, State};
 use tauri_plugin_shell::ShellExt;
 
-use crate::config::AppConfig;
+use crate::commands::onboarding::mark_onboarding_step;
+use crate::config::{AppConfig, DEFAULT_MANAGEMENT_KEY};
+use crate::error::CommandError;
+use crate::helpers::amp_pools;
 use crate::state::AppState;
-use crate::types::ProxyStatus;
-use crate::helpers::log_watcher::start_log_watcher;
-use crate::get_management_key;
+use crate::notifications;
+use crate::tray::{set_tray_state, tray_state, TrayProxyState};
+use crate::types::{
+    BinarySource, ProxyErrorEntry, ProxyStatus, SessionEventKind, STEP_PROXY_STARTED,
+};
+use crate::proxy::{lifecycle, pause};
 use crate::GPT5_BASE_MODELS;
 use crate::GPT5_REASONING_SUFFIXES;
 
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
-
 use env_proxy;
 use sysproxy::Sysproxy;
 use url::Url;
 
 const DEFAULT_PROXY_CHECK_URL: &str = "https://example.com";
 
+/// Extra five-second readiness rounds for a proxy slow to answer at start
+const LATE_READY_ROUNDS: usize = 6;
+
 fn env_proxy_for_url(target_url: &str) -> Option<String> {
     let parsed = Url::parse(target_url).ok()?;
     let proxy = env_proxy::for_url(&parsed);
@@ -38,7 +42,7 @@ fn normalize_system_proxy(host: &str, port: u16) -> String {
 }
 
 #[tauri::command]
-pub fn get_system_proxy() -> Result<Option<String>, String> {
+pub fn get_system_proxy() -> Result<Option<String>, CommandError> {
     // 1. Check environment variables first (common in Linux/Dev environments)
     // We use a neutral URL to avoid region-specific assumptions.
     if let Some(proxy) = env_proxy_for_url(DEFAULT_PROXY_CHECK_URL) {
@@ -51,7 +55,10 @@ pub fn get_system_proxy() -> Result<Option<String>, String> {
     match sys_proxy {
         Ok(proxy) if proxy.enable => Ok(Some(normalize_system_proxy(&proxy.host, proxy.port))),
         Ok(_) => Ok(None),
-        Err(e) => Err(format!("Failed to detect system proxy: {}", e)),
+        Err(e) => Err(CommandError::io(format!(
+            "Failed to detect system proxy: {}",
+            e
+        ))),
     }
 }
 
@@ -62,7 +69,7 @@ pub fn get_system_proxy() -> Result<Option<String>, String> {
 /// `auth_dir` is the absolute path to the credential directory (e.g. `~/.cli-proxy-api` expanded).
 /// We pass it explicitly so the Go binary receives an absolute path — on Windows `~` is not
 /// automatically expanded by the shell, causing the sidecar to create a literal `~` directory.
-fn build_proxy_config_yaml(
+pub(crate) fn build_proxy_config_yaml(
     config: &AppConfig,
     config_dir: &std::path::Path,
     auth_dir: &std::path::Path,
@@ -75,20 +82,33 @@ fn build_proxy_config_yaml(
     let gemini_api_key_section = build_gemini_api_key_section(config);
     let codex_api_key_section = build_codex_api_key_section(config);
     let vertex_api_key_section = build_vertex_api_key_section(config);
+    let amp_pool_alias_section = amp_pools::oauth_alias_section(config);
     let (thinking_budget, thinking_mode_display) = resolve_thinking_budget(config);
     let payload_section = build_payload_section(config, thinking_budget, thinking_mode_display);
     let routing_section = format!(
         "# Routing strategy for multiple API keys\nrouting:\n  strategy: \"{}\"\n\n",
         config.routing_strategy
     );
+    // The current key plus rotated-out keys still in their grace period
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let api_keys_section: String = crate::commands::proxy_keys::active_proxy_api_keys(config, now)
+        .iter()
+        .map(|key| format!("  - \"{}\"\n", key))
+        .collect();
+    let custom_yaml = read_custom_proxy_yaml(config_dir);
+    check_remote_management(config, custom_yaml.as_deref())?;
+    // With a management port, remote calls arrive through ProxyPal's gateway on
+    // loopback, so the proxy port never accepts them
+    let allow_remote = remote_management_enabled(config, custom_yaml.as_deref())
+        && config.management_port.is_none();
 
     let mut proxy_config = format!(
         r#"# ProxyPal generated config
 port: {}
+host: "{}"
 auth-dir: "{}"
 api-keys:
-  - "{}"
-debug: {}
+{}debug: {}
 usage-statistics-enabled: {}
 logging-to-file: {}
 logs-max-total-size-mb: {}
@@ -100,20 +120,21 @@ quota-exceeded:
   switch-project: {}
   switch-preview-model: {}
 
-# Enable Management API for OAuth flows
+# Management API for OAuth flows and key management (localhost only unless
+# remote management is turned on with a rotated key)
 remote-management:
-  allow-remote: true
+  allow-remote: {}
   secret-key: "{}"
   disable-control-panel: {}
 
-{}{}{}{}{}{}{}# Amp CLI Integration - enables amp login and management routes
+{}{}{}{}{}{}{}{}# Amp CLI Integration - enables amp login and management routes
 # See: https://help.router-for.me/agent-client/amp-cli.html
 # Get API key from: https://ampcode.com/settings
 ampcode:
   upstream-url: "https://ampcode.com"
 {}
 {}
-  restrict-management-to-localhost: false
+  restrict-management-to-localhost: {}
   force-model-mappings: {}
 
 # Additional settings
@@ -122,10 +143,11 @@ commercial-mode: {}
 ws-auth: {}
 "#,
         config.port,
+        config.proxy_bind,
         // Use forward slashes even on Windows — the Go binary handles both,
         // and this avoids YAML escaping issues with backslashes.
         auth_dir.to_string_lossy().replace('\\', "/"),
-        config.proxy_api_key,
+        api_keys_section,
         config.debug,
         config.usage_stats_enabled,
         config.logging_to_file,
@@ -135,6 +157,7 @@ ws-auth: {}
         proxy_url_line,
         config.quota_switch_project,
         config.quota_switch_preview_model,
+        allow_remote,
         config.management_key,
         config.disable_control_panel,
         openai_compat_section,
@@ -142,10 +165,12 @@ ws-auth: {}
         gemini_api_key_section,
         codex_api_key_section,
         vertex_api_key_section,
+        amp_pool_alias_section,
         routing_section,
         payload_section,
         amp_api_key_line,
         amp_model_mappings_section,
+        !allow_remote,
         config.force_model_mappings,
         config.request_logging,
         config.commercial_mode,
@@ -153,20 +178,119 @@ ws-auth: {}
     );
 
     // Append user customizations from proxy-config-custom.yaml if it exists
-    let custom_config_path = config_dir.join("proxy-config-custom.yaml");
-    if custom_config_path.exists() {
-        if let Ok(custom_yaml) = std::fs::read_to_string(&custom_config_path) {
-            if !custom_yaml.trim().is_empty() {
-                proxy_config.push_str("\n# User customizations (from proxy-config-custom.yaml)\n");
-                proxy_config.push_str(&custom_yaml);
-                proxy_config.push('\n');
-            }
-        }
+    if let Some(custom_yaml) = custom_yaml {
+        proxy_config.push_str("\n# User customizations (from proxy-config-custom.yaml)\n");
+        proxy_config.push_str(&custom_yaml);
+        proxy_config.push('\n');
     }
 
     Ok(proxy_config)
 }
 
+/// User customizations appended to the generated proxy config, if any
+pub(crate) fn read_custom_proxy_yaml(config_dir: &std::path::Path) -> Option<String> {
+    std::fs::read_to_string(config_dir.join("proxy-config-custom.yaml"))
+        .ok()
+        .filter(|yaml| !yaml.trim().is_empty())
+}
+
+/// Whether a CLIProxyAPI config (or fragment) sets `remote-management.allow-remote: true`
+pub(crate) fn yaml_allows_remote_management(yaml: &str) -> bool {
+    serde_yaml::from_str::<serde_yaml::Value>(yaml)
+        .ok()
+        .and_then(|v| v.get("remote-management")?.get("allow-remote")?.as_bool())
+        .unwrap_or(false)
+}
+
+/// Whether a full CLIProxyAPI config allows remote management behind the default key
+pub(crate) fn yaml_exposes_default_key(yaml: &str) -> bool {
+    yaml_allows_remote_management(yaml)
+        && serde_yaml::from_str::<serde_yaml::Value>(yaml)
+            .ok()
+            .and_then(|v| {
+                let key = v.get("remote-management")?.get("secret-key")?.as_str()?;
+                Some(key == DEFAULT_MANAGEMENT_KEY)
+            })
+            .unwrap_or(false)
+}
+
+/// Whether the Management API will accept calls from other hosts, either
+/// through the setting or through proxy-config-custom.yaml
+pub(crate) fn remote_management_enabled(config: &AppConfig, custom_yaml: Option<&str>) -> bool {
+    config.management_allow_remote || custom_yaml.is_some_and(yaml_allows_remote_management)
+}
+
+/// Refuse to expose the Management API behind the key every install shares
+fn check_remote_management(config: &AppConfig, custom_yaml: Option<&str>) -> Result<(), String> {
+    if remote_management_enabled(config, custom_yaml)
+        && config.management_key == DEFAULT_MANAGEMENT_KEY
+    {
+        return Err(
+            "Remote management is enabled but the management key is still the default. \
+             Rotate the management key or turn remote management off."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn is_loopback_bind(bind: &str) -> bool {
+    bind == "localhost"
+        || bind
+            .trim_matches(|c| c == '[' || c == ']')
+            .parse::<std::net::IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false)
+}
+
+/// Management API exposure problems in `config`, for `validate_config`
+pub(crate) fn management_warnings(config: &AppConfig, custom_yaml: Option<&str>) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let allow_remote = remote_management_enabled(config, custom_yaml);
+    if config.management_key.trim().is_empty() {
+        warnings.push(
+            "The management key is empty: CLIProxyAPI disables the Management API, so OAuth \
+             logins and key management will fail."
+                .to_string(),
+        );
+    }
+    if allow_remote && config.management_key == DEFAULT_MANAGEMENT_KEY {
+        warnings.push(
+            "Remote management is enabled with the default management key; the proxy will \
+             not start until the key is rotated."
+                .to_string(),
+        );
+    }
+    if !config.management_allow_remote && allow_remote {
+        warnings.push(
+            "proxy-config-custom.yaml sets remote-management.allow-remote: true, so the \
+             Management API accepts calls from other hosts."
+                .to_string(),
+        );
+    }
+    // Without its own port the Management API shares the proxy's listener
+    let bind = match config.management_port {
+        Some(_) => config.management_bind.as_str(),
+        None => config.proxy_bind.as_str(),
+    };
+    if allow_remote && !is_loopback_bind(bind) {
+        warnings.push(format!(
+            "The Management API is reachable from other hosts on {} (port {}); anyone with \
+             the management key can change proxy settings and credentials.",
+            if bind.is_empty() {
+                "all interfaces"
+            } else {
+                bind
+            },
+            config.management_port.unwrap_or(config.port)
+        ));
+    }
+    if let Err(e) = crate::management_gateway::validate_management_port(config) {
+        warnings.push(e);
+    }
+    warnings
+}
+
 fn build_proxy_url_line(config: &AppConfig) -> String {
     let mut effective_proxy_url = if config.use_system_proxy {
         get_system_proxy().ok().flatten().unwrap_or_default()
@@ -197,9 +321,8 @@ fn build_amp_api_key_line(config: &AppConfig) -> String {
 }
 
 fn build_amp_model_mappings_section(config: &AppConfig) -> String {
-    let enabled_mappings: Vec<_> = config.amp_model_mappings.iter()
-        .filter(|m| m.enabled)
-        .collect();
+    // Pool routes first, then the flat mappings no pool overrides
+    let enabled_mappings = amp_pools::effective_amp_mappings(config);
 
     if enabled_mappings.is_empty() {
         "  # model-mappings:  # Optional: map Amp model requests to different models\n  #   - from: claude-opus-4-5-20251101\n  #     to: your-preferred-model".to_string()
@@ -239,6 +362,31 @@ fn build_openai_compat_section(config: &AppConfig) -> String {
         }
     }
 
+    // Members of Amp pools, each with the pool's model alias
+    entries.extend(amp_pools::openai_pool_entries(config));
+
+    // DeepSeek, Zhipu GLM and the other key-only providers
+    for provider in crate::commands::api_keys::api_key_provider_entries(config) {
+        let label = crate::providers::get(&provider.name).map_or("", |p| p.display_name);
+        let mut entry = format!("  # {} API keys\n", label);
+        entry.push_str(&format!("  - name: \"{}\"\n", provider.name));
+        entry.push_str(&format!("    base-url: \"{}\"\n", provider.base_url));
+        entry.push_str("    api-key-entries:\n");
+        for key in &provider.api_key_entries {
+            entry.push_str(&format!("      - api-key: \"{}\"\n", key.api_key));
+            if let Some(ref proxy_url) = key.proxy_url {
+                entry.push_str(&format!("        proxy-url: \"{}\"\n", proxy_url));
+            }
+        }
+        entry.push_str("    models:\n");
+        for model in provider.models.iter().flatten() {
+            let alias = model.alias.as_deref().unwrap_or(&model.name);
+            entry.push_str(&format!("      - alias: \"{}\"\n", alias));
+            entry.push_str(&format!("        name: \"{}\"\n", model.name));
+        }
+        entries.push(entry);
+    }
+
     // Copilot OpenAI-compatible entry
     if config.copilot.enabled {
         entries.push(build_copilot_openai_entry(&config.copilot));
@@ -516,149 +664,103 @@ fn build_gemini_override_section(thinking_level: &str) -> String {
 // Tauri commands
 #[tauri::command]
 pub fn get_proxy_status(state: State<AppState>) -> ProxyStatus {
-    state.proxy_status.lock().unwrap().clone()
+    state.proxy_status.lock().clone()
 }
 
+// Manual start. Outside a scheduled window it needs `override_schedule`,
+// which keeps the proxy up until the next schedule boundary.
 #[tauri::command]
 pub async fn start_proxy(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
+    override_schedule: Option<bool>,
+) -> Result<ProxyStatus, CommandError> {
+    crate::scheduler::allow_manual_start(&state, override_schedule.unwrap_or(false))
+        .map_err(CommandError::invalid_input)?;
+    Ok(start_proxy_now(app, state).await?)
+}
+
+/// Start the proxy regardless of the schedule (scheduler, restarts)
+pub(crate) async fn start_proxy_now(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
 ) -> Result<ProxyStatus, String> {
-    let config = state.config.lock().unwrap().clone();
-    
-    // Check if already running (according to our tracked state)
-    {
-        let status = state.proxy_status.lock().unwrap();
-        if status.running {
-            return Ok(status.clone());
-        }
-    }
+    let config = state.config.lock().clone();
+    start_proxy_with(app, state, config).await
+}
 
-    // Kill any existing tracked proxy process first
-    {
-        let mut process = state.proxy_process.lock().unwrap();
-        if let Some(child) = process.take() {
-            println!("[ProxyPal] Killing tracked proxy process");
-            let _ = child.kill(); // Ignore errors, process might already be dead
-        }
+/// Start the proxy from `config` rather than the current settings
+/// (resuming from a pause)
+pub(crate) async fn start_proxy_with(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    config: AppConfig,
+) -> Result<ProxyStatus, String> {
+    let recovering = tray_state(&app) == Some(TrayProxyState::Error);
+    if !state.proxy_status.lock().running {
+        set_tray_state(&app, TrayProxyState::Starting);
     }
 
-    // Kill any external process using our port (handles orphaned processes from previous runs)
-    let port = config.port;
-    #[cfg(unix)]
-    {
-        // Kill by port
-        println!("[ProxyPal] Killing any process on port {}", port);
-        let _ = std::process::Command::new("sh")
-            .args(["-c", &format!("lsof -ti :{} | xargs kill -9 2>/dev/null", port)])
-            .output();
-        
-        // Also kill any orphaned cliproxyapi processes by name
-        println!("[ProxyPal] Killing any orphaned cliproxyapi processes");
-        let _ = std::process::Command::new("sh")
-            .args(["-c", "pkill -9 -f cliproxyapi 2>/dev/null"])
-            .output();
-    }
-    #[cfg(windows)]
-    {
-        // Kill by port using PowerShell (more reliable than cmd for /f parsing)
-        let kill_by_port_script = format!(
-            "Get-NetTCPConnection -LocalPort {} -State Listen -ErrorAction SilentlyContinue | \
-             Select-Object -ExpandProperty OwningProcess | \
-             ForEach-Object {{ taskkill /F /PID $_ 2>$null }}",
-            port
-        );
-        let mut ps_cmd = std::process::Command::new("powershell");
-        ps_cmd.args(["-NoProfile", "-NonInteractive", "-Command", &kill_by_port_script]);
-        #[cfg(target_os = "windows")]
-        ps_cmd.creation_flags(CREATE_NO_WINDOW);
-        let _ = ps_cmd.output();
-
-        // Kill by process name — sidecar binary is named cli-proxy-api.exe (with hyphens)
-        let mut cmd2 = std::process::Command::new("cmd");
-        cmd2.args(["/C", "taskkill /F /IM cli-proxy-api*.exe 2>nul"]);
-        #[cfg(target_os = "windows")]
-        cmd2.creation_flags(CREATE_NO_WINDOW);
-        let _ = cmd2.output();
-    }
-
-    // Delay to ensure port is fully released.
-    // On Windows, TIME_WAIT / CloseWait TCP connections from the previous process can
-    // linger for 2-3s, so we need a longer delay. On Unix, 500ms is sufficient.
-    #[cfg(target_os = "windows")]
-    tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
-    #[cfg(not(target_os = "windows"))]
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-    // Pre-flight: verify the port is actually bindable before spawning.
-    // On Windows, Docker Desktop / WSL2 can leave `netsh portproxy` rules that hold
-    // 127.0.0.1:<port> via svchost even when no real service is behind them.
-    // We retry a few times with a short sleep to reduce the TOCTOU window between the
-    // previous process releasing the port and us binding it.
-    {
-        use std::net::TcpListener;
-        let mut bind_ok = false;
-        for attempt in 0..3 {
-            if TcpListener::bind(format!("127.0.0.1:{}", port)).is_ok() {
-                bind_ok = true;
-                break;
-            }
-            if attempt < 2 {
-                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    match start_proxy_inner(&app, &state, config).await {
+        Ok(status) => {
+            state
+                .proxy_started_at
+                .lock()
+                .get_or_insert_with(std::time::Instant::now);
+            set_tray_state(&app, TrayProxyState::Running);
+            mark_onboarding_step(&app, STEP_PROXY_STARTED);
+            if recovering {
+                notifications::notify_proxy_restarted(&app);
             }
+            crate::session_journal::record_event(
+                &app,
+                SessionEventKind::ProxyStart,
+                recovering.then(|| "Restarted after a crash".to_string()),
+            );
+            Ok(status)
         }
-        if !bind_ok {
-            let hint = if cfg!(windows) {
-                format!(
-                    "\n\nHint: Port {} is held by another process (possibly Docker Desktop or WSL2 portproxy).\n\
-                     • Go to Settings → General and change the port to a free one (e.g. 8318).\n\
-                     • Or run as Administrator and execute:\n\
-                     \u{0020} netsh interface portproxy delete v4tov4 listenport={} listenaddress=127.0.0.1",
-                    port, port
-                )
-            } else {
-                String::new()
-            };
-            return Err(format!(
-                "Port {} is already in use and could not be released{}",
-                port, hint
-            ));
+        Err(e) => {
+            set_tray_state(&app, TrayProxyState::Error);
+            Err(e)
         }
-        // Bind succeeded — drop the listener immediately so the real proxy can take the port.
-    }
-    let config_dir = dirs::config_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("proxypal");
-    std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
-
-    // Compute the absolute auth-dir path (credential storage for OAuth tokens).
-    // We expand it here so the Go binary receives an absolute path — on Windows `~` is not
-    // expanded automatically, causing credentials to be stored in a literal `~` directory.
-    let auth_dir = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".cli-proxy-api");
-    std::fs::create_dir_all(&auth_dir).ok(); // Best-effort: create if missing
-    
-    let proxy_config_path = config_dir.join("proxy-config.yaml");
-
-    // Build YAML config and append user customizations
-    let proxy_config = build_proxy_config_yaml(&config, &config_dir, &auth_dir)?;
-    std::fs::write(&proxy_config_path, proxy_config).map_err(|e| e.to_string())?;
-
-    // Spawn the sidecar process with WRITABLE_PATH set to app config dir
+    }
+}
+
+async fn start_proxy_inner(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    config: AppConfig,
+) -> Result<ProxyStatus, String> {
+    state.ensure_hydrated()?;
+
+    // Check if already running (according to our tracked state)
+    if let Some(status) = lifecycle::running_status(state) {
+        return Ok(status);
+    }
+
+    let launch = lifecycle::prepare_launch(state, config).await?;
+    let config = launch.config;
+
+    // Spawn the sidecar process with WRITABLE_PATH set to the app data dir
     // This prevents CLIProxyAPI from writing logs to src-tauri/logs/ which triggers hot reload
-    let sidecar = app
-        .shell()
-        .sidecar("cli-proxy-api")
-        .map_err(|e| format!("Failed to create sidecar command: {}", e))?
-        .env("WRITABLE_PATH", config_dir.to_str().unwrap())
-        .args(["--config", proxy_config_path.to_str().unwrap()]);
+    let binary = lifecycle::resolve_sidecar_binary().await?;
+    let command = match binary.source {
+        BinarySource::Bundled => app
+            .shell()
+            .sidecar("cli-proxy-api")
+            .map_err(|e| format!("Failed to create sidecar command: {}", e))?,
+        BinarySource::System | BinarySource::Custom => app.shell().command(&binary.path),
+    };
+    let sidecar = command
+        .env("WRITABLE_PATH", launch.data_dir.to_str().unwrap())
+        .args(["--config", launch.proxy_config_path.to_str().unwrap()]);
 
     let (mut rx, child) = sidecar.spawn().map_err(|e| format!("Failed to spawn sidecar: {}", e))?;
+    lifecycle::record_sidecar(child.pid(), &binary.path).await;
 
     // Store the child process
     {
-        let mut process = state.proxy_process.lock().unwrap();
+        let mut process = state.proxy_process.lock();
         *process = Some(child);
     }
 
@@ -674,11 +776,11 @@ pub async fn start_proxy(
         while let Some(event) = rx.recv().await {
             match event {
                 CommandEvent::Stdout(line) => {
-                    let text = String::from_utf8_lossy(&line);
+                    let text = crate::redact::redact(&String::from_utf8_lossy(&line));
                     println!("[CLIProxyAPI] {}", text);
                 }
                 CommandEvent::Stderr(line) => {
-                    let text = String::from_utf8_lossy(&line);
+                    let text = crate::redact::redact(&String::from_utf8_lossy(&line));
                     eprintln!("[CLIProxyAPI ERROR] {}", text);
                 }
                 CommandEvent::Terminated(payload) => {
@@ -687,9 +789,45 @@ pub async fn start_proxy(
                     early_exit_watcher.store(true, Ordering::SeqCst);
                     // Update status when process dies unexpectedly
                     if let Some(state) = app_handle.try_state::<AppState>() {
-                        let mut status = state.proxy_status.lock().unwrap();
-                        status.running = false;
-                        let _ = app_handle.emit("proxy-status-changed", status.clone());
+                        // stop_proxy takes the child before killing it, so a tracked
+                        // child here means the sidecar exited on its own
+                        if state.proxy_process.lock().is_some() {
+                            let message =
+                                format!("Proxy exited unexpectedly (code {:?})", payload.code);
+                            crate::session_journal::record_event(
+                                &app_handle,
+                                SessionEventKind::ProxyCrash,
+                                Some(message.clone()),
+                            );
+                            state.recent_errors.lock().push(ProxyErrorEntry {
+                                id: uuid::Uuid::new_v4().to_string(),
+                                timestamp: chrono::Utc::now().timestamp_millis() as u64,
+                                kind: "process".to_string(),
+                                status: None,
+                                provider: "cliproxyapi".to_string(),
+                                model: String::new(),
+                                message,
+                            });
+                            set_tray_state(&app_handle, TrayProxyState::Error);
+                            crate::webhooks::emit(
+                                &app_handle,
+                                "proxy-crashed",
+                                &format!("ProxyPal proxy exited unexpectedly (code {:?})", payload.code),
+                                serde_json::json!({ "code": payload.code, "signal": payload.signal }),
+                            );
+                            notifications::notify_proxy_crashed(
+                                &app_handle,
+                                &format!("The proxy exited unexpectedly (code {:?})", payload.code),
+                            );
+                        }
+                        *state.proxy_started_at.lock() = None;
+                        let status = {
+                            let mut status = state.proxy_status.lock();
+                            status.set_stopped();
+                            status.clone()
+                        };
+                        // Emit after releasing the lock; the tray listener reads the status
+                        let _ = app_handle.emit("proxy-status-changed", status);
                     }
                     break;
                 }
@@ -699,120 +837,34 @@ pub async fn start_proxy(
     });
 
     // Wait for the proxy to be ready before syncing settings
-    let port = config.port;
-    let client = crate::build_management_client();
-    let health_url = format!("http://127.0.0.1:{}/v0/management/config.yaml", port);
-    let mut ready = false;
-    for attempt in 0..25 {
-        // 25 attempts × 200ms = 5s max
-        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-
-        // If the process already exited, abort immediately — no point waiting
-        if early_exit.load(Ordering::SeqCst) {
-            eprintln!("[ProxyPal] Proxy process exited early (port conflict or crash). Aborting start.");
-            let hint = if cfg!(windows) {
-                format!(
-                    " Port {} may still be in use.\n\
-                     Go to Settings → General and change the port, or restart your machine.",
-                    port
-                )
-            } else {
-                format!(" Port {} may still be in use.", port)
-            };
-            return Err(format!(
-                "Proxy failed to start — the process exited immediately.{}",
-                hint
-            ));
-        }
-
-        match client
-            .get(&health_url)
-            .header("X-Management-Key", &get_management_key())
-            .send()
-            .await
-        {
-            Ok(resp) if resp.status().is_success() => {
-                ready = true;
-                break;
-            }
-            _ => {
-                if attempt == 24 {
-                    eprintln!("[ProxyPal Debug] Proxy not ready after 5s, proceeding anyway");
-                }
-            }
-        }
-    }
+    let ready = lifecycle::wait_until_ready(&state.http, config.port, &early_exit).await?;
 
     // Sync settings via Management API (best-effort, don't fail proxy start)
     if ready {
-        let _ = client
-            .put(&format!(
-                "http://127.0.0.1:{}/v0/management/usage-statistics-enabled",
-                port
-            ))
-            .header("X-Management-Key", &get_management_key())
-            .json(&serde_json::json!({"value": config.usage_stats_enabled}))
-            .send()
-            .await;
-
-        let _ = client
-            .put(&format!(
-                "http://127.0.0.1:{}/v0/management/ampcode/force-model-mappings",
-                port
-            ))
-            .header("X-Management-Key", &get_management_key())
-            .json(&serde_json::json!({"value": config.force_model_mappings}))
-            .send()
-            .await;
-
-        let _ = client
-            .put(&format!(
-                "http://127.0.0.1:{}/v0/management/max-retry-interval",
-                port
-            ))
-            .header("X-Management-Key", &get_management_key())
-            .json(&serde_json::json!({"value": config.max_retry_interval}))
-            .send()
-            .await;
-    }
-    
-    // Start log file watcher for request tracking
-    // This replaces the old polling approach and captures ALL requests including Amp proxy forwarding
-    let log_path = config_dir.join("logs").join("main.log");
-    let log_watcher_running = state.log_watcher_running.clone();
-    let request_counter = state.request_counter.clone();
-    
-    // Signal any existing watcher to stop, then start new one
-    log_watcher_running.store(false, Ordering::SeqCst);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // Give old watcher time to stop
-    log_watcher_running.store(true, Ordering::SeqCst);
-    
-    let app_handle2 = app.clone();
-    start_log_watcher(app_handle2, log_path, log_watcher_running, request_counter);
-    
-    // Sync usage statistics from proxy to local history on startup (in background)
-    // This ensures analytics page shows data without requiring restart or manual refresh
-    let port = config.port;
-    tokio::spawn(async move {
-        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-        let client = crate::build_management_client();
-        let usage_url = format!("http://127.0.0.1:{}/v0/management/usage", port);
-        let _ = client
-            .get(&usage_url)
-            .header("X-Management-Key", &get_management_key())
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
-            .await;
-    });
+        lifecycle::sync_runtime_settings(&state.http, &config).await;
+    }
 
-    // Update status
-    let new_status = {
-        let mut status = state.proxy_status.lock().unwrap();
-        status.running = true;
-        status.port = config.port;
-        status.endpoint = format!("http://localhost:{}/v1", config.port);
-        status.clone()
-    };
+    lifecycle::start_request_pipeline(Some(app.clone()), state, &launch.data_dir, config.port)
+        .await;
+
+    // Update status; the endpoint waits for the sidecar to answer
+    let new_status =
+        lifecycle::mark_running(state, &config.proxy_bind, config.port, &binary, ready);
+    if !ready {
+        confirm_ready_later(app, config.proxy_bind.clone(), config.port, early_exit);
+    }
+    crate::health_monitor::start_health_monitor(app);
+    crate::idle_watchdog::start_idle_watchdog(app);
+
+    // A fresh proxy only has the config's mappings; restore cap redirects
+    if crate::model_caps::has_redirects(state) {
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = crate::failover::push_mappings(&app_handle).await {
+                eprintln!("[ModelCaps] Failed to restore model redirects: {}", e);
+            }
+        });
+    }
 
     // Emit status update
     let _ = app.emit("proxy-status-changed", new_status.clone());
@@ -820,54 +872,71 @@ pub async fn start_proxy(
     Ok(new_status)
 }
 
+/// Keep polling a proxy that was slow to answer at start and publish its
+/// endpoint once it does
+fn confirm_ready_later(
+    app: &tauri::AppHandle,
+    bind: String,
+    port: u16,
+    early_exit: std::sync::Arc<std::sync::atomic::AtomicBool>,
+) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        for _ in 0..LATE_READY_ROUNDS {
+            match lifecycle::wait_until_ready(&state.http, port, &early_exit).await {
+                Ok(true) => {
+                    if let Some(status) = lifecycle::mark_ready(&state, &bind, port) {
+                        let _ = app.emit("proxy-status-changed", status);
+                    }
+                    return;
+                }
+                Ok(false) if state.proxy_status.lock().running => continue,
+                _ => return,
+            }
+        }
+    });
+}
+
+// Manual stop; inside a scheduled window it holds until the window closes
 #[tauri::command]
 pub async fn stop_proxy(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
+) -> Result<ProxyStatus, CommandError> {
+    if state.proxy_status.lock().running {
+        crate::scheduler::note_manual_stop(&state);
+    }
+    Ok(stop_proxy_now(app, state).await?)
+}
+
+/// Stop the proxy without touching the schedule (scheduler, restarts)
+pub(crate) async fn stop_proxy_now(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
 ) -> Result<ProxyStatus, String> {
     // Check if running
     {
-        let status = state.proxy_status.lock().unwrap();
+        let status = state.proxy_status.lock();
         if !status.running {
             return Ok(status.clone());
         }
     }
 
-    // Stop the log watcher
-    state.log_watcher_running.store(false, Ordering::SeqCst);
-
     // Kill the tracked child process
     {
-        let mut process = state.proxy_process.lock().unwrap();
+        let mut process = state.proxy_process.lock();
         if let Some(child) = process.take() {
             println!("[ProxyPal] Killing tracked proxy process");
             let _ = child.kill();
         }
     }
 
-    // Also kill any orphaned cliproxyapi processes by name (belt and suspenders)
-    #[cfg(unix)]
-    {
-        println!("[ProxyPal] Cleaning up any orphaned cliproxyapi processes");
-        let _ = std::process::Command::new("sh")
-            .args(["-c", "pkill -9 -f cliproxyapi 2>/dev/null"])
-            .output();
-    }
-    #[cfg(windows)]
-    {
-        let mut cmd = std::process::Command::new("cmd");
-        cmd.args(["/C", "taskkill /F /IM cliproxyapi*.exe 2>nul"]);
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        let _ = cmd.output();
-    }
-
-    // Update status
-    let new_status = {
-        let mut status = state.proxy_status.lock().unwrap();
-        status.running = false;
-        status.clone()
-    };
+    let _ = tauri::async_runtime::spawn_blocking(lifecycle::kill_recorded_sidecar).await;
+
+    // Stops the log watcher and updates status
+    let new_status = lifecycle::mark_stopped(&state);
+    crate::session_journal::record_event(&app, SessionEventKind::ProxyStop, None);
 
     // Emit status update
     let _ = app.emit("proxy-status-changed", new_status.clone());
@@ -875,6 +944,25 @@ pub async fn stop_proxy(
     Ok(new_status)
 }
 
+// Drain in-flight requests (up to `drain_timeout_secs`, default 30), then
+// stop the proxy, keeping its config for `resume_proxy`
+#[tauri::command]
+pub async fn pause_proxy(
+    app: tauri::AppHandle,
+    drain_timeout_secs: Option<u64>,
+) -> Result<ProxyStatus, CommandError> {
+    let timeout = drain_timeout_secs
+        .map(std::time::Duration::from_secs)
+        .unwrap_or(pause::DEFAULT_DRAIN_TIMEOUT);
+    pause::pause(&app, timeout, false).await
+}
+
+// Start a paused proxy again with the config it was paused with
+#[tauri::command]
+pub async fn resume_proxy(app: tauri::AppHandle) -> Result<ProxyStatus, CommandError> {
+    pause::resume(&app).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -884,6 +972,107 @@ mod tests {
         LOCK.get_or_init(|| std::sync::Mutex::new(()))
     }
 
+    #[test]
+    fn remote_management_requires_rotated_key() {
+        let remote = AppConfig {
+            management_allow_remote: true,
+            ..AppConfig::default()
+        };
+        let custom = "remote-management:\n  allow-remote: true\n";
+        assert!(check_remote_management(&remote, None).is_err());
+        assert!(check_remote_management(&AppConfig::default(), Some(custom)).is_err());
+        assert!(check_remote_management(&AppConfig::default(), None).is_ok());
+
+        let rotated = AppConfig {
+            management_key: "rotated-key-1234".to_string(),
+            proxy_bind: "0.0.0.0".to_string(),
+            ..remote
+        };
+        assert!(check_remote_management(&rotated, Some(custom)).is_ok());
+        assert_eq!(management_warnings(&rotated, None).len(), 1);
+        assert!(management_warnings(&AppConfig::default(), None).is_empty());
+        assert_eq!(
+            management_warnings(&AppConfig::default(), Some(custom)).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn generated_config_keeps_management_local_by_default() {
+        let dir = std::env::temp_dir().join(format!("proxypal-yaml-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let yaml = build_proxy_config_yaml(&AppConfig::default(), &dir, &dir).unwrap();
+        assert!(yaml.contains("host: \"\""));
+        assert!(yaml.contains("allow-remote: false"));
+        assert!(yaml.contains("restrict-management-to-localhost: true"));
+        assert!(!yaml_allows_remote_management(&yaml));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn management_port_keeps_the_proxy_port_management_free() {
+        let dir = std::env::temp_dir().join(format!("proxypal-yaml-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = AppConfig {
+            management_allow_remote: true,
+            management_key: "rotated-key-1234".to_string(),
+            management_bind: "0.0.0.0".to_string(),
+            proxy_bind: "0.0.0.0".to_string(),
+            management_port: Some(8318),
+            ..AppConfig::default()
+        };
+        let yaml = build_proxy_config_yaml(&config, &dir, &dir).unwrap();
+        assert!(yaml.contains("host: \"0.0.0.0\""));
+        assert!(!yaml_allows_remote_management(&yaml));
+        assert!(yaml.contains("restrict-management-to-localhost: true"));
+        assert!(management_warnings(&config, None)[0].contains("(port 8318)"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn key_only_providers_become_openai_compatible_entries() {
+        use crate::types::OpenAICompatibleApiKeyEntry;
+
+        let dir = std::env::temp_dir().join(format!("proxypal-yaml-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key = |api_key: &str, proxy_url: Option<&str>| OpenAICompatibleApiKeyEntry {
+            api_key: api_key.to_string(),
+            proxy_url: proxy_url.map(str::to_string),
+        };
+        let config = AppConfig {
+            deepseek_api_keys: vec![
+                key("sk-deepseek-1", None),
+                key("sk-deepseek-2", Some("socks5://127.0.0.1:1080")),
+            ],
+            ..AppConfig::default()
+        };
+        let yaml = build_proxy_config_yaml(&config, &dir, &dir).unwrap();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        let entries = parsed["openai-compatibility"].as_sequence().unwrap();
+        assert_eq!(entries.len(), 1);
+        let deepseek = &entries[0];
+        assert_eq!(deepseek["name"].as_str(), Some("deepseek"));
+        assert_eq!(
+            deepseek["base-url"].as_str(),
+            Some("https://api.deepseek.com/v1")
+        );
+        let keys = deepseek["api-key-entries"].as_sequence().unwrap();
+        assert_eq!(keys[1]["api-key"].as_str(), Some("sk-deepseek-2"));
+        assert_eq!(
+            keys[1]["proxy-url"].as_str(),
+            Some("socks5://127.0.0.1:1080")
+        );
+        assert_eq!(
+            deepseek["models"][0]["name"].as_str(),
+            Some("deepseek-chat")
+        );
+
+        // Without keys the provider is left out
+        let yaml = build_proxy_config_yaml(&AppConfig::default(), &dir, &dir).unwrap();
+        assert!(!yaml.contains("openai-compatibility:"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn env_proxy_for_url_returns_none_for_invalid_target() {
         assert!(env_proxy_for_url("not-a-url").is_none());
@@ -891,7 +1080,7 @@ mod tests {
 
     #[test]
     fn env_proxy_for_url_returns_none_when_env_is_missing() {
-        let _guard = env_var_lock().lock().unwrap();
+        let _guard = env_var_lock().lock().unwrap_or_else(|e| e.into_inner());
         let old_http_upper = std::env::var_os("HTTP_PROXY");
         let old_https_upper = std::env::var_os("HTTPS_PROXY");
         let old_http_lower = std::env::var_os("http_proxy");
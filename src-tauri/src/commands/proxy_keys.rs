@@ -0,0 +1,694 @@
+//! Proxy API key rotation.
+//!
+//! The proxy API key is copied into agent configs, `~/.continue/config.yaml`
+//! and shell profiles. `rotate_proxy_api_key` replaces it everywhere ProxyPal
+//! wrote it. CLIProxyAPI accepts a list of keys, so the old key stays valid for
+//! a grace period and sessions already running keep working.
+//!
+//! Client keys are extra keys issued to one agent or machine. Each can be
+//! revoked on its own; usage per key comes from the Management API, which
+//! groups its stats by the key a request was made with.
+
+use std::collections::HashMap;
+
+use reqwest::Method;
+use serde_json::Value;
+use tauri::{Manager, State};
+
+use crate::config::AppConfig;
+use crate::error::CommandError;
+use crate::http::{management_json, send_management, HttpClients};
+use crate::state::AppState;
+use crate::types::{
+    ClientKey, ClientKeyInfo, ClientKeyRevocation, KeyRotationReport, KeyRotationTarget,
+    RetiredProxyKey,
+};
+
+fn now_millis() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+fn generate_proxy_api_key() -> String {
+    format!("pp-{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Keys the proxy accepts: the current one, retired keys not yet expired and
+/// client keys
+pub(crate) fn active_proxy_api_keys(config: &AppConfig, now: u64) -> Vec<String> {
+    let mut keys = vec![config.proxy_api_key.clone()];
+    for retired in &config.retired_proxy_api_keys {
+        if retired.expires_at > now && !keys.contains(&retired.key) {
+            keys.push(retired.key.clone());
+        }
+    }
+    for client in &config.client_keys {
+        if !keys.contains(&client.key) {
+            keys.push(client.key.clone());
+        }
+    }
+    keys
+}
+
+/// The key an agent's config should carry: its own client key if it has one
+pub(crate) fn agent_api_key(config: &AppConfig, agent_id: &str) -> String {
+    config
+        .client_keys
+        .iter()
+        .find(|k| k.agent_id.as_deref() == Some(agent_id))
+        .map(|k| k.key.clone())
+        .unwrap_or_else(|| config.proxy_api_key.clone())
+}
+
+/// Make `new_key` current. The old key is retired for `grace_minutes`, or
+/// dropped at once when that is 0. Returns when it expires.
+fn retire_current_key(
+    config: &mut AppConfig,
+    new_key: String,
+    grace_minutes: u64,
+    now: u64,
+) -> Option<u64> {
+    config.retired_proxy_api_keys.retain(|k| k.expires_at > now);
+    let old_key = std::mem::replace(&mut config.proxy_api_key, new_key);
+    if grace_minutes == 0 {
+        return None;
+    }
+    let expires_at = now + grace_minutes * 60_000;
+    config.retired_proxy_api_keys.push(RetiredProxyKey {
+        key: old_key,
+        expires_at,
+    });
+    Some(expires_at)
+}
+
+/// Replace every occurrence of `old_key`. None if it doesn't occur.
+fn replace_key(text: &str, old_key: &str, new_key: &str) -> Option<String> {
+    (!old_key.is_empty() && text.contains(old_key)).then(|| text.replace(old_key, new_key))
+}
+
+/// Send the accepted key list to the running proxy
+async fn push_api_keys(http: &HttpClients, port: u16, keys: &[String]) -> Result<(), String> {
+    let request = http.management(Method::PUT, port, "api-keys").json(keys);
+    send_management(request)
+        .await
+        .map_err(|e| format!("Failed to update proxy API keys: {}", e))?;
+    Ok(())
+}
+
+/// Bring the running proxy's key list in line with the config. Falls back to a
+/// restart (which regenerates proxy-config.yaml) when the Management API refuses.
+pub(crate) async fn apply_keys_to_proxy(
+    app: &tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> String {
+    let (running, port) = {
+        let status = state.proxy_status.lock();
+        (status.running, status.port)
+    };
+    if !running {
+        return "notRunning".to_string();
+    }
+    let keys = active_proxy_api_keys(&state.config.lock(), now_millis());
+    match push_api_keys(&state.http, port, &keys).await {
+        Ok(()) => return "hot".to_string(),
+        Err(e) => eprintln!("[ProxyPal] {}; restarting the proxy instead", e),
+    }
+    let restarted = match crate::commands::proxy::stop_proxy_now(app.clone(), state.clone()).await {
+        Ok(_) => crate::commands::proxy::start_proxy_now(app.clone(), state)
+            .await
+            .is_ok(),
+        Err(_) => false,
+    };
+    let outcome = if restarted { "restarted" } else { "failed" };
+    outcome.to_string()
+}
+
+/// Drop retired keys once they expire, in this session
+pub(crate) fn schedule_key_expiry(app: tauri::AppHandle, expires_at: u64) {
+    tauri::async_runtime::spawn(async move {
+        let wait = expires_at.saturating_sub(now_millis());
+        tokio::time::sleep(std::time::Duration::from_millis(wait)).await;
+
+        let state = app.state::<AppState>();
+        let now = now_millis();
+        let pruned = {
+            let config = state.config.lock();
+            if !config
+                .retired_proxy_api_keys
+                .iter()
+                .any(|k| k.expires_at <= now)
+            {
+                return;
+            }
+            let mut pruned = config.clone();
+            pruned.retired_proxy_api_keys.retain(|k| k.expires_at > now);
+            pruned
+        };
+        if let Err(e) = crate::commands::config::store_config(&state, pruned) {
+            eprintln!("[ProxyPal] Failed to drop expired proxy API keys: {}", e);
+            return;
+        }
+        println!("[ProxyPal] Rotated-out proxy API key expired");
+        apply_keys_to_proxy(&app, state).await;
+    });
+}
+
+/// Schedule expiry of keys retired in an earlier session
+pub(crate) fn schedule_pending_expiries(app: &tauri::AppHandle) {
+    let pending: Vec<u64> = app
+        .state::<AppState>()
+        .config
+        .lock()
+        .retired_proxy_api_keys
+        .iter()
+        .map(|k| k.expires_at)
+        .collect();
+    for expires_at in pending {
+        schedule_key_expiry(app.clone(), expires_at);
+    }
+}
+
+fn target(id: &str, name: &str, status: &str, detail: Option<String>) -> KeyRotationTarget {
+    KeyRotationTarget {
+        id: id.to_string(),
+        name: name.to_string(),
+        status: status.to_string(),
+        detail,
+    }
+}
+
+/// Swap the key inside a file ProxyPal appended to (Continue config, shell profile)
+fn rotate_in_file(
+    id: &str,
+    name: &str,
+    path: &std::path::Path,
+    old_key: &str,
+    new_key: &str,
+) -> Option<KeyRotationTarget> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let updated = replace_key(&content, old_key, new_key)?;
+    let result = crate::helpers::config_writer::ConfigWriter::new(false).write(path, &updated);
+    Some(match result {
+        Ok(()) => target(
+            id,
+            name,
+            "updated",
+            Some(path.to_string_lossy().to_string()),
+        ),
+        Err(e) => target(id, name, "failed", Some(e)),
+    })
+}
+
+/// Rewrite the configs of agents that point at ProxyPal, then the files
+/// ProxyPal appended the key to. Agents that only read the key from the
+/// environment are reported for manual action.
+async fn reconfigure_agents(
+    app: &tauri::AppHandle,
+    state: State<'_, AppState>,
+    old_key: &str,
+    new_key: &str,
+) -> Vec<KeyRotationTarget> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let mut targets = Vec::new();
+
+    // Rewriting the profile and detecting agents read and write files
+    let (profile, agents) = {
+        let handle = app.clone();
+        let home = home.clone();
+        let (old_key, new_key) = (old_key.to_string(), new_key.to_string());
+        tauri::async_runtime::spawn_blocking(move || {
+            let profile = crate::commands::agents::get_shell_profile_path()
+                .ok()
+                .and_then(|path| {
+                    let path = std::path::Path::new(&path);
+                    rotate_in_file("shell-profile", "Shell profile", path, &old_key, &new_key)
+                });
+            let agents: Vec<_> =
+                crate::commands::agents::detect_cli_agents(handle.state::<AppState>())
+                    .into_iter()
+                    .map(|agent| {
+                        // Claude Code is detected through its env vars but configured
+                        // through settings.json
+                        let claude_settings_has_key = agent.id == "claude-code"
+                            && std::fs::read_to_string(home.join(".claude/settings.json"))
+                                .map(|c| c.contains(&old_key))
+                                .unwrap_or(false);
+                        (agent, claude_settings_has_key)
+                    })
+                    .collect();
+            (profile, agents)
+        })
+        .await
+        .unwrap_or_default()
+    };
+    let profile_updated = profile.as_ref().is_some_and(|t| t.status == "updated");
+    let env_detail = |var: &str| {
+        Some(if profile_updated {
+            format!("{} is set in your shell profile; open a new terminal", var)
+        } else {
+            format!("Set {} to the new key wherever you export it", var)
+        })
+    };
+
+    for (agent, claude_settings_has_key) in agents {
+        if !agent.configured && !claude_settings_has_key {
+            continue;
+        }
+        let entry = match agent.id.as_str() {
+            "claude-code" if !claude_settings_has_key => target(
+                &agent.id,
+                &agent.name,
+                "manual",
+                env_detail("ANTHROPIC_AUTH_TOKEN"),
+            ),
+            "gemini-cli" => target(
+                &agent.id,
+                &agent.name,
+                "manual",
+                env_detail("GEMINI_API_KEY"),
+            ),
+            // Goose keeps the key in the keyring or environment, never in config.yaml
+            "goose" => target(
+                &agent.id,
+                &agent.name,
+                "manual",
+                env_detail("OPENAI_API_KEY"),
+            ),
+            id => {
+                let result = crate::commands::agents::write_cli_agent_config(
+                    state.clone(),
+                    id.to_string(),
+                    Vec::new(),
+                    None,
+                )
+                .await;
+                match result {
+                    Ok(value) if value["configType"] == "env" => target(
+                        &agent.id,
+                        &agent.name,
+                        "manual",
+                        env_detail("OPENAI_API_KEY"),
+                    ),
+                    Ok(value) => target(
+                        &agent.id,
+                        &agent.name,
+                        "updated",
+                        value["configPath"].as_str().map(String::from),
+                    ),
+                    Err(e) => target(&agent.id, &agent.name, "failed", Some(e)),
+                }
+            }
+        };
+        targets.push(entry);
+    }
+
+    let (old_key, new_key) = (old_key.to_string(), new_key.to_string());
+    let continue_config = tauri::async_runtime::spawn_blocking(move || {
+        rotate_in_file(
+            "continue",
+            "Continue",
+            &home.join(".continue/config.yaml"),
+            &old_key,
+            &new_key,
+        )
+    })
+    .await
+    .ok()
+    .flatten();
+    targets.extend(continue_config);
+    targets.extend(profile);
+    targets
+}
+
+/// Replace the proxy API key with a random one and update every agent and tool
+/// configured with the old key. The old key keeps working for `grace_minutes`
+/// (the `proxyKeyGraceMinutes` setting when omitted).
+#[tauri::command]
+pub async fn rotate_proxy_api_key(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    grace_minutes: Option<u64>,
+) -> Result<KeyRotationReport, CommandError> {
+    let old_config = state.config.lock().clone();
+    let mut config = old_config.clone();
+    let new_key = generate_proxy_api_key();
+    let grace = grace_minutes.unwrap_or(config.proxy_key_grace_minutes);
+    let expires_at = retire_current_key(&mut config, new_key.clone(), grace, now_millis());
+
+    let changes = crate::helpers::audit::config_changes(&old_config, &config);
+    let stored = crate::commands::config::store_config(&state, config).map_err(CommandError::io);
+    crate::helpers::audit::record("rotate_proxy_api_key", changes, &stored);
+    stored?;
+
+    let proxy = apply_keys_to_proxy(&app, state.clone()).await;
+    if let Some(expires_at) = expires_at {
+        schedule_key_expiry(app.clone(), expires_at);
+    }
+    let targets = reconfigure_agents(&app, state, &old_config.proxy_api_key, &new_key).await;
+
+    Ok(KeyRotationReport {
+        api_key: new_key,
+        previous_key_expires_at: expires_at,
+        proxy,
+        targets,
+    })
+}
+
+fn key_preview(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 10 {
+        return crate::redact::MASK.to_string();
+    }
+    let head: String = chars[..5].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+fn client_key_info(key: &ClientKey) -> ClientKeyInfo {
+    ClientKeyInfo {
+        id: key.id.clone(),
+        name: key.name.clone(),
+        key_preview: key_preview(&key.key),
+        agent_id: key.agent_id.clone(),
+        created_at: key.created_at,
+        last_used_at: key.last_used_at,
+        request_count: key.request_count,
+    }
+}
+
+fn detail_timestamp(detail: &Value) -> Option<u64> {
+    match detail.get("timestamp")? {
+        Value::String(s) => chrono::DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|dt| dt.timestamp_millis() as u64),
+        Value::Number(n) => n.as_u64(),
+        _ => None,
+    }
+}
+
+/// (requests, last request time) per API key from a `/v0/management/usage`
+/// response: `{ "usage": { "apis": { "<key>": { "total_requests": N, "models":
+/// { "<model>": { "details": [{ "timestamp": ... }] } } } } } }`
+fn usage_by_key(response: &Value) -> HashMap<String, (u64, Option<u64>)> {
+    let usage = response.get("usage").unwrap_or(response);
+    let mut by_key = HashMap::new();
+    let Some(apis) = usage.get("apis").and_then(|v| v.as_object()) else {
+        return by_key;
+    };
+    for (key, api) in apis {
+        let mut requests = 0;
+        let mut last_used = None;
+        if let Some(models) = api.get("models").and_then(|v| v.as_object()) {
+            for model in models.values() {
+                requests += model
+                    .get("total_requests")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0);
+                let details = model.get("details").and_then(|v| v.as_array());
+                for detail in details.into_iter().flatten() {
+                    last_used = last_used.max(detail_timestamp(detail));
+                }
+            }
+        }
+        let requests = api
+            .get("total_requests")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(requests);
+        by_key.insert(key.clone(), (requests, last_used));
+    }
+    by_key
+}
+
+/// Fold the proxy's counters into a key's totals. The proxy counts from 0
+/// again after a restart, so a lower counter than last time is all new.
+fn apply_key_usage(key: &mut ClientKey, requests: u64, last_used: Option<u64>) {
+    let new_requests = if requests >= key.synced_requests {
+        requests - key.synced_requests
+    } else {
+        requests
+    };
+    key.request_count += new_requests;
+    key.synced_requests = requests;
+    key.last_used_at = key.last_used_at.max(last_used);
+}
+
+async fn fetch_key_usage(
+    http: &HttpClients,
+    port: u16,
+) -> Result<HashMap<String, (u64, Option<u64>)>, String> {
+    let request = http.management(Method::GET, port, "usage");
+    let body: Value = management_json(request)
+        .await
+        .map_err(|e| format!("Failed to fetch usage: {}", e))?;
+    Ok(usage_by_key(&body))
+}
+
+/// Client keys with their usage, refreshed from the running proxy
+#[tauri::command]
+pub async fn list_client_keys(
+    state: State<'_, AppState>,
+) -> Result<Vec<ClientKeyInfo>, CommandError> {
+    let (running, port) = {
+        let status = state.proxy_status.lock();
+        (status.running, status.port)
+    };
+    if running && !state.config.lock().client_keys.is_empty() {
+        match fetch_key_usage(&state.http, port).await {
+            Ok(usage) => {
+                let updated = {
+                    let mut config = state.config.lock().clone();
+                    let before = config.client_keys.clone();
+                    for key in config.client_keys.iter_mut() {
+                        if let Some((requests, last_used)) = usage.get(&key.key) {
+                            apply_key_usage(key, *requests, *last_used);
+                        }
+                    }
+                    (config.client_keys != before).then_some(config)
+                };
+                if let Some(config) = updated {
+                    crate::commands::config::store_config(&state, config)
+                        .map_err(CommandError::io)?;
+                }
+            }
+            Err(e) => eprintln!("[ProxyPal] {}; showing the last known key usage", e),
+        }
+    }
+    let config = state.config.lock();
+    Ok(config.client_keys.iter().map(client_key_info).collect())
+}
+
+/// A fresh client key with a newly generated value
+pub(crate) fn new_client_key(name: String, agent_id: Option<String>) -> ClientKey {
+    ClientKey {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        key: generate_proxy_api_key(),
+        agent_id: agent_id.filter(|id| !id.is_empty()),
+        created_at: now_millis(),
+        last_used_at: None,
+        request_count: 0,
+        synced_requests: 0,
+    }
+}
+
+/// Issue a new client key, optionally for one agent. The full key is only
+/// returned here.
+#[tauri::command]
+pub async fn create_client_key(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+    agent_id: Option<String>,
+) -> Result<ClientKey, CommandError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(CommandError::invalid_input(
+            "Client key name cannot be empty",
+        ));
+    }
+    let key = new_client_key(name, agent_id);
+    let config = {
+        let mut config = state.config.lock().clone();
+        if let Some(agent_id) = &key.agent_id {
+            if config
+                .client_keys
+                .iter()
+                .any(|k| k.agent_id.as_ref() == Some(agent_id))
+            {
+                return Err(CommandError::invalid_input(format!(
+                    "{} already has a client key",
+                    agent_id
+                )));
+            }
+        }
+        config.client_keys.push(key.clone());
+        config
+    };
+    let changes = vec![format!("clientKeys: {} added", key.name)];
+    let stored = crate::commands::config::store_config(&state, config).map_err(CommandError::io);
+    crate::helpers::audit::record("create_client_key", changes, &stored);
+    stored?;
+    apply_keys_to_proxy(&app, state).await;
+    Ok(key)
+}
+
+/// Agents whose written config still contains `key`
+fn agents_using_key(state: &State<'_, AppState>, key: &ClientKey) -> Vec<String> {
+    let mut agents: Vec<String> = key.agent_id.iter().cloned().collect();
+    for agent in crate::commands::agents::detect_cli_agents(state.clone()) {
+        let holds_key = agent
+            .config_path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .is_some_and(|content| content.contains(&key.key));
+        if holds_key && !agents.contains(&agent.id) {
+            agents.push(agent.id);
+        }
+    }
+    agents
+}
+
+/// Remove one client key. The proxy stops accepting it right away (hot when
+/// the Management API allows) and agents still configured with it are
+/// flagged stale.
+#[tauri::command]
+pub async fn revoke_client_key(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<ClientKeyRevocation, CommandError> {
+    let key = state
+        .config
+        .lock()
+        .client_keys
+        .iter()
+        .find(|k| k.id == id)
+        .cloned()
+        .ok_or_else(|| CommandError::not_found(format!("Client key not found: {}", id)))?;
+    let stale_agents = agents_using_key(&state, &key);
+
+    let config = {
+        let mut config = state.config.lock().clone();
+        config.client_keys.retain(|k| k.id != id);
+        for agent in &stale_agents {
+            if !config.stale_agent_configs.contains(agent) {
+                config.stale_agent_configs.push(agent.clone());
+            }
+        }
+        config
+    };
+    let changes = vec![format!("clientKeys: {} revoked", key.name)];
+    let stored = crate::commands::config::store_config(&state, config).map_err(CommandError::io);
+    crate::helpers::audit::record("revoke_client_key", changes, &stored);
+    stored?;
+
+    let proxy = apply_keys_to_proxy(&app, state).await;
+    Ok(ClientKeyRevocation {
+        id,
+        proxy,
+        stale_agents,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retired_key_stays_active_until_it_expires() {
+        let mut config = AppConfig {
+            proxy_api_key: "old-key".to_string(),
+            retired_proxy_api_keys: vec![RetiredProxyKey {
+                key: "ancient-key".to_string(),
+                expires_at: 500,
+            }],
+            ..AppConfig::default()
+        };
+        let expires_at = retire_current_key(&mut config, "new-key".to_string(), 10, 1_000);
+        assert_eq!(expires_at, Some(601_000));
+        assert_eq!(config.proxy_api_key, "new-key");
+        // The already expired key is dropped on the way
+        assert_eq!(config.retired_proxy_api_keys.len(), 1);
+        assert_eq!(
+            active_proxy_api_keys(&config, 2_000),
+            vec!["new-key", "old-key"]
+        );
+        assert_eq!(active_proxy_api_keys(&config, 601_000), vec!["new-key"]);
+
+        assert_eq!(
+            retire_current_key(&mut config, "newer-key".to_string(), 0, 2_000),
+            None
+        );
+        assert_eq!(
+            active_proxy_api_keys(&config, 2_000),
+            vec!["newer-key", "old-key"]
+        );
+    }
+
+    #[test]
+    fn replaces_key_only_where_present() {
+        let profile = "export OPENAI_API_KEY=\"proxypal-local\"\nexport FOO=bar\n";
+        assert_eq!(
+            replace_key(profile, "proxypal-local", "pp-new").as_deref(),
+            Some("export OPENAI_API_KEY=\"pp-new\"\nexport FOO=bar\n")
+        );
+        assert_eq!(
+            replace_key("export FOO=bar\n", "proxypal-local", "pp-new"),
+            None
+        );
+        assert_eq!(replace_key(profile, "", "pp-new"), None);
+    }
+
+    fn client_key(key: &str) -> ClientKey {
+        ClientKey {
+            id: "laptop".to_string(),
+            name: "Old laptop".to_string(),
+            key: key.to_string(),
+            agent_id: Some("opencode".to_string()),
+            created_at: 0,
+            last_used_at: None,
+            request_count: 0,
+            synced_requests: 0,
+        }
+    }
+
+    #[test]
+    fn client_keys_are_accepted_and_used_by_their_agent() {
+        let config = AppConfig {
+            proxy_api_key: "main-key".to_string(),
+            client_keys: vec![client_key("pp-laptop")],
+            ..AppConfig::default()
+        };
+        assert_eq!(
+            active_proxy_api_keys(&config, 0),
+            vec!["main-key", "pp-laptop"]
+        );
+        assert_eq!(agent_api_key(&config, "opencode"), "pp-laptop");
+        assert_eq!(agent_api_key(&config, "crush"), "main-key");
+    }
+
+    #[test]
+    fn tracks_key_usage_across_proxy_restarts() {
+        let response = serde_json::json!({
+            "usage": { "apis": { "pp-laptop": { "total_requests": 3, "models": {
+                "gpt-5": { "total_requests": 3, "details": [
+                    { "timestamp": "2025-06-01T10:00:00Z" },
+                    { "timestamp": "2025-06-01T12:00:00Z" }
+                ] }
+            } } } }
+        });
+        let usage = usage_by_key(&response);
+        let (requests, last_used) = usage["pp-laptop"];
+        assert_eq!(requests, 3);
+        assert_eq!(last_used, Some(1_748_779_200_000));
+
+        let mut key = client_key("pp-laptop");
+        apply_key_usage(&mut key, requests, last_used);
+        apply_key_usage(&mut key, 5, None);
+        assert_eq!(key.request_count, 5);
+        assert_eq!(key.last_used_at, Some(1_748_779_200_000));
+        // After a restart the proxy counts from 0 again
+        apply_key_usage(&mut key, 2, Some(1_748_800_000_000));
+        assert_eq!(key.request_count, 7);
+        assert_eq!(key.last_used_at, Some(1_748_800_000_000));
+    }
+}
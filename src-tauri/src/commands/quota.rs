@@ -1,6 +1,7 @@
 //! Quota management - fetch quota/usage for all providers.
 
 use tauri::{Emitter, State};
+use crate::error::CommandError;
 use crate::state::AppState;
 use crate::types::{AuthStatus, ProviderTestResult};
 
@@ -47,19 +48,19 @@ async fn refresh_antigravity_token(client: &reqwest::Client, refresh_token: &str
 
 // Fetch Antigravity quota for all authenticated accounts
 #[tauri::command]
-pub async fn fetch_antigravity_quota() -> Result<Vec<crate::types::AntigravityQuotaResult>, String> {
+pub async fn fetch_antigravity_quota(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::types::AntigravityQuotaResult>, CommandError> {
     use crate::types::{AntigravityQuotaResult, ModelQuota, AntigravityModelsResponse};
     
-    let auth_dir = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".cli-proxy-api");
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
     
     if !auth_dir.exists() {
         return Ok(vec![]);
     }
     
     let mut results: Vec<AntigravityQuotaResult> = Vec::new();
-    let client = reqwest::Client::new();
+    let client = &state.http.external();
     
     // Scan for Antigravity auth files
     if let Ok(entries) = std::fs::read_dir(&auth_dir) {
@@ -122,7 +123,7 @@ pub async fn fetch_antigravity_quota() -> Result<Vec<crate::types::AntigravityQu
                 // Refresh token if expired
                 if is_expired {
                     if let Some(ref rt) = refresh_token {
-                        match refresh_antigravity_token(&client, rt).await {
+                        match refresh_antigravity_token(client, rt).await {
                             Ok(new_token) => {
                                 // Update auth file with new token
                                 if let Some(obj) = auth_json.as_object_mut() {
@@ -133,7 +134,9 @@ pub async fn fetch_antigravity_quota() -> Result<Vec<crate::types::AntigravityQu
                                     
                                     // Save updated auth file
                                     if let Ok(updated_content) = serde_json::to_string(&auth_json) {
-                                        let _ = std::fs::write(&file_path, updated_content);
+                                        if std::fs::write(&file_path, updated_content).is_ok() {
+                                            crate::helpers::permissions::restrict_after_write(&file_path);
+                                        }
                                     }
                                 }
                                 access_token = Some(new_token);
@@ -319,6 +322,9 @@ pub async fn fetch_antigravity_quota() -> Result<Vec<crate::types::AntigravityQu
     Ok(results)
 }
 
+// Per-attempt timeout for quota endpoints
+const QUOTA_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
 // Helper: HTTP GET with retry and exponential backoff
 // Retries on: timeout, connection errors, 5xx, 429
 // Does NOT retry on: client errors (4xx except 429)
@@ -333,7 +339,7 @@ async fn fetch_with_retry(
         if attempt > 0 {
             tokio::time::sleep(std::time::Duration::from_millis(500 * (1 << (attempt - 1)))).await;
         }
-        let mut req = client.get(url);
+        let mut req = client.get(url).timeout(QUOTA_REQUEST_TIMEOUT);
         for (key, value) in &headers {
             req = req.header(*key, value.as_str());
         }
@@ -356,9 +362,10 @@ async fn fetch_with_retry(
 // Fetch Codex/ChatGPT quota and usage for all authenticated accounts
 // Uses the ChatGPT internal API: https://chatgpt.com/backend-api/wham/usage
 #[tauri::command]
-pub async fn fetch_codex_quota() -> Result<Vec<crate::types::CodexQuotaResult>, String> {
-    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
-    let auth_dir = home.join(".cli-proxy-api");
+pub async fn fetch_codex_quota(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::types::CodexQuotaResult>, CommandError> {
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
     
     if !auth_dir.exists() {
         return Ok(vec![]);
@@ -366,7 +373,7 @@ pub async fn fetch_codex_quota() -> Result<Vec<crate::types::CodexQuotaResult>,
     
     // Find all codex-*.json files
     let entries = std::fs::read_dir(&auth_dir)
-        .map_err(|e| format!("Failed to read auth directory: {}", e))?;
+        .map_err(|e| CommandError::io(format!("Failed to read auth directory: {}", e)))?;
     
     // Phase 1: Collect credentials (sequential, fast I/O)
     struct CodexCred {
@@ -427,10 +434,7 @@ pub async fn fetch_codex_quota() -> Result<Vec<crate::types::CodexQuotaResult>,
     }
     
     // Phase 2: Fetch quotas in parallel with timeout
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .unwrap_or_default();
+    let client = state.http.external();
     
     let mut handles = Vec::new();
     for cred in credentials {
@@ -538,8 +542,10 @@ pub async fn fetch_codex_quota() -> Result<Vec<crate::types::CodexQuotaResult>,
 // - copilot-api: ~/.local/share/copilot-api/github_token (plain text)
 // - cli-proxy-api: ~/.cli-proxy-api/copilot-*.json
 #[tauri::command]
-pub async fn fetch_copilot_quota() -> Result<Vec<crate::types::CopilotQuotaResult>, String> {
-    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+pub async fn fetch_copilot_quota(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::types::CopilotQuotaResult>, CommandError> {
+    let home = dirs::home_dir().ok_or_else(|| CommandError::not_found("Could not determine home directory"))?;
     
     // Phase 1: Collect all tokens (sequential, fast I/O)
     struct CopilotCred {
@@ -573,10 +579,10 @@ pub async fn fetch_copilot_quota() -> Result<Vec<crate::types::CopilotQuotaResul
     }
     
     // Check for cli-proxy-api copilot credentials
-    let auth_dir = home.join(".cli-proxy-api");
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
     if auth_dir.exists() {
         let entries = std::fs::read_dir(&auth_dir)
-            .map_err(|e| format!("Failed to read auth directory: {}", e))?;
+            .map_err(|e| CommandError::io(format!("Failed to read auth directory: {}", e)))?;
         
         for entry in entries.flatten() {
             let filename = entry.file_name().to_string_lossy().to_string();
@@ -606,10 +612,12 @@ pub async fn fetch_copilot_quota() -> Result<Vec<crate::types::CopilotQuotaResul
     }
     
     // Phase 2: Fetch all quotas in parallel
+    let client = state.http.external();
     let mut handles = Vec::new();
     for cred in credentials {
+        let client = client.clone();
         handles.push(tokio::spawn(async move {
-            fetch_copilot_quota_with_token(&cred.token, &cred.login).await
+            fetch_copilot_quota_with_token(&client, &cred.token, &cred.login).await
         }));
     }
     
@@ -626,11 +634,11 @@ pub async fn fetch_copilot_quota() -> Result<Vec<crate::types::CopilotQuotaResul
 }
 
 // Helper function to fetch Copilot quota with a given token
-async fn fetch_copilot_quota_with_token(token: &str, login: &str) -> crate::types::CopilotQuotaResult {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .unwrap_or_default();
+async fn fetch_copilot_quota_with_token(
+    client: &reqwest::Client,
+    token: &str,
+    login: &str,
+) -> crate::types::CopilotQuotaResult {
     let url = "https://api.github.com/copilot_internal/user";
     
     let headers = vec![
@@ -642,7 +650,7 @@ async fn fetch_copilot_quota_with_token(token: &str, login: &str) -> crate::type
         ("X-Github-Api-Version", "2025-04-01".to_string()),
     ];
     
-    let response = fetch_with_retry(&client, url, headers, 2).await;
+    let response = fetch_with_retry(client, url, headers, 2).await;
     
     match response {
         Ok(resp) => {
@@ -721,7 +729,7 @@ async fn fetch_copilot_quota_with_token(token: &str, login: &str) -> crate::type
 }
 
 #[tauri::command]
-pub async fn fetch_kiro_quota() -> Result<Vec<crate::types::quota::KiroQuotaResult>, String> {
+pub async fn fetch_kiro_quota() -> Result<Vec<crate::types::quota::KiroQuotaResult>, CommandError> {
     use std::process::Command;
     use std::path::PathBuf;
     use regex::Regex;
@@ -925,8 +933,10 @@ pub async fn fetch_kiro_quota() -> Result<Vec<crate::types::quota::KiroQuotaResu
 // Uses the Anthropic OAuth API: https://api.anthropic.com/api/oauth/usage
 
 #[tauri::command]
-pub async fn fetch_claude_quota() -> Result<Vec<crate::types::quota::ClaudeQuotaResult>, String> {
-    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+pub async fn fetch_claude_quota(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::types::quota::ClaudeQuotaResult>, CommandError> {
+    let home = dirs::home_dir().ok_or_else(|| CommandError::not_found("Could not determine home directory"))?;
     
     let mut results: Vec<crate::types::ClaudeQuotaResult> = Vec::new();
     
@@ -994,10 +1004,7 @@ pub async fn fetch_claude_quota() -> Result<Vec<crate::types::quota::ClaudeQuota
         };
         
         // Fetch usage from Anthropic OAuth API
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .unwrap_or_default();
+        let client = state.http.external();
         let url = "https://api.anthropic.com/api/oauth/usage";
         
         let headers = vec![
@@ -1108,41 +1115,62 @@ pub async fn import_vertex_credential(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     file_path: String,
-) -> Result<AuthStatus, String> {
+) -> Result<AuthStatus, CommandError> {
+    let changes = vec![format!("file: {}", file_path)];
+    let result = copy_vertex_credential(app, state, &file_path);
+    crate::helpers::audit::record("import_vertex_credential", changes, &result);
+    result
+}
+
+fn copy_vertex_credential(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    file_path: &str,
+) -> Result<AuthStatus, CommandError> {
     // Read the service account JSON file
-    let content = std::fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let content = std::fs::read_to_string(file_path)
+        .map_err(|e| CommandError::io(format!("Failed to read file: {}", e)))?;
     
     // Parse to validate it's valid JSON with required fields
     let json: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Invalid JSON: {}", e))?;
+        .map_err(|e| CommandError::invalid_input(format!("Invalid JSON: {}", e)))?;
     
     // Check for required service account fields
     let project_id = json["project_id"]
         .as_str()
-        .ok_or("Missing 'project_id' field in service account JSON")?;
+        .ok_or_else(|| CommandError::invalid_input("Missing 'project_id' field in service account JSON"))?;
     
     if json["type"].as_str() != Some("service_account") {
-        return Err("Invalid service account: 'type' must be 'service_account'".to_string());
+        return Err(CommandError::invalid_input("Invalid service account: 'type' must be 'service_account'"));
     }
     
     // Copy to CLIProxyAPI auth directory
-    let auth_dir = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".cli-proxy-api");
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
     
-    std::fs::create_dir_all(&auth_dir).map_err(|e| e.to_string())?;
+    crate::helpers::permissions::create_private_dir(&auth_dir).map_err(|e| CommandError::io(e.to_string()))?;
     
+    // Keep a region chosen for this project before
+    let region = state.config.lock().vertex_regions.get(project_id).cloned();
+    let content = match region {
+        Some(region) => {
+            let mut json = json.clone();
+            crate::helpers::vertex::set_location(&mut json, Some(&region));
+            serde_json::to_string_pretty(&json).unwrap_or(content)
+        }
+        None => content,
+    };
+
     let dest_path = auth_dir.join(format!("vertex-{}.json", project_id));
     std::fs::write(&dest_path, &content)
-        .map_err(|e| format!("Failed to save credential: {}", e))?;
+        .map_err(|e| CommandError::io(format!("Failed to save credential: {}", e)))?;
+    crate::helpers::permissions::restrict_after_write(&dest_path);
     
     // Update auth status (increment count)
-    let mut auth = state.auth_status.lock().unwrap();
+    let mut auth = state.auth_status.lock();
     auth.vertex += 1;
     
     // Save to file
-    crate::save_auth_to_file(&auth)?;
+    crate::config::save_auth_to_file(&auth).map_err(CommandError::io)?;
     
     // Emit auth status update
     let _ = app.emit("auth-status-changed", auth.clone());
@@ -1154,7 +1182,7 @@ pub async fn import_vertex_credential(
 /// This runs `kiro-cli chat --no-interactive "/usage"` and interprets the result
 /// as a simple success/failure signal for the UI.
 #[tauri::command]
-pub async fn test_kiro_connection() -> Result<ProviderTestResult, String> {
+pub async fn test_kiro_connection() -> Result<ProviderTestResult, CommandError> {
     let start = std::time::Instant::now();
     let quota_result = fetch_kiro_quota().await;
     let latency = start.elapsed().as_millis() as u64;
@@ -0,0 +1,67 @@
+//! Provider soft rate limit commands.
+
+use tauri::State;
+
+use crate::config::save_config_to_file;
+use crate::error::CommandError;
+use crate::state::AppState;
+use crate::types::ProviderRateLimit;
+
+#[tauri::command]
+pub fn get_provider_rate_limits(state: State<AppState>) -> Vec<ProviderRateLimit> {
+    state.config.lock().provider_rate_limits.clone()
+}
+
+// Set the soft limit of a provider, replacing any it had. Counting starts
+// over from a full bucket.
+#[tauri::command]
+pub fn save_provider_rate_limit(
+    state: State<AppState>,
+    limit: ProviderRateLimit,
+) -> Result<Vec<ProviderRateLimit>, CommandError> {
+    let limit = crate::rate_limits::normalize(limit);
+    crate::rate_limits::validate(&limit)?;
+    let provider = limit.provider.clone();
+
+    let limits = {
+        let mut config = state.config.lock();
+        if let Some(existing) = config
+            .provider_rate_limits
+            .iter_mut()
+            .find(|l| l.provider == limit.provider)
+        {
+            *existing = limit;
+        } else {
+            config.provider_rate_limits.push(limit);
+        }
+        save_config_to_file(&config).map_err(CommandError::io)?;
+        config.provider_rate_limits.clone()
+    };
+    state.rate_limits.lock().forget(&provider);
+    Ok(limits)
+}
+
+#[tauri::command]
+pub fn delete_provider_rate_limit(
+    state: State<AppState>,
+    provider: String,
+) -> Result<Vec<ProviderRateLimit>, CommandError> {
+    let provider = provider.trim().to_lowercase();
+    let limits = {
+        let mut config = state.config.lock();
+        let before = config.provider_rate_limits.len();
+        config
+            .provider_rate_limits
+            .retain(|l| l.provider != provider);
+        if config.provider_rate_limits.len() == before {
+            return Err(CommandError::not_found(format!(
+                "No rate limit for provider {}",
+                provider
+            )));
+        }
+        save_config_to_file(&config).map_err(CommandError::io)?;
+        config.provider_rate_limits.clone()
+    };
+    state.rate_limits.lock().forget(&provider);
+    Ok(limits)
+}
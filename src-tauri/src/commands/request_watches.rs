@@ -0,0 +1,58 @@
+//! Request watch commands.
+
+use tauri::State;
+
+use crate::config::save_config_to_file;
+use crate::error::CommandError;
+use crate::state::AppState;
+use crate::types::{RequestWatch, RequestWatchFilter};
+
+// Start reporting requests that match `filter` as `request-watch-hit`
+// events; `notify` also shows desktop notifications for them
+#[tauri::command]
+pub fn create_request_watch(
+    state: State<AppState>,
+    filter: RequestWatchFilter,
+    notify: bool,
+) -> Result<RequestWatch, CommandError> {
+    let filter = crate::request_watches::normalize(filter);
+    crate::request_watches::validate_filter(&filter)?;
+    let watch = RequestWatch {
+        id: uuid::Uuid::new_v4().to_string(),
+        filter,
+        notify,
+        created_at: chrono::Utc::now().timestamp_millis() as u64,
+    };
+
+    let mut config = state.config.lock();
+    config.request_watches.push(watch.clone());
+    save_config_to_file(&config).map_err(CommandError::io)?;
+    Ok(watch)
+}
+
+#[tauri::command]
+pub fn list_request_watches(state: State<AppState>) -> Vec<RequestWatch> {
+    state.config.lock().request_watches.clone()
+}
+
+#[tauri::command]
+pub fn delete_request_watch(
+    state: State<AppState>,
+    id: String,
+) -> Result<Vec<RequestWatch>, CommandError> {
+    let watches = {
+        let mut config = state.config.lock();
+        let before = config.request_watches.len();
+        config.request_watches.retain(|w| w.id != id);
+        if config.request_watches.len() == before {
+            return Err(CommandError::not_found(format!(
+                "No request watch with id {}",
+                id
+            )));
+        }
+        save_config_to_file(&config).map_err(CommandError::io)?;
+        config.request_watches.clone()
+    };
+    state.request_watches.lock().forget(&id);
+    Ok(watches)
+}
@@ -0,0 +1,39 @@
+//! Weekly proxy schedule commands.
+
+use tauri::{AppHandle, State};
+
+use crate::config::save_config_to_file;
+use crate::error::CommandError;
+use crate::state::AppState;
+use crate::types::{ProxySchedule, ScheduleStatus};
+
+#[tauri::command]
+pub fn get_proxy_schedule(state: State<AppState>) -> Option<ProxySchedule> {
+    state.config.lock().schedule.clone()
+}
+
+// Replace the schedule (None removes it). Refused if windows overlap or a
+// time doesn't parse. Takes effect at once, dropping any manual override.
+#[tauri::command]
+pub fn set_proxy_schedule(
+    app: AppHandle,
+    state: State<AppState>,
+    schedule: Option<ProxySchedule>,
+) -> Result<Option<ProxySchedule>, CommandError> {
+    if let Some(schedule) = &schedule {
+        crate::scheduler::validate_schedule(schedule).map_err(CommandError::invalid_input)?;
+    }
+    {
+        let mut config = state.config.lock();
+        config.schedule = schedule.clone();
+        save_config_to_file(&config).map_err(CommandError::io)?;
+    }
+    crate::scheduler::reschedule(&state);
+    crate::tray::refresh_tray_tooltip(&app);
+    Ok(schedule)
+}
+
+#[tauri::command]
+pub fn get_schedule_status(state: State<AppState>) -> ScheduleStatus {
+    crate::scheduler::status(&state)
+}
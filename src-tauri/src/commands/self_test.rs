@@ -0,0 +1,254 @@
+//! End-to-end self-test.
+//!
+//! Runs the checklist in `helpers::self_test` with the same probes the rest of
+//! the app uses, emitting `self-test-step` after each step so the dashboard can
+//! fill in the checklist as it goes.
+
+use std::time::Instant;
+
+use reqwest::Method;
+use tauri::{Emitter, State};
+use tokio::sync::watch;
+
+use crate::error::CommandError;
+use crate::helpers::self_test::{self, Checklist, StepOutcome, STEPS};
+use crate::http::{send_management, ManagementError};
+use crate::proxy::lifecycle;
+use crate::state::AppState;
+use crate::types::SelfTestReport;
+
+// Steps left to finish when cancelled: stopping a proxy start halfway would
+// leave a sidecar running that ProxyPal does not track
+const UNINTERRUPTIBLE: &[&str] = &["proxy"];
+
+/// Resolves once the run is cancelled
+async fn cancelled(cancel: &mut watch::Receiver<bool>) {
+    loop {
+        let is_cancelled = *cancel.borrow_and_update();
+        if is_cancelled {
+            return;
+        }
+        if cancel.changed().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+async fn run_step(id: &str, app: &tauri::AppHandle, state: &State<'_, AppState>) -> StepOutcome {
+    let port = state.config.lock().port;
+    match id {
+        "config_dir" => self_test::check_config_dir(&crate::config::get_proxypal_config_dir()),
+        "sidecar" => match lifecycle::resolve_sidecar_binary().await {
+            Ok(binary) => {
+                let version = lifecycle::binary_version(&binary.path)
+                    .await
+                    .map(|(a, b, c)| format!("{}.{}.{}", a, b, c));
+                match (version, binary.warning) {
+                    (Some(version), None) => {
+                        StepOutcome::Pass(format!("{} ({})", binary.path.display(), version))
+                    }
+                    (Some(_), Some(warning)) => StepOutcome::fail(
+                        warning,
+                        "Update CLIProxyAPI from Settings, or remove the outdated copy from PATH",
+                    ),
+                    (None, _) => StepOutcome::fail(
+                        format!("{} did not report a version", binary.path.display()),
+                        "The binary may be damaged or blocked by antivirus; update CLIProxyAPI from Settings",
+                    ),
+                }
+            }
+            Err(e) => StepOutcome::fail(
+                e,
+                "Reinstall ProxyPal, or update CLIProxyAPI from Settings to download it",
+            ),
+        },
+        "port" => {
+            if state.proxy_status.lock().running {
+                StepOutcome::Pass(format!("Port {} is held by ProxyPal's proxy", port))
+            } else {
+                self_test::check_port(port)
+            }
+        }
+        "proxy" => {
+            let already_running = state.proxy_status.lock().running;
+            match crate::commands::proxy::start_proxy(app.clone(), state.clone(), None).await {
+                Ok(status) if already_running => {
+                    StepOutcome::Pass(format!("Already running on port {}", status.port))
+                }
+                Ok(status) => StepOutcome::Pass(format!("Started on port {}", status.port)),
+                Err(e) => StepOutcome::fail(
+                    e.message,
+                    "Check the proxy log in the Logs tab for why the sidecar exited",
+                ),
+            }
+        }
+        "management" => {
+            match send_management(state.http.management(Method::GET, port, "config.yaml")).await {
+                Ok(_) => StepOutcome::Pass("Authorized".to_string()),
+                Err(ManagementError::Status { status, .. })
+                    if status == reqwest::StatusCode::UNAUTHORIZED
+                        || status == reqwest::StatusCode::FORBIDDEN =>
+                {
+                    StepOutcome::fail(
+                        format!("The proxy rejected the management key ({})", status),
+                        "Restart the proxy so it picks up ProxyPal's current management key",
+                    )
+                }
+                Err(e) => StepOutcome::fail(
+                    e.to_string(),
+                    "Restart the proxy; if this keeps failing, check the proxy log",
+                ),
+            }
+        }
+        "credentials" => {
+            let accounts = state.auth_status.lock().clone();
+            let api_keys = {
+                let config = state.config.lock();
+                config.claude_api_keys.len()
+                    + config.gemini_api_keys.len()
+                    + config.codex_api_keys.len()
+                    + config.vertex_api_keys.len()
+            };
+            let copilot = state.config.lock().copilot.enabled;
+            if accounts.any_connected() || api_keys > 0 || copilot {
+                let connected: Vec<String> = crate::providers::ids()
+                    .filter(|p| accounts.count(p) > 0)
+                    .map(|p| format!("{} ({})", p, accounts.count(p)))
+                    .collect();
+                let mut detail = connected.join(", ");
+                if api_keys > 0 {
+                    if !detail.is_empty() {
+                        detail.push_str(", ");
+                    }
+                    detail.push_str(&format!("{} API key(s)", api_keys));
+                }
+                if copilot {
+                    if !detail.is_empty() {
+                        detail.push_str(", ");
+                    }
+                    detail.push_str("GitHub Copilot");
+                }
+                StepOutcome::Pass(detail)
+            } else {
+                StepOutcome::fail(
+                    "No provider accounts or API keys",
+                    "Connect a provider on the dashboard or add an API key in Settings",
+                )
+            }
+        }
+        "completion" => {
+            let models =
+                match crate::commands::models::get_available_models(app.clone(), state.clone())
+                    .await
+                {
+                    Ok(models) => models,
+                    Err(e) => {
+                        return StepOutcome::fail(e, "Restart the proxy and run the self-test again")
+                    }
+                };
+            let Some(model) = models.into_iter().find(|m| m.connected) else {
+                return StepOutcome::fail(
+                    "The proxy lists no models",
+                    "Reconnect your accounts; their tokens may have expired",
+                );
+            };
+            match crate::commands::models::test_provider_connection(model.id.clone(), state.clone())
+                .await
+            {
+                Ok(result) if result.success => StepOutcome::Pass(format!(
+                    "{} answered in {} ms",
+                    model.id,
+                    result.latency_ms.unwrap_or(0)
+                )),
+                Ok(result) => StepOutcome::fail(
+                    format!("{}: {}", model.id, crate::redact::redact(&result.message)),
+                    "Check the provider's status on the dashboard; reconnect the account if its token expired",
+                ),
+                Err(e) => StepOutcome::fail(e, "Restart the proxy and run the self-test again"),
+            }
+        }
+        "agents" => {
+            let agents: Vec<_> = crate::commands::agents::detect_cli_agents(state.clone())
+                .into_iter()
+                .filter(|a| a.installed)
+                .collect();
+            if agents.is_empty() {
+                StepOutcome::fail(
+                    "No supported coding agent found",
+                    "Install an agent such as Claude Code, Codex or OpenCode, or point any OpenAI-compatible tool at the proxy",
+                )
+            } else {
+                let names: Vec<String> = agents
+                    .iter()
+                    .map(|a| {
+                        if a.configured {
+                            format!("{} (configured)", a.name)
+                        } else {
+                            a.name.clone()
+                        }
+                    })
+                    .collect();
+                StepOutcome::Pass(names.join(", "))
+            }
+        }
+        other => StepOutcome::fail(format!("Unknown step {}", other), "Update ProxyPal"),
+    }
+}
+
+// Check the whole chain from config folder to agent, in order. Starts the proxy
+// if it is stopped. Each finished step is also emitted as `self-test-step`.
+#[tauri::command]
+pub async fn run_self_test(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SelfTestReport, CommandError> {
+    state.ensure_hydrated()?;
+    let (sender, mut cancel) = watch::channel(false);
+    // A new run replaces one still in progress
+    if let Some(previous) = state.self_test.lock().replace(sender) {
+        let _ = previous.send(true);
+    }
+
+    let mut checklist = Checklist::default();
+    let mut was_cancelled = false;
+    for step in STEPS {
+        if *cancel.borrow() {
+            was_cancelled = true;
+            break;
+        }
+        if let Some(skipped) = checklist.blocked(step) {
+            let _ = app.emit("self-test-step", checklist.push(skipped));
+            continue;
+        }
+
+        let start = Instant::now();
+        let outcome = if UNINTERRUPTIBLE.contains(&step.id) {
+            run_step(step.id, &app, &state).await
+        } else {
+            tokio::select! {
+                outcome = run_step(step.id, &app, &state) => outcome,
+                _ = cancelled(&mut cancel) => {
+                    was_cancelled = true;
+                    break;
+                }
+            }
+        };
+        let duration_ms = start.elapsed().as_millis() as u64;
+        let _ = app.emit("self-test-step", checklist.record(step, outcome, duration_ms));
+    }
+
+    Ok(SelfTestReport {
+        passed: !was_cancelled && !checklist.any_failed(),
+        cancelled: was_cancelled,
+        steps: checklist.steps,
+    })
+}
+
+// Stop a running self-test. The step in progress is abandoned, except a proxy
+// start, which finishes first.
+#[tauri::command]
+pub fn cancel_self_test(state: State<AppState>) {
+    if let Some(run) = state.self_test.lock().as_ref() {
+        let _ = run.send(true);
+    }
+}
@@ -0,0 +1,14 @@
+//! Last session summary command.
+
+use tauri::State;
+
+use crate::state::AppState;
+use crate::types::LastSessionSummary;
+
+/// How the previous run of ProxyPal went: uptime, whether it shut down
+/// cleanly, the crash reason if not, and its last request errors. `None` on
+/// the first launch.
+#[tauri::command]
+pub fn get_last_session_summary(state: State<AppState>) -> Option<LastSessionSummary> {
+    crate::session_journal::last_summary(&state)
+}
@@ -1,9 +1,13 @@
 //! Settings and runtime configuration commands.
 
 use crate::config::save_config_to_file;
+use crate::error::CommandError;
+use crate::http::{management_json, send_management, ManagementError};
 use crate::state::AppState;
-use crate::types::{ReasoningEffortSettings, ThinkingBudgetSettings};
-use crate::{build_management_client, get_management_key, get_management_url};
+use crate::types::{
+    AuditEntry, FilePermissionReport, ReasoningEffortSettings, ThinkingBudgetSettings,
+};
+use reqwest::Method;
 use tauri::State;
 
 // ============================================
@@ -11,8 +15,8 @@ use tauri::State;
 // ============================================
 
 #[tauri::command]
-pub async fn get_claude_code_settings() -> Result<crate::types::agents::ClaudeCodeSettings, String> {
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+pub async fn get_claude_code_settings() -> Result<crate::types::agents::ClaudeCodeSettings, CommandError> {
+    let home = dirs::home_dir().ok_or_else(|| CommandError::not_found("Could not find home directory"))?;
     let config_path = home.join(".claude").join("settings.json");
 
     if !config_path.exists() {
@@ -25,8 +29,9 @@ pub async fn get_claude_code_settings() -> Result<crate::types::agents::ClaudeCo
         });
     }
 
-    let content = std::fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-    let json: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let content = std::fs::read_to_string(&config_path).map_err(|e| CommandError::io(e.to_string()))?;
+    let json: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| CommandError::invalid_input(e.to_string()))?;
 
     let env = json.get("env").and_then(|e| e.as_object());
 
@@ -57,8 +62,8 @@ pub async fn get_claude_code_settings() -> Result<crate::types::agents::ClaudeCo
 #[tauri::command]
 pub async fn get_thinking_budget_settings(
     state: State<'_, AppState>,
-) -> Result<ThinkingBudgetSettings, String> {
-    let config = state.config.lock().unwrap();
+) -> Result<ThinkingBudgetSettings, CommandError> {
+    let config = state.config.lock();
     let mode = if config.thinking_budget_mode.is_empty() {
         "medium".to_string()
     } else {
@@ -76,17 +81,17 @@ pub async fn get_thinking_budget_settings(
 pub async fn set_thinking_budget_settings(
     state: State<'_, AppState>,
     settings: ThinkingBudgetSettings,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     {
-        let mut config = state.config.lock().unwrap();
+        let mut config = state.config.lock();
         config.thinking_budget_mode = settings.mode;
         config.thinking_budget_custom = settings.custom_budget;
     }
     let config_to_save = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         config.clone()
     };
-    crate::commands::config::save_config(state, config_to_save)?;
+    crate::commands::config::store_config(&state, config_to_save).map_err(CommandError::io)?;
 
     // Config is saved - proxy will pick up new thinking budget on next request
 
@@ -100,8 +105,8 @@ pub async fn set_thinking_budget_settings(
 #[tauri::command]
 pub async fn get_reasoning_effort_settings(
     state: State<'_, AppState>,
-) -> Result<ReasoningEffortSettings, String> {
-    let config = state.config.lock().unwrap();
+) -> Result<ReasoningEffortSettings, CommandError> {
+    let config = state.config.lock();
     let level = if config.reasoning_effort_level.is_empty() {
         "medium".to_string()
     } else {
@@ -114,25 +119,25 @@ pub async fn get_reasoning_effort_settings(
 pub async fn set_reasoning_effort_settings(
     state: State<'_, AppState>,
     settings: ReasoningEffortSettings,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // Validate level
     let valid_levels = ["none", "low", "medium", "high", "xhigh"];
     if !valid_levels.contains(&settings.level.as_str()) {
-        return Err(format!(
+        return Err(CommandError::invalid_input(format!(
             "Invalid reasoning effort level: {}. Must be one of: {:?}",
             settings.level, valid_levels
-        ));
+        )));
     }
 
     {
-        let mut config = state.config.lock().unwrap();
+        let mut config = state.config.lock();
         config.reasoning_effort_level = settings.level;
     }
     let config_to_save = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         config.clone()
     };
-    crate::commands::config::save_config(state, config_to_save)?;
+    crate::commands::config::store_config(&state, config_to_save).map_err(CommandError::io)?;
 
     Ok(())
 }
@@ -142,8 +147,8 @@ pub async fn set_reasoning_effort_settings(
 // ============================================
 
 #[tauri::command]
-pub async fn get_close_to_tray(state: State<'_, AppState>) -> Result<bool, String> {
-    let config = state.config.lock().unwrap();
+pub async fn get_close_to_tray(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    let config = state.config.lock();
     Ok(config.close_to_tray)
 }
 
@@ -151,42 +156,146 @@ pub async fn get_close_to_tray(state: State<'_, AppState>) -> Result<bool, Strin
 pub async fn set_close_to_tray(
     state: State<'_, AppState>,
     enabled: bool,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     {
-        let mut config = state.config.lock().unwrap();
+        let mut config = state.config.lock();
         config.close_to_tray = enabled;
     }
     let config_to_save = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         config.clone()
     };
-    crate::commands::config::save_config(state, config_to_save)?;
+    crate::commands::config::store_config(&state, config_to_save).map_err(CommandError::io)?;
     Ok(())
 }
 
+// ============================================
+// Mini Monitor
+// ============================================
+
+/// Show the always-on-top window with the live request feed. Async so the
+/// window is not built on the main thread, which deadlocks on Windows.
+#[tauri::command]
+pub async fn open_mini_monitor(app: tauri::AppHandle) -> Result<(), CommandError> {
+    crate::helpers::window_state::open_mini_monitor(&app).map_err(CommandError::from)
+}
+
+// ============================================
+// Notifications
+// ============================================
+
+#[tauri::command]
+pub fn send_test_notification(app: tauri::AppHandle, category: String) -> Result<(), CommandError> {
+    crate::notifications::send_test(&app, &category)
+}
+
+// ============================================
+// File Permissions
+// ============================================
+
+/// Audit config and credential files for access by other users. With
+/// `repair`, tighten them to owner-only and mark each fixed entry.
+#[tauri::command]
+pub fn check_file_permissions(repair: Option<bool>) -> FilePermissionReport {
+    use crate::helpers::permissions;
+
+    let paths = permissions::sensitive_paths();
+    let mut issues = permissions::audit(&paths);
+    if repair.unwrap_or(false) {
+        permissions::repair(&mut issues);
+    }
+    FilePermissionReport {
+        checked: paths.len(),
+        issues,
+    }
+}
+
+// ============================================
+// Audit Log
+// ============================================
+
+/// Recorded configuration and credential changes, newest first. `filter`
+/// matches the command name, change summaries and errors.
+#[tauri::command]
+pub fn get_audit_log(limit: Option<usize>, filter: Option<String>) -> Vec<AuditEntry> {
+    use crate::helpers::audit;
+
+    audit::read_entries(
+        &audit::audit_log_path(),
+        limit.unwrap_or(100),
+        filter.as_deref(),
+    )
+}
+
+// ============================================
+// Status Bar Plugin
+// ============================================
+
+/// Write an xbar or SwiftBar plugin that shows the status file into
+/// `plugins_dir` (xbar's own folder when omitted) and return its path. The
+/// status file has to be on and in JSON.
+#[tauri::command]
+pub fn generate_statusbar_plugin(
+    state: State<AppState>,
+    kind: String,
+    plugins_dir: Option<String>,
+    refresh_secs: Option<u32>,
+) -> Result<String, CommandError> {
+    use crate::helpers::statusbar_plugin;
+
+    let kind = kind.trim().to_lowercase();
+    let (enabled, format, status_file) = {
+        let config = state.config.lock();
+        (
+            config.status_file_enabled,
+            config.status_file_format.clone(),
+            crate::status_file::status_file_path(config.status_file_path.as_deref()),
+        )
+    };
+    let plugins_dir = plugins_dir
+        .map(|dir| std::path::PathBuf::from(dir.trim()))
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .or_else(|| statusbar_plugin::default_plugins_dir(&kind))
+        .ok_or_else(|| CommandError::invalid_input("Choose the SwiftBar plugins folder"))?;
+
+    let result = if !enabled || format != "json" {
+        Err(CommandError::invalid_input(
+            "Turn on the status file in JSON format first",
+        ))
+    } else {
+        statusbar_plugin::write_plugin(
+            &plugins_dir,
+            &kind,
+            &status_file,
+            refresh_secs.unwrap_or(statusbar_plugin::DEFAULT_REFRESH_SECS),
+        )
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(CommandError::from)
+    };
+    crate::helpers::audit::record(
+        "generate_statusbar_plugin",
+        vec![format!("{}: {}", kind, plugins_dir.display())],
+        &result,
+    );
+    result
+}
+
 // ============================================================================
 // Management API Settings (Runtime Updates)
 // ============================================================================
 
 // Get max retry interval from Management API
 #[tauri::command]
-pub async fn get_max_retry_interval(state: State<'_, AppState>) -> Result<i32, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = get_management_url(port, "max-retry-interval");
-
-    let client = build_management_client();
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to get max retry interval: {}", e))?;
-
-    if !response.status().is_success() {
-        return Ok(0); // Default to 0 if not set
-    }
-
-    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+pub async fn get_max_retry_interval(state: State<'_, AppState>) -> Result<i32, CommandError> {
+    let port = state.config.lock().port;
+    let request = state
+        .http
+        .management(Method::GET, port, "max-retry-interval");
+    let json: serde_json::Value = match management_json(request).await {
+        Ok(json) => json,
+        Err(ManagementError::Status { .. }) => return Ok(0), // Default to 0 if not set
+        Err(e) => return Err(CommandError::management("get max retry interval", e)),
+    };
     Ok(json["max-retry-interval"].as_i64().unwrap_or(0) as i32)
 }
 
@@ -195,101 +304,63 @@ pub async fn get_max_retry_interval(state: State<'_, AppState>) -> Result<i32, S
 pub async fn set_max_retry_interval(
     state: State<'_, AppState>,
     value: i32,
-) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = get_management_url(port, "max-retry-interval");
-
-    let client = build_management_client();
-    let response = client
-        .put(&url)
-        .header("X-Management-Key", &get_management_key())
-        .json(&serde_json::json!({ "value": value }))
-        .send()
+) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
+    let request = state
+        .http
+        .management(Method::PUT, port, "max-retry-interval")
+        .json(&serde_json::json!({ "value": value }));
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to set max retry interval: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!(
-            "Failed to set max retry interval: {} - {}",
-            status, text
-        ));
-    }
+        .map_err(|e| CommandError::management("set max retry interval", e))?;
 
     // Persist to Tauri config so it survives restart
-    let mut config = state.config.lock().unwrap();
+    let mut config = state.config.lock();
     config.max_retry_interval = value;
-    save_config_to_file(&config).map_err(|e| format!("Failed to save config: {}", e))?;
+    save_config_to_file(&config)
+        .map_err(|e| CommandError::io(format!("Failed to save config: {}", e)))?;
 
     Ok(())
 }
 
 // Get log size from Management API
 #[tauri::command]
-pub async fn get_log_size(state: State<'_, AppState>) -> Result<u32, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = get_management_url(port, "log-size");
-
-    let client = build_management_client();
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to get log size: {}", e))?;
-
-    if !response.status().is_success() {
-        return Ok(500); // Default to 500 if not set
-    }
-
-    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+pub async fn get_log_size(state: State<'_, AppState>) -> Result<u32, CommandError> {
+    let port = state.config.lock().port;
+    let request = state.http.management(Method::GET, port, "log-size");
+    let json: serde_json::Value = match management_json(request).await {
+        Ok(json) => json,
+        Err(ManagementError::Status { .. }) => return Ok(500), // Default to 500 if not set
+        Err(e) => return Err(CommandError::management("get log size", e)),
+    };
     Ok(json["log-size"].as_u64().unwrap_or(500) as u32)
 }
 
 // Set log size via Management API
 #[tauri::command]
-pub async fn set_log_size(state: State<'_, AppState>, size: u32) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = get_management_url(port, "log-size");
-
-    let client = build_management_client();
-    let response = client
-        .put(&url)
-        .header("X-Management-Key", &get_management_key())
-        .json(&serde_json::json!({ "value": size }))
-        .send()
+pub async fn set_log_size(state: State<'_, AppState>, size: u32) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
+    let request = state
+        .http
+        .management(Method::PUT, port, "log-size")
+        .json(&serde_json::json!({ "value": size }));
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to set log size: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to set log size: {} - {}", status, text));
-    }
+        .map_err(|e| CommandError::management("set log size", e))?;
 
     Ok(())
 }
 
 // Get WebSocket auth status from Management API
 #[tauri::command]
-pub async fn get_websocket_auth(state: State<'_, AppState>) -> Result<bool, String> {
-    let port = state.config.lock().unwrap().port;
-    let url = get_management_url(port, "ws-auth");
-
-    let client = build_management_client();
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &get_management_key())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to get WebSocket auth: {}", e))?;
-
-    if !response.status().is_success() {
-        return Ok(false); // Default to false
-    }
-
-    let json: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+pub async fn get_websocket_auth(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    let port = state.config.lock().port;
+    let request = state.http.management(Method::GET, port, "ws-auth");
+    let json: serde_json::Value = match management_json(request).await {
+        Ok(json) => json,
+        Err(ManagementError::Status { .. }) => return Ok(false), // Default to false
+        Err(e) => return Err(CommandError::management("get WebSocket auth", e)),
+    };
     Ok(json["ws-auth"].as_bool().unwrap_or(false))
 }
 
@@ -298,24 +369,15 @@ pub async fn get_websocket_auth(state: State<'_, AppState>) -> Result<bool, Stri
 pub async fn set_websocket_auth(
     state: State<'_, AppState>,
     value: bool,
-) -> Result<(), String> {
-    let port = state.config.lock().unwrap().port;
-    let url = get_management_url(port, "ws-auth");
-
-    let client = build_management_client();
-    let response = client
-        .put(&url)
-        .header("X-Management-Key", &get_management_key())
-        .json(&serde_json::json!({ "value": value }))
-        .send()
+) -> Result<(), CommandError> {
+    let port = state.config.lock().port;
+    let request = state
+        .http
+        .management(Method::PUT, port, "ws-auth")
+        .json(&serde_json::json!({ "value": value }));
+    send_management(request)
         .await
-        .map_err(|e| format!("Failed to set WebSocket auth: {}", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(format!("Failed to set WebSocket auth: {} - {}", status, text));
-    }
+        .map_err(|e| CommandError::management("set WebSocket auth", e))?;
 
     Ok(())
 }
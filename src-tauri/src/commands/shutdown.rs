@@ -0,0 +1,106 @@
+//! Quit confirmation when the proxy still has requests in flight.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogResult};
+
+use crate::state::AppState;
+
+/// Longest time "Wait for requests" holds the exit back
+const MAX_QUIT_WAIT: Duration = Duration::from_secs(30);
+
+static WAITING_TO_QUIT: AtomicBool = AtomicBool::new(false);
+
+fn in_flight_count(app: &AppHandle) -> usize {
+    let Some(state) = app.try_state::<AppState>() else {
+        return 0;
+    };
+    if !state.proxy_status.lock().running {
+        return 0;
+    }
+    let mut in_flight = state.in_flight.lock();
+    in_flight.count()
+}
+
+/// Quit, asking first if the proxy is serving requests.
+pub(crate) fn request_quit(app: &AppHandle) {
+    if !confirm_if_busy(app) {
+        app.exit(0);
+    }
+}
+
+/// If requests are in flight, ask the user how to proceed and return true; the
+/// caller must then hold the exit back. Returns false when it is safe to exit.
+pub(crate) fn confirm_if_busy(app: &AppHandle) -> bool {
+    let count = in_flight_count(app);
+    if count == 0 {
+        return false;
+    }
+    if WAITING_TO_QUIT.load(Ordering::SeqCst) {
+        return true;
+    }
+
+    let window_visible = app
+        .get_webview_window("main")
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false);
+    if window_visible {
+        // The frontend shows "Quit anyway" / "Wait for requests" / "Cancel" and
+        // answers with force_quit or quit_when_idle
+        let _ = app.emit("confirm-quit", count);
+        return true;
+    }
+
+    let handle = app.clone();
+    app.dialog()
+        .message(format!(
+            "{} request{} still in progress. Quitting now will cut off the response.",
+            count,
+            if count == 1 { " is" } else { "s are" }
+        ))
+        .title("Quit ProxyPal?")
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            "Quit anyway".to_string(),
+            "Wait for requests".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show_with_result(move |result| match result {
+            MessageDialogResult::Yes => handle.exit(0),
+            MessageDialogResult::Custom(label) if label == "Quit anyway" => handle.exit(0),
+            MessageDialogResult::No => wait_then_quit(handle),
+            MessageDialogResult::Custom(label) if label == "Wait for requests" => {
+                wait_then_quit(handle)
+            }
+            _ => {}
+        });
+    true
+}
+
+// Exit once in-flight requests drain, or after MAX_QUIT_WAIT
+fn wait_then_quit(app: AppHandle) {
+    if WAITING_TO_QUIT.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    println!("[ProxyPal] Waiting for in-flight requests before quitting...");
+    tauri::async_runtime::spawn(async move {
+        let started = Instant::now();
+        while in_flight_count(&app) > 0 && started.elapsed() < MAX_QUIT_WAIT {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        app.exit(0);
+    });
+}
+
+// Exit immediately, even if requests are in flight
+#[tauri::command]
+pub fn force_quit(app: AppHandle) {
+    app.exit(0);
+}
+
+// Exit after in-flight requests finish (capped at 30s)
+#[tauri::command]
+pub fn quit_when_idle(app: AppHandle) {
+    wait_then_quit(app);
+}
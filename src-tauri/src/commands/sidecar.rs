@@ -0,0 +1,45 @@
+//! Runtime update of the CLIProxyAPI binary.
+
+use crate::error::{CommandError, ErrorCode};
+use crate::proxy::lifecycle;
+use crate::sidecar_download;
+
+/// Download a CLIProxyAPI release (the latest unless `version` is given),
+/// verify it against the published checksum and install it as the binary
+/// ProxyPal starts. Takes effect on the next proxy start; returns the
+/// installed version.
+#[tauri::command]
+pub async fn update_sidecar(version: Option<String>) -> Result<String, CommandError> {
+    // The blocking HTTP client must stay off the async runtime
+    tauri::async_runtime::spawn_blocking(move || -> Result<String, String> {
+        // Routed through the app-level network proxy like other external requests
+        let client = sidecar_download::client(crate::http::blocking_external_builder(
+            &crate::config::load_config().network_proxy,
+        ))?;
+        let repo = sidecar_download::release_repo();
+        let version = match version
+            .map(|v| v.trim().trim_start_matches('v').to_string())
+            .filter(|v| !v.is_empty())
+        {
+            Some(version) => version,
+            None => sidecar_download::latest_version(&client, &repo)?,
+        };
+        let dest = lifecycle::updated_sidecar_path();
+        sidecar_download::download_verified(
+            &client,
+            &repo,
+            &version,
+            env!("PROXYPAL_TARGET"),
+            &dest,
+        )?;
+        println!(
+            "[ProxyPal] Installed CLIProxyAPI {} at {}",
+            version,
+            dest.display()
+        );
+        Ok(version)
+    })
+    .await
+    .map_err(|e| format!("Sidecar update failed: {}", e))?
+    .map_err(|e| CommandError::new(ErrorCode::Upstream, e))
+}
@@ -3,12 +3,25 @@
 //! Extracted from lib.rs — handles usage statistics, request history,
 //! and syncing usage data from the CLIProxyAPI management API.
 
-use crate::helpers::history::{load_aggregate, load_request_history, save_aggregate, save_request_history};
+use crate::commands::onboarding::mark_onboarding_step;
+use crate::error::{CommandError, ErrorCode};
+use crate::helpers::cost_ledger;
+use crate::helpers::history::{
+    load_aggregate, request_history_view, save_aggregate, usage_by_account, usage_by_client,
+};
+use crate::helpers::latency::latency_stats;
+use crate::helpers::request_tags;
+use crate::helpers::usage_day;
+use crate::helpers::versions;
+use crate::http::management_json;
 use crate::state::AppState;
 use crate::types::{
-    ModelStats, ModelUsage, ProviderUsage, RequestHistory, RequestLog, TimeSeriesPoint, UsageStats,
+    AccountUsage, ClientUsage, CliproxyImportReport, CostLedger, CostLedgerRow, CostLedgerView, LatencyStats, ModelStats, ModelUsage, ProviderUsage, QuotaSwitchEvent,
+    RequestHistory, RequestLog, TimeSeriesPoint, UsageAnomalyStatus, UsageStats, VersionChange,
+    VersionDimension, VersionStats, STEP_REQUEST_OBSERVED,
 };
 use crate::utils::estimate_request_cost;
+use reqwest::Method;
 use tauri::State;
 
 // Live usage data from Go backend
@@ -23,24 +36,29 @@ struct LiveUsageData {
     tokens_by_hour: Vec<TimeSeriesPoint>,
 }
 
-// Fetch live usage stats from Go backend (blocking version for sync context)
-fn fetch_live_usage_stats_blocking(port: u16) -> Option<LiveUsageData> {
-    let url = format!("http://127.0.0.1:{}/v0/management/usage", port);
-    let client = reqwest::blocking::Client::new();
-
-    let response = client
-        .get(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .timeout(std::time::Duration::from_secs(2))
-        .send()
-        .ok()?;
-
-    let json: serde_json::Value = response.json().ok()?;
-
-    // Parse the response structure:
-    // { "usage": { "total_tokens": N, "apis": { "api-name": { "models": { "model": { "total_tokens": N, "details": [...] } } } } } }
-    let usage = json.get("usage")?;
+// Fetch the `usage` object from the Go backend's Management API. Its structure:
+// { "usage": { "total_tokens": N, "apis": { "api-name": { "models": { "model": { "total_tokens": N, "details": [...] } } } } } }
+async fn fetch_proxy_usage(state: &AppState, port: u16) -> Option<serde_json::Value> {
+    let request = state
+        .http
+        .management(Method::GET, port, "usage")
+        .timeout(std::time::Duration::from_secs(5));
+    let mut body: serde_json::Value = match management_json(request).await {
+        Ok(body) => body,
+        Err(e) => {
+            eprintln!("[usage] fetch_proxy_usage: {}", e);
+            return None;
+        }
+    };
+    let usage = body.get_mut("usage").map(serde_json::Value::take);
+    if usage.is_none() {
+        eprintln!("[usage] fetch_proxy_usage: missing 'usage' field");
+    }
+    usage
+}
 
+// Live per-model usage for the current session, from the proxy's usage object
+fn live_usage_stats(usage: &serde_json::Value) -> Option<LiveUsageData> {
     let total_tokens = usage.get("total_tokens")?.as_u64().unwrap_or(0);
 
     // Extract input/output/cached tokens from the detailed data
@@ -120,11 +138,11 @@ fn fetch_live_usage_stats_blocking(port: u16) -> Option<LiveUsageData> {
                 .get("tokens_by_hour")
                 .and_then(|v| v.as_object())
             {
-                let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+                let (timezone, now) = (usage_day::current(), chrono::Utc::now());
                 for (hour, value) in tbh {
                     if let Some(v) = value.as_u64() {
                         // Convert "HH" format to "YYYY-MM-DDTHH" format
-                        let label = format!("{}T{}", today, hour);
+                        let label = usage_day::normalize_hour_key(timezone, now, hour);
                         result.push(TimeSeriesPoint { label, value: v });
                     }
                 }
@@ -135,50 +153,10 @@ fn fetch_live_usage_stats_blocking(port: u16) -> Option<LiveUsageData> {
     })
 }
 
-// Blocking version of sync_usage_from_proxy for use in sync contexts
-fn sync_usage_from_proxy_blocking(port: u16) {
-    let url = format!("http://127.0.0.1:{}/v0/management/usage", port);
-    let client = reqwest::blocking::Client::new();
-
-    let response = match client
-        .get(&url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .timeout(std::time::Duration::from_secs(5))
-        .send()
-    {
-        Ok(r) => r,
-        Err(e) => {
-            eprintln!("[usage] sync_usage_from_proxy_blocking: request failed: {}", e);
-            return;
-        }
-    };
-
-    if !response.status().is_success() {
-        eprintln!(
-            "[usage] sync_usage_from_proxy_blocking: HTTP {}",
-            response.status()
-        );
-        return;
-    }
-
-    let body: serde_json::Value = match response.json() {
-        Ok(j) => j,
-        Err(e) => {
-            eprintln!("[usage] sync_usage_from_proxy_blocking: parse error: {}", e);
-            return;
-        }
-    };
-
-    let usage = match body.get("usage") {
-        Some(u) => u,
-        None => {
-            eprintln!("[usage] sync_usage_from_proxy_blocking: missing 'usage' field");
-            return;
-        }
-    };
-
+// Merge the proxy's usage object into the persisted aggregate
+fn sync_aggregate_from_usage(usage: &serde_json::Value) {
     // Parse time-series data from CLIProxyAPI
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let (timezone, now) = (usage_day::current(), chrono::Utc::now());
 
     // Parse tokens_by_day
     let mut tokens_by_day: Vec<TimeSeriesPoint> = Vec::new();
@@ -199,11 +177,7 @@ fn sync_usage_from_proxy_blocking(port: u16) {
     if let Some(tbh) = usage.get("tokens_by_hour").and_then(|v| v.as_object()) {
         for (hour, value) in tbh {
             if let Some(v) = value.as_u64() {
-                let label = if hour.len() == 2 {
-                    format!("{}T{}", today, hour)
-                } else {
-                    hour.clone()
-                };
+                let label = usage_day::normalize_hour_key(timezone, now, hour);
                 tokens_by_hour.push(TimeSeriesPoint { label, value: v });
             }
         }
@@ -229,11 +203,7 @@ fn sync_usage_from_proxy_blocking(port: u16) {
     if let Some(rbh) = usage.get("requests_by_hour").and_then(|v| v.as_object()) {
         for (hour, value) in rbh {
             if let Some(v) = value.as_u64() {
-                let label = if hour.len() == 2 {
-                    format!("{}T{}", today, hour)
-                } else {
-                    hour.clone()
-                };
+                let label = usage_day::normalize_hour_key(timezone, now, hour);
                 requests_by_hour.push(TimeSeriesPoint { label, value: v });
             }
         }
@@ -316,11 +286,6 @@ fn sync_usage_from_proxy_blocking(port: u16) {
         }
     }
     agg.tokens_by_hour.sort_by(|a, b| a.label.cmp(&b.label));
-    if agg.tokens_by_hour.len() > 168 {
-        agg.tokens_by_hour = agg
-            .tokens_by_hour
-            .split_off(agg.tokens_by_hour.len() - 168);
-    }
 
     for point in &requests_by_day {
         if let Some(existing) = agg
@@ -349,11 +314,6 @@ fn sync_usage_from_proxy_blocking(port: u16) {
     }
     agg.requests_by_hour
         .sort_by(|a, b| a.label.cmp(&b.label));
-    if agg.requests_by_hour.len() > 168 {
-        agg.requests_by_hour = agg
-            .requests_by_hour
-            .split_off(agg.requests_by_hour.len() - 168);
-    }
 
     // Update model stats
     for (model_name, stats) in model_stats {
@@ -391,29 +351,44 @@ fn sync_usage_from_proxy_blocking(port: u16) {
 
 // Compute usage statistics - fetches live data from Go backend when proxy is running
 #[tauri::command]
-pub fn get_usage_stats(state: State<'_, AppState>) -> Result<UsageStats, String> {
+pub async fn get_usage_stats(state: State<'_, AppState>) -> Result<UsageStats, CommandError> {
+    compute_usage_stats(&state).await
+}
+
+// get_usage_stats for callers without a Tauri State, such as headless mode
+pub(crate) async fn compute_usage_stats(state: &AppState) -> Result<UsageStats, CommandError> {
     // Get proxy status
     let (is_running, port) = {
-        let status = state.proxy_status.lock().unwrap();
+        let status = state.proxy_status.lock();
         (status.running, status.port)
     };
 
+    // One usage report from the proxy both updates the aggregate and supplies
+    // the live per-model breakdown
+    let usage = if is_running {
+        fetch_proxy_usage(state, port).await
+    } else {
+        None
+    };
+    let history = state.history.snapshot();
+
+    // The aggregate is read and written on disk
+    tauri::async_runtime::spawn_blocking(move || usage_stats(usage.as_ref(), history))
+        .await
+        .map_err(|e| CommandError::from(e.to_string()))
+}
+
+fn usage_stats(usage: Option<&serde_json::Value>, history: RequestHistory) -> UsageStats {
     // Sync from proxy first if running (this updates aggregate with latest data from CLIProxyAPI)
-    if is_running {
-        sync_usage_from_proxy_blocking(port);
+    if let Some(usage) = usage {
+        sync_aggregate_from_usage(usage);
     }
 
-    // Now load the updated aggregate and history
+    // Now load the updated aggregate
     let agg = load_aggregate();
-    let history = load_request_history();
 
-    // Try to fetch live data from Go backend if proxy is running
     // Live data provides per-model breakdowns for the current session
-    let live_data = if is_running {
-        fetch_live_usage_stats_blocking(port)
-    } else {
-        None
-    };
+    let live_data = usage.and_then(live_usage_stats);
 
     // Use aggregate as the source of truth for all-time totals (preserved across restarts).
     // Live data only supplements per-model breakdowns for the current session.
@@ -443,33 +418,20 @@ pub fn get_usage_stats(state: State<'_, AppState>) -> Result<UsageStats, String>
 
     // If no data yet, return defaults
     if agg.total_requests == 0 && history.requests.is_empty() {
-        return Ok(UsageStats::default());
+        return UsageStats::default();
     }
 
     // Use aggregate as primary source of truth for all-time stats
     let total_requests = agg.total_requests;
     let success_count = agg.total_success_count;
     let failure_count = agg.total_failure_count;
+    let retried_success_count = agg.total_retried_success_count;
 
-    // Calculate today's stats from aggregate time-series
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let requests_today = agg
-        .requests_by_day
-        .iter()
-        .find(|p| p.label == today)
-        .map(|p| p.value)
-        .unwrap_or(0);
-
-    // Get today's tokens from live data or aggregate
-    let tokens_today = if let Some(ref live) = live_data {
-        live.total_tokens // Use live total as "today" since it's current session
-    } else {
-        agg.tokens_by_day
-            .iter()
-            .find(|p| p.label == today)
-            .map(|p| p.value)
-            .unwrap_or(0)
-    };
+    // Calculate today's stats from aggregate time-series, which the sync above
+    // filled from the proxy's per-day counters. Both are keyed by the usage day.
+    let (timezone, now) = (usage_day::current(), chrono::Utc::now());
+    let requests_today = usage_day::today_value(&agg.requests_by_day, timezone, now);
+    let tokens_today = usage_day::today_value(&agg.tokens_by_day, timezone, now);
 
     // Build model stats - merge aggregate with live token data
     let mut models: Vec<ModelUsage> = agg
@@ -531,8 +493,7 @@ pub fn get_usage_stats(state: State<'_, AppState>) -> Result<UsageStats, String>
         // Build from history requests
         let mut map: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
         for req in &history.requests {
-            if let Some(dt) = chrono::DateTime::from_timestamp_millis(req.timestamp as i64) {
-                let day = dt.format("%Y-%m-%d").to_string();
+            if let Some(day) = usage_day::day_label_ms(timezone, req.timestamp) {
                 *map.entry(day).or_insert(0) += 1;
             }
         }
@@ -566,8 +527,7 @@ pub fn get_usage_stats(state: State<'_, AppState>) -> Result<UsageStats, String>
         let mut requests_by_hour_map: std::collections::HashMap<String, u64> =
             std::collections::HashMap::new();
         for req in &history.requests {
-            if let Some(dt) = chrono::DateTime::from_timestamp_millis(req.timestamp as i64) {
-                let hour_label = dt.format("%Y-%m-%dT%H").to_string();
+            if let Some(hour_label) = usage_day::hour_label_ms(timezone, req.timestamp) {
                 *requests_by_hour_map.entry(hour_label).or_insert(0) += 1;
             }
         }
@@ -593,8 +553,7 @@ pub fn get_usage_stats(state: State<'_, AppState>) -> Result<UsageStats, String>
             let mut tokens_by_hour_map: std::collections::HashMap<String, u64> =
                 std::collections::HashMap::new();
             for req in &history.requests {
-                if let Some(dt) = chrono::DateTime::from_timestamp_millis(req.timestamp as i64) {
-                    let hour_label = dt.format("%Y-%m-%dT%H").to_string();
+                if let Some(hour_label) = usage_day::hour_label_ms(timezone, req.timestamp) {
                     let tokens =
                         (req.tokens_in.unwrap_or(0) + req.tokens_out.unwrap_or(0)) as u64;
                     *tokens_by_hour_map.entry(hour_label).or_insert(0) += tokens;
@@ -613,10 +572,11 @@ pub fn get_usage_stats(state: State<'_, AppState>) -> Result<UsageStats, String>
         tokens_by_hour = tokens_by_hour.split_off(tokens_by_hour.len() - 168);
     }
 
-    Ok(UsageStats {
+    UsageStats {
         total_requests,
         success_count,
         failure_count,
+        retried_success_count,
         total_tokens,
         input_tokens,
         output_tokens,
@@ -629,97 +589,343 @@ pub fn get_usage_stats(state: State<'_, AppState>) -> Result<UsageStats, String>
         tokens_by_day,
         requests_by_hour,
         tokens_by_hour,
+        granularity: crate::helpers::aggregate_compaction::granularity(&agg),
+    }
+}
+
+// Get request history; with `tag`, only the requests carrying it, with
+// `client`, only those sent by that tool (matched case-insensitively), and
+// with `app_version` / `sidecar_version`, only those recorded under that
+// version ("unknown" for requests from before versions were recorded)
+#[tauri::command]
+pub fn get_request_history(
+    state: State<'_, AppState>,
+    tag: Option<String>,
+    client: Option<String>,
+    app_version: Option<String>,
+    sidecar_version: Option<String>,
+) -> RequestHistory {
+    let mut history = state.history.snapshot();
+    if let Some(tag) = tag.map(|t| t.trim().to_lowercase()) {
+        history.requests.retain(|r| r.tags.contains(&tag));
+    }
+    if let Some(client) = client.map(|c| c.trim().to_string()) {
+        history.requests.retain(|r| {
+            r.client
+                .as_deref()
+                .is_some_and(|c| c.eq_ignore_ascii_case(&client))
+        });
+    }
+    for (dimension, version) in [
+        (VersionDimension::App, app_version),
+        (VersionDimension::Sidecar, sidecar_version),
+    ] {
+        if let Some(version) = version.map(|v| v.trim().to_string()) {
+            history
+                .requests
+                .retain(|r| versions::version_of(r, dimension) == version);
+        }
+    }
+    request_history_view(history, &load_aggregate())
+}
+
+// Replace the tags of a request still in history; an empty list clears them.
+// Its cost moves between the tags in the cost ledger, where it stays after
+// the request is trimmed.
+#[tauri::command]
+pub fn set_request_tag(
+    state: State<'_, AppState>,
+    request_id: String,
+    tags: Vec<String>,
+) -> Result<RequestLog, CommandError> {
+    let tags = request_tags::normalize(tags).map_err(CommandError::invalid_input)?;
+    let timezone = usage_day::current();
+    let mut agg = load_aggregate();
+    let updated = state.history.update(|history| {
+        let request = history.requests.iter_mut().find(|r| r.id == request_id)?;
+        request_tags::retag(&mut agg, timezone, request, tags);
+        Some(request.clone())
+    });
+    let updated = updated.ok_or_else(|| {
+        CommandError::not_found(format!("Request {} is no longer in history", request_id))
+    })?;
+    save_aggregate(&agg).map_err(CommandError::io)?;
+    Ok(updated)
+}
+
+// Replace the tags of every request in a session, including requests that
+// join it later. Returns how many requests in history were retagged.
+#[tauri::command]
+pub fn set_session_tag(
+    state: State<'_, AppState>,
+    session_id: String,
+    tags: Vec<String>,
+) -> Result<usize, CommandError> {
+    let tags = request_tags::normalize(tags).map_err(CommandError::invalid_input)?;
+    let timezone = usage_day::current();
+    let mut agg = load_aggregate();
+    let known = agg.session_tags.contains_key(&session_id);
+    let retagged = state.history.update(|history| {
+        let mut retagged = 0;
+        for request in history.requests.iter_mut() {
+            if request.session_id.as_deref() == Some(session_id.as_str()) {
+                request_tags::retag(&mut agg, timezone, request, tags.clone());
+                retagged += 1;
+            }
+        }
+        retagged
+    });
+    if retagged == 0 && !known {
+        return Err(CommandError::not_found(format!(
+            "Session {} is no longer in history",
+            session_id
+        )));
+    }
+    if tags.is_empty() {
+        agg.session_tags.remove(&session_id);
+    } else {
+        agg.session_tags.insert(session_id, tags);
+    }
+    save_aggregate(&agg).map_err(CommandError::io)?;
+    Ok(retagged)
+}
+
+// Requests in history rolled up per account, labelled from the credential
+// files. Requests the proxy log did not attribute are counted together as
+// "Unattributed".
+#[tauri::command]
+pub async fn get_usage_by_account(
+    state: State<'_, AppState>,
+) -> Result<Vec<AccountUsage>, CommandError> {
+    let files = crate::commands::auth_files::get_auth_files(state.clone())
+        .await
+        .unwrap_or_default();
+    Ok(state
+        .history
+        .read(|history| usage_by_account(&history.requests, &files)))
+}
+
+// Requests in history rolled up per calling tool: the client key's name when
+// the log showed one ProxyPal issued, else the tool named by its User-Agent.
+// Requests neither identified are counted together.
+#[tauri::command]
+pub fn get_usage_by_client(state: State<'_, AppState>) -> Vec<ClientUsage> {
+    state
+        .history
+        .read(|history| usage_by_client(&history.requests))
+}
+
+// Duration and time-to-first-token percentiles per provider and model, from
+// successful requests in history since `since` (ms timestamp; all when
+// omitted). With `group_by`, split further per app or sidecar version.
+#[tauri::command]
+pub fn get_latency_stats(
+    state: State<'_, AppState>,
+    since: Option<u64>,
+    group_by: Option<VersionDimension>,
+) -> Vec<LatencyStats> {
+    state
+        .history
+        .read(|history| latency_stats(&history.requests, since.unwrap_or(0), group_by))
+}
+
+// Error rate and latency per app or sidecar version, from requests in history
+// since `since` (ms timestamp; all when omitted)
+#[tauri::command]
+pub fn get_version_stats(
+    state: State<'_, AppState>,
+    dimension: VersionDimension,
+    since: Option<u64>,
+) -> Vec<VersionStats> {
+    state
+        .history
+        .read(|history| versions::version_stats(&history.requests, dimension, since.unwrap_or(0)))
+}
+
+// Before/after comparison for every version change between `since` and
+// `until` (ms timestamps; either may be left open). Empty when the version
+// did not change in that period.
+#[tauri::command]
+pub fn get_version_comparison(
+    state: State<'_, AppState>,
+    dimension: VersionDimension,
+    since: Option<u64>,
+    until: Option<u64>,
+) -> Vec<VersionChange> {
+    state.history.read(|history| {
+        versions::version_changes(
+            &history.requests,
+            dimension,
+            since.unwrap_or(0),
+            until.unwrap_or(u64::MAX),
+        )
+    })
+}
+
+// Quota-exceeded switches seen in the proxy log, oldest first. `since` and
+// `until` are millisecond timestamps; either may be left open.
+#[tauri::command]
+pub fn get_quota_events(
+    state: State<'_, AppState>,
+    since: Option<u64>,
+    until: Option<u64>,
+) -> Vec<QuotaSwitchEvent> {
+    state.history.read(|history| {
+        history
+            .quota_events
+            .iter()
+            .filter(|e| since.is_none_or(|since| e.timestamp >= since))
+            .filter(|e| until.is_none_or(|until| e.timestamp <= until))
+            .cloned()
+            .collect()
     })
 }
 
-// Get request history
+// Estimated spend from the cost ledger. `range` is "today", "7d", "30d",
+// "month" (month to date, the default) or "all"; `group_by` is "day" (the
+// default), "provider" or "model". Amounts are also given in the display
+// currency from the config.
 #[tauri::command]
-pub fn get_request_history() -> RequestHistory {
-    load_request_history()
+pub fn get_cost_ledger(
+    state: State<'_, AppState>,
+    range: Option<String>,
+    group_by: Option<String>,
+) -> Result<CostLedgerView, CommandError> {
+    let group_by = group_by.unwrap_or_else(|| "day".to_string());
+    cost_ledger_view(&state, range, group_by, cost_ledger::group)
+        .map_err(CommandError::invalid_input)
 }
 
-// Add a request to history (called when request-log event is emitted)
-// Returns only the added request to minimize data transfer (memory optimization)
+// Estimated spend per tag over `range` (as for `get_cost_ledger`), most
+// expensive first. A request with several tags counts towards each.
+#[tauri::command]
+pub fn get_cost_by_tag(
+    state: State<'_, AppState>,
+    range: Option<String>,
+) -> Result<CostLedgerView, CommandError> {
+    cost_ledger_view(&state, range, "tag".to_string(), |ledger, from, to, _| {
+        Ok(cost_ledger::group_by_tag(ledger, from, to))
+    })
+    .map_err(CommandError::invalid_input)
+}
+
+// Today's spend against what the previous days had spent by this hour,
+// with the costliest model and session, as the spend alert sees it
 #[tauri::command]
-pub fn add_request_to_history(request: RequestLog) -> Result<RequestLog, String> {
-    let mut history = load_request_history();
+pub fn get_anomaly_status(state: State<'_, AppState>) -> UsageAnomalyStatus {
+    crate::usage_anomaly::status(&state)
+}
 
-    // Calculate cost for this request
-    let tokens_in = request.tokens_in.unwrap_or(0);
-    let tokens_out = request.tokens_out.unwrap_or(0);
-    let cost = estimate_request_cost(&request.model, tokens_in, tokens_out);
-    let tokens_cached = request.tokens_cached.unwrap_or(0);
+fn cost_ledger_view(
+    state: &AppState,
+    range: Option<String>,
+    group_by: String,
+    group: impl FnOnce(&CostLedger, Option<&str>, &str, &str) -> Result<Vec<CostLedgerRow>, String>,
+) -> Result<CostLedgerView, String> {
+    let range = range.unwrap_or_else(|| "month".to_string());
+    let (currency, rate) = {
+        let config = state.config.lock();
+        (config.cost_currency.clone(), config.cost_currency_rate)
+    };
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err(format!("Invalid currency rate: {}", rate));
+    }
 
-    // Update totals
-    history.total_tokens_in += tokens_in as u64;
-    history.total_tokens_out += tokens_out as u64;
-    history.total_tokens_cached += tokens_cached as u64;
-    history.total_cost_usd += cost;
+    let today = usage_day::day_label(usage_day::current(), chrono::Utc::now());
+    let from = cost_ledger::range_start(&range, &today)?;
+    let agg = load_aggregate();
+    let rows: Vec<CostLedgerRow> = group(&agg.cost_ledger, from.as_deref(), &today, &group_by)?
+        .into_iter()
+        .map(|row| {
+            let cost = row.cost_usd * rate;
+            CostLedgerRow {
+                cost,
+                formatted: cost_ledger::format_currency(cost, &currency),
+                ..row
+            }
+        })
+        .collect();
+    let total_usd: f64 = rows.iter().map(|r| r.cost_usd).sum();
+    let total = total_usd * rate;
+    Ok(CostLedgerView {
+        range,
+        group_by,
+        from,
+        to: today,
+        formatted_total: cost_ledger::format_currency(total, &currency),
+        currency,
+        rate,
+        total_usd,
+        total,
+        rows,
+        granularity: crate::helpers::aggregate_compaction::granularity(&agg),
+    })
+}
 
-    // Add request (with deduplication check)
+// Add a request to history (called when request-log event is emitted)
+// Returns only the added request to minimize data transfer (memory optimization)
+#[tauri::command]
+pub fn add_request_to_history(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    request: RequestLog,
+) -> Result<RequestLog, CommandError> {
+    // Add request (with deduplication check). Totals are counted in the
+    // aggregate by the log watcher, and the store trims and saves.
     // Check if request with same ID already exists to prevent duplicates
     let request_clone = request.clone();
-    if !history.requests.iter().any(|r| r.id == request.id) {
-        history.requests.push(request);
-
-        // Trim to prevent unbounded growth (keep last 500 requests)
-        const MAX_HISTORY_SIZE: usize = 500;
-        if history.requests.len() > MAX_HISTORY_SIZE {
-            let excess = history.requests.len() - MAX_HISTORY_SIZE;
-            history.requests.drain(0..excess);
+    state.history.update(|history| {
+        if !history.requests.iter().any(|r| r.id == request.id) {
+            history.requests.push(request);
         }
-    }
+    });
 
-    // Save
-    save_request_history(&history)?;
+    mark_onboarding_step(&app, STEP_REQUEST_OBSERVED);
 
     // Return only the added request, not the full history
     Ok(request_clone)
 }
 
-// Clear request history
+// Clear the detailed request list; all-time totals stay in the aggregate. With
+// `synthetic_only`, only demo-mode requests are removed.
 #[tauri::command]
-pub fn clear_request_history() -> Result<(), String> {
-    let history = RequestHistory::default();
-    save_request_history(&history)
+pub fn clear_request_history(
+    state: State<'_, AppState>,
+    synthetic_only: Option<bool>,
+) -> Result<(), CommandError> {
+    state.history.update(|history| {
+        if synthetic_only.unwrap_or(false) {
+            history.requests.retain(|r| !r.synthetic);
+        } else {
+            *history = RequestHistory::default();
+        }
+    });
+    state.history.flush().map_err(CommandError::io)
 }
 
 // Sync usage statistics from CLIProxyAPI's Management API
 // This fetches real token counts that aren't available in GIN logs
 #[tauri::command]
-pub async fn sync_usage_from_proxy(state: State<'_, AppState>) -> Result<RequestHistory, String> {
+pub async fn sync_usage_from_proxy(
+    state: State<'_, AppState>,
+) -> Result<RequestHistory, CommandError> {
     let port = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         config.port
     };
 
-    let client = crate::build_management_client();
-    let usage_url = format!("http://127.0.0.1:{}/v0/management/usage", port);
-
-    let response = client
-        .get(&usage_url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .timeout(std::time::Duration::from_secs(5))
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch usage: {}. Is the proxy running?", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "Usage API returned status: {}",
-            response.status()
-        ));
-    }
-
-    let body: serde_json::Value = response
-        .json()
+    let request = state
+        .http
+        .management(Method::GET, port, "usage")
+        .timeout(std::time::Duration::from_secs(5));
+    let body: serde_json::Value = management_json(request)
         .await
-        .map_err(|e| format!("Failed to parse usage response: {}", e))?;
+        .map_err(|e| CommandError::management("fetch usage", e))?;
 
     // Extract token totals from CLIProxyAPI's usage response
-    let usage = body
-        .get("usage")
-        .ok_or("Missing 'usage' field in response")?;
+    let usage = body.get("usage").ok_or_else(|| {
+        CommandError::new(ErrorCode::Upstream, "Missing 'usage' field in response")
+    })?;
 
     // Calculate input/output token split from APIs data
     let mut total_input: u64 = 0;
@@ -773,6 +979,7 @@ pub async fn sync_usage_from_proxy(state: State<'_, AppState>) -> Result<Request
     }
 
     // Extract time-series data from CLIProxyAPI response
+    let (timezone, now) = (usage_day::current(), chrono::Utc::now());
     let mut tokens_by_day: Vec<TimeSeriesPoint> = Vec::new();
     let mut tokens_by_hour: Vec<TimeSeriesPoint> = Vec::new();
     let mut requests_by_day: Vec<TimeSeriesPoint> = Vec::new();
@@ -796,19 +1003,11 @@ pub async fn sync_usage_from_proxy(state: State<'_, AppState>) -> Result<Request
         }
     }
 
-    if let Some(tbh) = usage
-        .get("tokens_by_hour")
-        .and_then(|v| v.as_object())
-    {
-        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    if let Some(tbh) = usage.get("tokens_by_hour").and_then(|v| v.as_object()) {
         for (hour, value) in tbh {
             if let Some(v) = value.as_u64() {
                 // Normalize to YYYY-MM-DDTHH format if only HH is provided
-                let label = if hour.len() == 2 {
-                    format!("{}T{}", today, hour)
-                } else {
-                    hour.clone()
-                };
+                let label = usage_day::normalize_hour_key(timezone, now, hour);
                 tokens_by_hour.push(TimeSeriesPoint { label, value: v });
             }
         }
@@ -839,19 +1038,11 @@ pub async fn sync_usage_from_proxy(state: State<'_, AppState>) -> Result<Request
     }
 
     // Parse requests_by_hour from proxy (source of truth for Activity Patterns heatmap)
-    if let Some(rbh) = usage
-        .get("requests_by_hour")
-        .and_then(|v| v.as_object())
-    {
-        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    if let Some(rbh) = usage.get("requests_by_hour").and_then(|v| v.as_object()) {
         for (hour, value) in rbh {
             if let Some(v) = value.as_u64() {
                 // Normalize to YYYY-MM-DDTHH format if only HH is provided
-                let label = if hour.len() == 2 {
-                    format!("{}T{}", today, hour)
-                } else {
-                    hour.clone()
-                };
+                let label = usage_day::normalize_hour_key(timezone, now, hour);
                 requests_by_hour.push(TimeSeriesPoint { label, value: v });
             }
         }
@@ -862,33 +1053,15 @@ pub async fn sync_usage_from_proxy(state: State<'_, AppState>) -> Result<Request
         }
     }
 
-    // Update local history with synced data - use max() to never lose all-time data
+    // Update aggregate with token data from proxy - use max() to never lose all-time data.
     // The proxy reports cumulative session totals which reset on restart,
     // so we must never let a fresh session zero out persisted all-time data.
-    let mut history = load_request_history();
-    history.total_tokens_in = history.total_tokens_in.max(total_input);
-    history.total_tokens_out = history.total_tokens_out.max(total_output);
-    history.total_tokens_cached = history.total_tokens_cached.max(total_cached);
-    history.total_cost_usd = if total_cost > history.total_cost_usd {
-        total_cost
-    } else {
-        history.total_cost_usd
-    };
-    history.tokens_by_day = tokens_by_day.clone();
-    history.tokens_by_hour = tokens_by_hour.clone();
-
-    // Save updated history
-    save_request_history(&history)?;
-
-    // Also update aggregate with token data from proxy - use max() to preserve all-time
     let mut agg = load_aggregate();
     agg.total_tokens_in = agg.total_tokens_in.max(total_input);
     agg.total_tokens_out = agg.total_tokens_out.max(total_output);
-    agg.total_cost_usd = if total_cost > agg.total_cost_usd {
-        total_cost
-    } else {
-        agg.total_cost_usd
-    };
+    // The ledger holds every request the log watcher saw; the proxy's session
+    // estimate only wins while request logging is off
+    agg.total_cost_usd = cost_ledger::total_usd(&agg.cost_ledger).max(total_cost);
     // Merge tokens_by_day into aggregate (proxy is source of truth for tokens)
     for point in &tokens_by_day {
         if let Some(existing) = agg
@@ -932,12 +1105,6 @@ pub async fn sync_usage_from_proxy(state: State<'_, AppState>) -> Result<Request
     }
     agg.requests_by_hour
         .sort_by(|a, b| a.label.cmp(&b.label));
-    // Trim to last 168 hours (7 days)
-    if agg.requests_by_hour.len() > 168 {
-        agg.requests_by_hour = agg
-            .requests_by_hour
-            .split_off(agg.requests_by_hour.len() - 168);
-    }
 
     // Merge tokens_by_hour into aggregate
     for point in &tokens_by_hour {
@@ -952,12 +1119,6 @@ pub async fn sync_usage_from_proxy(state: State<'_, AppState>) -> Result<Request
         }
     }
     agg.tokens_by_hour.sort_by(|a, b| a.label.cmp(&b.label));
-    // Trim to last 168 hours (7 days)
-    if agg.tokens_by_hour.len() > 168 {
-        agg.tokens_by_hour = agg
-            .tokens_by_hour
-            .split_off(agg.tokens_by_hour.len() - 168);
-    }
 
     // Update total_requests from proxy data if available
     if !requests_by_day.is_empty() {
@@ -1033,43 +1194,23 @@ pub async fn sync_usage_from_proxy(state: State<'_, AppState>) -> Result<Request
 
     let _ = save_aggregate(&agg);
 
-    Ok(history)
+    Ok(request_history_view(state.history.snapshot(), &agg))
 }
 
 // Export usage statistics from CLIProxyAPI for backup
 #[tauri::command]
 pub async fn export_usage_stats(
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, CommandError> {
     let port = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         config.port
     };
 
-    let client = crate::build_management_client();
-    let export_url = format!("http://127.0.0.1:{}/v0/management/usage/export", port);
-
-    let response = client
-        .get(&export_url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .timeout(std::time::Duration::from_secs(10))
-        .send()
+    let request = state.http.management(Method::GET, port, "usage/export");
+    management_json(request)
         .await
-        .map_err(|e| format!("Failed to export usage: {}. Is the proxy running?", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "Export API returned status: {}",
-            response.status()
-        ));
-    }
-
-    let body: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse export response: {}", e))?;
-
-    Ok(body)
+        .map_err(|e| CommandError::management("export usage", e))
 }
 
 // Import usage statistics into CLIProxyAPI from backup
@@ -1077,38 +1218,57 @@ pub async fn export_usage_stats(
 pub async fn import_usage_stats(
     state: State<'_, AppState>,
     data: serde_json::Value,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, CommandError> {
     let port = {
-        let config = state.config.lock().unwrap();
+        let config = state.config.lock();
         config.port
     };
 
-    let client = crate::build_management_client();
-    let import_url = format!("http://127.0.0.1:{}/v0/management/usage/import", port);
-
-    let response = client
-        .post(&import_url)
-        .header("X-Management-Key", &crate::get_management_key())
-        .header("Content-Type", "application/json")
+    let request = state
+        .http
+        .management(Method::POST, port, "usage/import")
         .json(&data)
-        .timeout(std::time::Duration::from_secs(30))
-        .send()
+        .timeout(std::time::Duration::from_secs(30));
+    management_json(request)
         .await
-        .map_err(|e| format!("Failed to import usage: {}. Is the proxy running?", e))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!(
-            "Import API returned status: {} - {}",
-            status, body
-        ));
-    }
+        .map_err(|e| CommandError::management("import usage", e))
+}
 
-    let body: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse import response: {}", e))?;
+// Import request history from a standalone CLIProxyAPI install's usage file
+#[tauri::command]
+pub fn import_cliproxy_usage(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<CliproxyImportReport, CommandError> {
+    use crate::helpers::cliproxy_import::{apply_import, filter_new, parse_usage_export};
+
+    let data = std::fs::read_to_string(&path)
+        .map_err(|e| CommandError::io(format!("Failed to read {}: {}", path, e)))?;
+    let json: serde_json::Value = serde_json::from_str(&data)
+        .map_err(|e| CommandError::invalid_input(format!("Invalid JSON in {}: {}", path, e)))?;
+    let (logs, warnings) = parse_usage_export(&json).map_err(CommandError::invalid_input)?;
 
-    Ok(body)
+    let mut agg = load_aggregate();
+    let report = state.history.update(|history| {
+        let (fresh, skipped) = filter_new(logs, history, agg.cliproxy_imported_range);
+        let mut report = CliproxyImportReport {
+            skipped,
+            warnings,
+            ..Default::default()
+        };
+        apply_import(&fresh, &mut agg, history, &mut report);
+        report
+    });
+    if report.imported > 0 {
+        save_aggregate(&agg).map_err(CommandError::io)?;
+        state.history.flush().map_err(CommandError::io)?;
+    }
+    println!(
+        "[ProxyPal] Imported {} CLIProxyAPI requests ({} skipped, {} malformed)",
+        report.imported,
+        report.skipped,
+        report.warnings.len()
+    );
+    Ok(report)
 }
+
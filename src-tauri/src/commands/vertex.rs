@@ -0,0 +1,217 @@
+//! Vertex service-account regions and model availability.
+
+use futures_util::future::join_all;
+use serde_json::Value;
+use tauri::State;
+
+use crate::error::{CommandError, ErrorCode};
+use crate::helpers::vertex;
+use crate::state::AppState;
+use crate::types::{VertexCapabilities, VertexModelAvailability, VertexRegionCapabilities};
+
+fn read_credential(project_id: &str) -> Result<Value, CommandError> {
+    let path = vertex::credential_path(project_id).map_err(CommandError::invalid_input)?;
+    let content = std::fs::read_to_string(&path).map_err(|_| {
+        CommandError::not_found(format!("No Vertex credential imported for {}", project_id))
+    })?;
+    serde_json::from_str(&content)
+        .map_err(|e| CommandError::invalid_input(format!("Invalid Vertex credential: {}", e)))
+}
+
+async fn get_json(
+    client: &reqwest::Client,
+    token: &str,
+    project_id: &str,
+    url: &str,
+) -> Result<Value, String> {
+    let response = client
+        .get(url)
+        .bearer_auth(token)
+        .header("x-goog-user-project", project_id)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("{} returned {}", url, status));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse {}: {}", url, e))
+}
+
+// Regions the project offers, then the Claude and Gemini models each lists.
+// Fails as soon as the account is refused a listing.
+async fn list_capabilities(
+    client: &reqwest::Client,
+    token: &str,
+    project_id: &str,
+) -> Result<Vec<VertexRegionCapabilities>, String> {
+    let url = format!(
+        "https://aiplatform.googleapis.com/v1/projects/{}/locations",
+        project_id
+    );
+    let locations = get_json(client, token, project_id, &url).await?;
+    let mut regions = vec!["global".to_string()];
+    regions.extend(
+        locations["locations"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|l| l["locationId"].as_str())
+            .filter(|id| vertex::MODEL_REGIONS.contains(id))
+            .map(String::from),
+    );
+
+    let listings = regions.iter().map(|region| async move {
+        let mut models = Vec::new();
+        for publisher in ["google", "anthropic"] {
+            let url = format!(
+                "https://{}/v1beta1/publishers/{}/models?pageSize=200",
+                vertex::host(region),
+                publisher
+            );
+            let listing = get_json(client, token, project_id, &url).await?;
+            models.extend(
+                vertex::parse_publisher_models(&listing)
+                    .into_iter()
+                    .map(|model| VertexModelAvailability {
+                        publisher: publisher.to_string(),
+                        model,
+                        available: true,
+                        detail: None,
+                    }),
+            );
+        }
+        Ok::<_, String>(VertexRegionCapabilities {
+            region: region.clone(),
+            models,
+            error: None,
+        })
+    });
+    join_all(listings).await.into_iter().collect()
+}
+
+async fn probe_region(
+    client: &reqwest::Client,
+    token: &str,
+    project_id: &str,
+    region: &str,
+) -> VertexRegionCapabilities {
+    let probes = vertex::PROBE_MODELS
+        .iter()
+        .map(|(publisher, model)| async move {
+            let (url, body) = vertex::probe_request(project_id, region, publisher, model);
+            let result = client
+                .post(&url)
+                .bearer_auth(token)
+                .json(&body)
+                .send()
+                .await;
+            let (available, detail) = match result {
+                Ok(response) => vertex::probe_outcome(response.status().as_u16()),
+                Err(e) => (false, Some(format!("Network error: {}", e))),
+            };
+            VertexModelAvailability {
+                publisher: publisher.to_string(),
+                model: model.to_string(),
+                available,
+                detail,
+            }
+        });
+    VertexRegionCapabilities {
+        region: region.to_string(),
+        models: join_all(probes).await,
+        error: None,
+    }
+}
+
+// Which regions and Claude/Gemini models the imported service account for
+// `project_id` can use. Accounts that may not list locations or models are
+// checked by probing a few known regions instead.
+#[tauri::command]
+pub async fn get_vertex_capabilities(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<VertexCapabilities, CommandError> {
+    let credential = read_credential(&project_id)?;
+    let account = vertex::service_account(&credential).map_err(CommandError::invalid_input)?;
+    let client = &state.http.external();
+    let token = vertex::access_token(client, &account)
+        .await
+        .map_err(|e| CommandError::new(ErrorCode::Upstream, e))?;
+
+    let (method, list_error, regions) = match list_capabilities(client, &token, &project_id).await {
+        Ok(regions) => ("list", None, regions),
+        Err(e) => {
+            let probes = vertex::PROBE_REGIONS
+                .iter()
+                .map(|region| probe_region(client, &token, &project_id, region));
+            ("probe", Some(e), join_all(probes).await)
+        }
+    };
+
+    let region = vertex::location(&credential)
+        .or_else(|| state.config.lock().vertex_regions.get(&project_id).cloned());
+    Ok(VertexCapabilities {
+        project_id,
+        client_email: account.client_email,
+        method: method.to_string(),
+        list_error,
+        region,
+        regions,
+    })
+}
+
+// Set the region the proxy sends this credential's requests to, or clear it
+// to use the proxy default (us-central1). The proxy picks up the change to
+// the credential file on its own.
+#[tauri::command]
+pub fn set_vertex_region(
+    state: State<'_, AppState>,
+    project_id: String,
+    region: Option<String>,
+) -> Result<(), CommandError> {
+    let region = region
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    if let Some(region) = &region {
+        if !vertex::valid_region(region) {
+            return Err(CommandError::invalid_input(format!(
+                "Invalid region: {}",
+                region
+            )));
+        }
+    }
+    let changes = vec![format!(
+        "vertexRegions.{}: {}",
+        project_id,
+        region.as_deref().unwrap_or(vertex::DEFAULT_REGION)
+    )];
+    let result = write_vertex_region(&state, &project_id, region);
+    crate::helpers::audit::record("set_vertex_region", changes, &result);
+    result
+}
+
+fn write_vertex_region(
+    state: &AppState,
+    project_id: &str,
+    region: Option<String>,
+) -> Result<(), CommandError> {
+    let mut credential = read_credential(project_id)?;
+    vertex::set_location(&mut credential, region.as_deref());
+    let path = vertex::credential_path(project_id).map_err(CommandError::invalid_input)?;
+    let data = serde_json::to_string_pretty(&credential)
+        .map_err(|e| format!("Failed to serialize credential: {}", e))?;
+    std::fs::write(&path, data)
+        .map_err(|e| CommandError::io(format!("Failed to save credential: {}", e)))?;
+    crate::helpers::permissions::restrict_after_write(&path);
+
+    let mut config = state.config.lock().clone();
+    match region {
+        Some(region) => config.vertex_regions.insert(project_id.to_string(), region),
+        None => config.vertex_regions.remove(project_id),
+    };
+    crate::commands::config::store_config(state, config).map_err(CommandError::io)
+}
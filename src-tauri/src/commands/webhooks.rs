@@ -0,0 +1,113 @@
+//! Webhook configuration and delivery log commands.
+
+use tauri::{AppHandle, State};
+
+use crate::config::save_config_to_file;
+use crate::error::CommandError;
+use crate::state::AppState;
+use crate::types::{WebhookConfig, WebhookDelivery, WEBHOOK_EVENTS};
+
+fn validate_webhook(webhook: &WebhookConfig) -> Result<(), String> {
+    let url = url::Url::parse(webhook.url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err("Webhook URL must use http or https".to_string());
+    }
+    if webhook.events.is_empty() {
+        return Err("Select at least one event".to_string());
+    }
+    if let Some(event) = webhook
+        .events
+        .iter()
+        .find(|e| !WEBHOOK_EVENTS.contains(&e.as_str()))
+    {
+        return Err(format!("Unknown webhook event: {}", event));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_webhooks(state: State<AppState>) -> Vec<WebhookConfig> {
+    state.config.lock().webhooks.clone()
+}
+
+// Add a webhook, or replace the one with the same id
+#[tauri::command]
+pub fn save_webhook(
+    state: State<AppState>,
+    mut webhook: WebhookConfig,
+) -> Result<Vec<WebhookConfig>, CommandError> {
+    validate_webhook(&webhook).map_err(CommandError::invalid_input)?;
+    webhook.url = webhook.url.trim().to_string();
+    if webhook.id.is_empty() {
+        webhook.id = uuid::Uuid::new_v4().to_string();
+    }
+
+    let mut config = state.config.lock();
+    if let Some(existing) = config.webhooks.iter_mut().find(|w| w.id == webhook.id) {
+        *existing = webhook;
+    } else {
+        config.webhooks.push(webhook);
+    }
+    save_config_to_file(&config).map_err(CommandError::io)?;
+    Ok(config.webhooks.clone())
+}
+
+#[tauri::command]
+pub fn delete_webhook(
+    state: State<AppState>,
+    id: String,
+) -> Result<Vec<WebhookConfig>, CommandError> {
+    let mut config = state.config.lock();
+    config.webhooks.retain(|w| w.id != id);
+    save_config_to_file(&config).map_err(CommandError::io)?;
+    Ok(config.webhooks.clone())
+}
+
+// Send a test payload to a webhook and report how it went
+#[tauri::command]
+pub async fn test_webhook(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<WebhookDelivery, CommandError> {
+    let webhook = state
+        .config
+        .lock()
+        .webhooks
+        .iter()
+        .find(|w| w.id == id)
+        .cloned()
+        .ok_or_else(|| CommandError::not_found(format!("Webhook not found: {}", id)))?;
+    Ok(crate::webhooks::send_test(&app, webhook).await)
+}
+
+// Recent webhook deliveries, newest first
+#[tauri::command]
+pub fn get_webhook_delivery_log(state: State<AppState>) -> Vec<WebhookDelivery> {
+    state.webhooks.lock().deliveries()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(url: &str, events: &[&str]) -> WebhookConfig {
+        WebhookConfig {
+            id: String::new(),
+            url: url.to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            secret: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn validates_url_and_events() {
+        assert!(
+            validate_webhook(&webhook("https://hooks.slack.com/x", &["proxy-crashed"])).is_ok()
+        );
+        assert!(validate_webhook(&webhook("ftp://example.com", &["proxy-crashed"])).is_err());
+        assert!(validate_webhook(&webhook("https://example.com", &[])).is_err());
+        assert!(validate_webhook(&webhook("https://example.com", &["nope"])).is_err());
+    }
+}
@@ -1,9 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::types::{
-    amp::generate_uuid, cloudflare::CloudflareConfig, AmpModelMapping, AmpOpenAIProvider,
-    ClaudeApiKey, CodexApiKey, CopilotConfig, GeminiApiKey, SshConfig, VertexApiKey,
+    amp::generate_uuid, cloudflare::CloudflareConfig, AgentModelPrefs, AmpModelMapping,
+    AmpOpenAIProvider, AmpPool, AuthStatus, ClaudeApiKey, ClientKey, CodexApiKey, CopilotConfig,
+    FailoverRule, GeminiApiKey, ModelCap, NetworkProxy, NotificationSettings, OnboardingState,
+    OpenAICompatibleApiKeyEntry, ProviderRateLimit, ProxySchedule, RequestWatch, RetiredProxyKey,
+    SshConfig, VertexApiKey, WebhookConfig, WindowGeometry,
 };
 
 /// App configuration persisted to config.json
@@ -16,7 +21,9 @@ pub struct AppConfig {
     #[serde(default)]
     pub debug: bool,
     #[serde(default)]
-    pub proxy_url: String,
+    pub demo_mode_enabled: bool, // Allow synthetic demo traffic in release builds
+    #[serde(default)]
+    pub proxy_url: String, // Upstream proxy the sidecar sends model traffic through; see network_proxy for ProxyPal's own requests
     #[serde(default)]
     pub proxy_username: String,
     #[serde(default)]
@@ -48,6 +55,8 @@ pub struct AppConfig {
     #[serde(default)]
     pub amp_openai_providers: Vec<AmpOpenAIProvider>,
     #[serde(default)]
+    pub amp_pools: Vec<AmpPool>, // Amp models shared between ordered groups of providers
+    #[serde(default)]
     pub amp_routing_mode: String,
     #[serde(default = "default_routing_strategy")]
     pub routing_strategy: String,
@@ -64,6 +73,12 @@ pub struct AppConfig {
     #[serde(default)]
     pub vertex_api_keys: Vec<VertexApiKey>,
     #[serde(default)]
+    pub deepseek_api_keys: Vec<OpenAICompatibleApiKeyEntry>,
+    #[serde(default)]
+    pub zhipu_api_keys: Vec<OpenAICompatibleApiKeyEntry>,
+    #[serde(default)]
+    pub vertex_regions: HashMap<String, String>, // Project id -> region for imported service accounts
+    #[serde(default)]
     pub thinking_budget_mode: String,
     #[serde(default)]
     pub thinking_budget_custom: u32,
@@ -73,6 +88,9 @@ pub struct AppConfig {
     pub reasoning_effort_level: String,
     #[serde(default = "default_close_to_tray")]
     pub close_to_tray: bool,
+    /// Set once the "still running in the tray" notification has been shown
+    #[serde(default)]
+    pub close_to_tray_notice_shown: bool,
     #[serde(default)]
     pub max_retry_interval: i32,
     #[serde(default = "default_proxy_api_key")]
@@ -93,6 +111,168 @@ pub struct AppConfig {
     pub cloudflare_configs: Vec<CloudflareConfig>,
     #[serde(default = "default_disable_control_panel")]
     pub disable_control_panel: bool,
+    #[serde(default)]
+    pub agent_model_preferences: HashMap<String, AgentModelPrefs>,
+    #[serde(default)]
+    pub stale_agent_configs: Vec<String>, // Agent ids whose written config predates a preference change
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    #[serde(default)]
+    pub tray_show_stats: bool, // Show "$1.23 • 87 req" next to the tray icon (macOS/Linux)
+    #[serde(default = "default_tray_stats_refresh_secs")]
+    pub tray_stats_refresh_secs: u64,
+    #[serde(default = "default_tray_icon_style")]
+    pub tray_icon_style: String, // "auto" | "color" | "monochrome" (template image on macOS)
+    #[serde(default)]
+    pub onboarding: OnboardingState,
+    #[serde(default)]
+    pub metrics_port: Option<u16>, // Prometheus /metrics listener, disabled when unset
+    #[serde(default)]
+    pub metrics_allow_remote: bool, // Bind metrics on all interfaces instead of 127.0.0.1
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default)]
+    pub event_stream_port: Option<u16>, // Local WebSocket event stream, disabled when unset
+    #[serde(default)]
+    pub status_file_enabled: bool,
+    #[serde(default)]
+    pub status_file_path: Option<String>, // Defaults to <config_dir>/status.json
+    #[serde(default = "default_status_file_format")]
+    pub status_file_format: String, // "json" | "plain"
+    #[serde(default)]
+    pub mcp_server_enabled: bool, // Start the MCP introspection server at launch
+    #[serde(default)]
+    pub control_api_port: Option<u16>, // Local JSON control API for launchers, disabled when unset
+    #[serde(default)]
+    pub control_socket_enabled: bool, // JSON-RPC control socket (<config_dir>/control.sock, a named pipe on Windows) for scripts
+    #[serde(default)]
+    pub management_allow_remote: bool, // Accept Management API calls from other hosts (needs a rotated key)
+    #[serde(default = "default_management_bind")]
+    pub management_bind: String, // Address the Management API listens on when it has its own port
+    #[serde(default)]
+    pub proxy_bind: String, // Address the proxy listens on; empty for all interfaces
+    #[serde(default)]
+    pub management_port: Option<u16>, // Serve the Management API on its own port instead of the proxy's
+    #[serde(default)]
+    pub model_catalog_url: Option<String>, // Where refresh_model_catalog downloads model metadata from
+    #[serde(default)]
+    pub network_proxy: NetworkProxy, // Proxy for ProxyPal's own outbound requests (updates, quota, webhooks)
+    #[serde(default)]
+    pub retired_proxy_api_keys: Vec<RetiredProxyKey>, // Rotated-out keys still accepted until they expire
+    #[serde(default = "default_proxy_key_grace_minutes")]
+    pub proxy_key_grace_minutes: u64, // How long a rotated-out key keeps working (0 = revoke at once)
+    #[serde(default)]
+    pub client_keys: Vec<ClientKey>, // Extra proxy API keys issued per agent or machine, revocable one by one
+    #[serde(default)]
+    pub legacy_request_log_events: bool, // Also emit one request-log event per request besides request-log-batch
+    #[serde(default = "default_probe_mode")]
+    pub probe_mode: String, // "passive" (judge providers by recent requests) | "active" (1-token probe per provider)
+    #[serde(default = "default_health_check_interval_secs")]
+    pub health_check_interval_secs: u64, // Background provider health checks while the proxy runs (0 = off)
+    #[serde(default = "default_history_max_entries")]
+    pub history_max_entries: usize, // Newest requests kept in history.json (0 = no limit)
+    #[serde(default)]
+    pub history_max_age_days: u32, // Drop history entries older than this (0 = no age limit)
+    #[serde(default = "default_hourly_series_days")]
+    pub hourly_series_days: u32, // Days the aggregate keeps per-hour points; older ones live on per day, then per month
+    #[serde(default = "default_usage_timezone")]
+    pub usage_timezone: String, // "local" | "utc": zone the proxy's per-day usage keys and "today" use
+    #[serde(default = "default_cost_currency")]
+    pub cost_currency: String, // ISO code costs are shown in, e.g. "EUR"
+    #[serde(default = "default_cost_currency_rate")]
+    pub cost_currency_rate: f64, // Units of cost_currency per US dollar (fixed, never fetched)
+    #[serde(default)]
+    pub failover_rules: Vec<FailoverRule>, // Switch Amp's models to a backup provider while one is unhealthy (Amp routes only)
+    #[serde(default)]
+    pub schedule: Option<ProxySchedule>, // Weekly windows the proxy runs in; stopped outside them
+    #[serde(default)]
+    pub pause_on_sleep: bool, // Drain and pause the proxy before system sleep, resume on wake (Linux)
+    #[serde(default)]
+    pub model_caps: Vec<ModelCap>, // Daily request/token limits per model, with an optional fallback model for Amp requests
+    #[serde(default)]
+    pub request_watches: Vec<RequestWatch>, // Filters over live traffic that report (and optionally notify) matches
+    #[serde(default)]
+    pub provider_rate_limits: Vec<ProviderRateLimit>, // Soft requests-per-minute limits, warned about when exceeded
+    #[serde(default)]
+    pub main_window: Option<WindowGeometry>, // Restored on launch when it is still on a screen
+    #[serde(default)]
+    pub mini_monitor_window: Option<WindowGeometry>, // Same for the always-on-top mini monitor
+    #[serde(default = "default_auto_check_app_updates")]
+    pub auto_check_app_updates: bool, // Look for a new ProxyPal release once a day
+    #[serde(default)]
+    pub last_app_update_check: Option<i64>, // Unix seconds of the last successful check
+    #[serde(default)]
+    pub dismissed_app_version: Option<String>, // Release the user chose to skip; not announced again
+    #[serde(default)]
+    pub data_dir: Option<String>, // History, logs, captures and benchmarks kept here instead of the config dir; changed by migrate_data_dir
+    #[serde(default)]
+    pub idle_alert_minutes: u32, // Warn when agents are configured but no request arrives for this long while the proxy runs (0 = off)
+    #[serde(default = "default_usage_anomaly_multiplier")]
+    pub usage_anomaly_multiplier: f64, // Alert when today's spend reaches this many times the usual by the same hour (0 = off)
+}
+
+fn default_usage_anomaly_multiplier() -> f64 {
+    4.0
+}
+
+fn default_auto_check_app_updates() -> bool {
+    true
+}
+
+fn default_cost_currency() -> String {
+    "USD".to_string()
+}
+
+fn default_cost_currency_rate() -> f64 {
+    1.0
+}
+
+fn default_hourly_series_days() -> u32 {
+    crate::helpers::aggregate_compaction::DEFAULT_HOURLY_DAYS
+}
+
+fn default_usage_timezone() -> String {
+    "local".to_string()
+}
+
+fn default_history_max_entries() -> usize {
+    500
+}
+
+fn default_health_check_interval_secs() -> u64 {
+    60
+}
+
+fn default_probe_mode() -> String {
+    "passive".to_string()
+}
+
+fn default_proxy_key_grace_minutes() -> u64 {
+    60
+}
+
+/// Port the proxy listens on unless configured otherwise. On first run a
+/// taken default moves to the next free port (see [`pick_first_run_port`]).
+pub const DEFAULT_PORT: u16 = 8317;
+
+/// Default management key, identical on every install; remote management is
+/// refused while it is still in use
+pub(crate) const DEFAULT_MANAGEMENT_KEY: &str = "proxypal-mgmt-key";
+
+fn default_management_bind() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_status_file_format() -> String {
+    "json".to_string()
+}
+
+fn default_tray_icon_style() -> String {
+    "auto".to_string()
+}
+
+fn default_tray_stats_refresh_secs() -> u64 {
+    60
 }
 
 fn default_disable_control_panel() -> bool {
@@ -100,7 +280,7 @@ fn default_disable_control_panel() -> bool {
 }
 
 fn default_management_key() -> String {
-    "proxypal-mgmt-key".to_string()
+    DEFAULT_MANAGEMENT_KEY.to_string()
 }
 
 fn default_proxy_api_key() -> String {
@@ -119,6 +299,9 @@ fn default_logs_max_total_size_mb() -> u32 {
     100
 }
 
+// Version 2 added Amp pools
+pub(crate) const CONFIG_VERSION: u8 = 2;
+
 fn default_config_version() -> u8 {
     1
 }
@@ -135,13 +318,36 @@ fn default_locale() -> String {
     "en".to_string()
 }
 
+impl AppConfig {
+    /// Keys of a key-only registry provider, None for other providers
+    pub fn provider_api_keys(&self, provider: &str) -> Option<&Vec<OpenAICompatibleApiKeyEntry>> {
+        match provider {
+            "deepseek" => Some(&self.deepseek_api_keys),
+            "zhipu" => Some(&self.zhipu_api_keys),
+            _ => None,
+        }
+    }
+
+    pub fn provider_api_keys_mut(
+        &mut self,
+        provider: &str,
+    ) -> Option<&mut Vec<OpenAICompatibleApiKeyEntry>> {
+        match provider {
+            "deepseek" => Some(&mut self.deepseek_api_keys),
+            "zhipu" => Some(&mut self.zhipu_api_keys),
+            _ => None,
+        }
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
-            port: 8317,
+            port: DEFAULT_PORT,
             auto_start: true,
             launch_at_login: false,
             debug: false,
+            demo_mode_enabled: false,
             proxy_url: String::new(),
             proxy_username: String::new(),
             proxy_password: String::new(),
@@ -153,11 +359,12 @@ impl Default for AppConfig {
             request_logging: true,
             logging_to_file: true,
             logs_max_total_size_mb: 100,
-            config_version: 1,
+            config_version: CONFIG_VERSION,
             amp_api_key: String::new(),
             amp_model_mappings: Vec::new(),
             amp_openai_provider: None,
             amp_openai_providers: Vec::new(),
+            amp_pools: Vec::new(),
             amp_routing_mode: "mappings".to_string(),
             sidebar_pinned: false,
             routing_strategy: "round-robin".to_string(),
@@ -167,34 +374,81 @@ impl Default for AppConfig {
             gemini_api_keys: Vec::new(),
             codex_api_keys: Vec::new(),
             vertex_api_keys: Vec::new(),
+            deepseek_api_keys: Vec::new(),
+            zhipu_api_keys: Vec::new(),
+            vertex_regions: HashMap::new(),
             thinking_budget_mode: "medium".to_string(),
             thinking_budget_custom: 16000,
             gemini_thinking_injection: true,
             reasoning_effort_level: "medium".to_string(),
             close_to_tray: true,
+            close_to_tray_notice_shown: false,
             max_retry_interval: 0,
             proxy_api_key: "proxypal-local".to_string(),
-            management_key: "proxypal-mgmt-key".to_string(),
+            management_key: default_management_key(),
             commercial_mode: false,
             ws_auth: false,
             locale: "en".to_string(),
             ssh_configs: Vec::new(),
             cloudflare_configs: Vec::new(),
             disable_control_panel: true,
+            agent_model_preferences: HashMap::new(),
+            stale_agent_configs: Vec::new(),
+            notifications: NotificationSettings::default(),
+            tray_show_stats: false,
+            tray_stats_refresh_secs: default_tray_stats_refresh_secs(),
+            tray_icon_style: default_tray_icon_style(),
+            onboarding: OnboardingState::default(),
+            metrics_port: None,
+            metrics_allow_remote: false,
+            webhooks: Vec::new(),
+            event_stream_port: None,
+            status_file_enabled: false,
+            status_file_path: None,
+            status_file_format: default_status_file_format(),
+            mcp_server_enabled: false,
+            control_api_port: None,
+            control_socket_enabled: false,
+            management_allow_remote: false,
+            management_bind: default_management_bind(),
+            proxy_bind: String::new(),
+            management_port: None,
+            model_catalog_url: None,
+            network_proxy: NetworkProxy::default(),
+            retired_proxy_api_keys: Vec::new(),
+            proxy_key_grace_minutes: default_proxy_key_grace_minutes(),
+            client_keys: Vec::new(),
+            legacy_request_log_events: false,
+            probe_mode: default_probe_mode(),
+            health_check_interval_secs: default_health_check_interval_secs(),
+            history_max_entries: default_history_max_entries(),
+            history_max_age_days: 0,
+            hourly_series_days: default_hourly_series_days(),
+            usage_timezone: default_usage_timezone(),
+            cost_currency: default_cost_currency(),
+            cost_currency_rate: default_cost_currency_rate(),
+            failover_rules: Vec::new(),
+            schedule: None,
+            pause_on_sleep: false,
+            model_caps: Vec::new(),
+            request_watches: Vec::new(),
+            provider_rate_limits: Vec::new(),
+            main_window: None,
+            mini_monitor_window: None,
+            auto_check_app_updates: true,
+            last_app_update_check: None,
+            dismissed_app_version: None,
+            data_dir: None,
+            idle_alert_minutes: 0,
+            usage_anomaly_multiplier: default_usage_anomaly_multiplier(),
         }
     }
 }
 
 /// Get the proxypal config directory, creating it if needed
 pub fn get_proxypal_config_dir() -> std::path::PathBuf {
-    let config_dir = dirs::config_dir()
-        .unwrap_or_else(|| {
-            eprintln!(
-                "[ProxyPal] Warning: Could not determine config directory, using current directory"
-            );
-            std::path::PathBuf::from(".")
-        })
-        .join("proxypal");
+    // The usual config dir, or a fallback when it isn't writable
+    let config_dir = crate::helpers::storage::location().dir.clone();
 
     if let Err(e) = std::fs::create_dir_all(&config_dir) {
         eprintln!(
@@ -217,14 +471,28 @@ pub fn get_auth_path() -> std::path::PathBuf {
     get_proxypal_config_dir().join("auth.json")
 }
 
+/// Get the directory for history, logs, captures and benchmarks, creating it
+/// if needed (the config dir unless `data_dir` is set)
+pub fn get_data_dir() -> std::path::PathBuf {
+    let data_dir = crate::helpers::paths::data_dir();
+    if let Err(e) = std::fs::create_dir_all(&data_dir) {
+        eprintln!(
+            "[ProxyPal] Error: Failed to create data directory '{}': {}",
+            data_dir.display(),
+            e
+        );
+    }
+    data_dir
+}
+
 /// Request history file path
 pub fn get_history_path() -> std::path::PathBuf {
-    get_proxypal_config_dir().join("history.json")
+    get_data_dir().join("history.json")
 }
 
 /// Aggregate analytics file path (cumulative stats, never trimmed)
 pub fn get_aggregate_path() -> std::path::PathBuf {
-    get_proxypal_config_dir().join("aggregate.json")
+    get_data_dir().join("aggregate.json")
 }
 
 /// Load config from file
@@ -232,7 +500,49 @@ pub fn load_config() -> AppConfig {
     load_config_from_path(&get_config_path())
 }
 
+/// How far past [`DEFAULT_PORT`] a first run looks for a free port
+const FIRST_RUN_PORT_SPAN: u16 = 100;
+
+fn port_is_free(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+// The first port from `start` that is free and not claimed by another listener
+fn next_free_port(start: u16, claimed: &[u16], is_free: impl Fn(u16) -> bool) -> Option<u16> {
+    (start..start.saturating_add(FIRST_RUN_PORT_SPAN)).find(|p| !claimed.contains(p) && is_free(*p))
+}
+
+/// On first run, move the proxy off [`DEFAULT_PORT`] when another program
+/// holds it, and save the choice so agents are set up with it from the start.
+/// Returns the port moved to.
+pub(crate) fn pick_first_run_port(config: &mut AppConfig) -> Option<u16> {
+    // An imported config chose its own port
+    if config.port != DEFAULT_PORT || port_is_free(DEFAULT_PORT) {
+        return None;
+    }
+    let claimed: Vec<u16> = [
+        config.metrics_port,
+        config.event_stream_port,
+        config.control_api_port,
+        config.management_port,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let port = next_free_port(DEFAULT_PORT + 1, &claimed, port_is_free)?;
+    config.port = port;
+    if let Err(e) = save_startup_config(config) {
+        eprintln!("[ProxyPal] Failed to save the first-run port: {}", e);
+    }
+    println!(
+        "[ProxyPal] Port {} is taken by another program; using {}",
+        DEFAULT_PORT, port
+    );
+    Some(port)
+}
+
 fn migrate_config(config: &mut AppConfig) -> bool {
+    let mut migrated = false;
     if let Some(old_provider) = config.amp_openai_provider.take() {
         if config.amp_openai_providers.is_empty() {
             eprintln!("[ProxyPal] Migrating config from old provider format to array format...");
@@ -254,40 +564,49 @@ fn migrate_config(config: &mut AppConfig) -> bool {
             };
             config.amp_openai_providers.push(provider_with_id);
             eprintln!("[ProxyPal] Config migration complete");
-            return true;
+            migrated = true;
+        }
+    }
+
+    // Flat Amp mappings whose target several custom providers serve become pools
+    if config.config_version < 2 {
+        if config.amp_pools.is_empty() {
+            let (pools, remaining) = crate::helpers::amp_pools::pools_from_mappings(
+                &config.amp_model_mappings,
+                &config.amp_openai_providers,
+            );
+            if !pools.is_empty() {
+                eprintln!(
+                    "[ProxyPal] Migrating {} shared Amp mapping target(s) to pools",
+                    pools.len()
+                );
+                config.amp_pools = pools;
+                config.amp_model_mappings = remaining;
+            }
         }
+        config.config_version = CONFIG_VERSION;
+        migrated = true;
     }
 
-    false
+    migrated
 }
 
-fn load_config_from_path(path: &Path) -> AppConfig {
-    if !path.exists() {
-        return AppConfig::default();
-    }
+// Settings of a damaged config.json that still fit
+fn recover_config(damaged: &serde_json::Value) -> Option<(AppConfig, String)> {
+    let (config, kept, total) = crate::helpers::recovery::fitting_members::<AppConfig>(damaged)?;
+    (kept > 0).then(|| (config, format!("kept {} of {} settings", kept, total)))
+}
 
-    let data = match std::fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(e) => {
-            eprintln!(
-                "[ProxyPal] Failed to read config file '{}': {}. Falling back to defaults.",
-                path.display(),
-                e
-            );
-            return AppConfig::default();
-        }
-    };
+// Provider counts of a damaged auth.json that still fit
+fn recover_auth(damaged: &serde_json::Value) -> Option<(AuthStatus, String)> {
+    let (auth, kept, total) = crate::helpers::recovery::fitting_members::<AuthStatus>(damaged)?;
+    (kept > 0).then(|| (auth, format!("kept {} of {} entries", kept, total)))
+}
 
-    let mut config = match serde_json::from_str::<AppConfig>(&data) {
-        Ok(config) => config,
-        Err(e) => {
-            eprintln!(
-                "[ProxyPal] Failed to parse config file '{}': {}. Falling back to defaults.",
-                path.display(),
-                e
-            );
-            return AppConfig::default();
-        }
+fn load_config_from_path(path: &Path) -> AppConfig {
+    // A damaged file is moved aside; its readable settings are kept
+    let Some(mut config) = crate::helpers::recovery::load_json(path, recover_config) else {
+        return AppConfig::default();
     };
 
     if migrate_config(&mut config) {
@@ -297,10 +616,57 @@ fn load_config_from_path(path: &Path) -> AppConfig {
     config
 }
 
+/// Load auth status from file
+pub(crate) fn load_auth_status() -> AuthStatus {
+    load_auth_status_from_path(&get_auth_path())
+}
+
+fn load_auth_status_from_path(path: &Path) -> AuthStatus {
+    crate::helpers::recovery::load_json(path, recover_auth).unwrap_or_default()
+}
+
+/// Save auth status to file
+pub(crate) fn save_auth_to_file(auth: &AuthStatus) -> Result<(), String> {
+    let path = get_auth_path();
+    let data = serde_json::to_string_pretty(auth).map_err(|e| e.to_string())?;
+    std::fs::write(&path, data).map_err(|e| e.to_string())?;
+    crate::helpers::permissions::restrict_after_write(&path);
+    Ok(())
+}
+
+/// Set once startup has put the saved config into AppState. Before that the
+/// in-memory config is the default one, and saving it would overwrite the
+/// user's settings.
+static HYDRATED: AtomicBool = AtomicBool::new(false);
+
+pub(crate) const STILL_LOADING: &str =
+    "ProxyPal is still loading its settings, try again in a moment";
+
+/// Allow config saves; called once the loaded config is in AppState
+pub(crate) fn mark_hydrated() {
+    HYDRATED.store(true, Ordering::Release);
+}
+
 /// Save config to file
-/// Uses atomic write (write to temp file then rename) to prevent corruption
+/// Uses atomic write (write to temp file then rename) to prevent corruption.
+/// Refused until startup has loaded the saved config.
 pub fn save_config_to_file(config: &AppConfig) -> Result<(), String> {
-    save_config_to_path(&get_config_path(), config)
+    if !HYDRATED.load(Ordering::Acquire) {
+        return Err(STILL_LOADING.to_string());
+    }
+    save_startup_config(config)
+}
+
+/// [`save_config_to_file`] for startup itself, which saves the config it just
+/// loaded (first-run import, a moved default port) before hydration finishes
+pub(crate) fn save_startup_config(config: &AppConfig) -> Result<(), String> {
+    crate::redact::set_known_secrets(config);
+    crate::helpers::history::set_history_retention(config);
+    crate::helpers::aggregate_compaction::set_hourly_days(config);
+    crate::helpers::usage_day::set_usage_timezone(config);
+    crate::helpers::paths::set_data_dir(config);
+    save_config_to_path(&get_config_path(), config)?;
+    Ok(())
 }
 
 pub(crate) fn save_config_to_path(path: &Path, config: &AppConfig) -> Result<(), String> {
@@ -349,6 +715,11 @@ pub(crate) fn save_config_to_path(path: &Path, config: &AppConfig) -> Result<(),
         ));
     }
 
+    // config.json holds API keys: keep it (and its directory) owner-only.
+    // The rename carries the temp file's permissions over.
+    crate::helpers::permissions::restrict_after_write(&temp_path);
+    let _ = crate::helpers::permissions::restrict(config_dir, true);
+
     // Atomic rename from temp to actual config file
     std::fs::rename(&temp_path, &path)
         .map_err(|e| format!("Failed to rename temp file to config: {}", e))?;
@@ -385,6 +756,26 @@ mod tests {
         let _ = fs::remove_dir_all(dir);
     }
 
+    #[test]
+    fn auth_status_without_key_only_providers_still_loads() {
+        let auth: AuthStatus = serde_json::from_str(
+            r#"{"claude":1,"openai":0,"gemini":2,"qwen":0,"iflow":0,"vertex":0,"kiro":0,"antigravity":0,"kimi":0}"#,
+        )
+        .unwrap();
+        assert_eq!(auth.count("gemini"), 2);
+        assert_eq!(auth.count("deepseek"), 0);
+        assert_eq!(auth.count("zhipu"), 0);
+    }
+
+    fn quarantined(dir: &Path, name: &str) -> Vec<String> {
+        fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|file| file.starts_with(&format!("{}.corrupt-", name)))
+            .collect()
+    }
+
     #[test]
     fn load_config_from_invalid_json_returns_defaults() {
         let dir = test_dir("config-invalid");
@@ -398,10 +789,103 @@ mod tests {
             loaded.routing_strategy,
             AppConfig::default().routing_strategy
         );
+        // The damaged file is kept aside, not overwritten later
+        assert!(!path.exists());
+        assert_eq!(quarantined(&dir, "config.json").len(), 1);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn load_config_keeps_the_intact_settings_of_a_truncated_file() {
+        let dir = test_dir("config-truncated");
+        let path = dir.join("config.json");
+        fs::write(
+            &path,
+            r#"{
+  "port": 9000,
+  "routingStrategy": 42,
+  "proxyApiKey": "sk-kept",
+  "debug": tr"#,
+        )
+        .unwrap();
+
+        let loaded = load_config_from_path(&path);
+        assert_eq!(loaded.port, 9000);
+        assert_eq!(loaded.proxy_api_key, "sk-kept");
+        // The field of the wrong type and the one cut off fall back
+        assert_eq!(
+            loaded.routing_strategy,
+            AppConfig::default().routing_strategy
+        );
+        assert_eq!(loaded.debug, AppConfig::default().debug);
+        assert_eq!(quarantined(&dir, "config.json").len(), 1);
+
+        // The recovered settings took the damaged file's place, so a second
+        // load reads them back instead of starting fresh
+        let reloaded = load_config_from_path(&path);
+        assert_eq!(reloaded.port, 9000);
+        assert_eq!(reloaded.proxy_api_key, "sk-kept");
+        assert_eq!(quarantined(&dir, "config.json").len(), 1);
 
         let _ = fs::remove_dir_all(dir);
     }
 
+    #[test]
+    fn load_auth_status_recovers_truncated_and_drops_garbage_files() {
+        let dir = test_dir("auth-damaged");
+        let path = dir.join("auth.json");
+        fs::write(&path, r#"{"claude":2,"openai":1,"gemini":"#).unwrap();
+        let auth = load_auth_status_from_path(&path);
+        assert_eq!((auth.claude, auth.openai, auth.gemini), (2, 1, 0));
+        assert_eq!(quarantined(&dir, "auth.json").len(), 1);
+
+        fs::write(&path, [0u8; 64]).unwrap();
+        let auth = load_auth_status_from_path(&path);
+        assert!(!auth.any_connected());
+        assert!(!path.exists());
+
+        // A missing file is a fresh start, not damage
+        assert!(!load_auth_status_from_path(&dir.join("missing.json")).any_connected());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn first_run_port_skips_taken_and_claimed_ports() {
+        let busy = [DEFAULT_PORT + 1, DEFAULT_PORT + 2];
+        let is_free = |port: u16| !busy.contains(&port);
+        assert_eq!(
+            next_free_port(DEFAULT_PORT + 1, &[DEFAULT_PORT + 3], is_free),
+            Some(DEFAULT_PORT + 4)
+        );
+        assert_eq!(next_free_port(DEFAULT_PORT + 1, &[], |_| false), None);
+    }
+
+    #[test]
+    fn agent_checks_never_name_the_default_port() {
+        // Checks compare against the configured port; only DEFAULT_PORT names it
+        let literal = DEFAULT_PORT.to_string();
+        for (file, source) in [
+            ("commands/agents.rs", include_str!("commands/agents.rs")),
+            (
+                "commands/agent_bundle.rs",
+                include_str!("commands/agent_bundle.rs"),
+            ),
+            (
+                "helpers/agent_endpoints.rs",
+                include_str!("helpers/agent_endpoints.rs"),
+            ),
+            (
+                "helpers/env_conflicts.rs",
+                include_str!("helpers/env_conflicts.rs"),
+            ),
+        ] {
+            let code = source.split("#[cfg(test)]").next().unwrap_or_default();
+            assert!(!code.contains(&literal), "{} names port {}", file, literal);
+        }
+    }
+
     #[test]
     fn load_config_migrates_deprecated_openai_provider() {
         let dir = test_dir("config-migrate");
@@ -437,4 +921,53 @@ mod tests {
 
         let _ = fs::remove_dir_all(dir);
     }
+
+    #[test]
+    fn load_config_turns_shared_amp_targets_into_pools() {
+        let dir = test_dir("config-amp-pools");
+        let path = dir.join("config.json");
+
+        let provider = |id: &str, model: &str| {
+            serde_json::json!({
+                "id": id,
+                "name": id,
+                "baseUrl": format!("https://{}.example.com/v1", id),
+                "apiKey": "key",
+                "models": [{ "name": model, "alias": "glm" }]
+            })
+        };
+        let legacy_json = serde_json::json!({
+            "port": 8317,
+            "autoStart": true,
+            "launchAtLogin": false,
+            "ampModelMappings": [
+                { "name": "claude-opus-4-5-20251101", "alias": "glm" },
+                { "name": "claude-haiku-4-5", "alias": "gpt-5-mini" }
+            ],
+            "ampOpenaiProviders": [provider("zai", "glm-4.6"), provider("openrouter", "z-ai/glm-4.6")]
+        });
+        fs::write(&path, legacy_json.to_string()).unwrap();
+        let loaded = load_config_from_path(&path);
+
+        assert_eq!(loaded.config_version, CONFIG_VERSION);
+        assert_eq!(loaded.amp_pools.len(), 1);
+        assert_eq!(loaded.amp_pools[0].routes, ["claude-opus-4-5-20251101"]);
+        assert_eq!(loaded.amp_pools[0].members.len(), 2);
+        assert_eq!(loaded.amp_model_mappings.len(), 1);
+        assert_eq!(loaded.amp_model_mappings[0].name, "claude-haiku-4-5");
+
+        // Migrated once: a config that already has the new version is left alone
+        let mut current = loaded.clone();
+        current.amp_pools.clear();
+        current.amp_model_mappings = vec![AmpModelMapping {
+            name: "claude-opus-4-5-20251101".to_string(),
+            alias: "glm".to_string(),
+            enabled: true,
+            fork: false,
+        }];
+        assert!(!migrate_config(&mut current));
+        assert!(current.amp_pools.is_empty());
+
+        let _ = fs::remove_dir_all(dir);
+    }
 }
@@ -0,0 +1,234 @@
+//! Local control API for launchers (Raycast, Alfred, shell scripts).
+//!
+//! When `AppConfig.control_api_port` is set, ProxyPal serves compact JSON on
+//! `http://127.0.0.1:<port>/` so external tools can integrate without the
+//! Tauri IPC. Every request must carry the management key, as
+//! `Authorization: Bearer <key>` or `X-Management-Key: <key>`.
+//!
+//! ```text
+//! GET  /status       ControlStatus
+//! GET  /usage        ControlUsage
+//! GET  /credentials  [ControlCredential]
+//! POST /start        ControlStatus
+//! POST /stop         ControlStatus
+//! POST /toggle       ControlStatus
+//! ```
+//!
+//! Errors are `{"error": "..."}` with a 4xx/5xx status. The schema lives in
+//! `types/control.rs`.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+use crate::types::{ControlCredential, ControlError, ControlStatus, ControlUsage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    Status,
+    Usage,
+    Credentials,
+    Start,
+    Stop,
+    Toggle,
+}
+
+fn route(method: &str, path: &str) -> Option<Route> {
+    // Ignore query strings and a trailing slash
+    let path = path.split('?').next().unwrap_or("").trim_end_matches('/');
+    match (method, path) {
+        ("GET", "/status") => Some(Route::Status),
+        ("GET", "/usage") => Some(Route::Usage),
+        ("GET", "/credentials") => Some(Route::Credentials),
+        ("POST", "/start") => Some(Route::Start),
+        ("POST", "/stop") => Some(Route::Stop),
+        ("POST", "/toggle") => Some(Route::Toggle),
+        _ => None,
+    }
+}
+
+fn header<'a>(request: &'a tiny_http::Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+fn presented_key(request: &tiny_http::Request) -> Option<String> {
+    header(request, "X-Management-Key")
+        .or_else(|| header(request, "Authorization").and_then(|v| v.strip_prefix("Bearer ")))
+        .map(|v| v.trim().to_string())
+}
+
+/// Proxy state in the control API's schema
+pub(crate) fn control_status(state: &AppState) -> ControlStatus {
+    let status = state.proxy_status.lock().clone();
+    let uptime = state
+        .proxy_started_at
+        .lock()
+        .map(|started| started.elapsed().as_secs());
+    ControlStatus::new(status, uptime)
+}
+
+/// Usage totals in the control API's schema
+pub(crate) async fn control_usage(state: &AppState) -> Result<ControlUsage, String> {
+    let stats = crate::commands::usage::compute_usage_stats(state).await?;
+    let (_, cost_today) = crate::helpers::history::today_stats(&state.history);
+    Ok(ControlUsage::new(&stats, cost_today))
+}
+
+/// Credential files in the control API's schema
+pub(crate) async fn control_credentials(
+    state: &AppState,
+) -> Result<Vec<ControlCredential>, String> {
+    let files = crate::commands::auth_files::list_auth_files(state).await?;
+    Ok(files.iter().map(ControlCredential::from).collect())
+}
+
+/// Start or stop the proxy as the UI would; returns the state afterwards
+pub(crate) async fn set_proxy_running(
+    app: &AppHandle,
+    running: bool,
+) -> Result<ControlStatus, String> {
+    if running {
+        crate::commands::proxy::start_proxy(app.clone(), app.state(), None).await?;
+    } else {
+        crate::commands::proxy::stop_proxy(app.clone(), app.state()).await?;
+    }
+    Ok(control_status(&app.state::<AppState>()))
+}
+
+fn json_response<T: Serialize>(
+    code: u16,
+    body: &T,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    tiny_http::Response::from_string(serde_json::to_string(body).unwrap_or_default())
+        .with_status_code(code)
+        .with_header(
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap(),
+        )
+}
+
+fn error_response(code: u16, message: &str) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    json_response(
+        code,
+        &ControlError {
+            error: message.to_string(),
+        },
+    )
+}
+
+// Runs on the listener thread, outside the async runtime, so blocking on the
+// command futures is fine
+fn handle(app: &AppHandle, route: Route) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let state = app.state::<AppState>();
+    let result = match route {
+        Route::Status => return json_response(200, &control_status(&state)),
+        Route::Usage => tauri::async_runtime::block_on(control_usage(&state))
+            .map(|usage| json_response(200, &usage)),
+        Route::Credentials => tauri::async_runtime::block_on(control_credentials(&state))
+            .map(|credentials| json_response(200, &credentials)),
+        Route::Start | Route::Stop | Route::Toggle => {
+            let running = match route {
+                Route::Start => true,
+                Route::Stop => false,
+                _ => !state.proxy_status.lock().running,
+            };
+            tauri::async_runtime::block_on(set_proxy_running(app, running))
+                .map(|status| json_response(200, &status))
+        }
+    };
+    result.unwrap_or_else(|e| error_response(500, &e))
+}
+
+/// Running control API listener
+pub struct ControlApiServer {
+    server: Arc<tiny_http::Server>,
+    port: u16,
+}
+
+impl ControlApiServer {
+    fn start(app: &AppHandle, port: u16) -> Result<Self, String> {
+        let server = Arc::new(
+            tiny_http::Server::http(("127.0.0.1", port))
+                .map_err(|e| format!("Failed to bind control API on port {}: {}", port, e))?,
+        );
+        let listener = server.clone();
+        let app = app.clone();
+        std::thread::spawn(move || {
+            for request in listener.incoming_requests() {
+                let expected = app
+                    .try_state::<AppState>()
+                    .map(|state| state.config.lock().management_key.clone())
+                    .unwrap_or_default();
+                let response = if expected.is_empty()
+                    || presented_key(&request).as_deref() != Some(expected.as_str())
+                {
+                    error_response(401, "Invalid or missing management key")
+                } else {
+                    match route(request.method().as_str(), request.url()) {
+                        Some(route) => handle(&app, route),
+                        None => error_response(404, "Not found"),
+                    }
+                };
+                let _ = request.respond(response);
+            }
+        });
+        println!(
+            "[ProxyPal] Control API available at http://127.0.0.1:{}/",
+            port
+        );
+        Ok(Self { server, port })
+    }
+
+    // Not joined: a start/stop in progress may be waiting on the main thread,
+    // which is where the settings save that stops us runs
+    fn stop(self) {
+        self.server.unblock();
+        println!("[ProxyPal] Control API on port {} stopped", self.port);
+    }
+}
+
+/// Start, restart or stop the listener to match the current config
+pub fn apply_control_api_config(app: &AppHandle) -> Result<(), String> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+    let wanted = state.config.lock().control_api_port;
+    let mut current = state.control_api.lock();
+    if current.as_ref().map(|s| s.port) == wanted {
+        return Ok(());
+    }
+    if let Some(server) = current.take() {
+        server.stop();
+    }
+    if let Some(port) = wanted {
+        *current = Some(ControlApiServer::start(app, port)?);
+    }
+    Ok(())
+}
+
+/// Shut the listener down on app exit
+pub fn stop_control_api(state: &AppState) {
+    if let Some(server) = state.control_api.lock().take() {
+        server.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_by_method_and_path() {
+        assert_eq!(route("GET", "/status"), Some(Route::Status));
+        assert_eq!(route("GET", "/status/?format=json"), Some(Route::Status));
+        assert_eq!(route("POST", "/toggle"), Some(Route::Toggle));
+        // Actions must not be triggerable by a plain GET
+        assert_eq!(route("GET", "/stop"), None);
+        assert_eq!(route("GET", "/"), None);
+    }
+}
@@ -0,0 +1,531 @@
+//! JSON-RPC control socket for scripts and external tooling.
+//!
+//! Deep links start an action but return nothing. When
+//! `AppConfig.control_socket_enabled` is set, ProxyPal also listens on
+//! `<config_dir>/control.sock` (the `\\.\pipe\proxypal-control` named pipe on
+//! Windows), accessible to the current user only. Messages are
+//! newline-delimited JSON-RPC 2.0, as for the MCP server, and every client is
+//! served on its own task, so several can stay connected at once.
+//!
+//! A connection starts with `hello`, naming the protocol version the client
+//! speaks and presenting the management key. A wrong key closes the
+//! connection.
+//!
+//! ```text
+//! hello            {protocolVersion, managementKey}  {protocolVersion, serverVersion, methods}
+//! getStatus                                          ControlStatus
+//! startProxy                                         ControlStatus
+//! stopProxy                                          ControlStatus
+//! getUsageSummary                                    ControlUsage
+//! listCredentials                                    [ControlCredential]
+//! configureAgent   {agentId}                         result of configure_cli_agent
+//! ```
+//!
+//! Results share the control API's schema (`types/control.rs`), and the
+//! actions go through the same functions as the control API and the UI.
+//!
+//! Headless mode always serves this protocol at the same place, without
+//! `configureAgent`, and `proxypal status|start|stop` are clients of it; see
+//! `headless.rs`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::watch;
+
+use crate::state::AppState;
+
+#[cfg(windows)]
+pub const PIPE_NAME: &str = r"\\.\pipe\proxypal-control";
+
+/// Protocol versions this build speaks. Bumped only for changes that would
+/// break existing clients; new methods and result fields don't count.
+pub(crate) const PROTOCOL_VERSIONS: &[u64] = &[1];
+
+/// Methods available after `hello`
+const METHODS: &[&str] = &[
+    "getStatus",
+    "startProxy",
+    "stopProxy",
+    "getUsageSummary",
+    "listCredentials",
+    "configureAgent",
+];
+
+// JSON-RPC error codes, and ours from the implementation-defined range
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+const NOT_AUTHORIZED: i64 = -32001;
+const UNSUPPORTED_VERSION: i64 = -32002;
+
+/// Result of a method, or a JSON-RPC error code and message
+pub(crate) type Reply = Result<Value, (i64, String)>;
+
+pub(crate) type ReplyFuture<'a> = Pin<Box<dyn Future<Output = Reply> + Send + 'a>>;
+
+/// What connections act on: the app, a headless instance, or a stand-in in
+/// tests
+pub(crate) trait Backend: Send + Sync + 'static {
+    /// Key `hello` must present; empty refuses every client
+    fn management_key(&self) -> String;
+    /// Methods available after `hello`
+    fn methods(&self) -> &'static [&'static str] {
+        METHODS
+    }
+    /// Run one of [`Backend::methods`]
+    fn call<'a>(&'a self, method: &'a str, params: Value) -> ReplyFuture<'a>;
+}
+
+struct AppBackend(AppHandle);
+
+pub(crate) fn server_error(message: String) -> (i64, String) {
+    (SERVER_ERROR, message)
+}
+
+pub(crate) fn to_value<T: serde::Serialize>(value: T) -> Reply {
+    serde_json::to_value(value).map_err(|e| server_error(e.to_string()))
+}
+
+impl Backend for AppBackend {
+    fn management_key(&self) -> String {
+        self.0
+            .try_state::<AppState>()
+            .map(|state| state.config.lock().management_key.clone())
+            .unwrap_or_default()
+    }
+
+    fn call<'a>(&'a self, method: &'a str, params: Value) -> ReplyFuture<'a> {
+        let app = &self.0;
+        Box::pin(async move {
+            match method {
+                "getStatus" => {
+                    to_value(crate::control_api::control_status(&app.state::<AppState>()))
+                }
+                "startProxy" | "stopProxy" => {
+                    let status =
+                        crate::control_api::set_proxy_running(app, method == "startProxy").await;
+                    to_value(status.map_err(server_error)?)
+                }
+                "getUsageSummary" => {
+                    let usage = crate::control_api::control_usage(&app.state::<AppState>()).await;
+                    to_value(usage.map_err(server_error)?)
+                }
+                "listCredentials" => {
+                    let credentials =
+                        crate::control_api::control_credentials(&app.state::<AppState>()).await;
+                    to_value(credentials.map_err(server_error)?)
+                }
+                "configureAgent" => {
+                    let Some(agent_id) = params.get("agentId").and_then(Value::as_str) else {
+                        return Err((
+                            INVALID_PARAMS,
+                            "configureAgent needs an agentId".to_string(),
+                        ));
+                    };
+                    crate::commands::agents::configure_cli_agent(
+                        app.state(),
+                        agent_id.to_string(),
+                        Vec::new(),
+                        None,
+                        None,
+                    )
+                    .await
+                    .map_err(|e| server_error(e.into()))
+                }
+                other => Err((METHOD_NOT_FOUND, format!("Method not found: {}", other))),
+            }
+        })
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+    .to_string()
+}
+
+/// Where one connection stands
+#[derive(Default)]
+struct Session {
+    authenticated: bool,
+    // A failed hello; the connection is dropped after the reply
+    closed: bool,
+}
+
+fn hello(backend: &impl Backend, session: &mut Session, params: &Value) -> Reply {
+    let version = params.get("protocolVersion").and_then(Value::as_u64);
+    let Some(version) = version.filter(|v| PROTOCOL_VERSIONS.contains(v)) else {
+        return Err((
+            UNSUPPORTED_VERSION,
+            format!(
+                "Unsupported protocol version; this ProxyPal speaks {:?}",
+                PROTOCOL_VERSIONS
+            ),
+        ));
+    };
+    let expected = backend.management_key();
+    let presented = params.get("managementKey").and_then(Value::as_str);
+    if expected.is_empty() || presented != Some(expected.as_str()) {
+        session.closed = true;
+        return Err((NOT_AUTHORIZED, "Invalid management key".to_string()));
+    }
+    session.authenticated = true;
+    Ok(json!({
+        "protocolVersion": version,
+        "serverVersion": env!("CARGO_PKG_VERSION"),
+        "methods": backend.methods(),
+    }))
+}
+
+/// Handle one message. Returns the response line, or None for
+/// notifications, which get no reply.
+async fn handle_message(
+    backend: &impl Backend,
+    session: &mut Session,
+    line: &str,
+) -> Option<String> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+    let id = message.get("id").cloned()?;
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error_response(id, INVALID_REQUEST, "Missing method"));
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let reply = if method == "hello" {
+        hello(backend, session, &params)
+    } else if !session.authenticated {
+        Err((
+            NOT_AUTHORIZED,
+            "Send hello with the management key first".to_string(),
+        ))
+    } else {
+        backend.call(method, params).await
+    };
+    Some(match reply {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+async fn serve_client<B, S>(backend: Arc<B>, stream: S, mut shutdown: watch::Receiver<bool>)
+where
+    B: Backend,
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    let mut session = Session::default();
+    loop {
+        let line = tokio::select! {
+            line = lines.next_line() => line,
+            _ = shutdown.changed() => break,
+        };
+        let Ok(Some(line)) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(mut response) = handle_message(backend.as_ref(), &mut session, &line).await {
+            response.push('\n');
+            if writer.write_all(response.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+        if session.closed {
+            break;
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn accept_clients<B: Backend>(
+    listener: tokio::net::UnixListener,
+    backend: Arc<B>,
+    shutdown: watch::Receiver<bool>,
+) {
+    let mut stop = shutdown.clone();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else { continue };
+                tokio::spawn(serve_client(backend.clone(), stream, shutdown.clone()));
+            }
+            _ = stop.changed() => break,
+        }
+    }
+}
+
+/// Where the socket lives
+#[cfg(unix)]
+pub fn socket_path() -> std::path::PathBuf {
+    crate::config::get_proxypal_config_dir().join("control.sock")
+}
+
+pub(crate) fn endpoint_label() -> String {
+    #[cfg(unix)]
+    return socket_path().display().to_string();
+    #[cfg(windows)]
+    return PIPE_NAME.to_string();
+}
+
+/// Open the socket (or the first pipe instance) and return the loop serving
+/// `backend` until `shutdown` fires. Must be called inside a Tokio runtime.
+#[cfg(unix)]
+pub(crate) fn listen<B: Backend>(
+    backend: Arc<B>,
+    shutdown: watch::Receiver<bool>,
+) -> Result<impl Future<Output = ()> + Send + 'static, String> {
+    let path = socket_path();
+    // The socket can start and stop the proxy: current user only
+    let listener = crate::helpers::permissions::bind_private_socket(&path)
+        .and_then(|listener| {
+            listener.set_nonblocking(true)?;
+            tokio::net::UnixListener::from_std(listener)
+        })
+        .map_err(|e| format!("Failed to open control socket {}: {}", path.display(), e))?;
+    Ok(async move {
+        accept_clients(listener, backend, shutdown).await;
+        let _ = std::fs::remove_file(socket_path());
+    })
+}
+
+/// Open the socket (or the first pipe instance) and return the loop serving
+/// `backend` until `shutdown` fires. Must be called inside a Tokio runtime.
+#[cfg(windows)]
+pub(crate) fn listen<B: Backend>(
+    backend: Arc<B>,
+    shutdown: watch::Receiver<bool>,
+) -> Result<impl Future<Output = ()> + Send + 'static, String> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let first = ServerOptions::new()
+        .first_pipe_instance(true)
+        .reject_remote_clients(true)
+        .create(PIPE_NAME)
+        .map_err(|e| format!("Failed to open control pipe: {}", e))?;
+    Ok(async move {
+        let mut pipe = first;
+        let mut stop = shutdown.clone();
+        loop {
+            tokio::select! {
+                connected = pipe.connect() => {
+                    if connected.is_err() {
+                        break;
+                    }
+                    let Ok(next) = ServerOptions::new().reject_remote_clients(true).create(PIPE_NAME) else {
+                        break;
+                    };
+                    let client = std::mem::replace(&mut pipe, next);
+                    tokio::spawn(serve_client(backend.clone(), client, shutdown.clone()));
+                }
+                _ = stop.changed() => break,
+            }
+        }
+    })
+}
+
+/// Running control socket listener
+pub struct ControlSocket {
+    shutdown: watch::Sender<bool>,
+}
+
+impl ControlSocket {
+    fn start(app: &AppHandle) -> Result<Self, String> {
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        // Opening the socket needs the runtime's reactor
+        let _guard = tauri::async_runtime::handle().inner().enter();
+        let serving = listen(Arc::new(AppBackend(app.clone())), shutdown_rx)?;
+        tauri::async_runtime::spawn(serving);
+        Ok(Self { shutdown })
+    }
+
+    fn stop(self) {
+        let _ = self.shutdown.send(true);
+    }
+}
+
+/// Start or stop the listener to match the current config
+pub fn apply_control_socket_config(app: &AppHandle) -> Result<(), String> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+    let wanted = state.config.lock().control_socket_enabled;
+    let mut current = state.control_socket.lock();
+    if current.is_some() == wanted {
+        return Ok(());
+    }
+    if let Some(socket) = current.take() {
+        socket.stop();
+        println!("[ProxyPal] Control socket stopped");
+    } else {
+        *current = Some(ControlSocket::start(app)?);
+        println!(
+            "[ProxyPal] Control socket listening on {}",
+            endpoint_label()
+        );
+    }
+    Ok(())
+}
+
+/// Disconnect clients and stop listening on app exit
+pub fn stop_control_socket(state: &AppState) {
+    if let Some(socket) = state.control_socket.lock().take() {
+        socket.stop();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tokio::io::{ReadHalf, WriteHalf};
+    use tokio::net::UnixStream;
+
+    // Answers like the app would, without one
+    struct FakeBackend;
+
+    impl Backend for FakeBackend {
+        fn management_key(&self) -> String {
+            "secret".to_string()
+        }
+
+        fn call<'a>(&'a self, method: &'a str, params: Value) -> ReplyFuture<'a> {
+            Box::pin(async move {
+                match method {
+                    "getStatus" => Ok(json!({ "running": false, "port": 8317 })),
+                    "stopProxy" => Err((SERVER_ERROR, "Proxy is not running".to_string())),
+                    "configureAgent" => Ok(json!({ "agent": params["agentId"] })),
+                    other => Err((METHOD_NOT_FOUND, format!("Method not found: {}", other))),
+                }
+            })
+        }
+    }
+
+    struct Client {
+        lines: tokio::io::Lines<BufReader<ReadHalf<UnixStream>>>,
+        writer: WriteHalf<UnixStream>,
+        next_id: u64,
+    }
+
+    impl Client {
+        async fn connect(path: &std::path::Path) -> Self {
+            let (reader, writer) = tokio::io::split(UnixStream::connect(path).await.unwrap());
+            Self {
+                lines: BufReader::new(reader).lines(),
+                writer,
+                next_id: 1,
+            }
+        }
+
+        // Send a request and wait for its reply; None once the server hung up
+        async fn call(&mut self, method: &str, params: Value) -> Option<Value> {
+            let id = self.next_id;
+            self.next_id += 1;
+            let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+            self.writer
+                .write_all(format!("{}\n", request).as_bytes())
+                .await
+                .ok()?;
+            let reply: Value = serde_json::from_str(&self.lines.next_line().await.ok()??).unwrap();
+            assert_eq!(reply["id"], id);
+            Some(reply)
+        }
+
+        async fn hello(&mut self, key: &str) -> Option<Value> {
+            self.call(
+                "hello",
+                json!({ "protocolVersion": 1, "managementKey": key }),
+            )
+            .await
+        }
+    }
+
+    fn start_server() -> (std::path::PathBuf, watch::Sender<bool>) {
+        let dir = std::env::temp_dir().join(format!("proxypal-ctl-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("control.sock");
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        tokio::spawn(accept_clients(listener, Arc::new(FakeBackend), shutdown_rx));
+        (path, shutdown)
+    }
+
+    #[tokio::test]
+    async fn requires_hello_with_the_management_key() {
+        let (path, _shutdown) = start_server();
+
+        let mut client = Client::connect(&path).await;
+        let reply = client.call("getStatus", Value::Null).await.unwrap();
+        assert_eq!(reply["error"]["code"], NOT_AUTHORIZED);
+        let reply = client
+            .call(
+                "hello",
+                json!({ "protocolVersion": 99, "managementKey": "secret" }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(reply["error"]["code"], UNSUPPORTED_VERSION);
+
+        let reply = client.hello("secret").await.unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], 1);
+        assert_eq!(reply["result"]["methods"][0], "getStatus");
+        let reply = client.call("getStatus", Value::Null).await.unwrap();
+        assert_eq!(reply["result"]["port"], 8317);
+
+        // A wrong key gets its answer, then the connection is closed
+        let mut intruder = Client::connect(&path).await;
+        let reply = intruder.hello("guess").await.unwrap();
+        assert_eq!(reply["error"]["code"], NOT_AUTHORIZED);
+        assert!(intruder.call("getStatus", Value::Null).await.is_none());
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn serves_several_clients_at_once() {
+        let (path, shutdown) = start_server();
+        let mut first = Client::connect(&path).await;
+        let mut second = Client::connect(&path).await;
+        assert!(first.hello("secret").await.unwrap()["result"].is_object());
+        assert!(second.hello("secret").await.unwrap()["result"].is_object());
+
+        let (a, b) = tokio::join!(
+            first.call("configureAgent", json!({ "agentId": "claude-code" })),
+            second.call("stopProxy", Value::Null),
+        );
+        assert_eq!(a.unwrap()["result"]["agent"], "claude-code");
+        let b = b.unwrap();
+        assert_eq!(b["error"]["code"], SERVER_ERROR);
+        assert_eq!(b["error"]["message"], "Proxy is not running");
+        let reply = second.call("deleteEverything", Value::Null).await.unwrap();
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+
+        // Notifications get no reply; the next request still does
+        second
+            .writer
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"method\":\"getStatus\"}\nnot json\n")
+            .await
+            .unwrap();
+        let reply: Value =
+            serde_json::from_str(&second.lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(reply["error"]["code"], PARSE_ERROR);
+
+        // Shutting down disconnects everyone
+        shutdown.send(true).unwrap();
+        assert!(first.lines.next_line().await.unwrap().is_none());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
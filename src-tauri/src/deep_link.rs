@@ -0,0 +1,369 @@
+//! `proxypal://` deep links: OAuth callbacks, navigation and automation actions.
+//!
+//! Supported links:
+//! - `proxypal://oauth/callback?code=..&state=..`
+//! - `proxypal://navigate/<page>`
+//! - `proxypal://proxy/start`, `proxypal://proxy/stop`
+//! - `proxypal://auth/connect?provider=claude`
+//! - `proxypal://agents/configure?id=codex`
+//!
+//! Any app or web page can open a deep link, so actions that change state
+//! (proxy, auth and agent links) bring up the main window and ask first; only
+//! navigation and a callback matching an OAuth flow in progress run unprompted.
+//! Actions report their outcome through a `deep-link-action` event. Unknown
+//! links are ignored.
+
+use std::collections::HashMap;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::state::AppState;
+use crate::types::OAuthFlowStatus;
+
+/// Agents accepted by `agents/configure` (ids as used by configure_cli_agent)
+const CONFIGURABLE_AGENTS: &[&str] = &[
+    "claude-code",
+    "codex",
+    "gemini-cli",
+    "factory-droid",
+    "amp-cli",
+    "opencode",
+    "goose",
+    "crush",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DeepLinkAction {
+    OAuthCallback { code: String, state: String },
+    Navigate(String),
+    StartProxy,
+    StopProxy,
+    ConnectProvider(String),
+    ConfigureAgent(String),
+}
+
+impl DeepLinkAction {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::OAuthCallback { .. } => "oauth-callback",
+            Self::Navigate(_) => "navigate",
+            Self::StartProxy => "proxy-start",
+            Self::StopProxy => "proxy-stop",
+            Self::ConnectProvider(_) => "auth-connect",
+            Self::ConfigureAgent(_) => "agent-configure",
+        }
+    }
+
+    /// What to ask before running an action that changes state; `None` for
+    /// the ones that may run straight away
+    fn confirmation(&self) -> Option<String> {
+        match self {
+            Self::OAuthCallback { .. } | Self::Navigate(_) => None,
+            Self::StartProxy => {
+                Some("A link opened from another app wants to start the proxy.".to_string())
+            }
+            Self::StopProxy => Some(
+                "A link opened from another app wants to stop the proxy. Requests in \
+                 progress will fail."
+                    .to_string(),
+            ),
+            Self::ConnectProvider(provider) => Some(format!(
+                "A link opened from another app wants to sign in to {}.",
+                provider
+            )),
+            Self::ConfigureAgent(id) => Some(format!(
+                "A link opened from another app wants to point {} at ProxyPal, rewriting \
+                 its configuration.",
+                id
+            )),
+        }
+    }
+}
+
+/// Parse a deep link. `Ok(None)` means the link isn't one of ours and should be
+/// ignored; `Err` means a known action had missing or invalid parameters.
+pub(crate) fn parse_deep_link(url: &url::Url) -> Result<Option<DeepLinkAction>, String> {
+    if url.scheme() != "proxypal" {
+        return Ok(None);
+    }
+    // proxypal://proxy/start parses as host "proxy" + path "/start" and proxypal:///proxy/start
+    // with an empty host; treat both the same. The OAuth callback is matched on path alone.
+    let route = if url.path() == "/oauth/callback" {
+        "oauth/callback".to_string()
+    } else {
+        format!("{}{}", url.host_str().unwrap_or(""), url.path())
+    };
+    let route = route.trim_matches('/');
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let param = |name: &str| {
+        params
+            .get(name)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| format!("Missing '{}' parameter for {}", name, route))
+    };
+
+    let action = match route {
+        "oauth/callback" => DeepLinkAction::OAuthCallback {
+            code: param("code")?,
+            state: param("state")?,
+        },
+        "proxy/start" => DeepLinkAction::StartProxy,
+        "proxy/stop" => DeepLinkAction::StopProxy,
+        "auth/connect" => {
+            let provider = param("provider")?.to_lowercase();
+            // Only providers with a browser login; Vertex needs a file import
+            if !crate::providers::get(&provider).is_some_and(|p| p.has_login_flow()) {
+                return Err(format!("Unknown provider: {}", provider));
+            }
+            DeepLinkAction::ConnectProvider(provider)
+        }
+        "agents/configure" => {
+            let id = param("id")?.to_lowercase();
+            if !CONFIGURABLE_AGENTS.contains(&id.as_str()) {
+                return Err(format!("Unknown agent: {}", id));
+            }
+            DeepLinkAction::ConfigureAgent(id)
+        }
+        _ => match route.strip_prefix("navigate/") {
+            Some(page) if !page.is_empty() && !page.contains('/') => {
+                DeepLinkAction::Navigate(page.to_string())
+            }
+            _ => return Ok(None),
+        },
+    };
+    Ok(Some(action))
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn emit_outcome(app: &AppHandle, action: &str, result: Result<String, String>) {
+    // Errors can quote the link, including OAuth code/state parameters
+    let result = result
+        .map(|m| crate::redact::redact(&m))
+        .map_err(|e| crate::redact::redact(&e));
+    let (success, message) = match result {
+        Ok(message) => (true, message),
+        Err(message) => {
+            eprintln!("[ProxyPal] Deep link {} failed: {}", action, message);
+            (false, message)
+        }
+    };
+    let _ = app.emit(
+        "deep-link-action",
+        serde_json::json!({
+            "action": action,
+            "success": success,
+            "message": message,
+        }),
+    );
+}
+
+// Handle deep links received at launch or while running
+pub(crate) fn handle_deep_link(app: &AppHandle, urls: Vec<url::Url>) {
+    for url in urls {
+        let action = match parse_deep_link(&url) {
+            Ok(Some(action)) => action,
+            Ok(None) => continue,
+            Err(e) => {
+                emit_outcome(app, "invalid", Err(e));
+                continue;
+            }
+        };
+        dispatch(app, action);
+    }
+}
+
+fn dispatch(app: &AppHandle, action: DeepLinkAction) {
+    let Some(prompt) = action.confirmation() else {
+        run(app, action);
+        return;
+    };
+    show_main_window(app);
+    let handle = app.clone();
+    app.dialog()
+        .message(prompt)
+        .title("Allow this ProxyPal link?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Allow".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |allowed| {
+            if allowed {
+                run(&handle, action);
+            }
+        });
+}
+
+fn run(app: &AppHandle, action: DeepLinkAction) {
+    let name = action.name();
+    match action {
+        DeepLinkAction::OAuthCallback { code, state } => {
+            // Match the callback to its flow; other flows are left alone
+            let app_state = app.state::<AppState>();
+            let flow = app_state.oauth_flows.lock().set_status(
+                &state,
+                OAuthFlowStatus::CallbackReceived,
+                chrono::Utc::now().timestamp_millis() as u64,
+            );
+
+            // A callback no flow is waiting for is ignored
+            if let Some(oauth) = flow {
+                // Emit event to frontend
+                let _ = app.emit(
+                    "oauth-callback",
+                    serde_json::json!({
+                        "provider": oauth.provider,
+                        "state": oauth.state,
+                        "code": code
+                    }),
+                );
+                // Bring window to front
+                show_main_window(app);
+            }
+        }
+        DeepLinkAction::Navigate(page) => {
+            show_main_window(app);
+            let _ = app.emit("navigate", page);
+        }
+        DeepLinkAction::StartProxy => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<AppState>();
+                let result = crate::commands::proxy::start_proxy(app.clone(), state, None)
+                    .await
+                    .map(|status| format!("Proxy running on port {}", status.port))
+                    .map_err(String::from);
+                emit_outcome(&app, name, result);
+            });
+        }
+        DeepLinkAction::StopProxy => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<AppState>();
+                let result = crate::commands::proxy::stop_proxy(app.clone(), state)
+                    .await
+                    .map(|_| "Proxy stopped".to_string())
+                    .map_err(String::from);
+                emit_outcome(&app, name, result);
+            });
+        }
+        DeepLinkAction::ConnectProvider(provider) => {
+            show_main_window(app);
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<AppState>();
+                let result =
+                    crate::commands::auth::open_oauth(app.clone(), state, provider.clone())
+                        .await
+                        .map(|_| format!("Opened {} sign-in in the browser", provider))
+                        .map_err(String::from);
+                emit_outcome(&app, name, result);
+            });
+        }
+        DeepLinkAction::ConfigureAgent(id) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<AppState>();
+                let result = crate::commands::agents::configure_cli_agent(
+                    state,
+                    id.clone(),
+                    vec![],
+                    None,
+                    None,
+                )
+                .await
+                .map(|_| format!("Configured {}", id))
+                .map_err(String::from);
+                emit_outcome(&app, name, result);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(link: &str) -> Result<Option<DeepLinkAction>, String> {
+        parse_deep_link(&url::Url::parse(link).unwrap())
+    }
+
+    #[test]
+    fn parses_proxy_actions() {
+        assert_eq!(
+            parse("proxypal://proxy/start"),
+            Ok(Some(DeepLinkAction::StartProxy))
+        );
+        assert_eq!(
+            parse("proxypal://proxy/stop/"),
+            Ok(Some(DeepLinkAction::StopProxy))
+        );
+        assert_eq!(
+            parse("proxypal:///proxy/start"),
+            Ok(Some(DeepLinkAction::StartProxy))
+        );
+    }
+
+    #[test]
+    fn validates_auth_connect_provider() {
+        assert_eq!(
+            parse("proxypal://auth/connect?provider=Claude"),
+            Ok(Some(DeepLinkAction::ConnectProvider("claude".to_string())))
+        );
+        assert!(parse("proxypal://auth/connect").is_err());
+        assert!(parse("proxypal://auth/connect?provider=").is_err());
+        assert!(parse("proxypal://auth/connect?provider=vertex").is_err());
+    }
+
+    #[test]
+    fn validates_agent_configure_id() {
+        assert_eq!(
+            parse("proxypal://agents/configure?id=codex"),
+            Ok(Some(DeepLinkAction::ConfigureAgent("codex".to_string())))
+        );
+        assert!(parse("proxypal://agents/configure?id=../../etc").is_err());
+        assert!(parse("proxypal://agents/configure").is_err());
+    }
+
+    #[test]
+    fn parses_oauth_callback_and_navigate() {
+        assert_eq!(
+            parse("proxypal://oauth/callback?code=abc&state=xyz"),
+            Ok(Some(DeepLinkAction::OAuthCallback {
+                code: "abc".to_string(),
+                state: "xyz".to_string()
+            }))
+        );
+        assert_eq!(
+            parse("proxypal://navigate/logs"),
+            Ok(Some(DeepLinkAction::Navigate("logs".to_string())))
+        );
+    }
+
+    #[test]
+    fn asks_before_changing_state() {
+        let ask = |link: &str| parse(link).unwrap().unwrap().confirmation().is_some();
+        assert!(ask("proxypal://proxy/start"));
+        assert!(ask("proxypal://proxy/stop"));
+        assert!(ask("proxypal://auth/connect?provider=claude"));
+        assert!(ask("proxypal://agents/configure?id=codex"));
+        assert!(!ask("proxypal://navigate/logs"));
+        assert!(!ask("proxypal://oauth/callback?code=abc&state=xyz"));
+    }
+
+    #[test]
+    fn ignores_unknown_links() {
+        assert_eq!(parse("proxypal://something/else"), Ok(None));
+        assert_eq!(parse("proxypal://navigate/"), Ok(None));
+        assert_eq!(parse("https://proxy/start"), Ok(None));
+    }
+}
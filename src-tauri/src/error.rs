@@ -0,0 +1,196 @@
+//! Typed errors for Tauri commands.
+//!
+//! A bare `String` error leaves the frontend guessing whether the proxy is
+//! down, the network failed or the input was wrong. `CommandError` carries a
+//! machine-readable code next to the message and serializes as
+//! `{ code, message, details? }`. Tauri turns any `Serialize` error into its
+//! `InvokeError`, so returning `Result<T, CommandError>` is all a command
+//! needs. The frontend's invoke wrapper rethrows these objects as an `Error`
+//! whose string form is still the message, so callers showing
+//! `String(error)` keep working.
+//!
+//! Helpers that still return `Result<_, String>` convert with `?`: plain
+//! strings become `internal` errors. The reverse conversion lets String-based
+//! code call functions that already return `CommandError`.
+
+use serde::Serialize;
+
+use crate::http::ManagementError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The arguments were rejected; retrying the same call won't help
+    InvalidInput,
+    /// The thing the command refers to doesn't exist (anymore)
+    NotFound,
+    /// The command needs the proxy, and it isn't answering
+    ProxyNotRunning,
+    /// The proxy or a remote service answered with an error
+    Upstream,
+    /// A file couldn't be read or written
+    Io,
+    /// The user dismissed a prompt the command was waiting on
+    Cancelled,
+    /// Startup hasn't loaded the saved settings yet; retry after `state-hydrated`
+    Loading,
+    /// Anything else
+    Internal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Io, message)
+    }
+
+    /// Extra data for the frontend, e.g. the HTTP status the proxy returned
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Keep the code, rewording the message, e.g. to add context
+    pub fn map_message(mut self, f: impl FnOnce(String) -> String) -> Self {
+        self.message = f(self.message);
+        self
+    }
+
+    /// "Failed to <action>: ..." for a Management API call, keeping whether
+    /// the proxy was down or answered with an error
+    pub fn management(action: &str, e: ManagementError) -> Self {
+        Self::from(e).map_message(|m| format!("Failed to {}: {}", action, m))
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl From<CommandError> for String {
+    fn from(e: CommandError) -> Self {
+        e.message
+    }
+}
+
+impl From<ManagementError> for CommandError {
+    fn from(e: ManagementError) -> Self {
+        match &e {
+            ManagementError::Unreachable(_) => Self::new(
+                ErrorCode::ProxyNotRunning,
+                format!("{}. Is the proxy running?", e),
+            ),
+            ManagementError::Status { status, .. } => {
+                let status = status.as_u16();
+                Self::new(ErrorCode::Upstream, e.to_string())
+                    .with_details(serde_json::json!({ "status": status }))
+            }
+            ManagementError::InvalidResponse(_) => Self::new(ErrorCode::Upstream, e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_as_code_and_message() {
+        let error = CommandError::invalid_input("Unknown provider: foo");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "code": "invalid_input", "message": "Unknown provider: foo" })
+        );
+        let detailed = CommandError::new(ErrorCode::Upstream, "502 Bad Gateway")
+            .with_details(serde_json::json!({ "status": 502 }));
+        assert_eq!(
+            serde_json::to_value(&detailed).unwrap()["details"]["status"],
+            502
+        );
+    }
+
+    #[test]
+    fn plain_strings_are_internal_errors() {
+        let error = CommandError::from("disk full".to_string());
+        assert_eq!(error.code, ErrorCode::Internal);
+        assert_eq!(String::from(error), "disk full");
+    }
+
+    #[test]
+    fn management_errors_keep_their_cause() {
+        let down = CommandError::from(ManagementError::Unreachable(
+            "connection refused".to_string(),
+        ));
+        assert_eq!(down.code, ErrorCode::ProxyNotRunning);
+        assert!(down.message.contains("Is the proxy running?"));
+
+        let rejected = CommandError::from(ManagementError::Status {
+            status: reqwest::StatusCode::NOT_FOUND,
+            body: String::new(),
+        });
+        assert_eq!(rejected.code, ErrorCode::Upstream);
+        assert_eq!(rejected.details, Some(serde_json::json!({ "status": 404 })));
+    }
+
+    #[test]
+    fn failed_management_calls_say_whether_the_proxy_is_down() {
+        let down = CommandError::management(
+            "fetch usage",
+            ManagementError::Unreachable("connection refused".to_string()),
+        );
+        assert_eq!(down.code, ErrorCode::ProxyNotRunning);
+        assert!(down
+            .message
+            .starts_with("Failed to fetch usage: connection refused"));
+
+        let rejected = CommandError::management(
+            "import usage",
+            ManagementError::Status {
+                status: reqwest::StatusCode::BAD_REQUEST,
+                body: "bad snapshot".to_string(),
+            },
+        );
+        assert_eq!(rejected.code, ErrorCode::Upstream);
+        assert_eq!(rejected.details, Some(serde_json::json!({ "status": 400 })));
+        assert!(rejected.message.ends_with("bad snapshot"));
+    }
+}
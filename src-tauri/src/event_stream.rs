@@ -0,0 +1,370 @@
+//! Local WebSocket stream of app events for external dashboards.
+//!
+//! When `AppConfig.event_stream_port` is set, ProxyPal listens on
+//! `ws://127.0.0.1:<port>/` and forwards `request-log-batch`,
+//! `proxy-status-changed` and `auth-status-changed` with the same JSON payloads
+//! the webview receives (plus one `request-log` per request when
+//! `legacy_request_log_events` is on):
+//!
+//! ```text
+//! {"type":"hello","proxyStatus":{..},"authStatus":{..}}
+//! {"type":"event","event":"request-log-batch","payload":[{..},{..}]}
+//! ```
+//!
+//! Clients authenticate with the management key, either as `?token=<key>` or an
+//! `Authorization: Bearer <key>` header. The stream won't start while the key
+//! is still the default every install shares, and browsers may only connect
+//! from a local page or the app itself, so a website can't read the stream
+//! with a guessed key. Each client has a bounded buffer; a client that falls
+//! behind loses the oldest events rather than slowing down the others.
+
+use futures_util::{SinkExt, StreamExt};
+use tauri::{AppHandle, EventId, Listener, Manager};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, watch};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::config::DEFAULT_MANAGEMENT_KEY;
+use crate::state::AppState;
+
+/// Events mirrored to WebSocket clients
+const STREAMED_EVENTS: &[&str] = &[
+    "request-log-batch",
+    "request-log",
+    "feed-resumed",
+    "rate-soft-limit-exceeded",
+    "proxy-status-changed",
+    "auth-status-changed",
+];
+
+/// Events buffered per client before the oldest are dropped
+const CLIENT_BUFFER: usize = 256;
+
+/// Running event stream server
+pub struct EventStreamServer {
+    port: u16,
+    shutdown: watch::Sender<bool>,
+    listeners: Vec<EventId>,
+}
+
+impl EventStreamServer {
+    fn start(app: &AppHandle, port: u16) -> Result<Self, String> {
+        // Bind synchronously so a taken port is reported to the caller
+        let listener = std::net::TcpListener::bind(("127.0.0.1", port))
+            .map_err(|e| format!("Failed to bind event stream on port {}: {}", port, e))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+
+        let (events, _) = broadcast::channel::<String>(CLIENT_BUFFER);
+        let listeners = STREAMED_EVENTS
+            .iter()
+            .map(|name| {
+                let events = events.clone();
+                app.listen_any(*name, move |event| {
+                    // Payloads are already JSON; wrap without re-parsing
+                    let message = format!(
+                        "{{\"type\":\"event\",\"event\":\"{}\",\"payload\":{}}}",
+                        name,
+                        event.payload()
+                    );
+                    // No receivers just means no clients are connected
+                    let _ = events.send(crate::redact::redact(&message));
+                })
+            })
+            .collect();
+
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let Ok(listener) = TcpListener::from_std(listener) else {
+                return;
+            };
+            let mut shutdown_rx = shutdown_rx;
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => {
+                        let Ok((stream, _)) = accepted else { continue };
+                        tauri::async_runtime::spawn(serve_client(
+                            app.clone(),
+                            stream,
+                            events.subscribe(),
+                            shutdown_rx.clone(),
+                        ));
+                    }
+                    _ = shutdown_rx.changed() => break,
+                }
+            }
+        });
+
+        println!(
+            "[ProxyPal] Event stream available at ws://127.0.0.1:{}/",
+            port
+        );
+        Ok(Self {
+            port,
+            shutdown,
+            listeners,
+        })
+    }
+
+    fn stop(self, app: &AppHandle) {
+        for id in self.listeners {
+            app.unlisten(id);
+        }
+        let _ = self.shutdown.send(true);
+        println!("[ProxyPal] Event stream on port {} stopped", self.port);
+    }
+}
+
+// Accept the token as ?token=<key> or Authorization: Bearer <key>
+fn request_token(request: &Request) -> Option<String> {
+    let from_query = request.uri().query().and_then(|query| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == "token")
+            .map(|(_, value)| value.into_owned())
+    });
+    from_query.or_else(|| {
+        request
+            .headers()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(|v| v.trim().to_string())
+    })
+}
+
+// Browsers always send Origin; scripts usually don't. Pages may only connect
+// from the app's own webview or a page served from this machine.
+fn origin_allowed(request: &Request) -> bool {
+    let Some(origin) = request.headers().get("origin") else {
+        return true;
+    };
+    let Some(origin) = origin.to_str().ok().and_then(|o| url::Url::parse(o).ok()) else {
+        return false;
+    };
+    if origin.scheme() == "tauri" {
+        return true;
+    }
+    matches!(origin.scheme(), "http" | "https")
+        && matches!(
+            origin.host_str(),
+            Some("localhost" | "127.0.0.1" | "[::1]" | "tauri.localhost")
+        )
+}
+
+// The handshake callback's error type is fixed by tungstenite
+#[allow(clippy::result_large_err)]
+fn authorize(
+    request: &Request,
+    response: Response,
+    expected: &str,
+) -> Result<Response, ErrorResponse> {
+    if !origin_allowed(request) {
+        let mut error = ErrorResponse::new(Some("Origin not allowed".to_string()));
+        *error.status_mut() = StatusCode::FORBIDDEN;
+        return Err(error);
+    }
+    if !expected.is_empty()
+        && expected != DEFAULT_MANAGEMENT_KEY
+        && request_token(request).as_deref() == Some(expected)
+    {
+        return Ok(response);
+    }
+    let mut error = ErrorResponse::new(Some("Invalid or missing token".to_string()));
+    *error.status_mut() = StatusCode::UNAUTHORIZED;
+    Err(error)
+}
+
+fn hello_message(app: &AppHandle) -> String {
+    let Some(state) = app.try_state::<AppState>() else {
+        return serde_json::json!({ "type": "hello" }).to_string();
+    };
+    let proxy_status = state.proxy_status.lock().clone();
+    let auth_status = state.auth_status.lock().clone();
+    serde_json::json!({
+        "type": "hello",
+        "proxyStatus": proxy_status,
+        "authStatus": auth_status,
+    })
+    .to_string()
+}
+
+async fn serve_client(
+    app: AppHandle,
+    stream: TcpStream,
+    mut events: broadcast::Receiver<String>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let expected = app
+        .try_state::<AppState>()
+        .map(|state| state.config.lock().management_key.clone())
+        .unwrap_or_default();
+    #[allow(clippy::result_large_err)]
+    let handshake = |request: &Request, response: Response| authorize(request, response, &expected);
+    let Ok(socket) = tokio_tungstenite::accept_hdr_async(stream, handshake).await else {
+        return;
+    };
+    let (mut sink, mut incoming) = socket.split();
+
+    if sink.send(Message::text(hello_message(&app))).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(message) => {
+                    if sink.send(Message::text(message)).await.is_err() {
+                        break;
+                    }
+                }
+                // Slow client: the oldest events were dropped, keep going
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = incoming.next() => match message {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                // Clients don't send anything meaningful; pings are answered by tungstenite
+                Some(Ok(_)) => {}
+            },
+            _ = shutdown.changed() => {
+                let _ = sink.send(Message::Close(None)).await;
+                break;
+            }
+        }
+    }
+}
+
+/// Start, restart or stop the event stream to match the current config
+pub fn apply_event_stream_config(app: &AppHandle) -> Result<(), String> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+    let (wanted, default_key) = {
+        let config = state.config.lock();
+        (
+            config.event_stream_port,
+            config.management_key == DEFAULT_MANAGEMENT_KEY,
+        )
+    };
+    if wanted.is_some() && default_key {
+        stop_event_stream(app);
+        return Err("The event stream needs a management key of its own. \
+                    Rotate the management key to turn it on."
+            .to_string());
+    }
+    let current = state.event_stream.lock().as_ref().map(|s| s.port);
+    if current == wanted {
+        return Ok(());
+    }
+
+    stop_event_stream(app);
+    if let Some(port) = wanted {
+        let server = EventStreamServer::start(app, port)?;
+        *state.event_stream.lock() = Some(server);
+    }
+    Ok(())
+}
+
+/// Disconnect clients and stop listening (settings toggle or app exit)
+pub fn stop_event_stream(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let server = state.event_stream.lock().take();
+    if let Some(server) = server {
+        server.stop(app);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::tungstenite::Error;
+
+    fn request(uri: &str, auth: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(auth) = auth {
+            builder = builder.header("Authorization", auth);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn reads_token_from_query_or_header() {
+        assert_eq!(
+            request_token(&request("/?token=abc%2B1", None)).as_deref(),
+            Some("abc+1")
+        );
+        assert_eq!(
+            request_token(&request("/", Some("Bearer xyz"))).as_deref(),
+            Some("xyz")
+        );
+        assert_eq!(request_token(&request("/", Some("Basic xyz"))), None);
+        assert_eq!(request_token(&request("/", None)), None);
+    }
+
+    fn with_origin(origin: &str) -> Request {
+        Request::builder()
+            .uri("/")
+            .header("Origin", origin)
+            .body(())
+            .unwrap()
+    }
+
+    #[test]
+    fn allows_only_local_and_app_origins() {
+        assert!(origin_allowed(&request("/", None)));
+        assert!(origin_allowed(&with_origin("tauri://localhost")));
+        assert!(origin_allowed(&with_origin("http://tauri.localhost")));
+        assert!(origin_allowed(&with_origin("http://localhost:1420")));
+        assert!(origin_allowed(&with_origin("http://127.0.0.1:3000")));
+        assert!(!origin_allowed(&with_origin("https://example.com")));
+        assert!(!origin_allowed(&with_origin(
+            "http://localhost.example.com"
+        )));
+        assert!(!origin_allowed(&with_origin("null")));
+    }
+
+    async fn handshake_status(origin: Option<&str>, expected: &str) -> u16 {
+        let (client, server) = tokio::io::duplex(4096);
+        let mut request = "ws://127.0.0.1/?token=secret"
+            .into_client_request()
+            .unwrap();
+        if let Some(origin) = origin {
+            request
+                .headers_mut()
+                .insert("origin", origin.parse().unwrap());
+        }
+        #[allow(clippy::result_large_err)]
+        let handshake =
+            |request: &Request, response: Response| authorize(request, response, expected);
+        let (client, _) = tokio::join!(
+            tokio_tungstenite::client_async(request, client),
+            tokio_tungstenite::accept_hdr_async(server, handshake),
+        );
+        match client {
+            Ok((_, response)) => response.status().as_u16(),
+            Err(Error::Http(response)) => response.status().as_u16(),
+            Err(e) => panic!("unexpected handshake error: {}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_cross_origin_handshakes() {
+        assert_eq!(
+            handshake_status(Some("https://example.com"), "secret").await,
+            403
+        );
+        assert_eq!(
+            handshake_status(Some("http://localhost:1420"), "secret").await,
+            101
+        );
+        assert_eq!(handshake_status(None, "secret").await, 101);
+        assert_eq!(handshake_status(None, "other").await, 401);
+        assert_eq!(handshake_status(None, DEFAULT_MANAGEMENT_KEY).await, 401);
+    }
+}
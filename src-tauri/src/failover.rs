@@ -0,0 +1,489 @@
+//! Provider failover.
+//!
+//! Each `AppConfig.failover_rules` entry names a provider, a health state
+//! (`rate_limited`, `auth_expired` or `offline`) and model mappings to a backup
+//! provider. After every background health check the engine works out which
+//! rules should be in force: a rule activates while its provider is in the
+//! trigger state and the backup is healthy (or merely degraded), and clears
+//! once that stops being true.
+//!
+//! Active rules are applied by replacing the running proxy's model mappings
+//! through the Management API: redirects of reached model caps (see
+//! `model_caps`) first, then the mappings of active rules, then the
+//! configured Amp mappings for every model they don't cover. Clearing the
+//! last rule restores the configured mappings. Each change emits
+//! `failover-activated` / `failover-cleared` and is written to the audit log.
+//!
+//! Failover only covers Amp: CLIProxyAPI consults `ampcode.model-mappings`
+//! on its Amp routes alone, so requests to `/v1/chat/completions`,
+//! `/v1/messages` and the other provider routes still reach the unhealthy
+//! provider. Rules, activations and their descriptions say so.
+//!
+//! Rules are validated on save so that enabled rules never form a loop
+//! (claude → vertex → claude), which would bounce requests between providers.
+
+use std::collections::HashMap;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::AppConfig;
+use crate::http::send_management;
+use crate::state::AppState;
+use crate::types::{
+    FailoverActivation, FailoverModelMapping, FailoverRule, ProviderHealth, FAILOVER_TRIGGERS,
+};
+
+// An applied rule, kept as it was when applied so edits are noticed
+#[derive(Debug, Clone)]
+struct ActiveRule {
+    rule: FailoverRule,
+    activation: FailoverActivation,
+}
+
+/// Rules currently applied to the running proxy, kept in `AppState`
+#[derive(Default)]
+pub struct FailoverEngine {
+    active: parking_lot::Mutex<Vec<ActiveRule>>,
+    // One reconcile at a time, so pushes reach the proxy in order
+    apply_lock: tokio::sync::Mutex<()>,
+}
+
+impl FailoverEngine {
+    /// Activations in force, oldest first
+    pub fn active(&self) -> Vec<FailoverActivation> {
+        self.active
+            .lock()
+            .iter()
+            .map(|a| a.activation.clone())
+            .collect()
+    }
+
+    /// Forget applied rules; the proxy stopped and will restart with the
+    /// configured mappings
+    pub fn reset(&self) {
+        self.active.lock().clear();
+    }
+}
+
+fn validate_rule(rule: &FailoverRule) -> Result<(), String> {
+    for provider in [&rule.from_provider, &rule.to_provider] {
+        crate::providers::lookup(provider)?;
+    }
+    if rule.from_provider == rule.to_provider {
+        return Err("A failover rule must switch to a different provider".to_string());
+    }
+    if !FAILOVER_TRIGGERS.contains(&rule.trigger.as_str()) {
+        return Err(format!("Unknown failover trigger: {}", rule.trigger));
+    }
+    if rule.model_map.is_empty() {
+        return Err("Add at least one model mapping".to_string());
+    }
+    if rule
+        .model_map
+        .iter()
+        .any(|m| m.from.trim().is_empty() || m.to.trim().is_empty())
+    {
+        return Err("Model mappings need both a source and a target model".to_string());
+    }
+    Ok(())
+}
+
+// Providers along a loop formed by enabled rules, e.g. [claude, vertex, claude]
+fn find_loop(rules: &[FailoverRule]) -> Option<Vec<String>> {
+    let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+    for rule in rules.iter().filter(|r| r.enabled) {
+        edges
+            .entry(rule.from_provider.as_str())
+            .or_default()
+            .push(rule.to_provider.as_str());
+    }
+
+    fn visit<'a>(
+        node: &'a str,
+        edges: &HashMap<&'a str, Vec<&'a str>>,
+        path: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = path.iter().position(|p| *p == node) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|p| p.to_string()).collect();
+            cycle.push(node.to_string());
+            return Some(cycle);
+        }
+        path.push(node);
+        for next in edges.get(node).into_iter().flatten() {
+            if let Some(cycle) = visit(next, edges, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        None
+    }
+
+    let mut starts: Vec<&str> = edges.keys().copied().collect();
+    starts.sort();
+    starts
+        .into_iter()
+        .find_map(|start| visit(start, &edges, &mut Vec::new()))
+}
+
+/// Check every rule, and that enabled rules never lead back to a provider
+pub(crate) fn validate_rules(rules: &[FailoverRule]) -> Result<(), String> {
+    for rule in rules {
+        validate_rule(rule)?;
+    }
+    match find_loop(rules) {
+        Some(cycle) => Err(format!("Failover rules would loop: {}", cycle.join(" → "))),
+        None => Ok(()),
+    }
+}
+
+fn is_triggered(rule: &FailoverRule, health: &ProviderHealth) -> bool {
+    health.status(&rule.from_provider) == Some(rule.trigger.as_str())
+        && matches!(
+            health.status(&rule.to_provider),
+            Some("healthy") | Some("degraded")
+        )
+}
+
+// A rule's mappings with `prefix*` expanded against the known model ids
+pub(crate) fn expand(
+    model_map: &[FailoverModelMapping],
+    models: &[String],
+) -> Vec<FailoverModelMapping> {
+    let mut expanded = Vec::new();
+    for mapping in model_map {
+        match mapping.from.strip_suffix('*') {
+            Some(prefix) => expanded.extend(
+                models
+                    .iter()
+                    .filter(|model| model.starts_with(prefix) && **model != mapping.to)
+                    .map(|model| FailoverModelMapping {
+                        from: model.clone(),
+                        to: mapping.to.clone(),
+                    }),
+            ),
+            None => expanded.push(mapping.clone()),
+        }
+    }
+    expanded
+}
+
+/// Rules to activate and activations to clear. Without a health result only
+/// clearing happens (a rule was edited, disabled or removed).
+fn plan(
+    rules: &[FailoverRule],
+    health: Option<&ProviderHealth>,
+    active: &[ActiveRule],
+    models: &[String],
+    now_ms: u64,
+) -> (Vec<ActiveRule>, Vec<ActiveRule>) {
+    let cleared: Vec<ActiveRule> = active
+        .iter()
+        .filter(|a| {
+            let unchanged = rules.iter().any(|r| *r == a.rule && r.enabled);
+            !unchanged || health.is_some_and(|h| !is_triggered(&a.rule, h))
+        })
+        .cloned()
+        .collect();
+
+    let mut activated = Vec::new();
+    if let Some(health) = health {
+        for rule in rules
+            .iter()
+            .filter(|r| r.enabled && is_triggered(r, health))
+        {
+            let kept =
+                active.iter().any(|a| a.rule == *rule) && !cleared.iter().any(|c| c.rule == *rule);
+            if kept {
+                continue;
+            }
+            activated.push(ActiveRule {
+                rule: rule.clone(),
+                activation: FailoverActivation {
+                    rule_id: rule.id.clone(),
+                    from_provider: rule.from_provider.clone(),
+                    to_provider: rule.to_provider.clone(),
+                    trigger: rule.trigger.clone(),
+                    activated_at: now_ms,
+                    mappings: expand(&rule.model_map, models),
+                },
+            });
+        }
+    }
+    (activated, cleared)
+}
+
+/// Model mappings for the proxy: cap redirects and active failovers first,
+/// then the configured Amp mappings and pool routes for models they leave alone
+fn model_mappings(
+    config: &AppConfig,
+    active: &[ActiveRule],
+    cap_redirects: &[FailoverModelMapping],
+) -> Vec<Value> {
+    let mut mapped: Vec<&str> = Vec::new();
+    let mut entries = Vec::new();
+    let failovers = active.iter().flat_map(|a| &a.activation.mappings);
+    for mapping in cap_redirects.iter().chain(failovers) {
+        if !mapped.contains(&mapping.from.as_str()) {
+            mapped.push(&mapping.from);
+            entries.push(json!({"from": mapping.from, "to": mapping.to}));
+        }
+    }
+    for mapping in crate::helpers::amp_pools::effective_amp_mappings(config) {
+        if mapped.contains(&mapping.name.as_str()) {
+            continue;
+        }
+        let mut entry = json!({"from": mapping.name, "to": mapping.alias});
+        if mapping.fork {
+            entry["fork"] = json!(true);
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+fn describe(activation: &FailoverActivation) -> String {
+    format!(
+        "{} {} → {} ({} model mapping(s), Amp routes only)",
+        activation.from_provider,
+        activation.trigger,
+        activation.to_provider,
+        activation.mappings.len()
+    )
+}
+
+fn known_models(state: &AppState) -> Vec<String> {
+    crate::commands::models::cached_models(state)
+        .into_iter()
+        .map(|m| m.id)
+        .collect()
+}
+
+async fn put_mappings(
+    state: &AppState,
+    config: &AppConfig,
+    active: &[ActiveRule],
+    cap_redirects: &[FailoverModelMapping],
+) -> Result<(), String> {
+    let request = state
+        .http
+        .management(Method::PUT, config.port, "ampcode/model-mappings")
+        .json(&json!({"value": model_mappings(config, active, cap_redirects)}));
+    send_management(request)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Push the combined mappings again with the active rules unchanged, after
+/// the set of reached model caps changed. No-op while the proxy is stopped.
+pub async fn push_mappings(app: &AppHandle) -> Result<(), String> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+    let _guard = state.failover.apply_lock.lock().await;
+    if !state.proxy_status.lock().running {
+        return Ok(());
+    }
+    let config = state.config.lock().clone();
+    let models = known_models(&state);
+    let active = state.failover.active.lock().clone();
+    let redirects = crate::model_caps::redirects(&state, &config, &models);
+    put_mappings(&state, &config, &active, &redirects).await
+}
+
+/// Bring the applied rules in line with `health` (the latest check), or,
+/// without one, drop rules that were edited or removed. No-op while the proxy
+/// is stopped.
+pub async fn reconcile(app: &AppHandle, health: Option<&ProviderHealth>) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let _guard = state.failover.apply_lock.lock().await;
+    if !state.proxy_status.lock().running {
+        return;
+    }
+
+    let config = state.config.lock().clone();
+    let models = known_models(&state);
+    let active = state.failover.active.lock().clone();
+    let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+    let (activated, cleared) = plan(&config.failover_rules, health, &active, &models, now_ms);
+    if activated.is_empty() && cleared.is_empty() {
+        return;
+    }
+
+    let next: Vec<ActiveRule> = active
+        .into_iter()
+        .filter(|a| !cleared.iter().any(|c| c.rule == a.rule))
+        .chain(activated.iter().cloned())
+        .collect();
+    let redirects = crate::model_caps::redirects(&state, &config, &models);
+    if let Err(e) = put_mappings(&state, &config, &next, &redirects).await {
+        // Nothing recorded, so the next check tries again
+        eprintln!("[Failover] Failed to update model mappings: {}", e);
+        return;
+    }
+    *state.failover.active.lock() = next;
+
+    for cleared in &cleared {
+        eprintln!("[Failover] Cleared: {}", describe(&cleared.activation));
+        let _ = app.emit("failover-cleared", &cleared.activation);
+        crate::helpers::audit::record(
+            "failover_cleared",
+            vec![describe(&cleared.activation)],
+            &Ok::<(), String>(()),
+        );
+    }
+    for activated in &activated {
+        eprintln!("[Failover] Activated: {}", describe(&activated.activation));
+        let _ = app.emit("failover-activated", &activated.activation);
+        crate::helpers::audit::record(
+            "failover_activated",
+            vec![describe(&activated.activation)],
+            &Ok::<(), String>(()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::HealthStatus;
+
+    fn rule(id: &str, from: &str, to: &str) -> FailoverRule {
+        FailoverRule {
+            id: id.to_string(),
+            from_provider: from.to_string(),
+            to_provider: to.to_string(),
+            trigger: "rate_limited".to_string(),
+            model_map: vec![FailoverModelMapping {
+                from: "claude-*".to_string(),
+                to: "gpt-5".to_string(),
+            }],
+            enabled: true,
+        }
+    }
+
+    fn health(statuses: &[(&str, &str)]) -> ProviderHealth {
+        let status = |provider: &str| HealthStatus {
+            status: statuses
+                .iter()
+                .find(|(p, _)| *p == provider)
+                .map(|(_, s)| s.to_string())
+                .unwrap_or_else(|| "unconfigured".to_string()),
+            latency_ms: None,
+            last_checked: 1,
+        };
+        ProviderHealth {
+            claude: status("claude"),
+            openai: status("openai"),
+            gemini: status("gemini"),
+            qwen: status("qwen"),
+            iflow: status("iflow"),
+            vertex: status("vertex"),
+            kiro: status("kiro"),
+            antigravity: status("antigravity"),
+            kimi: status("kimi"),
+            deepseek: status("deepseek"),
+            zhipu: status("zhipu"),
+        }
+    }
+
+    #[test]
+    fn rejects_rules_that_loop() {
+        let chain = [rule("a", "claude", "vertex"), rule("b", "vertex", "openai")];
+        assert!(validate_rules(&chain).is_ok());
+
+        let looped = [
+            rule("a", "claude", "vertex"),
+            rule("b", "vertex", "openai"),
+            rule("c", "openai", "claude"),
+        ];
+        let err = validate_rules(&looped).unwrap_err();
+        assert!(err.contains("claude → vertex → openai → claude"), "{}", err);
+
+        // A disabled rule breaks the loop
+        let mut broken = looped.clone();
+        broken[2].enabled = false;
+        assert!(validate_rules(&broken).is_ok());
+
+        assert!(validate_rules(&[rule("a", "claude", "claude")]).is_err());
+        assert!(validate_rules(&[rule("a", "claude", "nope")]).is_err());
+    }
+
+    #[test]
+    fn activates_while_triggered_and_clears_after() {
+        let rules = [rule("a", "claude", "openai")];
+        let models = ["claude-sonnet-4-5".to_string(), "gpt-5".to_string()];
+
+        let limited = health(&[("claude", "rate_limited"), ("openai", "healthy")]);
+        let (activated, cleared) = plan(&rules, Some(&limited), &[], &models, 7);
+        assert!(cleared.is_empty());
+        assert_eq!(activated.len(), 1);
+        assert_eq!(
+            activated[0].activation.mappings,
+            [FailoverModelMapping {
+                from: "claude-sonnet-4-5".to_string(),
+                to: "gpt-5".to_string(),
+            }]
+        );
+
+        // Still limited: nothing to do
+        let (again, cleared) = plan(&rules, Some(&limited), &activated, &models, 8);
+        assert!(again.is_empty() && cleared.is_empty());
+
+        // No switching to a backup that is down too
+        let both_down = health(&[("claude", "rate_limited"), ("openai", "offline")]);
+        let (_, cleared) = plan(&rules, Some(&both_down), &activated, &models, 9);
+        assert_eq!(cleared.len(), 1);
+
+        let recovered = health(&[("claude", "healthy"), ("openai", "healthy")]);
+        let (_, cleared) = plan(&rules, Some(&recovered), &activated, &models, 9);
+        assert_eq!(cleared.len(), 1);
+
+        // Removing the rule clears it without waiting for a health check
+        let (_, cleared) = plan(&[], None, &activated, &models, 9);
+        assert_eq!(cleared.len(), 1);
+    }
+
+    #[test]
+    fn failover_mappings_take_precedence_over_configured_ones() {
+        let config = AppConfig {
+            amp_model_mappings: vec![
+                crate::types::AmpModelMapping {
+                    name: "claude-sonnet-4-5".to_string(),
+                    alias: "claude-opus-4-5".to_string(),
+                    enabled: true,
+                    fork: false,
+                },
+                crate::types::AmpModelMapping {
+                    name: "gemini-2.5-pro".to_string(),
+                    alias: "gemini-3-pro".to_string(),
+                    enabled: true,
+                    fork: true,
+                },
+            ],
+            ..AppConfig::default()
+        };
+        let limited = health(&[("claude", "rate_limited"), ("openai", "healthy")]);
+        let models = ["claude-sonnet-4-5".to_string()];
+        let (active, _) = plan(
+            &[rule("a", "claude", "openai")],
+            Some(&limited),
+            &[],
+            &models,
+            1,
+        );
+
+        assert_eq!(
+            model_mappings(&config, &active, &[]),
+            [
+                json!({"from": "claude-sonnet-4-5", "to": "gpt-5"}),
+                json!({"from": "gemini-2.5-pro", "to": "gemini-3-pro", "fork": true}),
+            ]
+        );
+        assert_eq!(model_mappings(&config, &[], &[]).len(), 2);
+    }
+}
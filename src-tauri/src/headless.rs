@@ -0,0 +1,585 @@
+//! Headless mode: run ProxyPal without the GUI.
+//!
+//! `proxypal --headless` (or `proxypal serve`) loads the same config, starts the
+//! sidecar with the same generated YAML and runs the request history pipeline.
+//! It serves the JSON-RPC control socket (`control_socket.rs`) at the same place
+//! the desktop app does, so the one-shot commands work against either:
+//!
+//! ```text
+//! proxypal status
+//! proxypal start
+//! proxypal stop
+//! proxypal connect <provider>
+//! proxypal mcp
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use reqwest::Method;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::watch;
+
+use crate::config::load_config;
+use crate::control_api::{control_credentials, control_status, control_usage};
+use crate::control_socket::{
+    server_error, to_value, Backend, ReplyFuture, METHOD_NOT_FOUND, PROTOCOL_VERSIONS,
+};
+use crate::helpers::migration::migrate_to_split_storage;
+use crate::http::{send_management, HttpClients};
+use crate::proxy::lifecycle;
+use crate::state::AppState;
+use crate::types::{ControlStatus, ProxyStatus};
+
+/// How long `connect` waits for the browser sign-in to finish
+const OAUTH_TIMEOUT: Duration = Duration::from_secs(300);
+
+const USAGE: &str = "\
+Usage: proxypal [COMMAND]
+
+Without a command, ProxyPal starts the desktop app.
+
+Commands:
+  --headless, serve     Run the proxy without the GUI
+  status                Show proxy status
+  start                 Start the proxy of a running instance
+  stop                  Stop the proxy of a running instance
+  connect <provider>    Sign in to a provider (prints the URL to open)
+  mcp                   Bridge stdio to the desktop app's MCP server
+  help                  Show this help
+
+Options:
+  --portable            Keep all data in data/ next to the executable";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CliCommand {
+    Serve,
+    Status,
+    Start,
+    Stop,
+    Connect(String),
+    Mcp,
+    Help,
+}
+
+// Returns None when the arguments are for the desktop app (no args, deep links,
+// --minimized from the autostart entry)
+fn parse_args(args: &[String]) -> Option<Result<CliCommand, String>> {
+    let first = args.first()?;
+    let command = match first.as_str() {
+        "--headless" | "serve" => CliCommand::Serve,
+        "status" => CliCommand::Status,
+        "start" => CliCommand::Start,
+        "stop" => CliCommand::Stop,
+        "connect" => match args.get(1) {
+            Some(provider) => CliCommand::Connect(provider.to_lowercase()),
+            None => {
+                return Some(Err(
+                    "connect needs a provider, e.g. `proxypal connect claude`".to_string(),
+                ))
+            }
+        },
+        "mcp" => CliCommand::Mcp,
+        "help" | "--help" | "-h" => CliCommand::Help,
+        _ => return None,
+    };
+    Some(Ok(command))
+}
+
+/// Handle CLI arguments before the desktop app starts. Returns the process exit
+/// code if a CLI command ran, or None to continue launching the GUI.
+pub fn run_cli() -> Option<i32> {
+    // --portable only changes where files live; see helpers::paths
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| arg != crate::helpers::paths::PORTABLE_ARG)
+        .collect();
+    let command = match parse_args(&args)? {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return Some(2);
+        }
+    };
+    if command == CliCommand::Help {
+        println!("{}", USAGE);
+        return Some(0);
+    }
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("[ProxyPal] Failed to start async runtime: {}", e);
+            return Some(1);
+        }
+    };
+    let result = runtime.block_on(async move {
+        match command {
+            CliCommand::Serve => serve().await,
+            CliCommand::Status => status().await,
+            CliCommand::Start | CliCommand::Stop => {
+                let method = if command == CliCommand::Start {
+                    "startProxy"
+                } else {
+                    "stopProxy"
+                };
+                print_json(&send_control(method).await?)
+            }
+            CliCommand::Connect(provider) => connect(&provider).await,
+            CliCommand::Mcp => mcp_bridge().await,
+            CliCommand::Help => Ok(()),
+        }
+    });
+    match result {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("{}", e);
+            Some(1)
+        }
+    }
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<(), String> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).map_err(|e| e.to_string())?
+    );
+    Ok(())
+}
+
+// ============================================
+// Server
+// ============================================
+
+/// Headless instance: app state plus the sidecar process it owns
+struct Headless {
+    state: AppState,
+    child: tokio::sync::Mutex<Option<tokio::process::Child>>,
+    // Held for the whole of a start so concurrent starts don't kill each other's sidecar
+    starting: tokio::sync::Mutex<()>,
+}
+
+fn build_state() -> AppState {
+    let config = load_config();
+    crate::redact::set_known_secrets(&config);
+    crate::helpers::history::set_history_retention(&config);
+    crate::helpers::aggregate_compaction::set_hourly_days(&config);
+    crate::helpers::paths::set_data_dir(&config);
+    crate::helpers::usage_day::set_usage_timezone(&config);
+    let auth_status = crate::commands::auth::scan_auth_dir(&config);
+    crate::config::mark_hydrated();
+    let state = AppState {
+        config: Mutex::new(config.clone()),
+        auth_status: Mutex::new(auth_status),
+        hydrated: AtomicBool::new(true),
+        ..AppState::default()
+    };
+    state.http.set_network_proxy(&config.network_proxy);
+    state
+}
+
+async fn start_headless(server: &Arc<Headless>) -> Result<ProxyStatus, String> {
+    let _starting = server.starting.lock().await;
+    if let Some(status) = lifecycle::running_status(&server.state) {
+        return Ok(status);
+    }
+
+    let config = server.state.config.lock().clone();
+    let launch = lifecycle::prepare_launch(&server.state, config).await?;
+    let binary = lifecycle::resolve_sidecar_binary().await?;
+    let mut child = tokio::process::Command::new(&binary.path)
+        .env("WRITABLE_PATH", &launch.data_dir)
+        .arg("--config")
+        .arg(&launch.proxy_config_path)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to spawn sidecar: {}", e))?;
+    if let Some(pid) = child.id() {
+        lifecycle::record_sidecar(pid, &binary.path).await;
+    }
+    if let Some(stdout) = child.stdout.take() {
+        tokio::spawn(forward_output(stdout, false));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(forward_output(stderr, true));
+    }
+    *server.child.lock().await = Some(child);
+
+    let early_exit = Arc::new(AtomicBool::new(false));
+    tokio::spawn(watch_sidecar(server.clone(), early_exit.clone()));
+
+    let http = &server.state.http;
+    let ready = lifecycle::wait_until_ready(http, launch.config.port, &early_exit).await?;
+    if ready {
+        lifecycle::sync_runtime_settings(http, &launch.config).await;
+    }
+    lifecycle::start_request_pipeline(None, &server.state, &launch.data_dir, launch.config.port)
+        .await;
+    *server.state.proxy_started_at.lock() = Some(std::time::Instant::now());
+    Ok(lifecycle::mark_running(
+        &server.state,
+        &launch.config.proxy_bind,
+        launch.config.port,
+        &binary,
+        ready,
+    ))
+}
+
+// Pass sidecar output through with secrets masked
+async fn forward_output(output: impl AsyncRead + Unpin, is_stderr: bool) {
+    let mut lines = BufReader::new(output).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let line = crate::redact::redact(&line);
+        if is_stderr {
+            eprintln!("[CLIProxyAPI ERROR] {}", line);
+        } else {
+            println!("[CLIProxyAPI] {}", line);
+        }
+    }
+}
+
+// Notice the sidecar exiting on its own
+async fn watch_sidecar(server: Arc<Headless>, early_exit: Arc<AtomicBool>) {
+    loop {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let mut child_slot = server.child.lock().await;
+        let Some(child) = child_slot.as_mut() else {
+            // Stopped on purpose
+            return;
+        };
+        if let Ok(Some(exit)) = child.try_wait() {
+            eprintln!("[ProxyPal] Proxy exited unexpectedly ({})", exit);
+            *child_slot = None;
+            early_exit.store(true, Ordering::SeqCst);
+            lifecycle::mark_stopped(&server.state);
+            return;
+        }
+    }
+}
+
+async fn stop_headless(server: &Arc<Headless>) -> ProxyStatus {
+    if let Some(mut child) = server.child.lock().await.take() {
+        println!("[ProxyPal] Killing proxy process");
+        let _ = child.kill().await;
+    }
+    let _ = tokio::task::spawn_blocking(lifecycle::kill_recorded_sidecar).await;
+    lifecycle::mark_stopped(&server.state)
+}
+
+// Control socket methods a headless instance offers; agent setup needs the
+// desktop app
+const HEADLESS_METHODS: &[&str] = &[
+    "getStatus",
+    "startProxy",
+    "stopProxy",
+    "getUsageSummary",
+    "listCredentials",
+];
+
+struct HeadlessBackend(Arc<Headless>);
+
+impl Backend for HeadlessBackend {
+    fn management_key(&self) -> String {
+        self.0.state.config.lock().management_key.clone()
+    }
+
+    fn methods(&self) -> &'static [&'static str] {
+        HEADLESS_METHODS
+    }
+
+    fn call<'a>(&'a self, method: &'a str, _params: Value) -> ReplyFuture<'a> {
+        let server = &self.0;
+        Box::pin(async move {
+            match method {
+                "getStatus" => to_value(control_status(&server.state)),
+                "startProxy" => {
+                    start_headless(server).await.map_err(server_error)?;
+                    to_value(control_status(&server.state))
+                }
+                "stopProxy" => {
+                    stop_headless(server).await;
+                    to_value(control_status(&server.state))
+                }
+                "getUsageSummary" => {
+                    to_value(control_usage(&server.state).await.map_err(server_error)?)
+                }
+                "listCredentials" => to_value(
+                    control_credentials(&server.state)
+                        .await
+                        .map_err(server_error)?,
+                ),
+                other => Err((
+                    METHOD_NOT_FOUND,
+                    format!("Method not available in headless mode: {}", other),
+                )),
+            }
+        })
+    }
+}
+
+async fn serve() -> Result<(), String> {
+    migrate_to_split_storage();
+    let server = Arc::new(Headless {
+        state: build_state(),
+        child: tokio::sync::Mutex::new(None),
+        starting: tokio::sync::Mutex::new(()),
+    });
+
+    // Refuse to run twice; the second instance would kill the first one's proxy
+    if open_control().await.is_ok() {
+        return Err("ProxyPal is already running and serving the control socket.".to_string());
+    }
+
+    match start_headless(&server).await {
+        Ok(status) if status.endpoint.is_empty() => println!(
+            "[ProxyPal] Proxy started on port {} but is not answering yet",
+            status.port
+        ),
+        Ok(status) => println!("[ProxyPal] Proxy running at {}", status.endpoint),
+        Err(e) => eprintln!("[ProxyPal] Failed to start proxy: {}", e),
+    }
+
+    let backend = Arc::new(HeadlessBackend(server.clone()));
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    let result = match crate::control_socket::listen(backend, shutdown_rx) {
+        Ok(serving) => {
+            println!(
+                "[ProxyPal] Control socket: {}",
+                crate::control_socket::endpoint_label()
+            );
+            tokio::select! {
+                _ = serving => {}
+                _ = tokio::signal::ctrl_c() => {}
+            }
+            Ok(())
+        }
+        Err(e) => Err(e),
+    };
+
+    println!("[ProxyPal] Shutting down");
+    let _ = shutdown.send(true);
+    stop_headless(&server).await;
+    if let Err(e) = server.state.history.flush() {
+        eprintln!("[ProxyPal] Failed to save history: {}", e);
+    }
+    #[cfg(unix)]
+    let _ = std::fs::remove_file(crate::control_socket::socket_path());
+    result
+}
+
+// ============================================
+// One-shot commands
+// ============================================
+
+// Say hello with `key`, then call `method`
+async fn exchange<S>(stream: S, key: &str, method: &str) -> Result<Value, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    let hello = json!({
+        "protocolVersion": PROTOCOL_VERSIONS.last(),
+        "managementKey": key,
+    });
+    let mut result = Value::Null;
+    for (id, (method, params)) in [("hello", hello), (method, Value::Null)]
+        .into_iter()
+        .enumerate()
+    {
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writer
+            .write_all(format!("{}\n", request).as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+        let line = lines
+            .next_line()
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("ProxyPal closed the connection")?;
+        let mut reply: Value =
+            serde_json::from_str(&line).map_err(|e| format!("Invalid response: {}", e))?;
+        if let Some(message) = reply["error"]["message"].as_str() {
+            return Err(message.to_string());
+        }
+        result = reply.get_mut("result").map(Value::take).unwrap_or_default();
+    }
+    Ok(result)
+}
+
+#[cfg(unix)]
+async fn open_control() -> std::io::Result<tokio::net::UnixStream> {
+    tokio::net::UnixStream::connect(crate::control_socket::socket_path()).await
+}
+
+#[cfg(windows)]
+async fn open_control() -> std::io::Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    tokio::net::windows::named_pipe::ClientOptions::new().open(crate::control_socket::PIPE_NAME)
+}
+
+/// Call one control socket method on the running instance, headless or desktop
+async fn send_control(method: &str) -> Result<Value, String> {
+    let stream = open_control().await.map_err(|_| {
+        "No ProxyPal instance is serving the control socket. Start one with \
+         `proxypal --headless`, or enable the control socket in the app's settings."
+            .to_string()
+    })?;
+    exchange(stream, &load_config().management_key, method).await
+}
+
+async fn status() -> Result<(), String> {
+    if let Ok(status) = send_control("getStatus").await {
+        return print_json(&status);
+    }
+    // Nothing serves the control socket; the desktop app may still be running
+    // the proxy
+    let config = load_config();
+    let port = config.port;
+    let request = HttpClients::default().management(Method::GET, port, "config.yaml");
+    let running = send_management(request).await.is_ok();
+    let endpoint = if running {
+        lifecycle::proxy_endpoint(&lifecycle::client_host(&config.proxy_bind), port)
+    } else {
+        String::new()
+    };
+    let status = ProxyStatus {
+        running,
+        port,
+        endpoint,
+        ..Default::default()
+    };
+    print_json(&ControlStatus::new(status, None))
+}
+
+async fn connect(provider: &str) -> Result<(), String> {
+    let config = load_config();
+    let port = config.port;
+    if provider == "kiro" {
+        println!(
+            "Open this URL in a browser to sign in:\nhttp://127.0.0.1:{}/v0/oauth/kiro",
+            port
+        );
+        return Ok(());
+    }
+
+    let http = HttpClients::default();
+    let (url, oauth_state) = crate::commands::auth::fetch_oauth_url(&http, port, provider).await?;
+    println!(
+        "Open this URL in a browser to sign in to {}:\n{}\n",
+        provider, url
+    );
+    println!("Waiting for sign-in to complete...");
+
+    let started = std::time::Instant::now();
+    while started.elapsed() < OAUTH_TIMEOUT {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        if crate::commands::auth::check_oauth_status(&http, port, &oauth_state).await? {
+            let mut auth = crate::commands::auth::scan_auth_dir(&config);
+            auth.keep_verification(&crate::config::load_auth_status());
+            crate::config::save_auth_to_file(&auth)?;
+            println!("Connected {}.", provider);
+            return Ok(());
+        }
+    }
+    Err("Timed out waiting for sign-in.".to_string())
+}
+
+// Forward stdin/stdout to the app's MCP socket so stdio-only agents can use it.
+// Nothing but protocol traffic may go to stdout here.
+async fn mcp_bridge() -> Result<(), String> {
+    #[cfg(unix)]
+    let stream = tokio::net::UnixStream::connect(crate::mcp::socket_path()).await;
+    #[cfg(windows)]
+    let stream = tokio::net::windows::named_pipe::ClientOptions::new().open(crate::mcp::PIPE_NAME);
+    let stream = stream.map_err(|_| {
+        "The ProxyPal MCP server is not running. Enable it in ProxyPal's settings.".to_string()
+    })?;
+
+    let (mut reader, mut writer) = tokio::io::split(stream);
+    let upstream = async {
+        tokio::io::copy(&mut tokio::io::stdin(), &mut writer).await?;
+        writer.shutdown().await?;
+        // Keep relaying replies until the server closes its side
+        std::future::pending::<std::io::Result<()>>().await
+    };
+    let mut stdout = tokio::io::stdout();
+    let downstream = tokio::io::copy(&mut reader, &mut stdout);
+    tokio::select! {
+        result = upstream => result.map_err(|e| e.to_string()),
+        result = downstream => result.map(|_| ()).map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn leaves_gui_arguments_alone() {
+        assert_eq!(parse_args(&[]), None);
+        assert_eq!(parse_args(&args(&["--minimized"])), None);
+        assert_eq!(parse_args(&args(&["proxypal://proxy/start"])), None);
+    }
+
+    #[test]
+    fn parses_cli_commands() {
+        assert_eq!(
+            parse_args(&args(&["--headless"])),
+            Some(Ok(CliCommand::Serve))
+        );
+        assert_eq!(parse_args(&args(&["status"])), Some(Ok(CliCommand::Status)));
+        assert_eq!(
+            parse_args(&args(&["connect", "Claude"])),
+            Some(Ok(CliCommand::Connect("claude".to_string())))
+        );
+        assert!(matches!(parse_args(&args(&["connect"])), Some(Err(_))));
+        assert_eq!(parse_args(&args(&["mcp"])), Some(Ok(CliCommand::Mcp)));
+    }
+
+    // Run `exchange` against a server answering each line with the next reply
+    async fn exchange_with(replies: Vec<Value>) -> Result<Value, String> {
+        let (client, server) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            let (reader, mut writer) = tokio::io::split(server);
+            let mut lines = BufReader::new(reader).lines();
+            for reply in replies {
+                let line = lines.next_line().await.ok().flatten().unwrap_or_default();
+                let request: Value = serde_json::from_str(&line).unwrap_or_default();
+                if request["method"] == "hello" {
+                    assert_eq!(request["params"]["managementKey"], "secret");
+                }
+                let _ = writer.write_all(format!("{}\n", reply).as_bytes()).await;
+            }
+        });
+        exchange(client, "secret", "getStatus").await
+    }
+
+    #[tokio::test]
+    async fn one_shot_commands_say_hello_first() {
+        let hello = json!({ "jsonrpc": "2.0", "id": 0, "result": { "protocolVersion": 1 } });
+        let status = json!({ "jsonrpc": "2.0", "id": 1, "result": { "running": true } });
+        assert_eq!(
+            exchange_with(vec![hello, status]).await.unwrap(),
+            json!({ "running": true })
+        );
+
+        let refused = json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "error": { "code": -32001, "message": "Invalid management key" },
+        });
+        assert_eq!(
+            exchange_with(vec![refused]).await.unwrap_err(),
+            "Invalid management key"
+        );
+    }
+}
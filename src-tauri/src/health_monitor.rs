@@ -0,0 +1,162 @@
+//! Background provider health monitoring.
+//!
+//! While the proxy runs, the provider health check repeats every
+//! `AppConfig.health_check_interval_secs` (four times as long on battery).
+//! The latest result is kept in `AppState.health_cache` and
+//! `provider-health-changed` is emitted only when some provider's status
+//! changes, so the UI subscribes instead of polling. Providers turning
+//! unhealthy are reported through the notification system, and every result
+//! is handed to the failover engine.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands::health::check_health;
+use crate::notifications;
+use crate::state::AppState;
+use crate::types::ProviderHealth;
+
+/// Interval multiplier while the machine runs on battery
+const BATTERY_BACKOFF: u32 = 4;
+
+/// Shortest interval accepted from the config
+const MIN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Providers whose status differs between two checks, with (old, new) status
+fn transitions<'a>(
+    previous: &'a ProviderHealth,
+    current: &'a ProviderHealth,
+) -> Vec<(&'static str, &'a str, &'a str)> {
+    previous
+        .statuses()
+        .into_iter()
+        .zip(current.statuses())
+        .filter(|((_, old), (_, new))| old.status != new.status)
+        .map(|((provider, old), (_, new))| (provider, old.status.as_str(), new.status.as_str()))
+        .collect()
+}
+
+fn notify_transition(app: &AppHandle, provider: &str, old: &str, new: &str) {
+    match new {
+        "auth_expired" => notifications::notify_auth_expired(app, provider),
+        "rate_limited" => notifications::notify_rate_limit(app, provider, true),
+        "offline" | "degraded" => notifications::notify_provider_unhealthy(app, provider, new),
+        _ => {}
+    }
+    if old == "rate_limited" {
+        notifications::notify_rate_limit(app, provider, false);
+    }
+}
+
+fn interval(state: &AppState) -> Option<Duration> {
+    let secs = state.config.lock().health_check_interval_secs;
+    if secs == 0 {
+        return None;
+    }
+    let interval = Duration::from_secs(secs).max(MIN_INTERVAL);
+    Some(if crate::helpers::power::on_battery() {
+        interval * BATTERY_BACKOFF
+    } else {
+        interval
+    })
+}
+
+/// Start checking provider health in the background, replacing any earlier
+/// monitor. The task ends on its own once the proxy stops.
+pub fn start_health_monitor(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let app = app.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        let mut previous: Option<ProviderHealth> = None;
+        loop {
+            let Some(state) = app.try_state::<AppState>() else {
+                return;
+            };
+            let Some(interval) = interval(&state) else {
+                return;
+            };
+            if !state.proxy_status.lock().running {
+                return;
+            }
+
+            // One check at a time: the next sleep only starts once this returns
+            let current = check_health(&state).await;
+            match &previous {
+                Some(previous) => {
+                    let changes = transitions(previous, &current);
+                    if !changes.is_empty() {
+                        let _ = app.emit("provider-health-changed", &current);
+                    }
+                    for (provider, old, new) in changes {
+                        notify_transition(&app, provider, old, new);
+                    }
+                }
+                None => {
+                    let _ = app.emit("provider-health-changed", &current);
+                }
+            }
+            crate::failover::reconcile(&app, Some(&current)).await;
+            previous = Some(current);
+            tokio::time::sleep(interval).await;
+        }
+    });
+    let old = state.health_monitor.lock().replace(handle);
+    if let Some(old) = old {
+        old.abort();
+    }
+}
+
+/// Cancel the background checks (proxy stopped)
+pub fn stop_health_monitor(state: &AppState) {
+    if let Some(handle) = state.health_monitor.lock().take() {
+        handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::HealthStatus;
+
+    fn status(status: &str) -> HealthStatus {
+        HealthStatus {
+            status: status.to_string(),
+            latency_ms: Some(120),
+            last_checked: 1,
+        }
+    }
+
+    #[test]
+    fn reports_only_changed_providers() {
+        let previous = ProviderHealth {
+            claude: status("healthy"),
+            openai: status("healthy"),
+            gemini: status("rate_limited"),
+            qwen: status("unconfigured"),
+            iflow: status("unconfigured"),
+            vertex: status("unconfigured"),
+            kiro: status("unconfigured"),
+            antigravity: status("unconfigured"),
+            kimi: status("unconfigured"),
+            deepseek: status("unconfigured"),
+            zhipu: status("unconfigured"),
+        };
+        let mut current = previous.clone();
+        // Latency alone is not a transition
+        current.claude.latency_ms = Some(900);
+        assert!(transitions(&previous, &current).is_empty());
+
+        current.openai = status("auth_expired");
+        current.gemini = status("healthy");
+        assert_eq!(
+            transitions(&previous, &current),
+            [
+                ("openai", "healthy", "auth_expired"),
+                ("gemini", "rate_limited", "healthy")
+            ]
+        );
+    }
+}
@@ -0,0 +1,455 @@
+//! Agent endpoint audit.
+//!
+//! After a reinstall or a port change, agents can be left pointing at an old
+//! ProxyPal: `~/.codex` at one port while the shell profile exports another, and
+//! requests split between two proxies. [`collect`] reads the endpoint from
+//! every agent config ProxyPal writes, from ProxyPal's own environment and
+//! from the shell profile; [`audit`] groups them by host and port and reports
+//! the ones that don't lead to this proxy, or carry a key it no longer accepts.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::commands::proxy::is_loopback_bind;
+use crate::types::{
+    AgentEndpoint, AgentEndpointAudit, AgentEndpointGroup, EndpointConflict, EndpointConflictKind,
+};
+
+/// Source name for variables set in ProxyPal's own environment
+pub(crate) const ENVIRONMENT: &str = "environment";
+
+/// Env vars ProxyPal tells agents to export: (agent id, URL var, key var)
+const ENV_VARS: &[(&str, &str, Option<&str>)] = &[
+    (
+        "claude-code",
+        "ANTHROPIC_BASE_URL",
+        Some("ANTHROPIC_AUTH_TOKEN"),
+    ),
+    ("gemini-cli", "CODE_ASSIST_ENDPOINT", None),
+    (
+        "gemini-cli",
+        "GOOGLE_GEMINI_BASE_URL",
+        Some("GEMINI_API_KEY"),
+    ),
+    ("amp-cli", "AMP_URL", Some("AMP_API_KEY")),
+    ("opencode", "OPENAI_BASE_URL", Some("OPENAI_API_KEY")),
+];
+
+/// An endpoint as read, before it is checked against the proxy
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Found {
+    pub agent_id: &'static str,
+    pub source: String,
+    pub variable: Option<String>,
+    pub url: String,
+    pub key: Option<String>,
+    /// Found under a provider entry only ProxyPal writes, e.g. `proxypal` in
+    /// crush.json; other sources only count when they look like ProxyPal's
+    pub named: bool,
+}
+
+fn found(agent_id: &'static str, source: &Path, url: &str, key: Option<&str>) -> Found {
+    Found {
+        agent_id,
+        source: source.to_string_lossy().to_string(),
+        variable: None,
+        url: url.to_string(),
+        key: key.map(str::to_string),
+        named: false,
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+fn str_at<'a>(json: &'a Value, pointer: &str) -> Option<&'a str> {
+    json.pointer(pointer).and_then(|v| v.as_str())
+}
+
+/// `base_url` of the `[model_providers.cliproxyapi]` table in Codex's config.toml
+fn codex_base_url(content: &str) -> Option<String> {
+    let mut in_table = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_table = line == "[model_providers.cliproxyapi]";
+        } else if in_table {
+            if let Some(value) = line.strip_prefix("base_url") {
+                let value = value.trim_start().strip_prefix('=')?.trim();
+                return Some(value.trim_matches('"').to_string());
+            }
+        }
+    }
+    None
+}
+
+/// `(variable, value)` of an export line in bash/zsh, fish or PowerShell syntax
+pub(crate) fn parse_env_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    let (var, value) = if let Some(rest) = line.strip_prefix("export ") {
+        rest.split_once('=')?
+    } else if let Some(rest) = line.strip_prefix("$env:") {
+        rest.split_once('=')?
+    } else if let Some(rest) = line.strip_prefix("set -gx ") {
+        rest.trim_start().split_once(char::is_whitespace)?
+    } else {
+        return None;
+    };
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+    Some((var.trim(), value))
+}
+
+/// Endpoints set through the variables in `lookup`, labelled with `source`
+fn from_env(source: &str, lookup: impl Fn(&str) -> Option<String>) -> Vec<Found> {
+    ENV_VARS
+        .iter()
+        .filter_map(|&(agent_id, url_var, key_var)| {
+            let url = lookup(url_var).filter(|v| !v.is_empty())?;
+            Some(Found {
+                agent_id,
+                source: source.to_string(),
+                variable: Some(url_var.to_string()),
+                url,
+                key: key_var.and_then(&lookup),
+                named: false,
+            })
+        })
+        .collect()
+}
+
+/// Every endpoint the agent configs under `home`, the shell `profile` and the
+/// variables in `env` point at
+pub(crate) fn collect(
+    home: &Path,
+    profile: Option<&Path>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Vec<Found> {
+    let mut all = Vec::new();
+
+    let claude = home.join(".claude/settings.json");
+    if let Some(json) = read_json(&claude) {
+        if let Some(url) = str_at(&json, "/env/ANTHROPIC_BASE_URL") {
+            let key = str_at(&json, "/env/ANTHROPIC_AUTH_TOKEN");
+            all.push(found("claude-code", &claude, url, key));
+        }
+    }
+
+    let codex = home.join(".codex/config.toml");
+    if let Some(url) = std::fs::read_to_string(&codex)
+        .ok()
+        .and_then(|c| codex_base_url(&c))
+    {
+        let auth = read_json(&home.join(".codex/auth.json"));
+        let key = auth.as_ref().and_then(|a| str_at(a, "/OPENAI_API_KEY"));
+        all.push(Found {
+            named: true,
+            ..found("codex", &codex, &url, key)
+        });
+    }
+
+    let droid = home.join(".factory/config.json");
+    if let Some(models) = read_json(&droid).and_then(|json| {
+        json.get("custom_models")
+            .and_then(|m| m.as_array())
+            .cloned()
+    }) {
+        for model in &models {
+            let Some(url) = str_at(model, "/base_url") else {
+                continue;
+            };
+            let entry = found("factory-droid", &droid, url, str_at(model, "/api_key"));
+            // One entry per model; they normally all share the endpoint
+            if !all.contains(&entry) {
+                all.push(entry);
+            }
+        }
+    }
+
+    let amp = home.join(".config/amp/settings.json");
+    if let Some(json) = read_json(&amp) {
+        if let Some(url) = json.get("amp.url").and_then(|v| v.as_str()) {
+            let key = json.get("amp.apiKey").and_then(|v| v.as_str());
+            all.push(found("amp-cli", &amp, url, key));
+        }
+    }
+
+    let opencode = home.join(".config/opencode/opencode.json");
+    if let Some(json) = read_json(&opencode) {
+        if let Some(url) = str_at(&json, "/provider/proxypal/options/baseURL") {
+            let key = str_at(&json, "/provider/proxypal/options/apiKey");
+            all.push(Found {
+                named: true,
+                ..found("opencode", &opencode, url, key)
+            });
+        }
+    }
+
+    // The key lives in the keyring or environment, never in config.yaml
+    let goose = home.join(".config/goose/config.yaml");
+    if let Ok(content) = std::fs::read_to_string(&goose) {
+        let host = content
+            .lines()
+            .find_map(|line| line.trim_start().strip_prefix("OPENAI_HOST:"));
+        if let Some(url) = host {
+            all.push(found("goose", &goose, url.trim().trim_matches('"'), None));
+        }
+    }
+
+    let crush = home.join(".config/crush/crush.json");
+    if let Some(json) = read_json(&crush) {
+        if let Some(url) = str_at(&json, "/providers/proxypal/base_url") {
+            let key = str_at(&json, "/providers/proxypal/api_key");
+            all.push(Found {
+                named: true,
+                ..found("crush", &crush, url, key)
+            });
+        }
+    }
+
+    all.extend(from_env(ENVIRONMENT, env));
+    if let Some(profile) = profile {
+        if let Ok(content) = std::fs::read_to_string(profile) {
+            // Later exports win, as they would in the shell
+            let mut vars = BTreeMap::new();
+            for line in content.lines().filter(|l| !l.trim_start().starts_with('#')) {
+                if let Some((var, value)) = parse_env_line(line) {
+                    vars.insert(var.to_string(), value.to_string());
+                }
+            }
+            let source = profile.to_string_lossy();
+            all.extend(from_env(&source, |var| vars.get(var).cloned()));
+        }
+    }
+    all
+}
+
+/// Host (loopback names as 127.0.0.1) and port of `url`
+pub(crate) fn host_port(url: &str) -> Option<(String, u16)> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+    let host = if is_loopback_bind(host) {
+        "127.0.0.1".to_string()
+    } else {
+        host.to_string()
+    };
+    Some((host, parsed.port_or_known_default()?))
+}
+
+fn is_proxypal_key(key: &str, accepted_keys: &[String]) -> bool {
+    key.starts_with("pp-") || key == "proxypal-local" || accepted_keys.iter().any(|k| k == key)
+}
+
+/// Check `found` against the proxy on `port` accepting `accepted_keys`.
+/// Endpoints that don't look like ProxyPal's (a remote host without a ProxyPal
+/// key, say a real OpenAI base URL) are left out.
+pub(crate) fn audit(found: Vec<Found>, port: u16, accepted_keys: &[String]) -> AgentEndpointAudit {
+    let expected = format!("http://127.0.0.1:{}", port);
+    let mut endpoints = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for entry in found {
+        let Some((host, found_port)) = host_port(&entry.url) else {
+            continue;
+        };
+        let local = host == "127.0.0.1";
+        let key = entry.key.as_deref().filter(|k| !k.is_empty());
+        if !entry.named && !local && !key.is_some_and(|k| is_proxypal_key(k, accepted_keys)) {
+            continue;
+        }
+
+        let kind = if !local {
+            Some(EndpointConflictKind::Host)
+        } else if found_port != port {
+            Some(EndpointConflictKind::Port)
+        } else if key.is_some_and(|k| !accepted_keys.iter().any(|a| a == k)) {
+            Some(EndpointConflictKind::StaleKey)
+        } else {
+            None
+        };
+        if let Some(kind) = kind {
+            let found = match kind {
+                EndpointConflictKind::StaleKey => "a key the proxy no longer accepts".to_string(),
+                _ => format!("{}:{}", host, found_port),
+            };
+            conflicts.push(EndpointConflict {
+                kind,
+                agent_id: entry.agent_id.to_string(),
+                source: entry.source.clone(),
+                found,
+                expected: expected.clone(),
+            });
+        }
+        endpoints.push(AgentEndpoint {
+            agent_id: entry.agent_id.to_string(),
+            source: entry.source,
+            variable: entry.variable,
+            url: entry.url,
+            host,
+            port: found_port,
+            key_stale: kind == Some(EndpointConflictKind::StaleKey),
+        });
+    }
+
+    let mut groups: Vec<AgentEndpointGroup> = Vec::new();
+    for endpoint in &endpoints {
+        let index = match groups
+            .iter()
+            .position(|g| g.host == endpoint.host && g.port == endpoint.port)
+        {
+            Some(index) => index,
+            None => {
+                groups.push(AgentEndpointGroup {
+                    host: endpoint.host.clone(),
+                    port: endpoint.port,
+                    matches_proxy: endpoint.host == "127.0.0.1" && endpoint.port == port,
+                    agent_ids: Vec::new(),
+                });
+                groups.len() - 1
+            }
+        };
+        if !groups[index].agent_ids.contains(&endpoint.agent_id) {
+            groups[index].agent_ids.push(endpoint.agent_id.clone());
+        }
+    }
+
+    let mut outliers: Vec<String> = Vec::new();
+    for conflict in &conflicts {
+        if !outliers.contains(&conflict.agent_id) {
+            outliers.push(conflict.agent_id.clone());
+        }
+    }
+
+    AgentEndpointAudit {
+        expected,
+        groups,
+        endpoints,
+        conflicts,
+        outliers,
+    }
+}
+
+/// Point the local ProxyPal URLs exported in a shell profile at `port`.
+/// Returns the new content and the variables changed, or None when there's
+/// nothing to change. Remote hosts are left for the user.
+pub(crate) fn retarget_profile(content: &str, port: u16) -> Option<(String, Vec<String>)> {
+    let mut changed = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.split('\n') {
+        let retargeted = parse_env_line(line)
+            .filter(|(var, _)| ENV_VARS.iter().any(|(_, url_var, _)| url_var == var))
+            .and_then(|(var, value)| {
+                let mut url = url::Url::parse(value).ok()?;
+                let local = url.host_str().is_some_and(is_loopback_bind);
+                if !local || url.port_or_known_default() == Some(port) {
+                    return None;
+                }
+                url.set_port(Some(port)).ok()?;
+                // Url adds a trailing slash to bare origins; keep the line's own form
+                let mut new_value = url.to_string();
+                if !value.ends_with('/') && new_value.ends_with('/') {
+                    new_value.pop();
+                }
+                changed.push(var.to_string());
+                Some(line.replacen(value, &new_value, 1))
+            });
+        lines.push(retargeted.unwrap_or_else(|| line.to_string()));
+    }
+    (!changed.is_empty()).then(|| (lines.join("\n"), changed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(agent_id: &'static str, url: &str, key: Option<&str>) -> Found {
+        found(agent_id, Path::new("/home/me/config"), url, key)
+    }
+
+    #[test]
+    fn parses_export_lines() {
+        assert_eq!(
+            parse_env_line("export ANTHROPIC_BASE_URL=\"http://127.0.0.1:8320\""),
+            Some(("ANTHROPIC_BASE_URL", "http://127.0.0.1:8320"))
+        );
+        assert_eq!(
+            parse_env_line("$env:AMP_URL = \"http://localhost:8317\""),
+            Some(("AMP_URL", "http://localhost:8317"))
+        );
+        assert_eq!(
+            parse_env_line("set -gx OPENAI_BASE_URL http://127.0.0.1:8317/v1"),
+            Some(("OPENAI_BASE_URL", "http://127.0.0.1:8317/v1"))
+        );
+        assert_eq!(parse_env_line("alias ll='ls -l'"), None);
+    }
+
+    #[test]
+    fn reads_codex_provider_table_only() {
+        let toml = "model = \"gpt-5\"\nbase_url = \"https://elsewhere\"\n\n\
+                    [model_providers.cliproxyapi]\nname = \"cliproxyapi\"\n\
+                    base_url = \"http://127.0.0.1:8317/v1\"\n";
+        assert_eq!(
+            codex_base_url(toml).as_deref(),
+            Some("http://127.0.0.1:8317/v1")
+        );
+    }
+
+    #[test]
+    fn reports_ports_hosts_and_stale_keys() {
+        let keys = vec!["pp-current".to_string()];
+        let audit = audit(
+            vec![
+                entry("codex", "http://127.0.0.1:8317/v1", Some("pp-current")),
+                entry("amp-cli", "http://localhost:8320", Some("pp-current")),
+                entry("crush", "http://127.0.0.1:8320/v1", Some("pp-retired")),
+                entry(
+                    "factory-droid",
+                    "http://10.0.0.5:8320/v1",
+                    Some("pp-current"),
+                ),
+                // A real provider endpoint, not ProxyPal's
+                entry("opencode", "https://api.openai.com/v1", Some("sk-live")),
+            ],
+            8320,
+            &keys,
+        );
+
+        assert_eq!(audit.expected, "http://127.0.0.1:8320");
+        assert_eq!(audit.endpoints.len(), 4);
+        let kinds: Vec<(&str, EndpointConflictKind)> = audit
+            .conflicts
+            .iter()
+            .map(|c| (c.agent_id.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("codex", EndpointConflictKind::Port),
+                ("crush", EndpointConflictKind::StaleKey),
+                ("factory-droid", EndpointConflictKind::Host),
+            ]
+        );
+        assert_eq!(audit.outliers, ["codex", "crush", "factory-droid"]);
+
+        let current = audit.groups.iter().find(|g| g.matches_proxy).unwrap();
+        assert_eq!(current.agent_ids, ["amp-cli", "crush"]);
+        assert_eq!(audit.groups.len(), 3);
+    }
+
+    #[test]
+    fn retargets_local_profile_exports() {
+        let profile = "export PATH=\"$HOME/bin:$PATH\"\n\
+                       # ProxyPal - Claude Code\n\
+                       export ANTHROPIC_BASE_URL=\"http://127.0.0.1:8317\"\n\
+                       export OPENAI_BASE_URL=\"http://localhost:8317/v1\"\n\
+                       export AMP_URL=\"https://amp.example.com\"\n";
+        let (updated, changed) = retarget_profile(profile, 8320).unwrap();
+        assert_eq!(changed, ["ANTHROPIC_BASE_URL", "OPENAI_BASE_URL"]);
+        assert!(updated.contains("export ANTHROPIC_BASE_URL=\"http://127.0.0.1:8320\"\n"));
+        assert!(updated.contains("export OPENAI_BASE_URL=\"http://localhost:8320/v1\""));
+        assert!(updated.contains("export AMP_URL=\"https://amp.example.com\""));
+        assert!(updated.ends_with('\n'));
+        assert_eq!(retarget_profile(&updated, 8320), None);
+    }
+}
@@ -0,0 +1,340 @@
+//! Downsampling of the aggregate's time series.
+//!
+//! Left alone, aggregate.json gains 24 hourly points a day and a cost ledger
+//! entry per day, provider and model, forever. [`save_aggregate`] runs
+//! [`compact`] once the data crosses the thresholds below, which keeps:
+//!
+//! - hourly points for the last `AppConfig.hourly_series_days` days. Older
+//!   hours are dropped: every request counted in an hour is also counted in
+//!   its day, so no total changes.
+//! - daily points (the day series, the cost ledger, tags and versions) for
+//!   the last [`DAILY_DAYS`] days
+//! - whole months before that, labeled "YYYY-MM" and summed exactly
+//!
+//! The ledger's own hourly spend is bounded by `cost_ledger::HOURLY_DAYS`.
+//! Compaction works on a copy and the file is replaced in one rename, so a
+//! crash leaves the old file or the compacted one. Compacting compacted data
+//! changes nothing.
+//!
+//! [`save_aggregate`]: crate::helpers::history::save_aggregate
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use chrono::NaiveDate;
+
+use crate::config::AppConfig;
+use crate::types::{
+    Aggregate, CostLedgerEntry, CostLedgerTagEntry, DayVersions, SeriesGranularity, TimeSeriesPoint,
+};
+
+/// Days of daily points kept, today included; older days become months
+pub(crate) const DAILY_DAYS: i64 = 365;
+
+/// Hourly days when the config has none
+pub(crate) const DEFAULT_HOURLY_DAYS: u32 = 14;
+
+static HOURLY_DAYS: AtomicU32 = AtomicU32::new(DEFAULT_HOURLY_DAYS);
+
+/// Register the hourly retention of the current config. Call after loading or saving it.
+pub(crate) fn set_hourly_days(config: &AppConfig) {
+    HOURLY_DAYS.store(config.hourly_series_days.max(1), Ordering::Relaxed);
+}
+
+/// The hourly retention registered from the current config
+pub(crate) fn hourly_days() -> u32 {
+    HOURLY_DAYS.load(Ordering::Relaxed)
+}
+
+/// Where compaction draws its lines for `today`
+struct Cutoffs {
+    first_hour_day: String, // Hours on earlier days are dropped
+    first_month: String,    // Days in earlier months are folded into them
+}
+
+impl Cutoffs {
+    fn new(today: &str, hourly_days: u32) -> Option<Self> {
+        let today = NaiveDate::parse_from_str(today, "%Y-%m-%d").ok()?;
+        let first_hour_day = today - chrono::Duration::days(i64::from(hourly_days.max(1)) - 1);
+        // Only months that ended before the daily window are folded
+        let first_daily = today - chrono::Duration::days(DAILY_DAYS - 1);
+        Some(Self {
+            first_hour_day: first_hour_day.format("%Y-%m-%d").to_string(),
+            first_month: first_daily.format("%Y-%m").to_string(),
+        })
+    }
+
+    fn keeps_hour(&self, hour: &str) -> bool {
+        hour.get(..10).unwrap_or(hour) >= self.first_hour_day.as_str()
+    }
+
+    /// The label `day` is kept under: its month when that is before the
+    /// daily window, else the day itself
+    fn day_key<'a>(&self, day: &'a str) -> &'a str {
+        match day.get(..7) {
+            Some(month) if month < self.first_month.as_str() => month,
+            _ => day,
+        }
+    }
+
+    fn folds(&self, day: &str) -> bool {
+        day.len() > 7 && self.day_key(day).len() == 7
+    }
+}
+
+/// Whether saving `agg` on `today` should compact it first
+pub(crate) fn needs_compaction(agg: &Aggregate, today: &str, hourly_days: u32) -> bool {
+    let Some(cutoffs) = Cutoffs::new(today, hourly_days) else {
+        return false;
+    };
+    let ledger = &agg.cost_ledger;
+    let hours = [&agg.requests_by_hour, &agg.tokens_by_hour];
+    let days = [&agg.requests_by_day, &agg.tokens_by_day];
+    hours
+        .iter()
+        .any(|series| series.iter().any(|p| !cutoffs.keeps_hour(&p.label)))
+        || days
+            .iter()
+            .any(|series| series.iter().any(|p| cutoffs.folds(&p.label)))
+        || ledger.entries.iter().any(|e| cutoffs.folds(&e.day))
+        || ledger.tags.iter().any(|e| cutoffs.folds(&e.day))
+        || agg.versions_by_day.keys().any(|day| cutoffs.folds(day))
+}
+
+fn compact_hours(series: &[TimeSeriesPoint], cutoffs: &Cutoffs) -> Vec<TimeSeriesPoint> {
+    series
+        .iter()
+        .filter(|p| cutoffs.keeps_hour(&p.label))
+        .cloned()
+        .collect()
+}
+
+fn compact_days(series: &[TimeSeriesPoint], cutoffs: &Cutoffs) -> Vec<TimeSeriesPoint> {
+    let mut folded: BTreeMap<String, u64> = BTreeMap::new();
+    for point in series {
+        *folded
+            .entry(cutoffs.day_key(&point.label).to_string())
+            .or_insert(0) += point.value;
+    }
+    folded
+        .into_iter()
+        .map(|(label, value)| TimeSeriesPoint { label, value })
+        .collect()
+}
+
+fn compact_entries(entries: &[CostLedgerEntry], cutoffs: &Cutoffs) -> Vec<CostLedgerEntry> {
+    let mut folded: BTreeMap<(String, String, String), CostLedgerEntry> = BTreeMap::new();
+    for entry in entries {
+        let day = cutoffs.day_key(&entry.day).to_string();
+        let key = (day.clone(), entry.provider.clone(), entry.model.clone());
+        let slot = folded.entry(key).or_insert_with(|| CostLedgerEntry {
+            day,
+            provider: entry.provider.clone(),
+            model: entry.model.clone(),
+            ..CostLedgerEntry::default()
+        });
+        slot.requests += entry.requests;
+        slot.tokens_in += entry.tokens_in;
+        slot.tokens_out += entry.tokens_out;
+        slot.cost_nano_usd += entry.cost_nano_usd;
+    }
+    folded.into_values().collect()
+}
+
+fn compact_tags(tags: &[CostLedgerTagEntry], cutoffs: &Cutoffs) -> Vec<CostLedgerTagEntry> {
+    let mut folded: BTreeMap<(String, String), CostLedgerTagEntry> = BTreeMap::new();
+    for entry in tags {
+        let day = cutoffs.day_key(&entry.day).to_string();
+        let slot = folded
+            .entry((day.clone(), entry.tag.clone()))
+            .or_insert_with(|| CostLedgerTagEntry {
+                day,
+                tag: entry.tag.clone(),
+                ..CostLedgerTagEntry::default()
+            });
+        slot.requests += entry.requests;
+        slot.tokens_in += entry.tokens_in;
+        slot.tokens_out += entry.tokens_out;
+        slot.cost_nano_usd += entry.cost_nano_usd;
+    }
+    folded.into_values().collect()
+}
+
+fn compact_versions(
+    versions_by_day: &HashMap<String, DayVersions>,
+    cutoffs: &Cutoffs,
+) -> HashMap<String, DayVersions> {
+    // Oldest first, so a month lists its versions in the order they appeared
+    let mut days: Vec<_> = versions_by_day.iter().collect();
+    days.sort_by(|a, b| a.0.cmp(b.0));
+    let mut folded: HashMap<String, DayVersions> = HashMap::new();
+    for (day, versions) in days {
+        let slot = folded.entry(cutoffs.day_key(day).to_string()).or_default();
+        for (seen, new) in [
+            (&mut slot.app, &versions.app),
+            (&mut slot.sidecar, &versions.sidecar),
+        ] {
+            for version in new {
+                if !seen.contains(version) {
+                    seen.push(version.clone());
+                }
+            }
+        }
+    }
+    folded
+}
+
+/// `agg` downsampled for `today` ("YYYY-MM-DD" in the usage time zone).
+/// Totals, model and provider stats are left as they are.
+pub(crate) fn compact(agg: &Aggregate, today: &str, hourly_days: u32) -> Aggregate {
+    let Some(cutoffs) = Cutoffs::new(today, hourly_days) else {
+        return agg.clone();
+    };
+    let mut compacted = agg.clone();
+    compacted.requests_by_hour = compact_hours(&agg.requests_by_hour, &cutoffs);
+    compacted.tokens_by_hour = compact_hours(&agg.tokens_by_hour, &cutoffs);
+    compacted.requests_by_day = compact_days(&agg.requests_by_day, &cutoffs);
+    compacted.tokens_by_day = compact_days(&agg.tokens_by_day, &cutoffs);
+    compacted.cost_ledger.entries = compact_entries(&agg.cost_ledger.entries, &cutoffs);
+    compacted.cost_ledger.tags = compact_tags(&agg.cost_ledger.tags, &cutoffs);
+    compacted.versions_by_day = compact_versions(&agg.versions_by_day, &cutoffs);
+    compacted
+}
+
+/// Resolution of `agg`'s stored series, for labeling charts
+pub(crate) fn granularity(agg: &Aggregate) -> SeriesGranularity {
+    let months = agg
+        .requests_by_day
+        .iter()
+        .map(|p| p.label.as_str())
+        .chain(agg.cost_ledger.entries.iter().map(|e| e.day.as_str()))
+        .filter(|label| label.len() == 7);
+    SeriesGranularity {
+        hourly_days: hourly_days(),
+        monthly_until: months.max().map(str::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::history::update_timeseries;
+
+    const PROVIDERS: [&str; 3] = ["claude", "openai", "gemini"];
+    const MODELS: [&str; 4] = ["sonnet", "opus", "gpt-5", "gemini-2.5-pro"];
+
+    // Two years of traffic: one request per provider and model each hour,
+    // compacted at the end of each day as saving would
+    fn synthetic_years() -> (Aggregate, u64, u64, String) {
+        let mut agg = Aggregate::default();
+        let (mut requests, mut cost) = (0, 0);
+        let first = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let mut today = String::new();
+        for offset in 0..730 {
+            today = (first + chrono::Duration::days(offset))
+                .format("%Y-%m-%d")
+                .to_string();
+            let per_hour = (PROVIDERS.len() * MODELS.len()) as u64;
+            for hour in 0..24 {
+                let hour_label = format!("{}T{:02}", today, hour);
+                update_timeseries(&mut agg.requests_by_hour, &hour_label, per_hour);
+                update_timeseries(&mut agg.tokens_by_hour, &hour_label, per_hour * 100);
+            }
+            update_timeseries(&mut agg.requests_by_day, &today, per_hour * 24);
+            update_timeseries(&mut agg.tokens_by_day, &today, per_hour * 2400);
+            for (p, provider) in PROVIDERS.iter().enumerate() {
+                for (m, model) in MODELS.iter().enumerate() {
+                    // Uneven amounts, so a lost or doubled entry shows
+                    let nano = 24_000_072 * (p as u64 + 1) + 17 * m as u64 + offset as u64;
+                    agg.cost_ledger.entries.push(CostLedgerEntry {
+                        day: today.clone(),
+                        provider: provider.to_string(),
+                        model: model.to_string(),
+                        requests: 24,
+                        tokens_in: 1200,
+                        tokens_out: 1200,
+                        cost_nano_usd: nano,
+                    });
+                    cost += nano;
+                }
+            }
+            requests += per_hour * 24;
+            if needs_compaction(&agg, &today, DEFAULT_HOURLY_DAYS) {
+                agg = compact(&agg, &today, DEFAULT_HOURLY_DAYS);
+            }
+        }
+        (agg, requests, cost, today)
+    }
+
+    #[test]
+    fn a_year_of_data_stays_bounded_and_exact() {
+        let (agg, requests, cost, today) = synthetic_years();
+
+        assert_eq!(
+            agg.requests_by_day.iter().map(|p| p.value).sum::<u64>(),
+            requests
+        );
+        assert_eq!(
+            agg.tokens_by_day.iter().map(|p| p.value).sum::<u64>(),
+            requests * 100
+        );
+        let ledger = &agg.cost_ledger.entries;
+        assert_eq!(ledger.iter().map(|e| e.cost_nano_usd).sum::<u64>(), cost);
+        assert_eq!(ledger.iter().map(|e| e.requests).sum::<u64>(), requests);
+
+        // 14 days of hours, a year of days, whole months before that
+        assert_eq!(agg.requests_by_hour.len(), 14 * 24);
+        assert_eq!(agg.requests_by_hour[0].label, "2026-12-18T00");
+        let months = agg.requests_by_day.iter().filter(|p| p.label.len() == 7);
+        assert_eq!(months.count(), 12);
+        assert!(agg.requests_by_day.len() <= 12 + 365 + 31);
+        assert!(ledger.len() <= (12 + 365 + 31) * PROVIDERS.len() * MODELS.len());
+        assert!(ledger.windows(2).all(|w| w[0].day <= w[1].day));
+
+        let size = serde_json::to_string_pretty(&agg).unwrap().len();
+        assert!(size < 2_000_000, "aggregate grew to {} bytes", size);
+
+        // Idempotent, and nothing left to do the same day
+        assert!(!needs_compaction(&agg, &today, DEFAULT_HOURLY_DAYS));
+        let again = compact(&agg, &today, DEFAULT_HOURLY_DAYS);
+        assert_eq!(
+            serde_json::to_value(&again).unwrap(),
+            serde_json::to_value(&agg).unwrap()
+        );
+    }
+
+    #[test]
+    fn folds_only_months_that_ended_before_the_daily_window() {
+        let cutoffs = Cutoffs::new("2026-03-15", 14).unwrap();
+        assert_eq!(cutoffs.day_key("2025-02-28"), "2025-02");
+        assert_eq!(cutoffs.day_key("2025-03-01"), "2025-03-01");
+        assert_eq!(cutoffs.day_key("2025-02"), "2025-02");
+        assert!(cutoffs.keeps_hour("2026-03-02T00"));
+        assert!(!cutoffs.keeps_hour("2026-03-01T23"));
+
+        let mut agg = Aggregate::default();
+        for (label, value) in [("2025-01-31", 2), ("2025-01", 5), ("2025-02-01", 1)] {
+            update_timeseries(&mut agg.requests_by_day, label, value);
+        }
+        agg.versions_by_day
+            .entry("2025-01-03".to_string())
+            .or_default()
+            .app = vec!["0.3.0".to_string()];
+        agg.versions_by_day
+            .entry("2025-01-20".to_string())
+            .or_default()
+            .app = vec!["0.3.0".to_string(), "0.4.0".to_string()];
+        let compacted = compact(&agg, "2026-03-15", 14);
+        let labels: Vec<_> = compacted
+            .requests_by_day
+            .iter()
+            .map(|p| (p.label.as_str(), p.value))
+            .collect();
+        assert_eq!(labels, [("2025-01", 7), ("2025-02", 1)]);
+        assert_eq!(compacted.versions_by_day["2025-01"].app, ["0.3.0", "0.4.0"]);
+        assert_eq!(
+            granularity(&compacted).monthly_until.as_deref(),
+            Some("2025-02")
+        );
+    }
+}
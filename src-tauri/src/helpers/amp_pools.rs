@@ -0,0 +1,568 @@
+//! Amp provider pools.
+//!
+//! A pool sends a set of Amp models to several providers at once. Each pool
+//! gets a model alias (`amp-pool-<name>`) that every member serves: custom
+//! OpenAI-compatible providers through an extra `openai-compatibility` entry,
+//! connected accounts through `oauth-model-alias`. Amp's models are then
+//! mapped onto that alias, and the proxy's routing strategy shares requests
+//! among the members. A member's weight repeats its API key in the entry, so a
+//! weight-3 provider gets three turns per round; weights don't apply to
+//! connected accounts, which take one turn per account.
+//!
+//! Pools take precedence over flat `amp_model_mappings` for the models they
+//! route.
+
+use std::collections::HashMap;
+
+use crate::config::AppConfig;
+use crate::types::{
+    AmpModelMapping, AmpOpenAIProvider, AmpPool, AmpPoolMember, AmpPoolUsage, AuthStatus,
+    ModelMapping, OpenAICompatibleApiKeyEntry, OpenAICompatibleProvider, RequestLog,
+};
+use crate::utils::{estimate_request_cost, is_amp_provider_path};
+
+pub(crate) const MAX_WEIGHT: u32 = 10;
+
+/// Connected provider id and the `oauth-model-alias` channel its accounts use
+const OAUTH_CHANNELS: &[(&str, &str)] = &[
+    ("claude", "claude"),
+    ("openai", "codex"),
+    ("gemini", "gemini-cli"),
+    ("vertex", "vertex"),
+    ("qwen", "qwen"),
+    ("iflow", "iflow"),
+    ("kiro", "kiro"),
+    ("antigravity", "antigravity"),
+    ("kimi", "kimi"),
+];
+
+fn oauth_channel(provider: &str) -> Option<&'static str> {
+    OAUTH_CHANNELS
+        .iter()
+        .find(|(id, _)| *id == provider)
+        .map(|(_, channel)| *channel)
+}
+
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+const POOL_PREFIX: &str = "amp-pool-";
+
+/// Model name the pool's members serve and Amp's models are mapped to
+pub(crate) fn pool_alias(pool: &AmpPool) -> String {
+    format!("{}{}", POOL_PREFIX, slug(&pool.name))
+}
+
+fn usable(provider: &AmpOpenAIProvider) -> bool {
+    !provider.name.is_empty() && !provider.base_url.is_empty() && !provider.api_key.is_empty()
+}
+
+fn validate_member(
+    member: &AmpPoolMember,
+    providers: &[AmpOpenAIProvider],
+    auth: &AuthStatus,
+) -> Result<(), String> {
+    if member.model.trim().is_empty() {
+        return Err(format!("Pool member {} needs a model", member.provider));
+    }
+    if !(1..=MAX_WEIGHT).contains(&member.weight) {
+        return Err(format!(
+            "Pool member {} has weight {}; weights go from 1 to {}",
+            member.provider, member.weight, MAX_WEIGHT
+        ));
+    }
+    if let Some(provider) = providers.iter().find(|p| p.id == member.provider) {
+        return if usable(provider) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Custom provider {} needs a name, base URL and API key",
+                provider.name
+            ))
+        };
+    }
+    match oauth_channel(&member.provider) {
+        Some(_) if auth.count(&member.provider) > 0 => Ok(()),
+        Some(_) => Err(format!(
+            "No {} account is connected for the pool",
+            member.provider
+        )),
+        None => Err(format!("Unknown pool provider: {}", member.provider)),
+    }
+}
+
+/// Check pools against the custom providers and connected accounts they name.
+/// Names must be distinct and an Amp model can be routed by one enabled pool.
+pub(crate) fn validate_pools(
+    pools: &[AmpPool],
+    providers: &[AmpOpenAIProvider],
+    auth: &AuthStatus,
+) -> Result<(), String> {
+    let mut aliases: HashMap<String, &str> = HashMap::new();
+    let mut routed: HashMap<&str, &str> = HashMap::new();
+    for pool in pools {
+        if slug(&pool.name).is_empty() {
+            return Err("Pool names need at least one letter or digit".to_string());
+        }
+        if let Some(other) = aliases.insert(pool_alias(pool), &pool.name) {
+            return Err(format!(
+                "Pool names \"{}\" and \"{}\" are too similar",
+                other, pool.name
+            ));
+        }
+        if pool.members.is_empty() {
+            return Err(format!("Pool {} has no members", pool.name));
+        }
+        for member in &pool.members {
+            validate_member(member, providers, auth)?;
+        }
+        if !pool.enabled {
+            continue;
+        }
+        for route in &pool.routes {
+            let route = route.trim();
+            if route.is_empty() {
+                return Err(format!("Pool {} has an empty model route", pool.name));
+            }
+            if let Some(other) = routed.insert(route, &pool.name) {
+                return Err(format!(
+                    "{} is routed by both pool {} and pool {}",
+                    route, other, pool.name
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Amp model mappings to apply: every enabled pool's routes, then the flat
+/// mappings for models no pool routes
+pub(crate) fn effective_amp_mappings(config: &AppConfig) -> Vec<AmpModelMapping> {
+    let mut mappings: Vec<AmpModelMapping> = Vec::new();
+    for pool in config.amp_pools.iter().filter(|p| p.enabled) {
+        let alias = pool_alias(pool);
+        for route in &pool.routes {
+            mappings.push(AmpModelMapping {
+                name: route.trim().to_string(),
+                alias: alias.clone(),
+                enabled: true,
+                fork: false,
+            });
+        }
+    }
+    let flat: Vec<AmpModelMapping> = config
+        .amp_model_mappings
+        .iter()
+        .filter(|m| m.enabled && !mappings.iter().any(|p| p.name == m.name))
+        .cloned()
+        .collect();
+    mappings.extend(flat);
+    mappings
+}
+
+/// Whether an `openai-compatibility` entry was generated for a pool member
+/// rather than configured by the user
+pub(crate) fn is_pool_entry(name: &str) -> bool {
+    name.starts_with(POOL_PREFIX)
+}
+
+/// `openai-compatibility` providers for pool members that are custom providers
+pub(crate) fn pool_providers(
+    pools: &[AmpPool],
+    providers: &[AmpOpenAIProvider],
+) -> Vec<OpenAICompatibleProvider> {
+    let mut entries = Vec::new();
+    for pool in pools.iter().filter(|p| p.enabled) {
+        let alias = pool_alias(pool);
+        for (index, member) in pool.members.iter().enumerate() {
+            let Some(provider) = providers
+                .iter()
+                .find(|p| p.id == member.provider && usable(p))
+            else {
+                continue;
+            };
+            let key = OpenAICompatibleApiKeyEntry {
+                api_key: provider.api_key.clone(),
+                proxy_url: None,
+            };
+            entries.push(OpenAICompatibleProvider {
+                name: format!("{}-{}", alias, index + 1),
+                base_url: provider.base_url.clone(),
+                api_key_entries: vec![key; member.weight.clamp(1, MAX_WEIGHT) as usize],
+                models: Some(vec![ModelMapping {
+                    name: member.model.clone(),
+                    alias: Some(alias.clone()),
+                }]),
+                headers: None,
+                prefix: None,
+            });
+        }
+    }
+    entries
+}
+
+/// The same providers as proxy YAML `openai-compatibility` entries
+pub(crate) fn openai_pool_entries(config: &AppConfig) -> Vec<String> {
+    pool_providers(&config.amp_pools, &config.amp_openai_providers)
+        .into_iter()
+        .map(|provider| {
+            let mut entry = String::from("  # Amp pool member\n");
+            entry.push_str(&format!("  - name: \"{}\"\n", provider.name));
+            entry.push_str(&format!("    base-url: \"{}\"\n", provider.base_url));
+            entry.push_str("    schema-cleaner: true\n");
+            entry.push_str("    api-key-entries:\n");
+            for key in &provider.api_key_entries {
+                entry.push_str(&format!("      - api-key: \"{}\"\n", key.api_key));
+            }
+            entry.push_str("    models:\n");
+            for model in provider.models.iter().flatten() {
+                entry.push_str(&format!(
+                    "      - alias: \"{}\"\n",
+                    model.alias.as_deref().unwrap_or_default()
+                ));
+                entry.push_str(&format!("        name: \"{}\"\n", model.name));
+            }
+            entry
+        })
+        .collect()
+}
+
+/// `oauth-model-alias` section for pool members that are connected accounts
+pub(crate) fn oauth_alias_section(config: &AppConfig) -> String {
+    let mut by_channel: Vec<(&str, Vec<String>)> = Vec::new();
+    for pool in config.amp_pools.iter().filter(|p| p.enabled) {
+        let alias = pool_alias(pool);
+        for member in &pool.members {
+            let Some(channel) = oauth_channel(&member.provider) else {
+                continue;
+            };
+            let entry = format!(
+                "    - name: \"{}\"\n      alias: \"{}\"\n      fork: true\n",
+                member.model, alias
+            );
+            match by_channel.iter_mut().find(|(c, _)| *c == channel) {
+                Some((_, entries)) => entries.push(entry),
+                None => by_channel.push((channel, vec![entry])),
+            }
+        }
+    }
+    if by_channel.is_empty() {
+        return String::new();
+    }
+    let mut section =
+        String::from("# Amp pools served by connected accounts\noauth-model-alias:\n");
+    for (channel, entries) in by_channel {
+        section.push_str(&format!("  {}:\n", channel));
+        for entry in entries {
+            section.push_str(&entry);
+        }
+    }
+    section.push('\n');
+    section
+}
+
+/// Pools made explicit from flat mappings whose target model is served by
+/// several custom providers, which the proxy already shared between them.
+/// Returns the pools and the mappings that stay flat.
+pub(crate) fn pools_from_mappings(
+    mappings: &[AmpModelMapping],
+    providers: &[AmpOpenAIProvider],
+) -> (Vec<AmpPool>, Vec<AmpModelMapping>) {
+    let mut pools: Vec<AmpPool> = Vec::new();
+    let mut remaining = Vec::new();
+    for mapping in mappings {
+        let members: Vec<AmpPoolMember> = providers
+            .iter()
+            .filter(|provider| usable(provider))
+            .filter_map(|provider| {
+                let model = provider.models.iter().find(|m| m.alias == mapping.alias)?;
+                Some(AmpPoolMember {
+                    provider: provider.id.clone(),
+                    model: model.name.clone(),
+                    weight: 1,
+                })
+            })
+            .collect();
+        if !mapping.enabled || mapping.fork || members.len() < 2 {
+            remaining.push(mapping.clone());
+            continue;
+        }
+        match pools.iter_mut().find(|p| p.name == mapping.alias) {
+            Some(pool) => pool.routes.push(mapping.name.clone()),
+            None => pools.push(AmpPool {
+                id: crate::types::amp::generate_uuid(),
+                name: mapping.alias.clone(),
+                routes: vec![mapping.name.clone()],
+                members,
+                enabled: true,
+            }),
+        }
+    }
+    (pools, remaining)
+}
+
+/// Whether a request was sent by Amp through `pool`: an Amp provider route
+/// asking for one of the pool's models, its alias or a member's model
+fn served_by(request: &RequestLog, pool: &AmpPool, alias: &str) -> bool {
+    is_amp_provider_path(&request.path)
+        && (request.model == alias
+            || pool.routes.iter().any(|r| r.trim() == request.model)
+            || pool.members.iter().any(|m| m.model == request.model))
+}
+
+/// Requests in history attributed to each pool, in pool order
+pub(crate) fn usage_by_pool(requests: &[RequestLog], pools: &[AmpPool]) -> Vec<AmpPoolUsage> {
+    pools
+        .iter()
+        .map(|pool| {
+            let alias = pool_alias(pool);
+            let mut usage = AmpPoolUsage {
+                pool_id: pool.id.clone(),
+                name: pool.name.clone(),
+                ..AmpPoolUsage::default()
+            };
+            for req in requests
+                .iter()
+                .filter(|r| !r.synthetic && served_by(r, pool, &alias))
+            {
+                usage.requests += 1;
+                if req.status < 400 {
+                    usage.success_count += 1;
+                }
+                let (tokens_in, tokens_out) =
+                    (req.tokens_in.unwrap_or(0), req.tokens_out.unwrap_or(0));
+                usage.tokens_in += tokens_in as u64;
+                usage.tokens_out += tokens_out as u64;
+                usage.estimated_cost += estimate_request_cost(&req.model, tokens_in, tokens_out);
+                usage.last_used = usage.last_used.max(req.timestamp);
+            }
+            usage
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AmpOpenAIModel;
+
+    fn provider(id: &str, alias: &str, name: &str) -> AmpOpenAIProvider {
+        AmpOpenAIProvider {
+            id: id.to_string(),
+            name: format!("Provider {}", id),
+            base_url: format!("https://{}.example.com/v1", id),
+            api_key: format!("sk-{}", id),
+            models: vec![AmpOpenAIModel {
+                name: name.to_string(),
+                alias: alias.to_string(),
+            }],
+        }
+    }
+
+    fn member(provider: &str, model: &str, weight: u32) -> AmpPoolMember {
+        AmpPoolMember {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            weight,
+        }
+    }
+
+    fn pool(name: &str, routes: &[&str], members: Vec<AmpPoolMember>) -> AmpPool {
+        AmpPool {
+            id: format!("id-{}", name),
+            name: name.to_string(),
+            routes: routes.iter().map(|r| r.to_string()).collect(),
+            members,
+            enabled: true,
+        }
+    }
+
+    fn mapping(name: &str, alias: &str) -> AmpModelMapping {
+        AmpModelMapping {
+            name: name.to_string(),
+            alias: alias.to_string(),
+            enabled: true,
+            fork: false,
+        }
+    }
+
+    fn pooled_config() -> AppConfig {
+        AppConfig {
+            amp_openai_providers: vec![provider("a", "glm", "glm-4.6")],
+            amp_pools: vec![pool(
+                "Smart Pool",
+                &["claude-opus-4-5-20251101"],
+                vec![
+                    member("a", "glm-4.6", 2),
+                    member("claude", "claude-opus-4-5", 1),
+                ],
+            )],
+            amp_model_mappings: vec![
+                mapping("claude-opus-4-5-20251101", "gpt-5"),
+                mapping("claude-haiku-4-5", "gemini-2.5-flash"),
+            ],
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn members_must_exist() {
+        let providers = vec![provider("a", "glm", "glm-4.6")];
+        let auth = AuthStatus {
+            claude: 1,
+            ..AuthStatus::default()
+        };
+        let good = pool(
+            "smart",
+            &["m"],
+            vec![member("a", "glm-4.6", 1), member("claude", "x", 1)],
+        );
+        assert!(validate_pools(std::slice::from_ref(&good), &providers, &auth).is_ok());
+
+        for bad in [
+            member("missing", "x", 1),
+            member("gemini", "x", 1), // not connected
+            member("a", "", 1),
+            member("a", "glm-4.6", 0),
+            member("a", "glm-4.6", MAX_WEIGHT + 1),
+        ] {
+            let invalid = pool("smart", &["m"], vec![bad]);
+            assert!(validate_pools(&[invalid], &providers, &auth).is_err());
+        }
+        assert!(validate_pools(&[pool("smart", &["m"], vec![])], &providers, &auth).is_err());
+
+        // Names that collide as aliases, and routes claimed twice
+        let twin = AmpPool {
+            id: "other".to_string(),
+            name: "Smart!".to_string(),
+            routes: vec!["n".to_string()],
+            ..good.clone()
+        };
+        assert!(validate_pools(&[good.clone(), twin], &providers, &auth).is_err());
+        let overlap = pool("fast", &["m"], good.members.clone());
+        assert!(validate_pools(&[good, overlap], &providers, &auth).is_err());
+    }
+
+    #[test]
+    fn pools_override_flat_mappings() {
+        let mappings = effective_amp_mappings(&pooled_config());
+        let pairs: Vec<(&str, &str)> = mappings
+            .iter()
+            .map(|m| (m.name.as_str(), m.alias.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("claude-opus-4-5-20251101", "amp-pool-smart-pool"),
+                ("claude-haiku-4-5", "gemini-2.5-flash"),
+            ]
+        );
+    }
+
+    #[test]
+    fn pools_emit_proxy_yaml() {
+        let config = pooled_config();
+        let entries = openai_pool_entries(&config);
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].contains("- name: \"amp-pool-smart-pool-1\""));
+        assert_eq!(entries[0].matches("- api-key: \"sk-a\"").count(), 2);
+        assert!(entries[0].contains("alias: \"amp-pool-smart-pool\"\n        name: \"glm-4.6\""));
+
+        let section = oauth_alias_section(&config);
+        assert!(section.starts_with(
+            "# Amp pools served by connected accounts\noauth-model-alias:\n  claude:\n"
+        ));
+        assert!(section.contains("name: \"claude-opus-4-5\"\n      alias: \"amp-pool-smart-pool\""));
+
+        let disabled = AppConfig {
+            amp_pools: vec![AmpPool {
+                enabled: false,
+                ..config.amp_pools[0].clone()
+            }],
+            ..config
+        };
+        assert!(openai_pool_entries(&disabled).is_empty());
+        assert!(oauth_alias_section(&disabled).is_empty());
+    }
+
+    #[test]
+    fn migrates_shared_mapping_targets_into_pools() {
+        let providers = vec![
+            provider("a", "glm", "glm-4.6"),
+            provider("b", "glm", "glm-4.6-air"),
+            provider("c", "kimi", "kimi-k2"),
+        ];
+        let mappings = vec![
+            mapping("claude-opus-4-5-20251101", "glm"),
+            mapping("claude-sonnet-4-5", "glm"),
+            mapping("claude-haiku-4-5", "kimi"),
+        ];
+        let (pools, remaining) = pools_from_mappings(&mappings, &providers);
+
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].name, "glm");
+        assert_eq!(
+            pools[0].routes,
+            ["claude-opus-4-5-20251101", "claude-sonnet-4-5"]
+        );
+        assert_eq!(
+            pools[0].members,
+            [member("a", "glm-4.6", 1), member("b", "glm-4.6-air", 1)]
+        );
+        // One provider behind the target: nothing to pool
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].name, "claude-haiku-4-5");
+    }
+
+    #[test]
+    fn attributes_amp_requests_to_pools() {
+        let config = pooled_config();
+        let request = |path: &str, model: &str| RequestLog {
+            id: "r".to_string(),
+            timestamp: 5,
+            provider: "claude".to_string(),
+            model: model.to_string(),
+            method: "POST".to_string(),
+            path: path.to_string(),
+            status: 200,
+            duration_ms: 100,
+            ttfb_ms: None,
+            tokens_in: Some(10),
+            tokens_out: Some(5),
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        };
+        let requests = vec![
+            request(
+                "/api/provider/anthropic/v1/messages",
+                "claude-opus-4-5-20251101",
+            ),
+            request("/api/provider/openai/v1/chat/completions", "glm-4.6"),
+            // Not through Amp's provider routes
+            request("/v1/messages", "claude-opus-4-5-20251101"),
+            request("/api/provider/anthropic/v1/messages", "claude-haiku-4-5"),
+        ];
+        let usage = usage_by_pool(&requests, &config.amp_pools);
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].requests, 2);
+        assert_eq!(usage[0].tokens_in, 20);
+        assert_eq!(usage[0].last_used, 5);
+    }
+}
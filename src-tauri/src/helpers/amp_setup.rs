@@ -0,0 +1,140 @@
+//! Amp onboarding checks.
+//!
+//! Amp reaches its models through the proxy's `/api/provider/<name>/` routes
+//! and everything else (sign-in, threads) through the `ampcode` upstream,
+//! which authenticates with `AppConfig.amp_api_key`. `amp_routing_mode`
+//! says how Amp's models find a provider: "mappings" sends them through the
+//! model mappings and pools, "openai" straight to the custom
+//! OpenAI-compatible providers. A mode whose routes are missing would send
+//! Amp's requests on to ampcode.com without a word, so it is refused instead.
+
+use crate::config::AppConfig;
+use crate::helpers::amp_pools::effective_amp_mappings;
+use crate::types::ProviderTestResult;
+
+/// Amp endpoint that answers only with a valid key, relative to the proxy
+pub(crate) const KEY_CHECK_ENDPOINT: &str = "api/user";
+
+/// `amp_routing_mode` values
+pub(crate) const ROUTING_MODES: &[&str] = &["mappings", "openai"];
+
+/// Refuse a routing mode that leaves Amp's models without a route
+pub(crate) fn validate_routing(config: &AppConfig) -> Result<(), String> {
+    let providers: Vec<&str> = config
+        .amp_openai_providers
+        .iter()
+        .filter(|p| {
+            !p.name.is_empty()
+                && !p.base_url.is_empty()
+                && !p.api_key.is_empty()
+                && !p.models.is_empty()
+        })
+        .map(|p| p.name.as_str())
+        .collect();
+    match config.amp_routing_mode.as_str() {
+        "mappings" => {
+            if effective_amp_mappings(config).is_empty() && !providers.is_empty() {
+                return Err(format!(
+                    "Amp routing is set to model mappings, but no mapping or pool is enabled, \
+                     so {} would never be used. Add a mapping or switch routing to \"openai\".",
+                    providers.join(", ")
+                ));
+            }
+            Ok(())
+        }
+        "openai" => {
+            if providers.is_empty() {
+                return Err(
+                    "Amp routing is set to \"openai\", but no custom provider has a base URL, \
+                     API key and models. Add one or switch routing to model mappings."
+                        .to_string(),
+                );
+            }
+            Ok(())
+        }
+        other => Err(format!(
+            "Unknown Amp routing mode \"{}\" (expected {})",
+            other,
+            ROUTING_MODES.join(" or ")
+        )),
+    }
+}
+
+/// What Amp's answer to the key check says about the key
+pub(crate) fn key_check_result(status: u16, latency_ms: u64) -> ProviderTestResult {
+    let (success, message) = match status {
+        200..=299 => (true, "Amp accepted the API key".to_string()),
+        401 | 403 => (
+            false,
+            "Amp rejected the API key. Copy it again from ampcode.com/settings.".to_string(),
+        ),
+        404 => (
+            false,
+            "The proxy has no Amp routes; update CLIProxyAPI to use Amp".to_string(),
+        ),
+        status => (false, format!("Amp answered with HTTP {}", status)),
+    };
+    ProviderTestResult {
+        success,
+        message,
+        latency_ms: Some(latency_ms),
+        models_found: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AmpModelMapping, AmpOpenAIModel, AmpOpenAIProvider};
+
+    fn provider() -> AmpOpenAIProvider {
+        AmpOpenAIProvider {
+            id: "zai".to_string(),
+            name: "Z.ai".to_string(),
+            base_url: "https://api.z.ai/api/coding/paas/v4".to_string(),
+            api_key: "sk-zai".to_string(),
+            models: vec![AmpOpenAIModel {
+                name: "glm-4.6".to_string(),
+                alias: "glm-4.6".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn refuses_a_routing_mode_without_routes() {
+        let mut config = AppConfig::default();
+        assert!(validate_routing(&config).is_ok());
+
+        // Providers only reachable through mappings nobody set up
+        config.amp_openai_providers = vec![provider()];
+        assert!(validate_routing(&config).unwrap_err().contains("Z.ai"));
+        config.amp_model_mappings = vec![AmpModelMapping {
+            name: "claude-opus-4-5-20251101".to_string(),
+            alias: "glm-4.6".to_string(),
+            enabled: true,
+            fork: false,
+        }];
+        assert!(validate_routing(&config).is_ok());
+
+        config.amp_routing_mode = "openai".to_string();
+        assert!(validate_routing(&config).is_ok());
+        config.amp_openai_providers[0].api_key.clear();
+        assert!(validate_routing(&config).is_err());
+
+        config.amp_routing_mode = "providers".to_string();
+        assert!(validate_routing(&config)
+            .unwrap_err()
+            .contains("Unknown Amp routing mode"));
+    }
+
+    #[test]
+    fn reads_the_key_check_status() {
+        assert!(key_check_result(200, 120).success);
+        assert!(!key_check_result(401, 80).success);
+        assert!(key_check_result(404, 5).message.contains("CLIProxyAPI"));
+        assert_eq!(
+            key_check_result(502, 30).message,
+            "Amp answered with HTTP 502"
+        );
+    }
+}
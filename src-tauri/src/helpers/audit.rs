@@ -0,0 +1,245 @@
+//! Append-only audit log of configuration and credential changes.
+//!
+//! Mutating commands (settings saves, provider disconnects, credential
+//! imports, API key edits, agent configuration) append one JSON line to
+//! `<config_dir>/audit.log` with the command, a summary of the changed
+//! fields and the outcome. Secret values never reach the file: changed
+//! secret fields are listed by name only, and everything else goes through
+//! `redact`.
+//!
+//! Recording is best-effort; a failed write is logged and never fails the
+//! command being audited. The file rotates to `audit.log.1` at 1 MiB.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use parking_lot::Mutex;
+use serde_json::Value;
+
+use crate::config::AppConfig;
+use crate::types::AuditEntry;
+
+/// Size at which `audit.log` is rotated to `audit.log.1`
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Longest value shown in a change summary
+const MAX_VALUE_CHARS: usize = 60;
+
+lazy_static::lazy_static! {
+    // Serializes appends and rotation between commands
+    static ref WRITE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+pub(crate) fn audit_log_path() -> PathBuf {
+    crate::config::get_proxypal_config_dir().join("audit.log")
+}
+
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+fn append_to(path: &Path, entry: &AuditEntry) -> std::io::Result<()> {
+    let line = serde_json::to_string(entry)?;
+    let _guard = WRITE_LOCK.lock();
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    if size > 0 && size + line.len() as u64 + 1 > MAX_LOG_BYTES {
+        std::fs::rename(path, rotated_path(path))?;
+    }
+    let is_new = !path.exists();
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if is_new {
+        crate::helpers::permissions::restrict_after_write(path);
+    }
+    writeln!(file, "{}", line)
+}
+
+/// Record a mutating command and its outcome. Never fails.
+pub(crate) fn record<T, E: std::fmt::Display>(
+    command: &str,
+    changes: Vec<String>,
+    result: &Result<T, E>,
+) {
+    let entry = AuditEntry {
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        command: command.to_string(),
+        changes: changes.iter().map(|c| crate::redact::redact(c)).collect(),
+        outcome: if result.is_ok() { "ok" } else { "error" }.to_string(),
+        error: result
+            .as_ref()
+            .err()
+            .map(|e| crate::redact::redact(&e.to_string())),
+    };
+    if let Err(e) = append_to(&audit_log_path(), &entry) {
+        eprintln!("[ProxyPal] Failed to write audit log: {}", e);
+    }
+}
+
+fn is_secret_field(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    ["key", "password", "secret", "token"]
+        .iter()
+        .any(|word| name.contains(word))
+}
+
+fn short(value: &Value) -> String {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.chars().count() > MAX_VALUE_CHARS {
+        let cut: String = text.chars().take(MAX_VALUE_CHARS).collect();
+        format!("{}…", cut)
+    } else {
+        text
+    }
+}
+
+/// One summary line per top-level config field that differs
+pub(crate) fn config_changes(old: &AppConfig, new: &AppConfig) -> Vec<String> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) =
+        (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+    let mut changes = Vec::new();
+    for (field, after) in &new {
+        let before = old.get(field).unwrap_or(&Value::Null);
+        if before == after {
+            continue;
+        }
+        let change = match (before, after) {
+            (Value::Array(a), Value::Array(b)) if a.len() != b.len() => {
+                format!("{}: {} → {} entries", field, a.len(), b.len())
+            }
+            (Value::Array(_), _) | (Value::Object(_), _) | (_, Value::Object(_)) => {
+                format!("{} changed", field)
+            }
+            _ if is_secret_field(field) => format!("{} changed", field),
+            _ => format!("{}: {} → {}", field, short(before), short(after)),
+        };
+        changes.push(change);
+    }
+    changes
+}
+
+/// Newest entries first, at most `limit`, optionally only those whose
+/// command, changes or error contain `filter` (case-insensitive)
+pub(crate) fn read_entries(path: &Path, limit: usize, filter: Option<&str>) -> Vec<AuditEntry> {
+    let filter = filter
+        .map(|f| f.trim().to_lowercase())
+        .filter(|f| !f.is_empty());
+    let mut entries = Vec::new();
+    for file in [rotated_path(path), path.to_path_buf()] {
+        let Ok(file) = std::fs::File::open(&file) else {
+            continue;
+        };
+        for line in std::io::BufReader::new(file).lines().map_while(Result::ok) {
+            // A line cut short by a crash is skipped rather than failing the read
+            if let Ok(entry) = serde_json::from_str::<AuditEntry>(&line) {
+                entries.push(entry);
+            }
+        }
+    }
+    entries.reverse();
+    entries
+        .into_iter()
+        .filter(|entry| match &filter {
+            Some(filter) => {
+                entry.command.to_lowercase().contains(filter)
+                    || entry
+                        .changes
+                        .iter()
+                        .any(|c| c.to_lowercase().contains(filter))
+                    || entry
+                        .error
+                        .as_deref()
+                        .is_some_and(|e| e.to_lowercase().contains(filter))
+            }
+            None => true,
+        })
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_config_changes_without_secret_values() {
+        let old = AppConfig::default();
+        let new = AppConfig {
+            routing_strategy: "fill-first".to_string(),
+            management_key: "new-management-key-value".to_string(),
+            claude_api_keys: vec![crate::types::ClaudeApiKey {
+                api_key: "sk-ant-secret-value".to_string(),
+                base_url: None,
+                proxy_url: None,
+                headers: None,
+                models: None,
+                excluded_models: None,
+                prefix: None,
+            }],
+            ..AppConfig::default()
+        };
+        let changes = config_changes(&old, &new);
+        assert!(changes.contains(&format!(
+            "routingStrategy: {} → fill-first",
+            old.routing_strategy
+        )));
+        assert!(changes.contains(&"managementKey changed".to_string()));
+        assert!(changes.contains(&"claudeApiKeys: 0 → 1 entries".to_string()));
+        assert!(!changes
+            .iter()
+            .any(|c| c.contains("new-management-key-value")));
+        assert!(config_changes(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn appends_rotates_and_filters() {
+        let dir = std::env::temp_dir().join(format!("proxypal-audit-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.log");
+        let entry = |command: &str, changes: Vec<&str>| AuditEntry {
+            timestamp: 1,
+            command: command.to_string(),
+            changes: changes.into_iter().map(str::to_string).collect(),
+            outcome: "ok".to_string(),
+            error: None,
+        };
+        append_to(&path, &entry("save_config", vec!["routingStrategy: a → b"])).unwrap();
+        append_to(
+            &path,
+            &entry("disconnect_provider", vec!["provider: claude"]),
+        )
+        .unwrap();
+
+        let all = read_entries(&path, 10, None);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].command, "disconnect_provider");
+        let routing = read_entries(&path, 10, Some("ROUTING"));
+        assert_eq!(routing.len(), 1);
+        assert_eq!(read_entries(&path, 1, None).len(), 1);
+
+        // Past the size limit the current file moves aside; both are still read
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .set_len(MAX_LOG_BYTES)
+            .unwrap();
+        append_to(&path, &entry("set_gemini_api_keys", vec![])).unwrap();
+        assert!(rotated_path(&path).exists());
+        assert!(std::fs::metadata(&path).unwrap().len() < 1024);
+        assert_eq!(
+            read_entries(&path, 10, None)[0].command,
+            "set_gemini_api_keys"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
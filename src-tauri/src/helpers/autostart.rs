@@ -0,0 +1,59 @@
+//! Launch-at-login registration via tauri-plugin-autostart.
+//!
+//! The plugin writes a LaunchAgent plist on macOS, a `Run` registry value on
+//! Windows and an XDG `.desktop` file under `~/.config/autostart` on Linux.
+
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+
+/// Launch argument passed by the OS autostart entry so the app starts in the tray
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+/// Whether this process was started with `--minimized`
+pub fn launched_minimized() -> bool {
+    std::env::args().any(|arg| arg == MINIMIZED_ARG)
+}
+
+/// Enable or disable the OS autostart entry.
+pub fn set_launch_at_login(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|e| {
+        format!(
+            "Failed to {} launch at login: {}",
+            if enabled { "enable" } else { "disable" },
+            e
+        )
+    })
+}
+
+/// Make the OS autostart entry match `wanted`, fixing drift in either direction.
+pub fn reconcile_launch_at_login(app: &AppHandle, wanted: bool) {
+    let registered = match app.autolaunch().is_enabled() {
+        Ok(registered) => registered,
+        Err(e) => {
+            eprintln!("[ProxyPal] Could not query launch-at-login state: {}", e);
+            return;
+        }
+    };
+    if registered == wanted {
+        return;
+    }
+
+    println!(
+        "[ProxyPal] Launch at login is {} in config but {} in the OS; correcting",
+        if wanted { "enabled" } else { "disabled" },
+        if registered {
+            "registered"
+        } else {
+            "not registered"
+        }
+    );
+    if let Err(e) = set_launch_at_login(app, wanted) {
+        eprintln!("[ProxyPal] {}", e);
+    }
+}
@@ -0,0 +1,453 @@
+//! Captured exchanges for request replay.
+//!
+//! With request logging on, the proxy writes every exchange to its own file in
+//! `logs/` (headers, request body and the full response). Those files are the
+//! captures `replay_request` starts from; their file name is the capture id.
+//! Replays are saved by ProxyPal as JSON in `captures/`, linked to the capture
+//! they replayed, and only the newest [`MAX_REPLAYS`] are kept.
+
+use std::path::PathBuf;
+
+use similar::{ChangeTag, TextDiff};
+
+use crate::helpers::permissions;
+use crate::types::{Capture, CaptureSummary, ReplayOverrides, ResponseDiffSummary};
+
+/// Replays kept in `captures/`; older ones are deleted
+pub(crate) const MAX_REPLAYS: usize = 200;
+
+// Request headers worth sending again; auth is replaced with the proxy key and
+// the rest (host, length, encodings) is set by the HTTP client
+const FORWARDED_HEADERS: &[&str] = &[
+    "content-type",
+    "accept",
+    "anthropic-version",
+    "anthropic-beta",
+    "openai-beta",
+    "user-agent",
+];
+
+fn captures_dir() -> PathBuf {
+    crate::config::get_data_dir().join("captures")
+}
+
+fn request_logs_dir() -> PathBuf {
+    crate::config::get_data_dir().join("logs")
+}
+
+// Ids are file names; refuse anything that could leave the directory
+fn check_id(id: &str) -> Result<(), String> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        return Err(format!("Invalid capture id: {}", id));
+    }
+    Ok(())
+}
+
+fn is_request_log(name: &str) -> bool {
+    name.ends_with(".log") && name != "main.log" && !name.starts_with("main-")
+}
+
+// "=== NAME ===" section markers of a request log file
+fn section_name(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix("=== ")?
+        .strip_suffix(" ===")
+        .map(str::trim)
+}
+
+fn split_sections(contents: &str) -> Vec<(&str, String)> {
+    let mut sections: Vec<(&str, String)> = Vec::new();
+    for line in contents.lines() {
+        if let Some(name) = section_name(line) {
+            sections.push((name, String::new()));
+        } else if let Some((_, text)) = sections.last_mut() {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    sections
+}
+
+fn header_line(line: &str) -> Option<(String, String)> {
+    let (name, value) = line.split_once(':')?;
+    let name = name.trim();
+    if name.is_empty() || name.contains(' ') {
+        return None;
+    }
+    Some((name.to_string(), value.trim().to_string()))
+}
+
+fn is_auth_header(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "authorization" | "x-api-key" | "x-goog-api-key" | "cookie" | "proxy-authorization"
+    )
+}
+
+/// Parse a request log file written by the proxy. `None` if it has no
+/// request section (e.g. not a request log).
+pub(crate) fn parse_request_log(id: &str, contents: &str) -> Option<Capture> {
+    let sections = split_sections(contents);
+    let section = |name: &str| {
+        sections
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, text)| text.as_str())
+    };
+
+    let info = section("REQUEST INFO")?;
+    let field = |key: &str| {
+        info.lines()
+            .filter_map(header_line)
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    };
+    let path = field("URL")?;
+    let method = field("Method").unwrap_or_else(|| "POST".to_string());
+    let timestamp = field("Timestamp")
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok())
+        .map(|t| t.timestamp_millis() as u64)
+        .unwrap_or(0);
+
+    let headers = section("HEADERS")
+        .unwrap_or("")
+        .lines()
+        .filter_map(header_line)
+        .filter(|(name, _)| !is_auth_header(name))
+        .collect();
+    let body = section("REQUEST BODY").unwrap_or("").trim().to_string();
+
+    // "Status: 200", response headers, a blank line, then the body
+    let mut response_status = None;
+    let mut response_body = String::new();
+    if let Some(response) = section("RESPONSE") {
+        let mut lines = response.lines();
+        for line in lines.by_ref() {
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some(("Status", status)) = line.split_once(':').map(|(k, v)| (k.trim(), v)) {
+                response_status = status.trim().parse().ok();
+            }
+        }
+        response_body = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+    }
+
+    Some(Capture {
+        id: id.to_string(),
+        timestamp,
+        method,
+        path,
+        headers,
+        body,
+        response_status,
+        response_body,
+        duration_ms: None,
+        replay_of: None,
+        overrides: None,
+    })
+}
+
+/// A replay saved by ProxyPal, or a request log file from the proxy
+pub(crate) fn load(id: &str) -> Result<Capture, String> {
+    check_id(id)?;
+    let replay = captures_dir().join(format!("{}.json", id));
+    if let Ok(contents) = std::fs::read_to_string(&replay) {
+        return serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to read capture {}: {}", id, e));
+    }
+
+    let log = request_logs_dir().join(id);
+    let contents = std::fs::read_to_string(&log).map_err(|_| {
+        format!(
+            "Capture {} not found. Turn on request logging to capture requests.",
+            id
+        )
+    })?;
+    let mut capture =
+        parse_request_log(id, &contents).ok_or_else(|| format!("{} is not a request log", id))?;
+    if capture.timestamp == 0 {
+        capture.timestamp = modified_ms(&log);
+    }
+    Ok(capture)
+}
+
+fn modified_ms(path: &std::path::Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Save a replay, readable only by the user since it holds request and
+/// response bodies, and drop the oldest beyond [`MAX_REPLAYS`]
+pub(crate) fn save(capture: &Capture) -> Result<(), String> {
+    let dir = captures_dir();
+    permissions::create_private_dir(&dir)
+        .map_err(|e| format!("Failed to create captures directory: {}", e))?;
+    let data = serde_json::to_string_pretty(capture)
+        .map_err(|e| format!("Failed to serialize capture: {}", e))?;
+    let path = dir.join(format!("{}.json", capture.id));
+    std::fs::write(&path, data).map_err(|e| format!("Failed to save capture: {}", e))?;
+    permissions::restrict_after_write(&path);
+
+    let mut replays: Vec<(u64, PathBuf)> = std::fs::read_dir(&dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .map(|p| (modified_ms(&p), p))
+                .collect()
+        })
+        .unwrap_or_default();
+    if replays.len() > MAX_REPLAYS {
+        replays.sort();
+        for (_, path) in &replays[..replays.len() - MAX_REPLAYS] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    Ok(())
+}
+
+/// Newest captures first, from both request logs and replays
+pub(crate) fn list(limit: usize) -> Vec<CaptureSummary> {
+    let mut files: Vec<(u64, PathBuf)> = [captures_dir(), request_logs_dir()]
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with(".json") || is_request_log(n))
+        })
+        .map(|p| (modified_ms(&p), p))
+        .collect();
+    files.sort_by_key(|f| std::cmp::Reverse(f.0));
+
+    files
+        .into_iter()
+        .filter_map(|(_, path)| {
+            let name = path.file_name()?.to_str()?;
+            let id = name.strip_suffix(".json").unwrap_or(name);
+            load(id).ok()
+        })
+        .take(limit)
+        .map(|c| CaptureSummary {
+            id: c.id,
+            timestamp: c.timestamp,
+            method: c.method,
+            path: c.path,
+            response_status: c.response_status,
+            replay_of: c.replay_of,
+        })
+        .collect()
+}
+
+/// Headers of `capture` to send with the replay
+pub(crate) fn forwarded_headers(capture: &Capture) -> Vec<(String, String)> {
+    capture
+        .headers
+        .iter()
+        .filter(|(name, _)| FORWARDED_HEADERS.contains(&name.to_ascii_lowercase().as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Apply `overrides` to a captured request, returning the path and body to
+/// send. Gemini-style paths carry the model in the URL; OpenAI Responses and
+/// Gemini bodies name their token limit differently.
+pub(crate) fn apply_overrides(
+    path: &str,
+    body: &str,
+    overrides: &ReplayOverrides,
+) -> Result<(String, String), String> {
+    if overrides == &ReplayOverrides::default() {
+        return Ok((path.to_string(), body.to_string()));
+    }
+    let mut json: serde_json::Value = serde_json::from_str(body)
+        .map_err(|_| "Only JSON request bodies can be overridden".to_string())?;
+    let object = json
+        .as_object_mut()
+        .ok_or_else(|| "Only JSON object request bodies can be overridden".to_string())?;
+
+    let mut path = path.to_string();
+    // /v1beta/models/<model>:generateContent
+    let gemini_model = path
+        .split_once("/models/")
+        .and_then(|(_, rest)| rest.split_once(':'))
+        .map(|(model, _)| model.to_string());
+
+    if let Some(model) = &overrides.model {
+        match &gemini_model {
+            Some(old) => {
+                path = path.replacen(
+                    &format!("/models/{}:", old),
+                    &format!("/models/{}:", model),
+                    1,
+                )
+            }
+            None => {
+                object.insert("model".to_string(), model.clone().into());
+            }
+        }
+    }
+    if let Some(max_tokens) = overrides.max_tokens {
+        if gemini_model.is_some() {
+            let generation = object
+                .entry("generationConfig")
+                .or_insert_with(|| serde_json::json!({}));
+            if let Some(generation) = generation.as_object_mut() {
+                generation.insert("maxOutputTokens".to_string(), max_tokens.into());
+            }
+        } else if path.contains("/responses") {
+            object.insert("max_output_tokens".to_string(), max_tokens.into());
+        } else {
+            object.insert("max_tokens".to_string(), max_tokens.into());
+        }
+    }
+
+    let body = serde_json::to_string(&json).map_err(|e| e.to_string())?;
+    Ok((path, body))
+}
+
+/// Compare the captured response with the replayed one
+pub(crate) fn diff_summary(
+    old_status: Option<u16>,
+    old_body: &str,
+    new_status: u16,
+    new_body: &str,
+) -> ResponseDiffSummary {
+    let (mut lines_added, mut lines_removed) = (0, 0);
+    for change in TextDiff::from_lines(old_body, new_body).iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => lines_added += 1,
+            ChangeTag::Delete => lines_removed += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    let identical = old_status == Some(new_status) && old_body == new_body;
+
+    let mut parts = Vec::new();
+    match old_status {
+        Some(old) if old != new_status => parts.push(format!("Status {} → {}", old, new_status)),
+        Some(_) => parts.push(format!("Status {} (unchanged)", new_status)),
+        None => parts.push(format!("Status {}", new_status)),
+    }
+    parts.push(format!("{} → {} bytes", old_body.len(), new_body.len()));
+    if identical {
+        parts.push("identical response".to_string());
+    } else {
+        parts.push(format!("+{}/-{} lines", lines_added, lines_removed));
+    }
+
+    ResponseDiffSummary {
+        old_status,
+        new_status,
+        old_bytes: old_body.len(),
+        new_bytes: new_body.len(),
+        identical,
+        lines_added,
+        lines_removed,
+        summary: parts.join("; "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUEST_LOG: &str = "=== REQUEST INFO ===
+Version: 6.6.0
+URL: /v1/chat/completions
+Method: POST
+Timestamp: 2026-03-10T14:02:11.123+01:00
+
+=== HEADERS ===
+Content-Type: application/json
+Authorization: Bearer sk-local
+User-Agent: codex/0.40
+
+=== REQUEST BODY ===
+{\"model\":\"gpt-5\",\"messages\":[{\"role\":\"user\",\"content\":\"hi\"}]}
+
+=== RESPONSE ===
+Status: 429
+Content-Type: application/json
+
+{\"error\":{\"message\":\"rate limited\"}}
+";
+
+    #[test]
+    fn parses_request_log_files() {
+        let capture = parse_request_log("v1-chat-completions-1.log", REQUEST_LOG).unwrap();
+        assert_eq!(capture.path, "/v1/chat/completions");
+        assert_eq!(capture.method, "POST");
+        assert_eq!(capture.timestamp, 1_773_147_731_123);
+        assert_eq!(capture.response_status, Some(429));
+        assert_eq!(
+            capture.response_body,
+            "{\"error\":{\"message\":\"rate limited\"}}"
+        );
+        assert!(capture.body.starts_with("{\"model\":\"gpt-5\""));
+        // The client's key is never kept
+        assert!(capture
+            .headers
+            .iter()
+            .all(|(name, _)| name != "Authorization"));
+        assert_eq!(forwarded_headers(&capture).len(), 2);
+
+        assert!(parse_request_log("main.log", "[2026-03-10 14:02:11] [info] started").is_none());
+        assert!(check_id("../config.json").is_err());
+    }
+
+    #[test]
+    fn applies_overrides_per_api_shape() {
+        let overrides = ReplayOverrides {
+            model: Some("claude-sonnet-4-5".to_string()),
+            max_tokens: Some(256),
+        };
+        let (path, body) =
+            apply_overrides("/v1/chat/completions", r#"{"model":"gpt-5"}"#, &overrides).unwrap();
+        assert_eq!(path, "/v1/chat/completions");
+        assert_eq!(body, r#"{"max_tokens":256,"model":"claude-sonnet-4-5"}"#);
+
+        let gemini = ReplayOverrides {
+            model: Some("gemini-2.5-flash".to_string()),
+            max_tokens: Some(64),
+        };
+        let (path, body) = apply_overrides(
+            "/v1beta/models/gemini-2.5-pro:streamGenerateContent?alt=sse",
+            r#"{"contents":[]}"#,
+            &gemini,
+        )
+        .unwrap();
+        assert_eq!(
+            path,
+            "/v1beta/models/gemini-2.5-flash:streamGenerateContent?alt=sse"
+        );
+        assert!(body.contains(r#""generationConfig":{"maxOutputTokens":64}"#));
+
+        // Non-JSON bodies can only be replayed as they are
+        assert!(apply_overrides("/v1/messages", "not json", &overrides).is_err());
+        assert!(apply_overrides("/v1/messages", "not json", &ReplayOverrides::default()).is_ok());
+    }
+
+    #[test]
+    fn summarizes_response_changes() {
+        let diff = diff_summary(
+            Some(429),
+            "{\"error\":1}\n",
+            200,
+            "{\"ok\":1}\n{\"done\":1}\n",
+        );
+        assert!(!diff.identical);
+        assert_eq!((diff.lines_added, diff.lines_removed), (2, 1));
+        assert!(diff.summary.starts_with("Status 429 → 200"));
+
+        let same = diff_summary(Some(200), "x", 200, "x");
+        assert!(same.identical);
+    }
+}
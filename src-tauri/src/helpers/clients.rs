@@ -0,0 +1,284 @@
+//! Which tool sent a request.
+//!
+//! CLIProxyAPI can log the client key a request authenticated with and the
+//! client's User-Agent, either on a line carrying the request id or at the
+//! end of the completion line. A known client key wins and is shown by its
+//! name. Requests on Amp's provider routes come from Amp whatever the
+//! User-Agent says; otherwise common agents get a friendly name and any
+//! other User-Agent is kept as logged.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::types::{ClientKey, RequestLog};
+use crate::utils::is_amp_provider_path;
+
+/// Requests whose client is tracked at once; unfinished ones are dropped
+/// beyond this
+const MAX_TRACKED_CLIENTS: usize = 1000;
+
+/// Longest User-Agent kept when it isn't a known agent
+const MAX_RAW_AGENT_LEN: usize = 80;
+
+/// Product token prefixes of well-known callers, most specific first
+const KNOWN_AGENTS: &[(&str, &str)] = &[
+    ("claude-cli", "Claude Code"),
+    ("claude-code", "Claude Code"),
+    ("codex", "Codex CLI"),
+    ("gemini-cli", "Gemini CLI"),
+    ("geminicli", "Gemini CLI"),
+    ("opencode", "OpenCode"),
+    ("cursor", "Cursor"),
+    ("factory-cli", "Factory Droid"),
+    ("droid", "Factory Droid"),
+    ("amp", "Amp"),
+    ("goose", "Goose"),
+    ("aider", "Aider"),
+    ("cline", "Cline"),
+    ("roo-code", "Roo Code"),
+    ("kilo-code", "Kilo Code"),
+    ("copilot", "GitHub Copilot"),
+    ("anthropic", "Anthropic SDK"),
+    ("openai", "OpenAI SDK"),
+    ("node-fetch", "Node.js (node-fetch)"),
+    ("undici", "Node.js (undici)"),
+    ("python-requests", "Python (requests)"),
+    ("python-httpx", "Python (httpx)"),
+    ("curl", "curl"),
+];
+
+/// Client shown for requests on Amp's provider routes
+const AMP_CLIENT: &str = "Amp";
+
+/// Friendly name for a User-Agent, or the User-Agent itself when unknown
+pub(crate) fn client_from_user_agent(user_agent: &str) -> Option<String> {
+    let user_agent = user_agent.trim().trim_matches('"').trim();
+    if user_agent.is_empty() || user_agent == "-" {
+        return None;
+    }
+    let product = user_agent
+        .split(['/', ' ', ';'])
+        .next()
+        .unwrap_or(user_agent)
+        .to_lowercase()
+        .replace('_', "-");
+    let known = KNOWN_AGENTS
+        .iter()
+        .find(|(prefix, _)| product.starts_with(prefix))
+        .map(|(_, name)| name.to_string());
+    Some(known.unwrap_or_else(|| user_agent.chars().take(MAX_RAW_AGENT_LEN).collect()))
+}
+
+/// Name of the client key `seen` in a log line, given in full or masked as
+/// head...tail
+pub(crate) fn client_key_name(seen: &str, keys: &[ClientKey]) -> Option<String> {
+    let masked = seen.split_once("...").or_else(|| seen.split_once('…'));
+    keys.iter()
+        .find(|key| match masked {
+            Some((head, tail)) => {
+                !head.is_empty()
+                    && key.key.len() > head.len() + tail.len()
+                    && key.key.starts_with(head)
+                    && key.key.ends_with(tail)
+            }
+            None => key.key == seen,
+        })
+        .map(|key| key.name.clone())
+}
+
+// Client key and User-Agent of one line, by request id when it has one
+// | f803bb77 | client key sk-pp...9f2c authorized
+// | f803bb77 | user-agent: claude-cli/1.0.83 (external, cli)
+// | f803bb77 | 200 | 12.453s | 127.0.0.1 | POST "/v1/messages" | ua="codex_cli_rs/0.20.0"
+fn parse_client_line(line: &str) -> (Option<&str>, Option<&str>, Option<&str>) {
+    lazy_static::lazy_static! {
+        static ref ID_REGEX: Regex = Regex::new(r"\|\s+([a-f0-9]{8})\s+\|").unwrap();
+        static ref KEY_REGEX: Regex = Regex::new(
+            r#"(?i)\bclient[-_ ]?key[=:\s]+"?([^\s"|,]+)"#
+        ).unwrap();
+        static ref AGENT_REGEX: Regex = Regex::new(
+            r#"(?i)\b(?:user[-_ ]?agent|ua)[=:\s]+(?:"([^"]*)"|([^|]+?))\s*(?:\||$)"#
+        ).unwrap();
+    }
+    let id = ID_REGEX
+        .captures(line)
+        .and_then(|c| c.get(1))
+        .map(|m| m.as_str());
+    let key = KEY_REGEX
+        .captures(line)
+        .and_then(|c| c.get(1))
+        .map(|m| m.as_str());
+    let agent = AGENT_REGEX
+        .captures(line)
+        .and_then(|c| c.get(1).or_else(|| c.get(2)))
+        .map(|m| m.as_str());
+    (id, key, agent)
+}
+
+#[derive(Default)]
+struct SeenClient {
+    key: Option<String>,
+    user_agent: Option<String>,
+}
+
+/// Client key and User-Agent seen so far per request id
+#[derive(Default)]
+pub(crate) struct ClientTracker {
+    seen: HashMap<String, SeenClient>,
+}
+
+impl ClientTracker {
+    pub fn observe(&mut self, line: &str) {
+        let (Some(id), key, agent) = parse_client_line(line) else {
+            return;
+        };
+        if key.is_none() && agent.is_none() {
+            return;
+        }
+        if self.seen.len() >= MAX_TRACKED_CLIENTS {
+            self.seen.clear();
+        }
+        let seen = self.seen.entry(id.to_string()).or_default();
+        if let Some(key) = key {
+            seen.key = Some(key.to_string());
+        }
+        if let Some(agent) = agent {
+            seen.user_agent = Some(agent.to_string());
+        }
+    }
+
+    /// Set the client of a completed request from what its earlier lines
+    /// and its completion `line` showed; a known client key wins, then an
+    /// Amp provider route
+    pub fn finish(
+        &mut self,
+        request_id: Option<&str>,
+        line: &str,
+        request: &mut RequestLog,
+        keys: &[ClientKey],
+    ) {
+        let mut seen = request_id
+            .and_then(|id| self.seen.remove(id))
+            .unwrap_or_default();
+        let (_, key, agent) = parse_client_line(line);
+        if let Some(key) = key {
+            seen.key = Some(key.to_string());
+        }
+        if let Some(agent) = agent {
+            seen.user_agent = Some(agent.to_string());
+        }
+        request.client = seen
+            .key
+            .and_then(|key| client_key_name(&key, keys))
+            .or_else(|| is_amp_provider_path(&request.path).then(|| AMP_CLIENT.to_string()))
+            .or_else(|| seen.user_agent.as_deref().and_then(client_from_user_agent));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str, key: &str) -> ClientKey {
+        ClientKey {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            key: key.to_string(),
+            agent_id: None,
+            created_at: 0,
+            last_used_at: None,
+            request_count: 0,
+            synced_requests: 0,
+        }
+    }
+
+    fn request() -> RequestLog {
+        RequestLog {
+            id: "req_1".to_string(),
+            timestamp: 1,
+            provider: "claude".to_string(),
+            model: "claude-sonnet-4-5".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status: 200,
+            duration_ms: 900,
+            ttfb_ms: None,
+            tokens_in: None,
+            tokens_out: None,
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    #[test]
+    fn names_known_agents_and_keeps_unknown_ones() {
+        let name = client_from_user_agent;
+        assert_eq!(
+            name("claude-cli/1.0.83 (external, cli)").as_deref(),
+            Some("Claude Code")
+        );
+        assert_eq!(name("codex_cli_rs/0.20.0").as_deref(), Some("Codex CLI"));
+        assert_eq!(
+            name("GeminiCLI/0.1.5 (darwin; arm64)").as_deref(),
+            Some("Gemini CLI")
+        );
+        assert_eq!(
+            name("node-fetch/1.0").as_deref(),
+            Some("Node.js (node-fetch)")
+        );
+        assert_eq!(
+            name("MyInternalBot/2.1").as_deref(),
+            Some("MyInternalBot/2.1")
+        );
+        assert_eq!(name(" - "), None);
+    }
+
+    #[test]
+    fn prefers_the_client_key_over_the_user_agent() {
+        let keys = [key("Laptop", "sk-pp-1234567890abcdef")];
+        let mut tracker = ClientTracker::default();
+        tracker.observe("[2026-03-11 09:14:02] [debug] | 5d0c7e21 | user-agent: claude-cli/1.0.83");
+        tracker.observe(
+            "[2026-03-11 09:14:02] [debug] | 5d0c7e21 | client key sk-pp...cdef authorized",
+        );
+        tracker.observe("[2026-03-11 09:14:02] [debug] | 77aa0b3c | User-Agent: claude-cli/1.0.83");
+
+        let completion = "[2026-03-11 09:14:12] [info] | 5d0c7e21 | 200 | 9.500s | 127.0.0.1 | POST \"/v1/messages\"";
+        let mut keyed = request();
+        tracker.finish(Some("5d0c7e21"), completion, &mut keyed, &keys);
+        assert_eq!(keyed.client.as_deref(), Some("Laptop"));
+
+        let mut by_agent = request();
+        tracker.finish(Some("77aa0b3c"), completion, &mut by_agent, &keys);
+        assert_eq!(by_agent.client.as_deref(), Some("Claude Code"));
+
+        // A key ProxyPal didn't issue falls back to the User-Agent
+        let gin = "[GIN] 2025/12/04 - 20:51:48 | 200 | 6.656s | ::1 | POST \"/v1/responses\" | client_key=sk-other | ua=\"codex_cli_rs/0.20.0\"";
+        let mut unkeyed = request();
+        tracker.finish(None, gin, &mut unkeyed, &keys);
+        assert_eq!(unkeyed.client.as_deref(), Some("Codex CLI"));
+
+        // Amp's provider routes name Amp, whichever runtime sent them
+        let amp_line = "[GIN] 2025/12/04 - 20:52:09 | 200 | 12.453s | ::1 | POST \"/api/provider/anthropic/v1/messages\" | ua=\"Bun/1.2.19\"";
+        let mut amp = RequestLog {
+            path: "/api/provider/anthropic/v1/messages".to_string(),
+            ..request()
+        };
+        tracker.finish(None, amp_line, &mut amp, &keys);
+        assert_eq!(amp.client.as_deref(), Some("Amp"));
+
+        let mut unknown = request();
+        tracker.finish(Some("0b1c2d3e"), completion, &mut unknown, &keys);
+        assert_eq!(unknown.client, None);
+        assert!(tracker.seen.is_empty());
+    }
+}
@@ -0,0 +1,322 @@
+//! Import usage history from a standalone CLIProxyAPI installation.
+//!
+//! Reads CLIProxyAPI's usage statistics JSON (the `/v0/management/usage/export`
+//! file, or a saved `/v0/management/usage` response) and turns every recorded
+//! request into a `RequestLog`:
+//!
+//! ```text
+//! {"usage": {"apis": {"<client key>": {"models": {"<model>": {"details": [
+//!     {"timestamp": "2025-06-01T10:00:00Z", "failed": false,
+//!      "tokens": {"input_tokens": 10, "output_tokens": 5, "cached_tokens": 0}}
+//! ]}}}}}}
+//! ```
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::helpers::cost_ledger;
+use crate::helpers::history::{update_model_stats, update_provider_stats, update_timeseries};
+use crate::helpers::usage_day;
+use crate::types::{Aggregate, CliproxyImportReport, RequestHistory, RequestLog};
+use crate::utils::detect_provider_from_model;
+
+/// Warnings listed individually before they are summarized
+const MAX_WARNINGS: usize = 50;
+
+fn parse_timestamp(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(s) => chrono::DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|dt| dt.timestamp_millis() as u64),
+        // Seconds or milliseconds since the epoch
+        Value::Number(n) => n
+            .as_u64()
+            .map(|n| if n < 100_000_000_000 { n * 1000 } else { n }),
+        _ => None,
+    }
+}
+
+fn token_count(tokens: Option<&Value>, key: &str) -> Option<u32> {
+    tokens
+        .and_then(|t| t.get(key))
+        .and_then(Value::as_u64)
+        .map(|n| n.min(u32::MAX as u64) as u32)
+}
+
+fn parse_detail(model: &str, detail: &Value) -> Result<RequestLog, String> {
+    if !detail.is_object() {
+        return Err("not an object".to_string());
+    }
+    let timestamp = detail
+        .get("timestamp")
+        .ok_or("missing timestamp")
+        .and_then(|v| parse_timestamp(v).ok_or("invalid timestamp"))?;
+    let tokens = detail.get("tokens");
+    let tokens_in = token_count(tokens, "input_tokens");
+    let tokens_out = token_count(tokens, "output_tokens");
+    let failed = detail
+        .get("failed")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    Ok(RequestLog {
+        // Stable across imports of the same file
+        id: format!(
+            "cliproxy-{}-{}-{}-{}",
+            timestamp,
+            model,
+            tokens_in.unwrap_or(0),
+            tokens_out.unwrap_or(0)
+        ),
+        timestamp,
+        provider: detect_provider_from_model(model),
+        model: model.to_string(),
+        method: "POST".to_string(),
+        path: "(imported)".to_string(),
+        status: if failed { 500 } else { 200 },
+        duration_ms: 0,
+        ttfb_ms: None,
+        tokens_in,
+        tokens_out,
+        tokens_cached: token_count(tokens, "cached_tokens"),
+        account: detail
+            .get("source")
+            .and_then(Value::as_str)
+            .filter(|s| !s.is_empty())
+            .map(String::from),
+        retries: 0,
+        attempts: Vec::new(),
+        synthetic: false,
+        session_id: None,
+        tags: Vec::new(),
+        client: None,
+        app_version: None,
+        sidecar_version: None,
+    })
+}
+
+/// Extract every request in a CLIProxyAPI usage file. Malformed entries are
+/// left out and described in the returned warnings.
+pub(crate) fn parse_usage_export(data: &Value) -> Result<(Vec<RequestLog>, Vec<String>), String> {
+    let usage = data.get("usage").unwrap_or(data);
+    let apis = usage
+        .get("apis")
+        .and_then(Value::as_object)
+        .ok_or("Not a CLIProxyAPI usage file (no \"apis\" section)")?;
+
+    let mut logs = Vec::new();
+    let mut warnings = Vec::new();
+    for api in apis.values() {
+        let Some(models) = api.get("models").and_then(Value::as_object) else {
+            continue;
+        };
+        for (model, model_data) in models {
+            let Some(details) = model_data.get("details").and_then(Value::as_array) else {
+                warnings.push(format!("{}: no request details", model));
+                continue;
+            };
+            for (index, detail) in details.iter().enumerate() {
+                match parse_detail(model, detail) {
+                    Ok(log) => logs.push(log),
+                    Err(e) => warnings.push(format!("{} details[{}]: {}", model, index, e)),
+                }
+            }
+        }
+    }
+    if warnings.len() > MAX_WARNINGS {
+        let more = warnings.len() - MAX_WARNINGS;
+        warnings.truncate(MAX_WARNINGS);
+        warnings.push(format!("... and {} more malformed entries", more));
+    }
+    Ok((logs, warnings))
+}
+
+// Same second, model and token counts: the log watcher's record of the same request
+fn dedup_key(log: &RequestLog) -> (u64, String, Option<u32>, Option<u32>) {
+    (
+        log.timestamp / 1000,
+        log.model.clone(),
+        log.tokens_in,
+        log.tokens_out,
+    )
+}
+
+/// Drop entries already in history, inside a previously imported range, or
+/// repeated within the file. Returns the new entries and the skipped count.
+pub(crate) fn filter_new(
+    logs: Vec<RequestLog>,
+    history: &RequestHistory,
+    imported_range: Option<(u64, u64)>,
+) -> (Vec<RequestLog>, u64) {
+    let mut seen: HashSet<_> = history.requests.iter().map(dedup_key).collect();
+    let mut skipped = 0;
+    let mut fresh = Vec::new();
+    for log in logs {
+        let previously_imported = imported_range
+            .map(|(from, to)| log.timestamp >= from && log.timestamp <= to)
+            .unwrap_or(false);
+        if previously_imported || !seen.insert(dedup_key(&log)) {
+            skipped += 1;
+        } else {
+            fresh.push(log);
+        }
+    }
+    fresh.sort_by_key(|log| log.timestamp);
+    (fresh, skipped)
+}
+
+/// Add imported requests to the aggregate and the retained history.
+pub(crate) fn apply_import(
+    logs: &[RequestLog],
+    agg: &mut Aggregate,
+    history: &mut RequestHistory,
+    report: &mut CliproxyImportReport,
+) {
+    let timezone = usage_day::current();
+    for log in logs {
+        let tokens_in = log.tokens_in.unwrap_or(0);
+        let tokens_out = log.tokens_out.unwrap_or(0);
+        let tokens_cached = log.tokens_cached.unwrap_or(0) as u64;
+
+        agg.total_requests += 1;
+        if log.status < 400 {
+            agg.total_success_count += 1;
+        } else {
+            agg.total_failure_count += 1;
+        }
+        agg.total_tokens_in += tokens_in as u64;
+        agg.total_tokens_out += tokens_out as u64;
+        agg.total_tokens_cached += tokens_cached;
+
+        if let Some(dt) = chrono::DateTime::from_timestamp_millis(log.timestamp as i64) {
+            let day = usage_day::day_label(timezone, dt);
+            cost_ledger::record_request(&mut agg.cost_ledger, &day, log);
+            let hour = usage_day::hour_label(timezone, dt);
+            cost_ledger::record_hour(&mut agg.cost_ledger, &hour, log);
+            let tokens = tokens_in as u64 + tokens_out as u64;
+            update_timeseries(&mut agg.requests_by_day, &day, 1);
+            update_timeseries(&mut agg.tokens_by_day, &day, tokens);
+            update_timeseries(&mut agg.requests_by_hour, &hour, 1);
+            update_timeseries(&mut agg.tokens_by_hour, &hour, tokens);
+        }
+        update_model_stats(agg, log);
+        update_provider_stats(agg, log);
+        report.imported += 1;
+    }
+    agg.total_cost_usd = cost_ledger::total_usd(&agg.cost_ledger);
+
+    // Imported days land anywhere in the past; keep the series ordered.
+    // Saving compacts the hours and days that are too old.
+    for series in [
+        &mut agg.requests_by_day,
+        &mut agg.tokens_by_day,
+        &mut agg.requests_by_hour,
+        &mut agg.tokens_by_hour,
+    ] {
+        series.sort_by(|a, b| a.label.cmp(&b.label));
+    }
+
+    history.requests.extend(logs.iter().cloned());
+    history.requests.sort_by_key(|log| log.timestamp);
+
+    if let (Some(first), Some(last)) = (logs.first(), logs.last()) {
+        agg.cliproxy_imported_range = Some(match agg.cliproxy_imported_range {
+            Some((from, to)) => (from.min(first.timestamp), to.max(last.timestamp)),
+            None => (first.timestamp, last.timestamp),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Value {
+        serde_json::json!({
+            "version": 1,
+            "usage": {
+                "apis": {
+                    "sk-client": {
+                        "models": {
+                            "claude-sonnet-4-5": {
+                                "details": [
+                                    {
+                                        "timestamp": "2025-06-01T10:00:00Z",
+                                        "failed": false,
+                                        "tokens": { "input_tokens": 100, "output_tokens": 50, "cached_tokens": 10 }
+                                    },
+                                    { "failed": false },
+                                    "garbage",
+                                    {
+                                        "timestamp": 1748772060,
+                                        "failed": true,
+                                        "tokens": { "input_tokens": 5 }
+                                    }
+                                ]
+                            },
+                            "gpt-5": {}
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn parses_details_and_collects_warnings() {
+        let (logs, warnings) = parse_usage_export(&sample()).unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].timestamp, 1_748_772_000_000);
+        assert_eq!(logs[0].tokens_in, Some(100));
+        assert_eq!(logs[0].tokens_cached, Some(10));
+        assert_eq!(
+            logs[0].provider,
+            detect_provider_from_model("claude-sonnet-4-5")
+        );
+        assert_eq!(logs[1].status, 500);
+        assert_eq!(
+            warnings,
+            vec![
+                "claude-sonnet-4-5 details[1]: missing timestamp",
+                "claude-sonnet-4-5 details[2]: not an object",
+                "gpt-5: no request details",
+            ]
+        );
+
+        assert!(parse_usage_export(&serde_json::json!({ "foo": 1 })).is_err());
+    }
+
+    #[test]
+    fn skips_requests_already_recorded_or_imported() {
+        let (logs, _) = parse_usage_export(&sample()).unwrap();
+        let first = logs[0].clone();
+
+        // The log watcher saw the first request (same second, different id)
+        let mut history = RequestHistory::default();
+        let mut watched = first.clone();
+        watched.id = "watcher-1".to_string();
+        watched.timestamp += 400;
+        history.requests.push(watched);
+        let (fresh, skipped) = filter_new(logs.clone(), &history, None);
+        assert_eq!((fresh.len(), skipped), (1, 1));
+
+        let (fresh, skipped) = filter_new(
+            logs.clone(),
+            &RequestHistory::default(),
+            Some((first.timestamp, first.timestamp)),
+        );
+        assert_eq!((fresh.len(), skipped), (1, 1));
+
+        // Re-importing everything applied once is a no-op
+        let mut agg = Aggregate::default();
+        let mut history = RequestHistory::default();
+        let mut report = CliproxyImportReport::default();
+        let (fresh, _) = filter_new(logs.clone(), &history, None);
+        apply_import(&fresh, &mut agg, &mut history, &mut report);
+        assert_eq!(report.imported, 2);
+        assert_eq!(agg.total_requests, 2);
+        assert_eq!(agg.total_failure_count, 1);
+        let (fresh, skipped) = filter_new(logs, &history, agg.cliproxy_imported_range);
+        assert_eq!((fresh.len(), skipped), (0, 2));
+    }
+}
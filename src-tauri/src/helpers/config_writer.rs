@@ -0,0 +1,112 @@
+//! Shared write path for agent and tool config generators.
+//!
+//! Generators hand every file they produce to a `ConfigWriter`. In apply mode the
+//! file is written (creating parent directories); in dry-run mode nothing touches
+//! disk and a unified diff against the current contents is recorded instead. Both
+//! modes consume the same generated content, so a preview always matches the write.
+
+use crate::types::FilePreview;
+use similar::TextDiff;
+use std::path::{Path, PathBuf};
+
+pub struct ConfigWriter {
+    dry_run: bool,
+    previews: Vec<FilePreview>,
+}
+
+impl ConfigWriter {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            previews: Vec::new(),
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Write `contents` to `path`, or record a diff preview in dry-run mode.
+    pub fn write(&mut self, path: &Path, contents: &str) -> Result<(), String> {
+        if self.dry_run {
+            let existing = std::fs::read_to_string(path).ok();
+            let current = existing.as_deref().unwrap_or("");
+            self.previews.push(FilePreview {
+                path: path.to_string_lossy().to_string(),
+                exists: existing.is_some(),
+                changed: current != contents,
+                diff: unified_diff(path, current, contents),
+            });
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory '{}': {}", parent.display(), e))?;
+        }
+        std::fs::write(path, contents)
+            .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+    }
+
+    /// Copy a config file aside before it is rewritten. The first backup is kept so
+    /// repeated runs don't overwrite the user's original. In dry-run mode only the
+    /// would-be backup path is returned.
+    pub fn backup(&self, path: &Path) -> Option<PathBuf> {
+        if !path.exists() {
+            return None;
+        }
+        let file_name = path.file_name()?.to_string_lossy().to_string();
+        let backup_path = path.with_file_name(format!("{}.proxypal-backup", file_name));
+        if !self.dry_run && !backup_path.exists() {
+            if let Err(e) = std::fs::copy(path, &backup_path) {
+                eprintln!("[ProxyPal] Failed to back up {}: {}", path.display(), e);
+                return None;
+            }
+        }
+        Some(backup_path)
+    }
+
+    pub fn into_previews(self) -> Vec<FilePreview> {
+        self.previews
+    }
+}
+
+/// Unified diff between the current and proposed contents of `path`.
+pub fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let display = path.to_string_lossy();
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", display), &format!("b/{}", display))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dry_run_records_diff_without_writing() {
+        let dir = std::env::temp_dir().join(format!(
+            "proxypal-config-writer-{}",
+            crate::types::amp::generate_uuid()
+        ));
+        let path = dir.join("settings.json");
+
+        let mut writer = ConfigWriter::new(true);
+        writer.write(&path, "{\n  \"a\": 1\n}\n").unwrap();
+        assert!(!path.exists(), "dry run must not write");
+
+        let previews = writer.into_previews();
+        assert_eq!(previews.len(), 1);
+        assert!(!previews[0].exists);
+        assert!(previews[0].changed);
+        assert!(previews[0].diff.contains("+  \"a\": 1"));
+
+        let mut writer = ConfigWriter::new(false);
+        writer.write(&path, "{\n  \"a\": 1\n}\n").unwrap();
+        assert!(path.exists());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,473 @@
+//! Per-day cost ledger.
+//!
+//! Every request counted in the aggregate adds its estimated cost to the entry
+//! for its usage day, provider and model. Costs are whole nano-dollars, so the
+//! all-time total is a sum of integers and does not drift the way one
+//! ever-growing float did. Display amounts are converted with the static rate
+//! from the config; nothing is fetched.
+//!
+//! Tagged requests are also counted per tag and day. Those entries change
+//! when tags are edited, not when the request is logged, so they stay right
+//! after the request itself has been trimmed from history.
+//!
+//! The last [`HOURLY_DAYS`] days are also kept per hour, across providers
+//! and models, for the usage baseline of `usage_anomaly`.
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::types::{
+    Aggregate, CostLedger, CostLedgerEntry, CostLedgerHour, CostLedgerRow, CostLedgerTagEntry,
+    RequestLog,
+};
+use crate::utils::estimate_request_cost;
+
+const NANO_PER_USD: f64 = 1_000_000_000.0;
+
+/// Days of hourly spend kept, the latest one included
+pub(crate) const HOURLY_DAYS: i64 = 15;
+
+pub(crate) fn to_nano(usd: f64) -> u64 {
+    (usd.max(0.0) * NANO_PER_USD).round() as u64
+}
+
+pub(crate) fn to_usd(nano: u64) -> f64 {
+    nano as f64 / NANO_PER_USD
+}
+
+// Same "unknown" bucket as the model and provider stats
+fn bucket(name: &str) -> &str {
+    if name.is_empty() {
+        "unknown"
+    } else {
+        name
+    }
+}
+
+/// Add a request's estimated cost to `day`
+pub(crate) fn record_request(ledger: &mut CostLedger, day: &str, req: &RequestLog) {
+    let (tokens_in, tokens_out) = (req.tokens_in.unwrap_or(0), req.tokens_out.unwrap_or(0));
+    let cost = to_nano(estimate_request_cost(&req.model, tokens_in, tokens_out));
+    let key = (day, bucket(&req.provider), bucket(&req.model));
+    let index = match ledger
+        .entries
+        .binary_search_by(|e| (e.day.as_str(), e.provider.as_str(), e.model.as_str()).cmp(&key))
+    {
+        Ok(index) => index,
+        Err(index) => {
+            ledger.entries.insert(
+                index,
+                CostLedgerEntry {
+                    day: key.0.to_string(),
+                    provider: key.1.to_string(),
+                    model: key.2.to_string(),
+                    ..CostLedgerEntry::default()
+                },
+            );
+            index
+        }
+    };
+    let entry = &mut ledger.entries[index];
+    entry.requests += 1;
+    entry.tokens_in += tokens_in as u64;
+    entry.tokens_out += tokens_out as u64;
+    entry.cost_nano_usd += cost;
+}
+
+/// Add a request's estimated cost to `hour` ("YYYY-MM-DDTHH"), dropping the
+/// hours that fell out of the last [`HOURLY_DAYS`] days
+pub(crate) fn record_hour(ledger: &mut CostLedger, hour: &str, req: &RequestLog) {
+    let (tokens_in, tokens_out) = (req.tokens_in.unwrap_or(0), req.tokens_out.unwrap_or(0));
+    let cost = to_nano(estimate_request_cost(&req.model, tokens_in, tokens_out));
+    match ledger.hours.binary_search_by(|h| h.hour.as_str().cmp(hour)) {
+        Ok(index) => ledger.hours[index].cost_nano_usd += cost,
+        Err(index) => ledger.hours.insert(
+            index,
+            CostLedgerHour {
+                hour: hour.to_string(),
+                cost_nano_usd: cost,
+            },
+        ),
+    }
+
+    let latest = ledger
+        .hours
+        .last()
+        .and_then(|h| NaiveDate::parse_from_str(h.hour.get(..10)?, "%Y-%m-%d").ok());
+    if let Some(latest) = latest {
+        let first = (latest - chrono::Duration::days(HOURLY_DAYS - 1))
+            .format("%Y-%m-%d")
+            .to_string();
+        ledger.hours.retain(|h| h.hour.as_str() >= first.as_str());
+    }
+}
+
+/// Count a request's estimated cost towards each of `tags` on `day`, or with
+/// `add` false take it back, e.g. when a tag is removed
+pub(crate) fn record_tags(
+    ledger: &mut CostLedger,
+    day: &str,
+    req: &RequestLog,
+    tags: &[String],
+    add: bool,
+) {
+    let (tokens_in, tokens_out) = (req.tokens_in.unwrap_or(0), req.tokens_out.unwrap_or(0));
+    let cost = to_nano(estimate_request_cost(&req.model, tokens_in, tokens_out));
+    let (tokens_in, tokens_out) = (tokens_in as u64, tokens_out as u64);
+    for tag in tags {
+        let key = (day, tag.as_str());
+        let found = ledger
+            .tags
+            .binary_search_by(|e| (e.day.as_str(), e.tag.as_str()).cmp(&key));
+        let index = match (found, add) {
+            (Ok(index), _) => index,
+            (Err(index), true) => {
+                ledger.tags.insert(
+                    index,
+                    CostLedgerTagEntry {
+                        day: day.to_string(),
+                        tag: tag.clone(),
+                        ..CostLedgerTagEntry::default()
+                    },
+                );
+                index
+            }
+            (Err(_), false) => continue,
+        };
+        let entry = &mut ledger.tags[index];
+        if add {
+            entry.requests += 1;
+            entry.tokens_in += tokens_in;
+            entry.tokens_out += tokens_out;
+            entry.cost_nano_usd += cost;
+        } else {
+            entry.requests = entry.requests.saturating_sub(1);
+            entry.tokens_in = entry.tokens_in.saturating_sub(tokens_in);
+            entry.tokens_out = entry.tokens_out.saturating_sub(tokens_out);
+            entry.cost_nano_usd = entry.cost_nano_usd.saturating_sub(cost);
+            if entry.requests == 0 {
+                ledger.tags.remove(index);
+            }
+        }
+    }
+}
+
+/// All-time spend in US dollars
+pub(crate) fn total_usd(ledger: &CostLedger) -> f64 {
+    let entries: u64 = ledger.entries.iter().map(|e| e.cost_nano_usd).sum();
+    to_usd(ledger.untracked_nano_usd + entries)
+}
+
+/// Keep the total of an aggregate written before the ledger existed
+pub(crate) fn adopt_legacy_total(agg: &mut Aggregate) {
+    let ledger = &mut agg.cost_ledger;
+    if ledger.entries.is_empty() && ledger.untracked_nano_usd == 0 && agg.total_cost_usd > 0.0 {
+        ledger.untracked_nano_usd = to_nano(agg.total_cost_usd);
+    }
+}
+
+/// First day of `range` ending `today`; None means every day. Ranges are
+/// "today", "7d", "30d", "month" (month to date) and "all".
+pub(crate) fn range_start(range: &str, today: &str) -> Result<Option<String>, String> {
+    let today = NaiveDate::parse_from_str(today, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid day {}: {}", today, e))?;
+    let start = match range {
+        "today" => today,
+        "7d" => today - chrono::Duration::days(6),
+        "30d" => today - chrono::Duration::days(29),
+        "month" => today.with_day(1).unwrap_or(today),
+        "all" => return Ok(None),
+        other => return Err(format!("Unknown range: {}", other)),
+    };
+    Ok(Some(start.format("%Y-%m-%d").to_string()))
+}
+
+/// Spend from `from` (inclusive, None for the start) through `to`, grouped by
+/// "day" (oldest first), "provider" or "model" (most expensive first). Spend
+/// not tied to a day only counts towards "all".
+pub(crate) fn group(
+    ledger: &CostLedger,
+    from: Option<&str>,
+    to: &str,
+    group_by: &str,
+) -> Result<Vec<CostLedgerRow>, String> {
+    let key: fn(&CostLedgerEntry) -> &str = match group_by {
+        "day" => |e| e.day.as_str(),
+        "provider" => |e| e.provider.as_str(),
+        "model" => |e| e.model.as_str(),
+        other => return Err(format!("Unknown grouping: {}", other)),
+    };
+    let mut rows: Vec<(CostLedgerRow, u64)> = Vec::new();
+    let in_range = ledger
+        .entries
+        .iter()
+        .filter(|e| from.is_none_or(|from| e.day.as_str() >= from) && e.day.as_str() <= to);
+    for entry in in_range {
+        let index = match rows.iter().position(|(row, _)| row.key == key(entry)) {
+            Some(index) => index,
+            None => {
+                rows.push((
+                    CostLedgerRow {
+                        key: key(entry).to_string(),
+                        ..CostLedgerRow::default()
+                    },
+                    0,
+                ));
+                rows.len() - 1
+            }
+        };
+        let (row, nano) = &mut rows[index];
+        row.requests += entry.requests;
+        row.tokens_in += entry.tokens_in;
+        row.tokens_out += entry.tokens_out;
+        *nano += entry.cost_nano_usd;
+    }
+    if from.is_none() && ledger.untracked_nano_usd > 0 {
+        rows.push((
+            CostLedgerRow {
+                key: "untracked".to_string(),
+                ..CostLedgerRow::default()
+            },
+            ledger.untracked_nano_usd,
+        ));
+    }
+    if group_by != "day" {
+        rows.sort_by(|(a, a_nano), (b, b_nano)| b_nano.cmp(a_nano).then(a.key.cmp(&b.key)));
+    }
+    Ok(rows
+        .into_iter()
+        .map(|(row, nano)| CostLedgerRow {
+            cost_usd: to_usd(nano),
+            ..row
+        })
+        .collect())
+}
+
+/// Spend per tag from `from` (inclusive, None for the start) through `to`,
+/// most expensive first. Untagged spend isn't listed.
+pub(crate) fn group_by_tag(
+    ledger: &CostLedger,
+    from: Option<&str>,
+    to: &str,
+) -> Vec<CostLedgerRow> {
+    let mut rows: Vec<(CostLedgerRow, u64)> = Vec::new();
+    let in_range = ledger
+        .tags
+        .iter()
+        .filter(|e| from.is_none_or(|from| e.day.as_str() >= from) && e.day.as_str() <= to);
+    for entry in in_range {
+        let index = match rows.iter().position(|(row, _)| row.key == entry.tag) {
+            Some(index) => index,
+            None => {
+                rows.push((
+                    CostLedgerRow {
+                        key: entry.tag.clone(),
+                        ..CostLedgerRow::default()
+                    },
+                    0,
+                ));
+                rows.len() - 1
+            }
+        };
+        let (row, nano) = &mut rows[index];
+        row.requests += entry.requests;
+        row.tokens_in += entry.tokens_in;
+        row.tokens_out += entry.tokens_out;
+        *nano += entry.cost_nano_usd;
+    }
+    rows.sort_by(|(a, a_nano), (b, b_nano)| b_nano.cmp(a_nano).then(a.key.cmp(&b.key)));
+    rows.into_iter()
+        .map(|(row, nano)| CostLedgerRow {
+            cost_usd: to_usd(nano),
+            ..row
+        })
+        .collect()
+}
+
+/// `amount` in `currency`, e.g. "$12.34", "€0.0042", "¥1,250"
+pub(crate) fn format_currency(amount: f64, currency: &str) -> String {
+    let code = currency.to_uppercase();
+    let (symbol, decimals) = match code.as_str() {
+        "USD" => ("$", 2),
+        "EUR" => ("€", 2),
+        "GBP" => ("£", 2),
+        "JPY" => ("¥", 0),
+        "CNY" => ("¥", 2),
+        "KRW" => ("₩", 0),
+        "INR" => ("₹", 2),
+        "VND" => ("₫", 0),
+        _ => ("", 2),
+    };
+    // Show sub-cent spend rather than rounding it to zero
+    let decimals = if decimals > 0 && amount > 0.0 && amount < 0.01 {
+        4
+    } else {
+        decimals
+    };
+    let fixed = format!("{:.*}", decimals, amount.abs());
+    let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if !fraction.is_empty() {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    let sign = if amount < 0.0 { "-" } else { "" };
+    if symbol.is_empty() {
+        format!("{}{} {}", sign, grouped, code)
+    } else {
+        format!("{}{}{}", sign, symbol, grouped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(model: &str, provider: &str, tokens_in: u32, tokens_out: u32) -> RequestLog {
+        RequestLog {
+            id: format!("req_{}_{}", model, tokens_in),
+            timestamp: 0,
+            provider: provider.to_string(),
+            model: model.to_string(),
+            method: "POST".to_string(),
+            path: "/v1/chat/completions".to_string(),
+            status: 200,
+            duration_ms: 500,
+            ttfb_ms: None,
+            tokens_in: Some(tokens_in),
+            tokens_out: Some(tokens_out),
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    #[test]
+    fn daily_entries_sum_to_the_request_costs() {
+        let mut ledger = CostLedger::default();
+        let mut expected = 0.0;
+        let models = [
+            ("claude-sonnet-4-5", "claude"),
+            ("gemini-2.5-flash", "gemini"),
+            ("gpt-5", "openai"),
+            ("qwen3-coder-plus", "qwen"),
+        ];
+        for i in 0..2000u32 {
+            let (model, provider) = models[i as usize % models.len()];
+            let req = request(model, provider, 17 + i * 31 % 9000, 3 + i * 7 % 2000);
+            let day = format!("2026-03-{:02}", 1 + i % 28);
+            record_request(&mut ledger, &day, &req);
+            expected += estimate_request_cost(&req.model, req.tokens_in.unwrap(), req.tokens_out.unwrap());
+        }
+
+        let by_day = group(&ledger, None, "2026-03-31", "day").unwrap();
+        assert_eq!(by_day.len(), 28);
+        let summed: f64 = by_day.iter().map(|r| r.cost_usd).sum();
+        assert!((summed - expected).abs() < 1e-5, "{} vs {}", summed, expected);
+        assert!((total_usd(&ledger) - expected).abs() < 1e-5);
+        assert_eq!(by_day.iter().map(|r| r.requests).sum::<u64>(), 2000);
+
+        let by_model = group(&ledger, None, "2026-03-31", "model").unwrap();
+        assert_eq!(by_model.len(), 4);
+        assert!(by_model.windows(2).all(|w| w[0].cost_usd >= w[1].cost_usd));
+    }
+
+    #[test]
+    fn month_to_date_leaves_out_earlier_days() {
+        let mut ledger = CostLedger::default();
+        let req = request("claude-sonnet-4-5", "claude", 1_000_000, 0);
+        for day in ["2026-02-27", "2026-03-01", "2026-03-14"] {
+            record_request(&mut ledger, day, &req);
+        }
+        ledger.untracked_nano_usd = to_nano(5.0);
+
+        let from = range_start("month", "2026-03-14").unwrap();
+        assert_eq!(from.as_deref(), Some("2026-03-01"));
+        let rows = group(&ledger, from.as_deref(), "2026-03-14", "provider").unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].requests, 2);
+        assert!((rows[0].cost_usd - 6.0).abs() < 1e-9);
+
+        // Untracked spend only shows up in the all-time view
+        let all = group(&ledger, None, "2026-03-14", "provider").unwrap();
+        assert_eq!(all.iter().map(|r| r.cost_usd).sum::<f64>(), 14.0);
+        assert_eq!(range_start("7d", "2026-03-03").unwrap().as_deref(), Some("2026-02-25"));
+        assert!(range_start("year", "2026-03-03").is_err());
+    }
+
+    #[test]
+    fn adopts_totals_from_before_the_ledger() {
+        let mut agg = Aggregate {
+            total_cost_usd: 12.5,
+            ..Aggregate::default()
+        };
+        adopt_legacy_total(&mut agg);
+        assert_eq!(total_usd(&agg.cost_ledger), 12.5);
+        // Only once: later totals come from the ledger itself
+        agg.total_cost_usd = 99.0;
+        adopt_legacy_total(&mut agg);
+        assert_eq!(total_usd(&agg.cost_ledger), 12.5);
+    }
+
+    #[test]
+    fn tag_entries_follow_tag_edits() {
+        let mut ledger = CostLedger::default();
+        let req = request("claude-sonnet-4-5", "claude", 1_000_000, 0);
+        let both = ["acme".to_string(), "refactor".to_string()];
+        record_tags(&mut ledger, "2026-03-01", &req, &both, true);
+        record_tags(&mut ledger, "2026-03-02", &req, &both[..1], true);
+
+        let rows = group_by_tag(&ledger, None, "2026-03-31");
+        assert_eq!(rows[0].key, "acme");
+        assert_eq!(rows[0].requests, 2);
+        assert!((rows[0].cost_usd - 6.0).abs() < 1e-9);
+        assert_eq!(rows[1].key, "refactor");
+
+        // Retagging takes the cost back; an emptied entry goes away
+        record_tags(&mut ledger, "2026-03-01", &req, &both[1..], false);
+        assert_eq!(ledger.tags.len(), 2);
+        let rows = group_by_tag(&ledger, Some("2026-03-02"), "2026-03-31");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].requests, 1);
+        // Removing a tag that was never counted changes nothing
+        record_tags(&mut ledger, "2026-03-05", &req, &both[..1], false);
+        assert_eq!(ledger.tags.len(), 2);
+    }
+
+    #[test]
+    fn hourly_spend_keeps_the_recent_days() {
+        let mut ledger = CostLedger::default();
+        let req = request("claude-sonnet-4-5", "claude", 1_000_000, 0);
+        record_hour(&mut ledger, "2026-03-01T09", &req);
+        record_hour(&mut ledger, "2026-03-01T09", &req);
+        record_hour(&mut ledger, "2026-03-02T23", &req);
+        assert_eq!(ledger.hours.len(), 2);
+        assert_eq!(ledger.hours[0].cost_nano_usd, to_nano(6.0));
+
+        // Fifteen days on, the first day is gone and the second stays
+        record_hour(&mut ledger, "2026-03-16T00", &req);
+        let hours: Vec<&str> = ledger.hours.iter().map(|h| h.hour.as_str()).collect();
+        assert_eq!(hours, ["2026-03-02T23", "2026-03-16T00"]);
+    }
+
+    #[test]
+    fn formats_currencies() {
+        assert_eq!(format_currency(1234.5, "USD"), "$1,234.50");
+        assert_eq!(format_currency(0.0042, "eur"), "€0.0042");
+        assert_eq!(format_currency(1250.4, "JPY"), "¥1,250");
+        assert_eq!(format_currency(3.0, "CHF"), "3.00 CHF");
+        assert_eq!(format_currency(0.0, "USD"), "$0.00");
+    }
+}
@@ -0,0 +1,256 @@
+//! Encrypted credential files.
+//!
+//! The OAuth tokens in `~/.cli-proxy-api` are exported as one JSON file: the
+//! auth files are serialized, then sealed with AES-256-GCM under a key derived
+//! from the user's passphrase with PBKDF2-HMAC-SHA256. Salt, nonce and
+//! iteration count travel with the ciphertext, so older exports still open if
+//! the default count is raised. Plaintext and keys are zeroized after use.
+
+use std::num::NonZeroU32;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::helpers::permissions;
+
+const FORMAT: &str = "proxypal-credentials";
+const VERSION: u8 = 1;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// Refuse files asking for more, which would hang the import
+const MAX_ITERATIONS: u32 = 10_000_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+pub(crate) const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// One auth file, by name inside the auth directory
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CredentialFile {
+    pub name: String,
+    pub content: String,
+}
+
+impl Drop for CredentialFile {
+    fn drop(&mut self) {
+        self.content.zeroize();
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope {
+    format: String,
+    version: u8,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey, String> {
+    let iterations = NonZeroU32::new(iterations).ok_or("Invalid iteration count")?;
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key[..],
+    );
+    let key = UnboundKey::new(&AES_256_GCM, &key[..]).map_err(|_| "Invalid key")?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// Seal auth files under a passphrase
+pub(crate) fn encrypt(files: &[CredentialFile], passphrase: &str) -> Result<Vec<u8>, String> {
+    encrypt_with(files, passphrase, PBKDF2_ITERATIONS)
+}
+
+fn encrypt_with(
+    files: &[CredentialFile],
+    passphrase: &str,
+    iterations: u32,
+) -> Result<Vec<u8>, String> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt).map_err(|_| "No secure random source")?;
+    rng.fill(&mut nonce)
+        .map_err(|_| "No secure random source")?;
+
+    let key = derive_key(passphrase, &salt, iterations)?;
+    let mut sealed = Zeroizing::new(serde_json::to_vec(files).map_err(|e| e.to_string())?);
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::from(FORMAT.as_bytes()),
+        &mut *sealed,
+    )
+    .map_err(|_| "Encryption failed")?;
+
+    let envelope = Envelope {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        iterations,
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(&*sealed),
+    };
+    serde_json::to_vec_pretty(&envelope).map_err(|e| e.to_string())
+}
+
+/// Open an encrypted credentials file
+pub(crate) fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<CredentialFile>, String> {
+    let envelope: Envelope = serde_json::from_slice(data)
+        .ok()
+        .filter(|e: &Envelope| e.format == FORMAT)
+        .ok_or("Not a ProxyPal credentials file")?;
+    if envelope.version > VERSION {
+        return Err("This credentials file was exported by a newer ProxyPal".to_string());
+    }
+    if envelope.iterations > MAX_ITERATIONS {
+        return Err("Corrupted credentials file".to_string());
+    }
+    let salt = STANDARD.decode(&envelope.salt).map_err(|e| e.to_string())?;
+    let nonce: [u8; NONCE_LEN] = STANDARD
+        .decode(&envelope.nonce)
+        .ok()
+        .and_then(|n| n.try_into().ok())
+        .ok_or("Corrupted credentials file")?;
+    let mut sealed = Zeroizing::new(
+        STANDARD
+            .decode(&envelope.ciphertext)
+            .map_err(|_| "Corrupted credentials file")?,
+    );
+
+    let key = derive_key(passphrase, &salt, envelope.iterations)?;
+    let plain = key
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(FORMAT.as_bytes()),
+            &mut sealed[..],
+        )
+        // GCM can't tell a wrong passphrase from a tampered file
+        .map_err(|_| "Wrong passphrase, or the file is damaged")?;
+    serde_json::from_slice(plain).map_err(|_| "Corrupted credentials file".to_string())
+}
+
+// Auth files are flat JSON files, optionally disabled by CLIProxyAPI
+fn is_auth_file_name(name: &str) -> bool {
+    !name.contains(['/', '\\'])
+        && !name.starts_with('.')
+        && (name.ends_with(".json") || name.ends_with(".json.disabled"))
+}
+
+/// Every auth file in `dir`, sorted by name
+pub(crate) fn read_auth_files(dir: &Path) -> Result<Vec<CredentialFile>, String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !is_auth_file_name(&name) || !entry.path().is_file() {
+            continue;
+        }
+        let content = std::fs::read_to_string(entry.path())
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        files.push(CredentialFile { name, content });
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+/// Write imported auth files into `dir`. Files that already exist are kept;
+/// returns the names written and the names skipped.
+pub(crate) fn write_auth_files(
+    dir: &Path,
+    files: &[CredentialFile],
+) -> Result<(Vec<String>, Vec<String>), String> {
+    if let Some(bad) = files.iter().find(|f| !is_auth_file_name(&f.name)) {
+        return Err(format!("Refusing to import {:?}", bad.name));
+    }
+    permissions::create_private_dir(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let (mut written, mut skipped) = (Vec::new(), Vec::new());
+    for file in files {
+        let path = dir.join(&file.name);
+        // A disabled copy counts as present too
+        let base = file.name.trim_end_matches(".disabled");
+        if dir.join(base).exists() || dir.join(format!("{}.disabled", base)).exists() {
+            skipped.push(file.name.clone());
+            continue;
+        }
+        std::fs::write(&path, file.content.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", file.name, e))?;
+        permissions::restrict_after_write(&path);
+        written.push(file.name.clone());
+    }
+    Ok((written, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fast enough for debug builds; real exports use PBKDF2_ITERATIONS
+    const TEST_ITERATIONS: u32 = 1_000;
+
+    fn file(name: &str, content: &str) -> CredentialFile {
+        CredentialFile {
+            name: name.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn round_trips_under_the_right_passphrase() {
+        let files = vec![
+            file("claude-a.json", r#"{"type":"claude"}"#),
+            file("codex-b.json.disabled", r#"{"type":"codex"}"#),
+        ];
+        let sealed = encrypt_with(&files, "correct horse", TEST_ITERATIONS).unwrap();
+        let text = String::from_utf8(sealed.clone()).unwrap();
+        assert!(!text.contains("claude-a") && !text.contains("\"type\""));
+
+        let opened = decrypt(&sealed, "correct horse").unwrap();
+        assert_eq!(opened.len(), 2);
+        assert_eq!(opened[1].name, "codex-b.json.disabled");
+        assert_eq!(opened[1].content, r#"{"type":"codex"}"#);
+    }
+
+    #[test]
+    fn rejects_wrong_passphrases_and_other_files() {
+        let sealed = encrypt_with(&[file("a.json", "{}")], "passphrase", TEST_ITERATIONS).unwrap();
+        assert!(decrypt(&sealed, "Passphrase").is_err());
+        assert!(decrypt(b"{\"files\": []}", "passphrase").is_err());
+        assert!(decrypt(b"not json", "passphrase").is_err());
+    }
+
+    #[test]
+    fn imports_only_new_flat_auth_files() {
+        let dir = std::env::temp_dir().join(format!("proxypal-creds-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("claude-a.json.disabled"), "old").unwrap();
+
+        let (written, skipped) = write_auth_files(
+            &dir,
+            &[file("claude-a.json", "new"), file("gemini-b.json", "{}")],
+        )
+        .unwrap();
+        assert_eq!(written, vec!["gemini-b.json"]);
+        assert_eq!(skipped, vec!["claude-a.json"]);
+        assert!(!dir.join("claude-a.json").exists());
+        assert_eq!(read_auth_files(&dir).unwrap().len(), 2);
+
+        assert!(write_auth_files(&dir, &[file("../escape.json", "{}")]).is_err());
+        assert!(write_auth_files(&dir, &[file("notes.txt", "")]).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
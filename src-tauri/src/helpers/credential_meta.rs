@@ -0,0 +1,166 @@
+//! Account details read from credential files in `~/.cli-proxy-api`.
+//!
+//! The Management API fills in email and account type for the providers it
+//! knows well, and nothing when the proxy is down. iFlow and Antigravity files
+//! carry their own token expiry and plan fields; these parsers read them so
+//! the credential list can show them either way. A file that cannot be read
+//! or parsed yields empty metadata, never an error, so one bad file does not
+//! break the scan.
+
+use serde_json::Value;
+
+/// What a credential file says about its account
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CredentialMeta {
+    pub email: Option<String>,
+    pub expires_at: Option<String>, // RFC 3339
+    pub plan: Option<String>,
+}
+
+fn string(json: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| json.get(*key))
+        .find_map(|value| match value {
+            Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+            // Tiers are sometimes objects, e.g. {"id": "standard-tier", "name": "Standard"}
+            Value::Object(_) => string(value, &["name", "id"]),
+            _ => None,
+        })
+}
+
+fn millis_to_rfc3339(ms: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp_millis(ms).map(|dt| dt.to_rfc3339())
+}
+
+// iflow-<email>.json, as written by CLIProxyAPI:
+// { "type": "iflow", "email": "...", "access_token": "...", "api_key": "...",
+//   "expired": "2026-03-01T10:00:00+08:00", "last_refresh": "..." }
+fn parse_iflow(json: &Value) -> CredentialMeta {
+    CredentialMeta {
+        email: string(json, &["email", "phone", "account"]),
+        expires_at: string(json, &["expired", "expires_at", "expiry"]),
+        plan: string(json, &["plan", "tier", "subscription", "account_type"]),
+    }
+}
+
+// antigravity-<email>.json, as written by CLIProxyAPI:
+// { "type": "antigravity", "email": "...", "project_id": "...",
+//   "expired": "...", or "timestamp": <ms> with "expires_in": <seconds>,
+//   "tier": "standard-tier" or {"id": ..., "name": ...} when known }
+fn parse_antigravity(json: &Value) -> CredentialMeta {
+    let expires_at = string(json, &["expired", "expires_at", "expiry"]).or_else(|| {
+        let issued = json["timestamp"].as_i64()?;
+        let lifetime = json["expires_in"].as_i64()?;
+        millis_to_rfc3339(issued + lifetime * 1000)
+    });
+    CredentialMeta {
+        email: string(json, &["email"]),
+        expires_at,
+        plan: string(
+            json,
+            &["tier", "current_tier", "paid_tier", "plan", "account_type"],
+        ),
+    }
+}
+
+/// Metadata from a credential file's contents. Providers without a dedicated
+/// parser only get the common `email` and `expired` fields.
+pub(crate) fn parse(provider: &str, contents: &str) -> CredentialMeta {
+    let Ok(json) = serde_json::from_str::<Value>(contents) else {
+        return CredentialMeta::default();
+    };
+    if !json.is_object() {
+        return CredentialMeta::default();
+    }
+    match provider {
+        "iflow" => parse_iflow(&json),
+        "antigravity" => parse_antigravity(&json),
+        _ => CredentialMeta {
+            email: string(&json, &["email"]),
+            expires_at: string(&json, &["expired"]),
+            plan: None,
+        },
+    }
+}
+
+/// Read and parse one credential file; unreadable files give empty metadata
+pub(crate) fn read(provider: &str, path: &std::path::Path) -> CredentialMeta {
+    std::fs::read_to_string(path)
+        .map(|contents| parse(provider, &contents))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IFLOW: &str = r#"{
+        "type": "iflow",
+        "email": "dev@example.com",
+        "access_token": "at-123",
+        "refresh_token": "rt-456",
+        "api_key": "sk-iflow",
+        "expired": "2026-03-01T10:00:00+08:00",
+        "last_refresh": "2026-02-28T10:00:00+08:00",
+        "plan": "pro"
+    }"#;
+
+    const ANTIGRAVITY: &str = r#"{
+        "type": "antigravity",
+        "email": "dev@gmail.com",
+        "project_id": "bright-river-123",
+        "access_token": "ya29.x",
+        "refresh_token": "1//y",
+        "timestamp": 1767225600000,
+        "expires_in": 3599,
+        "tier": { "id": "standard-tier", "name": "Gemini Code Assist" }
+    }"#;
+
+    #[test]
+    fn reads_iflow_files() {
+        let meta = parse("iflow", IFLOW);
+        assert_eq!(meta.email.as_deref(), Some("dev@example.com"));
+        assert_eq!(
+            meta.expires_at.as_deref(),
+            Some("2026-03-01T10:00:00+08:00")
+        );
+        assert_eq!(meta.plan.as_deref(), Some("pro"));
+    }
+
+    #[test]
+    fn reads_antigravity_files() {
+        let meta = parse("antigravity", ANTIGRAVITY);
+        assert_eq!(meta.email.as_deref(), Some("dev@gmail.com"));
+        assert_eq!(meta.plan.as_deref(), Some("Gemini Code Assist"));
+        assert_eq!(
+            meta.expires_at.as_deref(),
+            Some("2026-01-01T00:59:59+00:00")
+        );
+
+        // An explicit expiry wins over timestamp + expires_in
+        let explicit = ANTIGRAVITY.replace(
+            "\"timestamp\"",
+            "\"expired\": \"2026-01-02T00:00:00Z\", \"timestamp\"",
+        );
+        assert_eq!(
+            parse("antigravity", &explicit).expires_at.as_deref(),
+            Some("2026-01-02T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn malformed_files_give_empty_metadata() {
+        for contents in ["", "{not json", "[1, 2]", "null", r#"{"email": 42}"#] {
+            assert_eq!(parse("iflow", contents), CredentialMeta::default());
+            assert_eq!(parse("antigravity", contents), CredentialMeta::default());
+        }
+        // Partial files still give what they have
+        let partial = parse("antigravity", r#"{"email": "a@b.co", "timestamp": "soon"}"#);
+        assert_eq!(partial.email.as_deref(), Some("a@b.co"));
+        assert_eq!(partial.expires_at, None);
+        assert_eq!(
+            read("iflow", std::path::Path::new("/nonexistent/iflow-x.json")),
+            CredentialMeta::default()
+        );
+    }
+}
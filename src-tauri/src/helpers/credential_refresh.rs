@@ -0,0 +1,201 @@
+//! Renewing one credential without disconnecting its account.
+//!
+//! `refresh_provider_token` first asks the Management API to refresh the
+//! credential's token with `POST auth-files/refresh`. That endpoint is not
+//! in CLIProxyAPI's published Management API; it is assumed here, and a proxy
+//! without it answers 404/405/501. Only then is the file reported as
+//! "reauth-needed", and once the user agrees, `reauth_credential` signs in
+//! again: a regular OAuth flow whose result is written over the same file.
+//! Other failures (e.g. a rejected refresh token) are reported as they are.
+//! The sign-in makes CLIProxyAPI write a fresh credential, under the same name
+//! or a new one; [`finish_reauth`] merges it into the old file, keeping the
+//! fields the fresh one doesn't set (labels, prefixes, per-account proxies),
+//! and removes the new file so no duplicate account is left behind.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde_json::Value;
+
+use crate::helpers::credential_meta;
+use crate::providers::AuthKind;
+use crate::utils::{detect_provider_from_filename, provider_filename_prefixes};
+
+/// A sign-in that replaces an existing credential file, kept with its flow
+#[derive(Debug, Clone)]
+pub struct PendingReauth {
+    pub provider: String,
+    pub file: String,
+    // The file as it was, in case the sign-in writes over it
+    pub previous: String,
+    pub started: SystemTime,
+}
+
+/// Whether a provider's credentials can be refreshed or signed in again in
+/// place; login pages and imported files can't be tracked to a file
+pub(crate) fn is_refreshable(provider: &str) -> bool {
+    crate::providers::get(provider).is_some_and(|meta| {
+        meta.auth_kind == AuthKind::OAuth && meta.management_auth_endpoint.is_some()
+    })
+}
+
+/// Credential files `target` names in `dir`: one file (with or without
+/// `.json`), or every active file of a provider
+pub(crate) fn resolve_targets(dir: &Path, target: &str) -> Result<Vec<String>, String> {
+    let target = target.trim();
+    for name in [target.to_string(), format!("{}.json", target)] {
+        if name.ends_with(".json") && !name.contains(['/', '\\']) && dir.join(&name).is_file() {
+            return Ok(vec![name]);
+        }
+    }
+    let prefixes = provider_filename_prefixes(target);
+    if prefixes.is_empty() {
+        return Err(format!("No credential file or provider named {}", target));
+    }
+    let mut files: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+                .filter(|name| name.ends_with(".json"))
+                .filter(|name| prefixes.iter().any(|p| name.starts_with(p)))
+                .collect()
+        })
+        .unwrap_or_default();
+    if files.is_empty() {
+        return Err(format!("No {} credential files found", target));
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// `fresh` with every top-level field of `previous` it doesn't set itself
+pub(crate) fn merge(previous: &str, fresh: &str) -> Result<String, String> {
+    let Ok(Value::Object(previous)) = serde_json::from_str::<Value>(previous) else {
+        return Ok(fresh.to_string());
+    };
+    let Value::Object(mut merged) = serde_json::from_str::<Value>(fresh)
+        .map_err(|e| format!("The new credential is not valid JSON: {}", e))?
+    else {
+        return Err("The new credential is not a JSON object".to_string());
+    };
+    for (key, value) in previous {
+        merged.entry(key).or_insert(value);
+    }
+    serde_json::to_string_pretty(&Value::Object(merged)).map_err(|e| e.to_string())
+}
+
+// The provider's credential written most recently since the sign-in
+// started; the file being replaced counts only once its contents changed
+fn written_since(dir: &Path, reauth: &PendingReauth) -> Option<PathBuf> {
+    let target = dir.join(&reauth.file);
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.ends_with(".json") && detect_provider_from_filename(&name) == reauth.provider
+        })
+        .filter(|entry| {
+            entry.path() != target
+                || std::fs::read_to_string(&target).ok().as_deref()
+                    != Some(reauth.previous.as_str())
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            (modified >= reauth.started).then(|| (modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// Write the credential a completed sign-in produced over the file it
+/// replaces. Returns the new token expiry, when the file has one.
+pub(crate) fn finish_reauth(dir: &Path, reauth: &PendingReauth) -> Result<Option<String>, String> {
+    let target = dir.join(&reauth.file);
+    let fresh_path = written_since(dir, reauth)
+        .ok_or_else(|| format!("The sign-in wrote no new {} credential", reauth.provider))?;
+    let fresh = std::fs::read_to_string(&fresh_path)
+        .map_err(|e| format!("Failed to read {}: {}", fresh_path.display(), e))?;
+    // Written over in place, the old contents only survive in the snapshot
+    let previous = if fresh_path == target {
+        reauth.previous.clone()
+    } else {
+        std::fs::read_to_string(&target).unwrap_or_else(|_| reauth.previous.clone())
+    };
+    let merged = merge(&previous, &fresh)?;
+    crate::status_file::write_atomically(&target, &merged)
+        .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    crate::helpers::permissions::restrict_after_write(&target);
+    if fresh_path != target {
+        std::fs::remove_file(&fresh_path)
+            .map_err(|e| format!("Failed to remove {}: {}", fresh_path.display(), e))?;
+    }
+    Ok(credential_meta::parse(&reauth.provider, &merged).expires_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREVIOUS: &str = r#"{
+        "type": "claude",
+        "email": "dev@example.com",
+        "access_token": "old-access",
+        "refresh_token": "old-refresh",
+        "expired": "2026-03-01T10:00:00Z",
+        "label": "Work",
+        "prefix": "work"
+    }"#;
+
+    const FRESH: &str = r#"{
+        "type": "claude",
+        "email": "dev@example.com",
+        "access_token": "new-access",
+        "refresh_token": "new-refresh",
+        "expired": "2026-04-01T10:00:00Z"
+    }"#;
+
+    #[test]
+    fn merge_keeps_fields_the_fresh_credential_leaves_out() {
+        let merged: Value = serde_json::from_str(&merge(PREVIOUS, FRESH).unwrap()).unwrap();
+        assert_eq!(merged["access_token"], "new-access");
+        assert_eq!(merged["expired"], "2026-04-01T10:00:00Z");
+        assert_eq!(merged["label"], "Work");
+        assert_eq!(merged["prefix"], "work");
+
+        assert!(merge(PREVIOUS, "not json").is_err());
+    }
+
+    #[test]
+    fn reauth_replaces_the_old_file_instead_of_adding_an_account() {
+        let dir = std::env::temp_dir().join(format!("proxypal-reauth-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("claude-work.json"), PREVIOUS).unwrap();
+
+        let reauth = PendingReauth {
+            provider: "claude".to_string(),
+            file: "claude-work.json".to_string(),
+            previous: PREVIOUS.to_string(),
+            started: SystemTime::now() - std::time::Duration::from_secs(1),
+        };
+        // The sign-in came back under the account's email
+        std::fs::write(dir.join("claude-dev@example.com.json"), FRESH).unwrap();
+        let expires_at = finish_reauth(&dir, &reauth).unwrap();
+        assert_eq!(expires_at.as_deref(), Some("2026-04-01T10:00:00Z"));
+
+        assert_eq!(
+            resolve_targets(&dir, "claude").unwrap(),
+            ["claude-work.json"]
+        );
+        let written = std::fs::read_to_string(dir.join("claude-work.json")).unwrap();
+        assert!(written.contains("new-refresh") && written.contains("\"Work\""));
+        assert_eq!(
+            resolve_targets(&dir, "claude-work").unwrap(),
+            ["claude-work.json"]
+        );
+        assert!(resolve_targets(&dir, "gemini").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
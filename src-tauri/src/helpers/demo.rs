@@ -0,0 +1,137 @@
+//! Synthetic request traffic for UI development and demos.
+//!
+//! Demo mode feeds made-up requests through the same pipeline as real ones
+//! (history, `request-log-batch`, the request feed). They are tagged
+//! `synthetic`, so the aggregate, model caps and provider health ignore them
+//! and `clear_request_history` can remove just them.
+
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::types::RequestLog;
+
+/// Requests per second
+pub(crate) const DEFAULT_RATE: f64 = 1.0;
+pub(crate) const MIN_RATE: f64 = 0.1;
+pub(crate) const MAX_RATE: f64 = 20.0;
+
+pub(crate) const DEFAULT_DURATION_SECS: u64 = 300;
+pub(crate) const MAX_DURATION_SECS: u64 = 3600;
+
+// Provider, model, path and typical latency of a successful request in ms
+const MODELS: &[(&str, &str, &str, u64)] = &[
+    ("claude", "claude-sonnet-4-5", "/v1/messages", 6_000),
+    ("claude", "claude-opus-4-1", "/v1/messages", 14_000),
+    ("claude", "claude-haiku-4-5", "/v1/messages", 1_500),
+    ("openai", "gpt-5", "/v1/chat/completions", 9_000),
+    ("openai", "gpt-5-codex", "/v1/responses", 11_000),
+    (
+        "gemini",
+        "gemini-2.5-pro",
+        "/v1beta/models/gemini-2.5-pro:streamGenerateContent",
+        7_000,
+    ),
+    (
+        "gemini",
+        "gemini-2.5-flash",
+        "/v1beta/models/gemini-2.5-flash:generateContent",
+        2_000,
+    ),
+    ("qwen", "qwen3-coder-plus", "/v1/chat/completions", 4_000),
+];
+
+/// Whether demo mode may run: always in debug builds, otherwise only with
+/// `demo_mode_enabled` in the config
+pub(crate) fn allowed(enabled_in_config: bool) -> bool {
+    cfg!(debug_assertions) || enabled_in_config
+}
+
+/// One plausible request that finished at `timestamp` (ms)
+pub(crate) fn synthesize<R: Rng>(rng: &mut R, timestamp: u64) -> RequestLog {
+    let (provider, model, path, latency_ms) = MODELS[rng.gen_range(0..MODELS.len())];
+    // Mostly successes, with the odd rate limit or upstream error
+    let status = match rng.gen_range(0..100) {
+        0..=4 => 429,
+        5..=6 => 500,
+        _ => 200,
+    };
+    let (duration_ms, tokens_in, tokens_out, tokens_cached) = if status == 200 {
+        let tokens_in = rng.gen_range(200..40_000);
+        let cached = rng.gen_bool(0.3).then_some(tokens_in / 2);
+        (
+            latency_ms / 4 + rng.gen_range(0..latency_ms * 3 / 2),
+            Some(tokens_in),
+            Some(rng.gen_range(20..4_000)),
+            cached,
+        )
+    } else {
+        (rng.gen_range(50..800), None, None, None)
+    };
+    RequestLog {
+        id: format!("demo_{}", uuid::Uuid::new_v4().simple()),
+        timestamp,
+        provider: provider.to_string(),
+        model: model.to_string(),
+        method: "POST".to_string(),
+        path: path.to_string(),
+        status,
+        duration_ms,
+        ttfb_ms: None,
+        tokens_in,
+        tokens_out,
+        tokens_cached,
+        account: Some(format!("demo-{}@example.com", provider)),
+        retries: 0,
+        attempts: Vec::new(),
+        synthetic: true,
+        session_id: None,
+        tags: Vec::new(),
+        client: None,
+        app_version: None,
+        sidecar_version: None,
+    }
+}
+
+/// Wait before the next request: 1/rate on average, jittered so the feed
+/// does not tick like a clock
+pub(crate) fn next_delay<R: Rng>(rng: &mut R, rate: f64) -> Duration {
+    let mean = 1.0 / rate.clamp(MIN_RATE, MAX_RATE);
+    Duration::from_secs_f64(mean * rng.gen_range(0.5..1.5))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn synthesizes_varied_tagged_traffic() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let requests: Vec<RequestLog> = (0..1000).map(|i| synthesize(&mut rng, i)).collect();
+
+        assert!(requests.iter().all(|r| r.synthetic));
+        let count = |status: u16| requests.iter().filter(|r| r.status == status).count();
+        assert!(count(200) > 850);
+        assert!(count(429) > 0 && count(500) > 0);
+        for provider in ["claude", "openai", "gemini", "qwen"] {
+            assert!(requests.iter().any(|r| r.provider == provider));
+        }
+        // Failed requests carry no token counts, as in real proxy logs
+        assert!(requests
+            .iter()
+            .filter(|r| r.status != 200)
+            .all(|r| r.tokens_in.is_none() && r.duration_ms < 800));
+    }
+
+    #[test]
+    fn delays_follow_the_rate() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            let delay = next_delay(&mut rng, 4.0);
+            assert!(delay >= Duration::from_millis(125) && delay <= Duration::from_millis(375));
+        }
+        // Out-of-range rates are clamped rather than dividing by zero
+        assert!(next_delay(&mut rng, 0.0) <= Duration::from_secs(15));
+    }
+}
@@ -0,0 +1,154 @@
+//! Reaching the proxy from Docker containers and devcontainers.
+//!
+//! Inside a container "127.0.0.1" is the container itself. Docker Desktop
+//! (macOS, Windows) resolves `host.docker.internal` to the host; on Linux the
+//! host is the bridge gateway (usually 172.17.0.1), and `host.docker.internal`
+//! only resolves when the container is started with `--add-host`.
+
+use std::path::Path;
+use std::process::Command;
+
+use crate::types::DockerHost;
+
+/// Name Docker Desktop resolves to the host
+pub(crate) const DOCKER_DESKTOP_HOST: &str = "host.docker.internal";
+
+/// `docker run` flag that makes `host.docker.internal` resolve on Linux (Docker 20.10+)
+pub(crate) const ADD_HOST_FLAG: &str = "--add-host=host.docker.internal:host-gateway";
+
+/// Default gateway of Docker's bridge network, used when detection fails
+const DEFAULT_BRIDGE_GATEWAY: &str = "172.17.0.1";
+
+// Docker Desktop installs outside the usual binary folders
+const DOCKER_DESKTOP_BINARIES: &[&str] = &[
+    "/Applications/Docker.app/Contents/Resources/bin/docker",
+    r"C:\Program Files\Docker\Docker\resources\bin\docker.exe",
+];
+
+/// Whether the Docker CLI is installed
+pub(crate) fn docker_installed() -> bool {
+    crate::commands::agents::which_exists("docker")
+        || DOCKER_DESKTOP_BINARIES
+            .iter()
+            .any(|p| Path::new(p).is_file())
+}
+
+/// Gateway from `docker network inspect bridge --format '{{range .IPAM.Config}}{{.Gateway}} {{end}}'`
+pub(crate) fn parse_inspect_gateway(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find(|s| s.parse::<std::net::Ipv4Addr>().is_ok())
+        .map(String::from)
+}
+
+/// Host address on docker0 from `ip route`, e.g.
+/// "172.17.0.0/16 dev docker0 proto kernel scope link src 172.17.0.1"
+pub(crate) fn parse_docker0_route(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if !words.windows(2).any(|w| w == ["dev", "docker0"]) {
+            return None;
+        }
+        let ip = words.windows(2).find(|w| w[0] == "src")?[1];
+        ip.parse::<std::net::Ipv4Addr>()
+            .ok()
+            .map(|_| ip.to_string())
+    })
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Linux: ask Docker for the bridge gateway, else read docker0 from the routes
+fn bridge_gateway() -> String {
+    command_output(
+        "docker",
+        &[
+            "network",
+            "inspect",
+            "bridge",
+            "--format",
+            "{{range .IPAM.Config}}{{.Gateway}} {{end}}",
+        ],
+    )
+    .and_then(|out| parse_inspect_gateway(&out))
+    .or_else(|| command_output("ip", &["route"]).and_then(|out| parse_docker0_route(&out)))
+    .unwrap_or_else(|| DEFAULT_BRIDGE_GATEWAY.to_string())
+}
+
+/// How containers on this machine reach a proxy listening on `bind`
+pub(crate) fn docker_host(bind: &str) -> DockerHost {
+    let installed = docker_installed();
+    if !cfg!(target_os = "linux") {
+        return DockerHost {
+            installed,
+            host: DOCKER_DESKTOP_HOST.to_string(),
+            add_host_flag: None,
+            warning: None,
+        };
+    }
+    let gateway = if installed {
+        bridge_gateway()
+    } else {
+        DEFAULT_BRIDGE_GATEWAY.to_string()
+    };
+    linux_docker_host(installed, gateway, bind)
+}
+
+fn linux_docker_host(installed: bool, gateway: String, bind: &str) -> DockerHost {
+    // Without Docker Desktop's forwarding, a proxy on loopback is out of reach
+    let warning = crate::commands::proxy::is_loopback_bind(bind).then(|| {
+        format!(
+            "The proxy only listens on {}, which containers cannot reach on Linux. \
+             Set the listen address to 0.0.0.0 or {} and restart the proxy.",
+            bind, gateway
+        )
+    });
+    DockerHost {
+        installed,
+        host: gateway,
+        add_host_flag: Some(ADD_HOST_FLAG.to_string()),
+        warning,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_bridge_gateway() {
+        assert_eq!(
+            parse_inspect_gateway("172.18.0.1 \n").as_deref(),
+            Some("172.18.0.1")
+        );
+        // IPv6-enabled bridges list both subnets
+        assert_eq!(
+            parse_inspect_gateway("fd00::1 172.17.0.1 ").as_deref(),
+            Some("172.17.0.1")
+        );
+        assert_eq!(parse_inspect_gateway(""), None);
+
+        let routes = "default via 192.168.1.1 dev wlp3s0 proto dhcp metric 600\n\
+                      172.17.0.0/16 dev docker0 proto kernel scope link src 172.17.0.1 linkdown\n\
+                      192.168.1.0/24 dev wlp3s0 proto kernel scope link src 192.168.1.20 metric 600";
+        assert_eq!(parse_docker0_route(routes).as_deref(), Some("172.17.0.1"));
+        assert_eq!(parse_docker0_route("default via 10.0.0.1 dev eth0"), None);
+    }
+
+    #[test]
+    fn warns_when_the_proxy_is_on_loopback() {
+        let host = linux_docker_host(true, "172.17.0.1".to_string(), "127.0.0.1");
+        assert_eq!(host.host, "172.17.0.1");
+        assert_eq!(host.add_host_flag.as_deref(), Some(ADD_HOST_FLAG));
+        assert!(host.warning.unwrap().contains("0.0.0.0 or 172.17.0.1"));
+        assert!(linux_docker_host(true, "172.17.0.1".to_string(), "0.0.0.0")
+            .warning
+            .is_none());
+    }
+}
@@ -0,0 +1,229 @@
+//! Agent environment variables that route traffic away from ProxyPal.
+//!
+//! A leftover `ANTHROPIC_BASE_URL` pointing at an old tunnel, or an
+//! `OPENAI_BASE_URL` exported by another tool, wins over what ProxyPal set up
+//! while the UI shows the agent as configured. [`scan_env`] and
+//! [`scan_profile`] report every such variable set to a URL other than this
+//! proxy's, in ProxyPal's own environment or a shell profile, with the line
+//! it's on. [`comment_out`] disables the lines, but only inside blocks
+//! ProxyPal wrote unless the user allows the rest.
+
+use std::path::{Path, PathBuf};
+
+use crate::helpers::agent_endpoints::{host_port, parse_env_line, ENVIRONMENT};
+use crate::types::EnvConflict;
+
+/// Variables checked besides every `ANTHROPIC_*` one
+const URL_VARS: &[&str] = &[
+    "OPENAI_BASE_URL",
+    "GOOGLE_GEMINI_BASE_URL",
+    "CODE_ASSIST_ENDPOINT",
+    "AMP_URL",
+];
+
+/// Shell profiles checked under the home directory, besides the current
+/// shell's
+const PROFILE_FILES: &[&str] = &[
+    ".profile",
+    ".bashrc",
+    ".bash_profile",
+    ".bash_login",
+    ".zshenv",
+    ".zprofile",
+    ".zshrc",
+    ".config/fish/config.fish",
+];
+
+/// First line of every block ProxyPal writes to a shell profile; the block
+/// runs to the next blank line
+const MANAGED_HEADER: &str = "# ProxyPal";
+
+/// Put in front of the lines [`comment_out`] disables
+const DISABLED_PREFIX: &str = "# Disabled by ProxyPal: ";
+
+fn is_watched(var: &str) -> bool {
+    var.starts_with("ANTHROPIC_") || URL_VARS.contains(&var)
+}
+
+/// A URL that doesn't lead to `expected`. Other values (keys, model names)
+/// never conflict.
+fn points_elsewhere(value: &str, expected: &str) -> bool {
+    if !value.starts_with("http://") && !value.starts_with("https://") {
+        return false;
+    }
+    let found = host_port(value);
+    found.is_none() || found != host_port(expected)
+}
+
+fn conflict(variable: &str, value: &str, source: &str, expected: &str) -> EnvConflict {
+    EnvConflict {
+        variable: variable.to_string(),
+        value: crate::redact::redact(value),
+        source: source.to_string(),
+        line: None,
+        managed: false,
+        expected: expected.to_string(),
+    }
+}
+
+/// Shell profiles under `home` that exist, plus `current`
+pub(crate) fn profile_files(home: &Path, current: Option<&Path>) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = PROFILE_FILES.iter().map(|f| home.join(f)).collect();
+    if let Some(current) = current.filter(|c| !files.iter().any(|f| f == c)) {
+        files.push(current.to_path_buf());
+    }
+    files.retain(|f| f.is_file());
+    files
+}
+
+/// Whether each line of `content` is inside a block ProxyPal wrote
+fn managed_lines(content: &str) -> Vec<bool> {
+    let mut in_block = false;
+    content
+        .split('\n')
+        .map(|line| {
+            let line = line.trim();
+            if line.starts_with(MANAGED_HEADER) {
+                in_block = true;
+            } else if line.is_empty() {
+                in_block = false;
+            }
+            in_block
+        })
+        .collect()
+}
+
+/// Conflicting variables among `vars`, labelled as ProxyPal's environment
+pub(crate) fn scan_env(
+    vars: impl IntoIterator<Item = (String, String)>,
+    expected: &str,
+) -> Vec<EnvConflict> {
+    let mut conflicts: Vec<EnvConflict> = vars
+        .into_iter()
+        .filter(|(var, value)| is_watched(var) && points_elsewhere(value, expected))
+        .map(|(var, value)| conflict(&var, &value, ENVIRONMENT, expected))
+        .collect();
+    conflicts.sort_by(|a, b| a.variable.cmp(&b.variable));
+    conflicts
+}
+
+/// Conflicting exports in the shell profile at `source`, with their lines
+pub(crate) fn scan_profile(source: &str, content: &str, expected: &str) -> Vec<EnvConflict> {
+    let managed = managed_lines(content);
+    content
+        .split('\n')
+        .enumerate()
+        .filter(|(_, line)| !line.trim_start().starts_with('#'))
+        .filter_map(|(index, line)| {
+            let (var, value) = parse_env_line(line)?;
+            if !is_watched(var) || !points_elsewhere(value, expected) {
+                return None;
+            }
+            Some(EnvConflict {
+                line: Some(index as u32 + 1),
+                managed: managed[index],
+                ..conflict(var, value, source, expected)
+            })
+        })
+        .collect()
+}
+
+/// Comment out the watched exports on `lines` (1-based) that are inside a
+/// ProxyPal block, or all of them with `outside_managed`. Returns the new
+/// content and the lines changed.
+pub(crate) fn comment_out(
+    content: &str,
+    lines: &[u32],
+    outside_managed: bool,
+) -> (String, Vec<u32>) {
+    let managed = managed_lines(content);
+    let mut changed = Vec::new();
+    let updated: Vec<String> = content
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let number = index as u32 + 1;
+            let export = parse_env_line(line).filter(|(var, _)| is_watched(var));
+            if lines.contains(&number)
+                && export.is_some()
+                && !line.trim_start().starts_with('#')
+                && (managed[index] || outside_managed)
+            {
+                changed.push(number);
+                format!("{}{}", DISABLED_PREFIX, line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    (updated.join("\n"), changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPECTED: &str = "http://127.0.0.1:8317";
+
+    const PROFILE: &str = "export PATH=\"$HOME/bin:$PATH\"\n\
+                           export ANTHROPIC_BASE_URL=\"https://old-tunnel.ngrok.io\"\n\
+                           export ANTHROPIC_MODEL=\"claude-sonnet-4-5\"\n\
+                           \n\
+                           # ProxyPal - OpenCode Configuration\n\
+                           export OPENAI_BASE_URL=\"http://127.0.0.1:8320/v1\"\n\
+                           export OPENAI_API_KEY=\"pp-key\"\n\
+                           \n\
+                           # export AMP_URL=\"https://ampcode.com\"\n\
+                           export AMP_URL=\"http://localhost:8317\"\n";
+
+    #[test]
+    fn reports_urls_elsewhere_with_their_lines() {
+        let conflicts = scan_profile("/home/me/.zshrc", PROFILE, EXPECTED);
+        let found: Vec<(&str, Option<u32>, bool)> = conflicts
+            .iter()
+            .map(|c| (c.variable.as_str(), c.line, c.managed))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("ANTHROPIC_BASE_URL", Some(2), false),
+                ("OPENAI_BASE_URL", Some(6), true),
+            ]
+        );
+
+        let env = scan_env(
+            vec![
+                (
+                    "OPENAI_BASE_URL".to_string(),
+                    "https://api.example.com/v1".to_string(),
+                ),
+                (
+                    "ANTHROPIC_AUTH_TOKEN".to_string(),
+                    "sk-ant-test".to_string(),
+                ),
+                ("HOME".to_string(), "/home/me".to_string()),
+            ],
+            EXPECTED,
+        );
+        assert_eq!(env.len(), 1);
+        assert_eq!(env[0].source, ENVIRONMENT);
+        assert_eq!(env[0].line, None);
+    }
+
+    #[test]
+    fn comments_out_only_managed_lines_unless_allowed() {
+        let (updated, changed) = comment_out(PROFILE, &[2, 6], false);
+        assert_eq!(changed, [6]);
+        assert!(updated.contains("\n# Disabled by ProxyPal: export OPENAI_BASE_URL="));
+        assert!(updated.contains("\nexport ANTHROPIC_BASE_URL="));
+        assert_eq!(scan_profile("p", &updated, EXPECTED).len(), 1);
+
+        let (updated, changed) = comment_out(PROFILE, &[2, 6], true);
+        assert_eq!(changed, [2, 6]);
+        assert!(scan_profile("p", &updated, EXPECTED).is_empty());
+        assert!(updated.ends_with('\n'));
+
+        // Lines that aren't agent exports are never touched
+        assert!(comment_out(PROFILE, &[1], true).1.is_empty());
+    }
+}
@@ -1,48 +1,169 @@
 //! Request history and aggregate I/O helpers.
 
-use crate::config::{get_aggregate_path, get_history_path};
-use crate::types::{Aggregate, ModelStats, RequestHistory, RequestLog, TimeSeriesPoint};
+use std::path::{Path, PathBuf};
 
-// Load request history from file
-pub(crate) fn load_request_history() -> RequestHistory {
-    let path = get_history_path();
-    if path.exists() {
-        if let Ok(data) = std::fs::read_to_string(&path) {
-            if let Ok(mut history) = serde_json::from_str::<RequestHistory>(&data) {
-                // Recalculate totals from saved requests if counters are missing
-                // This handles migration from old format
-                if history.total_request_count == 0 && !history.requests.is_empty() {
-                    history.total_request_count = history.requests.len() as u64;
-                }
-                if history.total_success_count == 0 && !history.requests.is_empty() {
-                    history.total_success_count =
-                        history.requests.iter().filter(|r| r.status < 400).count() as u64;
-                }
-                return history;
-            }
-        }
+use parking_lot::RwLock;
+use serde::Serialize;
+
+use crate::config::{get_aggregate_path, get_history_path, AppConfig};
+use crate::helpers::aggregate_compaction;
+use crate::helpers::history_store::HistoryStore;
+use crate::helpers::usage_day::{self, UsageTimezone};
+use crate::types::{
+    AccountUsage, Aggregate, AuthFile, ClientUsage, ModelStats, QuotaSwitchEvent, RequestHistory,
+    RequestLog, TimeSeriesPoint,
+};
+use crate::utils::estimate_request_cost;
+
+/// How much of the detailed request list is kept on disk. Totals and the
+/// time series live in the aggregate, so trimming never changes them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct HistoryRetention {
+    /// Newest entries kept; 0 keeps every entry
+    pub max_entries: usize,
+    /// Entries older than this many days are dropped; 0 disables the age limit
+    pub max_age_days: u32,
+}
+
+lazy_static::lazy_static! {
+    static ref RETENTION: RwLock<HistoryRetention> = RwLock::new(HistoryRetention {
+        max_entries: 500,
+        max_age_days: 0,
+    });
+    // Daily request counts of the aggregate last loaded or saved, with its
+    // path, so today's figures don't re-read the whole file on every tick
+    static ref REQUESTS_BY_DAY: RwLock<Option<(PathBuf, Vec<TimeSeriesPoint>)>> =
+        RwLock::new(None);
+}
+
+/// Register the retention policy of the current config. Call after loading or saving it.
+pub(crate) fn set_history_retention(config: &AppConfig) {
+    *RETENTION.write() = HistoryRetention {
+        max_entries: config.history_max_entries,
+        max_age_days: config.history_max_age_days,
+    };
+}
+
+/// Drop entries the policy no longer keeps, in place. `requests` is ordered
+/// oldest first; returns how many entries were removed.
+pub(crate) fn apply_retention(
+    requests: &mut Vec<RequestLog>,
+    retention: HistoryRetention,
+    now_ms: u64,
+) -> usize {
+    let mut expired = 0;
+    if retention.max_age_days > 0 {
+        let cutoff = now_ms.saturating_sub(retention.max_age_days as u64 * 24 * 60 * 60 * 1000);
+        expired = requests.iter().take_while(|r| r.timestamp < cutoff).count();
+    }
+    if retention.max_entries > 0 {
+        expired = expired.max(requests.len().saturating_sub(retention.max_entries));
     }
-    RequestHistory::default()
+    requests.drain(..expired);
+    expired
 }
 
-// Save request history to file (keep last 500 requests)
-pub(crate) fn save_request_history(history: &RequestHistory) -> Result<(), String> {
-    let path = get_history_path();
-    let mut trimmed = history.clone();
-    // Keep only last 500 requests in the array for UI display
-    // But preserve totalRequestCount and totalSuccessCount (cumulative across all history)
-    if trimmed.requests.len() > 500 {
-        trimmed.requests = trimmed.requests.split_off(trimmed.requests.len() - 500);
+// On-disk shape of history.json: the detailed list and quota switches
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryFile<'a> {
+    requests: &'a [RequestLog],
+    quota_events: &'a [QuotaSwitchEvent],
+}
+
+/// Read a history file; None if it is missing or unreadable
+pub(crate) fn read_history_file(path: &Path) -> Option<RequestHistory> {
+    crate::startup::note_fs_read();
+    let data = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+// Entries of a damaged history file that still read as requests and quota
+// switches; anything cut off or malformed is left out
+fn recover_history(damaged: &serde_json::Value) -> Option<(RequestHistory, String)> {
+    fn fitting<T: serde::de::DeserializeOwned>(list: Option<&serde_json::Value>) -> Vec<T> {
+        list.and_then(|list| list.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| serde_json::from_value(item.clone()).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+    let requests: Vec<RequestLog> = fitting(damaged.get("requests"));
+    if requests.is_empty() {
+        return None;
     }
-    let data = serde_json::to_string_pretty(&trimmed).map_err(|e| e.to_string())?;
-    std::fs::write(path, data).map_err(|e| e.to_string())
+    let total = damaged["requests"]
+        .as_array()
+        .map_or(0, |items| items.len());
+    let detail = format!("recovered {} of {} requests", requests.len(), total);
+    let history = RequestHistory {
+        requests,
+        quota_events: fitting(damaged.get("quotaEvents")),
+        ..Default::default()
+    };
+    Some((history, detail))
+}
+
+/// Load the history file at `path`. Unlike [`read_history_file`], a damaged
+/// file is quarantined and the requests it still holds are kept.
+pub(crate) fn load_history_file(path: &Path) -> Option<RequestHistory> {
+    crate::helpers::recovery::load_json(path, recover_history)
+}
+
+// Load request history from file. Files written before totals moved to the
+// aggregate still carry them; migration reads them from here. Everything
+// else goes through `AppState.history`.
+pub(crate) fn load_request_history() -> RequestHistory {
+    load_history_file(&get_history_path()).unwrap_or_default()
+}
+
+/// Request history as shown to the UI: the retained list, with all-time
+/// totals and token series taken from the aggregate.
+pub(crate) fn request_history_view(mut history: RequestHistory, agg: &Aggregate) -> RequestHistory {
+    history.total_tokens_in = agg.total_tokens_in;
+    history.total_tokens_out = agg.total_tokens_out;
+    history.total_tokens_cached = agg.total_tokens_cached;
+    history.total_cost_usd = agg.total_cost_usd;
+    history.total_request_count = agg.total_requests;
+    history.total_success_count = agg.total_success_count;
+    history.tokens_by_day = agg.tokens_by_day.clone();
+    history.tokens_by_hour = agg.tokens_by_hour.clone();
+    history
+}
+
+/// The retention policy registered from the current config
+pub(crate) fn current_retention() -> HistoryRetention {
+    *RETENTION.read()
+}
+
+/// history.json content for `history`: the detailed list and quota switches
+pub(crate) fn encode_history(history: &RequestHistory) -> Result<String, String> {
+    let file = HistoryFile {
+        requests: &history.requests,
+        quota_events: &history.quota_events,
+    };
+    serde_json::to_string_pretty(&file).map_err(|e| e.to_string())
+}
+
+/// Replace a history file atomically: a crash leaves either the old file or
+/// the new one, plus at most a stray `.json.tmp`
+pub(crate) fn write_history_file(path: &Path, data: &str) -> Result<(), String> {
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, data).map_err(|e| e.to_string())?;
+    std::fs::rename(&temp_path, path).map_err(|e| e.to_string())
 }
 
 pub(crate) fn load_aggregate() -> Aggregate {
+    crate::startup::note_fs_read();
     let path = get_aggregate_path();
     if path.exists() {
         if let Ok(data) = std::fs::read_to_string(&path) {
-            if let Ok(agg) = serde_json::from_str(&data) {
+            if let Ok(mut agg) = serde_json::from_str::<Aggregate>(&data) {
+                crate::helpers::cost_ledger::adopt_legacy_total(&mut agg);
+                *REQUESTS_BY_DAY.write() = Some((path, agg.requests_by_day.clone()));
                 return agg;
             }
         }
@@ -50,12 +171,70 @@ pub(crate) fn load_aggregate() -> Aggregate {
     Aggregate::default()
 }
 
+/// Today's request count (from the aggregate) and estimated spend in USD (from
+/// the retained request history), for the configured usage day. The counts
+/// are those of the last aggregate loaded or saved; the file is only read
+/// when there is none yet, or the data dir has moved.
+pub(crate) fn today_stats(history: &HistoryStore) -> (u64, f64) {
+    let path = get_aggregate_path();
+    let cached = REQUESTS_BY_DAY
+        .read()
+        .as_ref()
+        .filter(|(cached, _)| *cached == path)
+        .map(|(_, series)| series.clone());
+    let requests_by_day = cached.unwrap_or_else(|| load_aggregate().requests_by_day);
+    history.read(|history| {
+        today_totals(
+            &requests_by_day,
+            &history.requests,
+            usage_day::current(),
+            chrono::Utc::now(),
+        )
+    })
+}
+
+fn today_totals(
+    requests_by_day: &[TimeSeriesPoint],
+    requests: &[RequestLog],
+    timezone: UsageTimezone,
+    now: chrono::DateTime<chrono::Utc>,
+) -> (u64, f64) {
+    let requests_today = usage_day::today_value(requests_by_day, timezone, now);
+    let today = usage_day::day_label(timezone, now);
+    let cost = requests
+        .iter()
+        .filter(|r| !r.synthetic)
+        .filter(|r| usage_day::day_label_ms(timezone, r.timestamp).as_deref() == Some(&today))
+        .map(|r| {
+            estimate_request_cost(
+                &r.model,
+                r.tokens_in.unwrap_or(0),
+                r.tokens_out.unwrap_or(0),
+            )
+        })
+        .sum();
+    (requests_today, cost)
+}
+
+/// Write the aggregate atomically, compacting its series first once they
+/// reach past the kept hours and days (see `aggregate_compaction`)
 pub(crate) fn save_aggregate(agg: &Aggregate) -> Result<(), String> {
+    let today = usage_day::day_label(usage_day::current(), chrono::Utc::now());
+    let hourly_days = aggregate_compaction::hourly_days();
+    let compacted;
+    let agg = if aggregate_compaction::needs_compaction(agg, &today, hourly_days) {
+        compacted = aggregate_compaction::compact(agg, &today, hourly_days);
+        &compacted
+    } else {
+        agg
+    };
     let path = get_aggregate_path();
     let temp_path = path.with_extension("json.tmp");
     let data = serde_json::to_string_pretty(agg).map_err(|e| e.to_string())?;
     std::fs::write(&temp_path, data).map_err(|e| e.to_string())?;
-    std::fs::rename(&temp_path, &path).map_err(|e| e.to_string())
+    std::fs::rename(&temp_path, &path).map_err(|e| e.to_string())?;
+    *REQUESTS_BY_DAY.write() = Some((path, agg.requests_by_day.clone()));
+    Ok(())
 }
 
 pub(crate) fn update_timeseries(series: &mut Vec<TimeSeriesPoint>, label: &str, increment: u64) {
@@ -107,3 +286,324 @@ pub(crate) fn update_provider_stats(agg: &mut Aggregate, req: &RequestLog) {
     }
     entry.tokens += (req.tokens_in.unwrap_or(0) + req.tokens_out.unwrap_or(0)) as u64;
 }
+
+// Friendly name for an account from the credential it came from. Logs name
+// an account by email or by its credential file, with or without ".json".
+fn account_label(account: &str, files: &[AuthFile]) -> Option<String> {
+    let file = files.iter().find(|f| {
+        let stem = f.name.strip_suffix(".json").unwrap_or(&f.name);
+        f.email.as_deref() == Some(account)
+            || f.account.as_deref() == Some(account)
+            || f.name == account
+            || stem == account.strip_suffix(".json").unwrap_or(account)
+    })?;
+    file.label
+        .clone()
+        .filter(|l| !l.is_empty())
+        .or_else(|| file.email.clone())
+}
+
+/// Requests rolled up per account, busiest first, with the unattributed
+/// bucket last
+pub(crate) fn usage_by_account(requests: &[RequestLog], files: &[AuthFile]) -> Vec<AccountUsage> {
+    let mut by_account: std::collections::HashMap<Option<String>, AccountUsage> =
+        std::collections::HashMap::new();
+    for req in requests.iter().filter(|r| !r.synthetic) {
+        let entry = by_account
+            .entry(req.account.clone())
+            .or_insert_with(|| AccountUsage {
+                account: req.account.clone(),
+                label: match &req.account {
+                    Some(account) => account_label(account, files),
+                    None => Some("Unattributed".to_string()),
+                },
+                ..AccountUsage::default()
+            });
+        entry.requests += 1;
+        if req.status < 400 {
+            entry.success_count += 1;
+        }
+        let (tokens_in, tokens_out) = (req.tokens_in.unwrap_or(0), req.tokens_out.unwrap_or(0));
+        entry.tokens_in += tokens_in as u64;
+        entry.tokens_out += tokens_out as u64;
+        entry.estimated_cost += estimate_request_cost(&req.model, tokens_in, tokens_out);
+        entry.last_used = entry.last_used.max(req.timestamp);
+        if !entry.providers.contains(&req.provider) {
+            entry.providers.push(req.provider.clone());
+        }
+    }
+    let mut rollup: Vec<AccountUsage> = by_account.into_values().collect();
+    rollup.sort_by(|a, b| {
+        a.account
+            .is_none()
+            .cmp(&b.account.is_none())
+            .then(b.requests.cmp(&a.requests))
+            .then(a.account.cmp(&b.account))
+    });
+    rollup
+}
+
+/// Requests rolled up per calling tool, busiest first, with the
+/// unidentified bucket last
+pub(crate) fn usage_by_client(requests: &[RequestLog]) -> Vec<ClientUsage> {
+    let mut by_client: std::collections::HashMap<Option<String>, ClientUsage> =
+        std::collections::HashMap::new();
+    for req in requests.iter().filter(|r| !r.synthetic) {
+        let entry = by_client
+            .entry(req.client.clone())
+            .or_insert_with(|| ClientUsage {
+                client: req.client.clone(),
+                ..ClientUsage::default()
+            });
+        entry.requests += 1;
+        if req.status < 400 {
+            entry.success_count += 1;
+        }
+        let (tokens_in, tokens_out) = (req.tokens_in.unwrap_or(0), req.tokens_out.unwrap_or(0));
+        entry.tokens_in += tokens_in as u64;
+        entry.tokens_out += tokens_out as u64;
+        entry.estimated_cost += estimate_request_cost(&req.model, tokens_in, tokens_out);
+        entry.last_used = entry.last_used.max(req.timestamp);
+        if !entry.providers.contains(&req.provider) {
+            entry.providers.push(req.provider.clone());
+        }
+    }
+    let mut rollup: Vec<ClientUsage> = by_client.into_values().collect();
+    rollup.sort_by(|a, b| {
+        a.client
+            .is_none()
+            .cmp(&b.client.is_none())
+            .then(b.requests.cmp(&a.requests))
+            .then(a.client.cmp(&b.client))
+    });
+    rollup
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+    fn request(timestamp: u64) -> RequestLog {
+        RequestLog {
+            id: format!("req_{}", timestamp),
+            timestamp,
+            provider: "claude".to_string(),
+            model: "claude-sonnet-4-5".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status: 200,
+            duration_ms: 800,
+            ttfb_ms: None,
+            tokens_in: Some(100),
+            tokens_out: Some(50),
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    // Ten requests, one per day, recorded in both stores
+    fn ten_days(now_ms: u64) -> (RequestHistory, Aggregate) {
+        let mut history = RequestHistory::default();
+        let mut agg = Aggregate::default();
+        for day in (0..10).rev() {
+            let req = request(now_ms - day * DAY_MS);
+            let label = chrono::DateTime::from_timestamp_millis(req.timestamp as i64)
+                .unwrap()
+                .format("%Y-%m-%d")
+                .to_string();
+            agg.total_requests += 1;
+            agg.total_success_count += 1;
+            agg.total_tokens_in += 100;
+            agg.total_tokens_out += 50;
+            update_timeseries(&mut agg.requests_by_day, &label, 1);
+            update_timeseries(&mut agg.tokens_by_day, &label, 150);
+            history.requests.push(req);
+        }
+        (history, agg)
+    }
+
+    #[test]
+    fn trimming_keeps_totals_and_daily_series() {
+        let now_ms = 1_760_000_000_000;
+        let (mut history, agg) = ten_days(now_ms);
+        let before = serde_json::to_value(request_history_view(history.clone(), &agg)).unwrap();
+
+        let path =
+            std::env::temp_dir().join(format!("proxypal-history-{}.json", uuid::Uuid::new_v4()));
+        let retention = HistoryRetention {
+            max_entries: 4,
+            max_age_days: 0,
+        };
+        apply_retention(&mut history.requests, retention, now_ms);
+        write_history_file(&path, &encode_history(&history).unwrap()).unwrap();
+        let saved = read_history_file(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        // Six old entries are gone from disk, and only the list is stored
+        assert_eq!(saved.requests.len(), 4);
+        assert_eq!(saved.requests[0].timestamp, now_ms - 3 * DAY_MS);
+        assert_eq!(saved.total_tokens_in, 0);
+
+        let after = request_history_view(saved, &agg);
+        assert_eq!(after.total_request_count, 10);
+        assert_eq!(after.total_tokens_in, 1000);
+        assert_eq!(after.tokens_by_day.len(), 10);
+        for field in [
+            "totalTokensIn",
+            "totalTokensOut",
+            "totalCostUsd",
+            "totalRequestCount",
+            "tokensByDay",
+        ] {
+            assert_eq!(serde_json::to_value(&after).unwrap()[field], before[field]);
+        }
+        assert_eq!(agg.requests_by_day.iter().map(|p| p.value).sum::<u64>(), 10);
+    }
+
+    #[test]
+    fn damaged_history_is_quarantined_and_keeps_whole_requests() {
+        let dir = std::env::temp_dir().join(format!("proxypal-history-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("history.json");
+        let history = RequestHistory {
+            requests: (0..3).map(request).collect(),
+            ..Default::default()
+        };
+        let full = encode_history(&history).unwrap();
+        // Cut off inside the third request, as a crash mid-write would
+        let cut = full.rfind("\"model\"").unwrap();
+        std::fs::write(&path, &full[..cut]).unwrap();
+
+        let loaded = load_history_file(&path).unwrap();
+        assert_eq!(loaded.requests.len(), 2);
+        // The recovered requests are written back, so the next load reads them
+        assert_eq!(load_history_file(&path).unwrap().requests.len(), 2);
+        let aside: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.path() != path)
+            .collect();
+        assert_eq!(aside.len(), 1);
+        assert!(aside[0]
+            .file_name()
+            .to_string_lossy()
+            .starts_with("history.json.corrupt-"));
+
+        // Garbage holds nothing; a missing file is simply empty
+        std::fs::write(&path, "\u{1}\u{2}garbage").unwrap();
+        assert!(load_history_file(&path).is_none());
+        assert!(load_history_file(&path).is_none());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn retention_applies_age_and_count_limits() {
+        let now_ms = 1_760_000_000_000;
+        let (history, _) = ten_days(now_ms);
+
+        let mut requests = history.requests.clone();
+        let by_age = HistoryRetention {
+            max_entries: 0,
+            max_age_days: 3,
+        };
+        assert_eq!(apply_retention(&mut requests, by_age, now_ms), 6);
+        assert_eq!(requests.first().unwrap().timestamp, now_ms - 3 * DAY_MS);
+
+        // The stricter of both limits wins
+        let mut requests = history.requests.clone();
+        let both = HistoryRetention {
+            max_entries: 2,
+            max_age_days: 3,
+        };
+        assert_eq!(apply_retention(&mut requests, both, now_ms), 8);
+
+        let mut requests = history.requests;
+        let unlimited = HistoryRetention {
+            max_entries: 0,
+            max_age_days: 0,
+        };
+        assert_eq!(apply_retention(&mut requests, unlimited, now_ms), 0);
+        assert_eq!(requests.len(), 10);
+    }
+
+    #[test]
+    fn today_totals_roll_over_at_the_usage_midnight() {
+        let ms = |s: &str| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .unwrap()
+                .timestamp_millis()
+        };
+        let late = request(ms("2026-03-10T23:59:00Z") as u64);
+        let early = request(ms("2026-03-11T00:01:00Z") as u64);
+        let mut agg = Aggregate::default();
+        update_timeseries(&mut agg.requests_by_day, "2026-03-10", 40);
+        update_timeseries(&mut agg.requests_by_day, "2026-03-11", 1);
+        let one_cost = estimate_request_cost(&late.model, 100, 50);
+        let requests = [late, early];
+
+        let before = chrono::DateTime::from_timestamp_millis(ms("2026-03-10T23:59:30Z")).unwrap();
+        let after = chrono::DateTime::from_timestamp_millis(ms("2026-03-11T00:01:30Z")).unwrap();
+        assert_eq!(
+            today_totals(&agg.requests_by_day, &requests, UsageTimezone::Utc, before),
+            (40, one_cost)
+        );
+        assert_eq!(
+            today_totals(&agg.requests_by_day, &requests, UsageTimezone::Utc, after),
+            (1, one_cost)
+        );
+    }
+
+    #[test]
+    fn usage_by_account_uses_labels_and_keeps_unattributed_apart() {
+        let files: Vec<AuthFile> = serde_json::from_value(serde_json::json!([{
+            "id": "claude-alice@example.com.json",
+            "name": "claude-alice@example.com.json",
+            "provider": "claude",
+            "status": "active",
+            "email": "alice@example.com",
+            "label": "Alice (work)",
+        }]))
+        .unwrap();
+        let mut requests: Vec<RequestLog> = (0..4).map(request).collect();
+        requests[0].account = Some("alice@example.com".to_string());
+        requests[1].account = Some("alice@example.com".to_string());
+        requests[2].account = Some("sk-...a1b2".to_string());
+
+        let rollup = usage_by_account(&requests, &files);
+        assert_eq!(rollup.len(), 3);
+        assert_eq!(rollup[0].account.as_deref(), Some("alice@example.com"));
+        assert_eq!(rollup[0].label.as_deref(), Some("Alice (work)"));
+        assert_eq!((rollup[0].requests, rollup[0].tokens_in), (2, 200));
+        assert_eq!(rollup[1].label, None);
+        assert_eq!(rollup[2].account, None);
+        assert_eq!(rollup[2].label.as_deref(), Some("Unattributed"));
+        assert_eq!(rollup[2].requests, 1);
+    }
+
+    #[test]
+    fn usage_by_client_keeps_unidentified_requests_last() {
+        let mut requests: Vec<RequestLog> = (0..5).map(request).collect();
+        requests[0].client = Some("Laptop".to_string());
+        requests[1].client = Some("Claude Code".to_string());
+        requests[2].client = Some("Claude Code".to_string());
+        requests[3].client = Some("Claude Code".to_string());
+        requests[3].synthetic = true;
+
+        let rollup = usage_by_client(&requests);
+        let clients: Vec<Option<&str>> = rollup.iter().map(|c| c.client.as_deref()).collect();
+        assert_eq!(clients, [Some("Claude Code"), Some("Laptop"), None]);
+        assert_eq!((rollup[0].requests, rollup[0].tokens_out), (2, 100));
+    }
+}
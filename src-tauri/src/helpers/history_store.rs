@@ -0,0 +1,245 @@
+//! In-memory request history.
+//!
+//! `AppState.history` holds the authoritative `RequestHistory`. It is read
+//! from history.json on first use and served from memory after that. Changes
+//! apply to memory at once; a background flush writes them out at most once
+//! per [`FLUSH_DELAY`], and the app flushes again on exit.
+//!
+//! While changes are unsaved, a `history.dirty` marker sits next to
+//! history.json. Finding it on first load means the last run ended before
+//! flushing, possibly mid-write, so the store reconciles history.json with
+//! the temp file of an interrupted write before trusting either.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::config::get_history_path;
+use crate::helpers::history::{
+    apply_retention, current_retention, encode_history, load_history_file, read_history_file,
+    write_history_file,
+};
+use crate::types::{RequestHistory, RequestLog};
+
+/// Longest a change stays in memory only
+pub(crate) const FLUSH_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+pub struct HistoryStore {
+    // None until first use
+    history: Mutex<Option<RequestHistory>>,
+    flush_scheduled: AtomicBool,
+    // history.json location; the storage default unless set by tests
+    path: Option<PathBuf>,
+}
+
+fn dirty_marker(path: &Path) -> PathBuf {
+    path.with_extension("dirty")
+}
+
+fn temp_file(path: &Path) -> PathBuf {
+    path.with_extension("json.tmp")
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+// Merge the entries of an interrupted write into the saved ones, oldest first
+fn reconcile(saved: Vec<RequestLog>, interrupted: Vec<RequestLog>) -> Vec<RequestLog> {
+    let mut seen = std::collections::HashSet::new();
+    let mut merged: Vec<RequestLog> = saved
+        .into_iter()
+        .chain(interrupted)
+        .filter(|log| seen.insert(log.id.clone()))
+        .collect();
+    merged.sort_by_key(|log| log.timestamp);
+    merged
+}
+
+impl HistoryStore {
+    #[cfg(test)]
+    fn at(path: PathBuf) -> Self {
+        Self {
+            path: Some(path),
+            ..Self::default()
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.path.clone().unwrap_or_else(get_history_path)
+    }
+
+    fn load(&self) -> RequestHistory {
+        let path = self.path();
+        let mut history = load_history_file(&path).unwrap_or_default();
+        let marker = dirty_marker(&path);
+        if !marker.exists() {
+            return history;
+        }
+
+        // Unclean shutdown: the temp file holds whatever the last write got to
+        let interrupted = read_history_file(&temp_file(&path))
+            .map(|h| h.requests)
+            .unwrap_or_default();
+        let before = history.requests.len();
+        history.requests = reconcile(std::mem::take(&mut history.requests), interrupted);
+        apply_retention(&mut history.requests, current_retention(), now_ms());
+        eprintln!(
+            "[ProxyPal] History was not saved cleanly last time; reconciled {} -> {} entries",
+            before,
+            history.requests.len()
+        );
+        match encode_history(&history).and_then(|data| write_history_file(&path, &data)) {
+            Ok(()) => {
+                let _ = std::fs::remove_file(&marker);
+            }
+            Err(e) => eprintln!("[ProxyPal] Failed to save reconciled history: {}", e),
+        }
+        history
+    }
+
+    /// Read the history, loading it on first use
+    pub fn read<R>(&self, f: impl FnOnce(&RequestHistory) -> R) -> R {
+        let mut history = self.history.lock();
+        f(history.get_or_insert_with(|| self.load()))
+    }
+
+    /// Copy of the current history
+    pub fn snapshot(&self) -> RequestHistory {
+        self.read(RequestHistory::clone)
+    }
+
+    /// Change the history in memory and schedule a flush. Retention is
+    /// applied right after `f`, so memory stays as bounded as the file.
+    pub fn update<R>(self: &Arc<Self>, f: impl FnOnce(&mut RequestHistory) -> R) -> R {
+        let result = {
+            let mut guard = self.history.lock();
+            let history = guard.get_or_insert_with(|| self.load());
+            let result = f(history);
+            apply_retention(&mut history.requests, current_retention(), now_ms());
+            result
+        };
+        self.schedule_flush();
+        result
+    }
+
+    fn schedule_flush(self: &Arc<Self>) {
+        if self.flush_scheduled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let _ = std::fs::write(dirty_marker(&self.path()), b"");
+        let store = self.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(FLUSH_DELAY);
+            if let Err(e) = store.flush() {
+                eprintln!("[ProxyPal] Failed to save history: {}", e);
+            }
+        });
+    }
+
+    /// Write pending changes now (also called on exit)
+    pub fn flush(&self) -> Result<(), String> {
+        // Changes made from here on schedule another flush
+        if !self.flush_scheduled.swap(false, Ordering::AcqRel) {
+            return Ok(());
+        }
+        let data = match self.history.lock().as_ref() {
+            Some(history) => encode_history(history)?,
+            None => return Ok(()),
+        };
+        let path = self.path();
+        write_history_file(&path, &data)?;
+        if !self.flush_scheduled.load(Ordering::Acquire) {
+            let _ = std::fs::remove_file(dirty_marker(&path));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(id: &str, timestamp: u64) -> RequestLog {
+        RequestLog {
+            id: id.to_string(),
+            timestamp,
+            provider: "claude".to_string(),
+            model: "claude-sonnet-4-5".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status: 200,
+            duration_ms: 800,
+            ttfb_ms: None,
+            tokens_in: None,
+            tokens_out: None,
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    fn temp_history_path() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("proxypal-store-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("history.json")
+    }
+
+    fn write(path: &Path, requests: Vec<RequestLog>) {
+        let history = RequestHistory {
+            requests,
+            ..RequestHistory::default()
+        };
+        std::fs::write(path, encode_history(&history).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn serves_from_memory_and_flushes_on_demand() {
+        let path = temp_history_path();
+        write(&path, vec![request("a", 1)]);
+        let store = Arc::new(HistoryStore::at(path.clone()));
+        assert_eq!(store.read(|h| h.requests.len()), 1);
+
+        // Later disk changes are not re-read
+        write(&path, vec![]);
+        store.update(|h| h.requests.push(request("b", now_ms())));
+        assert_eq!(store.read(|h| h.requests.len()), 2);
+        assert!(dirty_marker(&path).exists());
+
+        store.flush().unwrap();
+        assert_eq!(read_history_file(&path).unwrap().requests.len(), 2);
+        assert!(!dirty_marker(&path).exists());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn reconciles_after_an_unclean_shutdown() {
+        let path = temp_history_path();
+        let now = now_ms();
+        write(&path, vec![request("a", now - 2), request("b", now - 1)]);
+        // A write that never got renamed into place
+        write(
+            &temp_file(&path),
+            vec![request("b", now - 1), request("c", now)],
+        );
+        std::fs::write(dirty_marker(&path), b"").unwrap();
+
+        let store = HistoryStore::at(path.clone());
+        let ids = store.read(|h| h.requests.iter().map(|r| r.id.clone()).collect::<Vec<_>>());
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert!(!dirty_marker(&path).exists());
+        assert_eq!(read_history_file(&path).unwrap().requests.len(), 3);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,56 @@
+//! Tracks proxy requests that have started but not yet completed.
+//!
+//! CLIProxyAPI logs a `| <request id> | ... for model <name>` line when it picks
+//! credentials for a request and a `| <request id> | <status> | ...` line when the
+//! request finishes; the log watcher feeds both into this tracker.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Requests older than this are assumed to have finished without a completion line
+const STALE_AFTER: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Default)]
+pub struct InFlightRequests {
+    started: HashMap<String, Instant>,
+}
+
+impl InFlightRequests {
+    pub fn start(&mut self, request_id: &str) {
+        self.started.insert(request_id.to_string(), Instant::now());
+    }
+
+    pub fn complete(&mut self, request_id: &str) {
+        self.started.remove(request_id);
+    }
+
+    pub fn clear(&mut self) {
+        self.started.clear();
+    }
+
+    /// Number of requests currently in flight, dropping stale entries
+    pub fn count(&mut self) -> usize {
+        self.started
+            .retain(|_, started| started.elapsed() < STALE_AFTER);
+        self.started.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_start_and_complete() {
+        let mut requests = InFlightRequests::default();
+        requests.start("f803bb77");
+        requests.start("0a1b2c3d");
+        assert_eq!(requests.count(), 2);
+        requests.complete("f803bb77");
+        assert_eq!(requests.count(), 1);
+        requests.complete("unknown");
+        assert_eq!(requests.count(), 1);
+        requests.clear();
+        assert_eq!(requests.count(), 0);
+    }
+}
@@ -0,0 +1,183 @@
+//! Latency percentiles from request history.
+//!
+//! Total duration mostly measures how much the model wrote, so time to first
+//! token (TTFB) is reported next to it; that is what an interactive agent
+//! waits on. Only successful requests count. Requests without a TTFB
+//! (non-streaming, or logs that don't show the first chunk) count towards
+//! duration only.
+
+use std::collections::HashMap;
+
+use crate::helpers::versions;
+use crate::types::{LatencyStats, RequestLog, VersionDimension};
+
+/// Fewest TTFB samples a median is taken from
+pub(crate) const MIN_TTFB_SAMPLES: usize = 3;
+
+/// Nearest-rank percentile (0-100) of sorted values
+pub(crate) fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Provider, model and (when grouping by version) the version of a row
+type GroupKey<'a> = (&'a str, &'a str, Option<&'a str>);
+
+fn counts(request: &RequestLog, since: u64) -> bool {
+    !request.synthetic && (200..300).contains(&request.status) && request.timestamp >= since
+}
+
+/// Duration and TTFB percentiles per provider and model for successful
+/// requests since `since` (Unix millis), busiest first. With `group_by`, each
+/// version of the app or sidecar gets its own row.
+pub(crate) fn latency_stats(
+    requests: &[RequestLog],
+    since: u64,
+    group_by: Option<VersionDimension>,
+) -> Vec<LatencyStats> {
+    let mut groups: HashMap<GroupKey, (Vec<u64>, Vec<u64>)> = HashMap::new();
+    for request in requests.iter().filter(|r| counts(r, since)) {
+        let version = group_by.map(|dimension| versions::version_of(request, dimension));
+        let (durations, ttfbs) = groups
+            .entry((request.provider.as_str(), request.model.as_str(), version))
+            .or_default();
+        durations.push(request.duration_ms);
+        ttfbs.extend(request.ttfb_ms);
+    }
+
+    let mut stats: Vec<LatencyStats> = groups
+        .into_iter()
+        .map(|((provider, model, version), (mut durations, mut ttfbs))| {
+            durations.sort_unstable();
+            ttfbs.sort_unstable();
+            LatencyStats {
+                provider: provider.to_string(),
+                model: model.to_string(),
+                requests: durations.len() as u64,
+                duration_p50_ms: percentile(&durations, 50.0).unwrap_or(0),
+                duration_p90_ms: percentile(&durations, 90.0).unwrap_or(0),
+                ttfb_samples: ttfbs.len() as u64,
+                ttfb_p50_ms: percentile(&ttfbs, 50.0),
+                ttfb_p90_ms: percentile(&ttfbs, 90.0),
+                version: version.map(str::to_string),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        b.requests
+            .cmp(&a.requests)
+            .then_with(|| a.provider.cmp(&b.provider))
+            .then_with(|| a.model.cmp(&b.model))
+            .then_with(|| a.version.cmp(&b.version))
+    });
+    stats
+}
+
+/// Median TTFB of the successful requests among `requests`; None with fewer
+/// than `MIN_TTFB_SAMPLES` of them showing one
+pub(crate) fn median_ttfb<'a>(requests: impl IntoIterator<Item = &'a RequestLog>) -> Option<u64> {
+    let mut ttfbs: Vec<u64> = requests
+        .into_iter()
+        .filter(|r| counts(r, 0))
+        .filter_map(|r| r.ttfb_ms)
+        .collect();
+    if ttfbs.len() < MIN_TTFB_SAMPLES {
+        return None;
+    }
+    ttfbs.sort_unstable();
+    percentile(&ttfbs, 50.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(model: &str, status: u16, duration_ms: u64, ttfb_ms: Option<u64>) -> RequestLog {
+        RequestLog {
+            id: "req".to_string(),
+            timestamp: 10,
+            provider: "claude".to_string(),
+            model: model.to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status,
+            duration_ms,
+            ttfb_ms,
+            tokens_in: None,
+            tokens_out: None,
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let values: Vec<u64> = (1..=10).collect();
+        assert_eq!(percentile(&values, 50.0), Some(5));
+        assert_eq!(percentile(&values, 90.0), Some(9));
+        assert_eq!(percentile(&values, 0.0), Some(1));
+        assert_eq!(percentile(&[7], 90.0), Some(7));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn requests_without_ttfb_only_count_towards_duration() {
+        let requests = vec![
+            request("opus", 200, 30_000, Some(900)),
+            request("opus", 200, 12_000, None),
+            request("opus", 200, 8_000, Some(1_500)),
+            request("opus", 500, 100, Some(50)),
+            request("haiku", 200, 2_000, None),
+        ];
+        let stats = latency_stats(&requests, 0, None);
+        assert_eq!(stats.len(), 2);
+        let opus = &stats[0];
+        assert_eq!((opus.model.as_str(), opus.requests), ("opus", 3));
+        assert_eq!(opus.duration_p50_ms, 12_000);
+        assert_eq!(opus.duration_p90_ms, 30_000);
+        assert_eq!(opus.ttfb_samples, 2);
+        assert_eq!(opus.ttfb_p50_ms, Some(900));
+        assert_eq!(opus.ttfb_p90_ms, Some(1_500));
+        assert_eq!(stats[1].ttfb_p50_ms, None);
+
+        assert!(latency_stats(&requests, 11, None).is_empty());
+
+        let mut requests = requests;
+        requests[0].sidecar_version = Some("6.8.0".to_string());
+        let by_version = latency_stats(&requests, 0, Some(VersionDimension::Sidecar));
+        let rows: Vec<(&str, Option<&str>, u64)> = by_version
+            .iter()
+            .map(|s| (s.model.as_str(), s.version.as_deref(), s.requests))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("opus", Some("unknown"), 2),
+                ("haiku", Some("unknown"), 1),
+                ("opus", Some("6.8.0"), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn median_ttfb_needs_enough_samples() {
+        let mut requests = vec![
+            request("opus", 200, 60_000, Some(700)),
+            request("opus", 200, 60_000, Some(9_000)),
+        ];
+        assert_eq!(median_ttfb(&requests), None);
+        requests.push(request("opus", 200, 60_000, Some(10_000)));
+        assert_eq!(median_ttfb(&requests), Some(9_000));
+    }
+}
@@ -0,0 +1,127 @@
+//! The live request feed shown by the request monitor.
+//!
+//! Every processed request lands in a short in-memory buffer the UI can
+//! snapshot on mount. While the feed is paused, requests are still recorded
+//! to history but not emitted; up to `PAUSED_CAPACITY` of them are held back
+//! and sent as one catch-up batch on resume, along with how many were missed.
+
+use std::collections::VecDeque;
+
+use crate::types::RequestLog;
+
+/// Recent requests kept for `snapshot_live_feed`
+pub(crate) const RECENT_CAPACITY: usize = 200;
+
+/// Requests held back while paused; older ones are only counted
+pub(crate) const PAUSED_CAPACITY: usize = 500;
+
+#[derive(Default)]
+pub struct LiveFeed {
+    recent: VecDeque<RequestLog>,
+    paused: bool,
+    held: VecDeque<RequestLog>,
+    missed: usize,
+}
+
+/// What to emit when the feed resumes
+#[derive(Debug)]
+pub struct Resumed {
+    pub batch: Vec<RequestLog>,
+    pub missed: usize,
+}
+
+impl LiveFeed {
+    /// Note a processed batch; returns it when it should be emitted now
+    pub fn record<'a>(&mut self, batch: &'a [RequestLog]) -> Option<&'a [RequestLog]> {
+        push_bounded(&mut self.recent, batch, RECENT_CAPACITY);
+        if !self.paused {
+            return Some(batch);
+        }
+        push_bounded(&mut self.held, batch, PAUSED_CAPACITY);
+        self.missed += batch.len();
+        None
+    }
+
+    /// Pause or resume; resuming hands back what was held while paused
+    pub fn set_paused(&mut self, paused: bool) -> Option<Resumed> {
+        let was_paused = std::mem::replace(&mut self.paused, paused);
+        if paused || !was_paused {
+            return None;
+        }
+        Some(Resumed {
+            batch: self.held.drain(..).collect(),
+            missed: std::mem::take(&mut self.missed),
+        })
+    }
+
+    /// Recent requests, oldest first, whether or not the feed is paused
+    pub fn snapshot(&self) -> Vec<RequestLog> {
+        self.recent.iter().cloned().collect()
+    }
+}
+
+fn push_bounded(buffer: &mut VecDeque<RequestLog>, batch: &[RequestLog], capacity: usize) {
+    buffer.extend(batch.iter().cloned());
+    let excess = buffer.len().saturating_sub(capacity);
+    buffer.drain(..excess);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(timestamp: u64) -> RequestLog {
+        RequestLog {
+            id: format!("req_{}", timestamp),
+            timestamp,
+            provider: "claude".to_string(),
+            model: "claude-sonnet-4-5".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status: 200,
+            duration_ms: 900,
+            ttfb_ms: None,
+            tokens_in: None,
+            tokens_out: None,
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    fn timestamps(logs: &[RequestLog]) -> Vec<u64> {
+        logs.iter().map(|r| r.timestamp).collect()
+    }
+
+    #[test]
+    fn paused_feed_holds_requests_until_resumed() {
+        let mut feed = LiveFeed::default();
+        assert!(feed.record(&[request(1)]).is_some());
+        assert!(feed.set_paused(false).is_none());
+
+        assert!(feed.set_paused(true).is_none());
+        let burst: Vec<RequestLog> = (2..PAUSED_CAPACITY as u64 + 12).map(request).collect();
+        assert!(feed.record(&burst).is_none());
+        // The snapshot keeps up while paused
+        let snapshot = feed.snapshot();
+        assert_eq!(snapshot.len(), RECENT_CAPACITY);
+        assert_eq!(
+            snapshot.last().unwrap().timestamp,
+            PAUSED_CAPACITY as u64 + 11
+        );
+
+        let resumed = feed.set_paused(false).unwrap();
+        assert_eq!(resumed.missed, PAUSED_CAPACITY + 10);
+        assert_eq!(resumed.batch.len(), PAUSED_CAPACITY);
+        assert_eq!(resumed.batch[0].timestamp, 12);
+        assert_eq!(timestamps(feed.record(&[request(9_999)]).unwrap()), [9_999]);
+        assert!(feed.set_paused(false).is_none());
+    }
+}
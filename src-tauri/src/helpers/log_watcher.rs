@@ -3,18 +3,57 @@
 use regex::Regex;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 
+use crate::commands::onboarding::mark_onboarding_step;
+use crate::helpers::clients::ClientTracker;
+use crate::helpers::cost_ledger;
 use crate::helpers::history::{
-    load_aggregate, load_request_history, save_aggregate, save_request_history, update_model_stats,
-    update_provider_stats, update_timeseries,
+    load_aggregate, save_aggregate, update_model_stats, update_provider_stats, update_timeseries,
+};
+use crate::helpers::history_store::HistoryStore;
+use crate::helpers::proxy_errors::error_from_request;
+use crate::helpers::request_tags;
+use crate::helpers::usage_day::{self, UsageTimezone};
+use crate::helpers::versions;
+use crate::notifications;
+use crate::state::AppState;
+use crate::types::{
+    Aggregate, AttemptInfo, QuotaSwitchEvent, RequestHistory, RequestLog, STEP_REQUEST_OBSERVED,
 };
-use crate::types::RequestLog;
 use crate::utils::{
     detect_provider_from_model, detect_provider_from_path, extract_model_from_path,
 };
+use crate::webhooks;
+
+/// Parsed requests waiting for the consumer. When it falls this far behind,
+/// new requests are dropped rather than stalling the log reader.
+pub(crate) const REQUEST_QUEUE_CAPACITY: usize = 1024;
+
+/// Longest a parsed request waits before it is emitted and persisted
+pub(crate) const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Quota switches kept in history
+const MAX_QUOTA_EVENTS: usize = 1000;
+
+/// Requests whose attempts are tracked at once; unfinished ones are dropped
+/// beyond this
+const MAX_TRACKED_ATTEMPTS: usize = 1000;
+
+/// Requests whose time to first token is tracked at once; unfinished ones
+/// are dropped beyond this
+const MAX_TRACKED_TTFB: usize = 1000;
+
+/// Throughput of the request pipeline, shown in the log diagnostics
+#[derive(Default)]
+pub struct RequestLogCounters {
+    pub processed: AtomicU64,
+    pub dropped: AtomicU64,
+}
 
 // Parse duration string to milliseconds
 fn parse_duration(duration_str: &str) -> u64 {
@@ -53,6 +92,276 @@ fn extract_timestamp_from_line(line: &str) -> Option<u64> {
     None
 }
 
+// Timestamp with sub-second precision, e.g. 2025-12-24 15:14:21.374. Lines
+// with whole seconds only give None: too coarse to time a first token.
+fn extract_precise_timestamp(line: &str) -> Option<u64> {
+    lazy_static::lazy_static! {
+        static ref PRECISE_TS_REGEX: Regex = Regex::new(
+            r#"(\d{4}-\d{2}-\d{2})[\sT](\d{2}:\d{2}:\d{2}\.\d{1,9})"#
+        ).unwrap();
+    }
+    let caps = PRECISE_TS_REGEX.captures(line)?;
+    let datetime_str = format!("{} {}", caps.get(1)?.as_str(), caps.get(2)?.as_str());
+    chrono::NaiveDateTime::parse_from_str(&datetime_str, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()?
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .map(|dt| dt.timestamp_millis() as u64)
+}
+
+// Detect request start/completion lines carrying a CLIProxyAPI request id.
+// Start:    | f803bb77 | Use OAuth user@email.com for model claude-opus-4-5-thinking
+// Complete: | f803bb77 | 200 | 12.453s | 127.0.0.1 | POST "/v1/messages"
+// Returns (true, id) for a start and (false, id) for a completion.
+fn parse_request_lifecycle(line: &str) -> Option<(bool, String)> {
+    lazy_static::lazy_static! {
+        static ref START_REGEX: Regex = Regex::new(
+            r#"\|\s+([a-f0-9]{8})\s+\|.*for model\s+\S+"#
+        ).unwrap();
+        static ref COMPLETE_REGEX: Regex = Regex::new(
+            r#"\|\s+([a-f0-9]{8})\s+\|\s+\d{3}\s+\|\s+\S+\s+\|"#
+        ).unwrap();
+    }
+    if let Some(caps) = START_REGEX.captures(line) {
+        return Some((true, caps.get(1)?.as_str().to_string()));
+    }
+    let caps = COMPLETE_REGEX.captures(line)?;
+    Some((false, caps.get(1)?.as_str().to_string()))
+}
+
+/// Attempts seen so far per request id, so a request the proxy retried ends
+/// up as one row listing every attempt
+#[derive(Default)]
+pub(crate) struct RetryTracker {
+    attempts: std::collections::HashMap<String, Vec<AttemptInfo>>,
+}
+
+impl RetryTracker {
+    // Each credential pick starts an attempt; an error line naming a status
+    // ends it. The wording varies between releases, so only the request id,
+    // "for model" or a status are required.
+    // | f803bb77 | Use OAuth user@email.com for model claude-opus-4-5
+    // | f803bb77 | Use API key sk-...a1b2 for model gpt-5 (provider=codex)
+    // | f803bb77 | upstream request failed, status: 429, retrying
+    fn observe(&mut self, line: &str) {
+        lazy_static::lazy_static! {
+            static ref START_REGEX: Regex = Regex::new(
+                r#"\|\s+([a-f0-9]{8})\s+\|.*for model\s+(\S+)"#
+            ).unwrap();
+            static ref FAILURE_REGEX: Regex = Regex::new(
+                r#"\|\s+([a-f0-9]{8})\s+\|.*?(?i:status(?:\s+code)?)[=:\s]+(\d{3})\b"#
+            ).unwrap();
+            static ref EMAIL_REGEX: Regex = Regex::new(
+                r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+"
+            ).unwrap();
+            static ref KEY_REGEX: Regex = Regex::new(r"(?i)\bkey\s+(\S+)\s+for model").unwrap();
+            static ref PROVIDER_REGEX: Regex = Regex::new(r"(?i)\bprovider[=:]\s*([\w-]+)").unwrap();
+        }
+
+        if let Some(caps) = START_REGEX.captures(line) {
+            let (Some(id), Some(model)) = (caps.get(1), caps.get(2)) else {
+                return;
+            };
+            let model_provider = detect_provider_from_model(model.as_str());
+            let provider = PROVIDER_REGEX
+                .captures(line)
+                .and_then(|c| c.get(1))
+                .map(|m| m.as_str().to_string())
+                .or_else(|| (model_provider != "unknown").then_some(model_provider));
+            let account = EMAIL_REGEX
+                .find(line)
+                .map(|m| m.as_str().to_string())
+                .or_else(|| {
+                    KEY_REGEX
+                        .captures(line)
+                        .and_then(|c| c.get(1))
+                        .map(|m| m.as_str().to_string())
+                });
+            if self.attempts.len() >= MAX_TRACKED_ATTEMPTS {
+                self.attempts.clear();
+            }
+            self.attempts
+                .entry(id.as_str().to_string())
+                .or_default()
+                .push(AttemptInfo {
+                    provider,
+                    account,
+                    status: None,
+                });
+            return;
+        }
+
+        if let Some(caps) = FAILURE_REGEX.captures(line) {
+            let (Some(id), Some(status)) = (caps.get(1), caps.get(2)) else {
+                return;
+            };
+            let Ok(status) = status.as_str().parse::<u16>() else {
+                return;
+            };
+            if status < 400 {
+                return;
+            }
+            let attempts = self.attempts.entry(id.as_str().to_string()).or_default();
+            match attempts.last_mut() {
+                Some(last) if last.status.is_none() => last.status = Some(status),
+                _ => attempts.push(AttemptInfo {
+                    status: Some(status),
+                    ..AttemptInfo::default()
+                }),
+            }
+        }
+    }
+
+    // Attribute a completed request to the account of its last attempt, and
+    // attach the attempts when there was more than one
+    fn finish(&mut self, request_id: &str, request: &mut RequestLog) {
+        let Some(mut attempts) = self.attempts.remove(request_id) else {
+            return;
+        };
+        request.account = attempts.last().and_then(|a| a.account.clone());
+        if attempts.len() < 2 {
+            return;
+        }
+        if let Some(last) = attempts.last_mut() {
+            last.status = Some(request.status);
+        }
+        request.retries = (attempts.len() - 1).min(u8::MAX as usize) as u8;
+        request.attempts = attempts;
+    }
+}
+
+/// Time to first token per request id: from the first credential pick to
+/// the first chunk the proxy streams back
+#[derive(Default)]
+pub(crate) struct TtfbTracker {
+    // Start time when the start line had a precise timestamp
+    started: std::collections::HashMap<String, Option<u64>>,
+    ttfb: std::collections::HashMap<String, u64>,
+}
+
+impl TtfbTracker {
+    // A first-chunk line either states the delay or is timed against the
+    // start line; only the first one per request counts.
+    // | f803bb77 | Use OAuth user@email.com for model claude-opus-4-5
+    // | f803bb77 | first chunk received after 812ms
+    // 2025-12-24 15:14:22.186 | f803bb77 | streaming: first token sent
+    fn observe(&mut self, line: &str) {
+        lazy_static::lazy_static! {
+            static ref START_REGEX: Regex = Regex::new(
+                r#"\|\s+([a-f0-9]{8})\s+\|.*for model\s+\S+"#
+            ).unwrap();
+            static ref FIRST_CHUNK_REGEX: Regex = Regex::new(
+                r#"\|\s+([a-f0-9]{8})\s+\|.*?(?i:\bfirst\s+(?:stream(?:ing)?\s+)?(?:chunk|token|byte)|\bttf[bt]\b)"#
+            ).unwrap();
+            static ref DELAY_REGEX: Regex = Regex::new(
+                r#"(?i)\b(?:ttf[bt]|after|in|took)[=:\s]+(\d+(?:\.\d+)?m?s)\b"#
+            ).unwrap();
+        }
+
+        if let Some(id) = START_REGEX.captures(line).and_then(|c| c.get(1)) {
+            if self.started.len() >= MAX_TRACKED_TTFB {
+                self.started.clear();
+                self.ttfb.clear();
+            }
+            // Retries keep the first start: the client has been waiting since
+            self.started
+                .entry(id.as_str().to_string())
+                .or_insert_with(|| extract_precise_timestamp(line));
+            return;
+        }
+
+        let Some(id) = FIRST_CHUNK_REGEX.captures(line).and_then(|c| c.get(1)) else {
+            return;
+        };
+        let id = id.as_str();
+        if self.ttfb.contains_key(id) {
+            return;
+        }
+        let stated = DELAY_REGEX
+            .captures(line)
+            .and_then(|c| c.get(1))
+            .map(|d| parse_duration(d.as_str()));
+        let timed = || {
+            let start = (*self.started.get(id)?)?;
+            extract_precise_timestamp(line)?.checked_sub(start)
+        };
+        if let Some(ttfb) = stated.or_else(timed) {
+            self.ttfb.insert(id.to_string(), ttfb);
+        }
+    }
+
+    fn finish(&mut self, request_id: &str, request: &mut RequestLog) {
+        self.started.remove(request_id);
+        request.ttfb_ms = self.ttfb.remove(request_id);
+    }
+}
+
+// Detect CLIProxyAPI moving on after a quota error, e.g.
+// [2025-12-04 20:51:42] [warn] gemini-cli user@gmail.com: quota exceeded on project alpha-1, switching to project beta-2
+// [2025-12-04 20:51:42] [warn] quota exceeded for gemini-2.5-pro, switching to preview model gemini-2.5-pro-preview
+// The wording varies between releases, so only "quota", "switch" and a
+// target are required; provider, account and origin are filled in when present.
+fn parse_quota_switch(line: &str) -> Option<QuotaSwitchEvent> {
+    lazy_static::lazy_static! {
+        static ref QUOTA_REGEX: Regex = Regex::new(r"(?i)quota.*\bswitch").unwrap();
+        static ref TO_REGEX: Regex = Regex::new(
+            r#"(?i)\bswitch(?:ing|ed)?\b.*?\bto\s+(?:(preview\s+model|model|project)\s+)?["']?([\w.@:/-]+)"#
+        ).unwrap();
+        static ref FROM_REGEX: Regex = Regex::new(
+            r#"(?i)\b(?:from|on|for)\s+(?:(?:project|model)\s+)?["']?([\w.@:/-]+?)["']?[,;]?\s"#
+        ).unwrap();
+        static ref EMAIL_REGEX: Regex = Regex::new(
+            r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+"
+        ).unwrap();
+    }
+    if !QUOTA_REGEX.is_match(line) {
+        return None;
+    }
+    let to_caps = TO_REGEX.captures(line)?;
+    let target = to_caps.get(2)?.as_str().trim_end_matches('.');
+    let kind = match to_caps.get(1).map(|m| m.as_str().to_lowercase()) {
+        Some(k) if k.ends_with("model") => "model",
+        Some(_) => "project",
+        None if line.to_lowercase().contains("preview") => "model",
+        None => "project",
+    };
+    let switch_at = to_caps.get(0)?.start();
+    let from = FROM_REGEX
+        .captures(&line[..switch_at])
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string());
+
+    let lower = line.to_lowercase();
+    // Quota switching is a Gemini CLI feature unless the line says otherwise
+    let provider = ["antigravity", "vertex", "claude", "codex", "qwen"]
+        .into_iter()
+        .find(|p| lower.contains(p))
+        .unwrap_or("gemini");
+
+    Some(QuotaSwitchEvent {
+        timestamp: extract_timestamp_from_line(line)
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() as u64),
+        provider: provider.to_string(),
+        account: EMAIL_REGEX.find(line).map(|m| m.as_str().to_string()),
+        kind: kind.to_string(),
+        from,
+        to: target.to_string(),
+    })
+}
+
+// Only track actual API calls: OpenAI chat/completions/responses, Anthropic
+// messages, Gemini-native generateContent (including Gemini CLI's
+// v1internal routes) and anything Amp sends through /api/provider/<name>/
+fn is_trackable(line: &str) -> bool {
+    line.contains("/chat/completions")
+        || line.contains("/v1/messages")
+        || line.contains("/completions")
+        || line.contains("/v1/responses")
+        || line.contains(":generateContent")
+        || line.contains(":streamGenerateContent")
+        || line.contains("/api/provider/")
+}
+
 // Parse a GIN log line and extract request information
 // Format: [GIN] 2025/12/04 - 20:51:48 | 200 | 6.656s | ::1 | POST "/api/provider/anthropic/v1/messages"
 // Also handles new format: | request_id | 200 | 6.656s | ip | POST "/path"
@@ -107,15 +416,7 @@ fn parse_gin_log_line(
         return None;
     }
 
-    // Only track actual API calls (chat completions, messages, etc.)
-    let is_trackable = line.contains("/chat/completions")
-        || line.contains("/v1/messages")
-        || line.contains("/completions")
-        || line.contains("/v1beta")
-        || line.contains(":generateContent")
-        || line.contains(":streamGenerateContent");
-
-    if !is_trackable {
+    if !is_trackable(line) {
         return None;
     }
 
@@ -179,12 +480,23 @@ fn parse_gin_log_line(
             provider,
             model,
             method,
-            path,
+            // Gemini-style ?key= parameters must not reach history or events
+            path: crate::redact::redact(&path),
             status,
             duration_ms,
+            ttfb_ms: None,
             tokens_in: None,
             tokens_out: None,
             tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
         });
     }
 
@@ -243,26 +555,47 @@ fn parse_gin_log_line(
         provider,
         model,
         method,
-        path,
+        path: crate::redact::redact(&path),
         status,
         duration_ms,
+        ttfb_ms: None,
         tokens_in: None,     // Not available from GIN logs
         tokens_out: None,    // Not available from GIN logs
         tokens_cached: None, // Not available from GIN logs
+        account: None,
+        retries: 0,
+        attempts: Vec::new(),
+        synthetic: false,
+        session_id: None,
+        tags: Vec::new(),
+        client: None,
+        app_version: None,
+        sidecar_version: None,
     })
 }
 
-// Start watching the proxy log file for new entries
+// Start watching the proxy log file for new entries. Parsed requests go
+// through a bounded queue to a consumer thread that batches them; without an
+// app handle (headless mode) they are only persisted to history.
 pub(crate) fn start_log_watcher(
-    app_handle: tauri::AppHandle,
+    app_handle: Option<tauri::AppHandle>,
     log_path: std::path::PathBuf,
     running: Arc<AtomicBool>,
     request_counter: Arc<AtomicU64>,
+    counters: Arc<RequestLogCounters>,
+    history: Arc<HistoryStore>,
 ) {
+    let (sender, receiver) = mpsc::sync_channel(REQUEST_QUEUE_CAPACITY);
+    let quota_history = history.clone();
+    spawn_request_consumer(app_handle.clone(), receiver, counters.clone(), history);
+
     std::thread::spawn(move || {
         // Model cache to associate request IDs with model names from DEBUG lines
         let model_cache: std::sync::RwLock<std::collections::HashMap<String, String>> =
             std::sync::RwLock::new(std::collections::HashMap::new());
+        let mut retry_tracker = RetryTracker::default();
+        let mut ttfb_tracker = TtfbTracker::default();
+        let mut client_tracker = ClientTracker::default();
 
         // Wait for log file to exist
         let mut attempts = 0;
@@ -327,82 +660,51 @@ pub(crate) fn start_log_watcher(
             // Read new lines
             let mut line = String::new();
             while reader.read_line(&mut line).unwrap_or(0) > 0 {
-                if let Some(request_log) = parse_gin_log_line(&line, &request_counter, &model_cache)
-                {
-                    // Emit to frontend for live display
-                    let _ = app_handle.emit("request-log", request_log.clone());
-
-                    // Persist to history (without token data for now)
-                    let mut history = load_request_history();
-
-                    // Check for duplicate by timestamp and path
-                    let is_duplicate = history.requests.iter().any(|r| {
-                        r.timestamp == request_log.timestamp && r.path == request_log.path
-                    });
-
-                    if !is_duplicate {
-                        // Load aggregate for cumulative stats
-                        let mut agg = load_aggregate();
-
-                        // Update aggregate counters
-                        agg.total_requests += 1;
-                        if request_log.status < 400 {
-                            agg.total_success_count += 1;
+                // Track requests in flight for the quit confirmation
+                let lifecycle = parse_request_lifecycle(&line);
+                if let Some((started, request_id)) = &lifecycle {
+                    if let Some(state) = app_handle.as_ref().and_then(|a| a.try_state::<AppState>())
+                    {
+                        let mut in_flight = state.in_flight.lock();
+                        if *started {
+                            in_flight.start(request_id);
                         } else {
-                            agg.total_failure_count += 1;
-                        }
-                        agg.total_tokens_in += request_log.tokens_in.unwrap_or(0) as u64;
-                        agg.total_tokens_out += request_log.tokens_out.unwrap_or(0) as u64;
-                        agg.total_tokens_cached += request_log.tokens_cached.unwrap_or(0) as u64;
-
-                        // Update time-series (today's date and current hour)
-                        let now = chrono::Local::now();
-                        let today = now.format("%Y-%m-%d").to_string();
-                        let hour_label = now.format("%Y-%m-%dT%H").to_string();
-
-                        // Update daily data
-                        update_timeseries(&mut agg.requests_by_day, &today, 1);
-                        let tokens = (request_log.tokens_in.unwrap_or(0)
-                            + request_log.tokens_out.unwrap_or(0))
-                            as u64;
-                        update_timeseries(&mut agg.tokens_by_day, &today, tokens);
-
-                        // Update hourly data (for Activity Patterns heatmap)
-                        update_timeseries(&mut agg.requests_by_hour, &hour_label, 1);
-                        update_timeseries(&mut agg.tokens_by_hour, &hour_label, tokens);
-
-                        // Trim hourly data to keep last 7 days worth (168 hours)
-                        const MAX_HOURLY_POINTS: usize = 168;
-                        if agg.requests_by_hour.len() > MAX_HOURLY_POINTS {
-                            agg.requests_by_hour = agg
-                                .requests_by_hour
-                                .split_off(agg.requests_by_hour.len() - MAX_HOURLY_POINTS);
+                            in_flight.complete(request_id);
                         }
-                        if agg.tokens_by_hour.len() > MAX_HOURLY_POINTS {
-                            agg.tokens_by_hour = agg
-                                .tokens_by_hour
-                                .split_off(agg.tokens_by_hour.len() - MAX_HOURLY_POINTS);
-                        }
-
-                        // Update model/provider stats
-                        update_model_stats(&mut agg, &request_log);
-                        update_provider_stats(&mut agg, &request_log);
+                    }
+                }
+                let completed = match &lifecycle {
+                    Some((false, request_id)) => Some(request_id.as_str()),
+                    _ => None,
+                };
+                if completed.is_none() {
+                    retry_tracker.observe(&line);
+                    ttfb_tracker.observe(&line);
+                    client_tracker.observe(&line);
+                }
 
-                        // Update history (keep only last 500 for UI display)
-                        history.requests.push(request_log);
-                        if history.requests.len() > 500 {
-                            history.requests =
-                                history.requests.split_off(history.requests.len() - 500);
-                        }
+                if let Some(event) = parse_quota_switch(&line) {
+                    record_quota_switch(app_handle.as_ref(), &quota_history, event);
+                }
 
-                        // Save both files
-                        if let Err(e) = save_request_history(&history) {
-                            eprintln!("[LogWatcher] Failed to save history: {}", e);
-                        }
-                        if let Err(e) = save_aggregate(&agg) {
-                            eprintln!("[LogWatcher] Failed to save aggregate: {}", e);
-                        }
+                if let Some(mut request_log) =
+                    parse_gin_log_line(&line, &request_counter, &model_cache)
+                {
+                    if let Some(request_id) = completed {
+                        retry_tracker.finish(request_id, &mut request_log);
+                        ttfb_tracker.finish(request_id, &mut request_log);
                     }
+                    let state = app_handle.as_ref().and_then(|a| a.try_state::<AppState>());
+                    let client_keys = state
+                        .as_ref()
+                        .map(|state| state.config.lock().client_keys.clone())
+                        .unwrap_or_default();
+                    client_tracker.finish(completed, &line, &mut request_log, &client_keys);
+                    let sidecar_version = state
+                        .as_ref()
+                        .and_then(|state| state.proxy_status.lock().sidecar_version.clone());
+                    versions::stamp(&mut request_log, sidecar_version.as_deref());
+                    queue_request(&sender, request_log, &counters);
                 }
                 line.clear();
             }
@@ -413,3 +715,525 @@ pub(crate) fn start_log_watcher(
         println!("[LogWatcher] Stopped watching");
     });
 }
+
+// Keep a quota switch in history and tell the UI; the first switch of the
+// usage day also raises a (rate-limited) notification
+fn record_quota_switch(
+    app_handle: Option<&tauri::AppHandle>,
+    history: &Arc<HistoryStore>,
+    event: QuotaSwitchEvent,
+) {
+    println!(
+        "[LogWatcher] {} quota exceeded, switched to {} {}",
+        event.provider, event.kind, event.to
+    );
+    let timezone = usage_day::current();
+    let day = usage_day::day_label_ms(timezone, event.timestamp);
+    let first_today = history.update(|history| {
+        let first = !history
+            .quota_events
+            .iter()
+            .any(|e| usage_day::day_label_ms(timezone, e.timestamp) == day);
+        history.quota_events.push(event.clone());
+        let excess = history.quota_events.len().saturating_sub(MAX_QUOTA_EVENTS);
+        history.quota_events.drain(..excess);
+        first
+    });
+
+    if let Some(app_handle) = app_handle {
+        let _ = app_handle.emit("quota-switch", &event);
+        if first_today {
+            notifications::notify_quota_switch(app_handle, &event);
+        }
+    }
+}
+
+// Hand a parsed request to the consumer without ever blocking the reader
+fn queue_request(sender: &SyncSender<RequestLog>, log: RequestLog, counters: &RequestLogCounters) {
+    match sender.try_send(log) {
+        Ok(()) => {}
+        Err(TrySendError::Full(_)) => {
+            let dropped = counters.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped.is_power_of_two() {
+                eprintln!(
+                    "[LogWatcher] Request queue full, {} requests dropped so far",
+                    dropped
+                );
+            }
+        }
+        // Consumer gone: the watcher is shutting down
+        Err(TrySendError::Disconnected(_)) => {}
+    }
+}
+
+// Wait for a request, then collect whatever else arrives within BATCH_INTERVAL.
+// Returns None once the reader has stopped and the queue is drained.
+fn next_batch(receiver: &Receiver<RequestLog>) -> Option<Vec<RequestLog>> {
+    let mut batch = vec![receiver.recv().ok()?];
+    let deadline = Instant::now() + BATCH_INTERVAL;
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match receiver.recv_timeout(remaining) {
+            Ok(log) => batch.push(log),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    Some(batch)
+}
+
+fn spawn_request_consumer(
+    app_handle: Option<tauri::AppHandle>,
+    receiver: Receiver<RequestLog>,
+    counters: Arc<RequestLogCounters>,
+    history: Arc<HistoryStore>,
+) {
+    std::thread::spawn(move || {
+        while let Some(batch) = next_batch(&receiver) {
+            process_batch(app_handle.as_ref(), &history, &batch);
+            counters
+                .processed
+                .fetch_add(batch.len() as u64, Ordering::Relaxed);
+        }
+    });
+}
+
+// Metrics, error tracking and notifications for one request
+fn observe_request(app_handle: &tauri::AppHandle, request_log: &RequestLog) {
+    if let Some(state) = app_handle.try_state::<AppState>() {
+        state.metrics.lock().record(request_log);
+    }
+
+    // Keep failed requests for the tray's "Recent Errors" submenu
+    if let Some(error) = error_from_request(request_log) {
+        let error_json = serde_json::to_value(&error).unwrap_or_default();
+        webhooks::emit(
+            app_handle,
+            "request-failed",
+            &format!("{} request failed: {}", error.provider, error.message),
+            error_json,
+        );
+        match error.kind.as_str() {
+            "auth" => notifications::notify_auth_expired(app_handle, &error.provider),
+            "rate_limit" => notifications::notify_rate_limit(app_handle, &error.provider, true),
+            _ => {}
+        }
+        if let Some(state) = app_handle.try_state::<AppState>() {
+            state.recent_errors.lock().push(error);
+        }
+    } else {
+        notifications::notify_rate_limit(app_handle, &request_log.provider, false);
+    }
+}
+
+// Add a batch to the in-memory history and write the aggregate once, then
+// tell the UI. The history store flushes to disk on its own schedule.
+pub(crate) fn process_batch(
+    app_handle: Option<&tauri::AppHandle>,
+    history: &Arc<HistoryStore>,
+    batch: &[RequestLog],
+) {
+    if let Some(app_handle) = app_handle {
+        for request_log in batch.iter().filter(|r| !r.synthetic) {
+            observe_request(app_handle, request_log);
+        }
+        crate::request_watches::check(app_handle, batch);
+        crate::rate_limits::check(app_handle, batch);
+        crate::session_journal::record_requests(app_handle, batch);
+        crate::idle_watchdog::record_requests(app_handle, batch);
+    }
+
+    let mut agg = load_aggregate();
+    let added = history.update(|history| {
+        merge_requests(
+            history,
+            &mut agg,
+            batch,
+            usage_day::current(),
+            chrono::Utc::now(),
+        )
+    });
+    if added > 0 {
+        if let Err(e) = save_aggregate(&agg) {
+            eprintln!("[LogWatcher] Failed to save aggregate: {}", e);
+        }
+    }
+
+    if let Some(app_handle) = app_handle {
+        // Held back while the user has the live feed paused
+        let live = match app_handle.try_state::<AppState>() {
+            Some(state) => state.live_feed.lock().record(batch),
+            None => Some(batch),
+        };
+        if let Some(live) = live {
+            emit_request_logs(app_handle, live);
+        }
+        if added > 0 {
+            mark_onboarding_step(app_handle, STEP_REQUEST_OBSERVED);
+            crate::model_caps::record(app_handle, batch);
+            crate::usage_anomaly::check(app_handle, &agg.cost_ledger);
+        }
+    }
+}
+
+// Send requests to the frontend for live display
+pub(crate) fn emit_request_logs(app_handle: &tauri::AppHandle, batch: &[RequestLog]) {
+    let legacy_events = app_handle
+        .try_state::<AppState>()
+        .is_some_and(|state| state.config.lock().legacy_request_log_events);
+    if legacy_events {
+        for request_log in batch {
+            let _ = app_handle.emit("request-log", request_log);
+        }
+    }
+    let _ = app_handle.emit("request-log-batch", batch);
+}
+
+// Add new requests to the history and aggregate; returns how many real ones
+// were new. Synthetic (demo) requests only go into the history.
+fn merge_requests(
+    history: &mut RequestHistory,
+    agg: &mut Aggregate,
+    batch: &[RequestLog],
+    timezone: UsageTimezone,
+    now: chrono::DateTime<chrono::Utc>,
+) -> usize {
+    let today = usage_day::day_label(timezone, now);
+    let hour_label = usage_day::hour_label(timezone, now);
+    let mut added = 0;
+
+    for request_log in batch {
+        // Check for duplicate by timestamp and path
+        let is_duplicate = history
+            .requests
+            .iter()
+            .any(|r| r.timestamp == request_log.timestamp && r.path == request_log.path);
+        if is_duplicate {
+            continue;
+        }
+        let mut request_log = request_log.clone();
+        let previous = history
+            .requests
+            .iter()
+            .rev()
+            .find(|r| r.synthetic == request_log.synthetic);
+        request_tags::assign_session(previous, &mut request_log);
+        let session_tags = request_log
+            .session_id
+            .as_ref()
+            .and_then(|id| agg.session_tags.get(id))
+            .filter(|_| request_log.tags.is_empty())
+            .cloned();
+        if let Some(tags) = session_tags {
+            request_tags::retag(agg, timezone, &mut request_log, tags);
+        }
+        if request_log.synthetic {
+            history.requests.push(request_log);
+            continue;
+        }
+
+        // Update aggregate counters
+        agg.total_requests += 1;
+        if request_log.status < 400 {
+            agg.total_success_count += 1;
+            if request_log.retries > 0 {
+                agg.total_retried_success_count += 1;
+            }
+        } else {
+            agg.total_failure_count += 1;
+        }
+        agg.total_tokens_in += request_log.tokens_in.unwrap_or(0) as u64;
+        agg.total_tokens_out += request_log.tokens_out.unwrap_or(0) as u64;
+        agg.total_tokens_cached += request_log.tokens_cached.unwrap_or(0) as u64;
+        cost_ledger::record_request(&mut agg.cost_ledger, &today, &request_log);
+        cost_ledger::record_hour(&mut agg.cost_ledger, &hour_label, &request_log);
+
+        // Daily data, and hourly data for the Activity Patterns heatmap
+        let tokens =
+            (request_log.tokens_in.unwrap_or(0) + request_log.tokens_out.unwrap_or(0)) as u64;
+        update_timeseries(&mut agg.requests_by_day, &today, 1);
+        update_timeseries(&mut agg.tokens_by_day, &today, tokens);
+        update_timeseries(&mut agg.requests_by_hour, &hour_label, 1);
+        update_timeseries(&mut agg.tokens_by_hour, &hour_label, tokens);
+        versions::record_day(&mut agg.versions_by_day, &today, &request_log);
+
+        update_model_stats(agg, &request_log);
+        update_provider_stats(agg, &request_log);
+
+        history.requests.push(request_log);
+        added += 1;
+    }
+    agg.total_cost_usd = cost_ledger::total_usd(&agg.cost_ledger);
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_request_lifecycle_lines() {
+        let start = "[2025-12-04 20:51:42] [debug] | f803bb77 | Use OAuth user@email.com for model claude-opus-4-5-thinking";
+        assert_eq!(
+            parse_request_lifecycle(start),
+            Some((true, "f803bb77".to_string()))
+        );
+        let complete =
+            "[2025-12-04 20:51:48] [info] | f803bb77 | 200 | 12.453s | 127.0.0.1 | POST \"/v1/messages\"";
+        assert_eq!(
+            parse_request_lifecycle(complete),
+            Some((false, "f803bb77".to_string()))
+        );
+        assert_eq!(parse_request_lifecycle("[info] server started"), None);
+    }
+
+    #[test]
+    fn parses_quota_switch_lines() {
+        let project = "[2025-12-04 20:51:42] [warn] gemini-cli user@gmail.com: quota exceeded on project alpha-1, switching to project beta-2";
+        let event = parse_quota_switch(project).unwrap();
+        assert_eq!(event.provider, "gemini");
+        assert_eq!(event.account.as_deref(), Some("user@gmail.com"));
+        assert_eq!(event.kind, "project");
+        assert_eq!(event.from.as_deref(), Some("alpha-1"));
+        assert_eq!(event.to, "beta-2");
+
+        let model = "[2025-12-04 20:51:42] [warn] quota exceeded for gemini-2.5-pro, switching to preview model gemini-2.5-pro-preview";
+        let event = parse_quota_switch(model).unwrap();
+        assert_eq!(event.kind, "model");
+        assert_eq!(event.account, None);
+        assert_eq!(event.from.as_deref(), Some("gemini-2.5-pro"));
+        assert_eq!(event.to, "gemini-2.5-pro-preview");
+
+        assert!(parse_quota_switch("[info] switching to project beta-2").is_none());
+        assert!(parse_quota_switch("[warn] quota exceeded for project alpha-1").is_none());
+    }
+
+    fn parse(line: &str) -> Option<RequestLog> {
+        let model_cache = std::sync::RwLock::new(std::collections::HashMap::new());
+        parse_gin_log_line(line, &AtomicU64::new(0), &model_cache)
+    }
+
+    #[test]
+    fn tracks_gemini_native_and_amp_requests() {
+        // Gemini CLI against the Gemini-native API
+        let gemini = parse("[2025-12-04 20:51:48] [info] | 3fa2c1d9 | 200 | 8.125s | 127.0.0.1 | POST \"/v1beta/models/gemini-2.5-pro:streamGenerateContent?alt=sse\"").unwrap();
+        assert_eq!(gemini.provider, "gemini");
+        assert_eq!(gemini.model, "gemini-2.5-pro");
+        assert_eq!(gemini.duration_ms, 8125);
+
+        // Gemini CLI's Code Assist route has no model in the path
+        let code_assist = parse("[2025-12-04 20:51:50] [info] | 9b01e7aa | 200 | 2.301s | 127.0.0.1 | POST \"/v1internal:generateContent\"").unwrap();
+        assert_eq!(code_assist.provider, "gemini");
+
+        // Amp routes name the upstream provider
+        let amp_openai = parse("[GIN] 2025/12/04 - 20:51:48 | 200 | 6.656s | ::1 | POST \"/api/provider/openai/v1/responses\"").unwrap();
+        assert_eq!(amp_openai.provider, "openai");
+        let amp_google = parse("[GIN] 2025/12/04 - 20:52:03 | 200 | 1.204s | ::1 | POST \"/api/provider/google/v1beta1/publishers/google/models/gemini-2.5-flash:generateContent\"").unwrap();
+        assert_eq!(amp_google.provider, "gemini");
+        assert_eq!(amp_google.model, "gemini-2.5-flash");
+        let amp_claude = parse("[GIN] 2025/12/04 - 20:52:09 | 200 | 12.453s | ::1 | POST \"/api/provider/anthropic/v1/messages\"").unwrap();
+        assert_eq!(amp_claude.provider, "claude");
+
+        // Model listings are not requests
+        assert!(
+            parse("[GIN] 2025/12/04 - 20:52:10 | 200 | 3ms | ::1 | GET \"/v1beta/models\"")
+                .is_none()
+        );
+        assert!(parse("[GIN] 2025/12/04 - 20:52:10 | 200 | 3ms | ::1 | GET \"/api/provider/openai/v1/models\"").is_none());
+    }
+
+    fn request(timestamp: u64, status: u16) -> RequestLog {
+        RequestLog {
+            id: format!("req_{}", timestamp),
+            timestamp,
+            provider: "claude".to_string(),
+            model: "claude-sonnet-4-5".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status,
+            duration_ms: 1200,
+            ttfb_ms: None,
+            tokens_in: None,
+            tokens_out: None,
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    #[test]
+    fn full_queue_drops_and_counts_instead_of_blocking() {
+        let (sender, receiver) = mpsc::sync_channel(2);
+        let counters = RequestLogCounters::default();
+        for timestamp in 0..5 {
+            queue_request(&sender, request(timestamp, 200), &counters);
+        }
+        assert_eq!(counters.dropped.load(Ordering::Relaxed), 3);
+
+        // Everything queued arrives in one batch, then the stream ends
+        drop(sender);
+        let batch = next_batch(&receiver).unwrap();
+        assert_eq!(
+            batch.iter().map(|r| r.timestamp).collect::<Vec<_>>(),
+            [0, 1]
+        );
+        assert!(next_batch(&receiver).is_none());
+    }
+
+    #[test]
+    fn merges_a_batch_in_one_pass() {
+        let mut history = RequestHistory {
+            requests: (0..500).map(|t| request(t, 200)).collect(),
+            ..RequestHistory::default()
+        };
+        let mut agg = Aggregate::default();
+        let batch = [
+            request(1000, 200),
+            request(1001, 429),
+            request(1000, 200),
+            request(3, 200),
+        ];
+
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-11T00:01:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let added = merge_requests(&mut history, &mut agg, &batch, UsageTimezone::Utc, now);
+        assert_eq!(added, 2);
+        assert_eq!(agg.total_requests, 2);
+        assert_eq!(agg.total_failure_count, 1);
+        assert_eq!(agg.requests_by_day.len(), 1);
+        assert_eq!(agg.requests_by_day[0].value, 2);
+        assert_eq!(agg.requests_by_day[0].label, "2026-03-11");
+        assert_eq!(agg.requests_by_hour[0].label, "2026-03-11T00");
+        // Trimming is left to the storage layer
+        assert_eq!(history.requests.len(), 502);
+        assert_eq!(history.requests.last().unwrap().timestamp, 1001);
+    }
+
+    #[test]
+    fn synthetic_requests_stay_out_of_the_aggregate() {
+        let mut history = RequestHistory::default();
+        let mut agg = Aggregate::default();
+        let demo = RequestLog {
+            synthetic: true,
+            ..request(2000, 500)
+        };
+        let now = chrono::Utc::now();
+        let added = merge_requests(
+            &mut history,
+            &mut agg,
+            &[demo, request(2001, 200)],
+            UsageTimezone::Utc,
+            now,
+        );
+        assert_eq!(added, 1);
+        assert_eq!(agg.total_requests, 1);
+        assert_eq!(agg.total_failure_count, 0);
+        assert_eq!(
+            agg.provider_stats.values().map(|s| s.requests).sum::<u64>(),
+            1
+        );
+        assert_eq!(history.requests.len(), 2);
+        assert!(history.requests[0].synthetic);
+    }
+
+    #[test]
+    fn retried_request_collapses_into_one_row() {
+        let lines = [
+            "[2026-03-11 09:14:02] [debug] | 5d0c7e21 | Use OAuth alice@example.com for model claude-sonnet-4-5",
+            "[2026-03-11 09:14:03] [warn] | 5d0c7e21 | upstream request failed, status: 429, retrying",
+            "[2026-03-11 09:14:04] [debug] | 5d0c7e21 | Use OAuth bob@example.com for model claude-sonnet-4-5",
+            "[2026-03-11 09:14:06] [warn] | 5d0c7e21 | upstream request failed, status: 529, retrying",
+            "[2026-03-11 09:14:07] [debug] | 5d0c7e21 | Use API key sk-...a1b2 for model claude-sonnet-4-5 (provider=claude-api)",
+            "[2026-03-11 09:14:12] [info] | 5d0c7e21 | 200 | 9.500s | 127.0.0.1 | POST \"/v1/messages\"",
+        ];
+        let mut tracker = RetryTracker::default();
+        let model_cache = std::sync::RwLock::new(std::collections::HashMap::new());
+        let counter = AtomicU64::new(0);
+        let mut requests = Vec::new();
+        for line in lines {
+            let completed = match parse_request_lifecycle(line) {
+                Some((false, id)) => Some(id),
+                _ => None,
+            };
+            if completed.is_none() {
+                tracker.observe(line);
+            }
+            if let Some(mut request) = parse_gin_log_line(line, &counter, &model_cache) {
+                if let Some(id) = &completed {
+                    tracker.finish(id, &mut request);
+                }
+                requests.push(request);
+            }
+        }
+
+        assert_eq!(requests.len(), 1);
+        let retried = &requests[0];
+        assert_eq!(retried.retries, 2);
+        assert_eq!(retried.attempts.len(), 3);
+        let statuses: Vec<Option<u16>> = retried.attempts.iter().map(|a| a.status).collect();
+        assert_eq!(statuses, [Some(429), Some(529), Some(200)]);
+        assert_eq!(
+            retried.attempts[0].account.as_deref(),
+            Some("alice@example.com")
+        );
+        assert_eq!(retried.attempts[0].provider.as_deref(), Some("claude"));
+        assert_eq!(retried.attempts[2].account.as_deref(), Some("sk-...a1b2"));
+        assert_eq!(retried.attempts[2].provider.as_deref(), Some("claude-api"));
+        // The row is attributed to the credential that finally served it
+        assert_eq!(retried.account.as_deref(), Some("sk-...a1b2"));
+
+        let mut history = RequestHistory::default();
+        let mut agg = Aggregate::default();
+        let now = chrono::Utc::now();
+        merge_requests(&mut history, &mut agg, &requests, UsageTimezone::Utc, now);
+        assert_eq!(history.requests.len(), 1);
+        assert_eq!(agg.total_success_count, 1);
+        assert_eq!(agg.total_retried_success_count, 1);
+        assert_eq!(agg.total_failure_count, 0);
+
+        // A request served on the first try carries no attempts
+        tracker.observe("| 77aa0b3c | Use OAuth alice@example.com for model gpt-5");
+        let mut single = request(0, 200);
+        tracker.finish("77aa0b3c", &mut single);
+        assert_eq!((single.retries, single.attempts.len()), (0, 0));
+        assert_eq!(single.account.as_deref(), Some("alice@example.com"));
+    }
+
+    #[test]
+    fn times_the_first_streamed_chunk() {
+        let mut tracker = TtfbTracker::default();
+        // Timed from precise line timestamps, from the first attempt on
+        tracker.observe("2026-03-11 09:14:02.250 [debug] | 5d0c7e21 | Use OAuth alice@example.com for model claude-sonnet-4-5");
+        tracker.observe("2026-03-11 09:14:03.100 [debug] | 5d0c7e21 | Use OAuth bob@example.com for model claude-sonnet-4-5");
+        tracker.observe("2026-03-11 09:14:03.912 [debug] | 5d0c7e21 | streaming: first chunk sent");
+        tracker.observe("2026-03-11 09:14:05.000 [debug] | 5d0c7e21 | streaming: first chunk sent");
+        let mut timed = request(0, 200);
+        tracker.finish("5d0c7e21", &mut timed);
+        assert_eq!(timed.ttfb_ms, Some(1_662));
+
+        // A stated delay wins over whole-second timestamps
+        tracker.observe("[2026-03-11 09:14:02] [debug] | 77aa0b3c | Use OAuth alice@example.com for model gpt-5");
+        tracker
+            .observe("[2026-03-11 09:14:03] [debug] | 77aa0b3c | first token received after 812ms");
+        let mut stated = request(0, 200);
+        tracker.finish("77aa0b3c", &mut stated);
+        assert_eq!(stated.ttfb_ms, Some(812));
+
+        // Whole seconds only, or no first-chunk line at all: unknown
+        tracker.observe("[2026-03-11 09:14:02] [debug] | 0b1c2d3e | Use OAuth alice@example.com for model gpt-5");
+        tracker.observe("[2026-03-11 09:14:04] [debug] | 0b1c2d3e | first chunk sent");
+        tracker.observe("[2026-03-11 09:14:02] [debug] | 9f8e7d6c | Use OAuth alice@example.com for model gpt-5");
+        for id in ["0b1c2d3e", "9f8e7d6c"] {
+            let mut unknown = request(0, 200);
+            tracker.finish(id, &mut unknown);
+            assert_eq!(unknown.ttfb_ms, None);
+        }
+        assert!(tracker.started.is_empty() && tracker.ttfb.is_empty());
+    }
+}
@@ -5,6 +5,7 @@ use crate::helpers::history::{
     load_request_history, save_aggregate, update_model_stats, update_provider_stats,
     update_timeseries,
 };
+use crate::helpers::usage_day;
 use crate::types::Aggregate;
 
 /// Migrate from old single-file format to split storage
@@ -40,8 +41,7 @@ pub(crate) fn migrate_to_split_storage() {
 
     // Build time-series from requests
     for req in &history.requests {
-        if let Some(dt) = chrono::DateTime::from_timestamp_millis(req.timestamp as i64) {
-            let day = dt.format("%Y-%m-%d").to_string();
+        if let Some(day) = usage_day::day_label_ms(usage_day::current(), req.timestamp) {
             update_timeseries(&mut agg.requests_by_day, &day, 1);
             let tokens = (req.tokens_in.unwrap_or(0) + req.tokens_out.unwrap_or(0)) as u64;
             update_timeseries(&mut agg.tokens_by_day, &day, tokens);
@@ -1,5 +1,43 @@
 //! Internal helper modules.
 
+pub mod agent_endpoints;
+pub mod aggregate_compaction;
+pub mod amp_pools;
+pub mod amp_setup;
+pub mod audit;
+pub mod autostart;
+pub mod captures;
+pub mod clients;
+pub mod cliproxy_config;
+pub mod cliproxy_import;
+pub mod config_writer;
+pub mod cost_ledger;
+pub mod credential_export;
+pub mod credential_meta;
+pub mod credential_refresh;
+pub mod demo;
+pub mod diagnostics;
+pub mod docker;
+pub mod env_conflicts;
 pub mod history;
+pub mod history_store;
+pub mod in_flight;
+pub mod latency;
+pub mod live_feed;
 pub mod log_watcher;
 pub mod migration;
+pub mod oauth_flows;
+pub mod paths;
+pub mod permissions;
+pub mod power;
+pub mod provider_checks;
+pub mod proxy_errors;
+pub mod recovery;
+pub mod request_tags;
+pub mod self_test;
+pub mod statusbar_plugin;
+pub mod storage;
+pub mod usage_day;
+pub mod versions;
+pub mod vertex;
+pub mod window_state;
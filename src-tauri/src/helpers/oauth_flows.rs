@@ -0,0 +1,155 @@
+//! OAuth flows in progress.
+//!
+//! Several providers can be connecting at once, so flows are keyed by the
+//! OAuth state string CLIProxyAPI hands out; finishing or cancelling one
+//! leaves the others alone. A flow is dropped at its deadline. Completed
+//! flows are kept until then too, so a late poll still sees the result. A
+//! flow started to sign an existing credential in again carries the file its
+//! result replaces.
+
+use std::collections::HashMap;
+
+use crate::helpers::credential_refresh::PendingReauth;
+use crate::types::{OAuthFlowStatus, OAuthState};
+
+/// How long a browser sign-in may take
+pub(crate) const FLOW_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+
+#[derive(Default)]
+pub struct OAuthFlows {
+    flows: HashMap<String, OAuthState>,
+    reauths: HashMap<String, PendingReauth>,
+}
+
+impl OAuthFlows {
+    /// Track a new flow; an empty state (login pages) can't be tracked
+    pub fn start(&mut self, provider: &str, state: &str, now: u64, timeout_ms: u64) {
+        self.prune(now);
+        if state.is_empty() {
+            return;
+        }
+        self.flows.insert(
+            state.to_string(),
+            OAuthState {
+                provider: provider.to_string(),
+                state: state.to_string(),
+                created_at: now,
+                expires_at: now + timeout_ms,
+                status: OAuthFlowStatus::Pending,
+            },
+        );
+    }
+
+    /// The flow for `state`, unless it expired or was cancelled
+    pub fn get(&mut self, state: &str, now: u64) -> Option<OAuthState> {
+        self.prune(now);
+        self.flows.get(state).cloned()
+    }
+
+    /// Move a flow on; returns it as updated, or None when it's gone
+    pub fn set_status(
+        &mut self,
+        state: &str,
+        status: OAuthFlowStatus,
+        now: u64,
+    ) -> Option<OAuthState> {
+        self.prune(now);
+        let flow = self.flows.get_mut(state)?;
+        flow.status = status;
+        Some(flow.clone())
+    }
+
+    /// The newest flow of `provider` whose callback arrived
+    pub fn awaiting_exchange(&mut self, provider: &str, now: u64) -> Option<String> {
+        self.prune(now);
+        self.flows
+            .values()
+            .filter(|f| f.provider == provider && f.status == OAuthFlowStatus::CallbackReceived)
+            .max_by_key(|f| f.created_at)
+            .map(|f| f.state.clone())
+    }
+
+    pub fn cancel(&mut self, state: &str) -> Option<OAuthState> {
+        self.reauths.remove(state);
+        self.flows.remove(state)
+    }
+
+    /// Have the flow's result written over an existing credential
+    pub fn replace_on_completion(&mut self, state: &str, reauth: PendingReauth) {
+        if self.flows.contains_key(state) {
+            self.reauths.insert(state.to_string(), reauth);
+        }
+    }
+
+    /// The credential the completed flow replaces, handed out once
+    pub fn take_reauth(&mut self, state: &str) -> Option<PendingReauth> {
+        self.reauths.remove(state)
+    }
+
+    /// Flows not completed yet, oldest first
+    pub fn in_progress(&mut self, now: u64) -> Vec<OAuthState> {
+        self.prune(now);
+        let mut flows: Vec<OAuthState> = self
+            .flows
+            .values()
+            .filter(|f| f.status != OAuthFlowStatus::Completed)
+            .cloned()
+            .collect();
+        flows.sort_by_key(|f| f.created_at);
+        flows
+    }
+
+    fn prune(&mut self, now: u64) {
+        self.flows.retain(|_, flow| flow.expires_at > now);
+        let flows = &self.flows;
+        self.reauths.retain(|state, _| flows.contains_key(state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flows_run_side_by_side() {
+        let mut flows = OAuthFlows::default();
+        flows.start("claude", "state-claude", 1_000, FLOW_TIMEOUT_MS);
+        flows.start("gemini", "state-gemini", 2_000, FLOW_TIMEOUT_MS);
+        flows.start("kiro", "", 2_000, FLOW_TIMEOUT_MS);
+        let listed: Vec<_> = flows
+            .in_progress(3_000)
+            .into_iter()
+            .map(|f| f.provider)
+            .collect();
+        assert_eq!(listed, ["claude", "gemini"]);
+
+        // Cancelling one keeps the other's callback working
+        assert!(flows.cancel("state-claude").is_some());
+        let flow = flows
+            .set_status("state-gemini", OAuthFlowStatus::CallbackReceived, 4_000)
+            .unwrap();
+        assert_eq!(flow.provider, "gemini");
+        assert_eq!(
+            flows.awaiting_exchange("gemini", 4_000).as_deref(),
+            Some("state-gemini")
+        );
+        assert!(flows.get("state-claude", 4_000).is_none());
+
+        // Completed flows still answer polls but aren't listed
+        flows.set_status("state-gemini", OAuthFlowStatus::Completed, 5_000);
+        assert!(flows.in_progress(5_000).is_empty());
+        assert!(flows.get("state-gemini", 5_000).is_some());
+    }
+
+    #[test]
+    fn flows_expire() {
+        let mut flows = OAuthFlows::default();
+        flows.start("qwen", "device", 0, 900_000);
+        flows.start("claude", "browser", 0, FLOW_TIMEOUT_MS);
+        assert!(flows.get("browser", FLOW_TIMEOUT_MS).is_none());
+        assert!(flows.get("device", FLOW_TIMEOUT_MS).is_some());
+        assert!(flows
+            .set_status("device", OAuthFlowStatus::Completed, 900_000)
+            .is_none());
+    }
+}
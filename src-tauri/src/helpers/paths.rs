@@ -0,0 +1,227 @@
+//! Where ProxyPal's data lives: the per-user config directory and
+//! `~/.cli-proxy-api` normally, or a `data/` folder beside the executable in
+//! portable mode.
+//!
+//! Portable mode is on when a `portable.flag` file sits next to the binary or
+//! the app was started with `--portable`. It is decided once, on first use;
+//! every path helper asks [`provider`] rather than the platform directories.
+//!
+//! Bulky data (history, logs, captures, benchmarks) follows
+//! `AppConfig.data_dir` when it is set, so it can live on another disk while
+//! config.json and auth.json stay put; see [`data_dir`].
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use parking_lot::RwLock;
+
+use crate::config::AppConfig;
+
+/// File beside the executable that turns portable mode on
+pub(crate) const PORTABLE_FLAG: &str = "portable.flag";
+/// Argument that turns portable mode on for one launch
+pub(crate) const PORTABLE_ARG: &str = "--portable";
+
+/// Roots for everything ProxyPal reads and writes
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PathProvider {
+    // `data/` beside the executable, in portable mode
+    portable_root: Option<PathBuf>,
+}
+
+static PROVIDER: OnceLock<PathProvider> = OnceLock::new();
+
+lazy_static::lazy_static! {
+    // `AppConfig.data_dir`; the outer None until config.json has been read
+    static ref DATA_DIR: RwLock<Option<Option<PathBuf>>> = RwLock::new(None);
+}
+
+/// Files and folders under the data dir, as opposed to the config dir
+pub(crate) const DATA_ENTRIES: &[&str] = &[
+    "history.json",
+    "aggregate.json",
+    "logs",
+    "captures",
+    "benchmarks",
+];
+
+impl PathProvider {
+    pub fn standard() -> Self {
+        Self {
+            portable_root: None,
+        }
+    }
+
+    /// Keep everything under `data/` in `exe_dir`
+    pub fn portable(exe_dir: &Path) -> Self {
+        Self {
+            portable_root: Some(exe_dir.join("data")),
+        }
+    }
+
+    /// Portable when `args` has `--portable` or the flag file is beside `exe`
+    pub fn detect(exe: Option<&Path>, args: &[String]) -> Self {
+        let exe_dir = exe.and_then(Path::parent);
+        match exe_dir {
+            Some(dir) if args.iter().any(|a| a == PORTABLE_ARG) => Self::portable(dir),
+            Some(dir) if dir.join(PORTABLE_FLAG).is_file() => Self::portable(dir),
+            _ => Self::standard(),
+        }
+    }
+
+    pub fn is_portable(&self) -> bool {
+        self.portable_root.is_some()
+    }
+
+    /// ProxyPal's own files: config, auth status, history, logs
+    pub fn config_dir(&self) -> PathBuf {
+        if let Some(root) = &self.portable_root {
+            return root.clone();
+        }
+        dirs::config_dir()
+            .unwrap_or_else(|| {
+                eprintln!(
+                    "[ProxyPal] Warning: Could not determine config directory, using current directory"
+                );
+                PathBuf::from(".")
+            })
+            .join("proxypal")
+    }
+
+    /// Where CLIProxyAPI keeps OAuth tokens and imported credentials
+    pub fn auth_dir(&self) -> PathBuf {
+        if let Some(root) = &self.portable_root {
+            return root.join("auth");
+        }
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".cli-proxy-api")
+    }
+
+    /// Where bulky data goes: `configured` if set (relative to the `data/`
+    /// folder in portable mode, so it can stay on the same drive), else
+    /// `config_dir`
+    pub fn data_dir(&self, configured: Option<&Path>, config_dir: &Path) -> PathBuf {
+        match (configured, &self.portable_root) {
+            (Some(dir), Some(root)) if dir.is_relative() => root.join(dir),
+            (Some(dir), _) => dir.to_path_buf(),
+            (None, _) => config_dir.to_path_buf(),
+        }
+    }
+}
+
+fn configured_data_dir(config: &AppConfig) -> Option<PathBuf> {
+    config
+        .data_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Register the data dir of the current config. Call after loading or saving it.
+pub(crate) fn set_data_dir(config: &AppConfig) {
+    *DATA_DIR.write() = Some(configured_data_dir(config));
+}
+
+/// History, logs, captures and benchmarks: `AppConfig.data_dir`, or the
+/// config dir in use when unset. Before the config is registered the value
+/// is read from config.json, so early startup sees the same folder.
+pub(crate) fn data_dir() -> PathBuf {
+    let known = DATA_DIR.read().clone();
+    let configured = match known {
+        Some(configured) => configured,
+        None => {
+            // Read outside the lock; loading the config resolves paths too
+            let configured = configured_data_dir(&crate::config::load_config());
+            DATA_DIR.write().get_or_insert(configured).clone()
+        }
+    };
+    resolve_data_dir(configured.as_deref())
+}
+
+/// The data dir `config` would use
+pub(crate) fn data_dir_for(config: &AppConfig) -> PathBuf {
+    resolve_data_dir(configured_data_dir(config).as_deref())
+}
+
+fn resolve_data_dir(configured: Option<&Path>) -> PathBuf {
+    let config_dir = &crate::helpers::storage::location().dir;
+    provider().data_dir(configured, config_dir)
+}
+
+/// The provider for this process, detected on first call
+pub(crate) fn provider() -> &'static PathProvider {
+    PROVIDER.get_or_init(|| {
+        let exe = std::env::current_exe().ok();
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let provider = PathProvider::detect(exe.as_deref(), &args);
+        if provider.is_portable() {
+            eprintln!(
+                "[ProxyPal] Portable mode: storing data in {}",
+                provider.config_dir().display()
+            );
+        }
+        provider
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exe_in(dir: &Path) -> PathBuf {
+        dir.join("proxypal")
+    }
+
+    #[test]
+    fn standard_mode_uses_the_user_dirs() {
+        let dir = std::env::temp_dir().join(format!("proxypal-paths-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let provider = PathProvider::detect(Some(&exe_in(&dir)), &[]);
+        assert_eq!(provider, PathProvider::standard());
+        assert!(!provider.config_dir().starts_with(&dir));
+        assert!(provider.config_dir().ends_with("proxypal"));
+        assert!(provider.auth_dir().ends_with(".cli-proxy-api"));
+
+        // Without an executable path there is nothing to be portable next to
+        let args = vec![PORTABLE_ARG.to_string()];
+        assert!(!PathProvider::detect(None, &args).is_portable());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn portable_mode_keeps_data_beside_the_executable() {
+        let dir = std::env::temp_dir().join(format!("proxypal-paths-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let data = dir.join("data");
+
+        let by_arg = PathProvider::detect(Some(&exe_in(&dir)), &[PORTABLE_ARG.to_string()]);
+        assert!(by_arg.is_portable());
+        assert_eq!(by_arg.config_dir(), data);
+        assert_eq!(by_arg.auth_dir(), data.join("auth"));
+
+        std::fs::write(dir.join(PORTABLE_FLAG), "").unwrap();
+        let by_flag = PathProvider::detect(Some(&exe_in(&dir)), &[]);
+        assert_eq!(by_flag, by_arg);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn data_dir_defaults_to_the_config_dir() {
+        let config_dir = Path::new("/home/me/.config/proxypal");
+        let other_disk = Path::new("/mnt/big/proxypal");
+        let standard = PathProvider::standard();
+        assert_eq!(standard.data_dir(None, config_dir), config_dir);
+        assert_eq!(standard.data_dir(Some(other_disk), config_dir), other_disk);
+
+        let portable = PathProvider::portable(Path::new("/media/usb/ProxyPal"));
+        assert_eq!(
+            portable.data_dir(Some(Path::new("bulk")), config_dir),
+            Path::new("/media/usb/ProxyPal/data/bulk")
+        );
+    }
+}
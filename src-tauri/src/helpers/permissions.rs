@@ -0,0 +1,245 @@
+//! Owner-only permissions for files holding keys and credentials.
+//!
+//! config.json (provider and Amp API keys), auth.json, proxy-config.yaml and
+//! the OAuth tokens in `~/.cli-proxy-api` would otherwise be created with the
+//! default umask, which leaves them world-readable on most Linux setups.
+//! Files get 0600 and directories 0700 on Unix; on Windows inheritance is
+//! removed and only the current user is granted access.
+
+use std::path::{Path, PathBuf};
+
+use crate::types::FilePermissionIssue;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Where CLIProxyAPI keeps OAuth tokens and imported credentials
+pub(crate) fn cli_proxy_auth_dir() -> PathBuf {
+    crate::helpers::paths::provider().auth_dir()
+}
+
+/// Restrict a file (or, with `is_dir`, a directory) to the current user
+#[cfg(unix)]
+pub(crate) fn restrict(path: &Path, is_dir: bool) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = if is_dir { 0o700 } else { 0o600 };
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+}
+
+// Paths restricted this session, so each is handed to icacls (a process
+// spawn) once rather than on every config save. A file created again at a
+// known path, like the temp file a save renames, inherits the owner-only ACL
+// of its restricted directory.
+#[cfg(windows)]
+lazy_static::lazy_static! {
+    static ref RESTRICTED: parking_lot::Mutex<std::collections::HashSet<PathBuf>> =
+        Default::default();
+}
+
+/// Restrict a file (or, with `is_dir`, a directory) to the current user
+#[cfg(windows)]
+pub(crate) fn restrict(path: &Path, is_dir: bool) -> std::io::Result<()> {
+    if RESTRICTED.lock().contains(path) {
+        return Ok(());
+    }
+    grant_owner_only(path, is_dir)?;
+    RESTRICTED.lock().insert(path.to_path_buf());
+    Ok(())
+}
+
+#[cfg(windows)]
+fn grant_owner_only(path: &Path, is_dir: bool) -> std::io::Result<()> {
+    let user = std::env::var("USERNAME")
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::NotFound, "USERNAME is not set"))?;
+    // Directories pass full control on to the files created inside them
+    let grant = if is_dir {
+        format!("{}:(OI)(CI)F", user)
+    } else {
+        format!("{}:F", user)
+    };
+    let output = std::process::Command::new("icacls")
+        .arg(path)
+        .args(["/inheritance:r", "/grant:r", &grant])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// Restrict a freshly written file, logging instead of failing the save
+pub(crate) fn restrict_after_write(path: &Path) {
+    if let Err(e) = restrict(path, false) {
+        eprintln!(
+            "[ProxyPal] Failed to restrict permissions on {}: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+/// Create a directory (and parents) readable only by the current user
+pub(crate) fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(path)?;
+    restrict(path, true)
+}
+
+// The mode as shown by `ls`/`chmod`, if group or others have any access
+#[cfg(unix)]
+fn loose_mode(path: &Path) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(path).ok()?.permissions().mode() & 0o777;
+    (mode & 0o077 != 0).then(|| format!("{:o}", mode))
+}
+
+// Broad groups allowed in the ACL. Matched by well-known SID, since `icacls`
+// prints account names in the system's display language.
+#[cfg(windows)]
+fn loose_mode(path: &Path) -> Option<String> {
+    const ALLOWED_SIDS: &str = "(Get-Acl -LiteralPath $env:PROXYPAL_ACL_PATH).GetAccessRules(\
+        $true, $true, [System.Security.Principal.SecurityIdentifier]) | \
+        Where-Object { $_.AccessControlType -eq 'Allow' } | \
+        ForEach-Object { $_.IdentityReference.Value }";
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", ALLOWED_SIDS])
+        .env("PROXYPAL_ACL_PATH", path)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    broad_principals(&String::from_utf8_lossy(&output.stdout))
+}
+
+// Everyone, BUILTIN\Users and Authenticated Users among the SIDs, one per line
+#[cfg(any(windows, test))]
+fn broad_principals(sids: &str) -> Option<String> {
+    const BROAD: &[(&str, &str)] = &[
+        ("S-1-1-0", "Everyone"),
+        ("S-1-5-32-545", "Users"),
+        ("S-1-5-11", "Authenticated Users"),
+    ];
+    let found: Vec<&str> = BROAD
+        .iter()
+        .filter(|(sid, _)| sids.lines().any(|line| line.trim() == *sid))
+        .map(|(_, name)| *name)
+        .collect();
+    (!found.is_empty()).then(|| found.join(", "))
+}
+
+/// Bind a Unix socket only the current user can connect to. The directory is
+/// made private first, so the socket is never reachable by others in the
+/// window between bind and chmod.
+#[cfg(unix)]
+pub(crate) fn bind_private_socket(
+    path: &Path,
+) -> std::io::Result<std::os::unix::net::UnixListener> {
+    if let Some(dir) = path.parent() {
+        create_private_dir(dir)?;
+    }
+    // Left behind by an instance that didn't shut down cleanly
+    let _ = std::fs::remove_file(path);
+    let listener = std::os::unix::net::UnixListener::bind(path)?;
+    restrict(path, false)?;
+    Ok(listener)
+}
+
+/// Files and directories that must stay private: the ProxyPal config
+/// directory with its key-bearing files, and the CLIProxyAPI auth directory
+/// with everything in it.
+pub(crate) fn sensitive_paths() -> Vec<(PathBuf, bool)> {
+    let config_dir = crate::config::get_proxypal_config_dir();
+    let mut paths = vec![(config_dir.clone(), true)];
+    for name in ["config.json", "auth.json", "proxy-config.yaml"] {
+        paths.push((config_dir.join(name), false));
+    }
+    let auth_dir = cli_proxy_auth_dir();
+    paths.push((auth_dir.clone(), true));
+    if let Ok(entries) = std::fs::read_dir(&auth_dir) {
+        for entry in entries.flatten() {
+            if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                paths.push((entry.path(), false));
+            }
+        }
+    }
+    paths.retain(|(path, _)| path.exists());
+    paths
+}
+
+/// Entries of `paths` that other users can access
+pub(crate) fn audit(paths: &[(PathBuf, bool)]) -> Vec<FilePermissionIssue> {
+    paths
+        .iter()
+        .filter_map(|(path, is_dir)| {
+            loose_mode(path).map(|mode| FilePermissionIssue {
+                path: path.to_string_lossy().to_string(),
+                is_dir: *is_dir,
+                mode,
+                repaired: false,
+            })
+        })
+        .collect()
+}
+
+/// Tighten every issue found by `audit`, marking the ones that worked
+pub(crate) fn repair(issues: &mut [FilePermissionIssue]) {
+    for issue in issues {
+        // Loosened since this session restricted it
+        #[cfg(windows)]
+        RESTRICTED.lock().remove(Path::new(&issue.path));
+        match restrict(Path::new(&issue.path), issue.is_dir) {
+            Ok(()) => issue.repaired = true,
+            Err(e) => eprintln!(
+                "[ProxyPal] Failed to repair permissions on {}: {}",
+                issue.path, e
+            ),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn finds_and_repairs_world_readable_files() {
+        let dir = std::env::temp_dir().join(format!("proxypal-perms-{}", uuid::Uuid::new_v4()));
+        let file = dir.join("config.json");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&file, "{}").unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let paths = vec![(dir.clone(), true), (file.clone(), false)];
+        let mut issues = audit(&paths);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[1].mode, "644");
+
+        repair(&mut issues);
+        assert!(issues.iter().all(|issue| issue.repaired));
+        assert!(audit(&paths).is_empty());
+        let mode = std::fs::metadata(&file).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn finds_broad_groups_by_sid() {
+        let sids = "S-1-5-21-1004336348-1177238915-682003330-1001\r\nS-1-5-18\r\nS-1-5-11\r\n";
+        assert_eq!(
+            broad_principals(sids).as_deref(),
+            Some("Authenticated Users")
+        );
+        assert_eq!(
+            broad_principals("S-1-1-0\nS-1-5-32-545\n").as_deref(),
+            Some("Everyone, Users")
+        );
+        // Administrators and SYSTEM are expected on a private file
+        assert_eq!(broad_principals("S-1-5-32-544\nS-1-5-18\n"), None);
+    }
+}
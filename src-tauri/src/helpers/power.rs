@@ -0,0 +1,93 @@
+//! Power source detection, so background work can slow down on battery.
+//!
+//! Best effort: Linux reads `/sys/class/power_supply`, macOS asks `pmset`.
+//! Elsewhere, or when detection fails, the machine counts as plugged in.
+
+use std::path::Path;
+
+/// Whether the machine is running on battery right now
+pub(crate) fn on_battery() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        sysfs_on_battery(Path::new("/sys/class/power_supply"))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .map(|output| pmset_on_battery(&String::from_utf8_lossy(&output.stdout)))
+            .unwrap_or(false)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        false
+    }
+}
+
+// On battery when there is a mains supply and none of them is online
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn sysfs_on_battery(dir: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    let mut has_mains = false;
+    for entry in entries.flatten() {
+        let supply = entry.path();
+        if read(&supply.join("type")) != "Mains" {
+            continue;
+        }
+        has_mains = true;
+        if read(&supply.join("online")) == "1" {
+            return false;
+        }
+    }
+    has_mains
+}
+
+// `pmset -g batt` starts with "Now drawing from 'Battery Power'" on battery
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn pmset_on_battery(output: &str) -> bool {
+    output.contains("'Battery Power'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_linux_power_supplies() {
+        let dir = std::env::temp_dir().join(format!("proxypal-power-{}", uuid::Uuid::new_v4()));
+        let supply = |name: &str, kind: &str, online: Option<&str>| {
+            let path = dir.join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            std::fs::write(path.join("type"), format!("{}\n", kind)).unwrap();
+            if let Some(online) = online {
+                std::fs::write(path.join("online"), format!("{}\n", online)).unwrap();
+            }
+        };
+        supply("BAT0", "Battery", None);
+        // A desktop without a mains entry is never "on battery"
+        assert!(!sysfs_on_battery(&dir));
+        supply("AC", "Mains", Some("0"));
+        assert!(sysfs_on_battery(&dir));
+        supply("AC", "Mains", Some("1"));
+        assert!(!sysfs_on_battery(&dir));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reads_pmset_output() {
+        assert!(pmset_on_battery(
+            "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t87%; discharging;"
+        ));
+        assert!(!pmset_on_battery(
+            "Now drawing from 'AC Power'\n -InternalBattery-0 (id=1234)\t100%; charged;"
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+//! Post-auth provider checks.
+//!
+//! A finished OAuth flow only proves the account signed in. Gemini accounts in
+//! particular also need a Cloud project with the Code Assist API enabled, and
+//! without one every request fails with an opaque upstream error. These
+//! helpers pick a model to try and turn the failure into guidance.
+
+use crate::types::AvailableModel;
+use crate::utils::detect_provider_from_model;
+
+/// What went wrong and what to do about it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Diagnosis {
+    pub issue: &'static str,
+    pub guidance: String,
+    pub link: Option<&'static str>,
+}
+
+const CODE_ASSIST_API: &str =
+    "https://console.cloud.google.com/apis/library/cloudaicompanion.googleapis.com";
+const CODE_ASSIST_SETUP: &str =
+    "https://developers.google.com/gemini-code-assist/docs/set-up-gemini";
+
+// Used when the proxy has not listed any models for the provider yet
+fn fallback_model(provider: &str) -> Option<&'static str> {
+    match provider {
+        "gemini" => Some("gemini-2.5-flash"),
+        "claude" => Some("claude-sonnet-4-5"),
+        "openai" => Some("gpt-5"),
+        "qwen" => Some("qwen3-coder-plus"),
+        "antigravity" => Some("gemini-3-flash"),
+        "deepseek" => Some("deepseek-chat"),
+        "zhipu" => Some("glm-4.5-air"),
+        _ => None,
+    }
+}
+
+/// Model to send the check request to: one the proxy lists for the provider,
+/// else a well-known one
+pub(crate) fn check_model(provider: &str, models: &[AvailableModel]) -> Option<String> {
+    models
+        .iter()
+        .find(|m| detect_provider_from_model(&m.id) == provider)
+        .map(|m| m.id.clone())
+        .or_else(|| fallback_model(provider).map(String::from))
+}
+
+/// Read a failed check. Returns None when the status means the account works
+/// (a rate limit still proves the credential and project are usable).
+pub(crate) fn diagnose(provider: &str, status: u16, body: &str) -> Option<Diagnosis> {
+    if (200..300).contains(&status) || status == 429 || body.contains("RESOURCE_EXHAUSTED") {
+        return None;
+    }
+    let lower = body.to_lowercase();
+    let gemini = provider == "gemini";
+
+    if lower.contains("billing_disabled")
+        || (lower.contains("billing") && lower.contains("disabled"))
+    {
+        return Some(Diagnosis {
+            issue: "billingDisabled",
+            guidance: "Billing is disabled for the Google Cloud project. Link a billing account to the project, then check again.".to_string(),
+            link: Some("https://console.cloud.google.com/billing"),
+        });
+    }
+    if lower.contains("service_disabled")
+        || lower.contains("has not been used in project")
+        || lower.contains("api has not been enabled")
+        || lower.contains("it is disabled")
+    {
+        return Some(Diagnosis {
+            issue: "apiDisabled",
+            guidance: "The Gemini for Google Cloud (Code Assist) API is not enabled for the project. Enable it, wait a minute, then check again.".to_string(),
+            link: Some(CODE_ASSIST_API),
+        });
+    }
+    if gemini
+        && ((lower.contains("project")
+            && (lower.contains("not found")
+                || lower.contains("no project")
+                || lower.contains("missing")
+                || lower.contains("required")))
+            || lower.contains("google_cloud_project"))
+    {
+        return Some(Diagnosis {
+            issue: "noProject",
+            guidance: "The Google account has no Cloud project to use with Code Assist. Create a project, enable the Code Assist API on it and sign in again.".to_string(),
+            link: Some("https://console.cloud.google.com/projectcreate"),
+        });
+    }
+    if status == 401 {
+        return Some(Diagnosis {
+            issue: "unauthorized",
+            guidance: "The provider rejected the credential. Sign in again.".to_string(),
+            link: None,
+        });
+    }
+    if status == 403 || lower.contains("permission_denied") {
+        return Some(Diagnosis {
+            issue: "permissionDenied",
+            guidance: if gemini {
+                "The account is not entitled to Gemini Code Assist on this project. Check the Code Assist setup for the account.".to_string()
+            } else {
+                "The provider refused the request for this account.".to_string()
+            },
+            link: gemini.then_some(CODE_ASSIST_SETUP),
+        });
+    }
+    Some(Diagnosis {
+        issue: "unknown",
+        guidance: format!("The check request failed with HTTP {}.", status),
+        link: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_common_gemini_setup_failures() {
+        let disabled = r#"{"error":{"code":403,"message":"Gemini for Google Cloud API has not been used in project 123 before or it is disabled.","status":"PERMISSION_DENIED","details":[{"reason":"SERVICE_DISABLED"}]}}"#;
+        assert_eq!(
+            diagnose("gemini", 403, disabled).unwrap().issue,
+            "apiDisabled"
+        );
+
+        let billing = r#"{"error":{"status":"FAILED_PRECONDITION","details":[{"reason":"BILLING_DISABLED"}]}}"#;
+        assert_eq!(
+            diagnose("gemini", 400, billing).unwrap().issue,
+            "billingDisabled"
+        );
+
+        let project = r#"{"error":{"message":"Project not found or user has no access"}}"#;
+        assert_eq!(diagnose("gemini", 404, project).unwrap().issue, "noProject");
+
+        let denied = r#"{"error":{"status":"PERMISSION_DENIED"}}"#;
+        let diagnosis = diagnose("gemini", 403, denied).unwrap();
+        assert_eq!(diagnosis.issue, "permissionDenied");
+        assert_eq!(diagnosis.link, Some(CODE_ASSIST_SETUP));
+    }
+
+    #[test]
+    fn working_or_rate_limited_accounts_pass() {
+        assert_eq!(diagnose("gemini", 200, "{}"), None);
+        assert_eq!(diagnose("gemini", 429, "quota"), None);
+        assert_eq!(diagnose("claude", 401, "").unwrap().issue, "unauthorized");
+        assert_eq!(
+            check_model("gemini", &[]).as_deref(),
+            Some("gemini-2.5-flash")
+        );
+        assert_eq!(check_model("kimi", &[]), None);
+    }
+}
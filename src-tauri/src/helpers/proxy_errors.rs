@@ -0,0 +1,109 @@
+//! In-memory buffer of recent, classified proxy errors.
+
+use std::collections::VecDeque;
+
+use crate::types::{ProxyErrorEntry, RequestLog};
+
+/// Number of errors kept for the tray's "Recent Errors" submenu
+pub const MAX_RECENT_ERRORS: usize = 10;
+
+/// Bounded error buffer; `generation` bumps on every push so consumers can
+/// tell cheaply whether anything changed since they last looked.
+#[derive(Default)]
+pub struct RecentErrors {
+    pub entries: VecDeque<ProxyErrorEntry>,
+    pub generation: u64,
+}
+
+impl RecentErrors {
+    pub fn push(&mut self, mut entry: ProxyErrorEntry) {
+        entry.message = crate::redact::redact(&entry.message);
+        if self.entries.len() >= MAX_RECENT_ERRORS {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+        self.generation += 1;
+    }
+}
+
+/// Map an HTTP status to an error kind
+pub fn classify_status(status: u16) -> &'static str {
+    match status {
+        401 | 403 => "auth",
+        429 => "rate_limit",
+        500..=599 => "upstream",
+        _ => "client",
+    }
+}
+
+/// Build a buffer entry from a failed request (status >= 400)
+pub fn error_from_request(log: &RequestLog) -> Option<ProxyErrorEntry> {
+    if log.status < 400 {
+        return None;
+    }
+    Some(ProxyErrorEntry {
+        id: log.id.clone(),
+        timestamp: log.timestamp,
+        kind: classify_status(log.status).to_string(),
+        status: Some(log.status),
+        provider: log.provider.clone(),
+        model: log.model.clone(),
+        message: format!("{} {} returned {}", log.method, log.path, log.status),
+    })
+}
+
+/// Short label used for menu items, e.g. "14:02 · 429 claude-sonnet-4 (rate limit)"
+pub fn error_label(entry: &ProxyErrorEntry) -> String {
+    let time = chrono::DateTime::from_timestamp_millis(entry.timestamp as i64)
+        .map(|dt| dt.with_timezone(&chrono::Local).format("%H:%M").to_string())
+        .unwrap_or_default();
+    let subject = if entry.model.is_empty() || entry.model == "unknown" {
+        entry.provider.clone()
+    } else {
+        entry.model.clone()
+    };
+    let status = entry.status.map(|s| format!("{} ", s)).unwrap_or_default();
+    format!(
+        "{} · {}{} ({})",
+        time,
+        status,
+        subject,
+        entry.kind.replace('_', " ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str) -> ProxyErrorEntry {
+        ProxyErrorEntry {
+            id: id.to_string(),
+            timestamp: 0,
+            kind: "upstream".to_string(),
+            status: Some(502),
+            provider: "claude".to_string(),
+            model: "claude-sonnet-4".to_string(),
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn buffer_is_bounded_and_bumps_generation() {
+        let mut errors = RecentErrors::default();
+        for i in 0..MAX_RECENT_ERRORS + 3 {
+            errors.push(entry(&i.to_string()));
+        }
+        assert_eq!(errors.entries.len(), MAX_RECENT_ERRORS);
+        assert_eq!(errors.entries.front().unwrap().id, "3");
+        assert_eq!(errors.generation, (MAX_RECENT_ERRORS + 3) as u64);
+    }
+
+    #[test]
+    fn classifies_statuses() {
+        assert_eq!(classify_status(401), "auth");
+        assert_eq!(classify_status(429), "rate_limit");
+        assert_eq!(classify_status(503), "upstream");
+        assert_eq!(classify_status(400), "client");
+    }
+}
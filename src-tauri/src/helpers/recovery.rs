@@ -0,0 +1,309 @@
+//! Damaged JSON files: quarantine and partial recovery.
+//!
+//! A missing config, auth or history file means a fresh start; a file that
+//! exists but doesn't parse (truncated by a power loss, zero-filled, edited by
+//! hand) must not be silently replaced by defaults. [`load_json`] moves such a
+//! file to `<name>.corrupt-<unix millis>` and reads what it can from the
+//! damaged text: the complete part before the damage, with any member that
+//! doesn't fit its type dropped, and writes that back in place of the damaged
+//! file so the next load reads it. The result is reported as `data-recovered`,
+//! or `data-lost` when nothing was usable, and noted in the session journal.
+//! Reports made before startup wires the app up are held until
+//! [`start_reporting`].
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Deserializer, Map, Value};
+use tauri::{AppHandle, Emitter};
+
+use crate::types::{DataRecovery, SessionEventKind};
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+lazy_static::lazy_static! {
+    static ref PENDING: Mutex<Vec<DataRecovery>> = Mutex::new(Vec::new());
+}
+
+fn publish(app: &AppHandle, report: &DataRecovery) {
+    let (event, kind) = if report.recovered {
+        ("data-recovered", SessionEventKind::DataRecovered)
+    } else {
+        ("data-lost", SessionEventKind::DataLost)
+    };
+    let _ = app.emit(event, report);
+    crate::session_journal::record_event(
+        app,
+        kind,
+        Some(format!("{}: {}", report.file, report.detail)),
+    );
+}
+
+fn report(report: DataRecovery) {
+    eprintln!(
+        "[ProxyPal] {} was damaged ({}); moved to {}",
+        report.path,
+        report.detail,
+        report.quarantined_to.as_deref().unwrap_or("nowhere")
+    );
+    match APP.get() {
+        Some(app) => publish(app, &report),
+        None => PENDING.lock().push(report),
+    }
+}
+
+/// Publish the reports held since launch, and later ones as they come. Call
+/// once the session journal has begun.
+pub(crate) fn start_reporting(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    let pending = std::mem::take(&mut *PENDING.lock());
+    for report in &pending {
+        publish(app, report);
+    }
+}
+
+// Move a damaged file out of the way, keeping it next to where it was
+fn quarantine(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let mut stamp = chrono::Utc::now().timestamp_millis();
+    // Never overwrite an earlier quarantined copy
+    let mut target = path.with_file_name(format!("{}.corrupt-{}", name, stamp));
+    while target.exists() {
+        stamp += 1;
+        target = path.with_file_name(format!("{}.corrupt-{}", name, stamp));
+    }
+    match std::fs::rename(path, &target) {
+        Ok(()) => Some(target),
+        Err(e) => {
+            eprintln!("[ProxyPal] Failed to move aside {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+// One complete JSON value at the start of `text` and the offset after it
+fn complete_value(text: &str) -> Option<(Value, usize)> {
+    let mut stream = Deserializer::from_str(text).into_iter::<Value>();
+    let value = stream.next()?.ok()?;
+    Some((value, stream.byte_offset()))
+}
+
+fn skip_whitespace(text: &str, pos: usize) -> usize {
+    pos + (text[pos..].len() - text[pos..].trim_start().len())
+}
+
+/// Nesting deeper than this is treated as damage, like serde_json's own
+/// recursion limit, so a file of brackets can't overflow the stack
+const MAX_DEPTH: usize = 128;
+
+/// The JSON value at the start of `text`, as far as the text is intact.
+/// Returns the value and the offset after it, or no offset when the value
+/// breaks off: an object or array then holds its complete members (and the
+/// intact part of the last one), while a broken scalar gives nothing.
+pub(crate) fn intact_prefix(text: &str) -> Option<(Value, Option<usize>)> {
+    intact_prefix_at(text, 0)
+}
+
+fn intact_prefix_at(text: &str, depth: usize) -> Option<(Value, Option<usize>)> {
+    let start = skip_whitespace(text, 0);
+    let open = text[start..].chars().next()?;
+    if open != '{' && open != '[' {
+        // A number could be cut short, so a scalar needs something after it
+        let (value, len) = complete_value(&text[start..])?;
+        let end = start + len;
+        return (end < text.len()).then_some((value, Some(end)));
+    }
+    if depth >= MAX_DEPTH {
+        return None;
+    }
+
+    let close = if open == '{' { '}' } else { ']' };
+    let mut members = Map::new();
+    let mut items = Vec::new();
+    let mut pos = start + 1;
+    let finish = |members: Map<String, Value>, items: Vec<Value>| {
+        if open == '{' {
+            Value::Object(members)
+        } else {
+            Value::Array(items)
+        }
+    };
+    loop {
+        pos = skip_whitespace(text, pos);
+        match text[pos..].chars().next() {
+            Some(c) if c == close => return Some((finish(members, items), Some(pos + 1))),
+            Some(',') if !members.is_empty() || !items.is_empty() => {
+                pos = skip_whitespace(text, pos + 1);
+            }
+            Some(_) => {}
+            None => return Some((finish(members, items), None)),
+        }
+
+        let key = if open == '{' {
+            let Some((Value::String(key), len)) = complete_value(&text[pos..]) else {
+                return Some((finish(members, items), None));
+            };
+            pos = skip_whitespace(text, pos + len);
+            if !text[pos..].starts_with(':') {
+                return Some((finish(members, items), None));
+            }
+            pos += 1;
+            Some(key)
+        } else {
+            None
+        };
+
+        let Some((value, end)) = intact_prefix_at(&text[pos..], depth + 1) else {
+            return Some((finish(members, items), None));
+        };
+        match key {
+            Some(key) => {
+                members.insert(key, value);
+            }
+            None => items.push(value),
+        }
+        match end {
+            Some(end) => pos += end,
+            None => return Some((finish(members, items), None)),
+        }
+    }
+}
+
+/// The members of `damaged` that deserialize into `T` over its defaults,
+/// and how many of them there were
+pub(crate) fn fitting_members<T: Default + serde::Serialize + DeserializeOwned>(
+    damaged: &Value,
+) -> Option<(T, usize, usize)> {
+    let Value::Object(members) = damaged else {
+        return None;
+    };
+    let Value::Object(mut kept) = serde_json::to_value(T::default()).ok()? else {
+        return None;
+    };
+    let mut count = 0;
+    for (key, value) in members {
+        let previous = kept.insert(key.clone(), value.clone());
+        if serde_json::from_value::<T>(Value::Object(kept.clone())).is_ok() {
+            count += 1;
+        } else {
+            match previous {
+                Some(previous) => kept.insert(key.clone(), previous),
+                None => kept.remove(key),
+            };
+        }
+    }
+    let value = serde_json::from_value(Value::Object(kept)).ok()?;
+    Some((value, count, members.len()))
+}
+
+// Put the salvaged value where the damaged file was. Without it the next
+// load would find no file at all and start fresh.
+fn write_back<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("recovered.tmp");
+    std::fs::write(&tmp, data).map_err(|e| e.to_string())?;
+    // Config and auth hold keys: restrict before the file takes its place
+    crate::helpers::permissions::restrict_after_write(&tmp);
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        e.to_string()
+    })
+}
+
+/// Read the JSON file at `path` as a `T`. A missing or unreadable file gives
+/// None. A file that doesn't parse is quarantined and `recover` gets its
+/// intact part; it returns what it salvaged and a description of it, and
+/// None when nothing was usable. A salvaged value replaces the damaged file.
+pub(crate) fn load_json<T: Serialize + DeserializeOwned>(
+    path: &Path,
+    recover: impl FnOnce(&Value) -> Option<(T, String)>,
+) -> Option<T> {
+    crate::startup::note_fs_read();
+    let data = match std::fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            eprintln!("[ProxyPal] Failed to read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    let error = match serde_json::from_str::<T>(&data) {
+        Ok(value) => return Some(value),
+        Err(e) => e,
+    };
+
+    let quarantined_to = quarantine(path);
+    let recovered = intact_prefix(&data).and_then(|(value, _)| recover(&value));
+    let (value, detail) = match recovered {
+        Some((value, detail)) => (Some(value), detail),
+        None => (None, format!("nothing could be read ({})", error)),
+    };
+    if let Some(value) = &value {
+        if let Err(e) = write_back(path, value) {
+            eprintln!(
+                "[ProxyPal] Failed to save the recovered {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+    report(DataRecovery {
+        file: path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: path.to_string_lossy().to_string(),
+        quarantined_to: quarantined_to.map(|p| p.to_string_lossy().to_string()),
+        recovered: value.is_some(),
+        detail,
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+    });
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_intact_part_of_truncated_json() {
+        let text = r#"{"a": 1, "list": [{"x": 1}, {"x": 2}, {"x": 3"#;
+        let (value, end) = intact_prefix(text).unwrap();
+        assert_eq!(end, None);
+        assert_eq!(value["a"], 1);
+        assert_eq!(value["list"].as_array().unwrap().len(), 3);
+        assert_eq!(value["list"][2], serde_json::json!({}));
+
+        // A number cut short is dropped rather than misread
+        let (value, _) = intact_prefix(r#"{"a": "x", "b": 12"#).unwrap();
+        assert_eq!(value, serde_json::json!({ "a": "x" }));
+
+        let (whole, end) = intact_prefix(r#" [1, 2] "#).unwrap();
+        assert_eq!((whole, end), (serde_json::json!([1, 2]), Some(7)));
+        assert!(intact_prefix("\0\0\0\0").is_none());
+        assert!(intact_prefix("").is_none());
+    }
+
+    #[test]
+    fn stops_at_deep_nesting_without_overflowing() {
+        let text = "[".repeat(100_000);
+        let (value, end) = intact_prefix(&text).unwrap();
+        assert_eq!(end, None);
+        let mut depth = 0;
+        let mut current = &value;
+        while let Value::Array(items) = current {
+            depth += 1;
+            match items.first() {
+                Some(item) => current = item,
+                None => break,
+            }
+        }
+        assert_eq!(depth, MAX_DEPTH);
+
+        let closed = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(matches!(intact_prefix(&closed), Some((_, None))));
+    }
+}
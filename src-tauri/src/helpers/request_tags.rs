@@ -0,0 +1,148 @@
+//! Tags on requests and sessions, for cost review.
+//!
+//! A session is a run of requests less than [`SESSION_GAP_MS`] apart, named
+//! after the id of its first request. Tags are stored on the requests in
+//! history, and every edit is counted in the aggregate's cost ledger at once,
+//! so per-tag spend outlives the requests. Session tags are also kept in the
+//! aggregate and given to requests that join the session later.
+
+use crate::helpers::cost_ledger;
+use crate::helpers::usage_day::{self, UsageTimezone};
+use crate::types::{Aggregate, RequestLog};
+
+/// Longest pause between two requests of one session
+pub(crate) const SESSION_GAP_MS: u64 = 30 * 60 * 1000;
+
+const MAX_TAGS: usize = 10;
+const MAX_TAG_LEN: usize = 40;
+
+/// Trimmed, lowercased, deduplicated and sorted tags; an empty list clears them
+pub(crate) fn normalize(tags: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() {
+            continue;
+        }
+        if tag.chars().count() > MAX_TAG_LEN || tag.chars().any(char::is_control) {
+            return Err(format!(
+                "Invalid tag '{}': use up to {} printable characters",
+                tag, MAX_TAG_LEN
+            ));
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    if normalized.len() > MAX_TAGS {
+        return Err(format!("At most {} tags per request", MAX_TAGS));
+    }
+    normalized.sort();
+    Ok(normalized)
+}
+
+/// Put `req` in the session of `previous` when it follows closely enough,
+/// otherwise start a new one
+pub(crate) fn assign_session(previous: Option<&RequestLog>, req: &mut RequestLog) {
+    if req.session_id.is_some() {
+        return;
+    }
+    let continued = previous
+        .filter(|prev| req.timestamp.saturating_sub(prev.timestamp) < SESSION_GAP_MS)
+        .and_then(|prev| prev.session_id.clone());
+    req.session_id = Some(continued.unwrap_or_else(|| req.id.clone()));
+}
+
+/// Replace the tags of `req`, moving its cost between tags in the ledger.
+/// Demo requests aren't in the ledger, so only their tags change.
+pub(crate) fn retag(
+    agg: &mut Aggregate,
+    timezone: UsageTimezone,
+    req: &mut RequestLog,
+    tags: Vec<String>,
+) {
+    if req.tags == tags {
+        return;
+    }
+    let day = usage_day::day_label_ms(timezone, req.timestamp).filter(|_| !req.synthetic);
+    if let Some(day) = day {
+        let ledger = &mut agg.cost_ledger;
+        cost_ledger::record_tags(ledger, &day, req, &req.tags, false);
+        cost_ledger::record_tags(ledger, &day, req, &tags, true);
+    }
+    req.tags = tags;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(id: &str, timestamp: u64) -> RequestLog {
+        RequestLog {
+            id: id.to_string(),
+            timestamp,
+            provider: "claude".to_string(),
+            model: "claude-sonnet-4-5".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status: 200,
+            duration_ms: 800,
+            ttfb_ms: None,
+            tokens_in: Some(1_000_000),
+            tokens_out: Some(0),
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    #[test]
+    fn normalizes_tags() {
+        let tags = vec![" Acme ".to_string(), "acme".to_string(), "".to_string()];
+        assert_eq!(normalize(tags).unwrap(), vec!["acme"]);
+        assert!(normalize(vec!["x".repeat(41)]).is_err());
+        assert!(normalize((0..11).map(|i| i.to_string()).collect()).is_err());
+    }
+
+    #[test]
+    fn sessions_break_on_long_pauses() {
+        let mut first = request("req_1", 0);
+        assign_session(None, &mut first);
+        let mut second = request("req_2", SESSION_GAP_MS - 1);
+        assign_session(Some(&first), &mut second);
+        let mut third = request("req_3", 2 * SESSION_GAP_MS);
+        assign_session(Some(&second), &mut third);
+        assert_eq!(first.session_id.as_deref(), Some("req_1"));
+        assert_eq!(second.session_id.as_deref(), Some("req_1"));
+        assert_eq!(third.session_id.as_deref(), Some("req_3"));
+    }
+
+    #[test]
+    fn retagging_moves_the_cost_between_tags() {
+        let (utc, tag) = (UsageTimezone::Utc, |t: &str| vec![t.to_string()]);
+        let mut agg = Aggregate::default();
+        let mut req = request("req_1", 1_773_000_000_000);
+        retag(&mut agg, utc, &mut req, tag("acme"));
+        retag(&mut agg, utc, &mut req, tag("internal"));
+        let rows = cost_ledger::group_by_tag(&agg.cost_ledger, None, "9999-12-31");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].key, "internal");
+        assert!((rows[0].cost_usd - 3.0).abs() < 1e-9);
+        assert_eq!(req.tags, vec!["internal"]);
+
+        let mut demo = RequestLog {
+            synthetic: true,
+            ..request("req_2", 1_773_000_000_000)
+        };
+        retag(&mut agg, utc, &mut demo, tag("acme"));
+        assert_eq!(demo.tags, vec!["acme"]);
+        assert_eq!(agg.cost_ledger.tags.len(), 1);
+    }
+}
@@ -0,0 +1,216 @@
+//! End-to-end self-test checklist.
+//!
+//! "It doesn't work" usually means one link of the chain is broken: the config
+//! folder, the sidecar binary, the port, the proxy itself, the management key,
+//! the accounts, or the agent. The steps run in order and a step whose
+//! prerequisites did not pass is skipped, so the first failure is the one to fix.
+
+use std::path::Path;
+
+use crate::types::{SelfTestStatus, SelfTestStep};
+
+/// One item of the checklist
+pub(crate) struct Step {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub requires: &'static [&'static str],
+}
+
+/// The checklist, in the order it runs
+pub(crate) const STEPS: &[Step] = &[
+    Step {
+        id: "config_dir",
+        label: "Config folder is writable",
+        requires: &[],
+    },
+    Step {
+        id: "sidecar",
+        label: "CLIProxyAPI binary is present",
+        requires: &[],
+    },
+    Step {
+        id: "port",
+        label: "Proxy port is available",
+        requires: &[],
+    },
+    Step {
+        id: "proxy",
+        label: "Proxy starts and answers",
+        requires: &["config_dir", "sidecar", "port"],
+    },
+    Step {
+        id: "management",
+        label: "Management API accepts the key",
+        requires: &["proxy"],
+    },
+    Step {
+        id: "credentials",
+        label: "An account or API key is connected",
+        requires: &[],
+    },
+    Step {
+        id: "completion",
+        label: "A test completion succeeds",
+        requires: &["proxy", "credentials"],
+    },
+    Step {
+        id: "agents",
+        label: "A coding agent is installed",
+        requires: &[],
+    },
+];
+
+/// What running a step found
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StepOutcome {
+    Pass(String),
+    Fail { detail: String, hint: String },
+}
+
+impl StepOutcome {
+    pub fn fail(detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self::Fail {
+            detail: detail.into(),
+            hint: hint.into(),
+        }
+    }
+}
+
+/// Results so far
+#[derive(Default)]
+pub(crate) struct Checklist {
+    pub steps: Vec<SelfTestStep>,
+}
+
+impl Checklist {
+    fn passed(&self, id: &str) -> bool {
+        self.steps
+            .iter()
+            .any(|s| s.id == id && s.status == SelfTestStatus::Pass)
+    }
+
+    /// Skipped entry if a prerequisite of `step` did not pass
+    pub fn blocked(&self, step: &Step) -> Option<SelfTestStep> {
+        let missing = step.requires.iter().find(|id| !self.passed(id))?;
+        let label = STEPS
+            .iter()
+            .find(|s| s.id == *missing)
+            .map_or(*missing, |s| s.label);
+        Some(SelfTestStep {
+            id: step.id.to_string(),
+            label: step.label.to_string(),
+            status: SelfTestStatus::Skip,
+            detail: format!("Skipped: \"{}\" did not pass", label),
+            hint: Some("Fix the earlier step, then run the self-test again".to_string()),
+            duration_ms: 0,
+        })
+    }
+
+    pub fn record(&mut self, step: &Step, outcome: StepOutcome, duration_ms: u64) -> &SelfTestStep {
+        let (status, detail, hint) = match outcome {
+            StepOutcome::Pass(detail) => (SelfTestStatus::Pass, detail, None),
+            StepOutcome::Fail { detail, hint } => (SelfTestStatus::Fail, detail, Some(hint)),
+        };
+        self.push(SelfTestStep {
+            id: step.id.to_string(),
+            label: step.label.to_string(),
+            status,
+            detail,
+            hint,
+            duration_ms,
+        })
+    }
+
+    pub fn push(&mut self, step: SelfTestStep) -> &SelfTestStep {
+        self.steps.push(step);
+        &self.steps[self.steps.len() - 1]
+    }
+
+    pub fn any_failed(&self) -> bool {
+        self.steps.iter().any(|s| s.status == SelfTestStatus::Fail)
+    }
+}
+
+/// Create the folder if needed and write and remove a probe file in it
+pub(crate) fn check_config_dir(dir: &Path) -> StepOutcome {
+    let hint = format!(
+        "Make sure {} exists and your user can write to it",
+        dir.display()
+    );
+    if let Err(e) = crate::helpers::permissions::create_private_dir(dir) {
+        return StepOutcome::fail(format!("Cannot create {}: {}", dir.display(), e), hint);
+    }
+    let probe = dir.join(format!(".self-test-{}", uuid::Uuid::new_v4()));
+    match std::fs::write(&probe, b"ok") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            StepOutcome::Pass(dir.display().to_string())
+        }
+        Err(e) => StepOutcome::fail(format!("Cannot write to {}: {}", dir.display(), e), hint),
+    }
+}
+
+/// Whether the proxy could bind `port` on localhost right now
+pub(crate) fn check_port(port: u16) -> StepOutcome {
+    match std::net::TcpListener::bind(("127.0.0.1", port)) {
+        Ok(_) => StepOutcome::Pass(format!("Port {} is free", port)),
+        Err(e) => StepOutcome::fail(
+            format!("Port {} is in use: {}", port, e),
+            "Quit the program using the port, or pick another port in Settings → General",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str) -> &'static Step {
+        STEPS.iter().find(|s| s.id == id).unwrap()
+    }
+
+    #[test]
+    fn prerequisites_run_first() {
+        for (index, step) in STEPS.iter().enumerate() {
+            for required in step.requires {
+                let position = STEPS.iter().position(|s| s.id == *required).unwrap();
+                assert!(position < index, "{} must run before {}", required, step.id);
+            }
+        }
+    }
+
+    #[test]
+    fn skips_steps_after_a_failure() {
+        let mut checklist = Checklist::default();
+        checklist.record(step("config_dir"), StepOutcome::Pass("ok".into()), 1);
+        checklist.record(step("sidecar"), StepOutcome::fail("missing", "reinstall"), 1);
+        checklist.record(step("port"), StepOutcome::Pass("free".into()), 1);
+
+        let skipped = checklist.blocked(step("proxy")).unwrap();
+        assert_eq!(skipped.status, SelfTestStatus::Skip);
+        assert!(skipped.detail.contains("CLIProxyAPI binary is present"));
+        assert!(checklist.blocked(step("credentials")).is_none());
+        assert!(checklist.any_failed());
+
+        // A skipped step blocks its own dependents
+        checklist.push(skipped);
+        assert!(checklist.blocked(step("management")).is_some());
+    }
+
+    #[test]
+    fn config_dir_probe_cleans_up() {
+        let dir = std::env::temp_dir().join(format!("proxypal-self-test-{}", uuid::Uuid::new_v4()));
+        assert!(matches!(check_config_dir(&dir), StepOutcome::Pass(_)));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn reports_a_taken_port() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(matches!(check_port(port), StepOutcome::Fail { .. }));
+        drop(listener);
+        assert!(matches!(check_port(port), StepOutcome::Pass(_)));
+    }
+}
@@ -0,0 +1,190 @@
+//! xbar / SwiftBar plugin showing ProxyPal in the macOS menu bar.
+//!
+//! The generated shell script reads the JSON status file (see
+//! `status_file`) with `plutil`, which ships with macOS, and shows the proxy
+//! state and today's spend, with a start or stop item that opens the
+//! matching `proxypal://proxy/...` link. Both apps take the refresh interval
+//! from the file name (`proxypal.30s.sh`). Each generated script carries
+//! [`MARKER`]; re-generating replaces ProxyPal's own scripts in the folder
+//! and never a file without it.
+
+use std::path::{Path, PathBuf};
+
+/// Line that tags a script as generated by ProxyPal
+const MARKER: &str = "# proxypal-statusbar-plugin";
+
+/// Refresh interval when none is given, and the shortest one allowed
+pub(crate) const DEFAULT_REFRESH_SECS: u32 = 30;
+const MIN_REFRESH_SECS: u32 = 5;
+
+/// Menu bar apps a plugin can be generated for
+pub(crate) const PLUGIN_KINDS: &[&str] = &["xbar", "swiftbar"];
+
+/// xbar's plugin folder; SwiftBar's is whatever the user picked
+pub(crate) fn default_plugins_dir(kind: &str) -> Option<PathBuf> {
+    if kind != "xbar" {
+        return None;
+    }
+    dirs::home_dir().map(|home| home.join("Library/Application Support/xbar/plugins"))
+}
+
+// Single-quoted for sh, so the path is taken literally
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// The plugin script for `status_file`
+pub(crate) fn render(kind: &str, status_file: &Path) -> String {
+    let metadata = if kind == "swiftbar" {
+        "# <swiftbar.hideAbout>true</swiftbar.hideAbout>\n\
+         # <swiftbar.hideRunInTerminal>true</swiftbar.hideRunInTerminal>\n"
+    } else {
+        "# <xbar.title>ProxyPal</xbar.title>\n\
+         # <xbar.desc>Proxy state and today's spend from ProxyPal's status file</xbar.desc>\n"
+    };
+    format!(
+        r#"#!/bin/bash
+{marker}
+# Generated by ProxyPal; generating the plugin again replaces this file.
+{metadata}
+STATUS_FILE={status_file}
+
+field() {{ plutil -extract "$1" raw -o - "$STATUS_FILE" 2>/dev/null; }}
+
+if [ ! -f "$STATUS_FILE" ]; then
+  echo "PP ?"
+  echo "---"
+  echo "ProxyPal status file not found | color=gray"
+  echo "Open ProxyPal | href=proxypal://navigate/dashboard"
+  exit 0
+fi
+
+running=$(field running)
+port=$(field port)
+requests=$(field requests_today)
+cost=$(field cost_today)
+last_error=$(field last_error)
+
+if [ "$running" = "true" ]; then
+  printf 'PP ● $%.2f\n' "${{cost:-0}}"
+  echo "---"
+  echo "Running on port $port | color=green"
+  echo "${{requests:-0}} requests today"
+  printf 'Spent today: $%.2f\n' "${{cost:-0}}"
+  if [ -n "$last_error" ]; then
+    echo "Last error: $last_error | color=red"
+  fi
+  echo "---"
+  echo "Stop proxy | href=proxypal://proxy/stop"
+else
+  echo "PP ○"
+  echo "---"
+  echo "Proxy stopped | color=gray"
+  echo "---"
+  echo "Start proxy | href=proxypal://proxy/start"
+fi
+echo "Open ProxyPal | href=proxypal://navigate/dashboard"
+echo "Refresh | refresh=true"
+"#,
+        marker = MARKER,
+        metadata = metadata,
+        status_file = shell_quote(&status_file.to_string_lossy()),
+    )
+}
+
+fn is_generated(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|content| content.lines().any(|l| l == MARKER))
+}
+
+/// Write the plugin into `plugins_dir`, replacing earlier generated ones
+/// (possibly with another interval). Returns the script's path.
+pub(crate) fn write_plugin(
+    plugins_dir: &Path,
+    kind: &str,
+    status_file: &Path,
+    refresh_secs: u32,
+) -> Result<PathBuf, String> {
+    if !PLUGIN_KINDS.contains(&kind) {
+        return Err(format!(
+            "Unknown status bar app \"{}\" (expected {})",
+            kind,
+            PLUGIN_KINDS.join(" or ")
+        ));
+    }
+    if !plugins_dir.is_dir() {
+        return Err(format!(
+            "The plugins folder {} does not exist",
+            plugins_dir.display()
+        ));
+    }
+    let refresh_secs = refresh_secs.max(MIN_REFRESH_SECS);
+    let path = plugins_dir.join(format!("proxypal.{}s.sh", refresh_secs));
+    if path.exists() && !is_generated(&path) {
+        return Err(format!(
+            "{} exists and was not generated by ProxyPal; move it away first",
+            path.display()
+        ));
+    }
+
+    crate::status_file::write_atomically(&path, &render(kind, status_file))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+    }
+
+    // Scripts from earlier runs with another interval
+    let stale: Vec<PathBuf> = std::fs::read_dir(plugins_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|p| *p != path && p.extension().is_some_and(|ext| ext == "sh"))
+                .filter(|p| is_generated(p))
+                .collect()
+        })
+        .unwrap_or_default();
+    for old in stale {
+        let _ = std::fs::remove_file(old);
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_the_status_file_path_quoted() {
+        let script = render("swiftbar", Path::new("/Users/o'neil/ProxyPal/status.json"));
+        assert!(script.contains(r"STATUS_FILE='/Users/o'\''neil/ProxyPal/status.json'"));
+        assert!(script.contains("href=proxypal://proxy/start"));
+        assert!(script.contains("swiftbar.hideRunInTerminal"));
+        assert!(!render("xbar", Path::new("/tmp/s.json")).contains("swiftbar"));
+    }
+
+    #[test]
+    fn regenerating_replaces_only_its_own_script() {
+        let dir = std::env::temp_dir().join(format!("proxypal-statusbar-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let status = Path::new("/tmp/status.json");
+
+        let first = write_plugin(&dir, "xbar", status, 30).unwrap();
+        assert!(first.ends_with("proxypal.30s.sh"));
+        std::fs::write(dir.join("weather.5m.sh"), "#!/bin/bash\necho sun\n").unwrap();
+
+        let second = write_plugin(&dir, "xbar", status, 10).unwrap();
+        assert!(second.ends_with("proxypal.10s.sh") && !first.exists());
+        assert!(dir.join("weather.5m.sh").exists());
+        assert_eq!(write_plugin(&dir, "xbar", status, 10).unwrap(), second);
+
+        std::fs::write(dir.join("proxypal.60s.sh"), "#!/bin/bash\necho mine\n").unwrap();
+        assert!(write_plugin(&dir, "xbar", status, 60).is_err());
+        assert!(write_plugin(&dir.join("missing"), "xbar", status, 30).is_err());
+        assert!(write_plugin(&dir, "bitbar", status, 30).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,324 @@
+//! Where ProxyPal keeps its files.
+//!
+//! The directory is resolved once, on first use. When the usual config
+//! directory can't be created or written, the first writable fallback is used
+//! instead ($XDG_STATE_HOME/proxypal, then the temp dir) and storage counts as
+//! degraded: saves write there and succeed, and since the data may not survive
+//! a restart, startup emits `storage-degraded` and [`storage_info`] carries
+//! the reason for the settings page to show.
+//! In portable mode the usual directory is the `data/` folder beside the
+//! executable, and only the temp dir is tried after it.
+//!
+//! History, logs, captures and benchmarks can live in a separate data dir
+//! (`AppConfig.data_dir`); [`copy_data`] and [`remove_data`] move them there.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::helpers::paths::DATA_ENTRIES;
+use crate::types::{StorageDegraded, StorageInfo, StoragePath};
+
+/// The directory in use, and why it isn't the usual one
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StorageLocation {
+    pub dir: PathBuf,
+    pub degraded: Option<String>,
+}
+
+static LOCATION: OnceLock<StorageLocation> = OnceLock::new();
+
+fn preferred_dir() -> PathBuf {
+    crate::helpers::paths::provider().config_dir()
+}
+
+fn fallback_dirs() -> Vec<PathBuf> {
+    // A portable copy shouldn't leave state in the machine's user dirs
+    if crate::helpers::paths::provider().is_portable() {
+        return vec![std::env::temp_dir().join("proxypal-portable")];
+    }
+    let state_home = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::state_dir);
+    state_home
+        .into_iter()
+        .chain([std::env::temp_dir()])
+        .map(|dir| dir.join("proxypal"))
+        .collect()
+}
+
+// Create `dir` and prove a file can be written in it
+fn probe_writable(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+    let probe = dir.join(format!(".write-test-{}", std::process::id()));
+    std::fs::write(&probe, b"ok")
+        .map_err(|e| format!("cannot write to {}: {}", dir.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+// The preferred directory if writable, else the first writable fallback
+fn choose(preferred: PathBuf, fallbacks: Vec<PathBuf>) -> StorageLocation {
+    let reason = match probe_writable(&preferred) {
+        Ok(()) => {
+            return StorageLocation {
+                dir: preferred,
+                degraded: None,
+            }
+        }
+        Err(reason) => reason,
+    };
+    for dir in fallbacks {
+        if probe_writable(&dir).is_ok() {
+            return StorageLocation {
+                dir,
+                degraded: Some(reason),
+            };
+        }
+    }
+    StorageLocation {
+        dir: preferred,
+        degraded: Some(format!(
+            "{}; no fallback location is writable either",
+            reason
+        )),
+    }
+}
+
+/// The resolved storage location; probes the disk on first call
+pub(crate) fn location() -> &'static StorageLocation {
+    LOCATION.get_or_init(|| {
+        let location = choose(preferred_dir(), fallback_dirs());
+        if let Some(reason) = &location.degraded {
+            eprintln!(
+                "[ProxyPal] Error: config directory unusable ({}), storing files in {}",
+                reason,
+                location.dir.display()
+            );
+        }
+        location
+    })
+}
+
+/// Reason and path when storage fell back from the usual config directory
+pub(crate) fn degraded() -> Option<StorageDegraded> {
+    let location = location();
+    location.degraded.as_ref().map(|reason| StorageDegraded {
+        reason: reason.clone(),
+        path: location.dir.display().to_string(),
+    })
+}
+
+// Free space on the volume holding `dir`, from `df`
+#[cfg(unix)]
+fn free_bytes(dir: &Path) -> Option<u64> {
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(dir)
+        .output()
+        .ok()?;
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(unix))]
+fn free_bytes(_dir: &Path) -> Option<u64> {
+    None
+}
+
+// "Available" column (KiB) of POSIX `df -Pk` output, in bytes
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_df_available(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    let available: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(available * 1024)
+}
+
+// Number of files and total bytes under `path` (a file or a directory)
+fn tree_size(path: &Path) -> (u64, u64) {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return (0, 0);
+    };
+    if !metadata.is_dir() {
+        return (1, metadata.len());
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return (0, 0);
+    };
+    entries.flatten().fold((0, 0), |(files, bytes), entry| {
+        let (f, b) = tree_size(&entry.path());
+        (files + f, bytes + b)
+    })
+}
+
+// Total bytes of the entries of `dir` that `include` selects by name
+fn entries_size(dir: &Path, include: impl Fn(&str) -> bool) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| include(&entry.file_name().to_string_lossy()))
+        .map(|entry| tree_size(&entry.path()).1)
+        .sum()
+}
+
+fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+    if !from.is_dir() {
+        return std::fs::copy(from, to).map(|_| ());
+    }
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        copy_tree(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+fn remove_tree(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// Copy the data entries present in `from` to `to` and check every copy has
+/// the same files and bytes. Nothing in `to` is overwritten, and on failure
+/// the copies made so far are removed. Returns the entries copied.
+pub(crate) fn copy_data(from: &Path, to: &Path) -> Result<Vec<&'static str>, String> {
+    let entries: Vec<&'static str> = DATA_ENTRIES
+        .iter()
+        .copied()
+        .filter(|entry| from.join(entry).exists())
+        .collect();
+    if let Some(entry) = entries.iter().find(|entry| to.join(entry).exists()) {
+        return Err(format!("{} already has a {}", to.display(), entry));
+    }
+    probe_writable(to)?;
+
+    let mut copied = Vec::new();
+    for entry in &entries {
+        let (source, target) = (from.join(entry), to.join(entry));
+        let result = copy_tree(&source, &target)
+            .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))
+            .and_then(|_| {
+                if tree_size(&source) == tree_size(&target) {
+                    Ok(())
+                } else {
+                    Err(format!("The copy of {} does not match", source.display()))
+                }
+            });
+        copied.push(*entry);
+        if let Err(e) = result {
+            remove_data(to, &copied);
+            return Err(e);
+        }
+    }
+    Ok(copied)
+}
+
+/// Delete `entries` from `dir`, best-effort
+pub(crate) fn remove_data(dir: &Path, entries: &[&str]) {
+    for entry in entries {
+        let path = dir.join(entry);
+        if let Err(e) = remove_tree(&path) {
+            eprintln!("[ProxyPal] Failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Paths in use, free space, sizes and writability, for the settings screen
+pub(crate) fn storage_info() -> StorageInfo {
+    let location = location();
+    let data_dir = crate::config::get_data_dir();
+    let path = |name: &str, path: PathBuf| StoragePath {
+        name: name.to_string(),
+        exists: path.exists(),
+        size_bytes: tree_size(&path).1,
+        path: path.display().to_string(),
+    };
+    StorageInfo {
+        config_dir: location.dir.display().to_string(),
+        preferred_dir: preferred_dir().display().to_string(),
+        degraded_reason: location.degraded.clone(),
+        portable: crate::helpers::paths::provider().is_portable(),
+        writable: probe_writable(&location.dir).is_ok(),
+        free_bytes: free_bytes(&location.dir),
+        config_bytes: entries_size(&location.dir, |name| !DATA_ENTRIES.contains(&name)),
+        data_dir: data_dir.display().to_string(),
+        data_free_bytes: free_bytes(&data_dir),
+        data_bytes: entries_size(&data_dir, |name| DATA_ENTRIES.contains(&name)),
+        paths: vec![
+            path("config", crate::config::get_config_path()),
+            path("auth", crate::config::get_auth_path()),
+            path("history", crate::config::get_history_path()),
+            path("aggregate", crate::config::get_aggregate_path()),
+            path("logs", data_dir.join("logs")),
+            path("captures", data_dir.join("captures")),
+            path("benchmarks", data_dir.join("benchmarks")),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_when_the_config_dir_is_unusable() {
+        let root = std::env::temp_dir().join(format!("proxypal-storage-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        // A directory can't be created below a regular file
+        let blocker = root.join("not-a-dir");
+        std::fs::write(&blocker, "").unwrap();
+        let unusable = blocker.join("proxypal");
+        let fallback = root.join("state").join("proxypal");
+
+        let usable = choose(fallback.clone(), vec![]);
+        assert_eq!(usable.dir, fallback);
+        assert_eq!(usable.degraded, None);
+
+        let location = choose(unusable.clone(), vec![unusable.clone(), fallback.clone()]);
+        assert_eq!(location.dir, fallback);
+        assert!(location.degraded.unwrap().contains("cannot create"));
+
+        let stuck = choose(unusable.clone(), vec![]);
+        assert_eq!(stuck.dir, unusable);
+        assert!(stuck.degraded.unwrap().contains("no fallback"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn copies_data_without_overwriting() {
+        let root = std::env::temp_dir().join(format!("proxypal-storage-{}", uuid::Uuid::new_v4()));
+        let (from, to) = (root.join("old"), root.join("new"));
+        std::fs::create_dir_all(from.join("logs")).unwrap();
+        std::fs::write(from.join("history.json"), "[]").unwrap();
+        std::fs::write(from.join("logs").join("main.log"), "line\n").unwrap();
+        std::fs::write(from.join("config.json"), "{}").unwrap();
+
+        let copied = copy_data(&from, &to).unwrap();
+        assert_eq!(copied, ["history.json", "logs"]);
+        assert_eq!(
+            std::fs::read_to_string(to.join("logs").join("main.log")).unwrap(),
+            "line\n"
+        );
+        assert!(!to.join("config.json").exists(), "config stays put");
+
+        // A second copy would overwrite, so it is refused
+        assert!(copy_data(&from, &to).unwrap_err().contains("already has"));
+
+        remove_data(&from, &copied);
+        assert!(!from.join("history.json").exists());
+        assert!(from.join("config.json").exists());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn reads_available_space_from_df() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/disk3s5   482797652 301245108 160383256      66% /System/Volumes/Data\n";
+        assert_eq!(parse_df_available(output), Some(160_383_256 * 1024));
+        assert_eq!(parse_df_available(""), None);
+    }
+}
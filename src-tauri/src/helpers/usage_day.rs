@@ -0,0 +1,238 @@
+//! Calendar days for usage statistics.
+//!
+//! CLIProxyAPI keys its per-day and per-hour usage by the clock of the
+//! process that counted them, which is not always the user's local time
+//! (a sidecar in a UTC container, for one). `AppConfig.usage_timezone` names
+//! that zone, and every day/hour label ProxyPal writes or looks up goes
+//! through here so its own buckets, the proxy's and "today" all agree.
+//!
+//! The functions take the current time as an argument so tests can pin the
+//! clock on either side of midnight.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use parking_lot::RwLock;
+use tauri::{AppHandle, Emitter};
+
+use crate::config::AppConfig;
+use crate::types::TimeSeriesPoint;
+
+/// Zone usage buckets are labelled in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UsageTimezone {
+    Local,
+    Utc,
+}
+
+impl UsageTimezone {
+    /// `AppConfig.usage_timezone` value; anything but "utc" is local time
+    pub fn parse(value: &str) -> Self {
+        if value.eq_ignore_ascii_case("utc") {
+            Self::Utc
+        } else {
+            Self::Local
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref TIMEZONE: RwLock<UsageTimezone> = RwLock::new(UsageTimezone::Local);
+}
+
+/// Register the usage time zone of the current config. Call after loading or saving it.
+pub(crate) fn set_usage_timezone(config: &AppConfig) {
+    *TIMEZONE.write() = UsageTimezone::parse(&config.usage_timezone);
+}
+
+/// The usage time zone registered from the current config
+pub(crate) fn current() -> UsageTimezone {
+    *TIMEZONE.read()
+}
+
+fn format_in<Z: TimeZone>(at: DateTime<Utc>, zone: &Z, format: &str) -> String
+where
+    Z::Offset: std::fmt::Display,
+{
+    at.with_timezone(zone).format(format).to_string()
+}
+
+fn format_label(timezone: UsageTimezone, at: DateTime<Utc>, format: &str) -> String {
+    match timezone {
+        UsageTimezone::Local => format_in(at, &chrono::Local, format),
+        UsageTimezone::Utc => format_in(at, &Utc, format),
+    }
+}
+
+/// "YYYY-MM-DD" label of the day `at` falls on
+pub(crate) fn day_label(timezone: UsageTimezone, at: DateTime<Utc>) -> String {
+    format_label(timezone, at, "%Y-%m-%d")
+}
+
+/// "YYYY-MM-DDTHH" label of the hour `at` falls in
+pub(crate) fn hour_label(timezone: UsageTimezone, at: DateTime<Utc>) -> String {
+    format_label(timezone, at, "%Y-%m-%dT%H")
+}
+
+/// Day label of a request timestamp in milliseconds
+pub(crate) fn day_label_ms(timezone: UsageTimezone, timestamp_ms: u64) -> Option<String> {
+    DateTime::from_timestamp_millis(timestamp_ms as i64).map(|at| day_label(timezone, at))
+}
+
+/// Hour label of a request timestamp in milliseconds
+pub(crate) fn hour_label_ms(timezone: UsageTimezone, timestamp_ms: u64) -> Option<String> {
+    DateTime::from_timestamp_millis(timestamp_ms as i64).map(|at| hour_label(timezone, at))
+}
+
+/// Full hour label for a key from the proxy, which may be just "HH" of today
+pub(crate) fn normalize_hour_key(timezone: UsageTimezone, now: DateTime<Utc>, key: &str) -> String {
+    if key.len() == 2 {
+        format!("{}T{}", day_label(timezone, now), key)
+    } else {
+        key.to_string()
+    }
+}
+
+/// Value of today's point in a by-day series
+pub(crate) fn today_value(
+    series: &[TimeSeriesPoint],
+    timezone: UsageTimezone,
+    now: DateTime<Utc>,
+) -> u64 {
+    let today = day_label(timezone, now);
+    series
+        .iter()
+        .find(|p| p.label == today)
+        .map(|p| p.value)
+        .unwrap_or(0)
+}
+
+fn next_midnight_in<Z: TimeZone>(now: DateTime<Utc>, zone: &Z) -> DateTime<Utc> {
+    let tomorrow = now.with_timezone(zone).date_naive() + chrono::Days::new(1);
+    let midnight = tomorrow.and_hms_opt(0, 0, 0).unwrap_or_default();
+    zone.from_local_datetime(&midnight)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+        // Midnight skipped by a DST change; an hour later always exists
+        .unwrap_or_else(|| now + chrono::Duration::hours(1))
+}
+
+/// When the current usage day ends
+pub(crate) fn next_day_boundary(timezone: UsageTimezone, now: DateTime<Utc>) -> DateTime<Utc> {
+    match timezone {
+        UsageTimezone::Local => next_midnight_in(now, &chrono::Local),
+        UsageTimezone::Utc => next_midnight_in(now, &Utc),
+    }
+}
+
+/// Longest single wait, so a changed zone setting or a wake from sleep is
+/// noticed without waiting for the old boundary
+const MAX_ROLLOVER_WAIT: Duration = Duration::from_secs(10 * 60);
+
+/// Refresh everything that shows "today" once the usage day changes: the tray
+/// tooltip and status file, model caps, and the UI through `usage-day-changed`.
+pub fn start_day_rollover_watcher(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut today = day_label(current(), Utc::now());
+        loop {
+            let now = Utc::now();
+            let wait = (next_day_boundary(current(), now) - now)
+                .to_std()
+                .unwrap_or_default()
+                .clamp(Duration::from_secs(1), MAX_ROLLOVER_WAIT);
+            tokio::time::sleep(wait).await;
+
+            let label = day_label(current(), Utc::now());
+            if label == today {
+                continue;
+            }
+            today = label;
+            crate::tray::refresh_tray_tooltip(&app);
+            crate::status_file::refresh_status_file(&app);
+            crate::model_caps::on_day_changed(&app, &today);
+            let _ = app.emit("usage-day-changed", &today);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    // Stands in for a local zone seven hours behind UTC
+    fn pacific() -> FixedOffset {
+        FixedOffset::west_opt(7 * 3600).unwrap()
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn series(points: &[(&str, u64)]) -> Vec<TimeSeriesPoint> {
+        points
+            .iter()
+            .map(|(label, value)| TimeSeriesPoint {
+                label: label.to_string(),
+                value: *value,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn buckets_around_local_midnight() {
+        // 23:59 and 00:01 local are both in the same UTC day
+        let before = utc("2026-03-10T06:59:00Z");
+        let after = utc("2026-03-10T07:01:00Z");
+        assert_eq!(format_in(before, &pacific(), "%Y-%m-%d"), "2026-03-09");
+        assert_eq!(format_in(after, &pacific(), "%Y-%m-%d"), "2026-03-10");
+        assert_eq!(day_label(UsageTimezone::Utc, before), "2026-03-10");
+        assert_eq!(day_label(UsageTimezone::Utc, after), "2026-03-10");
+        assert_eq!(
+            next_midnight_in(before, &pacific()),
+            utc("2026-03-10T07:00:00Z")
+        );
+        assert_eq!(
+            next_midnight_in(after, &pacific()),
+            utc("2026-03-11T07:00:00Z")
+        );
+    }
+
+    #[test]
+    fn buckets_around_utc_midnight() {
+        // 23:59 and 00:01 UTC are 16:59 and 17:01 on one local day
+        let before = utc("2026-03-10T23:59:00Z");
+        let after = utc("2026-03-11T00:01:00Z");
+        assert_eq!(day_label(UsageTimezone::Utc, before), "2026-03-10");
+        assert_eq!(day_label(UsageTimezone::Utc, after), "2026-03-11");
+        assert_eq!(hour_label(UsageTimezone::Utc, after), "2026-03-11T00");
+        assert_eq!(format_in(before, &pacific(), "%Y-%m-%d"), "2026-03-10");
+        assert_eq!(format_in(after, &pacific(), "%Y-%m-%d"), "2026-03-10");
+        assert_eq!(
+            next_day_boundary(UsageTimezone::Utc, before),
+            utc("2026-03-11T00:00:00Z")
+        );
+
+        // A proxy keying by UTC has moved on to the 11th; "today" follows it
+        let by_day = series(&[("2026-03-10", 40), ("2026-03-11", 3)]);
+        assert_eq!(today_value(&by_day, UsageTimezone::Utc, before), 40);
+        assert_eq!(today_value(&by_day, UsageTimezone::Utc, after), 3);
+        assert_eq!(
+            normalize_hour_key(UsageTimezone::Utc, after, "00"),
+            "2026-03-11T00"
+        );
+        assert_eq!(
+            normalize_hour_key(UsageTimezone::Utc, after, "2026-03-10T23"),
+            "2026-03-10T23"
+        );
+    }
+
+    #[test]
+    fn parses_the_setting() {
+        assert_eq!(UsageTimezone::parse("utc"), UsageTimezone::Utc);
+        assert_eq!(UsageTimezone::parse("UTC"), UsageTimezone::Utc);
+        assert_eq!(UsageTimezone::parse("local"), UsageTimezone::Local);
+        assert_eq!(UsageTimezone::parse(""), UsageTimezone::Local);
+    }
+}
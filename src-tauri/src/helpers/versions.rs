@@ -0,0 +1,228 @@
+//! App and sidecar versions in request history.
+//!
+//! The log watcher stamps every request with the ProxyPal version and the
+//! CLIProxyAPI version the running proxy reported at start, and notes both
+//! per usage day in the aggregate. History queries can then filter or group
+//! by either, and [`version_changes`] compares error rate and latency before
+//! and after each upgrade inside a period. Requests recorded before stamping
+//! started fall under [`UNKNOWN`].
+
+use std::collections::HashMap;
+
+use crate::helpers::latency::percentile;
+use crate::types::{DayVersions, RequestLog, VersionChange, VersionDimension, VersionStats};
+
+/// Version of requests recorded without one
+pub(crate) const UNKNOWN: &str = "unknown";
+
+/// Stamp a request with this app's version and the sidecar's, if known
+pub(crate) fn stamp(request: &mut RequestLog, sidecar_version: Option<&str>) {
+    request.app_version = Some(env!("CARGO_PKG_VERSION").to_string());
+    request.sidecar_version = sidecar_version.map(str::to_string);
+}
+
+pub(crate) fn version_of(request: &RequestLog, dimension: VersionDimension) -> &str {
+    let version = match dimension {
+        VersionDimension::Sidecar => request.sidecar_version.as_deref(),
+        VersionDimension::App => request.app_version.as_deref(),
+    };
+    version.unwrap_or(UNKNOWN)
+}
+
+/// Note the versions of a request under its usage day
+pub(crate) fn record_day(
+    versions_by_day: &mut HashMap<String, DayVersions>,
+    day: &str,
+    request: &RequestLog,
+) {
+    fn note(seen: &mut Vec<String>, version: &str) {
+        if !seen.iter().any(|v| v == version) {
+            seen.push(version.to_string());
+        }
+    }
+    let app = version_of(request, VersionDimension::App);
+    let sidecar = version_of(request, VersionDimension::Sidecar);
+    let versions = versions_by_day.entry(day.to_string()).or_default();
+    note(&mut versions.app, app);
+    note(&mut versions.sidecar, sidecar);
+}
+
+fn stats_of<'a>(version: &str, requests: impl IntoIterator<Item = &'a RequestLog>) -> VersionStats {
+    let mut stats = VersionStats {
+        version: version.to_string(),
+        first_seen: u64::MAX,
+        ..VersionStats::default()
+    };
+    let mut durations = Vec::new();
+    let mut ttfbs = Vec::new();
+    for request in requests {
+        stats.requests += 1;
+        stats.first_seen = stats.first_seen.min(request.timestamp);
+        stats.last_seen = stats.last_seen.max(request.timestamp);
+        if request.status >= 400 {
+            stats.failure_count += 1;
+        } else {
+            durations.push(request.duration_ms);
+            ttfbs.extend(request.ttfb_ms);
+        }
+    }
+    if stats.requests == 0 {
+        stats.first_seen = 0;
+        return stats;
+    }
+    durations.sort_unstable();
+    ttfbs.sort_unstable();
+    stats.error_rate = stats.failure_count as f64 / stats.requests as f64;
+    stats.duration_p50_ms = percentile(&durations, 50.0).unwrap_or(0);
+    stats.duration_p90_ms = percentile(&durations, 90.0).unwrap_or(0);
+    stats.ttfb_p50_ms = percentile(&ttfbs, 50.0);
+    stats
+}
+
+fn real_requests_in(requests: &[RequestLog], since: u64, until: u64) -> Vec<&RequestLog> {
+    let mut in_range: Vec<&RequestLog> = requests
+        .iter()
+        .filter(|r| !r.synthetic && r.timestamp >= since && r.timestamp <= until)
+        .collect();
+    in_range.sort_by_key(|r| r.timestamp);
+    in_range
+}
+
+/// Error rate and latency per version for requests since `since` (Unix
+/// millis), in the order the versions were first seen
+pub(crate) fn version_stats(
+    requests: &[RequestLog],
+    dimension: VersionDimension,
+    since: u64,
+) -> Vec<VersionStats> {
+    let mut order: Vec<&str> = Vec::new();
+    let mut groups: HashMap<&str, Vec<&RequestLog>> = HashMap::new();
+    for request in real_requests_in(requests, since, u64::MAX) {
+        let version = version_of(request, dimension);
+        if !groups.contains_key(version) {
+            order.push(version);
+        }
+        groups.entry(version).or_default().push(request);
+    }
+    order
+        .into_iter()
+        .map(|version| stats_of(version, groups[version].iter().copied()))
+        .collect()
+}
+
+/// Every change of version between `since` and `until`, with the requests
+/// under the old version since the previous change (or `since`) against
+/// those under the new one until the next change (or `until`). Changes from
+/// or to [`UNKNOWN`] are left out: they mark when stamping began, not an
+/// upgrade.
+pub(crate) fn version_changes(
+    requests: &[RequestLog],
+    dimension: VersionDimension,
+    since: u64,
+    until: u64,
+) -> Vec<VersionChange> {
+    // Runs of consecutive requests under the same known version
+    let mut runs: Vec<(&str, Vec<&RequestLog>)> = Vec::new();
+    for request in real_requests_in(requests, since, until) {
+        let version = version_of(request, dimension);
+        if version == UNKNOWN {
+            continue;
+        }
+        match runs.last_mut() {
+            Some((current, run)) if *current == version => run.push(request),
+            _ => runs.push((version, vec![request])),
+        }
+    }
+    runs.windows(2)
+        .map(|pair| {
+            let (before, after) = (&pair[0], &pair[1]);
+            VersionChange {
+                dimension,
+                changed_at: after.1[0].timestamp,
+                before: stats_of(before.0, before.1.iter().copied()),
+                after: stats_of(after.0, after.1.iter().copied()),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(timestamp: u64, sidecar: Option<&str>, status: u16, duration_ms: u64) -> RequestLog {
+        RequestLog {
+            id: format!("req_{}", timestamp),
+            timestamp,
+            provider: "claude".to_string(),
+            model: "claude-sonnet-4-5".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status,
+            duration_ms,
+            ttfb_ms: None,
+            tokens_in: Some(10),
+            tokens_out: Some(10),
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: sidecar.map(|_| "0.4.0".to_string()),
+            sidecar_version: sidecar.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn unstamped_requests_group_under_unknown() {
+        let requests = vec![
+            request(1, None, 200, 900),
+            request(2, Some("6.7.1"), 200, 1000),
+            request(3, Some("6.7.1"), 500, 1000),
+        ];
+        let stats = version_stats(&requests, VersionDimension::Sidecar, 0);
+        let versions: Vec<&str> = stats.iter().map(|s| s.version.as_str()).collect();
+        assert_eq!(versions, ["unknown", "6.7.1"]);
+        assert_eq!(stats[1].requests, 2);
+        assert_eq!(stats[1].failure_count, 1);
+        assert_eq!(stats[1].error_rate, 0.5);
+
+        let mut versions_by_day = HashMap::new();
+        for r in &requests {
+            record_day(&mut versions_by_day, "2026-03-10", r);
+        }
+        assert_eq!(versions_by_day["2026-03-10"].sidecar, ["unknown", "6.7.1"]);
+        assert_eq!(versions_by_day["2026-03-10"].app, ["unknown", "0.4.0"]);
+    }
+
+    #[test]
+    fn compares_requests_before_and_after_an_upgrade() {
+        let requests = vec![
+            request(1, None, 200, 500),
+            request(2, Some("6.7.1"), 200, 1000),
+            request(3, Some("6.7.1"), 200, 1200),
+            request(4, Some("6.8.0"), 500, 3000),
+            request(5, Some("6.8.0"), 200, 3000),
+        ];
+        let changes = version_changes(&requests, VersionDimension::Sidecar, 0, u64::MAX);
+        assert_eq!(changes.len(), 1, "unknown -> 6.7.1 is not an upgrade");
+        let change = &changes[0];
+        assert_eq!(change.changed_at, 4);
+        assert_eq!(
+            (
+                change.before.version.as_str(),
+                change.after.version.as_str()
+            ),
+            ("6.7.1", "6.8.0")
+        );
+        assert_eq!(change.before.error_rate, 0.0);
+        assert_eq!(change.after.error_rate, 0.5);
+        assert_eq!(change.after.duration_p50_ms, 3000);
+
+        // Outside the period there is nothing to compare
+        assert!(version_changes(&requests, VersionDimension::Sidecar, 4, u64::MAX).is_empty());
+    }
+}
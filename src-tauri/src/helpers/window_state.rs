@@ -0,0 +1,290 @@
+//! Window geometry persistence and the mini monitor window.
+//!
+//! Windows save their position and size to the config shortly after the user
+//! stops moving or resizing them, and get them back on the next launch unless
+//! the screen they were on is gone. Geometry is in physical pixels, as the
+//! window and monitor APIs report it.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, WindowEvent,
+};
+
+use crate::config::AppConfig;
+use crate::state::AppState;
+use crate::types::WindowGeometry;
+
+pub(crate) const MAIN_WINDOW: &str = "main";
+pub(crate) const MINI_MONITOR_WINDOW: &str = "mini-monitor";
+
+// Moves and resizes arrive many times a second while dragging
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Width and height of the window's top edge that must be on a screen, so the
+/// title bar can still be grabbed
+const MIN_VISIBLE: i64 = 64;
+
+/// Saved sizes below this are treated as garbage rather than restored
+const MIN_WIDTH: u32 = 200;
+const MIN_HEIGHT: u32 = 120;
+
+/// A monitor's work area, in physical pixels
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Screen {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// `saved`, shrunk to fit the screen most of its title bar is on, or None when
+/// the title bar is on no screen (a monitor was unplugged or rearranged)
+pub(crate) fn fit_to_screens(saved: WindowGeometry, screens: &[Screen]) -> Option<WindowGeometry> {
+    if saved.width < MIN_WIDTH || saved.height < MIN_HEIGHT {
+        return None;
+    }
+    let (left, top) = (saved.x as i64, saved.y as i64);
+    let right = left + saved.width as i64;
+    let (_, screen) = screens
+        .iter()
+        .filter_map(|s| {
+            let (s_left, s_top) = (s.x as i64, s.y as i64);
+            let (s_right, s_bottom) = (s_left + s.width as i64, s_top + s.height as i64);
+            let overlap = right.min(s_right) - left.max(s_left);
+            (overlap >= MIN_VISIBLE && top >= s_top && top <= s_bottom - MIN_VISIBLE)
+                .then_some((overlap, s))
+        })
+        .max_by_key(|(overlap, _)| *overlap)?;
+    Some(WindowGeometry {
+        width: saved.width.min(screen.width),
+        height: saved.height.min(screen.height),
+        ..saved
+    })
+}
+
+fn screens(window: &WebviewWindow) -> Vec<Screen> {
+    window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let area = monitor.work_area();
+            Screen {
+                x: area.position.x,
+                y: area.position.y,
+                width: area.size.width,
+                height: area.size.height,
+            }
+        })
+        .collect()
+}
+
+/// Move and resize `window` to `saved` if that is still on a screen
+pub(crate) fn restore(window: &WebviewWindow, saved: Option<WindowGeometry>) {
+    let Some(geometry) = saved.and_then(|saved| fit_to_screens(saved, &screens(window))) else {
+        return;
+    };
+    let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Restore the main window once the config is loaded
+pub(crate) fn restore_main_window(app: &AppHandle) {
+    let saved = app.state::<AppState>().config.lock().main_window;
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        restore(&window, saved);
+    }
+}
+
+fn geometry_slot<'a>(
+    config: &'a mut AppConfig,
+    label: &str,
+) -> Option<&'a mut Option<WindowGeometry>> {
+    match label {
+        MAIN_WINDOW => Some(&mut config.main_window),
+        MINI_MONITOR_WINDOW => Some(&mut config.mini_monitor_window),
+        _ => None,
+    }
+}
+
+fn save(window: &WebviewWindow) {
+    let app = window.app_handle();
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    // Saving before startup loaded the config would write defaults over it
+    if state.ensure_hydrated().is_err() || window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    // Ask the window before taking the config lock; these calls wait on the main thread
+    let maximized = window.is_maximized().unwrap_or(false);
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+
+    let config = {
+        let mut config = state.config.lock();
+        let Some(slot) = geometry_slot(&mut config, window.label()) else {
+            return;
+        };
+        let next = match *slot {
+            // Keep the size to go back to when the window is unmaximized
+            Some(previous) if maximized => WindowGeometry {
+                maximized: true,
+                ..previous
+            },
+            _ => WindowGeometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized,
+            },
+        };
+        if *slot == Some(next) {
+            return;
+        }
+        *slot = Some(next);
+        config.clone()
+    };
+    if let Err(e) = crate::config::save_config_to_file(&config) {
+        eprintln!("[ProxyPal] Failed to save window position: {}", e);
+    }
+}
+
+/// Save `window`'s geometry once it has stopped moving or resizing
+pub(crate) fn track(window: &WebviewWindow) {
+    let pending = Arc::new(AtomicU64::new(0));
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if !matches!(event, WindowEvent::Moved(_) | WindowEvent::Resized(_)) {
+            return;
+        }
+        let generation = pending.fetch_add(1, Ordering::SeqCst) + 1;
+        let (pending, window) = (pending.clone(), handle.clone());
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(SAVE_DEBOUNCE).await;
+            if pending.load(Ordering::SeqCst) == generation {
+                save(&window);
+            }
+        });
+    });
+}
+
+/// Show the mini monitor, creating it on first use
+pub(crate) fn open_mini_monitor(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(MINI_MONITOR_WINDOW) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+    // The frontend renders the live request feed for this window's label
+    let window = WebviewWindowBuilder::new(
+        app,
+        MINI_MONITOR_WINDOW,
+        WebviewUrl::App("index.html".into()),
+    )
+    .title("ProxyPal Monitor")
+    .inner_size(380.0, 460.0)
+    .min_inner_size(280.0, 200.0)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible(false)
+    .build()
+    .map_err(|e| format!("Failed to open the mini monitor: {}", e))?;
+
+    let saved = app.state::<AppState>().config.lock().mini_monitor_window;
+    restore(&window, saved);
+    track(&window);
+    let _ = window.show();
+    Ok(())
+}
+
+/// Close the mini monitor if it is open, open it otherwise
+pub(crate) fn toggle_mini_monitor(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MINI_MONITOR_WINDOW) {
+        let _ = window.close();
+    } else if let Err(e) = open_mini_monitor(app) {
+        eprintln!("[ProxyPal] {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAPTOP: Screen = Screen {
+        x: 0,
+        y: 0,
+        width: 2560,
+        height: 1600,
+    };
+    // Second display to the left of the laptop
+    const EXTERNAL: Screen = Screen {
+        x: -1920,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+
+    fn geometry(x: i32, y: i32, width: u32, height: u32) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+        }
+    }
+
+    #[test]
+    fn restores_windows_on_any_connected_screen() {
+        let on_external = geometry(-1500, 100, 800, 640);
+        assert_eq!(
+            fit_to_screens(on_external, &[LAPTOP, EXTERNAL]),
+            Some(on_external)
+        );
+        // Hanging off the right edge is fine while the title bar can be grabbed
+        let mostly_off = geometry(2400, 40, 800, 640);
+        assert_eq!(fit_to_screens(mostly_off, &[LAPTOP]), Some(mostly_off));
+    }
+
+    #[test]
+    fn drops_geometry_for_a_missing_screen() {
+        // The external display was unplugged
+        assert_eq!(
+            fit_to_screens(geometry(-1500, 100, 800, 640), &[LAPTOP]),
+            None
+        );
+        // Title bar above the top of the screen
+        assert_eq!(
+            fit_to_screens(geometry(100, -200, 800, 640), &[LAPTOP]),
+            None
+        );
+        assert_eq!(fit_to_screens(geometry(100, 100, 800, 640), &[]), None);
+        assert_eq!(fit_to_screens(geometry(100, 100, 10, 10), &[LAPTOP]), None);
+    }
+
+    #[test]
+    fn shrinks_windows_larger_than_their_screen() {
+        let big = WindowGeometry {
+            maximized: true,
+            ..geometry(-1900, 0, 2560, 1600)
+        };
+        assert_eq!(
+            fit_to_screens(big, &[LAPTOP, EXTERNAL]),
+            Some(WindowGeometry {
+                maximized: true,
+                ..geometry(-1900, 0, 1920, 1080)
+            })
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Shared HTTP clients.
+//!
+//! A `reqwest::Client` owns a connection pool and TLS state, so building one
+//! per command throws both away on every dashboard poll. `AppState` holds one
+//! `HttpClients`, built on first use and cloned cheaply into background tasks:
+//! - `local`: the sidecar on 127.0.0.1, never routed through any proxy
+//! - `external`: provider, update and webhook endpoints, routed as
+//!   `AppConfig.network_proxy` says; rebuilt by [`HttpClients::set_network_proxy`]
+//!   when that changes
+//!
+//! Management API calls go through [`HttpClients::management`] and
+//! [`send_management`], which add the key header and turn non-2xx replies
+//! into [`ManagementError::Status`].
+
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use reqwest::{Client, Method, NoProxy, Proxy, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+use crate::types::{NetworkProxy, NetworkProxyMode};
+
+const LOCAL_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const LOCAL_TIMEOUT: Duration = Duration::from_secs(10);
+const EXTERNAL_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const EXTERNAL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Hosts a manual proxy is never used for
+const LOOPBACK_HOSTS: &[&str] = &["localhost", "127.0.0.1", "::1"];
+
+/// Refuse a manual proxy URL reqwest can't use
+pub(crate) fn validate_network_proxy(settings: &NetworkProxy) -> Result<(), String> {
+    if settings.mode != NetworkProxyMode::Manual {
+        return Ok(());
+    }
+    if settings.url.trim().is_empty() {
+        return Err("A manual network proxy needs a URL".to_string());
+    }
+    manual_proxy(settings).map(|_| ())
+}
+
+fn manual_proxy(settings: &NetworkProxy) -> Result<Proxy, String> {
+    let bypass: Vec<&str> = LOOPBACK_HOSTS
+        .iter()
+        .copied()
+        .chain(settings.no_proxy.iter().map(|h| h.trim()))
+        .filter(|h| !h.is_empty())
+        .collect();
+    Proxy::all(settings.url.trim())
+        .map(|proxy| proxy.no_proxy(NoProxy::from_string(&bypass.join(","))))
+        .map_err(|e| format!("Invalid network proxy URL: {}", e))
+}
+
+// How a client reaches external hosts
+enum Route {
+    System,
+    Direct,
+    Via(Box<Proxy>),
+}
+
+fn route(settings: &NetworkProxy) -> Route {
+    match settings.mode {
+        NetworkProxyMode::System => Route::System,
+        NetworkProxyMode::None => Route::Direct,
+        // An invalid URL is refused when saving; fall back to a direct
+        // connection rather than a surprise system proxy
+        NetworkProxyMode::Manual => manual_proxy(settings).map_or_else(
+            |e| {
+                eprintln!("[ProxyPal] {}; connecting directly", e);
+                Route::Direct
+            },
+            |proxy| Route::Via(Box::new(proxy)),
+        ),
+    }
+}
+
+/// Blocking client builder for external hosts, routed like
+/// [`HttpClients::external`]
+pub(crate) fn blocking_external_builder(
+    settings: &NetworkProxy,
+) -> reqwest::blocking::ClientBuilder {
+    let builder = reqwest::blocking::Client::builder();
+    match route(settings) {
+        Route::System => builder,
+        Route::Direct => builder.no_proxy(),
+        Route::Via(proxy) => builder.no_proxy().proxy(*proxy),
+    }
+}
+
+fn build_external(settings: &NetworkProxy) -> Client {
+    let builder = Client::builder()
+        .connect_timeout(EXTERNAL_CONNECT_TIMEOUT)
+        .timeout(EXTERNAL_TIMEOUT);
+    let builder = match route(settings) {
+        Route::System => builder,
+        Route::Direct => builder.no_proxy(),
+        Route::Via(proxy) => builder.no_proxy().proxy(*proxy),
+    };
+    builder.build().unwrap_or_else(|_| Client::new())
+}
+
+// Settings the external client was built from, and the client once built
+#[derive(Default)]
+struct External {
+    settings: NetworkProxy,
+    client: Option<Client>,
+}
+
+/// Lazily built clients shared by every command. Requests needing a different
+/// overall timeout set it with `RequestBuilder::timeout`.
+#[derive(Clone, Default)]
+pub struct HttpClients {
+    local: Arc<OnceLock<Client>>,
+    external: Arc<RwLock<External>>,
+}
+
+impl HttpClients {
+    /// Client for the local sidecar. Bypasses the system proxy, which would
+    /// answer 127.0.0.1 requests with a 502.
+    pub fn local(&self) -> &Client {
+        self.local.get_or_init(|| {
+            Client::builder()
+                .no_proxy()
+                .connect_timeout(LOCAL_CONNECT_TIMEOUT)
+                .timeout(LOCAL_TIMEOUT)
+                .build()
+                .unwrap_or_else(|_| Client::new())
+        })
+    }
+
+    /// Client for upstream providers, update checks and webhooks. Cheap to
+    /// clone; the pool is shared.
+    pub fn external(&self) -> Client {
+        if let Some(client) = &self.external.read().client {
+            return client.clone();
+        }
+        let mut external = self.external.write();
+        let client = build_external(&external.settings);
+        external.client.get_or_insert(client).clone()
+    }
+
+    /// Route external requests per `settings` from now on
+    pub fn set_network_proxy(&self, settings: &NetworkProxy) {
+        let mut external = self.external.write();
+        if external.settings != *settings {
+            external.settings = settings.clone();
+            external.client = None;
+        }
+    }
+
+    /// Request to `/v0/management/<endpoint>` with the management key set
+    pub fn management(&self, method: Method, port: u16, endpoint: &str) -> RequestBuilder {
+        self.local()
+            .request(method, get_management_url(port, endpoint))
+            .header("X-Management-Key", get_management_key())
+    }
+}
+
+/// Management key from config, sent with every Management API call
+pub(crate) fn get_management_key() -> String {
+    crate::config::load_config().management_key
+}
+
+/// Management API URL for `endpoint` on the local sidecar
+pub(crate) fn get_management_url(port: u16, endpoint: &str) -> String {
+    format!("http://127.0.0.1:{}/v0/management/{}", port, endpoint)
+}
+
+/// Why a Management API call failed
+#[derive(Debug)]
+pub enum ManagementError {
+    /// The sidecar could not be reached or did not answer in time
+    Unreachable(String),
+    /// The sidecar answered with a non-2xx status
+    Status { status: StatusCode, body: String },
+    /// The reply body was not what the caller expected
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for ManagementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreachable(e) => write!(f, "{}", e),
+            Self::Status { status, body } if body.is_empty() => write!(f, "{}", status),
+            Self::Status { status, body } => write!(f, "{} - {}", status, body),
+            Self::InvalidResponse(e) => write!(f, "invalid response: {}", e),
+        }
+    }
+}
+
+impl From<ManagementError> for String {
+    fn from(e: ManagementError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Send a Management API request; non-2xx replies become `Status` errors
+pub async fn send_management(request: RequestBuilder) -> Result<Response, ManagementError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ManagementError::Unreachable(e.to_string()))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(ManagementError::Status {
+            status,
+            body: body.trim().to_string(),
+        });
+    }
+    Ok(response)
+}
+
+/// Send a Management API request and decode the JSON reply
+pub async fn management_json<T: DeserializeOwned>(
+    request: RequestBuilder,
+) -> Result<T, ManagementError> {
+    send_management(request)
+        .await?
+        .json()
+        .await
+        .map_err(|e| ManagementError::InvalidResponse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    // Answer one request on a local port with a canned HTTP response
+    fn serve_once(response: &'static str) -> u16 {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn non_success_status_is_a_typed_error() {
+        let port = serve_once(
+            "HTTP/1.1 403 Forbidden\r\nContent-Length: 15\r\nConnection: close\r\n\r\ninvalid key\r\n\r\n",
+        );
+        let http = HttpClients::default();
+        let result = send_management(http.management(Method::GET, port, "config.yaml")).await;
+        match result {
+            Err(ManagementError::Status { status, body }) => {
+                assert_eq!(status, StatusCode::FORBIDDEN);
+                assert_eq!(body, "invalid key");
+            }
+            other => panic!("expected a status error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn decodes_success_and_reports_unreachable_sidecar() {
+        let port = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 13\r\nConnection: close\r\n\r\n{\"value\":42}\n",
+        );
+        let http = HttpClients::default();
+        let json: serde_json::Value = management_json(http.management(Method::GET, port, "x"))
+            .await
+            .unwrap();
+        assert_eq!(json["value"], 42);
+
+        // Nothing listens on a port that was just released
+        let closed = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let result = send_management(http.management(Method::GET, closed, "x")).await;
+        assert!(matches!(result, Err(ManagementError::Unreachable(_))));
+    }
+
+    #[test]
+    fn manual_proxy_needs_a_usable_url() {
+        let mut settings = NetworkProxy::default();
+        assert!(validate_network_proxy(&settings).is_ok());
+
+        settings.mode = NetworkProxyMode::Manual;
+        assert!(validate_network_proxy(&settings).is_err());
+        settings.url = "http://proxy.corp.example:3128".to_string();
+        settings.no_proxy = vec![".corp.example".to_string(), "10.0.0.0/8".to_string()];
+        assert!(validate_network_proxy(&settings).is_ok());
+        settings.url = "not a proxy".to_string();
+        assert!(validate_network_proxy(&settings).is_err());
+    }
+
+    #[tokio::test]
+    async fn external_requests_follow_the_manual_proxy() {
+        let proxy_port =
+            serve_once("HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nproxied");
+        let http = HttpClients::default();
+        http.set_network_proxy(&NetworkProxy {
+            mode: NetworkProxyMode::Manual,
+            url: format!("http://127.0.0.1:{}", proxy_port),
+            no_proxy: Vec::new(),
+        });
+        let body = http
+            .external()
+            .get("http://updates.example.invalid/latest")
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(body, "proxied");
+    }
+}
@@ -0,0 +1,188 @@
+//! Warning for a proxy that runs while its agents go around it.
+//!
+//! Everything can look fine while an agent that was reinstalled, or
+//! reconfigured by another tool, sends its requests straight to the provider.
+//! While the proxy runs and `AppConfig.idle_alert_minutes` is set, a
+//! background task checks every minute how long it has been since the later
+//! of the proxy start and the last observed request. Once that reaches the
+//! threshold and some detected agent reports itself configured,
+//! `idle-warning` is emitted along with a notification pointing at the
+//! endpoint audit, once per idle stretch. Nothing is reported within
+//! `STARTUP_GRACE` of a start, outside the schedule's windows, or while
+//! request logging is off (the proxy then reports no requests at all). Demo
+//! traffic doesn't count as a request.
+
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::AppConfig;
+use crate::state::AppState;
+use crate::types::{IdleWarning, RequestLog, ScheduleStatus};
+
+/// How often the idle time is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Quiet period after a proxy start, whatever the threshold
+const STARTUP_GRACE: Duration = Duration::from_secs(10 * 60);
+
+/// Task and request bookkeeping, kept in `AppState`
+#[derive(Default)]
+pub struct IdleWatchdog {
+    task: Option<tauri::async_runtime::JoinHandle<()>>,
+    last_request: Option<Instant>,
+    // This idle stretch was checked (and warned about, if any agent was
+    // configured); cleared by the next request
+    settled: bool,
+}
+
+/// Minutes without requests, once they reach `threshold_minutes`
+fn idle_minutes(
+    threshold_minutes: u32,
+    started_at: Instant,
+    last_request: Option<Instant>,
+    now: Instant,
+) -> Option<u64> {
+    if threshold_minutes == 0 || now.saturating_duration_since(started_at) < STARTUP_GRACE {
+        return None;
+    }
+    let since = last_request.map_or(started_at, |last| last.max(started_at));
+    let idle = now.saturating_duration_since(since);
+    (idle >= Duration::from_secs(threshold_minutes as u64 * 60)).then_some(idle.as_secs() / 60)
+}
+
+/// Whether no requests are expected, or none would be observed
+fn suppressed(config: &AppConfig, schedule: &ScheduleStatus) -> bool {
+    !config.request_logging || config.commercial_mode || (schedule.enabled && !schedule.in_window)
+}
+
+// Idle minutes, when a warning may be due
+fn due(state: &AppState) -> Option<u64> {
+    // The schedule status locks the config itself
+    let schedule = crate::scheduler::status(state);
+    let threshold = {
+        let config = state.config.lock();
+        if suppressed(&config, &schedule) {
+            return None;
+        }
+        config.idle_alert_minutes
+    };
+    let started_at = (*state.proxy_started_at.lock())?;
+    let watchdog = state.idle_watchdog.lock();
+    if watchdog.settled {
+        return None;
+    }
+    idle_minutes(threshold, started_at, watchdog.last_request, Instant::now())
+}
+
+// Names of the detected agents that report being set up for the proxy
+async fn configured_agents(app: &AppHandle) -> Vec<String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::commands::agents::detect_cli_agents(app.state::<AppState>())
+    })
+    .await
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|agent| agent.configured)
+    .map(|agent| agent.name)
+    .collect()
+}
+
+/// Note a batch of observed requests
+pub(crate) fn record_requests(app: &AppHandle, batch: &[RequestLog]) {
+    if batch.iter().all(|r| r.synthetic) {
+        return;
+    }
+    if let Some(state) = app.try_state::<AppState>() {
+        let mut watchdog = state.idle_watchdog.lock();
+        watchdog.last_request = Some(Instant::now());
+        watchdog.settled = false;
+    }
+}
+
+/// Start watching for an idle proxy, replacing any earlier watchdog. The
+/// task ends on its own once the proxy stops.
+pub fn start_idle_watchdog(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let app = app.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let Some(state) = app.try_state::<AppState>() else {
+                return;
+            };
+            if !state.proxy_status.lock().running {
+                return;
+            }
+            let Some(idle_minutes) = due(&state) else {
+                continue;
+            };
+
+            let configured_agents = configured_agents(&app).await;
+            state.idle_watchdog.lock().settled = true;
+            if configured_agents.is_empty() {
+                continue;
+            }
+            let warning = IdleWarning {
+                idle_minutes,
+                configured_agents,
+                timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            };
+            let _ = app.emit("idle-warning", &warning);
+            crate::notifications::notify_idle_warning(&app, &warning);
+        }
+    });
+    let mut watchdog = state.idle_watchdog.lock();
+    watchdog.settled = false;
+    if let Some(old) = watchdog.task.replace(handle) {
+        old.abort();
+    }
+}
+
+/// Stop watching (proxy stopped)
+pub fn stop_idle_watchdog(state: &AppState) {
+    if let Some(handle) = state.idle_watchdog.lock().task.take() {
+        handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn counts_idle_time_from_the_last_request() {
+        let started = Instant::now();
+        let at = |minutes: u32| started + minutes * MINUTE;
+        assert_eq!(idle_minutes(30, started, None, at(31)), Some(31));
+        assert_eq!(idle_minutes(30, started, Some(at(20)), at(31)), None);
+        assert_eq!(idle_minutes(30, started, Some(at(20)), at(50)), Some(30));
+
+        // Never right after a start, nor when switched off
+        assert_eq!(idle_minutes(1, started, None, at(5)), None);
+        assert_eq!(idle_minutes(0, started, None, at(600)), None);
+    }
+
+    #[test]
+    fn stays_quiet_outside_the_schedule_and_without_request_logging() {
+        let mut config = AppConfig::default();
+        let mut schedule = ScheduleStatus::default();
+        assert!(!suppressed(&config, &schedule));
+
+        schedule.enabled = true;
+        assert!(suppressed(&config, &schedule));
+        schedule.in_window = true;
+        assert!(!suppressed(&config, &schedule));
+
+        config.request_logging = false;
+        assert!(suppressed(&config, &schedule));
+        config.request_logging = true;
+        config.commercial_mode = true;
+        assert!(suppressed(&config, &schedule));
+    }
+}
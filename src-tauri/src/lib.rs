@@ -1,40 +1,50 @@
+mod app_update;
 mod commands;
 mod config;
+mod control_api;
+mod control_socket;
+mod deep_link;
+mod error;
+mod event_stream;
+mod failover;
+mod headless;
+mod health_monitor;
 mod helpers;
+mod http;
+mod idle_watchdog;
+mod management_gateway;
+mod mcp;
+mod metrics;
+mod model_caps;
+mod model_catalog;
+mod notifications;
+mod providers;
 mod proxy;
+mod rate_limits;
+mod redact;
+mod request_watches;
+mod scheduler;
+mod secrets;
+mod session_journal;
+mod sidecar_download;
 mod state;
+mod status_file;
+mod tray;
 mod types;
+mod usage_anomaly;
 mod utils;
+mod webhooks;
 mod ssh_manager;
+mod startup;
 mod cloudflare_manager;
 
-use crate::config::{get_auth_path, load_config};
-use crate::helpers::migration::migrate_to_split_storage;
 use crate::state::AppState;
-use crate::types::{ProxyStatus, AuthStatus, CopilotStatus};
 use crate::ssh_manager::SshManager;
 use crate::cloudflare_manager::CloudflareManager;
-use std::sync::Mutex;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Emitter, Manager,
-};
+use std::sync::atomic::Ordering;
+use tauri::Manager;
 
-/// Get management key from config (used for internal proxy API calls)
-pub(crate) fn get_management_key() -> String {
-    load_config().management_key
-}
-
-// Windows-specific imports for hiding CMD windows
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
-
-// Windows CREATE_NO_WINDOW flag
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+pub use headless::run_cli;
 
 // GPT-5 base models that support reasoning level suffixes (single source of truth)
 // Used by both backend (proxy config generation) and frontend (Settings UI)
@@ -59,212 +69,17 @@ pub(crate) const GPT5_BASE_MODELS: &[&str] = &[
 // GPT-5 reasoning level suffixes
 pub(crate) const GPT5_REASONING_SUFFIXES: &[&str] = &["minimal", "low", "medium", "high", "xhigh"];
 
-// Load auth status from file
-pub(crate) fn load_auth_status() -> AuthStatus {
-    let path = get_auth_path();
-    if path.exists() {
-        if let Ok(data) = std::fs::read_to_string(&path) {
-            if let Ok(auth) = serde_json::from_str(&data) {
-                return auth;
-            }
-        }
-    }
-    AuthStatus::default()
-}
-
-// Save auth status to file
-pub(crate) fn save_auth_to_file(auth: &AuthStatus) -> Result<(), String> {
-    let path = get_auth_path();
-    let data = serde_json::to_string_pretty(auth).map_err(|e| e.to_string())?;
-    std::fs::write(path, data).map_err(|e| e.to_string())
-}
-
-// Handle deep link OAuth callback
-fn handle_deep_link(app: &tauri::AppHandle, urls: Vec<url::Url>) {
-    for url in urls {
-        if url.scheme() == "proxypal" && url.path() == "/oauth/callback" {
-            // Parse query parameters
-            let params: std::collections::HashMap<_, _> = url.query_pairs().collect();
-
-            if let (Some(code), Some(state)) = (params.get("code"), params.get("state")) {
-                // Verify state and get provider from pending OAuth
-                let app_state = app.state::<AppState>();
-                let pending = app_state.pending_oauth.lock().unwrap().clone();
-
-                if let Some(oauth) = pending {
-                    if oauth.state == state.as_ref() {
-                        // Emit event to frontend
-                        let _ = app.emit(
-                            "oauth-callback",
-                            serde_json::json!({
-                                "provider": oauth.provider,
-                                "code": code.as_ref()
-                            }),
-                        );
-                    }
-                }
-            }
-
-            // Bring window to front
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
-            }
-        }
-    }
-}
-
-// Setup system tray
-fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    let toggle_item = MenuItem::with_id(app, "toggle", "Toggle Proxy", true, None::<&str>)?;
-    let dashboard_item = MenuItem::with_id(app, "dashboard", "Open Dashboard", true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit ProxyPal", true, None::<&str>)?;
-
-    let menu = Menu::with_items(app, &[&toggle_item, &dashboard_item, &quit_item])?;
-
-    // Use dedicated tray icon (22x22 @1x, 44x44 @2x for retina)
-    let tray_icon = tauri::image::Image::from_bytes(include_bytes!("../icons/tray-icon@2x.png"))
-        .expect("Failed to load tray icon");
-
-    // icon_as_template is macOS-only (renders icon as template image for dark/light mode).
-    // On Windows/Linux the concept doesn't exist — calling it causes a transparent/invisible tray icon.
-    #[allow(unused_mut)]
-    let mut tray_builder = TrayIconBuilder::new()
-        .icon(tray_icon);
-    #[cfg(target_os = "macos")]
-    {
-        tray_builder = tray_builder.icon_as_template(true);
-    }
-    let _tray = tray_builder
-        .menu(&menu)
-        .show_menu_on_left_click(false)
-        .tooltip("ProxyPal - Proxy stopped")
-        .on_menu_event(move |app, event| match event.id.as_ref() {
-            "toggle" => {
-                let app_state = app.state::<AppState>();
-                let is_running = app_state.proxy_status.lock().unwrap().running;
-
-                // Emit toggle event to frontend
-                let _ = app.emit("tray-toggle-proxy", !is_running);
-            }
-            "dashboard" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.unminimize();
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
-            "quit" => {
-                app.exit(0);
-            }
-            _ => {}
-        })
-        .on_tray_icon_event(|tray, event| {
-            if let TrayIconEvent::Click {
-                button: MouseButton::Left,
-                button_state: MouseButtonState::Up,
-                ..
-            } = event
-            {
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.unminimize();
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
-        })
-        .build(app)?;
-
-    Ok(())
-}
 
-// Helper to build HTTP client for Management API
-// Uses no_proxy() to prevent local 127.0.0.1 requests from being
-// routed through the user's system proxy (which causes 502 errors)
-pub(crate) fn build_management_client() -> reqwest::Client {
-    reqwest::Client::builder()
-        .no_proxy()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .unwrap_or_else(|_| reqwest::Client::new())
-}
-
-// Helper to get Management API base URL
-pub(crate) fn get_management_url(port: u16, endpoint: &str) -> String {
-    format!("http://127.0.0.1:{}/v0/management/{}", port, endpoint)
-}
-
-
-// Check if auto-updater is supported on this platform/install type
-// Linux .deb installations do NOT support auto-update (only AppImage does)
-#[tauri::command]
-fn is_updater_supported() -> Result<serde_json::Value, String> {
-    #[cfg(target_os = "linux")]
-    {
-        // On Linux, check if running as AppImage (APPIMAGE env var is set)
-        let is_appimage = std::env::var("APPIMAGE").is_ok();
-        Ok(serde_json::json!({
-            "supported": is_appimage,
-            "reason": if is_appimage { 
-                "AppImage supports auto-update" 
-            } else { 
-                "Auto-update is only supported for AppImage installations. Please download the new version manually from GitHub Releases." 
-            }
-        }))
-    }
-    #[cfg(not(target_os = "linux"))]
-    {
-        // Windows and macOS support auto-update
-        Ok(serde_json::json!({
-            "supported": true,
-            "reason": "Auto-update supported"
-        }))
-    }
-}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Migrate old format to split storage on first run
-    migrate_to_split_storage();
-
-    // Clean up any orphaned clipproxyapi processes from previous crashes
-    #[cfg(unix)]
-    {
-        println!("[ProxyPal] Cleaning up orphaned clipproxyapi processes on startup");
-        let _ = std::process::Command::new("sh")
-            .args(["-c", "pkill -9 -f clipproxyapi 2>/dev/null"])
-            .spawn()
-            .and_then(|mut child| child.wait());
-    }
-    #[cfg(windows)]
-    {
-        let mut cmd = std::process::Command::new("cmd");
-        cmd.args(["/C", "taskkill /F /IM clipproxyapi*.exe 2>nul"]);
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        let _ = cmd.spawn().and_then(|mut child| child.wait());
-    }
-
-    // Load persisted config and auth
-    let config = load_config();
-    let auth = load_auth_status();
-
-    let app_state = AppState {
-        proxy_status: Mutex::new(ProxyStatus::default()),
-        auth_status: Mutex::new(auth),
-        config: Mutex::new(config),
-        pending_oauth: Mutex::new(None),
-        proxy_process: Mutex::new(None),
-        copilot_status: Mutex::new(CopilotStatus::default()),
-        copilot_process: Mutex::new(None),
-        log_watcher_running: Arc::new(AtomicBool::new(false)),
-        request_counter: Arc::new(AtomicU64::new(0)),
-    };
+    // Nothing is read from disk before the builder runs; startup::hydrate
+    // loads config and auth once the window is up
+    let app_state = startup::initial_state();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_notification::init())
@@ -273,6 +88,10 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![crate::helpers::autostart::MINIMIZED_ARG]),
+        ))
         .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
             // Handle deep links when app is already running
             let urls: Vec<url::Url> = args
@@ -280,7 +99,7 @@ pub fn run() {
                 .filter_map(|arg| url::Url::parse(arg).ok())
                 .collect();
             if !urls.is_empty() {
-                handle_deep_link(app, urls);
+                deep_link::handle_deep_link(app, urls);
             }
 
             // Show existing window
@@ -296,7 +115,43 @@ pub fn run() {
         .setup(|app| {
             // Setup system tray
             #[cfg(desktop)]
-            setup_tray(app)?;
+            tray::setup_tray(app)?;
+
+            // Hide to tray on close when close_to_tray is enabled. The setting is read on
+            // every close so toggling it takes effect without a restart.
+            // Quitting from the tray menu goes through app.exit(), which still runs the
+            // sidecar cleanup in RunEvent::ExitRequested below.
+            #[cfg(desktop)]
+            {
+                if let Some(window) = app.get_webview_window("main") {
+                    let handle = app.handle().clone();
+                    window.on_window_event(move |event| match event {
+                        tauri::WindowEvent::CloseRequested { api, .. }
+                            if tray::hide_to_tray_on_close(&handle) =>
+                        {
+                            api.prevent_close();
+                        }
+                        // Activation after a notification click routes its link
+                        tauri::WindowEvent::Focused(true) => {
+                            notifications::open_pending_link(&handle);
+                        }
+                        // Light/dark tray glyphs follow the system theme
+                        tauri::WindowEvent::ThemeChanged(theme) => {
+                            tray::set_tray_theme(&handle, *theme);
+                        }
+                        _ => {}
+                    });
+                    // Remember where the window was for the next launch
+                    crate::helpers::window_state::track(&window);
+                }
+            }
+
+            // Launched by the OS autostart entry: stay in the tray
+            if crate::helpers::autostart::launched_minimized() {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+            }
 
             // Register deep link handler for when app is already running
             #[cfg(desktop)]
@@ -306,51 +161,15 @@ pub fn run() {
                 app.deep_link().on_open_url(move |event| {
                     let urls: Vec<url::Url> = event.urls().to_vec();
                     if !urls.is_empty() {
-                        handle_deep_link(&handle, urls);
+                        deep_link::handle_deep_link(&handle, urls);
                     }
                 });
             }
 
-            // Auto-start SSH connections
-            let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                let config = crate::config::load_config();
-                let ssh_manager = app_handle.state::<SshManager>();
-                for ssh_config in config.ssh_configs {
-                    if ssh_config.enabled {
-                        ssh_manager.connect(app_handle.clone(), ssh_config);
-                    }
-                }
-            });
-
-            // Auto-start Cloudflare tunnels
-            let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                let config = crate::config::load_config();
-                let cf_manager = app_handle.state::<CloudflareManager>();
-                for cf_config in config.cloudflare_configs {
-                    if cf_config.enabled {
-                        println!("[Cloudflare] Auto-starting tunnel: {}", cf_config.name);
-                        cf_manager.connect(app_handle.clone(), cf_config);
-                    }
-                }
-            });
+            webhooks::start_worker(app.handle());
 
-            // Auto-start Copilot if enabled
-            let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                let config = crate::config::load_config();
-                if config.copilot.enabled {
-                    println!("[Copilot] Auto-starting copilot-api...");
-                    // Small delay to let the app fully initialize
-                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-                    let state = app_handle.state::<AppState>();
-                    match commands::copilot::start_copilot(app_handle.clone(), state).await {
-                        Ok(status) => println!("[Copilot] Auto-start successful: running={}", status.running),
-                        Err(e) => eprintln!("[Copilot] Auto-start failed: {}", e),
-                    }
-                }
-            });
+            // Load config and auth, then start what depends on them
+            startup::hydrate(app.handle());
 
             Ok(())
         })
@@ -359,6 +178,13 @@ pub fn run() {
             commands::models::get_gpt_reasoning_models,
             commands::proxy::start_proxy,
             commands::proxy::stop_proxy,
+            commands::proxy::pause_proxy,
+            commands::proxy::resume_proxy,
+            commands::proxy_keys::rotate_proxy_api_key,
+            commands::proxy_keys::list_client_keys,
+            commands::proxy_keys::create_client_key,
+            commands::proxy_keys::revoke_client_key,
+            commands::sidecar::update_sidecar,
             // Copilot Management
             commands::copilot::get_copilot_status,
             commands::copilot::start_copilot,
@@ -368,14 +194,20 @@ pub fn run() {
             commands::copilot::install_copilot_api,
             // Auth & OAuth
             commands::auth::get_auth_status,
+            commands::auth::get_providers,
             commands::auth::refresh_auth_status,
             commands::auth::open_oauth,
             commands::auth::get_oauth_url,
             commands::auth::get_device_code,
             commands::auth::open_url_in_browser,
             commands::auth::poll_oauth_status,
+            commands::auth::list_pending_oauth,
+            commands::auth::cancel_oauth,
             commands::auth::complete_oauth,
+            commands::auth::verify_provider,
             commands::auth::disconnect_provider,
+            commands::auth::refresh_provider_token,
+            commands::auth::reauth_credential,
             commands::quota::fetch_antigravity_quota,
             commands::quota::fetch_codex_quota,
             commands::quota::fetch_copilot_quota,
@@ -383,31 +215,67 @@ pub fn run() {
             commands::quota::fetch_kiro_quota,
             commands::quota::test_kiro_connection,
             commands::quota::import_vertex_credential,
+            commands::vertex::get_vertex_capabilities,
+            commands::vertex::set_vertex_region,
             commands::config::get_config,
+            commands::config::get_startup_state,
+            commands::config::get_storage_info,
+            commands::config::migrate_data_dir,
             commands::config::save_config,
             commands::config::get_config_yaml,
             commands::config::save_config_yaml,
             commands::config::reload_config,
+            commands::config::validate_config,
+            commands::config::import_cliproxy_config,
             commands::proxy::get_system_proxy,
             // CLI Agent & IDE Tool detection
             commands::agents::detect_ai_tools,
             commands::agents::configure_continue,
             commands::agents::get_tool_setup_info,
             commands::agents::detect_cli_agents,
+            commands::agents::get_connection_info,
+            commands::agents::get_docker_host,
+            commands::agents::get_devcontainer_snippet,
             commands::agents::configure_cli_agent,
+            commands::agents::get_agent_model_preferences,
+            commands::agents::set_agent_model_preferences,
             commands::agents::get_shell_profile_path,
             commands::agents::append_to_shell_profile,
+            // Agent setup bundles
+            commands::agent_bundle::export_agent_bundle,
+            commands::agent_bundle::import_agent_bundle,
+            // Agent endpoint audit
+            commands::agent_endpoints::audit_agent_endpoints,
+            commands::agent_endpoints::fix_agent_endpoints,
+            commands::agent_endpoints::scan_environment_conflicts,
+            commands::agent_endpoints::fix_environment_conflicts,
             // Usage & Analytics
             commands::usage::get_usage_stats,
             commands::usage::get_request_history,
+            commands::usage::get_usage_by_account,
+            commands::usage::get_usage_by_client,
+            commands::usage::get_latency_stats,
+            commands::usage::get_version_stats,
+            commands::usage::get_version_comparison,
+            commands::usage::get_quota_events,
+            commands::usage::get_cost_ledger,
+            commands::usage::get_anomaly_status,
+            commands::usage::get_cost_by_tag,
+            commands::usage::set_request_tag,
+            commands::usage::set_session_tag,
             // Provider Health Check
             commands::health::check_provider_health,
+            // Provider benchmark
+            commands::benchmark::run_provider_benchmark,
+            commands::benchmark::get_benchmark_history,
             commands::usage::add_request_to_history,
             commands::usage::clear_request_history,
             commands::usage::sync_usage_from_proxy,
             commands::usage::export_usage_stats,
             commands::usage::import_usage_stats,
+            commands::usage::import_cliproxy_usage,
             commands::models::get_available_models,
+            commands::models::export_model_aliases,
             commands::models::test_openai_provider,
             commands::models::test_provider_connection,
             commands::models::fetch_openai_compatible_models,
@@ -428,6 +296,8 @@ pub fn run() {
             commands::api_keys::set_vertex_api_keys,
             commands::api_keys::add_vertex_api_key,
             commands::api_keys::delete_vertex_api_key,
+            commands::api_keys::get_provider_api_keys,
+            commands::api_keys::set_provider_api_keys,
             // Thinking Budget Settings
             commands::settings::get_thinking_budget_settings,
             commands::settings::set_thinking_budget_settings,
@@ -446,9 +316,29 @@ pub fn run() {
             commands::auth_files::download_auth_file,
             commands::auth_files::delete_all_auth_files,
             commands::auth_files::verify_proxy_auth_status,
+            // Encrypted credential export
+            commands::credentials::answer_passphrase_request,
+            commands::credentials::export_credentials,
+            commands::credentials::import_credentials,
             // Log Viewer
             commands::logs::get_logs,
             commands::logs::clear_logs,
+            commands::logs::get_recent_errors,
+            commands::logs::get_proxy_log_diagnostics,
+            commands::logs::set_live_feed_paused,
+            commands::logs::snapshot_live_feed,
+            commands::diagnostics::generate_diagnostic_report,
+            // Self-test
+            commands::self_test::run_self_test,
+            commands::self_test::cancel_self_test,
+            // Demo mode
+            commands::demo::start_demo_mode,
+            commands::demo::stop_demo_mode,
+            commands::demo::is_demo_mode_running,
+            // Request replay
+            commands::captures::list_captures,
+            commands::captures::get_capture,
+            commands::captures::replay_request,
             // Management API Settings
             commands::settings::get_max_retry_interval,
             commands::settings::set_max_retry_interval,
@@ -461,11 +351,69 @@ pub fn run() {
             // Window behavior
             commands::settings::get_close_to_tray,
             commands::settings::set_close_to_tray,
+            commands::settings::open_mini_monitor,
+            commands::settings::send_test_notification,
+            commands::settings::check_file_permissions,
+            commands::settings::get_audit_log,
+            commands::settings::generate_statusbar_plugin,
+            // Quit confirmation
+            commands::shutdown::force_quit,
+            commands::shutdown::quit_when_idle,
+            commands::session::get_last_session_summary,
+            // Webhooks
+            commands::webhooks::get_webhooks,
+            commands::webhooks::save_webhook,
+            commands::webhooks::delete_webhook,
+            commands::webhooks::test_webhook,
+            commands::webhooks::get_webhook_delivery_log,
+            // Provider failover
+            commands::failover::get_failover_rules,
+            commands::failover::save_failover_rule,
+            commands::failover::delete_failover_rule,
+            commands::failover::get_active_failovers,
+            // Amp provider pools
+            commands::amp_pools::get_amp_pools,
+            commands::amp_pools::save_amp_pool,
+            commands::amp_pools::delete_amp_pool,
+            commands::amp_pools::get_amp_pool_usage,
+            // Amp onboarding
+            commands::amp::validate_amp_api_key,
+            commands::amp::configure_amp,
+            // Model caps
+            commands::model_caps::get_model_caps,
+            commands::model_caps::save_model_cap,
+            commands::model_caps::delete_model_cap,
+            commands::model_caps::get_model_cap_status,
+            commands::model_catalog::get_model_catalog,
+            commands::model_catalog::refresh_model_catalog,
+            // Request watches
+            commands::request_watches::create_request_watch,
+            commands::request_watches::list_request_watches,
+            commands::request_watches::delete_request_watch,
+            // Provider soft rate limits
+            commands::rate_limits::get_provider_rate_limits,
+            commands::rate_limits::save_provider_rate_limit,
+            commands::rate_limits::delete_provider_rate_limit,
+            // Proxy schedule
+            commands::schedule::get_proxy_schedule,
+            commands::schedule::set_proxy_schedule,
+            commands::schedule::get_schedule_status,
+            // MCP server
+            commands::mcp::start_mcp_server,
+            commands::mcp::stop_mcp_server,
+            // Onboarding
+            commands::onboarding::get_onboarding_state,
+            commands::onboarding::complete_onboarding_step,
+            commands::onboarding::reset_onboarding,
             // Claude Code Settings
             commands::settings::get_claude_code_settings,
             commands::models::set_claude_code_model,
-            // Updater support check
-            is_updater_supported,
+            // App updates
+            commands::app_update::is_updater_supported,
+            commands::app_update::check_app_update,
+            commands::app_update::get_app_update,
+            commands::app_update::dismiss_app_update,
+            commands::app_update::open_app_release_page,
             // SSH
             commands::ssh::get_ssh_configs,
             commands::ssh::save_ssh_config,
@@ -480,61 +428,49 @@ pub fn run() {
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
-            match event {
-                tauri::RunEvent::WindowEvent {
-                    label,
-                    event: win_event,
-                    ..
-                } => {
-                    // Handle close button based on close_to_tray setting
-                    if label == "main" {
-                        if let tauri::WindowEvent::CloseRequested { api, .. } = win_event {
-                            // Check if close_to_tray is enabled
-                            let close_to_tray = app_handle
-                                .try_state::<AppState>()
-                                .map(|state| state.config.lock().unwrap().close_to_tray)
-                                .unwrap_or(true);
-                            
-                            if close_to_tray {
-                                // Hide to tray instead of closing
-                                if let Some(window) = app_handle.get_webview_window("main") {
-                                    println!("[ProxyPal] Hiding to system tray...");
-                                    let _ = window.hide();
-                                }
-                                api.prevent_close();
-                            }
-                            // If close_to_tray is false, allow normal close behavior
-                        }
-                    }
+            if let tauri::RunEvent::ExitRequested { code, api, .. } = event {
+                // Cmd+Q or closing the last window (app.exit() passes a code):
+                // confirm first if the proxy is still serving requests
+                if code.is_none() && commands::shutdown::confirm_if_busy(app_handle) {
+                    api.prevent_exit();
+                    return;
                 }
-                tauri::RunEvent::ExitRequested { .. } => {
-                    // Cleanup: Kill proxy and copilot processes before exit
-                    if let Some(state) = app_handle.try_state::<AppState>() {
-                        // Stop log watcher thread
-                        state.log_watcher_running.store(false, Ordering::SeqCst);
-                        
-                        // Kill cliproxyapi process
-                        if let Ok(mut process_guard) = state.proxy_process.lock() {
-                            if let Some(child) = process_guard.take() {
-                                println!("[ProxyPal] Shutting down cliproxyapi...");
-                                let _ = child.kill();
-                            }
-                        }
-                        // Kill copilot-api process
-                        if let Ok(mut process_guard) = state.copilot_process.lock() {
-                            if let Some(child) = process_guard.take() {
-                                println!("[ProxyPal] Shutting down copilot-api...");
-                                let _ = child.kill();
-                            }
-                        }
+
+                // Cleanup: Kill proxy and copilot processes before exit
+                if let Some(state) = app_handle.try_state::<AppState>() {
+                    // Stop log watcher thread
+                    state.log_watcher_running.store(false, Ordering::SeqCst);
+                    
+                    // Kill cliproxyapi process
+                    if let Some(child) = state.proxy_process.lock().take() {
+                        println!("[ProxyPal] Shutting down cliproxyapi...");
+                        let _ = child.kill();
                     }
+                    // Kill copilot-api process
+                    if let Some(child) = state.copilot_process.lock().take() {
+                        println!("[ProxyPal] Shutting down copilot-api...");
+                        let _ = child.kill();
+                    }
+                }
 
-                    // Cleaning up SSH connections
-                    if let Some(ssh_manager) = app_handle.try_state::<SshManager>() {
-                        ssh_manager.disconnect_all();
+                if let Some(state) = app_handle.try_state::<AppState>() {
+                    metrics::stop_metrics_server(&state);
+                    control_api::stop_control_api(&state);
+                    control_socket::stop_control_socket(&state);
+                    management_gateway::stop_management_gateway(&state);
+                    proxy::pause::stop_sleep_watcher(&state);
+                    if let Err(e) = state.history.flush() {
+                        eprintln!("[ProxyPal] Failed to save history: {}", e);
                     }
+                    session_journal::end(&state);
+                }
+                event_stream::stop_event_stream(app_handle);
+                mcp::stop_mcp_server(app_handle);
+
+                // Cleaning up SSH connections
+                if let Some(ssh_manager) = app_handle.try_state::<SshManager>() {
+                    ssh_manager.disconnect_all();
                 }
-                _ => {}
             }
         });
 }
@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // `proxypal --headless`, `proxypal status`, ... run without the GUI
+    if let Some(code) = proxypal_lib::run_cli() {
+        std::process::exit(code);
+    }
     proxypal_lib::run()
 }
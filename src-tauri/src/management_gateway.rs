@@ -0,0 +1,260 @@
+//! Management API on its own port.
+//!
+//! CLIProxyAPI serves `/v0/management/*` on the proxy port and has no setting
+//! for a separate listener. When `AppConfig.management_port` is set, the
+//! generated config keeps the sidecar's Management API loopback-only and
+//! ProxyPal listens on the management port instead, forwarding management
+//! calls to the sidecar. The proxy port can then be opened to the LAN while
+//! management stays behind a port that is firewalled separately.
+//!
+//! The gateway binds `management_bind` when remote management is on and
+//! 127.0.0.1 otherwise. ProxyPal's own management calls keep going straight to
+//! the sidecar over loopback, and agent-facing connection info only ever
+//! names the proxy port.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::AppConfig;
+use crate::http::{get_management_url, HttpClients};
+use crate::state::AppState;
+
+const MANAGEMENT_PREFIX: &str = "/v0/management/";
+
+/// Longest a forwarded call may take; OAuth status polls return quickly, but
+/// auth-file uploads can be slow
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Calls forwarded at once, each on its own thread; a slow upload or OAuth
+/// poll no longer holds up the calls behind it
+const MAX_IN_FLIGHT: usize = 16;
+
+/// Headers not copied between the two legs of a forwarded call
+const HOP_HEADERS: &[&str] = &[
+    "host",
+    "connection",
+    "content-length",
+    "transfer-encoding",
+    "keep-alive",
+];
+
+/// Refuse a management port that collides with another ProxyPal listener
+pub(crate) fn validate_management_port(config: &AppConfig) -> Result<(), String> {
+    let Some(port) = config.management_port else {
+        return Ok(());
+    };
+    let taken = [
+        (Some(config.port), "the proxy port"),
+        (config.metrics_port, "the metrics port"),
+        (config.event_stream_port, "the event stream port"),
+        (config.control_api_port, "the control API port"),
+    ];
+    match taken.iter().find(|(other, _)| *other == Some(port)) {
+        Some((_, name)) => Err(format!(
+            "The management port {} is already used as {}",
+            port, name
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Address the gateway should listen on, if it should run at all
+fn gateway_addr(config: &AppConfig) -> Option<String> {
+    let port = config.management_port?;
+    let host = if !config.management_allow_remote {
+        "127.0.0.1"
+    } else if config.management_bind.is_empty() {
+        "0.0.0.0"
+    } else {
+        &config.management_bind
+    };
+    Some(format!("{}:{}", host, port))
+}
+
+fn is_management_path(url: &str) -> bool {
+    url.starts_with(MANAGEMENT_PREFIX)
+}
+
+// Runs on a per-call thread outside the async runtime, so it blocks on the
+// shared client. The caller's own key is forwarded, never ProxyPal's.
+fn forward(
+    http: &HttpClients,
+    proxy_port: u16,
+    request: &mut tiny_http::Request,
+) -> Result<tiny_http::Response<std::io::Cursor<Vec<u8>>>, String> {
+    let method = reqwest::Method::from_bytes(request.method().as_str().as_bytes())
+        .map_err(|e| e.to_string())?;
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .read_to_end(&mut body)
+        .map_err(|e| e.to_string())?;
+
+    let endpoint = request
+        .url()
+        .strip_prefix(MANAGEMENT_PREFIX)
+        .unwrap_or_default();
+    let mut upstream = http
+        .local()
+        .request(method, get_management_url(proxy_port, endpoint))
+        .timeout(FORWARD_TIMEOUT)
+        .body(body);
+    for header in request.headers() {
+        let name = header.field.as_str().as_str();
+        if !HOP_HEADERS.iter().any(|h| name.eq_ignore_ascii_case(h)) {
+            upstream = upstream.header(name, header.value.as_str());
+        }
+    }
+
+    let (status, content_type, bytes) = tauri::async_runtime::block_on(async move {
+        let reply = upstream.send().await.map_err(|e| e.to_string())?;
+        let status = reply.status().as_u16();
+        let content_type = reply
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let bytes = reply.bytes().await.map_err(|e| e.to_string())?.to_vec();
+        Ok::<_, String>((status, content_type, bytes))
+    })?;
+
+    let mut response = tiny_http::Response::from_data(bytes).with_status_code(status);
+    if let Some(content_type) = content_type {
+        if let Ok(header) = tiny_http::Header::from_bytes(&b"Content-Type"[..], content_type) {
+            response = response.with_header(header);
+        }
+    }
+    Ok(response)
+}
+
+fn handle(app: &AppHandle, mut request: tiny_http::Request) {
+    let upstream = app
+        .try_state::<AppState>()
+        .map(|state| (state.http.clone(), state.config.lock().port));
+    let response = match upstream {
+        Some((http, port)) if is_management_path(request.url()) => {
+            forward(&http, port, &mut request).unwrap_or_else(|e| {
+                tiny_http::Response::from_string(format!("Proxy unreachable: {}", e))
+                    .with_status_code(502)
+            })
+        }
+        _ => tiny_http::Response::from_string("Not Found").with_status_code(404),
+    };
+    let _ = request.respond(response);
+}
+
+/// Running management gateway
+pub struct ManagementGateway {
+    server: Arc<tiny_http::Server>,
+    addr: String,
+}
+
+impl ManagementGateway {
+    fn start(app: &AppHandle, addr: String) -> Result<Self, String> {
+        let server = Arc::new(
+            tiny_http::Server::http(&addr)
+                .map_err(|e| format!("Failed to bind management port on {}: {}", addr, e))?,
+        );
+        let listener = server.clone();
+        let app = app.clone();
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        std::thread::spawn(move || {
+            for request in listener.incoming_requests() {
+                if in_flight.fetch_add(1, Ordering::SeqCst) >= MAX_IN_FLIGHT {
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    let _ = request.respond(
+                        tiny_http::Response::from_string("Too many management calls")
+                            .with_status_code(503),
+                    );
+                    continue;
+                }
+                let (app, in_flight) = (app.clone(), in_flight.clone());
+                std::thread::spawn(move || {
+                    handle(&app, request);
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        println!(
+            "[ProxyPal] Management API available at http://{}{}",
+            addr, MANAGEMENT_PREFIX
+        );
+        Ok(Self { server, addr })
+    }
+
+    // Not joined: a forwarded call may still be waiting on the sidecar
+    fn stop(self) {
+        self.server.unblock();
+        println!("[ProxyPal] Management port on {} stopped", self.addr);
+    }
+}
+
+/// Start, restart or stop the gateway to match the current config
+pub fn apply_management_gateway_config(app: &AppHandle) -> Result<(), String> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+    let wanted = gateway_addr(&state.config.lock());
+    let mut current = state.management_gateway.lock();
+    if current.as_ref().map(|g| &g.addr) == wanted.as_ref() {
+        return Ok(());
+    }
+    if let Some(gateway) = current.take() {
+        gateway.stop();
+    }
+    if let Some(addr) = wanted {
+        *current = Some(ManagementGateway::start(app, addr)?);
+    }
+    Ok(())
+}
+
+/// Shut the gateway down on app exit
+pub fn stop_management_gateway(state: &AppState) {
+    if let Some(gateway) = state.management_gateway.lock().take() {
+        gateway.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn management_port_must_not_collide_with_other_listeners() {
+        let mut config = AppConfig::default();
+        assert!(validate_management_port(&config).is_ok());
+
+        config.management_port = Some(config.port);
+        assert!(validate_management_port(&config)
+            .unwrap_err()
+            .contains("proxy port"));
+
+        config.metrics_port = Some(9090);
+        config.management_port = Some(9090);
+        assert!(validate_management_port(&config)
+            .unwrap_err()
+            .contains("metrics port"));
+
+        config.management_port = Some(8318);
+        assert!(validate_management_port(&config).is_ok());
+    }
+
+    #[test]
+    fn gateway_stays_on_loopback_unless_remote_management_is_on() {
+        let mut config = AppConfig::default();
+        assert_eq!(gateway_addr(&config), None);
+
+        config.management_port = Some(8318);
+        config.management_bind = "0.0.0.0".to_string();
+        assert_eq!(gateway_addr(&config).as_deref(), Some("127.0.0.1:8318"));
+
+        config.management_allow_remote = true;
+        assert_eq!(gateway_addr(&config).as_deref(), Some("0.0.0.0:8318"));
+
+        assert!(is_management_path("/v0/management/config.yaml"));
+        assert!(!is_management_path("/v1/chat/completions"));
+    }
+}
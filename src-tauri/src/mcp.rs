@@ -0,0 +1,474 @@
+//! MCP (Model Context Protocol) server for agent introspection.
+//!
+//! Agents running through the proxy can ask ProxyPal about itself with a few
+//! read-only tools: `get_status`, `get_usage_summary`, `list_models` and
+//! `get_rate_limit_status`. Messages are newline-delimited JSON-RPC 2.0, the
+//! same framing as the MCP stdio transport.
+//!
+//! The server listens on `<config_dir>/mcp.sock`, accessible to the current
+//! user only, or on a named pipe on Windows. The pipe rejects remote clients
+//! and keeps the default pipe security, under which other local accounts may
+//! only open it for reading, which isn't enough to send a request. Agents
+//! that expect a stdio server can run `proxypal mcp`, which bridges
+//! stdin/stdout to the socket:
+//!
+//! ```json
+//! { "mcpServers": { "proxypal": { "command": "proxypal", "args": ["mcp"] } } }
+//! ```
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::watch;
+
+use crate::state::AppState;
+
+#[cfg(windows)]
+pub const PIPE_NAME: &str = r"\\.\pipe\proxypal-mcp";
+
+/// Protocol revisions we can speak, newest first
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Where the server listens
+#[cfg(unix)]
+pub fn socket_path() -> std::path::PathBuf {
+    crate::config::get_proxypal_config_dir().join("mcp.sock")
+}
+
+fn endpoint_label() -> String {
+    #[cfg(unix)]
+    return socket_path().display().to_string();
+    #[cfg(windows)]
+    return PIPE_NAME.to_string();
+}
+
+fn tool_definitions() -> Value {
+    let no_args = json!({ "type": "object", "properties": {} });
+    json!([
+        {
+            "name": "get_status",
+            "description": "Proxy status (running, port, endpoint, uptime) and the number of connected accounts per provider.",
+            "inputSchema": no_args,
+        },
+        {
+            "name": "get_usage_summary",
+            "description": "Requests, tokens and estimated cost for this session, today and all time.",
+            "inputSchema": no_args,
+        },
+        {
+            "name": "list_models",
+            "description": "Models the proxy currently serves, with their provider and auth source.",
+            "inputSchema": no_args,
+        },
+        {
+            "name": "get_rate_limit_status",
+            "description": "Providers currently answering with 429 Too Many Requests.",
+            "inputSchema": no_args,
+        },
+    ])
+}
+
+fn get_status(state: &AppState) -> Value {
+    let proxy = state.proxy_status.lock().clone();
+    let uptime_secs = state
+        .proxy_started_at
+        .lock()
+        .map(|started| started.elapsed().as_secs());
+    let auth = state.auth_status.lock().clone();
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "proxy": proxy,
+        "uptimeSecs": uptime_secs,
+        "connectedAccounts": auth,
+    })
+}
+
+fn get_usage_summary(state: &AppState) -> Value {
+    let (requests, tokens_in, tokens_out, tokens_cached) = state.metrics.lock().session_totals();
+    let (requests_today, cost_today) = crate::helpers::history::today_stats(&state.history);
+    let aggregate = crate::helpers::history::load_aggregate();
+    json!({
+        "session": {
+            "requests": requests,
+            "tokensIn": tokens_in,
+            "tokensOut": tokens_out,
+            "tokensCached": tokens_cached,
+        },
+        "today": {
+            "requests": requests_today,
+            "estimatedCostUsd": cost_today,
+        },
+        "allTime": {
+            "requests": aggregate.total_requests,
+            "failures": aggregate.total_failure_count,
+            "tokensIn": aggregate.total_tokens_in,
+            "tokensOut": aggregate.total_tokens_out,
+            "estimatedCostUsd": aggregate.total_cost_usd,
+        },
+    })
+}
+
+fn list_models(state: &AppState) -> Value {
+    let models: Vec<Value> = crate::commands::models::cached_models(state)
+        .into_iter()
+        .map(|m| json!({ "id": m.id, "provider": m.provider, "source": m.source }))
+        .collect();
+    json!({ "models": models })
+}
+
+fn get_rate_limit_status(state: &AppState) -> Value {
+    let providers = state.notifications.lock().rate_limited_providers();
+    json!({ "rateLimitedProviders": providers })
+}
+
+fn call_tool(state: &AppState, name: &str) -> Option<Value> {
+    let result = match name {
+        "get_status" => get_status(state),
+        "get_usage_summary" => get_usage_summary(state),
+        "list_models" => list_models(state),
+        "get_rate_limit_status" => get_rate_limit_status(state),
+        _ => return None,
+    };
+    Some(json!({
+        "content": [{
+            "type": "text",
+            "text": serde_json::to_string_pretty(&result).unwrap_or_default(),
+        }],
+        "structuredContent": result,
+        "isError": false,
+    }))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Handle one JSON-RPC message. Returns the response line, or None for
+/// notifications.
+pub fn handle_message(state: &AppState, line: &str) -> Option<String> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => {
+            return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string()).to_string())
+        }
+    };
+    let method = message.get("method").and_then(Value::as_str);
+    // Notifications (no id) never get a reply, including `notifications/initialized`
+    let id = message.get("id").cloned()?;
+    let Some(method) = method else {
+        return Some(error_response(id, INVALID_REQUEST, "Missing method").to_string());
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => {
+            let requested = params.get("protocolVersion").and_then(Value::as_str);
+            let version = requested
+                .filter(|v| PROTOCOL_VERSIONS.contains(v))
+                .unwrap_or(PROTOCOL_VERSIONS[0]);
+            json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": { "listChanged": false } },
+                "serverInfo": { "name": "proxypal", "version": env!("CARGO_PKG_VERSION") },
+            })
+        }
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tool_definitions() }),
+        "tools/call" => {
+            let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+            match call_tool(state, name) {
+                Some(result) => result,
+                None => {
+                    let message = format!("Unknown tool: {}", name);
+                    return Some(error_response(id, INVALID_PARAMS, &message).to_string());
+                }
+            }
+        }
+        other => {
+            let message = format!("Method not found: {}", other);
+            return Some(error_response(id, METHOD_NOT_FOUND, &message).to_string());
+        }
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string())
+}
+
+async fn serve_client<S>(app: AppHandle, stream: S, mut shutdown: watch::Receiver<bool>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    loop {
+        let line = tokio::select! {
+            line = lines.next_line() => line,
+            _ = shutdown.changed() => break,
+        };
+        let Ok(Some(line)) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        // The usage summary reads the history files, so handle the message
+        // off the async runtime
+        let handler = app.clone();
+        let response = tauri::async_runtime::spawn_blocking(move || {
+            let state = handler.try_state::<AppState>()?;
+            handle_message(&state, &line)
+        })
+        .await;
+        let Ok(response) = response else {
+            break;
+        };
+        if let Some(mut response) = response {
+            response.push('\n');
+            if writer.write_all(response.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Running MCP listener
+pub struct McpServer {
+    shutdown: watch::Sender<bool>,
+}
+
+impl McpServer {
+    #[cfg(unix)]
+    fn start(app: &AppHandle) -> Result<Self, String> {
+        let path = socket_path();
+        // Usage and account counts are for the current user only
+        let listener = crate::helpers::permissions::bind_private_socket(&path)
+            .map_err(|e| format!("Failed to open MCP socket {}: {}", path.display(), e))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let Ok(listener) = tokio::net::UnixListener::from_std(listener) else {
+                return;
+            };
+            let mut stop = shutdown_rx.clone();
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => {
+                        let Ok((stream, _)) = accepted else { continue };
+                        tauri::async_runtime::spawn(serve_client(app.clone(), stream, shutdown_rx.clone()));
+                    }
+                    _ = stop.changed() => break,
+                }
+            }
+            let _ = std::fs::remove_file(socket_path());
+        });
+        Ok(Self { shutdown })
+    }
+
+    #[cfg(windows)]
+    fn start(app: &AppHandle) -> Result<Self, String> {
+        use tokio::net::windows::named_pipe::ServerOptions;
+
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        // Creating a pipe needs the runtime's reactor
+        let _guard = tauri::async_runtime::handle().inner().enter();
+        let first = ServerOptions::new()
+            .first_pipe_instance(true)
+            .reject_remote_clients(true)
+            .create(PIPE_NAME)
+            .map_err(|e| format!("Failed to open MCP pipe: {}", e))?;
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut pipe = first;
+            let mut stop = shutdown_rx.clone();
+            loop {
+                tokio::select! {
+                    connected = pipe.connect() => {
+                        if connected.is_err() {
+                            break;
+                        }
+                        let Ok(next) = ServerOptions::new().reject_remote_clients(true).create(PIPE_NAME) else {
+                            break;
+                        };
+                        let client = std::mem::replace(&mut pipe, next);
+                        tauri::async_runtime::spawn(serve_client(app.clone(), client, shutdown_rx.clone()));
+                    }
+                    _ = stop.changed() => break,
+                }
+            }
+        });
+        Ok(Self { shutdown })
+    }
+
+    fn stop(self) {
+        let _ = self.shutdown.send(true);
+    }
+}
+
+/// Start the server if it isn't running. Returns where it listens.
+pub fn start_mcp_server(app: &AppHandle) -> Result<String, String> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Err("App state unavailable".to_string());
+    };
+    let mut server = state.mcp_server.lock();
+    if server.is_none() {
+        *server = Some(McpServer::start(app)?);
+        println!("[ProxyPal] MCP server listening on {}", endpoint_label());
+    }
+    Ok(endpoint_label())
+}
+
+/// Disconnect clients and stop listening
+pub fn stop_mcp_server(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let server = state.mcp_server.lock().take();
+    if let Some(server) = server {
+        server.stop();
+        println!("[ProxyPal] MCP server stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AvailableModel;
+
+    // Drive the server like a client would and decode each reply
+    fn call(state: &AppState, message: Value) -> Option<Value> {
+        handle_message(state, &message.to_string()).map(|line| serde_json::from_str(&line).unwrap())
+    }
+
+    #[test]
+    fn performs_initialize_handshake() {
+        let state = AppState::default();
+        let reply = call(
+            &state,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test", "version": "0" },
+                },
+            }),
+        )
+        .unwrap();
+        assert_eq!(reply["id"], 1);
+        assert_eq!(reply["result"]["protocolVersion"], "2025-03-26");
+        assert!(reply["result"]["capabilities"]["tools"].is_object());
+        assert_eq!(reply["result"]["serverInfo"]["name"], "proxypal");
+
+        // Unknown revisions get our newest one
+        let reply = call(
+            &state,
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "initialize", "params": { "protocolVersion": "1999-01-01" } }),
+        )
+        .unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(call(&state, notification).is_none());
+    }
+
+    #[test]
+    fn lists_and_calls_tools() {
+        let state = AppState::default();
+        let reply = call(
+            &state,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }),
+        )
+        .unwrap();
+        let names: Vec<&str> = reply["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "get_status",
+                "get_usage_summary",
+                "list_models",
+                "get_rate_limit_status"
+            ]
+        );
+
+        let reply = call(
+            &state,
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": { "name": "get_status", "arguments": {} } }),
+        )
+        .unwrap();
+        let result = &reply["result"];
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["structuredContent"]["proxy"]["running"], false);
+        assert_eq!(result["content"][0]["type"], "text");
+
+        *state.models_cache.lock() = Some(crate::state::ModelsCache {
+            models: vec![AvailableModel {
+                id: "gpt-5".to_string(),
+                owned_by: "openai".to_string(),
+                source: "oauth".to_string(),
+                provider: "openai".to_string(),
+                connected: true,
+            }],
+            fetched_at: std::time::Instant::now(),
+        });
+        let reply = call(
+            &state,
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": { "name": "list_models" } }),
+        )
+        .unwrap();
+        assert_eq!(
+            reply["result"]["structuredContent"]["models"][0]["id"],
+            "gpt-5"
+        );
+
+        let reply = call(
+            &state,
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": { "name": "get_rate_limit_status" } }),
+        )
+        .unwrap();
+        assert_eq!(
+            reply["result"]["structuredContent"]["rateLimitedProviders"],
+            json!([])
+        );
+    }
+
+    #[test]
+    fn reports_protocol_errors() {
+        let state = AppState::default();
+        let reply = handle_message(&state, "{not json").unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&reply).unwrap()["error"]["code"],
+            PARSE_ERROR
+        );
+
+        let reply = call(
+            &state,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "resources/list" }),
+        )
+        .unwrap();
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+
+        let reply = call(
+            &state,
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": { "name": "delete_everything" } }),
+        )
+        .unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+    }
+}
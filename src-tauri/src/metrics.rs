@@ -0,0 +1,307 @@
+//! Prometheus metrics endpoint.
+//!
+//! When `AppConfig.metrics_port` is set, a small HTTP listener serves `/metrics`
+//! in the Prometheus text format. It binds 127.0.0.1 unless `metrics_allow_remote`
+//! is on, runs for the lifetime of the app (independent of the proxy) and is
+//! restarted when the port setting changes.
+//!
+//! Counters are fed by the log watcher as requests are parsed and start from
+//! zero with the app, as Prometheus counters are expected to.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+use crate::types::RequestLog;
+
+/// Upper bounds (seconds) of the request duration histogram buckets
+const DURATION_BUCKETS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+#[derive(Default, Clone)]
+struct Histogram {
+    // Cumulative counts per bucket, same order as DURATION_BUCKETS
+    buckets: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; DURATION_BUCKETS.len()];
+        }
+        for (bucket, upper) in self.buckets.iter_mut().zip(DURATION_BUCKETS) {
+            if seconds <= *upper {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// In-memory request counters, kept in `AppState`
+#[derive(Default)]
+pub struct ProxyMetrics {
+    // (provider, model, status) -> count
+    requests: BTreeMap<(String, String, u16), u64>,
+    tokens_in: u64,
+    tokens_out: u64,
+    tokens_cached: u64,
+    durations: BTreeMap<String, Histogram>,
+}
+
+impl ProxyMetrics {
+    pub fn record(&mut self, request: &RequestLog) {
+        *self
+            .requests
+            .entry((
+                request.provider.clone(),
+                request.model.clone(),
+                request.status,
+            ))
+            .or_default() += 1;
+        self.tokens_in += request.tokens_in.unwrap_or(0) as u64;
+        self.tokens_out += request.tokens_out.unwrap_or(0) as u64;
+        self.tokens_cached += request.tokens_cached.unwrap_or(0) as u64;
+        self.durations
+            .entry(request.provider.clone())
+            .or_default()
+            .observe(request.duration_ms as f64 / 1000.0);
+    }
+
+    /// (requests, input tokens, output tokens, cached tokens) since the app started
+    pub fn session_totals(&self) -> (u64, u64, u64, u64) {
+        (
+            self.requests.values().sum(),
+            self.tokens_in,
+            self.tokens_out,
+            self.tokens_cached,
+        )
+    }
+
+    /// Render in the Prometheus text exposition format
+    pub fn render(&self, proxy_up: bool) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP proxypal_proxy_up Whether the proxy is running.\n");
+        out.push_str("# TYPE proxypal_proxy_up gauge\n");
+        let _ = writeln!(out, "proxypal_proxy_up {}", proxy_up as u8);
+
+        out.push_str("# HELP proxypal_requests_total Requests served by the proxy.\n");
+        out.push_str("# TYPE proxypal_requests_total counter\n");
+        for ((provider, model, status), count) in &self.requests {
+            let _ = writeln!(
+                out,
+                "proxypal_requests_total{{provider=\"{}\",model=\"{}\",status=\"{}\"}} {}",
+                escape_label(provider),
+                escape_label(model),
+                status,
+                count
+            );
+        }
+
+        out.push_str("# HELP proxypal_tokens_total Tokens processed by the proxy.\n");
+        out.push_str("# TYPE proxypal_tokens_total counter\n");
+        for (direction, value) in [
+            ("input", self.tokens_in),
+            ("output", self.tokens_out),
+            ("cached", self.tokens_cached),
+        ] {
+            let _ = writeln!(
+                out,
+                "proxypal_tokens_total{{direction=\"{}\"}} {}",
+                direction, value
+            );
+        }
+
+        out.push_str("# HELP proxypal_request_duration_seconds Request latency per provider.\n");
+        out.push_str("# TYPE proxypal_request_duration_seconds histogram\n");
+        for (provider, histogram) in &self.durations {
+            let provider = escape_label(provider);
+            for (count, upper) in histogram.buckets.iter().zip(DURATION_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "proxypal_request_duration_seconds_bucket{{provider=\"{}\",le=\"{}\"}} {}",
+                    provider, upper, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "proxypal_request_duration_seconds_bucket{{provider=\"{}\",le=\"+Inf\"}} {}",
+                provider, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "proxypal_request_duration_seconds_sum{{provider=\"{}\"}} {}",
+                provider, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "proxypal_request_duration_seconds_count{{provider=\"{}\"}} {}",
+                provider, histogram.count
+            );
+        }
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Running `/metrics` listener
+pub struct MetricsServer {
+    server: Arc<tiny_http::Server>,
+    thread: Option<std::thread::JoinHandle<()>>,
+    addr: String,
+}
+
+impl MetricsServer {
+    fn start(app: &AppHandle, addr: String) -> Result<Self, String> {
+        let server = Arc::new(
+            tiny_http::Server::http(&addr)
+                .map_err(|e| format!("Failed to bind metrics listener on {}: {}", addr, e))?,
+        );
+        let listener = server.clone();
+        let app = app.clone();
+        let thread = std::thread::spawn(move || {
+            for request in listener.incoming_requests() {
+                let response = if request.url() == "/metrics" {
+                    let body = app
+                        .try_state::<AppState>()
+                        .map(|state| {
+                            let up = state.proxy_status.lock().running;
+                            state.metrics.lock().render(up)
+                        })
+                        .unwrap_or_default();
+                    tiny_http::Response::from_string(body).with_header(
+                        tiny_http::Header::from_bytes(
+                            &b"Content-Type"[..],
+                            &b"text/plain; version=0.0.4; charset=utf-8"[..],
+                        )
+                        .unwrap(),
+                    )
+                } else {
+                    tiny_http::Response::from_string("Not Found").with_status_code(404)
+                };
+                let _ = request.respond(response);
+            }
+        });
+        println!("[ProxyPal] Metrics available at http://{}/metrics", addr);
+        Ok(Self {
+            server,
+            thread: Some(thread),
+            addr,
+        })
+    }
+
+    fn stop(mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        println!("[ProxyPal] Metrics listener on {} stopped", self.addr);
+    }
+}
+
+/// Start, restart or stop the listener to match the current config
+pub fn apply_metrics_config(app: &AppHandle) -> Result<(), String> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+    let wanted = {
+        let config = state.config.lock();
+        config.metrics_port.map(|port| {
+            let host = if config.metrics_allow_remote {
+                "0.0.0.0"
+            } else {
+                "127.0.0.1"
+            };
+            format!("{}:{}", host, port)
+        })
+    };
+
+    let mut current = state.metrics_server.lock();
+    if current.as_ref().map(|s| &s.addr) == wanted.as_ref() {
+        return Ok(());
+    }
+    if let Some(server) = current.take() {
+        server.stop();
+    }
+    if let Some(addr) = wanted {
+        *current = Some(MetricsServer::start(app, addr)?);
+    }
+    Ok(())
+}
+
+/// Shut the listener down on app exit
+pub fn stop_metrics_server(state: &AppState) {
+    if let Some(server) = state.metrics_server.lock().take() {
+        server.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(provider: &str, status: u16, duration_ms: u64) -> RequestLog {
+        RequestLog {
+            id: "1".to_string(),
+            timestamp: 0,
+            provider: provider.to_string(),
+            model: "gpt-5".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/chat/completions".to_string(),
+            status,
+            duration_ms,
+            ttfb_ms: None,
+            tokens_in: Some(10),
+            tokens_out: Some(5),
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    #[test]
+    fn renders_counters_and_histogram() {
+        let mut metrics = ProxyMetrics::default();
+        metrics.record(&request("openai", 200, 300));
+        metrics.record(&request("openai", 200, 3000));
+        metrics.record(&request("openai", 429, 50));
+
+        let text = metrics.render(true);
+        assert!(text.contains("proxypal_proxy_up 1\n"));
+        assert!(text.contains(
+            "proxypal_requests_total{provider=\"openai\",model=\"gpt-5\",status=\"200\"} 2\n"
+        ));
+        assert!(text.contains("proxypal_tokens_total{direction=\"input\"} 30\n"));
+        assert!(text.contains(
+            "proxypal_request_duration_seconds_bucket{provider=\"openai\",le=\"0.5\"} 2\n"
+        ));
+        assert!(text.contains(
+            "proxypal_request_duration_seconds_bucket{provider=\"openai\",le=\"+Inf\"} 3\n"
+        ));
+        assert!(text.contains("proxypal_request_duration_seconds_count{provider=\"openai\"} 3\n"));
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape_label("a\"b\\c"), "a\\\"b\\\\c");
+    }
+}
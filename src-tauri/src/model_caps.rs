@@ -0,0 +1,451 @@
+//! Per-model daily usage caps.
+//!
+//! `AppConfig.model_caps` limits the requests or tokens that models matching
+//! a pattern may use per usage day (see `helpers::usage_day`). The log watcher
+//! hands every batch of new requests to [`record`], which keeps per-model
+//! counts for the current day. When a cap is reached, `model-cap-reached` is
+//! emitted and a notification shown; if the cap names a fallback model, the
+//! matching models are redirected to it through the proxy's model mappings,
+//! which `failover::push_mappings` applies together with any failovers.
+//! Counts and redirects reset when the usage day changes.
+//!
+//! Like failover, the redirect only covers Amp: the mappings live in
+//! `ampcode.model-mappings`, which CLIProxyAPI consults on its Amp routes
+//! alone. Requests from other clients are counted but keep reaching the
+//! capped model; the notification says so.
+//!
+//! Counts are kept in memory. The first batch after launch seeds them from
+//! today's requests in history, so they are only as complete as the history
+//! retention allows.
+
+use std::collections::HashMap;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::AppConfig;
+use crate::helpers::usage_day;
+use crate::state::AppState;
+use crate::types::{FailoverModelMapping, ModelCap, ModelCapStatus, ModelCatalog, RequestLog};
+
+/// Today's consumption, kept in `AppState`
+#[derive(Default)]
+pub struct ModelCapUsage {
+    // Usage day the counts belong to; empty until the first batch
+    day: String,
+    // Requests and tokens per model
+    by_model: HashMap<String, (u64, u64)>,
+    // Reached caps by id, with when they were reached (ms)
+    reached: HashMap<String, u64>,
+}
+
+/// Whether `model` is covered by `pattern` (exact id, or prefix ending in `*`)
+pub(crate) fn matches(pattern: &str, model: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => model.starts_with(prefix),
+        None => model == pattern,
+    }
+}
+
+fn validate_cap(cap: &ModelCap) -> Result<(), String> {
+    let pattern = cap.model_pattern.trim();
+    if pattern.is_empty() {
+        return Err("A model cap needs a model or model prefix".to_string());
+    }
+    if pattern.trim_end_matches('*').contains('*') {
+        return Err(format!(
+            "Invalid model pattern '{}': only a trailing * is supported",
+            pattern
+        ));
+    }
+    let limited = |limit: Option<u64>| limit.is_some_and(|l| l > 0);
+    if !limited(cap.max_requests_per_day) && !limited(cap.max_tokens_per_day) {
+        return Err(format!(
+            "Set a daily request or token limit for {}",
+            pattern
+        ));
+    }
+    if let Some(fallback) = &cap.fallback_model {
+        if fallback.trim().is_empty() {
+            return Err("The fallback model can't be empty".to_string());
+        }
+        if matches(pattern, fallback) {
+            return Err(format!(
+                "The fallback model {} is covered by the same cap",
+                fallback
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check every cap and that ids are unique
+pub(crate) fn validate_caps(caps: &[ModelCap]) -> Result<(), String> {
+    for (i, cap) in caps.iter().enumerate() {
+        validate_cap(cap)?;
+        if caps[..i].iter().any(|c| c.id == cap.id) {
+            return Err(format!("Duplicate model cap id: {}", cap.id));
+        }
+    }
+    Ok(())
+}
+
+fn enabled_caps(config: &AppConfig) -> Vec<ModelCap> {
+    config
+        .model_caps
+        .iter()
+        .filter(|c| c.enabled)
+        .cloned()
+        .collect()
+}
+
+fn consumption(by_model: &HashMap<String, (u64, u64)>, cap: &ModelCap) -> (u64, u64) {
+    by_model
+        .iter()
+        .filter(|(model, _)| matches(&cap.model_pattern, model))
+        .fold((0, 0), |(requests, tokens), (_, (r, t))| {
+            (requests + r, tokens + t)
+        })
+}
+
+fn over_limit(cap: &ModelCap, requests: u64, tokens: u64) -> bool {
+    let over = |limit: Option<u64>, used: u64| limit.is_some_and(|l| l > 0 && used >= l);
+    over(cap.max_requests_per_day, requests) || over(cap.max_tokens_per_day, tokens)
+}
+
+fn count(usage: &mut ModelCapUsage, requests: &[RequestLog]) {
+    for request in requests {
+        let tokens = request.tokens_in.unwrap_or(0) as u64 + request.tokens_out.unwrap_or(0) as u64;
+        let entry = usage.by_model.entry(request.model.clone()).or_default();
+        entry.0 += 1;
+        entry.1 += tokens;
+    }
+}
+
+fn status_of(usage: &ModelCapUsage, cap: &ModelCap) -> ModelCapStatus {
+    let (requests_today, tokens_today) = consumption(&usage.by_model, cap);
+    let reached_at = usage.reached.get(&cap.id).copied();
+    ModelCapStatus {
+        cap_id: cap.id.clone(),
+        model_pattern: cap.model_pattern.clone(),
+        day: usage.day.clone(),
+        requests_today,
+        tokens_today,
+        max_requests_per_day: cap.max_requests_per_day,
+        max_tokens_per_day: cap.max_tokens_per_day,
+        fallback_model: cap.fallback_model.clone(),
+        reached: reached_at.is_some(),
+        reached_at,
+    }
+}
+
+// Update the reached set against `caps`; returns the caps reached just now
+// and whether the set changed at all
+fn evaluate(
+    usage: &mut ModelCapUsage,
+    caps: &[ModelCap],
+    now_ms: u64,
+) -> (Vec<ModelCapStatus>, bool) {
+    let before = usage.reached.len();
+    let by_model = &usage.by_model;
+    usage.reached.retain(|id, _| {
+        caps.iter().any(|cap| {
+            let (requests, tokens) = consumption(by_model, cap);
+            cap.id == *id && over_limit(cap, requests, tokens)
+        })
+    });
+    let mut changed = usage.reached.len() != before;
+
+    let mut newly = Vec::new();
+    for cap in caps {
+        let (requests, tokens) = consumption(&usage.by_model, cap);
+        if !usage.reached.contains_key(&cap.id) && over_limit(cap, requests, tokens) {
+            usage.reached.insert(cap.id.clone(), now_ms);
+            newly.push(status_of(usage, cap));
+            changed = true;
+        }
+    }
+    (newly, changed)
+}
+
+// Start a new day's counts if the usage day moved on; true if it did
+fn roll_over(usage: &mut ModelCapUsage, today: &str) -> bool {
+    if usage.day == today {
+        return false;
+    }
+    *usage = ModelCapUsage {
+        day: today.to_string(),
+        ..ModelCapUsage::default()
+    };
+    true
+}
+
+// Tell the user about newly reached caps and re-push mappings if needed
+fn announce(app: &AppHandle, newly: Vec<ModelCapStatus>, changed: bool) {
+    for status in &newly {
+        eprintln!(
+            "[ModelCaps] Daily cap reached for {} ({} requests, {} tokens)",
+            status.model_pattern, status.requests_today, status.tokens_today
+        );
+        let _ = app.emit("model-cap-reached", status);
+        crate::notifications::notify_model_cap_reached(app, status);
+    }
+    if changed {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = crate::failover::push_mappings(&app).await {
+                eprintln!("[ModelCaps] Failed to update model mappings: {}", e);
+            }
+        });
+    }
+}
+
+/// Count a batch of new requests from the log watcher
+pub(crate) fn record(app: &AppHandle, batch: &[RequestLog]) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let caps = enabled_caps(&state.config.lock());
+    if caps.is_empty() {
+        return;
+    }
+    let timezone = usage_day::current();
+    let now = chrono::Utc::now();
+    let today = usage_day::day_label(timezone, now);
+    // Demo traffic never counts towards a cap
+    let is_today = |r: &RequestLog| {
+        !r.synthetic && usage_day::day_label_ms(timezone, r.timestamp).as_deref() == Some(&today)
+    };
+
+    let (newly, changed) = {
+        let mut usage = state.model_caps.lock();
+        let first_batch = usage.day.is_empty();
+        let rolled_over = roll_over(&mut usage, &today);
+        if first_batch {
+            // History already holds this batch
+            let todays = state.history.read(|h| {
+                h.requests
+                    .iter()
+                    .filter(|r| is_today(r))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            });
+            count(&mut usage, &todays);
+        } else {
+            let todays: Vec<RequestLog> = batch.iter().filter(|r| is_today(r)).cloned().collect();
+            count(&mut usage, &todays);
+        }
+        let (newly, changed) = evaluate(&mut usage, &caps, now.timestamp_millis() as u64);
+        (newly, changed || (rolled_over && !first_batch))
+    };
+    announce(app, newly, changed);
+}
+
+/// Re-check after caps were edited
+pub(crate) fn refresh(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let caps = enabled_caps(&state.config.lock());
+    let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+    let (newly, changed) = evaluate(&mut state.model_caps.lock(), &caps, now_ms);
+    announce(app, newly, changed);
+}
+
+/// Reset counts and lift redirects when the usage day changes
+pub(crate) fn on_day_changed(app: &AppHandle, today: &str) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let had_reached = {
+        let mut usage = state.model_caps.lock();
+        let had_reached = !usage.reached.is_empty();
+        if !usage.day.is_empty() {
+            roll_over(&mut usage, today);
+        }
+        had_reached
+    };
+    announce(app, Vec::new(), had_reached);
+}
+
+/// Whether any cap currently redirects its models
+pub(crate) fn has_redirects(state: &AppState) -> bool {
+    let caps = enabled_caps(&state.config.lock());
+    let usage = state.model_caps.lock();
+    caps.iter()
+        .any(|cap| cap.fallback_model.is_some() && usage.reached.contains_key(&cap.id))
+}
+
+/// Mappings sending the models of reached caps to their fallback, with
+/// prefixes expanded against `models`
+pub(crate) fn redirects(
+    state: &AppState,
+    config: &AppConfig,
+    models: &[String],
+) -> Vec<FailoverModelMapping> {
+    let usage = state.model_caps.lock();
+    let mappings: Vec<FailoverModelMapping> = config
+        .model_caps
+        .iter()
+        .filter(|cap| cap.enabled && usage.reached.contains_key(&cap.id))
+        .filter_map(|cap| {
+            cap.fallback_model
+                .as_ref()
+                .map(|fallback| FailoverModelMapping {
+                    from: cap.model_pattern.clone(),
+                    to: fallback.clone(),
+                })
+        })
+        .collect();
+    crate::failover::expand(&mappings, models)
+}
+
+/// Today's consumption for every configured cap
+pub(crate) fn status(state: &AppState) -> Vec<ModelCapStatus> {
+    let today = usage_day::day_label(usage_day::current(), chrono::Utc::now());
+    let caps = state.config.lock().model_caps.clone();
+    let mut usage = state.model_caps.lock();
+    if !usage.day.is_empty() {
+        roll_over(&mut usage, &today);
+    }
+    caps.iter()
+        .map(|cap| ModelCapStatus {
+            day: today.clone(),
+            ..status_of(&usage, cap)
+        })
+        .collect()
+}
+
+/// Caps whose fallback model costs more than the models they cover, going by
+/// the catalog's cost tiers
+pub(crate) fn fallback_tier_warnings(caps: &[ModelCap], catalog: &ModelCatalog) -> Vec<String> {
+    caps.iter()
+        .filter_map(|cap| {
+            let fallback = cap.fallback_model.as_deref()?;
+            let fallback_tier = crate::model_catalog::cost_tier(catalog, fallback)?;
+            let capped_tier =
+                crate::model_catalog::cheapest_tier_for_pattern(catalog, &cap.model_pattern)?;
+            (fallback_tier > capped_tier).then(|| {
+                format!(
+                    "The cap on {} falls back to {}, which is in a higher cost tier ({:?} vs {:?})",
+                    cap.model_pattern, fallback, fallback_tier, capped_tier
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cap(id: &str, pattern: &str, requests: Option<u64>, tokens: Option<u64>) -> ModelCap {
+        ModelCap {
+            id: id.to_string(),
+            model_pattern: pattern.to_string(),
+            max_requests_per_day: requests,
+            max_tokens_per_day: tokens,
+            fallback_model: Some("gpt-4o-mini".to_string()),
+            enabled: true,
+        }
+    }
+
+    fn request(model: &str, tokens_out: u32) -> RequestLog {
+        RequestLog {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: 1,
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            method: "POST".to_string(),
+            path: "/v1/chat/completions".to_string(),
+            status: 200,
+            duration_ms: 900,
+            ttfb_ms: None,
+            tokens_in: Some(100),
+            tokens_out: Some(tokens_out),
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    #[test]
+    fn validates_caps() {
+        assert!(validate_caps(&[cap("a", "gpt-5*", Some(50), None)]).is_ok());
+        assert!(validate_caps(&[cap("a", "o3", None, Some(100_000))]).is_ok());
+        assert!(validate_caps(&[cap("a", "gpt-5*", None, None)]).is_err());
+        assert!(validate_caps(&[cap("a", "gpt-*-pro", Some(5), None)]).is_err());
+        assert!(validate_caps(&[cap("a", "", Some(5), None)]).is_err());
+        // The fallback would be capped by the same rule
+        let mut looping = cap("a", "gpt-5*", Some(5), None);
+        looping.fallback_model = Some("gpt-5-mini-2".to_string());
+        assert!(validate_caps(&[looping]).is_err());
+        let twice = [cap("a", "o3", Some(5), None), cap("a", "o1", Some(5), None)];
+        assert!(validate_caps(&twice).is_err());
+    }
+
+    #[test]
+    fn reaches_caps_once_and_resets_with_the_day() {
+        let caps = [
+            cap("requests", "gpt-5*", Some(2), None),
+            cap("tokens", "o3", None, Some(1_000)),
+        ];
+        let mut usage = ModelCapUsage::default();
+        roll_over(&mut usage, "2026-03-10");
+
+        count(
+            &mut usage,
+            &[request("gpt-5", 10), request("claude-sonnet-4-5", 10)],
+        );
+        let (newly, changed) = evaluate(&mut usage, &caps, 1);
+        assert!(newly.is_empty() && !changed);
+
+        count(
+            &mut usage,
+            &[request("gpt-5-codex", 10), request("o3", 950)],
+        );
+        let (newly, changed) = evaluate(&mut usage, &caps, 2);
+        let ids: Vec<&str> = newly.iter().map(|s| s.cap_id.as_str()).collect();
+        assert_eq!(ids, ["requests", "tokens"]);
+        assert!(changed);
+        assert_eq!(newly[0].requests_today, 2);
+        assert_eq!(newly[1].tokens_today, 1_050);
+
+        // Reported once per day
+        count(&mut usage, &[request("gpt-5", 10)]);
+        let (newly, changed) = evaluate(&mut usage, &caps, 3);
+        assert!(newly.is_empty() && !changed);
+
+        // Raising the limit lifts the cap
+        let raised = [cap("requests", "gpt-5*", Some(10), None), caps[1].clone()];
+        let (_, changed) = evaluate(&mut usage, &raised, 4);
+        assert!(changed);
+        assert!(!usage.reached.contains_key("requests"));
+
+        assert!(roll_over(&mut usage, "2026-03-11"));
+        assert!(usage.reached.is_empty() && usage.by_model.is_empty());
+    }
+
+    #[test]
+    fn warns_when_the_fallback_costs_more() {
+        let catalog = crate::model_catalog::builtin();
+        let mut cheap = cap("cheap", "gpt-5*", Some(10), None);
+        assert!(fallback_tier_warnings(&[cheap.clone()], catalog).is_empty());
+
+        cheap.fallback_model = Some("claude-opus-4-1".to_string());
+        let warnings = fallback_tier_warnings(&[cheap], catalog);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("claude-opus-4-1"));
+
+        // Unknown models aren't judged
+        let mut unknown = cap("unknown", "in-house*", Some(10), None);
+        unknown.fallback_model = Some("claude-opus-4-1".to_string());
+        assert!(fallback_tier_warnings(&[unknown], catalog).is_empty());
+    }
+}
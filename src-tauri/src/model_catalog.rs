@@ -0,0 +1,289 @@
+//! Model metadata: context window, vision and tool support, cost tier.
+//!
+//! A catalog ships with the app (`model_catalog.json` beside this file). A
+//! `model_catalog.json` in the config dir overrides it entry by entry, and
+//! `refresh_model_catalog` replaces that file with one downloaded from
+//! `AppConfig.model_catalog_url`, checked against the SHA-256 published next
+//! to it as `<url>.sha256`. A model takes the entry with the longest pattern
+//! that matches it.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::model_caps::matches;
+use crate::types::{AvailableModel, CatalogModel, CostTier, ModelCatalog, ModelCatalogEntry};
+
+const BUILTIN: &str = include_str!("model_catalog.json");
+
+/// Override file in the config dir
+pub(crate) const CATALOG_FILE: &str = "model_catalog.json";
+
+/// The catalog shipped with the app
+pub(crate) fn builtin() -> &'static ModelCatalog {
+    static CATALOG: OnceLock<ModelCatalog> = OnceLock::new();
+    CATALOG.get_or_init(|| serde_json::from_str(BUILTIN).expect("built-in model catalog is valid"))
+}
+
+fn catalog_path() -> PathBuf {
+    crate::config::get_proxypal_config_dir().join(CATALOG_FILE)
+}
+
+/// `base` with the entries of `overrides` replacing those with the same
+/// pattern; the newer version wins
+fn merge(base: &ModelCatalog, overrides: ModelCatalog) -> ModelCatalog {
+    let mut models: Vec<ModelCatalogEntry> = base
+        .models
+        .iter()
+        .filter(|entry| !overrides.models.iter().any(|o| o.pattern == entry.pattern))
+        .cloned()
+        .collect();
+    models.extend(overrides.models);
+    ModelCatalog {
+        version: base.version.max(overrides.version),
+        models,
+    }
+}
+
+fn load_from(path: &Path) -> ModelCatalog {
+    let overrides = match std::fs::read_to_string(path) {
+        Ok(data) => match serde_json::from_str::<ModelCatalog>(&data) {
+            Ok(catalog) => catalog,
+            Err(e) => {
+                eprintln!("[ProxyPal] Ignoring invalid {}: {}", path.display(), e);
+                return builtin().clone();
+            }
+        },
+        Err(_) => return builtin().clone(),
+    };
+    merge(builtin(), overrides)
+}
+
+/// The built-in catalog with the config dir's overrides applied
+pub(crate) fn load() -> ModelCatalog {
+    load_from(&catalog_path())
+}
+
+/// Every pattern non-empty, with at most a trailing `*`
+pub(crate) fn validate(catalog: &ModelCatalog) -> Result<(), String> {
+    if catalog.models.is_empty() {
+        return Err("The model catalog has no models".to_string());
+    }
+    for entry in &catalog.models {
+        let pattern = entry.pattern.trim();
+        if pattern.is_empty() || pattern.trim_end_matches('*').contains('*') {
+            return Err(format!(
+                "Invalid model pattern '{}' in the catalog",
+                entry.pattern
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Most specific entry for `model`; a provider path such as `openrouter/`
+/// in front of the id is ignored
+pub(crate) fn lookup<'a>(catalog: &'a ModelCatalog, model: &str) -> Option<&'a ModelCatalogEntry> {
+    let id = model.rsplit('/').next().unwrap_or(model).to_lowercase();
+    catalog
+        .models
+        .iter()
+        .filter(|entry| matches(&entry.pattern.to_lowercase(), &id))
+        .max_by_key(|entry| {
+            let prefix = entry.pattern.trim_end_matches('*');
+            (prefix.len(), prefix.len() == entry.pattern.len())
+        })
+}
+
+pub(crate) fn cost_tier(catalog: &ModelCatalog, model: &str) -> Option<CostTier> {
+    lookup(catalog, model).map(|entry| entry.cost_tier)
+}
+
+/// Lowest tier among the entries a model pattern (exact id or prefix ending
+/// in `*`) may cover
+pub(crate) fn cheapest_tier_for_pattern(catalog: &ModelCatalog, pattern: &str) -> Option<CostTier> {
+    if !pattern.ends_with('*') {
+        return cost_tier(catalog, pattern);
+    }
+    let prefix = pattern.trim_end_matches('*').to_lowercase();
+    catalog
+        .models
+        .iter()
+        .filter(|entry| {
+            let entry_prefix = entry.pattern.trim_end_matches('*').to_lowercase();
+            entry_prefix.starts_with(&prefix) || matches(&entry.pattern.to_lowercase(), &prefix)
+        })
+        .map(|entry| entry.cost_tier)
+        .min()
+}
+
+/// Attach catalog metadata to each model
+pub(crate) fn annotate(models: Vec<AvailableModel>, catalog: &ModelCatalog) -> Vec<CatalogModel> {
+    models
+        .into_iter()
+        .map(|model| CatalogModel {
+            metadata: lookup(catalog, &model.id).cloned(),
+            model,
+        })
+        .collect()
+}
+
+fn tier_name(tier: CostTier) -> &'static str {
+    match tier {
+        CostTier::Low => "low",
+        CostTier::Medium => "medium",
+        CostTier::High => "high",
+        CostTier::Premium => "premium",
+    }
+}
+
+// One search word against a model: part of its id, provider or owner, a
+// cost tier, or "vision" / "tools"
+fn matches_term(model: &CatalogModel, term: &str) -> bool {
+    let metadata = model.metadata.as_ref();
+    model.model.id.to_lowercase().contains(term)
+        || model.model.provider.to_lowercase().contains(term)
+        || model.model.owned_by.to_lowercase().contains(term)
+        || metadata.is_some_and(|m| match term {
+            "vision" => m.supports_vision,
+            "tools" => m.supports_tools,
+            _ => tier_name(m.cost_tier) == term,
+        })
+}
+
+/// Models matching every word of `query`
+pub(crate) fn search(models: Vec<CatalogModel>, query: &str) -> Vec<CatalogModel> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    models
+        .into_iter()
+        .filter(|model| terms.iter().all(|term| matches_term(model, term)))
+        .collect()
+}
+
+/// Check a downloaded catalog against its published digest and store it as
+/// the override file
+pub(crate) fn install(
+    body: &[u8],
+    checksums: &str,
+    file_name: &str,
+) -> Result<ModelCatalog, String> {
+    let expected = crate::sidecar_download::parse_checksums(checksums, file_name)
+        .ok_or_else(|| format!("No checksum published for {}", file_name))?;
+    crate::sidecar_download::verify_checksum(body, &expected)?;
+    let catalog: ModelCatalog =
+        serde_json::from_slice(body).map_err(|e| format!("Invalid model catalog: {}", e))?;
+    validate(&catalog)?;
+
+    let path = catalog_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, body)
+        .and_then(|_| std::fs::rename(&temp_path, &path))
+        .map_err(|e| format!("Failed to save model catalog: {}", e))?;
+    Ok(load())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pattern: &str, cost_tier: CostTier) -> ModelCatalogEntry {
+        ModelCatalogEntry {
+            pattern: pattern.to_string(),
+            context_window: Some(128_000),
+            supports_vision: false,
+            supports_tools: true,
+            cost_tier,
+        }
+    }
+
+    fn model(id: &str) -> AvailableModel {
+        AvailableModel {
+            id: id.to_string(),
+            owned_by: "anthropic".to_string(),
+            source: "oauth".to_string(),
+            provider: "claude".to_string(),
+            connected: true,
+        }
+    }
+
+    #[test]
+    fn builtin_catalog_is_valid_and_most_specific_pattern_wins() {
+        let catalog = builtin();
+        assert!(validate(catalog).is_ok());
+
+        assert_eq!(
+            cost_tier(catalog, "claude-opus-4-1-20250805"),
+            Some(CostTier::Premium)
+        );
+        assert_eq!(
+            cost_tier(catalog, "claude-opus-4-5-20251101"),
+            Some(CostTier::High)
+        );
+        assert_eq!(cost_tier(catalog, "gpt-5-mini"), Some(CostTier::Low));
+        assert_eq!(
+            cost_tier(catalog, "openrouter/gpt-5"),
+            Some(CostTier::Medium)
+        );
+        assert_eq!(cost_tier(catalog, "some-unknown-model"), None);
+        assert_eq!(
+            cheapest_tier_for_pattern(catalog, "claude-opus*"),
+            Some(CostTier::High)
+        );
+    }
+
+    #[test]
+    fn overrides_replace_entries_by_pattern() {
+        let dir = std::env::temp_dir().join(format!("proxypal-catalog-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CATALOG_FILE);
+
+        let overrides = ModelCatalog {
+            version: 99,
+            models: vec![
+                entry("gpt-5*", CostTier::High),
+                entry("in-house-model", CostTier::Low),
+            ],
+        };
+        std::fs::write(&path, serde_json::to_string(&overrides).unwrap()).unwrap();
+        let catalog = load_from(&path);
+        assert_eq!(catalog.version, 99);
+        assert_eq!(cost_tier(&catalog, "gpt-5"), Some(CostTier::High));
+        assert_eq!(cost_tier(&catalog, "in-house-model"), Some(CostTier::Low));
+        assert_eq!(
+            catalog.models.len(),
+            builtin().models.len() + 1,
+            "gpt-5* is replaced, not duplicated"
+        );
+
+        // A broken override file falls back to the built-in catalog
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(load_from(&path).models, builtin().models);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn search_matches_ids_capabilities_and_tiers() {
+        let catalog = builtin();
+        let models = annotate(
+            vec![
+                model("claude-sonnet-4-5-20250929"),
+                model("claude-3-5-haiku-20241022"),
+                model("qwen3-coder-plus"),
+            ],
+            catalog,
+        );
+        let ids = |models: Vec<CatalogModel>| -> Vec<String> {
+            models.into_iter().map(|m| m.model.id).collect()
+        };
+        assert_eq!(
+            ids(search(models.clone(), "Claude vision")),
+            ["claude-sonnet-4-5-20250929", "claude-3-5-haiku-20241022"]
+        );
+        assert_eq!(ids(search(models.clone(), "low tools")).len(), 2);
+        assert_eq!(ids(search(models, "high")), ["claude-sonnet-4-5-20250929"]);
+    }
+}
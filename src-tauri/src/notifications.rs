@@ -0,0 +1,488 @@
+//! Desktop notifications for proxy lifecycle events.
+//!
+//! Each category can be switched off in `AppConfig.notifications` and has its own
+//! cooldown, so a crash loop or a burst of 429s produces one notification rather
+//! than dozens.
+//!
+//! Desktop notification backends don't report clicks back to the app. Instead the
+//! last notification's `proxypal://` link is kept for a few seconds and consumed
+//! when the main window next gains focus (clicking a notification activates the
+//! app on macOS and Windows), which routes it through the regular deep-link handler.
+//! No link is kept when the window already had focus, since a later focus can't
+//! have come from the notification, and an expired link is dropped rather than
+//! followed on some unrelated alt-tab.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::error::CommandError;
+use crate::state::AppState;
+use crate::types::{
+    IdleWarning, ModelCapStatus, NotificationSettings, QuotaSwitchEvent, RateSoftLimitExceeded,
+    RequestLog, UsageAnomalyStatus,
+};
+
+/// How long a notification's link stays pending waiting for the app to be
+/// activated; about as long as the notification stays on screen
+const PENDING_LINK_TTL: Duration = Duration::from_secs(10);
+
+/// Whether any provider is rate limited, so the successful requests that
+/// follow skip the lock when there is nothing to clear
+static ANY_RATE_LIMITED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationCategory {
+    ProxyCrash,
+    AuthExpired,
+    BudgetThreshold,
+    RateLimit,
+    SidecarUpdate,
+    ProviderHealth,
+    IdleWarning,
+    UsageAnomaly,
+}
+
+impl NotificationCategory {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "proxyCrash" | "proxy_crash" => Some(Self::ProxyCrash),
+            "authExpired" | "auth_expired" => Some(Self::AuthExpired),
+            "budgetThreshold" | "budget_threshold" => Some(Self::BudgetThreshold),
+            "rateLimit" | "rate_limit" => Some(Self::RateLimit),
+            "sidecarUpdate" | "sidecar_update" => Some(Self::SidecarUpdate),
+            "providerHealth" | "provider_health" => Some(Self::ProviderHealth),
+            "idleWarning" | "idle_warning" => Some(Self::IdleWarning),
+            "usageAnomaly" | "usage_anomaly" => Some(Self::UsageAnomaly),
+            _ => None,
+        }
+    }
+
+    fn enabled(self, settings: &NotificationSettings) -> bool {
+        match self {
+            Self::ProxyCrash => settings.proxy_crash,
+            Self::AuthExpired => settings.auth_expired,
+            Self::BudgetThreshold => settings.budget_threshold,
+            Self::RateLimit => settings.rate_limit,
+            Self::SidecarUpdate => settings.sidecar_update,
+            Self::ProviderHealth => settings.provider_health,
+            Self::IdleWarning => settings.idle_warning,
+            Self::UsageAnomaly => settings.usage_anomaly,
+        }
+    }
+
+    fn cooldown(self) -> Duration {
+        match self {
+            Self::ProxyCrash => Duration::from_secs(5 * 60),
+            Self::AuthExpired => Duration::from_secs(30 * 60),
+            Self::BudgetThreshold => Duration::from_secs(60 * 60),
+            Self::RateLimit => Duration::from_secs(10 * 60),
+            Self::SidecarUpdate => Duration::from_secs(24 * 60 * 60),
+            Self::ProviderHealth => Duration::from_secs(15 * 60),
+            Self::IdleWarning => Duration::from_secs(6 * 60 * 60),
+            // Repeats are already limited to a doubled multiplier per day
+            Self::UsageAnomaly => Duration::from_secs(10 * 60),
+        }
+    }
+
+    /// Screen the notification links to
+    fn page(self) -> &'static str {
+        match self {
+            Self::ProxyCrash => "logs",
+            Self::AuthExpired => "auth-files",
+            Self::BudgetThreshold | Self::RateLimit | Self::UsageAnomaly => "analytics",
+            Self::SidecarUpdate => "settings",
+            Self::ProviderHealth | Self::IdleWarning => "dashboard",
+        }
+    }
+}
+
+/// Rate-limit and click-through bookkeeping, kept in `AppState`
+#[derive(Default)]
+pub struct NotificationState {
+    last_sent: HashMap<NotificationCategory, Instant>,
+    pending_link: Option<(String, Instant)>,
+    rate_limited_providers: HashSet<String>,
+}
+
+impl NotificationState {
+    fn should_send(&mut self, category: NotificationCategory, now: Instant) -> bool {
+        if let Some(last) = self.last_sent.get(&category) {
+            if now.duration_since(*last) < category.cooldown() {
+                return false;
+            }
+        }
+        self.last_sent.insert(category, now);
+        true
+    }
+
+    // The pending link, if it is recent enough to be the notification's click
+    fn take_link(&mut self, now: Instant) -> Option<String> {
+        let (link, sent_at) = self.pending_link.take()?;
+        (now.duration_since(sent_at) <= PENDING_LINK_TTL).then_some(link)
+    }
+
+    /// Providers currently returning 429s, sorted
+    pub fn rate_limited_providers(&self) -> Vec<String> {
+        let mut providers: Vec<String> = self.rate_limited_providers.iter().cloned().collect();
+        providers.sort();
+        providers
+    }
+}
+
+/// Send a notification if its category is enabled and not cooling down.
+/// Returns whether it was shown.
+pub fn notify(app: &AppHandle, category: NotificationCategory, title: &str, body: &str) -> bool {
+    let Some(state) = app.try_state::<AppState>() else {
+        return false;
+    };
+    if !category.enabled(&state.config.lock().notifications) {
+        return false;
+    }
+    let focused = main_window_focused(app);
+    {
+        let mut notifications = state.notifications.lock();
+        if !notifications.should_send(category, Instant::now()) {
+            return false;
+        }
+        notifications.pending_link = (!focused).then(|| {
+            (
+                format!("proxypal://navigate/{}", category.page()),
+                Instant::now(),
+            )
+        });
+    }
+    show(app, title, body)
+}
+
+fn main_window_focused(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+fn show(app: &AppHandle, title: &str, body: &str) -> bool {
+    match app.notification().builder().title(title).body(body).show() {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("[ProxyPal] Failed to show notification: {}", e);
+            false
+        }
+    }
+}
+
+/// Route the pending notification link, if any, once the app is activated.
+pub fn open_pending_link(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let pending = state.notifications.lock().take_link(Instant::now());
+    if let Some(url) = pending.and_then(|link| url::Url::parse(&link).ok()) {
+        crate::deep_link::handle_deep_link(app, vec![url]);
+    }
+}
+
+pub fn notify_proxy_crashed(app: &AppHandle, detail: &str) {
+    notify(
+        app,
+        NotificationCategory::ProxyCrash,
+        "ProxyPal proxy crashed",
+        &format!("{}. Open ProxyPal to check the logs.", detail),
+    );
+}
+
+pub fn notify_proxy_restarted(app: &AppHandle) {
+    notify(
+        app,
+        NotificationCategory::ProxyCrash,
+        "ProxyPal proxy restarted",
+        "The proxy is running again.",
+    );
+}
+
+pub fn notify_auth_expired(app: &AppHandle, provider: &str) {
+    notify(
+        app,
+        NotificationCategory::AuthExpired,
+        "Provider sign-in expired",
+        &format!(
+            "Requests to {} are being rejected. Reconnect the account in ProxyPal.",
+            provider
+        ),
+    );
+}
+
+/// Not emitted yet: there is no spend budget to compare against.
+#[allow(dead_code)]
+pub fn notify_budget_threshold(app: &AppHandle, percent: u8, spent_usd: f64, budget_usd: f64) {
+    notify(
+        app,
+        NotificationCategory::BudgetThreshold,
+        "Budget threshold reached",
+        &format!(
+            "You've used {}% of your budget (${:.2} of ${:.2}).",
+            percent, spent_usd, budget_usd
+        ),
+    );
+}
+
+/// Track a provider entering (`limited = true`) or leaving rate limiting and
+/// notify on the transition only.
+pub fn notify_rate_limit(app: &AppHandle, provider: &str, limited: bool) {
+    // Called for every successful request
+    if !limited && !ANY_RATE_LIMITED.load(Ordering::Acquire) {
+        return;
+    }
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let changed = {
+        let mut notifications = state.notifications.lock();
+        let providers = &mut notifications.rate_limited_providers;
+        let changed = if limited {
+            providers.insert(provider.to_string())
+        } else {
+            providers.remove(provider)
+        };
+        ANY_RATE_LIMITED.store(!providers.is_empty(), Ordering::Release);
+        changed
+    };
+    if !changed {
+        return;
+    }
+    if limited {
+        crate::webhooks::emit(
+            app,
+            "rate-limited",
+            &format!("{} is rate limiting requests (429)", provider),
+            serde_json::json!({ "provider": provider }),
+        );
+    }
+    let (title, body) = if limited {
+        (
+            "Provider rate limited",
+            format!("{} is returning 429 Too Many Requests.", provider),
+        )
+    } else {
+        (
+            "Rate limit cleared",
+            format!("{} is accepting requests again.", provider),
+        )
+    };
+    notify(app, NotificationCategory::RateLimit, title, &body);
+}
+
+/// First quota-exceeded switch of the usage day
+pub fn notify_quota_switch(app: &AppHandle, event: &QuotaSwitchEvent) {
+    let account = event
+        .account
+        .as_deref()
+        .map(|a| format!(" ({})", a))
+        .unwrap_or_default();
+    notify(
+        app,
+        NotificationCategory::RateLimit,
+        "Quota exceeded",
+        &format!(
+            "{}{} hit its quota; the proxy switched to {} {}.",
+            event.provider, account, event.kind, event.to
+        ),
+    );
+}
+
+/// A per-model daily cap was reached
+pub fn notify_model_cap_reached(app: &AppHandle, status: &ModelCapStatus) {
+    let action = match &status.fallback_model {
+        Some(fallback) => format!(
+            "Amp requests now go to {} until tomorrow, other clients are not redirected",
+            fallback
+        ),
+        None => "requests still go through".to_string(),
+    };
+    notify(
+        app,
+        NotificationCategory::BudgetThreshold,
+        "Model cap reached",
+        &format!(
+            "{} reached its daily cap ({} requests, {} tokens); {}.",
+            status.model_pattern, status.requests_today, status.tokens_today, action
+        ),
+    );
+}
+
+/// A provider went over its soft rate limit
+pub fn notify_rate_soft_limit(app: &AppHandle, warning: &RateSoftLimitExceeded) {
+    notify(
+        app,
+        NotificationCategory::RateLimit,
+        "Soft rate limit exceeded",
+        &format!(
+            "{} got {:.0} requests in the last minute, over its limit of {}.",
+            warning.provider, warning.measured_per_minute, warning.requests_per_minute
+        ),
+    );
+}
+
+/// A request matched a watch that asked for notifications. Watches are opted
+/// into one by one and keep their own cooldown, so no category applies.
+pub fn notify_request_watch(app: &AppHandle, request: &RequestLog, cost_usd: f64) -> bool {
+    if let Some(state) = app.try_state::<AppState>() {
+        let focused = main_window_focused(app);
+        state.notifications.lock().pending_link =
+            (!focused).then(|| ("proxypal://navigate/logs".to_string(), Instant::now()));
+    }
+    show(
+        app,
+        "Request watch matched",
+        &format!(
+            "{} via {} returned {} (about ${:.4}).",
+            request.model, request.provider, request.status, cost_usd
+        ),
+    )
+}
+
+/// A provider's background health check turned offline or degraded
+pub fn notify_provider_unhealthy(app: &AppHandle, provider: &str, status: &str) {
+    let problem = if status == "offline" {
+        "is not responding"
+    } else {
+        "is responding slowly or with errors"
+    };
+    notify(
+        app,
+        NotificationCategory::ProviderHealth,
+        "Provider unhealthy",
+        &format!("{} {}.", provider, problem),
+    );
+}
+
+/// Agents are set up for the proxy but none of their requests arrive
+pub fn notify_idle_warning(app: &AppHandle, warning: &IdleWarning) {
+    notify(
+        app,
+        NotificationCategory::IdleWarning,
+        "No requests reaching ProxyPal",
+        &format!(
+            "No requests for {} minutes from {}. An agent may be calling its provider directly; run the endpoint audit in Agent Setup.",
+            warning.idle_minutes,
+            warning.configured_agents.join(", ")
+        ),
+    );
+}
+
+/// Today's spend is far above the usual by this hour
+pub fn notify_usage_anomaly(app: &AppHandle, status: &UsageAnomalyStatus) {
+    let mut culprits = Vec::new();
+    if let Some(model) = &status.top_model {
+        culprits.push(format!("{} (${:.2})", model, status.top_model_usd));
+    }
+    if let Some(session) = &status.top_session {
+        culprits.push(format!(
+            "session {} (${:.2})",
+            session, status.top_session_usd
+        ));
+    }
+    let culprits = if culprits.is_empty() {
+        String::new()
+    } else {
+        format!(" Most of it: {}.", culprits.join(", "))
+    };
+    notify(
+        app,
+        NotificationCategory::UsageAnomaly,
+        "Unusual spend today",
+        &format!(
+            "You've spent {:.1}x your usual by this time (${:.2} against ${:.2}).{}",
+            status.multiplier.unwrap_or_default(),
+            status.spent_today_usd,
+            status.baseline_usd.unwrap_or_default(),
+            culprits
+        ),
+    );
+}
+
+/// Not emitted yet: nothing checks for new sidecar releases.
+#[allow(dead_code)]
+pub fn notify_sidecar_update(app: &AppHandle, version: &str) {
+    notify(
+        app,
+        NotificationCategory::SidecarUpdate,
+        "CLIProxyAPI update available",
+        &format!("Version {} of the proxy engine is available.", version),
+    );
+}
+
+/// Show a sample notification for a category, bypassing the toggle and cooldown.
+pub fn send_test(app: &AppHandle, category: &str) -> Result<(), CommandError> {
+    let category = NotificationCategory::parse(category).ok_or_else(|| {
+        CommandError::invalid_input(format!("Unknown notification category: {}", category))
+    })?;
+    let title = match category {
+        NotificationCategory::ProxyCrash => "ProxyPal proxy crashed",
+        NotificationCategory::AuthExpired => "Provider sign-in expired",
+        NotificationCategory::BudgetThreshold => "Budget threshold reached",
+        NotificationCategory::RateLimit => "Provider rate limited",
+        NotificationCategory::SidecarUpdate => "CLIProxyAPI update available",
+        NotificationCategory::ProviderHealth => "Provider unhealthy",
+        NotificationCategory::IdleWarning => "No requests reaching ProxyPal",
+        NotificationCategory::UsageAnomaly => "Unusual spend today",
+    };
+    if show(app, title, "This is a test notification from ProxyPal.") {
+        Ok(())
+    } else {
+        Err("Failed to show notification".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cooldown_is_per_category() {
+        let mut state = NotificationState::default();
+        let now = Instant::now();
+        assert!(state.should_send(NotificationCategory::ProxyCrash, now));
+        assert!(!state.should_send(
+            NotificationCategory::ProxyCrash,
+            now + Duration::from_secs(60)
+        ));
+        assert!(state.should_send(NotificationCategory::RateLimit, now));
+        assert!(state.should_send(
+            NotificationCategory::ProxyCrash,
+            now + NotificationCategory::ProxyCrash.cooldown()
+        ));
+    }
+
+    #[test]
+    fn pending_link_is_taken_once_and_expires() {
+        let mut state = NotificationState::default();
+        let now = Instant::now();
+        state.pending_link = Some(("proxypal://navigate/logs".to_string(), now));
+        assert_eq!(
+            state.take_link(now + Duration::from_secs(2)).as_deref(),
+            Some("proxypal://navigate/logs")
+        );
+        assert_eq!(state.take_link(now + Duration::from_secs(2)), None);
+
+        state.pending_link = Some(("proxypal://navigate/logs".to_string(), now));
+        assert_eq!(state.take_link(now + PENDING_LINK_TTL * 2), None);
+        assert!(state.pending_link.is_none());
+    }
+
+    #[test]
+    fn parses_camel_and_snake_case_categories() {
+        assert_eq!(
+            NotificationCategory::parse("authExpired"),
+            Some(NotificationCategory::AuthExpired)
+        );
+        assert_eq!(
+            NotificationCategory::parse("rate_limit"),
+            Some(NotificationCategory::RateLimit)
+        );
+        assert_eq!(NotificationCategory::parse("nope"), None);
+    }
+}
@@ -0,0 +1,312 @@
+//! Provider metadata registry.
+//!
+//! One entry per provider that ProxyPal connects accounts for. Auth status
+//! scanning, OAuth, disconnect, credential file detection and health checks
+//! all read this table. Adding a provider means one entry here, plus its
+//! `AuthStatus` and `ProviderHealth` fields (and, for key-only providers, its
+//! key list in `AppConfig`); the wiring test below fails until they exist.
+
+use serde::Serialize;
+
+use crate::error::CommandError;
+
+/// How accounts for a provider are connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AuthKind {
+    /// Browser OAuth through the proxy's Management API
+    #[serde(rename = "oauth")]
+    OAuth,
+    /// Device-code flow: the user enters a code on the provider's site
+    #[serde(rename = "device")]
+    Device,
+    /// A service account JSON imported from disk
+    #[serde(rename = "service-account")]
+    ServiceAccount,
+    /// Keys entered in settings; no credential files
+    #[serde(rename = "api-key")]
+    ApiKey,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderMeta {
+    pub id: &'static str,
+    pub display_name: &'static str,
+    pub auth_kind: AuthKind,
+    /// Prefixes of the provider's credential files in the auth directory
+    pub credential_file_prefixes: &'static [&'static str],
+    /// Management API endpoint returning the login URL, e.g. "anthropic-auth-url"
+    pub management_auth_endpoint: Option<&'static str>,
+    /// Also offers a device-code flow through `management_auth_endpoint`
+    pub device_code: bool,
+    /// Login page served by the proxy itself, for providers without an endpoint
+    pub login_page: Option<&'static str>,
+    /// OpenAI-compatible endpoint the proxy sends a key-only provider's requests to
+    pub api_base_url: Option<&'static str>,
+    pub logo: &'static str,
+    pub docs_url: &'static str,
+}
+
+impl ProviderMeta {
+    /// Whether accounts are added through a browser login
+    pub fn has_login_flow(&self) -> bool {
+        self.management_auth_endpoint.is_some() || self.login_page.is_some()
+    }
+}
+
+pub const PROVIDERS: &[ProviderMeta] = &[
+    ProviderMeta {
+        id: "claude",
+        display_name: "Claude",
+        auth_kind: AuthKind::OAuth,
+        credential_file_prefixes: &["claude-", "anthropic-"],
+        management_auth_endpoint: Some("anthropic-auth-url"),
+        device_code: false,
+        login_page: None,
+        api_base_url: None,
+        logo: "/logos/claude.svg",
+        docs_url: "https://docs.anthropic.com",
+    },
+    ProviderMeta {
+        id: "openai",
+        display_name: "ChatGPT",
+        auth_kind: AuthKind::OAuth,
+        credential_file_prefixes: &["codex-"],
+        management_auth_endpoint: Some("codex-auth-url"),
+        device_code: true,
+        login_page: None,
+        api_base_url: None,
+        logo: "/logos/openai.svg",
+        docs_url: "https://github.com/openai/codex",
+    },
+    ProviderMeta {
+        id: "gemini",
+        display_name: "Gemini",
+        auth_kind: AuthKind::OAuth,
+        credential_file_prefixes: &["gemini-"],
+        management_auth_endpoint: Some("gemini-cli-auth-url"),
+        device_code: false,
+        login_page: None,
+        api_base_url: None,
+        logo: "/logos/gemini.svg",
+        docs_url: "https://github.com/google-gemini/gemini-cli",
+    },
+    ProviderMeta {
+        id: "qwen",
+        display_name: "Qwen",
+        auth_kind: AuthKind::Device,
+        credential_file_prefixes: &["qwen-"],
+        management_auth_endpoint: Some("qwen-auth-url"),
+        device_code: true,
+        login_page: None,
+        api_base_url: None,
+        logo: "/logos/qwen.png",
+        docs_url: "https://github.com/QwenLM/qwen-code",
+    },
+    ProviderMeta {
+        id: "iflow",
+        display_name: "iFlow",
+        auth_kind: AuthKind::OAuth,
+        credential_file_prefixes: &["iflow-"],
+        management_auth_endpoint: Some("iflow-auth-url"),
+        device_code: false,
+        login_page: None,
+        api_base_url: None,
+        logo: "/logos/iflow.svg",
+        docs_url: "https://github.com/iflow-ai/iflow-cli",
+    },
+    ProviderMeta {
+        id: "vertex",
+        display_name: "Vertex AI",
+        auth_kind: AuthKind::ServiceAccount,
+        credential_file_prefixes: &["vertex-"],
+        management_auth_endpoint: None,
+        device_code: false,
+        login_page: None,
+        api_base_url: None,
+        logo: "/logos/vertex.svg",
+        docs_url: "https://cloud.google.com/vertex-ai/docs",
+    },
+    ProviderMeta {
+        id: "kiro",
+        display_name: "Kiro",
+        auth_kind: AuthKind::OAuth,
+        credential_file_prefixes: &["kiro-"],
+        management_auth_endpoint: None,
+        device_code: false,
+        login_page: Some("/v0/oauth/kiro"),
+        api_base_url: None,
+        logo: "/logos/kiro.svg",
+        docs_url: "https://kiro.dev",
+    },
+    ProviderMeta {
+        id: "antigravity",
+        display_name: "Antigravity",
+        auth_kind: AuthKind::OAuth,
+        credential_file_prefixes: &["antigravity-"],
+        management_auth_endpoint: Some("antigravity-auth-url"),
+        device_code: false,
+        login_page: None,
+        api_base_url: None,
+        logo: "/logos/antigravity.webp",
+        docs_url: "https://antigravity.google",
+    },
+    ProviderMeta {
+        id: "kimi",
+        display_name: "Kimi",
+        auth_kind: AuthKind::OAuth,
+        credential_file_prefixes: &["kimi-"],
+        management_auth_endpoint: Some("kimi-auth-url"),
+        device_code: false,
+        login_page: None,
+        api_base_url: None,
+        logo: "/logos/kimi.png",
+        docs_url: "https://www.kimi.com",
+    },
+    ProviderMeta {
+        id: "deepseek",
+        display_name: "DeepSeek",
+        auth_kind: AuthKind::ApiKey,
+        credential_file_prefixes: &[],
+        management_auth_endpoint: None,
+        device_code: false,
+        login_page: None,
+        api_base_url: Some("https://api.deepseek.com/v1"),
+        logo: "/logos/deepseek.svg",
+        docs_url: "https://api-docs.deepseek.com",
+    },
+    ProviderMeta {
+        id: "zhipu",
+        display_name: "GLM (Zhipu)",
+        auth_kind: AuthKind::ApiKey,
+        credential_file_prefixes: &[],
+        management_auth_endpoint: None,
+        device_code: false,
+        login_page: None,
+        api_base_url: Some("https://open.bigmodel.cn/api/paas/v4"),
+        logo: "/logos/zhipu.svg",
+        docs_url: "https://docs.bigmodel.cn",
+    },
+];
+
+/// Registry entry for a provider id
+pub fn get(id: &str) -> Option<&'static ProviderMeta> {
+    PROVIDERS.iter().find(|p| p.id == id)
+}
+
+/// Provider ids in registry order
+pub fn ids() -> impl Iterator<Item = &'static str> {
+    PROVIDERS.iter().map(|p| p.id)
+}
+
+/// Logo path under public/, empty for unknown ids
+pub fn logo(id: &str) -> &'static str {
+    get(id).map_or("", |p| p.logo)
+}
+
+/// Providers connected with keys entered in settings, in registry order
+pub fn api_key_providers() -> impl Iterator<Item = &'static ProviderMeta> {
+    PROVIDERS.iter().filter(|p| p.auth_kind == AuthKind::ApiKey)
+}
+
+/// Registry entry for a provider id, or "Unknown provider" for the caller to return
+pub fn lookup(id: &str) -> Result<&'static ProviderMeta, CommandError> {
+    get(id).ok_or_else(|| CommandError::invalid_input(format!("Unknown provider: {}", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::types::{AuthStatus, ProviderHealth};
+    use crate::utils::{detect_provider_from_filename, provider_filename_prefixes};
+
+    #[test]
+    fn ids_are_unique_and_prefixes_disjoint() {
+        let mut prefixes: Vec<&str> = Vec::new();
+        for (i, provider) in PROVIDERS.iter().enumerate() {
+            assert!(
+                PROVIDERS[..i].iter().all(|p| p.id != provider.id),
+                "duplicate provider '{}'",
+                provider.id
+            );
+            for prefix in provider.credential_file_prefixes {
+                assert!(
+                    prefixes
+                        .iter()
+                        .all(|p| !p.starts_with(prefix) && !prefix.starts_with(p)),
+                    "prefix '{}' overlaps another provider's",
+                    prefix
+                );
+                prefixes.push(*prefix);
+            }
+        }
+    }
+
+    #[test]
+    fn registry_is_fully_wired() {
+        let health = ProviderHealth::default();
+        for provider in PROVIDERS {
+            let id = provider.id;
+
+            // OAuth: a login flow, an import for service accounts, or keys
+            // stored in the config and sent to an OpenAI-compatible endpoint
+            assert!(
+                provider.has_login_flow()
+                    || provider.auth_kind == AuthKind::ServiceAccount
+                    || provider.auth_kind == AuthKind::ApiKey,
+                "'{}' has no way to connect an account",
+                id
+            );
+            if provider.auth_kind == AuthKind::ApiKey {
+                assert!(provider.api_base_url.is_some(), "'{}' base URL", id);
+                assert!(
+                    AppConfig::default().provider_api_keys(id).is_some(),
+                    "'{}' missing from AppConfig",
+                    id
+                );
+                assert!(
+                    !crate::commands::models::fallback_model_ids(id).is_empty(),
+                    "'{}' models",
+                    id
+                );
+            }
+            if provider.device_code || provider.auth_kind == AuthKind::Device {
+                assert!(
+                    provider.management_auth_endpoint.is_some(),
+                    "'{}' device flow",
+                    id
+                );
+            }
+
+            // Health reports a status for it
+            assert!(
+                health.status(id).is_some(),
+                "'{}' missing from ProviderHealth",
+                id
+            );
+
+            // Connect and disconnect adjust its AuthStatus count
+            let mut auth = AuthStatus::default();
+            *auth
+                .count_mut(id)
+                .unwrap_or_else(|| panic!("'{}' missing from AuthStatus", id)) += 2;
+            assert_eq!(auth.count(id), 2, "'{}' count", id);
+            assert!(auth.any_connected());
+
+            // Its credential files are detected and matched for deletion
+            if provider.auth_kind == AuthKind::ApiKey {
+                continue;
+            }
+            assert!(
+                !provider_filename_prefixes(id).is_empty(),
+                "'{}' prefixes",
+                id
+            );
+            for prefix in provider.credential_file_prefixes {
+                let filename = format!("{}user@example.com.json", prefix);
+                assert_eq!(detect_provider_from_filename(&filename), id);
+            }
+        }
+    }
+}
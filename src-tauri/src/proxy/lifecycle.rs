@@ -0,0 +1,639 @@
+//! Proxy lifecycle steps that don't depend on a Tauri app handle.
+//!
+//! The desktop commands in `commands::proxy` and the headless entrypoint both
+//! drive the sidecar through these functions; only spawning the process and
+//! reporting status changes differ between the two.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::AppConfig;
+use crate::helpers::log_watcher::start_log_watcher;
+use crate::http::{send_management, HttpClients};
+use crate::state::AppState;
+use crate::types::{BinarySource, ProxyStatus};
+use reqwest::Method;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Everything needed to spawn the sidecar once `prepare_launch` has run
+pub(crate) struct ProxyLaunch {
+    pub config: AppConfig,
+    pub data_dir: PathBuf, // The sidecar's WRITABLE_PATH; its logs go under logs/
+    pub proxy_config_path: PathBuf,
+}
+
+/// Current status if the proxy is already running
+pub(crate) fn running_status(state: &AppState) -> Option<ProxyStatus> {
+    let status = state.proxy_status.lock();
+    status.running.then(|| status.clone())
+}
+
+/// Kill the sidecar an earlier run left behind, make sure the port is free
+/// and write proxy-config.yaml.
+pub(crate) async fn prepare_launch(
+    state: &AppState,
+    config: AppConfig,
+) -> Result<ProxyLaunch, String> {
+
+    // Kill any existing tracked proxy process first
+    {
+        let mut process = state.proxy_process.lock();
+        if let Some(child) = process.take() {
+            println!("[ProxyPal] Killing tracked proxy process");
+            let _ = child.kill(); // Ignore errors, process might already be dead
+        }
+    }
+
+    // A sidecar left running by a crashed earlier run; anything else on the
+    // port is reported by the pre-flight check below rather than killed
+    let _ = tokio::task::spawn_blocking(kill_recorded_sidecar).await;
+    let port = config.port;
+
+    // Delay to ensure port is fully released.
+    // On Windows, TIME_WAIT / CloseWait TCP connections from the previous process can
+    // linger for 2-3s, so we need a longer delay. On Unix, 500ms is sufficient.
+    #[cfg(target_os = "windows")]
+    tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
+    #[cfg(not(target_os = "windows"))]
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    // Pre-flight: verify the port is actually bindable before spawning.
+    // On Windows, Docker Desktop / WSL2 can leave `netsh portproxy` rules that hold
+    // 127.0.0.1:<port> via svchost even when no real service is behind them.
+    // We retry a few times with a short sleep to reduce the TOCTOU window between the
+    // previous process releasing the port and us binding it.
+    {
+        use std::net::TcpListener;
+        let mut bind_ok = false;
+        for attempt in 0..3 {
+            if TcpListener::bind(format!("127.0.0.1:{}", port)).is_ok() {
+                bind_ok = true;
+                break;
+            }
+            if attempt < 2 {
+                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+            }
+        }
+        if !bind_ok {
+            let hint = if cfg!(windows) {
+                format!(
+                    "\n\nHint: Port {} is held by another process (possibly Docker Desktop or WSL2 portproxy).\n\
+                     • Go to Settings → General and change the port to a free one (e.g. 8318).\n\
+                     • Or run as Administrator and execute:\n\
+                     \u{0020} netsh interface portproxy delete v4tov4 listenport={} listenaddress=127.0.0.1",
+                    port, port
+                )
+            } else {
+                String::new()
+            };
+            return Err(format!(
+                "Port {} is already in use and could not be released{}",
+                port, hint
+            ));
+        }
+        // Bind succeeded — drop the listener immediately so the real proxy can take the port.
+    }
+    let proxy_config_path = write_proxy_config(&config)?;
+
+    Ok(ProxyLaunch {
+        config,
+        data_dir: crate::config::get_data_dir(),
+        proxy_config_path,
+    })
+}
+
+/// Generate proxy-config.yaml from `config`. A running sidecar reloads it
+/// when it changes.
+pub(crate) fn write_proxy_config(config: &AppConfig) -> Result<PathBuf, String> {
+    let config_dir = crate::config::get_proxypal_config_dir();
+    crate::helpers::permissions::create_private_dir(&config_dir).map_err(|e| e.to_string())?;
+
+    // Compute the absolute auth-dir path (credential storage for OAuth tokens).
+    // We expand it here so the Go binary receives an absolute path — on Windows `~` is not
+    // expanded automatically, causing credentials to be stored in a literal `~` directory.
+    let auth_dir = crate::helpers::permissions::cli_proxy_auth_dir();
+    crate::helpers::permissions::create_private_dir(&auth_dir).ok(); // Best-effort: create if missing
+
+    let proxy_config_path = config_dir.join("proxy-config.yaml");
+
+    // Build YAML config and append user customizations
+    let proxy_config =
+        crate::commands::proxy::build_proxy_config_yaml(config, &config_dir, &auth_dir)?;
+    std::fs::write(&proxy_config_path, proxy_config).map_err(|e| e.to_string())?;
+    // Holds the management key and provider API keys
+    crate::helpers::permissions::restrict_after_write(&proxy_config_path);
+    Ok(proxy_config_path)
+}
+
+/// Where `update_sidecar` installs a newer CLIProxyAPI. When present it is
+/// started instead of the binary bundled with the app.
+pub(crate) fn updated_sidecar_path() -> PathBuf {
+    crate::config::get_proxypal_config_dir()
+        .join("bin")
+        .join(format!("cli-proxy-api{}", std::env::consts::EXE_SUFFIX))
+}
+
+/// Oldest CLIProxyAPI taken from PATH without a warning (the detailed auth
+/// status endpoint needs v6.6.72+)
+const MIN_SYSTEM_VERSION: (u32, u32, u32) = (6, 6, 72);
+
+/// The CLIProxyAPI binary a start will run
+pub(crate) struct SidecarBinary {
+    pub path: PathBuf,
+    pub source: BinarySource,
+    pub warning: Option<String>,
+    pub version: Option<String>, // "x.y.z" from --version; stamped on the requests it serves
+}
+
+fn sidecar_file_name() -> String {
+    format!("cli-proxy-api{}", std::env::consts::EXE_SUFFIX)
+}
+
+// Sidecars are bundled next to the main executable without the target triple
+fn bundled_sidecar_path() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.parent()?.join(sidecar_file_name()))
+}
+
+// Exists and is an executable, not the placeholder check-only builds leave
+fn is_usable_binary(path: &Path) -> bool {
+    use std::io::Read;
+    let mut magic = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .map(|_| crate::sidecar_download::is_executable(&magic))
+        .unwrap_or(false)
+}
+
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// First `x.y.z` in `--version` output
+pub(crate) fn parse_version(output: &str) -> Option<(u32, u32, u32)> {
+    let re = regex::Regex::new(r"(\d+)\.(\d+)\.(\d+)").ok()?;
+    let caps = re.captures(output)?;
+    Some((
+        caps[1].parse().ok()?,
+        caps[2].parse().ok()?,
+        caps[3].parse().ok()?,
+    ))
+}
+
+pub(crate) async fn binary_version(path: &Path) -> Option<(u32, u32, u32)> {
+    let mut cmd = tokio::process::Command::new(path);
+    cmd.arg("--version").kill_on_drop(true);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    // A build without --version might start serving instead of exiting
+    let output = tokio::time::timeout(tokio::time::Duration::from_secs(5), cmd.output())
+        .await
+        .ok()?
+        .ok()?;
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+        .or_else(|| parse_version(&String::from_utf8_lossy(&output.stderr)))
+}
+
+fn version_label(version: Option<(u32, u32, u32)>) -> Option<String> {
+    version.map(|(major, minor, patch)| format!("{}.{}.{}", major, minor, patch))
+}
+
+fn version_warning(path: &Path, version: Option<(u32, u32, u32)>) -> Option<String> {
+    let (major, minor, patch) = MIN_SYSTEM_VERSION;
+    match version {
+        Some(found) if found >= MIN_SYSTEM_VERSION => None,
+        Some((a, b, c)) => Some(format!(
+            "{} is CLIProxyAPI {}.{}.{}; ProxyPal needs {}.{}.{} or newer, some features may fail",
+            path.display(),
+            a,
+            b,
+            c,
+            major,
+            minor,
+            patch
+        )),
+        None => Some(format!(
+            "Could not determine the version of {}; ProxyPal needs CLIProxyAPI {}.{}.{} or newer",
+            path.display(),
+            major,
+            minor,
+            patch
+        )),
+    }
+}
+
+/// Pick the binary to start: one installed by `update_sidecar`, then the
+/// bundled sidecar, then `cli-proxy-api` on PATH.
+pub(crate) async fn resolve_sidecar_binary() -> Result<SidecarBinary, String> {
+    let custom = updated_sidecar_path();
+    if custom.exists() {
+        return Ok(SidecarBinary {
+            version: version_label(binary_version(&custom).await),
+            path: custom,
+            source: BinarySource::Custom,
+            warning: None,
+        });
+    }
+    let bundled = bundled_sidecar_path();
+    if let Some(path) = bundled.as_ref().filter(|p| is_usable_binary(p)) {
+        return Ok(SidecarBinary {
+            path: path.clone(),
+            source: BinarySource::Bundled,
+            warning: None,
+            version: version_label(binary_version(path).await),
+        });
+    }
+    let Some(path) = find_on_path(&sidecar_file_name()) else {
+        return Err(format!(
+            "Proxy binary not found at {} or on PATH. Reinstall ProxyPal or install cli-proxy-api.",
+            bundled
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(sidecar_file_name)
+        ));
+    };
+    let version = binary_version(&path).await;
+    let warning = version_warning(&path, version);
+    if let Some(warning) = &warning {
+        eprintln!("[ProxyPal] {}", warning);
+    }
+    println!(
+        "[ProxyPal] Bundled sidecar missing, using system binary {}",
+        path.display()
+    );
+    Ok(SidecarBinary {
+        path,
+        source: BinarySource::System,
+        warning,
+        version: version_label(version),
+    })
+}
+
+/// Poll the Management API until the sidecar answers. Returns Ok(false) if it
+/// never became ready within 5s, and Err if the process exited meanwhile.
+pub(crate) async fn wait_until_ready(
+    http: &HttpClients,
+    port: u16,
+    early_exit: &AtomicBool,
+) -> Result<bool, String> {
+    for attempt in 0..25 {
+        // 25 attempts × 200ms = 5s max
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+        // If the process already exited, abort immediately — no point waiting
+        if early_exit.load(Ordering::SeqCst) {
+            eprintln!(
+                "[ProxyPal] Proxy process exited early (port conflict or crash). Aborting start."
+            );
+            let hint = if cfg!(windows) {
+                format!(
+                    " Port {} may still be in use.\n\
+                     Go to Settings → General and change the port, or restart your machine.",
+                    port
+                )
+            } else {
+                format!(" Port {} may still be in use.", port)
+            };
+            return Err(format!(
+                "Proxy failed to start — the process exited immediately.{}",
+                hint
+            ));
+        }
+
+        match send_management(http.management(Method::GET, port, "config.yaml")).await {
+            Ok(_) => return Ok(true),
+            Err(_) => {
+                if attempt == 24 {
+                    eprintln!("[ProxyPal Debug] Proxy not ready after 5s, proceeding anyway");
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Push runtime settings through the Management API (best-effort)
+pub(crate) async fn sync_runtime_settings(http: &HttpClients, config: &AppConfig) {
+    let settings = [
+        (
+            "usage-statistics-enabled",
+            serde_json::json!({"value": config.usage_stats_enabled}),
+        ),
+        (
+            "ampcode/force-model-mappings",
+            serde_json::json!({"value": config.force_model_mappings}),
+        ),
+        (
+            "max-retry-interval",
+            serde_json::json!({"value": config.max_retry_interval}),
+        ),
+        (
+            "quota-exceeded/switch-project",
+            serde_json::json!({"value": config.quota_switch_project}),
+        ),
+        (
+            "quota-exceeded/switch-preview-model",
+            serde_json::json!({"value": config.quota_switch_preview_model}),
+        ),
+    ];
+    for (endpoint, body) in settings {
+        let request = http
+            .management(Method::PUT, config.port, endpoint)
+            .json(&body);
+        if let Err(e) = send_management(request).await {
+            eprintln!("[ProxyPal] Failed to sync {}: {}", endpoint, e);
+        }
+    }
+}
+
+/// Start the log watcher that turns proxy log lines into request history.
+/// `app` is None in headless mode, where nothing listens for events.
+pub(crate) async fn start_request_pipeline(
+    app: Option<tauri::AppHandle>,
+    state: &AppState,
+    data_dir: &Path,
+    port: u16,
+) {
+    // Start log file watcher for request tracking
+    // This replaces the old polling approach and captures ALL requests including Amp proxy forwarding
+    let log_path = data_dir.join("logs").join("main.log");
+    let log_watcher_running = state.log_watcher_running.clone();
+    let request_counter = state.request_counter.clone();
+    let counters = state.request_log_counters.clone();
+    let history = state.history.clone();
+    let http = state.http.clone();
+
+    // Signal any existing watcher to stop, then start new one
+    log_watcher_running.store(false, Ordering::SeqCst);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await; // Give old watcher time to stop
+    log_watcher_running.store(true, Ordering::SeqCst);
+
+    start_log_watcher(
+        app,
+        log_path,
+        log_watcher_running,
+        request_counter,
+        counters,
+        history,
+    );
+
+    // Sync usage statistics from proxy to local history on startup (in background)
+    // This ensures analytics page shows data without requiring restart or manual refresh
+    tokio::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        let _ = http
+            .management(Method::GET, port, "usage")
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await;
+    });
+}
+
+/// Host a client on this machine uses to reach a proxy bound to `bind`
+pub(crate) fn client_host(bind: &str) -> String {
+    let bind = bind.trim().trim_matches(|c| c == '[' || c == ']');
+    match bind.parse::<std::net::IpAddr>() {
+        Ok(ip) if ip.is_unspecified() => "127.0.0.1".to_string(),
+        Ok(std::net::IpAddr::V6(ip)) => format!("[{}]", ip),
+        Ok(ip) => ip.to_string(),
+        Err(_) if bind.is_empty() || bind == "localhost" => "127.0.0.1".to_string(),
+        Err(_) => bind.to_string(),
+    }
+}
+
+/// OpenAI-style base URL of a proxy reachable at `host`:`port`
+pub(crate) fn proxy_endpoint(host: &str, port: u16) -> String {
+    format!("http://{}:{}/v1", host, port)
+}
+
+/// Host and port agents should be pointed at: those of the running proxy
+/// once it answered, otherwise the configured ones the next start will use
+pub(crate) fn client_address(state: &AppState) -> (String, u16) {
+    let endpoint = state.proxy_status.lock().endpoint.clone();
+    let confirmed = url::Url::parse(&endpoint).ok().and_then(|url| {
+        let host = url.host_str()?.to_string();
+        Some((host, url.port()?))
+    });
+    confirmed.unwrap_or_else(|| {
+        let config = state.config.lock();
+        (client_host(&config.proxy_bind), config.port)
+    })
+}
+
+/// Record the proxy as running and return the new status. The endpoint is
+/// only set when `ready`, i.e. the sidecar answered on `port`; otherwise
+/// [`mark_ready`] sets it once it does.
+pub(crate) fn mark_running(
+    state: &AppState,
+    bind: &str,
+    port: u16,
+    binary: &SidecarBinary,
+    ready: bool,
+) -> ProxyStatus {
+    let mut status = state.proxy_status.lock();
+    status.running = true;
+    status.port = port;
+    status.endpoint = if ready {
+        proxy_endpoint(&client_host(bind), port)
+    } else {
+        String::new()
+    };
+    status.last_known_endpoint = None;
+    status.binary_source = binary.source;
+    status.binary_warning = binary.warning.clone();
+    status.sidecar_version = binary.version.clone();
+    status.draining = false;
+    status.paused = false;
+    *state.paused.lock() = None;
+    status.clone()
+}
+
+/// Set the endpoint of a proxy that answered after `mark_running`; None if
+/// it has stopped or moved to another port meanwhile
+pub(crate) fn mark_ready(state: &AppState, bind: &str, port: u16) -> Option<ProxyStatus> {
+    let mut status = state.proxy_status.lock();
+    if !status.running || status.port != port {
+        return None;
+    }
+    status.endpoint = proxy_endpoint(&client_host(bind), port);
+    Some(status.clone())
+}
+
+/// Stop the request pipeline and record the proxy as stopped
+pub(crate) fn mark_stopped(state: &AppState) -> ProxyStatus {
+    state.log_watcher_running.store(false, Ordering::SeqCst);
+    crate::health_monitor::stop_health_monitor(state);
+    crate::idle_watchdog::stop_idle_watchdog(state);
+    state.failover.reset();
+    state.in_flight.lock().clear();
+    *state.proxy_started_at.lock() = None;
+
+    let mut status = state.proxy_status.lock();
+    status.set_stopped();
+    status.clone()
+}
+
+// The PID and binary of the sidecar this app spawned, one per line
+fn pid_file() -> PathBuf {
+    crate::config::get_proxypal_config_dir().join("sidecar.pid")
+}
+
+fn parse_pid_file(contents: &str) -> Option<(u32, String)> {
+    let mut lines = contents.lines();
+    let pid = lines.next()?.trim().parse().ok()?;
+    let name = Path::new(lines.next()?.trim()).file_name()?;
+    Some((pid, name.to_string_lossy().into_owned()))
+}
+
+/// Remember the sidecar just spawned from `binary`, so a later run can clean
+/// it up if this one crashes
+pub(crate) async fn record_sidecar(pid: u32, binary: &Path) {
+    let contents = format!("{}\n{}\n", pid, binary.display());
+    let written = tokio::task::spawn_blocking(move || std::fs::write(pid_file(), contents)).await;
+    if let Ok(Err(e)) = written {
+        eprintln!("[ProxyPal] Failed to record the sidecar PID: {}", e);
+    }
+}
+
+// Whether `pid` is still a process running the binary called `name`; the
+// PID may have been reused since it was recorded
+fn is_sidecar(pid: u32, name: &str) -> bool {
+    #[cfg(unix)]
+    let output = std::process::Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "args="])
+        .output();
+    #[cfg(windows)]
+    let output = {
+        let mut cmd = std::process::Command::new("tasklist");
+        cmd.args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"]);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd.output()
+    };
+    output.is_ok_and(|output| {
+        String::from_utf8_lossy(&output.stdout)
+            .to_lowercase()
+            .contains(&name.to_lowercase())
+    })
+}
+
+fn kill_pid(pid: u32) {
+    #[cfg(unix)]
+    let _ = std::process::Command::new("kill")
+        .args(["-9", &pid.to_string()])
+        .output();
+    #[cfg(windows)]
+    {
+        let mut cmd = std::process::Command::new("taskkill");
+        cmd.args(["/F", "/PID", &pid.to_string()]);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        let _ = cmd.output();
+    }
+}
+
+/// Kill the sidecar recorded by [`record_sidecar`] if it is still running,
+/// then forget it. Other cli-proxy-api processes, such as a standalone
+/// install, are left alone. Blocks on `ps`/`tasklist`; async callers run it
+/// with `spawn_blocking`.
+pub(crate) fn kill_recorded_sidecar() {
+    let path = pid_file();
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    if let Some((pid, name)) = parse_pid_file(&contents) {
+        if is_sidecar(pid, &name) {
+            println!("[ProxyPal] Killing leftover sidecar {} (PID {})", name, pid);
+            kill_pid(pid);
+        }
+    }
+    let _ = std::fs::remove_file(path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_recorded_sidecar() {
+        assert_eq!(
+            parse_pid_file("4242\n/opt/proxypal/cli-proxy-api-plus\n"),
+            Some((4242, "cli-proxy-api-plus".to_string()))
+        );
+        assert_eq!(parse_pid_file("4242\n"), None);
+        assert_eq!(parse_pid_file("not a pid\n/usr/bin/cli-proxy-api\n"), None);
+    }
+
+    #[test]
+    fn warns_about_old_or_unknown_system_binary() {
+        assert_eq!(
+            parse_version("CLIProxyAPI Version: 6.7.1, Commit: abc123"),
+            Some((6, 7, 1))
+        );
+        assert_eq!(
+            parse_version("flag provided but not defined: -version"),
+            None
+        );
+
+        let path = Path::new("/usr/bin/cli-proxy-api");
+        assert!(version_warning(path, Some((6, 7, 1))).is_none());
+        assert!(version_warning(path, Some(MIN_SYSTEM_VERSION)).is_none());
+        assert!(version_warning(path, Some((6, 6, 9)))
+            .unwrap()
+            .contains("6.6.9"));
+        assert!(version_warning(path, None).is_some());
+    }
+
+    #[test]
+    fn client_host_follows_the_bind_address() {
+        assert_eq!(client_host("127.0.0.1"), "127.0.0.1");
+        assert_eq!(client_host(""), "127.0.0.1");
+        assert_eq!(client_host("0.0.0.0"), "127.0.0.1");
+        assert_eq!(client_host("::"), "127.0.0.1");
+        assert_eq!(client_host("::1"), "[::1]");
+        assert_eq!(client_host("192.168.1.20"), "192.168.1.20");
+        assert_eq!(client_host("proxy.lan"), "proxy.lan");
+    }
+
+    #[test]
+    fn endpoint_is_only_set_once_the_proxy_answered() {
+        let state = AppState::default();
+        state.config.lock().port = 8400;
+        let binary = SidecarBinary {
+            path: PathBuf::from("cli-proxy-api"),
+            source: BinarySource::Bundled,
+            warning: None,
+            version: Some("6.7.1".to_string()),
+        };
+        // Nothing has run yet: no endpoint, agents get the configured address
+        assert_eq!(state.proxy_status.lock().endpoint, "");
+        assert_eq!(client_address(&state), ("127.0.0.1".to_string(), 8400));
+
+        // Started but never answered the readiness check
+        let status = mark_running(&state, "0.0.0.0", 8401, &binary, false);
+        assert!(status.running);
+        assert_eq!(status.endpoint, "");
+        assert!(mark_ready(&state, "0.0.0.0", 9999).is_none());
+        let status = mark_ready(&state, "0.0.0.0", 8401).unwrap();
+        assert_eq!(status.endpoint, "http://127.0.0.1:8401/v1");
+        assert_eq!(client_address(&state), ("127.0.0.1".to_string(), 8401));
+
+        // Once stopped the old URL is only a last known value
+        let status = mark_stopped(&state);
+        assert_eq!(status.endpoint, "");
+        assert_eq!(
+            status.last_known_endpoint.as_deref(),
+            Some("http://127.0.0.1:8401/v1")
+        );
+        assert!(mark_ready(&state, "0.0.0.0", 8401).is_none());
+        assert_eq!(client_address(&state), ("127.0.0.1".to_string(), 8400));
+
+        let status = mark_running(&state, "192.168.1.20", 8400, &binary, true);
+        assert_eq!(status.endpoint, "http://192.168.1.20:8400/v1");
+        assert_eq!(status.last_known_endpoint, None);
+    }
+}
@@ -1 +1,4 @@
 // Proxy-specific helpers (config generation, log watcher, etc.) will live here.
+
+pub mod lifecycle;
+pub mod pause;
@@ -0,0 +1,363 @@
+//! Pausing the proxy for a maintenance window.
+//!
+//! [`pause`] marks the proxy as draining, waits until the requests tracked as
+//! in flight from the log have finished (or the timeout passes), then stops
+//! the sidecar and keeps the config it ran with. [`resume`] starts it again
+//! from that snapshot, so settings changed while paused apply on the next
+//! restart. CLIProxyAPI can't be told to refuse new connections, so requests
+//! that arrive while draining are still served and extend the wait.
+//!
+//! With `AppConfig.pause_on_sleep`, the proxy is paused when the system is
+//! about to sleep and resumed on wake. The notifications come from logind's
+//! `PrepareForSleep` signal, so this only works on Linux. logind sends it as
+//! sleep begins, so the watcher holds a delay lock (`Inhibit("sleep", ...,
+//! "delay")`, taken through `systemd-inhibit`) and releases it once the
+//! proxy has drained. The watcher only runs while the setting is on.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands::proxy::{start_proxy_with, stop_proxy_now};
+use crate::config::AppConfig;
+use crate::error::{CommandError, ErrorCode};
+use crate::state::AppState;
+use crate::types::ProxyStatus;
+
+/// Drain timeout when `pause_proxy` is called without one
+pub(crate) const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// logind only delays sleep for a few seconds
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const SLEEP_DRAIN_TIMEOUT: Duration = Duration::from_secs(3);
+
+const DRAIN_POLL: Duration = Duration::from_millis(250);
+
+/// A stopped proxy waiting for `resume_proxy`
+pub struct PausedProxy {
+    config: AppConfig,
+    // Paused by the sleep watcher, so waking resumes it
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    by_sleep: bool,
+}
+
+fn emit_status(app: &AppHandle, state: &AppState) -> ProxyStatus {
+    let status = state.proxy_status.lock().clone();
+    let _ = app.emit("proxy-status-changed", status.clone());
+    status
+}
+
+// Mark the running proxy as draining
+fn begin_pause(state: &AppState) -> Result<(), CommandError> {
+    let mut status = state.proxy_status.lock();
+    if !status.running {
+        return Err(CommandError::new(
+            ErrorCode::ProxyNotRunning,
+            "The proxy is not running",
+        ));
+    }
+    if status.draining {
+        return Err(CommandError::invalid_input("The proxy is already pausing"));
+    }
+    status.draining = true;
+    Ok(())
+}
+
+// The snapshot kept by the last pause
+fn take_paused(state: &AppState) -> Result<PausedProxy, CommandError> {
+    state
+        .paused
+        .lock()
+        .take()
+        .ok_or_else(|| CommandError::invalid_input("The proxy is not paused"))
+}
+
+/// Drain and stop the running proxy, keeping its config for [`resume`]
+pub(crate) async fn pause(
+    app: &AppHandle,
+    drain_timeout: Duration,
+    by_sleep: bool,
+) -> Result<ProxyStatus, CommandError> {
+    let state = app.state::<AppState>();
+    begin_pause(&state)?;
+    let config = state.config.lock().clone();
+    emit_status(app, &state);
+
+    let started = Instant::now();
+    loop {
+        let in_flight = state.in_flight.lock().count();
+        if in_flight == 0 {
+            break;
+        }
+        if started.elapsed() >= drain_timeout {
+            eprintln!(
+                "[ProxyPal] Pausing with {} request(s) still in flight after {}s",
+                in_flight,
+                drain_timeout.as_secs()
+            );
+            break;
+        }
+        tokio::time::sleep(DRAIN_POLL).await;
+    }
+
+    state.proxy_status.lock().draining = false;
+    if let Err(e) = stop_proxy_now(app.clone(), state.clone()).await {
+        emit_status(app, &state);
+        return Err(e.into());
+    }
+    *state.paused.lock() = Some(PausedProxy { config, by_sleep });
+    state.proxy_status.lock().paused = true;
+    println!("[ProxyPal] Proxy paused");
+    Ok(emit_status(app, &state))
+}
+
+/// Start a paused proxy again with the config it was paused with
+pub(crate) async fn resume(app: &AppHandle) -> Result<ProxyStatus, CommandError> {
+    let state = app.state::<AppState>();
+    let paused = take_paused(&state)?;
+    let config = paused.config.clone();
+    match start_proxy_with(app.clone(), state.clone(), config).await {
+        Ok(status) => {
+            println!("[ProxyPal] Proxy resumed");
+            Ok(status)
+        }
+        Err(e) => {
+            // Keep the snapshot so resuming can be retried
+            *state.paused.lock() = Some(paused);
+            state.proxy_status.lock().paused = true;
+            emit_status(app, &state);
+            Err(e.into())
+        }
+    }
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+async fn on_sleep(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    if !state.config.lock().pause_on_sleep || !state.proxy_status.lock().running {
+        return;
+    }
+    println!("[ProxyPal] System is going to sleep, pausing the proxy");
+    if let Err(e) = pause(app, SLEEP_DRAIN_TIMEOUT, true).await {
+        eprintln!("[ProxyPal] Failed to pause before sleep: {}", e);
+    }
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+async fn on_wake(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let by_sleep = state.paused.lock().as_ref().is_some_and(|p| p.by_sleep);
+    if !by_sleep {
+        return;
+    }
+    println!("[ProxyPal] System woke up, resuming the proxy");
+    if let Err(e) = resume(app).await {
+        eprintln!("[ProxyPal] Failed to resume after sleep: {}", e);
+    }
+}
+
+// `gdbus monitor` line for logind's PrepareForSleep: Some(true) before
+// sleep, Some(false) after waking
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_prepare_for_sleep(line: &str) -> Option<bool> {
+    let args = line.split("PrepareForSleep").nth(1)?;
+    if args.contains("true") {
+        Some(true)
+    } else if args.contains("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// `gdbus monitor` following logind's sleep signals, and the delay lock
+/// held until the next sleep
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct SleepWatcher {
+    monitor: std::process::Child,
+    sleep_lock: Arc<Mutex<Option<std::process::Child>>>,
+}
+
+impl SleepWatcher {
+    #[cfg(target_os = "linux")]
+    fn start(app: &AppHandle) -> Option<Self> {
+        use std::io::{BufRead, BufReader};
+
+        let monitor = std::process::Command::new("gdbus")
+            .args([
+                "monitor",
+                "--system",
+                "--dest",
+                "org.freedesktop.login1",
+                "--object-path",
+                "/org/freedesktop/login1",
+            ])
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn();
+        let mut monitor = match monitor {
+            Ok(monitor) => monitor,
+            Err(e) => {
+                eprintln!("[ProxyPal] Sleep/wake events unavailable (gdbus: {})", e);
+                return None;
+            }
+        };
+        let stdout = monitor.stdout.take()?;
+        let sleep_lock = Arc::new(Mutex::new(take_sleep_lock()));
+
+        let app = app.clone();
+        let lock = sleep_lock.clone();
+        std::thread::spawn(move || {
+            // Ends when `stop` kills gdbus
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                let Some(sleeping) = parse_prepare_for_sleep(&line) else {
+                    continue;
+                };
+                let app = app.clone();
+                // Block the reader while pausing so the wake event waits for it
+                tauri::async_runtime::block_on(async move {
+                    if sleeping {
+                        on_sleep(&app).await;
+                    } else {
+                        on_wake(&app).await;
+                    }
+                });
+                if sleeping {
+                    release_sleep_lock(&lock);
+                } else {
+                    *lock.lock() = take_sleep_lock();
+                }
+            }
+        });
+        Some(Self {
+            monitor,
+            sleep_lock,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn start(_app: &AppHandle) -> Option<Self> {
+        None
+    }
+
+    fn stop(mut self) {
+        let _ = self.monitor.kill();
+        let _ = self.monitor.wait();
+        release_sleep_lock(&self.sleep_lock);
+    }
+}
+
+// A logind delay lock, held while the returned `systemd-inhibit` runs. Its
+// `cat` exits when the pipe closes, so the lock also goes if the app dies.
+#[cfg(target_os = "linux")]
+fn take_sleep_lock() -> Option<std::process::Child> {
+    let inhibitor = std::process::Command::new("systemd-inhibit")
+        .args([
+            "--what=sleep",
+            "--mode=delay",
+            "--who=ProxyPal",
+            "--why=Pausing the proxy",
+            "cat",
+        ])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+    match inhibitor {
+        Ok(inhibitor) => Some(inhibitor),
+        Err(e) => {
+            eprintln!("[ProxyPal] Can't delay sleep (systemd-inhibit: {})", e);
+            None
+        }
+    }
+}
+
+fn release_sleep_lock(lock: &Mutex<Option<std::process::Child>>) {
+    if let Some(mut inhibitor) = lock.lock().take() {
+        // Closing stdin ends `cat`, and with it the lock
+        drop(inhibitor.stdin.take());
+        let _ = inhibitor.wait();
+    }
+}
+
+/// Start or stop the sleep watcher to match `pause_on_sleep`. Call after
+/// loading or saving the config.
+pub fn apply_sleep_watcher_config(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let wanted = state.config.lock().pause_on_sleep;
+    let mut current = state.sleep_watcher.lock();
+    if current.is_some() == wanted {
+        return;
+    }
+    if let Some(watcher) = current.take() {
+        watcher.stop();
+    }
+    if wanted {
+        *current = SleepWatcher::start(app);
+    }
+}
+
+/// Stop watching on app exit, releasing the delay lock
+pub fn stop_sleep_watcher(state: &AppState) {
+    if let Some(watcher) = state.sleep_watcher.lock().take() {
+        watcher.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_logind_sleep_signals() {
+        let before =
+            "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)";
+        let after =
+            "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (false,)";
+        assert_eq!(parse_prepare_for_sleep(before), Some(true));
+        assert_eq!(parse_prepare_for_sleep(after), Some(false));
+        assert_eq!(
+            parse_prepare_for_sleep(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.SessionNew ('3', '/org/freedesktop/login1/session/_33')"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn pausing_reports_why_it_cannot() {
+        let state = AppState::default();
+        assert_eq!(
+            begin_pause(&state).unwrap_err().code,
+            ErrorCode::ProxyNotRunning
+        );
+
+        state.proxy_status.lock().running = true;
+        assert!(begin_pause(&state).is_ok());
+        assert!(state.proxy_status.lock().draining);
+        assert_eq!(
+            begin_pause(&state).unwrap_err().code,
+            ErrorCode::InvalidInput
+        );
+    }
+
+    #[test]
+    fn resuming_needs_a_paused_proxy() {
+        let state = AppState::default();
+        assert_eq!(
+            take_paused(&state).err().map(|e| e.code),
+            Some(ErrorCode::InvalidInput)
+        );
+
+        *state.paused.lock() = Some(PausedProxy {
+            config: AppConfig::default(),
+            by_sleep: false,
+        });
+        assert!(take_paused(&state).is_ok());
+        assert!(state.paused.lock().is_none());
+    }
+}
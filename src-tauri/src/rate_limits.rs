@@ -0,0 +1,252 @@
+//! Soft per-provider rate limits.
+//!
+//! `AppConfig.provider_rate_limits` sets a requests-per-minute ceiling per
+//! provider. CLIProxyAPI has no setting to throttle outbound requests by, so
+//! ProxyPal only warns: the log watcher hands every batch of new requests to
+//! [`check`], which runs each provider's requests through a token bucket
+//! holding a minute's worth of requests. A request that finds the bucket empty
+//! is over the limit and is reported as `rate-soft-limit-exceeded` with the
+//! rate measured over the last minute, at most once per `WARN_COOLDOWN` per
+//! provider; the desktop notification goes through the rate-limit category.
+//! Demo traffic is never counted.
+
+use std::collections::{HashMap, VecDeque};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::CommandError;
+use crate::state::AppState;
+use crate::types::{ProviderRateLimit, RateSoftLimitExceeded, RequestLog};
+
+/// Window the measured rate is taken over, in millis
+const RATE_WINDOW_MS: u64 = 60_000;
+
+/// Shortest time between two warnings for the same provider, in millis
+const WARN_COOLDOWN_MS: u64 = 60_000;
+
+struct Bucket {
+    tokens: f64,
+    updated_at: u64,
+    // Timestamps of the provider's requests in the last `RATE_WINDOW_MS`
+    recent: VecDeque<u64>,
+    warned_at: Option<u64>,
+}
+
+/// Token buckets per provider, kept in `AppState`
+#[derive(Default)]
+pub struct RateLimitState {
+    buckets: HashMap<String, Bucket>,
+}
+
+impl RateLimitState {
+    /// Count a request at `now` against `limit`; returns the measured rate
+    /// when it went over and a warning is due
+    fn take(&mut self, limit: &ProviderRateLimit, now: u64) -> Option<f64> {
+        let capacity = limit.requests_per_minute.max(1.0);
+        let bucket = self
+            .buckets
+            .entry(limit.provider.clone())
+            .or_insert_with(|| Bucket {
+                tokens: capacity,
+                updated_at: now,
+                recent: VecDeque::new(),
+                warned_at: None,
+            });
+        // Requests can arrive slightly out of order; never refill backwards
+        let elapsed = now.saturating_sub(bucket.updated_at);
+        bucket.tokens = (bucket.tokens
+            + elapsed as f64 * limit.requests_per_minute / RATE_WINDOW_MS as f64)
+            .min(capacity);
+        bucket.updated_at = bucket.updated_at.max(now);
+
+        bucket.recent.push_back(now);
+        while bucket
+            .recent
+            .front()
+            .is_some_and(|t| now.saturating_sub(*t) >= RATE_WINDOW_MS)
+        {
+            bucket.recent.pop_front();
+        }
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return None;
+        }
+        if bucket
+            .warned_at
+            .is_some_and(|at| now.saturating_sub(at) < WARN_COOLDOWN_MS)
+        {
+            return None;
+        }
+        bucket.warned_at = Some(now);
+        Some(bucket.recent.len() as f64)
+    }
+
+    /// Requests to `provider` in the minute before `now`
+    pub(crate) fn measured(&self, provider: &str, now: u64) -> usize {
+        self.buckets.get(provider).map_or(0, |bucket| {
+            bucket
+                .recent
+                .iter()
+                .filter(|t| now.saturating_sub(**t) < RATE_WINDOW_MS)
+                .count()
+        })
+    }
+
+    /// Drop the bucket of a provider whose limit changed or was removed
+    pub(crate) fn forget(&mut self, provider: &str) {
+        self.buckets.remove(provider);
+    }
+}
+
+/// Lowercase and trim the provider so it matches request logs
+pub(crate) fn normalize(limit: ProviderRateLimit) -> ProviderRateLimit {
+    ProviderRateLimit {
+        provider: limit.provider.trim().to_lowercase(),
+        ..limit
+    }
+}
+
+pub(crate) fn validate(limit: &ProviderRateLimit) -> Result<(), CommandError> {
+    if limit.provider.is_empty() {
+        return Err(CommandError::invalid_input("A rate limit needs a provider"));
+    }
+    if !limit.requests_per_minute.is_finite() || limit.requests_per_minute <= 0.0 {
+        return Err(CommandError::invalid_input(format!(
+            "The rate limit for {} must be a positive number of requests per minute",
+            limit.provider
+        )));
+    }
+    Ok(())
+}
+
+// Warnings for `batch`, in request order
+fn exceeded(
+    state: &mut RateLimitState,
+    limits: &[ProviderRateLimit],
+    batch: &[RequestLog],
+) -> Vec<RateSoftLimitExceeded> {
+    let mut warnings = Vec::new();
+    for request in batch.iter().filter(|r| !r.synthetic) {
+        let Some(limit) = limits
+            .iter()
+            .find(|l| request.provider.eq_ignore_ascii_case(&l.provider))
+        else {
+            continue;
+        };
+        if let Some(measured) = state.take(limit, request.timestamp) {
+            warnings.push(RateSoftLimitExceeded {
+                provider: limit.provider.clone(),
+                requests_per_minute: limit.requests_per_minute,
+                measured_per_minute: measured,
+                timestamp: request.timestamp,
+            });
+        }
+    }
+    warnings
+}
+
+/// Warn about providers a batch from the log watcher pushed over their limit
+pub(crate) fn check(app: &AppHandle, batch: &[RequestLog]) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let limits = state.config.lock().provider_rate_limits.clone();
+    if limits.is_empty() {
+        return;
+    }
+    let warnings = exceeded(&mut state.rate_limits.lock(), &limits, batch);
+    for warning in warnings {
+        let _ = app.emit("rate-soft-limit-exceeded", &warning);
+        crate::notifications::notify_rate_soft_limit(app, &warning);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    fn limit(provider: &str, requests_per_minute: f64) -> ProviderRateLimit {
+        ProviderRateLimit {
+            provider: provider.to_string(),
+            requests_per_minute,
+        }
+    }
+
+    fn request(provider: &str, timestamp: u64) -> RequestLog {
+        RequestLog {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp,
+            provider: provider.to_string(),
+            model: "gemini-2.5-pro".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/chat/completions".to_string(),
+            status: 200,
+            duration_ms: 900,
+            ttfb_ms: None,
+            tokens_in: None,
+            tokens_out: None,
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    #[test]
+    fn validates_limits() {
+        let gemini = normalize(limit(" Gemini ", 10.0));
+        assert_eq!(gemini.provider, "gemini");
+        assert!(validate(&gemini).is_ok());
+        for bad in [
+            limit("", 10.0),
+            limit("gemini", 0.0),
+            limit("gemini", -5.0),
+            limit("gemini", f64::NAN),
+        ] {
+            assert_eq!(
+                validate(&normalize(bad)).unwrap_err().code,
+                ErrorCode::InvalidInput
+            );
+        }
+    }
+
+    #[test]
+    fn warns_once_a_burst_empties_the_bucket() {
+        let limits = [limit("gemini", 10.0)];
+        let mut state = RateLimitState::default();
+        let start = 1_767_225_600_000;
+
+        // Ten requests in a second fit the bucket; the eleventh doesn't
+        let mut burst: Vec<RequestLog> = (0..12)
+            .map(|i| request("gemini", start + i * 100))
+            .collect();
+        let mut demo = request("gemini", start + 1_150);
+        demo.synthetic = true;
+        burst.push(demo);
+        burst.push(request("claude", start + 1_150));
+        let warnings = exceeded(&mut state, &limits, &burst);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].timestamp, start + 1_000);
+        assert_eq!(warnings[0].measured_per_minute, 11.0);
+        assert_eq!(state.measured("gemini", start + 1_200), 12);
+
+        // Still over the limit, but within the cooldown
+        let later = [request("gemini", start + 2_000)];
+        assert!(exceeded(&mut state, &limits, &later).is_empty());
+
+        // A minute refills the bucket and the burst has left the window
+        let next = [request("gemini", start + 90_000)];
+        assert!(exceeded(&mut state, &limits, &next).is_empty());
+        assert_eq!(state.measured("gemini", start + 90_000), 1);
+        state.forget("gemini");
+        assert_eq!(state.measured("gemini", start + 90_000), 0);
+    }
+}
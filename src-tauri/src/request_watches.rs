@@ -0,0 +1,307 @@
+//! Request watches.
+//!
+//! `AppConfig.request_watches` holds filters over live traffic: provider,
+//! model pattern, minimum estimated cost and status class. The log watcher
+//! hands every batch of new requests to [`check`], and each request matching
+//! a watch is emitted as `request-watch-hit`. Watches with `notify` also show
+//! a desktop notification, at most one per `NOTIFY_COOLDOWN` per watch, so a
+//! leaking model mapping doesn't bury the desktop in notifications. Demo
+//! traffic never matches.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::CommandError;
+use crate::model_caps::matches as model_matches;
+use crate::state::AppState;
+use crate::types::{RequestLog, RequestWatch, RequestWatchFilter, RequestWatchHit};
+use crate::utils::estimate_request_cost;
+
+/// Shortest time between two notifications for the same watch
+const NOTIFY_COOLDOWN: Duration = Duration::from_secs(60);
+
+const STATUS_CLASSES: &[&str] = &["2xx", "3xx", "4xx", "5xx"];
+
+/// Notification bookkeeping, kept in `AppState`
+#[derive(Default)]
+pub struct RequestWatchState {
+    last_notified: HashMap<String, Instant>,
+}
+
+impl RequestWatchState {
+    fn should_notify(&mut self, watch_id: &str, now: Instant) -> bool {
+        if let Some(last) = self.last_notified.get(watch_id) {
+            if now.duration_since(*last) < NOTIFY_COOLDOWN {
+                return false;
+            }
+        }
+        self.last_notified.insert(watch_id.to_string(), now);
+        true
+    }
+
+    /// Drop bookkeeping for a deleted watch
+    pub(crate) fn forget(&mut self, watch_id: &str) {
+        self.last_notified.remove(watch_id);
+    }
+}
+
+/// Trim the filter's text fields; empty ones match everything
+pub(crate) fn normalize(filter: RequestWatchFilter) -> RequestWatchFilter {
+    let text = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_lowercase())
+            .filter(|v| !v.is_empty())
+    };
+    RequestWatchFilter {
+        provider: text(filter.provider),
+        model_pattern: text(filter.model_pattern),
+        status_class: text(filter.status_class),
+        ..filter
+    }
+}
+
+pub(crate) fn validate_filter(filter: &RequestWatchFilter) -> Result<(), CommandError> {
+    if filter.provider.is_none()
+        && filter.model_pattern.is_none()
+        && filter.min_cost_usd.is_none()
+        && filter.status_class.is_none()
+    {
+        return Err(CommandError::invalid_input(
+            "A request watch needs at least one criterion",
+        ));
+    }
+    if let Some(pattern) = &filter.model_pattern {
+        if pattern.trim_end_matches('*').contains('*') {
+            return Err(CommandError::invalid_input(format!(
+                "Invalid model pattern '{}': only a trailing * is supported",
+                pattern
+            )));
+        }
+    }
+    if let Some(min_cost) = filter.min_cost_usd {
+        if !min_cost.is_finite() || min_cost < 0.0 {
+            return Err(CommandError::invalid_input(
+                "The minimum cost must be a positive amount",
+            ));
+        }
+    }
+    if let Some(class) = &filter.status_class {
+        if !STATUS_CLASSES.contains(&class.as_str()) {
+            return Err(CommandError::invalid_input(format!(
+                "Unknown status class '{}': use 2xx, 3xx, 4xx or 5xx",
+                class
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `request`, with its estimated cost, passes every set criterion
+pub(crate) fn matches(filter: &RequestWatchFilter, request: &RequestLog, cost_usd: f64) -> bool {
+    if let Some(provider) = &filter.provider {
+        if !request.provider.eq_ignore_ascii_case(provider) {
+            return false;
+        }
+    }
+    if let Some(pattern) = &filter.model_pattern {
+        if !model_matches(pattern, &request.model.to_lowercase()) {
+            return false;
+        }
+    }
+    if filter.min_cost_usd.is_some_and(|min| cost_usd < min) {
+        return false;
+    }
+    if let Some(class) = &filter.status_class {
+        if *class != format!("{}xx", request.status / 100) {
+            return false;
+        }
+    }
+    true
+}
+
+fn cost_of(request: &RequestLog) -> f64 {
+    estimate_request_cost(
+        &request.model,
+        request.tokens_in.unwrap_or(0),
+        request.tokens_out.unwrap_or(0),
+    )
+}
+
+// Hits in `batch` for `watches`, in request order
+fn hits(watches: &[RequestWatch], batch: &[RequestLog]) -> Vec<RequestWatchHit> {
+    let mut hits = Vec::new();
+    for request in batch.iter().filter(|r| !r.synthetic) {
+        let cost_usd = cost_of(request);
+        for watch in watches {
+            if matches(&watch.filter, request, cost_usd) {
+                hits.push(RequestWatchHit {
+                    watch_id: watch.id.clone(),
+                    cost_usd,
+                    request: request.clone(),
+                });
+            }
+        }
+    }
+    hits
+}
+
+/// Report the requests of a batch from the log watcher that match a watch
+pub(crate) fn check(app: &AppHandle, batch: &[RequestLog]) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let watches = state.config.lock().request_watches.clone();
+    if watches.is_empty() {
+        return;
+    }
+    for hit in hits(&watches, batch) {
+        let _ = app.emit("request-watch-hit", &hit);
+        let notify = watches.iter().any(|w| w.id == hit.watch_id && w.notify)
+            && state
+                .request_watches
+                .lock()
+                .should_notify(&hit.watch_id, Instant::now());
+        if notify {
+            crate::notifications::notify_request_watch(app, &hit.request, hit.cost_usd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    fn filter() -> RequestWatchFilter {
+        RequestWatchFilter::default()
+    }
+
+    fn request(provider: &str, model: &str, status: u16, tokens_out: u32) -> RequestLog {
+        RequestLog {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: 1,
+            provider: provider.to_string(),
+            model: model.to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status,
+            duration_ms: 900,
+            ttfb_ms: None,
+            tokens_in: Some(1_000),
+            tokens_out: Some(tokens_out),
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    #[test]
+    fn validates_filters() {
+        assert_eq!(
+            validate_filter(&filter()).unwrap_err().code,
+            ErrorCode::InvalidInput
+        );
+        let opus = normalize(RequestWatchFilter {
+            model_pattern: Some(" Claude-Opus* ".to_string()),
+            status_class: Some("".to_string()),
+            ..filter()
+        });
+        assert_eq!(opus.model_pattern.as_deref(), Some("claude-opus*"));
+        assert_eq!(opus.status_class, None);
+        assert!(validate_filter(&opus).is_ok());
+
+        let bad_pattern = RequestWatchFilter {
+            model_pattern: Some("claude-*-4".to_string()),
+            ..filter()
+        };
+        assert!(validate_filter(&bad_pattern).is_err());
+        let bad_class = RequestWatchFilter {
+            status_class: Some("6xx".to_string()),
+            ..filter()
+        };
+        assert!(validate_filter(&bad_class).is_err());
+        let bad_cost = RequestWatchFilter {
+            min_cost_usd: Some(-1.0),
+            ..filter()
+        };
+        assert!(validate_filter(&bad_cost).is_err());
+    }
+
+    #[test]
+    fn matches_every_set_criterion() {
+        let opus = request("claude", "claude-opus-4-5", 200, 2_000);
+        let cost = cost_of(&opus);
+        assert!(cost > 0.1);
+
+        let by_model = RequestWatchFilter {
+            model_pattern: Some("claude-opus*".to_string()),
+            ..filter()
+        };
+        assert!(matches(&by_model, &opus, cost));
+        assert!(!matches(
+            &by_model,
+            &request("claude", "claude-sonnet-4-5", 200, 2_000),
+            cost
+        ));
+
+        let expensive_errors = RequestWatchFilter {
+            provider: Some("claude".to_string()),
+            min_cost_usd: Some(0.1),
+            status_class: Some("5xx".to_string()),
+            ..filter()
+        };
+        assert!(!matches(&expensive_errors, &opus, cost));
+        let failed = request("claude", "claude-opus-4-5", 503, 2_000);
+        assert!(matches(&expensive_errors, &failed, cost));
+        assert!(!matches(&expensive_errors, &failed, 0.01));
+        let elsewhere = request("openai", "claude-opus-4-5", 503, 2_000);
+        assert!(!matches(&expensive_errors, &elsewhere, cost));
+    }
+
+    #[test]
+    fn reports_hits_per_watch_and_skips_demo_traffic() {
+        let watch = |id: &str, pattern: &str| RequestWatch {
+            id: id.to_string(),
+            filter: RequestWatchFilter {
+                model_pattern: Some(pattern.to_string()),
+                ..filter()
+            },
+            notify: true,
+            created_at: 1,
+        };
+        let watches = [watch("opus", "claude-opus*"), watch("claude", "claude-*")];
+        let mut demo = request("claude", "claude-opus-4-5", 200, 10);
+        demo.synthetic = true;
+        let batch = [
+            request("claude", "claude-opus-4-5", 200, 10),
+            request("openai", "gpt-5", 200, 10),
+            demo,
+        ];
+        let ids: Vec<String> = hits(&watches, &batch)
+            .into_iter()
+            .map(|h| h.watch_id)
+            .collect();
+        assert_eq!(ids, ["opus", "claude"]);
+    }
+
+    #[test]
+    fn notifications_cool_down_per_watch() {
+        let mut state = RequestWatchState::default();
+        let now = Instant::now();
+        assert!(state.should_notify("a", now));
+        assert!(!state.should_notify("a", now + Duration::from_secs(10)));
+        assert!(state.should_notify("b", now + Duration::from_secs(10)));
+        assert!(state.should_notify("a", now + NOTIFY_COOLDOWN));
+        state.forget("b");
+        assert!(state.should_notify("b", now + Duration::from_secs(11)));
+    }
+}
@@ -0,0 +1,369 @@
+//! Weekly proxy timetable.
+//!
+//! `AppConfig.schedule` lists local-time windows the proxy should run in. A
+//! background task starts the proxy when a window opens and stops it when the
+//! window closes, acting only when the wanted state changes, so a proxy that
+//! crashed or was stopped in between is not restarted behind the user's back.
+//!
+//! A manual start or stop overrides the schedule until the next boundary.
+//! Starting the proxy outside a window needs an explicit override, so the
+//! timetable can't be bypassed by accident (a tray click, a stale shortcut).
+
+use std::time::Duration;
+
+use chrono::{Datelike, Local, NaiveDateTime, TimeZone, Timelike};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::config::AppConfig;
+use crate::state::AppState;
+use crate::types::{ProxySchedule, ScheduleStatus, ScheduleWindow};
+
+/// Longest single wait, so a wake from sleep or a changed clock is noticed
+/// without waiting for the old boundary
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+#[derive(Default)]
+struct ScheduleState {
+    // Manual start/stop in effect until this local time
+    override_until: Option<NaiveDateTime>,
+    // State the schedule last asked for; None re-applies it on the next pass
+    last_wanted: Option<bool>,
+}
+
+#[derive(Default)]
+pub struct ProxyScheduler {
+    state: Mutex<ScheduleState>,
+    wake: tokio::sync::Notify,
+}
+
+// A window resolved to weekday and minutes since midnight
+struct Window {
+    day: chrono::Weekday,
+    start: u32,
+    end: u32,
+}
+
+fn parse_minutes(value: &str, allow_end_of_day: bool) -> Result<u32, String> {
+    if allow_end_of_day && value == "24:00" {
+        return Ok(MINUTES_PER_DAY);
+    }
+    let time = chrono::NaiveTime::parse_from_str(value, "%H:%M")
+        .map_err(|_| format!("Invalid time '{}', expected HH:MM", value))?;
+    Ok(time.hour() * 60 + time.minute())
+}
+
+fn parse_window(window: &ScheduleWindow) -> Result<Window, String> {
+    let day = window
+        .day
+        .parse::<chrono::Weekday>()
+        .map_err(|_| format!("Invalid day '{}', expected mon…sun", window.day))?;
+    let start = parse_minutes(&window.start, false)?;
+    let end = parse_minutes(&window.end, true)?;
+    if start >= end {
+        return Err(format!(
+            "Window {} {}-{} ends before it starts; split windows that cross midnight in two",
+            window.day, window.start, window.end
+        ));
+    }
+    Ok(Window { day, start, end })
+}
+
+fn parse_windows(schedule: &ProxySchedule) -> Result<Vec<Window>, String> {
+    schedule.windows.iter().map(parse_window).collect()
+}
+
+/// Reject malformed times and windows that overlap on the same day
+pub(crate) fn validate_schedule(schedule: &ProxySchedule) -> Result<(), String> {
+    let windows = parse_windows(schedule)?;
+    for (i, a) in windows.iter().enumerate() {
+        for (j, b) in windows.iter().enumerate().skip(i + 1) {
+            if a.day == b.day && a.start < b.end && b.start < a.end {
+                let (wa, wb) = (&schedule.windows[i], &schedule.windows[j]);
+                return Err(format!(
+                    "Windows {} {}-{} and {} {}-{} overlap",
+                    wa.day, wa.start, wa.end, wb.day, wb.start, wb.end
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The schedule in force, if one is configured and enabled
+fn active_schedule(config: &AppConfig) -> Option<ProxySchedule> {
+    config.schedule.clone().filter(|s| s.enabled)
+}
+
+/// Whether the proxy should run at local time `now`
+fn in_window(schedule: &ProxySchedule, now: NaiveDateTime) -> bool {
+    let minute = now.hour() * 60 + now.minute();
+    parse_windows(schedule)
+        .unwrap_or_default()
+        .iter()
+        .any(|w| w.day == now.weekday() && w.start <= minute && minute < w.end)
+}
+
+/// Next window start or end after `now`
+fn next_boundary(schedule: &ProxySchedule, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let windows = parse_windows(schedule).unwrap_or_default();
+    let windows = &windows;
+    (0..=7)
+        .flat_map(move |offset| {
+            let midnight = (now.date() + chrono::Days::new(offset))
+                .and_hms_opt(0, 0, 0)
+                .unwrap_or_default();
+            windows
+                .iter()
+                .filter(move |w| w.day == midnight.weekday())
+                .flat_map(move |w| {
+                    [w.start, w.end].map(|m| midnight + chrono::Duration::minutes(m as i64))
+                })
+        })
+        .filter(|at| *at > now)
+        .min()
+}
+
+// "17:00" for later today, "Mon 17:00" beyond that
+fn boundary_label(at: NaiveDateTime, now: NaiveDateTime) -> String {
+    if at.date() == now.date() {
+        at.format("%H:%M").to_string()
+    } else {
+        at.format("%a %H:%M").to_string()
+    }
+}
+
+fn to_millis(at: NaiveDateTime) -> Option<u64> {
+    Local
+        .from_local_datetime(&at)
+        .earliest()
+        .map(|at| at.timestamp_millis() as u64)
+}
+
+fn override_active(state: &ScheduleState, now: NaiveDateTime) -> bool {
+    state.override_until.is_some_and(|until| now < until)
+}
+
+/// "17:00" while the schedule keeps the proxy off, for the tray tooltip.
+/// Some("") when no window is coming up at all.
+pub(crate) fn scheduled_off_until(state: &AppState) -> Option<String> {
+    let schedule = active_schedule(&state.config.lock())?;
+    let now = Local::now().naive_local();
+    if override_active(&state.scheduler.state.lock(), now) || in_window(&schedule, now) {
+        return None;
+    }
+    Some(
+        next_boundary(&schedule, now)
+            .map(|at| boundary_label(at, now))
+            .unwrap_or_default(),
+    )
+}
+
+/// Where the schedule stands right now
+pub(crate) fn status(state: &AppState) -> ScheduleStatus {
+    let Some(schedule) = active_schedule(&state.config.lock()) else {
+        return ScheduleStatus::default();
+    };
+    let now = Local::now().naive_local();
+    ScheduleStatus {
+        enabled: true,
+        in_window: in_window(&schedule, now),
+        overridden: override_active(&state.scheduler.state.lock(), now),
+        next_boundary: next_boundary(&schedule, now).and_then(to_millis),
+    }
+}
+
+// Let a manual start/stop win until the next boundary
+fn override_until_next_boundary(state: &AppState, schedule: &ProxySchedule) {
+    let now = Local::now().naive_local();
+    state.scheduler.state.lock().override_until =
+        Some(next_boundary(schedule, now).unwrap_or(NaiveDateTime::MAX));
+}
+
+/// Check a manual start against the schedule. Outside a window it is refused
+/// unless `override_schedule` is set, in which case the proxy stays up until
+/// the next boundary.
+pub(crate) fn allow_manual_start(state: &AppState, override_schedule: bool) -> Result<(), String> {
+    let Some(schedule) = active_schedule(&state.config.lock()) else {
+        return Ok(());
+    };
+    let now = Local::now().naive_local();
+    if in_window(&schedule, now) || override_active(&state.scheduler.state.lock(), now) {
+        return Ok(());
+    }
+    if !override_schedule {
+        let until = next_boundary(&schedule, now)
+            .map(|at| format!(" until {}", boundary_label(at, now)))
+            .unwrap_or_default();
+        return Err(format!(
+            "The proxy is scheduled off{}. Start it with the schedule override to run it anyway.",
+            until
+        ));
+    }
+    override_until_next_boundary(state, &schedule);
+    Ok(())
+}
+
+/// A manual stop inside a window holds until the window closes
+pub(crate) fn note_manual_stop(state: &AppState) {
+    let Some(schedule) = active_schedule(&state.config.lock()) else {
+        return;
+    };
+    if in_window(&schedule, Local::now().naive_local()) {
+        override_until_next_boundary(state, &schedule);
+    }
+}
+
+/// Whether the schedule, rather than auto-start, decides if the proxy runs at launch
+pub(crate) fn controls_startup(config: &AppConfig) -> bool {
+    active_schedule(config).is_some()
+}
+
+/// Re-evaluate after the schedule changed; drops any manual override
+pub(crate) fn reschedule(state: &AppState) {
+    *state.scheduler.state.lock() = ScheduleState::default();
+    state.scheduler.wake.notify_one();
+}
+
+// Start or stop the proxy if the wanted state changed; returns how long to
+// sleep before looking again
+async fn enforce(app: &AppHandle) -> Duration {
+    let state = app.state::<AppState>();
+    let Some(schedule) = active_schedule(&state.config.lock()) else {
+        return MAX_WAIT;
+    };
+    let now = Local::now().naive_local();
+    let wait = next_boundary(&schedule, now)
+        .and_then(|at| (at - now).to_std().ok())
+        .unwrap_or(MAX_WAIT)
+        .clamp(Duration::from_secs(1), MAX_WAIT);
+
+    let wanted = {
+        let mut scheduled = state.scheduler.state.lock();
+        if override_active(&scheduled, now) {
+            return wait;
+        }
+        if scheduled.override_until.take().is_some() {
+            scheduled.last_wanted = None;
+        }
+        let wanted = in_window(&schedule, now);
+        if scheduled.last_wanted == Some(wanted) {
+            return wait;
+        }
+        scheduled.last_wanted = Some(wanted);
+        wanted
+    };
+
+    let running = state.proxy_status.lock().running;
+    if wanted && !running {
+        println!("[Scheduler] Schedule window opened, starting the proxy");
+        if let Err(e) = crate::commands::proxy::start_proxy_now(app.clone(), state).await {
+            eprintln!("[Scheduler] Scheduled start failed: {}", e);
+        }
+    } else if !wanted && running {
+        println!("[Scheduler] Schedule window closed, stopping the proxy");
+        if let Err(e) = crate::commands::proxy::stop_proxy_now(app.clone(), state).await {
+            eprintln!("[Scheduler] Scheduled stop failed: {}", e);
+        }
+    }
+    crate::tray::refresh_tray_tooltip(app);
+    wait
+}
+
+/// Run the schedule for the rest of the session
+pub fn start_scheduler(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let wait = enforce(&app).await;
+            let state = app.state::<AppState>();
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = state.scheduler.wake.notified() => {}
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(day: &str, start: &str, end: &str) -> ScheduleWindow {
+        ScheduleWindow {
+            day: day.to_string(),
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    fn schedule(windows: Vec<ScheduleWindow>) -> ProxySchedule {
+        ProxySchedule {
+            enabled: true,
+            windows,
+        }
+    }
+
+    fn local(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn validates_windows() {
+        let evenings = schedule(vec![
+            window("mon", "17:00", "21:00"),
+            window("tue", "17:00", "21:00"),
+            window("mon", "21:00", "24:00"),
+        ]);
+        assert!(validate_schedule(&evenings).is_ok());
+
+        let overlapping = schedule(vec![
+            window("mon", "17:00", "21:00"),
+            window("Monday", "20:00", "22:00"),
+        ]);
+        assert!(validate_schedule(&overlapping)
+            .unwrap_err()
+            .contains("overlap"));
+
+        let overnight = schedule(vec![window("fri", "22:00", "02:00")]);
+        assert!(validate_schedule(&overnight)
+            .unwrap_err()
+            .contains("cross midnight"));
+        assert!(validate_schedule(&schedule(vec![window("someday", "17:00", "18:00")])).is_err());
+        assert!(validate_schedule(&schedule(vec![window("mon", "5pm", "18:00")])).is_err());
+    }
+
+    #[test]
+    fn finds_the_current_window_and_next_boundary() {
+        // 2026-03-09 is a Monday
+        let weekday_evenings = schedule(
+            ["mon", "tue", "wed", "thu", "fri"]
+                .iter()
+                .map(|day| window(day, "17:00", "21:00"))
+                .collect(),
+        );
+        let afternoon = local("2026-03-09 14:30");
+        assert!(!in_window(&weekday_evenings, afternoon));
+        let next = next_boundary(&weekday_evenings, afternoon).unwrap();
+        assert_eq!(next, local("2026-03-09 17:00"));
+        assert_eq!(boundary_label(next, afternoon), "17:00");
+
+        let evening = local("2026-03-09 17:00");
+        assert!(in_window(&weekday_evenings, evening));
+        assert_eq!(
+            next_boundary(&weekday_evenings, evening),
+            Some(local("2026-03-09 21:00"))
+        );
+
+        // Friday night waits for Monday
+        let friday = local("2026-03-13 21:00");
+        assert!(!in_window(&weekday_evenings, friday));
+        let monday = next_boundary(&weekday_evenings, friday).unwrap();
+        assert_eq!(monday, local("2026-03-16 17:00"));
+        assert_eq!(boundary_label(monday, friday), "Mon 17:00");
+
+        assert_eq!(next_boundary(&schedule(vec![]), friday), None);
+    }
+}
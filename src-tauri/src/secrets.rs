@@ -0,0 +1,140 @@
+//! Passphrases collected from the user for a single operation.
+//!
+//! `request_passphrase` emits `passphrase-requested` for the frontend to show
+//! its dialog, then waits for `answer_passphrase_request` on a one-shot
+//! channel. The passphrase never goes through the config, events or logs, and
+//! is zeroized when the caller drops it. A prompt nobody answers gives up after
+//! `PROMPT_TIMEOUT` and tells the frontend to close the dialog.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::oneshot;
+use zeroize::Zeroizing;
+
+use crate::error::{CommandError, ErrorCode};
+use crate::state::AppState;
+use crate::types::PassphraseRequest;
+
+pub(crate) const PROMPT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+pub(crate) const PURPOSE_EXPORT_CREDENTIALS: &str = "export-credentials";
+pub(crate) const PURPOSE_IMPORT_CREDENTIALS: &str = "import-credentials";
+
+// None when the user dismissed the dialog
+type Answer = Option<Zeroizing<String>>;
+
+/// Passphrase prompts waiting for the user, by id
+#[derive(Default)]
+pub struct PassphrasePrompts {
+    pending: Mutex<HashMap<String, oneshot::Sender<Answer>>>,
+}
+
+impl PassphrasePrompts {
+    fn open(&self) -> (String, oneshot::Receiver<Answer>) {
+        let id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(id.clone(), tx);
+        (id, rx)
+    }
+
+    /// Hand the user's answer (None to cancel) to the prompt waiting for it.
+    /// False when no prompt has that id, e.g. it already timed out.
+    pub(crate) fn answer(&self, id: &str, passphrase: Answer) -> bool {
+        match self.pending.lock().remove(id) {
+            Some(tx) => tx.send(passphrase).is_ok(),
+            None => false,
+        }
+    }
+
+    async fn wait(
+        &self,
+        id: &str,
+        rx: oneshot::Receiver<Answer>,
+        timeout: Duration,
+    ) -> Result<Zeroizing<String>, CommandError> {
+        let answer = tokio::time::timeout(timeout, rx).await;
+        self.pending.lock().remove(id);
+        match answer {
+            Ok(Ok(Some(passphrase))) => Ok(passphrase),
+            Ok(Ok(None)) | Ok(Err(_)) => Err(CommandError::new(ErrorCode::Cancelled, "Cancelled")),
+            Err(_) => Err(CommandError::new(
+                ErrorCode::Cancelled,
+                "Timed out waiting for the passphrase",
+            )),
+        }
+    }
+}
+
+/// Ask the user for a passphrase. `confirm` has the dialog ask twice, for
+/// passphrases that protect something new.
+pub(crate) async fn request_passphrase(
+    app: &AppHandle,
+    purpose: &str,
+    confirm: bool,
+) -> Result<Zeroizing<String>, CommandError> {
+    let state = app.state::<AppState>();
+    let prompts = &state.passphrase_prompts;
+    let (id, rx) = prompts.open();
+    let request = PassphraseRequest {
+        id: id.clone(),
+        purpose: purpose.to_string(),
+        confirm,
+    };
+    if let Err(e) = app.emit("passphrase-requested", &request) {
+        prompts.pending.lock().remove(&id);
+        return Err(format!("Could not show the passphrase prompt: {}", e).into());
+    }
+    let passphrase = prompts.wait(&id, rx, PROMPT_TIMEOUT).await;
+    if passphrase.is_err() {
+        let _ = app.emit("passphrase-request-closed", &id);
+    }
+    passphrase
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn answers_reach_the_waiting_prompt() {
+        let prompts = PassphrasePrompts::default();
+        let (id, rx) = prompts.open();
+        assert!(prompts.answer(&id, Some(Zeroizing::new("hunter22".to_string()))));
+        let passphrase = prompts.wait(&id, rx, Duration::from_secs(1)).await;
+        assert_eq!(passphrase.unwrap().as_str(), "hunter22");
+        // Each prompt takes one answer
+        assert!(!prompts.answer(&id, None));
+    }
+
+    #[tokio::test]
+    async fn dismissing_the_dialog_cancels() {
+        let prompts = PassphrasePrompts::default();
+        let (id, rx) = prompts.open();
+        assert!(prompts.answer(&id, None));
+        let err = prompts
+            .wait(&id, rx, Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::Cancelled);
+        assert_eq!(err.message, "Cancelled");
+        assert!(prompts.pending.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unanswered_prompts_time_out() {
+        let prompts = PassphrasePrompts::default();
+        let (id, rx) = prompts.open();
+        let err = prompts
+            .wait(&id, rx, Duration::from_millis(20))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::Cancelled);
+        assert!(err.message.contains("Timed out"));
+        // A late answer finds nothing waiting
+        assert!(!prompts.answer(&id, Some(Zeroizing::new("late".to_string()))));
+        assert!(prompts.pending.lock().is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+//! Crash-safe journal of the current session.
+//!
+//! At startup [`begin`] reads what the previous run left in
+//! `<config_dir>/session.json`, keeps its summary for
+//! `get_last_session_summary`, and starts a new journal marked as not shut
+//! down cleanly. Proxy starts, stops and crashes, the last classified request
+//! errors and the request counts are written to it as the session goes
+//! (atomically, counts at most every `WRITE_INTERVAL_MS`). [`end`] marks it
+//! clean from the exit handler, so a journal still marked unclean at the next
+//! launch means ProxyPal crashed, was killed or lost power.
+
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+use crate::types::{
+    LastSessionSummary, RequestLog, SessionEvent, SessionEventKind, SessionJournal,
+};
+
+const JOURNAL_FILE: &str = "session.json";
+
+/// Proxy events kept in the journal
+const MAX_EVENTS: usize = 50;
+
+/// Request errors kept in the journal
+const MAX_ERRORS: usize = 5;
+
+/// Longest time request counts go unwritten, in millis
+const WRITE_INTERVAL_MS: u64 = 30_000;
+
+/// Reported when an unclean session recorded no proxy crash
+const UNKNOWN_CRASH: &str = "ProxyPal did not shut down cleanly";
+
+/// The journal being written and the previous session's summary, kept in
+/// `AppState`
+#[derive(Default)]
+pub struct SessionState {
+    journal: SessionJournal,
+    previous: Option<LastSessionSummary>,
+    // Nothing is written before `begin` has read the previous journal
+    started: bool,
+}
+
+fn journal_path() -> PathBuf {
+    crate::config::get_proxypal_config_dir().join(JOURNAL_FILE)
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+fn write(journal: &mut SessionJournal) {
+    journal.updated_at = now_ms();
+    let result = serde_json::to_string_pretty(journal)
+        .map_err(|e| e.to_string())
+        .and_then(|data| {
+            crate::status_file::write_atomically(&journal_path(), &data).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        eprintln!("[ProxyPal] Failed to write session journal: {}", e);
+    }
+}
+
+/// What a finished (or abandoned) journal says about its session
+pub(crate) fn summarize(journal: &SessionJournal) -> LastSessionSummary {
+    let count = |kind: SessionEventKind| journal.events.iter().filter(|e| e.kind == kind).count();
+    let crash_reason = (!journal.clean_shutdown).then(|| {
+        journal
+            .events
+            .iter()
+            .rev()
+            .find(|e| e.kind == SessionEventKind::ProxyCrash)
+            .and_then(|e| e.detail.clone())
+            .unwrap_or_else(|| UNKNOWN_CRASH.to_string())
+    });
+    LastSessionSummary {
+        started_at: journal.started_at,
+        ended_at: journal.updated_at,
+        uptime_secs: journal.updated_at.saturating_sub(journal.started_at) / 1000,
+        clean_shutdown: journal.clean_shutdown,
+        crash_reason,
+        app_version: journal.app_version.clone(),
+        proxy_starts: count(SessionEventKind::ProxyStart) as u32,
+        proxy_crashes: count(SessionEventKind::ProxyCrash) as u32,
+        requests: journal.requests,
+        failures: journal.failures,
+        last_errors: journal.last_errors.iter().rev().cloned().collect(),
+    }
+}
+
+fn push_event(journal: &mut SessionJournal, kind: SessionEventKind, detail: Option<String>) {
+    if journal.events.len() >= MAX_EVENTS {
+        journal.events.remove(0);
+    }
+    journal.events.push(SessionEvent {
+        timestamp: now_ms(),
+        kind,
+        detail: detail.map(|d| crate::redact::redact(&d)),
+    });
+}
+
+// Count a batch of requests; returns whether any of them failed
+fn note_requests(journal: &mut SessionJournal, batch: &[RequestLog]) -> bool {
+    let mut failed = false;
+    for request in batch.iter().filter(|r| !r.synthetic) {
+        journal.requests += 1;
+        if let Some(mut error) = crate::helpers::proxy_errors::error_from_request(request) {
+            error.message = crate::redact::redact(&error.message);
+            journal.failures += 1;
+            if journal.last_errors.len() >= MAX_ERRORS {
+                journal.last_errors.remove(0);
+            }
+            journal.last_errors.push(error);
+            failed = true;
+        }
+    }
+    failed
+}
+
+/// Read the previous session's journal and start this one's
+pub(crate) fn begin(state: &AppState) {
+    crate::startup::note_fs_read();
+    let previous = std::fs::read_to_string(journal_path())
+        .ok()
+        .and_then(|data| serde_json::from_str::<SessionJournal>(&data).ok())
+        .map(|journal| summarize(&journal));
+
+    let mut session = state.session.lock();
+    session.previous = previous;
+    session.journal = SessionJournal {
+        started_at: now_ms(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        ..SessionJournal::default()
+    };
+    session.started = true;
+    write(&mut session.journal);
+}
+
+/// Note a proxy start, stop or crash
+pub(crate) fn record_event(app: &AppHandle, kind: SessionEventKind, detail: Option<String>) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let mut session = state.session.lock();
+    if !session.started {
+        return;
+    }
+    push_event(&mut session.journal, kind, detail);
+    write(&mut session.journal);
+}
+
+/// Count a batch of new requests; failures are written right away
+pub(crate) fn record_requests(app: &AppHandle, batch: &[RequestLog]) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let mut session = state.session.lock();
+    if !session.started {
+        return;
+    }
+    let failed = note_requests(&mut session.journal, batch);
+    if failed || now_ms().saturating_sub(session.journal.updated_at) >= WRITE_INTERVAL_MS {
+        write(&mut session.journal);
+    }
+}
+
+/// Mark the session as shut down cleanly
+pub(crate) fn end(state: &AppState) {
+    let mut session = state.session.lock();
+    if !session.started {
+        return;
+    }
+    session.journal.clean_shutdown = true;
+    write(&mut session.journal);
+}
+
+/// Summary of the previous session, if it left a journal
+pub(crate) fn last_summary(state: &AppState) -> Option<LastSessionSummary> {
+    state.session.lock().previous.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(status: u16) -> RequestLog {
+        RequestLog {
+            id: format!("req_{}", status),
+            timestamp: 1_000,
+            provider: "claude".to_string(),
+            model: "claude-sonnet-4-5".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status,
+            duration_ms: 800,
+            ttfb_ms: None,
+            tokens_in: None,
+            tokens_out: None,
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    #[test]
+    fn unclean_session_reports_the_last_proxy_crash() {
+        let mut journal = SessionJournal {
+            started_at: 10_000,
+            updated_at: 3_610_000,
+            ..SessionJournal::default()
+        };
+        push_event(&mut journal, SessionEventKind::ProxyStart, None);
+        push_event(
+            &mut journal,
+            SessionEventKind::ProxyCrash,
+            Some("Proxy exited unexpectedly (code Some(2))".to_string()),
+        );
+        let summary = summarize(&journal);
+        assert!(!summary.clean_shutdown);
+        assert_eq!(summary.uptime_secs, 3600);
+        assert_eq!(summary.proxy_starts, 1);
+        assert_eq!(summary.proxy_crashes, 1);
+        assert_eq!(
+            summary.crash_reason.as_deref(),
+            Some("Proxy exited unexpectedly (code Some(2))")
+        );
+
+        // Without a proxy crash the app itself went down
+        journal.events.clear();
+        assert_eq!(
+            summarize(&journal).crash_reason.as_deref(),
+            Some(UNKNOWN_CRASH)
+        );
+
+        journal.clean_shutdown = true;
+        assert_eq!(summarize(&journal).crash_reason, None);
+    }
+
+    #[test]
+    fn keeps_only_the_latest_errors() {
+        let mut journal = SessionJournal::default();
+        assert!(!note_requests(&mut journal, &[request(200), request(200)]));
+        let failing: Vec<RequestLog> = (0..MAX_ERRORS as u16 + 2)
+            .map(|i| request(500 + i))
+            .collect();
+        assert!(note_requests(&mut journal, &failing));
+
+        assert_eq!(journal.requests, 2 + failing.len() as u64);
+        assert_eq!(journal.failures, failing.len() as u64);
+        assert_eq!(journal.last_errors.len(), MAX_ERRORS);
+        let summary = summarize(&journal);
+        assert_eq!(summary.last_errors[0].status, Some(506), "latest first");
+    }
+}
@@ -0,0 +1,423 @@
+//! Verified download of the CLIProxyAPI binary from its GitHub releases.
+//!
+//! Shared by `build.rs` (which includes this file with `#[path]`) and the
+//! runtime `update_sidecar` command, so it must not use anything from the
+//! crate: only std and crates listed in both `[dependencies]` and
+//! `[build-dependencies]`.
+//!
+//! Every archive is checked against the SHA-256 published with the release
+//! (`checksums.txt`, or `<asset>.sha256`) before the binary is extracted,
+//! and transient failures are retried with exponential backoff.
+
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+/// Release repository unless `CLIPROXYAPI_REPO` overrides it
+pub const DEFAULT_REPO: &str = "router-for-me/CLIProxyAPIPlus";
+
+/// Tries per file before giving up
+const ATTEMPTS: u32 = 4;
+
+/// Wait before the first retry; doubled after every failed attempt
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Names the executable has had inside the release archives
+const BINARY_NAMES: &[&str] = &[
+    "cli-proxy-api-plus",
+    "CLIProxyAPIPlus",
+    "CLIProxyAPI",
+    "cli-proxy-api",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    TarGz,
+    Zip,
+}
+
+/// Repository to download from
+pub fn release_repo() -> String {
+    std::env::var("CLIPROXYAPI_REPO")
+        .ok()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| DEFAULT_REPO.to_string())
+}
+
+/// Release asset holding the binary for a Rust target triple, e.g.
+/// `CLIProxyAPIPlus_6.1.0_darwin_arm64.tar.gz`. Assets are prefixed with the
+/// repository name.
+pub fn asset_for_target(repo: &str, target: &str, version: &str) -> Option<(String, ArchiveKind)> {
+    let os = if target.contains("apple-darwin") {
+        "darwin"
+    } else if target.contains("linux") {
+        "linux"
+    } else if target.contains("windows") {
+        "windows"
+    } else {
+        return None;
+    };
+    let arch = if target.starts_with("aarch64") {
+        "arm64"
+    } else if target.starts_with("x86_64") {
+        "amd64"
+    } else {
+        return None;
+    };
+    let (kind, ext) = if os == "windows" {
+        (ArchiveKind::Zip, "zip")
+    } else {
+        (ArchiveKind::TarGz, "tar.gz")
+    };
+    let prefix = repo.rsplit('/').next().unwrap_or(repo);
+    Some((
+        format!("{}_{}_{}_{}.{}", prefix, version, os, arch, ext),
+        kind,
+    ))
+}
+
+/// Find an asset's digest in a checksum listing (`<sha256>  <name>` per line,
+/// `sha256sum` style, optionally with `*` before binary-mode names). A
+/// listing with a single bare digest, as in `<asset>.sha256`, also matches.
+pub fn parse_checksums(text: &str, asset: &str) -> Option<String> {
+    let is_digest = |s: &str| s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit());
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(digest), name) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if !is_digest(digest) {
+            continue;
+        }
+        match name.map(|n| n.trim_start_matches('*')) {
+            Some(name) if name == asset => return Some(digest.to_ascii_lowercase()),
+            None if text.trim() == digest => return Some(digest.to_ascii_lowercase()),
+            _ => {}
+        }
+    }
+    None
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Reject a download whose digest differs from the published one
+pub fn verify_checksum(bytes: &[u8], expected: &str) -> Result<(), String> {
+    let actual = sha256_hex(bytes);
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "checksum mismatch (expected {}, got {})",
+            expected.trim(),
+            actual
+        ))
+    }
+}
+
+/// Whether the bytes start like a real executable rather than an archive or
+/// an HTML error page. Checks the magic bytes:
+///   - gzip: starts with 0x1f 0x8b (rejected)
+///   - Mach-O 64-bit: starts with 0xcf 0xfa 0xed 0xfe
+///   - ELF: starts with 0x7f 'E' 'L' 'F'
+///   - PE (Windows): starts with 'M' 'Z'
+pub fn is_executable(bytes: &[u8]) -> bool {
+    if bytes.len() < 4 || bytes.starts_with(&[0x1f, 0x8b]) {
+        return false;
+    }
+    bytes.starts_with(&[0xcf, 0xfa, 0xed, 0xfe])
+        || bytes.starts_with(&[0x7f, b'E', b'L', b'F'])
+        || bytes.starts_with(b"MZ")
+}
+
+/// Download client on top of `builder`. The runtime passes one routed through
+/// the app-level network proxy; build.rs, which can't see the app config,
+/// passes a plain builder.
+pub fn client(
+    builder: reqwest::blocking::ClientBuilder,
+) -> Result<reqwest::blocking::Client, String> {
+    builder
+        .user_agent("ProxyPal")
+        .connect_timeout(Duration::from_secs(15))
+        .timeout(Duration::from_secs(300))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+// Server errors, rate limiting and transport failures are worth another try;
+// a 404 will not change
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// GET `url` and run `check` on the body, retrying transient failures and
+/// bodies that fail the check (a truncated or corrupted transfer).
+fn fetch_checked(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    check: impl Fn(&[u8]) -> Result<(), String>,
+) -> Result<Vec<u8>, String> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        let (error, retryable) = match client.get(url).send() {
+            Ok(response) if response.status().is_success() => match response.bytes() {
+                Ok(body) => match check(&body) {
+                    Ok(()) => return Ok(body.to_vec()),
+                    Err(e) => (e, true),
+                },
+                Err(e) => (e.to_string(), true),
+            },
+            Ok(response) => {
+                let status = response.status();
+                (format!("HTTP {}", status), is_retryable(status))
+            }
+            Err(e) => (e.to_string(), true),
+        };
+        if !retryable || attempt >= ATTEMPTS {
+            return Err(format!(
+                "{} (after {} attempt{}): {}",
+                url,
+                attempt,
+                if attempt == 1 { "" } else { "s" },
+                error
+            ));
+        }
+        std::thread::sleep(backoff);
+        backoff *= 2;
+        attempt += 1;
+    }
+}
+
+/// Latest release version, without the leading `v`
+pub fn latest_version(client: &reqwest::blocking::Client, repo: &str) -> Result<String, String> {
+    let url = format!("https://api.github.com/repos/{}/releases/latest", repo);
+    let body = fetch_checked(client, &url, |_| Ok(()))?;
+    let release: serde_json::Value =
+        serde_json::from_slice(&body).map_err(|e| format!("Invalid release info: {}", e))?;
+    release["tag_name"]
+        .as_str()
+        .map(|tag| tag.trim_start_matches('v').to_string())
+        .ok_or_else(|| format!("No tag_name in the latest release of {}", repo))
+}
+
+// Digest published for the asset: the release-wide listing first, then a
+// per-asset file
+fn published_checksum(
+    client: &reqwest::blocking::Client,
+    base_url: &str,
+    asset: &str,
+) -> Result<String, String> {
+    let mut errors = Vec::new();
+    for file in ["checksums.txt".to_string(), format!("{}.sha256", asset)] {
+        let url = format!("{}/{}", base_url, file);
+        match fetch_checked(client, &url, |_| Ok(())) {
+            Ok(body) => match parse_checksums(&String::from_utf8_lossy(&body), asset) {
+                Some(digest) => return Ok(digest),
+                None => errors.push(format!("{} does not list {}", file, asset)),
+            },
+            Err(e) => errors.push(e),
+        }
+    }
+    Err(format!(
+        "No published checksum for {}: {}",
+        asset,
+        errors.join("; ")
+    ))
+}
+
+fn is_binary_entry(path: &str) -> bool {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let name = name.strip_suffix(".exe").unwrap_or(name);
+    BINARY_NAMES.contains(&name)
+}
+
+/// Pull the executable out of a release archive
+pub fn extract_binary(archive: &[u8], kind: ArchiveKind) -> Result<Vec<u8>, String> {
+    let mut binary = Vec::new();
+    match kind {
+        ArchiveKind::TarGz => {
+            let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+            let entries = tar
+                .entries()
+                .map_err(|e| format!("Invalid archive: {}", e))?;
+            for entry in entries {
+                let mut entry = entry.map_err(|e| format!("Invalid archive: {}", e))?;
+                let path = entry.path().map_err(|e| e.to_string())?;
+                if entry.header().entry_type().is_file() && is_binary_entry(&path.to_string_lossy())
+                {
+                    entry
+                        .read_to_end(&mut binary)
+                        .map_err(|e| format!("Failed to extract binary: {}", e))?;
+                    return Ok(binary);
+                }
+            }
+        }
+        ArchiveKind::Zip => {
+            let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive))
+                .map_err(|e| format!("Invalid archive: {}", e))?;
+            for index in 0..zip.len() {
+                let mut file = zip
+                    .by_index(index)
+                    .map_err(|e| format!("Invalid archive: {}", e))?;
+                if file.is_file() && is_binary_entry(file.name()) {
+                    file.read_to_end(&mut binary)
+                        .map_err(|e| format!("Failed to extract binary: {}", e))?;
+                    return Ok(binary);
+                }
+            }
+        }
+    }
+    Err(format!(
+        "Archive does not contain a CLIProxyAPI binary (looked for {})",
+        BINARY_NAMES.join(", ")
+    ))
+}
+
+/// Write the binary to `dest` atomically (temp file + rename) and make it
+/// executable
+pub fn install_binary(binary: &[u8], dest: &Path) -> Result<(), String> {
+    if !is_executable(binary) {
+        return Err("Not an executable (unexpected file format)".to_string());
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut tmp = dest.as_os_str().to_owned();
+    tmp.push(".download");
+    let tmp = std::path::PathBuf::from(tmp);
+    std::fs::write(&tmp, binary)
+        .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o755));
+    }
+    std::fs::rename(&tmp, dest).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("Failed to replace {}: {}", dest.display(), e)
+    })
+}
+
+/// Download the release archive for `target`, verify it against the
+/// published checksum and install the binary it contains at `dest`.
+pub fn download_verified(
+    client: &reqwest::blocking::Client,
+    repo: &str,
+    version: &str,
+    target: &str,
+    dest: &Path,
+) -> Result<(), String> {
+    let (asset, kind) = asset_for_target(repo, target, version)
+        .ok_or_else(|| format!("No CLIProxyAPI release asset for target {}", target))?;
+    let base_url = format!("https://github.com/{}/releases/download/v{}", repo, version);
+
+    let expected = published_checksum(client, &base_url, &asset)?;
+    let archive = fetch_checked(client, &format!("{}/{}", base_url, asset), |body| {
+        verify_checksum(body, &expected)
+    })?;
+    let binary = extract_binary(&archive, kind)?;
+    install_binary(&binary, dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    #[test]
+    fn names_assets_after_the_repository() {
+        assert_eq!(
+            asset_for_target(DEFAULT_REPO, "aarch64-apple-darwin", "6.1.0"),
+            Some((
+                "CLIProxyAPIPlus_6.1.0_darwin_arm64.tar.gz".to_string(),
+                ArchiveKind::TarGz
+            ))
+        );
+        assert_eq!(
+            asset_for_target(
+                "router-for-me/CLIProxyAPI",
+                "x86_64-pc-windows-msvc",
+                "6.1.0"
+            ),
+            Some((
+                "CLIProxyAPI_6.1.0_windows_amd64.zip".to_string(),
+                ArchiveKind::Zip
+            ))
+        );
+        assert_eq!(
+            asset_for_target(DEFAULT_REPO, "wasm32-unknown-unknown", "1"),
+            None
+        );
+    }
+
+    #[test]
+    fn finds_asset_digest_in_listing() {
+        let listing = format!(
+            "{}  CLIProxyAPIPlus_6.1.0_linux_amd64.tar.gz\n{} *CLIProxyAPIPlus_6.1.0_windows_amd64.zip\n",
+            "0".repeat(64),
+            DIGEST.to_uppercase()
+        );
+        assert_eq!(
+            parse_checksums(&listing, "CLIProxyAPIPlus_6.1.0_windows_amd64.zip").as_deref(),
+            Some(DIGEST)
+        );
+        assert_eq!(parse_checksums(&listing, "other.zip"), None);
+        assert_eq!(
+            parse_checksums(&format!("{}\n", DIGEST), "any.tar.gz").as_deref(),
+            Some(DIGEST)
+        );
+        assert_eq!(
+            parse_checksums("not-a-digest  any.tar.gz", "any.tar.gz"),
+            None
+        );
+    }
+
+    #[test]
+    fn verifies_sha256() {
+        assert_eq!(sha256_hex(b"test"), DIGEST);
+        assert!(verify_checksum(b"test", DIGEST).is_ok());
+        assert!(verify_checksum(b"tampered", DIGEST).is_err());
+    }
+
+    #[test]
+    fn extracts_and_installs_binary_from_tarball() {
+        let elf = b"\x7fELF-fake-binary".to_vec();
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::fast(),
+        ));
+        for (name, data) in [
+            ("README.md", &b"docs"[..]),
+            ("cli-proxy-api-plus", &elf[..]),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, name, data).unwrap();
+        }
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        // The archive itself is never mistaken for the executable
+        assert!(!is_executable(&archive));
+        let binary = extract_binary(&archive, ArchiveKind::TarGz).unwrap();
+        assert_eq!(binary, elf);
+
+        let dir = std::env::temp_dir().join(format!("proxypal-sidecar-{}", uuid::Uuid::new_v4()));
+        let dest = dir.join("cli-proxy-api");
+        install_binary(&binary, &dest).unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), elf);
+        assert!(install_binary(b"<html>Not Found</html>", &dest).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 use tokio::process::Command;
 use tokio::sync::Notify;
+use parking_lot::Mutex;
 
 use crate::types::ssh::SshConfig;
 
@@ -191,14 +192,14 @@ impl SshManager {
             }
         });
 
-        connections.lock().unwrap().insert(config_id, RunningConnection {
+        connections.lock().insert(config_id, RunningConnection {
             notify_stop,
             handle,
         });
     }
 
     pub fn disconnect(&self, id: &str) {
-        let mut connections = self.connections.lock().unwrap();
+        let mut connections = self.connections.lock();
         if let Some(conn) = connections.remove(id) {
             conn.notify_stop.notify_one();
         }
@@ -206,7 +207,7 @@ impl SshManager {
     
     pub fn disconnect_all(&self) {
         println!("[SSH Manager] Shutting down all connections...");
-        let mut connections = self.connections.lock().unwrap();
+        let mut connections = self.connections.lock();
         for (id, conn) in connections.iter() {
             println!("[SSH Manager] Stopping connection: {}", id);
             conn.notify_stop.notify_one();
@@ -218,7 +219,7 @@ impl SshManager {
     pub fn get_status(&self, id: &str) -> String {
        // Ideally status is tracked. But for now, if it's in the map, it's "running" (enabled).
        // Real-time status comes via events. This is just for initial checks or check if "enabled".
-       let connections = self.connections.lock().unwrap();
+       let connections = self.connections.lock();
        if connections.contains_key(id) {
            "active".to_string()
        } else {
@@ -0,0 +1,330 @@
+//! Non-blocking startup.
+//!
+//! `run()` builds the app from [`initial_state`], which touches no files, so
+//! the window appears straight away. [`hydrate`] then loads config and auth on
+//! a blocking thread, runs migrations, starts the config-driven services and
+//! emits `state-hydrated` with the loaded snapshot.
+//!
+//! Until hydration finishes, commands see default state. `save_config_to_file`
+//! refuses to write until then, so no caller can overwrite the saved config
+//! with defaults; commands that start the proxy or report the config call
+//! [`AppState::ensure_hydrated`] and fail with a "still loading" error.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::cloudflare_manager::CloudflareManager;
+use crate::ssh_manager::SshManager;
+use crate::state::AppState;
+use crate::types::{AuthStatus, CliproxyConfigImport, CopilotStatus, ProxyStatus, StartupState};
+use crate::{
+    app_update, commands, config, control_api, control_socket, event_stream, helpers,
+    management_gateway, mcp, metrics, scheduler, status_file,
+};
+
+#[cfg(test)]
+thread_local! {
+    static FS_READS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Record a read of ProxyPal's files on the current thread, so tests can
+/// check what happens on the main thread before the builder runs
+pub(crate) fn note_fs_read() {
+    #[cfg(test)]
+    FS_READS.with(|reads| reads.set(reads.get() + 1));
+}
+
+/// State the app is built with: defaults only, nothing read from disk
+pub(crate) fn initial_state() -> AppState {
+    AppState {
+        proxy_status: Mutex::new(ProxyStatus::default()),
+        auth_status: Mutex::new(AuthStatus::default()),
+        config: Mutex::new(config::AppConfig::default()),
+        oauth_flows: Mutex::new(helpers::oauth_flows::OAuthFlows::default()),
+        proxy_process: Mutex::new(None),
+        copilot_status: Mutex::new(CopilotStatus::default()),
+        copilot_process: Mutex::new(None),
+        log_watcher_running: Arc::new(AtomicBool::new(false)),
+        request_counter: Arc::new(AtomicU64::new(0)),
+        request_log_counters: Arc::new(helpers::log_watcher::RequestLogCounters::default()),
+        live_feed: Mutex::new(helpers::live_feed::LiveFeed::default()),
+        models_cache: Mutex::new(None),
+        health_cache: Mutex::new(None),
+        health_check_lock: tokio::sync::Mutex::new(()),
+        health_monitor: Mutex::new(None),
+        tray: Mutex::new(None),
+        proxy_started_at: Mutex::new(None),
+        recent_errors: Mutex::new(helpers::proxy_errors::RecentErrors::default()),
+        notifications: Mutex::new(crate::notifications::NotificationState::default()),
+        in_flight: Mutex::new(helpers::in_flight::InFlightRequests::default()),
+        metrics: Mutex::new(metrics::ProxyMetrics::default()),
+        metrics_server: Mutex::new(None),
+        webhooks: Mutex::new(crate::webhooks::WebhookState::default()),
+        event_stream: Mutex::new(None),
+        status_file_wake: Arc::new(tokio::sync::Notify::new()),
+        mcp_server: Mutex::new(None),
+        control_api: Mutex::new(None),
+        control_socket: Mutex::new(None),
+        management_gateway: Mutex::new(None),
+        http: crate::http::HttpClients::default(),
+        history: Arc::new(helpers::history_store::HistoryStore::default()),
+        failover: crate::failover::FailoverEngine::default(),
+        scheduler: crate::scheduler::ProxyScheduler::default(),
+        model_caps: Mutex::new(crate::model_caps::ModelCapUsage::default()),
+        request_watches: Mutex::new(crate::request_watches::RequestWatchState::default()),
+        rate_limits: Mutex::new(crate::rate_limits::RateLimitState::default()),
+        paused: Mutex::new(None),
+        sleep_watcher: Mutex::new(None),
+        session: Mutex::new(crate::session_journal::SessionState::default()),
+        idle_watchdog: Mutex::new(crate::idle_watchdog::IdleWatchdog::default()),
+        usage_anomaly: Mutex::new(crate::usage_anomaly::UsageAnomalyState::default()),
+        self_test: Mutex::new(None),
+        demo_mode: Mutex::new(None),
+        app_update: Mutex::new(None),
+        passphrase_prompts: crate::secrets::PassphrasePrompts::default(),
+        hydrated: AtomicBool::new(false),
+    }
+}
+
+// Everything startup needs from disk; runs on a blocking thread. On first
+// run, a standalone CLIProxyAPI config is imported if there is one, and a
+// taken default port is swapped for a free one.
+fn load_snapshot() -> (StartupState, Option<CliproxyConfigImport>, Option<u16>) {
+    crate::proxy::lifecycle::kill_recorded_sidecar();
+    // Migrate old format to split storage on first run
+    helpers::migration::migrate_to_split_storage();
+    let first_run = !config::get_config_path().exists();
+    let mut config = config::load_config();
+    let (imported, moved_port) = if first_run {
+        let imported = helpers::cliproxy_config::import_on_first_run(&mut config);
+        (imported, config::pick_first_run_port(&mut config))
+    } else {
+        (None, None)
+    };
+    let snapshot = StartupState {
+        config,
+        auth_status: crate::config::load_auth_status(),
+    };
+    (snapshot, imported, moved_port)
+}
+
+/// Load persisted state in the background, then start everything that
+/// depends on it
+pub fn hydrate(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let (snapshot, imported, moved_port) =
+            match tauri::async_runtime::spawn_blocking(load_snapshot).await {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    eprintln!("[ProxyPal] Failed to load settings: {}", e);
+                    let snapshot = StartupState {
+                        config: config::AppConfig::default(),
+                        auth_status: AuthStatus::default(),
+                    };
+                    (snapshot, None, None)
+                }
+            };
+        crate::redact::set_known_secrets(&snapshot.config);
+        helpers::history::set_history_retention(&snapshot.config);
+        helpers::aggregate_compaction::set_hourly_days(&snapshot.config);
+        helpers::paths::set_data_dir(&snapshot.config);
+        helpers::usage_day::set_usage_timezone(&snapshot.config);
+
+        let state = app.state::<AppState>();
+        state.http.set_network_proxy(&snapshot.config.network_proxy);
+        *state.config.lock() = snapshot.config.clone();
+        *state.auth_status.lock() = snapshot.auth_status.clone();
+        crate::session_journal::begin(&state);
+        state.hydrated.store(true, Ordering::Release);
+        config::mark_hydrated();
+        crate::tray::refresh_tray_tooltip(&app);
+        #[cfg(desktop)]
+        helpers::window_state::restore_main_window(&app);
+        let _ = app.emit("state-hydrated", &snapshot);
+        if let Some(report) = imported {
+            let _ = app.emit("cliproxy-config-imported", report);
+        }
+        if let Some(port) = moved_port {
+            let _ = app.emit("default-port-moved", port);
+        }
+        if let Some(status) = helpers::storage::degraded() {
+            let _ = app.emit("storage-degraded", status);
+        }
+        // Config and auth were read above; history reports as it loads
+        helpers::recovery::start_reporting(&app);
+
+        start_services(&app);
+    });
+}
+
+// Services and autostarts driven by the loaded config
+fn start_services(app: &AppHandle) {
+    // Bring the OS autostart entry in line with the saved setting
+    #[cfg(desktop)]
+    {
+        let launch_at_login = app.state::<AppState>().config.lock().launch_at_login;
+        helpers::autostart::reconcile_launch_at_login(app, launch_at_login);
+    }
+
+    // Prometheus /metrics listener, if configured
+    if let Err(e) = metrics::apply_metrics_config(app) {
+        eprintln!("[ProxyPal] {}", e);
+    }
+
+    // WebSocket event stream for external dashboards, if configured
+    if let Err(e) = event_stream::apply_event_stream_config(app) {
+        eprintln!("[ProxyPal] {}", e);
+    }
+
+    // JSON control API for launchers (Raycast, Alfred), if configured
+    if let Err(e) = control_api::apply_control_api_config(app) {
+        eprintln!("[ProxyPal] {}", e);
+    }
+
+    // JSON-RPC control socket for scripts, if enabled
+    if let Err(e) = control_socket::apply_control_socket_config(app) {
+        eprintln!("[ProxyPal] {}", e);
+    }
+
+    // Management API on its own port, if configured
+    if let Err(e) = management_gateway::apply_management_gateway_config(app) {
+        eprintln!("[ProxyPal] {}", e);
+    }
+
+    // status.json for shell prompts / status bars, if enabled
+    status_file::start_status_file_writer(app);
+
+    // Refresh "today" figures when the usage day rolls over
+    helpers::usage_day::start_day_rollover_watcher(app);
+
+    // Look for a new ProxyPal release in the background, if enabled
+    app_update::start_update_checker(app);
+
+    // MCP server for agent introspection, if enabled
+    let mcp_enabled = app.state::<AppState>().config.lock().mcp_server_enabled;
+    if mcp_enabled {
+        if let Err(e) = mcp::start_mcp_server(app) {
+            eprintln!("[ProxyPal] {}", e);
+        }
+    }
+
+    // Keys retired by an earlier rotation stop working on schedule
+    commands::proxy_keys::schedule_pending_expiries(app);
+
+    // Warn about config or credential files other users can read
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let issues = tauri::async_runtime::spawn_blocking(|| {
+            helpers::permissions::audit(&helpers::permissions::sensitive_paths())
+        })
+        .await
+        .unwrap_or_default();
+        if issues.is_empty() {
+            return;
+        }
+        // Give the webview a moment to register its event listeners
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        eprintln!(
+            "[ProxyPal] {} config/credential file(s) are accessible to other users",
+            issues.len()
+        );
+        let _ = app_handle.emit("file-permissions-warning", issues);
+    });
+
+    let config = app.state::<AppState>().config.lock().clone();
+    let scheduled = scheduler::controls_startup(&config);
+
+    // Auto-start SSH connections
+    let ssh_manager = app.state::<SshManager>();
+    for ssh_config in config.ssh_configs {
+        if ssh_config.enabled {
+            ssh_manager.connect(app.clone(), ssh_config);
+        }
+    }
+
+    // Auto-start Cloudflare tunnels
+    let cf_manager = app.state::<CloudflareManager>();
+    for cf_config in config.cloudflare_configs {
+        if cf_config.enabled {
+            println!("[Cloudflare] Auto-starting tunnel: {}", cf_config.name);
+            cf_manager.connect(app.clone(), cf_config);
+        }
+    }
+
+    // Auto-start Copilot if enabled
+    if config.copilot.enabled {
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            println!("[Copilot] Auto-starting copilot-api...");
+            // Small delay to let the app fully initialize
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            let state = app_handle.state::<AppState>();
+            match commands::copilot::start_copilot(app_handle.clone(), state).await {
+                Ok(status) => println!(
+                    "[Copilot] Auto-start successful: running={}",
+                    status.running
+                ),
+                Err(e) => eprintln!("[Copilot] Auto-start failed: {}", e),
+            }
+        });
+    }
+
+    // Weekly proxy schedule; when one is set it decides instead of auto-start
+    scheduler::start_scheduler(app);
+
+    // Pause the proxy around system sleep when `pause_on_sleep` is set
+    crate::proxy::pause::apply_sleep_watcher_config(app);
+
+    // Auto-start the proxy if enabled. Runs in the background so a failure is
+    // reported to the user instead of aborting app launch.
+    if config.auto_start && !scheduled {
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            // Give the webview a moment to register its event listeners
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            println!("[ProxyPal] Auto-starting proxy...");
+            let state = app_handle.state::<AppState>();
+            if let Err(e) = commands::proxy::start_proxy_now(app_handle.clone(), state).await {
+                eprintln!("[ProxyPal] Proxy auto-start failed: {}", e);
+                let _ = app_handle.emit("proxy-start-failed", e.clone());
+                use tauri_plugin_notification::NotificationExt;
+                let _ = app_handle
+                    .notification()
+                    .builder()
+                    .title("ProxyPal could not start the proxy")
+                    .body(e)
+                    .show();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fs_reads() -> usize {
+        FS_READS.with(|reads| reads.get())
+    }
+
+    #[test]
+    fn building_the_app_state_reads_no_files() {
+        let before = fs_reads();
+        let state = initial_state();
+        assert_eq!(fs_reads(), before);
+        assert!(!state.hydrated.load(Ordering::Acquire));
+        assert!(state.ensure_hydrated().is_err());
+
+        // The probe does see the reads hydration makes. Resolving the data dir
+        // may read config.json once per process, so do that first.
+        let _ = crate::config::get_data_dir();
+        let before = fs_reads();
+        let _ = crate::config::load_auth_status();
+        let _ = helpers::history::load_aggregate();
+        assert_eq!(fs_reads(), before + 2);
+    }
+}
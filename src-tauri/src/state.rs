@@ -1,22 +1,106 @@
-use std::sync::Mutex;
+use parking_lot::Mutex;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::time::Instant;
 use tauri_plugin_shell::process::CommandChild;
 
-use crate::types::{ProxyStatus, AuthStatus, OAuthState, CopilotStatus};
+use crate::types::{ProxyStatus, AuthStatus, CopilotStatus, AvailableModel, ProviderHealth, AppUpdateInfo};
 use crate::config::AppConfig;
+use crate::error::{CommandError, ErrorCode};
+use crate::failover::FailoverEngine;
+use crate::helpers::history_store::HistoryStore;
+use crate::helpers::in_flight::InFlightRequests;
+use crate::helpers::live_feed::LiveFeed;
+use crate::helpers::log_watcher::RequestLogCounters;
+use crate::helpers::oauth_flows::OAuthFlows;
+use crate::helpers::proxy_errors::RecentErrors;
+use crate::control_api::ControlApiServer;
+use crate::control_socket::ControlSocket;
+use crate::event_stream::EventStreamServer;
+use crate::http::HttpClients;
+use crate::idle_watchdog::IdleWatchdog;
+use crate::management_gateway::ManagementGateway;
+use crate::mcp::McpServer;
+use crate::metrics::{MetricsServer, ProxyMetrics};
+use crate::model_caps::ModelCapUsage;
+use crate::notifications::NotificationState;
+use crate::proxy::pause::{PausedProxy, SleepWatcher};
+use crate::rate_limits::RateLimitState;
+use crate::request_watches::RequestWatchState;
+use crate::scheduler::ProxyScheduler;
+use crate::secrets::PassphrasePrompts;
+use crate::session_journal::SessionState;
+use crate::tray::TrayHandles;
+use crate::usage_anomaly::UsageAnomalyState;
+use crate::webhooks::WebhookState;
 
-/// App state shared across all Tauri commands
+/// App state shared across all Tauri commands.
+///
+/// Locks are `parking_lot` mutexes: a panic while one is held does not poison
+/// it, so one failed command cannot break every later one. Guards are not
+/// `Send`; copy what you need out of a lock before awaiting.
 pub struct AppState {
     pub proxy_status: Mutex<ProxyStatus>,
     pub auth_status: Mutex<AuthStatus>,
     pub config: Mutex<AppConfig>,
-    pub pending_oauth: Mutex<Option<OAuthState>>,
+    pub oauth_flows: Mutex<OAuthFlows>,
     pub proxy_process: Mutex<Option<CommandChild>>,
     pub copilot_status: Mutex<CopilotStatus>,
     pub copilot_process: Mutex<Option<CommandChild>>,
     pub log_watcher_running: Arc<AtomicBool>,
     pub request_counter: Arc<AtomicU64>,
+    pub request_log_counters: Arc<RequestLogCounters>,
+    pub live_feed: Mutex<LiveFeed>,
+    pub models_cache: Mutex<Option<ModelsCache>>,
+    pub health_cache: Mutex<Option<(Instant, ProviderHealth)>>,
+    pub health_check_lock: tokio::sync::Mutex<()>,
+    pub health_monitor: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    pub tray: Mutex<Option<TrayHandles>>,
+    pub proxy_started_at: Mutex<Option<Instant>>,
+    pub recent_errors: Mutex<RecentErrors>,
+    pub notifications: Mutex<NotificationState>,
+    pub in_flight: Mutex<InFlightRequests>,
+    pub metrics: Mutex<ProxyMetrics>,
+    pub metrics_server: Mutex<Option<MetricsServer>>,
+    pub webhooks: Mutex<WebhookState>,
+    pub event_stream: Mutex<Option<EventStreamServer>>,
+    pub status_file_wake: Arc<tokio::sync::Notify>,
+    pub mcp_server: Mutex<Option<McpServer>>,
+    pub control_api: Mutex<Option<ControlApiServer>>,
+    pub control_socket: Mutex<Option<ControlSocket>>,
+    pub management_gateway: Mutex<Option<ManagementGateway>>,
+    pub http: HttpClients,
+    pub history: Arc<HistoryStore>,
+    pub failover: FailoverEngine,
+    pub scheduler: ProxyScheduler,
+    pub model_caps: Mutex<ModelCapUsage>,
+    pub request_watches: Mutex<RequestWatchState>,
+    pub rate_limits: Mutex<RateLimitState>,
+    pub paused: Mutex<Option<PausedProxy>>,
+    // Follows system sleep while `pause_on_sleep` is set
+    pub sleep_watcher: Mutex<Option<SleepWatcher>>,
+    // This session's journal and the previous session's summary
+    pub session: Mutex<SessionState>,
+    // Last observed request and the task warning when none arrive
+    pub idle_watchdog: Mutex<IdleWatchdog>,
+    // Today's spend alerts, so repeats need a doubled multiplier
+    pub usage_anomaly: Mutex<UsageAnomalyState>,
+    // Cancels the self-test in progress
+    pub self_test: Mutex<Option<tokio::sync::watch::Sender<bool>>>,
+    // Stops the demo traffic generator, while it runs
+    pub demo_mode: Mutex<Option<tokio::sync::watch::Sender<bool>>>,
+    // Result of the last successful app update check
+    pub app_update: Mutex<Option<AppUpdateInfo>>,
+    // Passphrase dialogs waiting for the user
+    pub passphrase_prompts: PassphrasePrompts,
+    // Set once the startup task has loaded config and auth from disk
+    pub hydrated: AtomicBool,
+}
+
+/// Last model list fetched from the proxy's /v1/models endpoint
+pub struct ModelsCache {
+    pub models: Vec<AvailableModel>,
+    pub fetched_at: Instant,
 }
 
 impl Default for AppState {
@@ -25,12 +109,93 @@ impl Default for AppState {
             proxy_status: Mutex::new(ProxyStatus::default()),
             auth_status: Mutex::new(AuthStatus::default()),
             config: Mutex::new(AppConfig::default()),
-            pending_oauth: Mutex::new(None),
+            oauth_flows: Mutex::new(OAuthFlows::default()),
             proxy_process: Mutex::new(None),
             copilot_status: Mutex::new(CopilotStatus::default()),
             copilot_process: Mutex::new(None),
             log_watcher_running: Arc::new(AtomicBool::new(false)),
             request_counter: Arc::new(AtomicU64::new(0)),
+            request_log_counters: Arc::new(RequestLogCounters::default()),
+            live_feed: Mutex::new(LiveFeed::default()),
+            models_cache: Mutex::new(None),
+            health_cache: Mutex::new(None),
+            health_check_lock: tokio::sync::Mutex::new(()),
+            health_monitor: Mutex::new(None),
+            tray: Mutex::new(None),
+            proxy_started_at: Mutex::new(None),
+            recent_errors: Mutex::new(RecentErrors::default()),
+            notifications: Mutex::new(NotificationState::default()),
+            in_flight: Mutex::new(InFlightRequests::default()),
+            metrics: Mutex::new(ProxyMetrics::default()),
+            metrics_server: Mutex::new(None),
+            webhooks: Mutex::new(WebhookState::default()),
+            event_stream: Mutex::new(None),
+            status_file_wake: Arc::new(tokio::sync::Notify::new()),
+            mcp_server: Mutex::new(None),
+            control_api: Mutex::new(None),
+            control_socket: Mutex::new(None),
+            management_gateway: Mutex::new(None),
+            http: HttpClients::default(),
+            history: Arc::new(HistoryStore::default()),
+            failover: FailoverEngine::default(),
+            scheduler: ProxyScheduler::default(),
+            model_caps: Mutex::new(ModelCapUsage::default()),
+            request_watches: Mutex::new(RequestWatchState::default()),
+            rate_limits: Mutex::new(RateLimitState::default()),
+            paused: Mutex::new(None),
+            sleep_watcher: Mutex::new(None),
+            session: Mutex::new(SessionState::default()),
+            idle_watchdog: Mutex::new(IdleWatchdog::default()),
+            usage_anomaly: Mutex::new(UsageAnomalyState::default()),
+            self_test: Mutex::new(None),
+            demo_mode: Mutex::new(None),
+            app_update: Mutex::new(None),
+            passphrase_prompts: PassphrasePrompts::default(),
+            hydrated: AtomicBool::new(false),
+        }
+    }
+}
+
+impl AppState {
+    /// Refuse to act on default state before startup has loaded the saved one
+    pub fn ensure_hydrated(&self) -> Result<(), CommandError> {
+        if self.hydrated.load(std::sync::atomic::Ordering::Acquire) {
+            Ok(())
+        } else {
+            Err(CommandError::new(
+                ErrorCode::Loading,
+                crate::config::STILL_LOADING,
+            ))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_stays_usable_after_a_panic_under_lock() {
+        let state = Arc::new(AppState::default());
+        let worker = Arc::clone(&state);
+        let result = std::thread::spawn(move || {
+            let _config = worker.config.lock();
+            let mut status = worker.proxy_status.lock();
+            status.running = true;
+            panic!("command failed while holding the lock");
+        })
+        .join();
+        assert!(result.is_err());
+
+        // The half-finished update stays visible and later commands still run
+        assert!(crate::proxy::lifecycle::running_status(&state).is_some());
+        assert!(!crate::proxy::lifecycle::mark_stopped(&state).running);
+        state.config.lock().port = 8318;
+        let reply = crate::mcp::handle_message(
+            &state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"get_status","arguments":{}}}"#,
+        )
+        .unwrap();
+        assert!(reply.contains(r#""isError":false"#));
+    }
+}
@@ -0,0 +1,241 @@
+//! Machine-readable status file for shell prompts and status bars.
+//!
+//! With `AppConfig.status_file_enabled` on, ProxyPal rewrites a small file
+//! (`<config_dir>/status.json` unless `status_file_path` is set) whenever the
+//! proxy status, today's request count or today's estimated spend changes.
+//! Writes are debounced to at most one per second and atomic (temp file +
+//! rename), so readers never see a half-written file.
+//!
+//! `status_file_format` selects the content:
+//!
+//! ```text
+//! json:  {"running":true,"port":8317,"endpoint":"http://127.0.0.1:8317/v1","requests_today":87,"cost_today":1.23,"last_error":null}
+//! plain: running :8317 | 87 req | $1.23
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Listener, Manager};
+use tokio::sync::Notify;
+
+use crate::state::AppState;
+
+/// Minimum time between two writes
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Re-check interval without events, so the counters roll over at midnight
+const IDLE_REFRESH: Duration = Duration::from_secs(60);
+
+/// Events that can change the file's content
+const WATCHED_EVENTS: &[&str] = &["proxy-status-changed", "request-log-batch"];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct StatusSnapshot {
+    running: bool,
+    port: u16,
+    endpoint: String,
+    requests_today: u64,
+    cost_today: f64,
+    last_error: Option<String>,
+}
+
+impl StatusSnapshot {
+    fn render(&self, format: &str) -> String {
+        if format == "plain" {
+            return self.render_plain();
+        }
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    // One line without a trailing newline, so `$(cat status)` needs no trimming
+    fn render_plain(&self) -> String {
+        if !self.running {
+            return "stopped".to_string();
+        }
+        let mut line = format!(":{} | {} req", self.port, self.requests_today);
+        if self.cost_today >= 0.01 {
+            line.push_str(&format!(" | ${:.2}", self.cost_today));
+        }
+        match &self.last_error {
+            Some(error) => format!("running {} | last error: {}", line, error),
+            None => format!("running {}", line),
+        }
+    }
+}
+
+/// Where the status file goes for the given config value
+pub fn status_file_path(configured: Option<&str>) -> PathBuf {
+    configured
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| crate::config::get_proxypal_config_dir().join("status.json"))
+}
+
+fn snapshot(state: &AppState) -> StatusSnapshot {
+    let status = state.proxy_status.lock().clone();
+    let last_error = state
+        .recent_errors
+        .lock()
+        .entries
+        .back()
+        .map(crate::helpers::proxy_errors::error_label);
+    let (requests_today, cost_today) = crate::helpers::history::today_stats(&state.history);
+    StatusSnapshot {
+        running: status.running,
+        port: status.port,
+        endpoint: status.endpoint,
+        requests_today,
+        cost_today: (cost_today * 100.0).round() / 100.0,
+        last_error,
+    }
+}
+
+// Write to a sibling temp file and rename it over the target
+pub(crate) fn write_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+/// Ask the writer to re-check the file (e.g. after the settings changed)
+pub fn refresh_status_file(app: &AppHandle) {
+    if let Some(state) = app.try_state::<AppState>() {
+        state.status_file_wake.notify_one();
+    }
+}
+
+/// Start the background writer. Called once from setup; it idles while the
+/// option is off.
+pub fn start_status_file_writer(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let wake: Arc<Notify> = state.status_file_wake.clone();
+    for name in WATCHED_EVENTS {
+        let wake = wake.clone();
+        app.listen_any(*name, move |_| wake.notify_one());
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // (path, content) last written, and the last error reported
+        let mut written: Option<(PathBuf, String)> = None;
+        let mut reported_error: Option<String> = None;
+        loop {
+            let _ = tokio::time::timeout(IDLE_REFRESH, wake.notified()).await;
+
+            let Some(state) = app.try_state::<AppState>() else {
+                return;
+            };
+            let (enabled, path, format) = {
+                let config = state.config.lock();
+                (
+                    config.status_file_enabled,
+                    status_file_path(config.status_file_path.as_deref()),
+                    config.status_file_format.clone(),
+                )
+            };
+            if !enabled {
+                written = None;
+                continue;
+            }
+
+            // Today's figures read the history, and the write may hit a slow
+            // disk; neither belongs on the async workers
+            let tick_app = app.clone();
+            let previous = written.clone();
+            let tick = tauri::async_runtime::spawn_blocking(move || {
+                let content = snapshot(&tick_app.state::<AppState>()).render(&format);
+                if previous.as_ref() == Some(&(path.clone(), content.clone())) {
+                    return None;
+                }
+                let result = write_atomically(&path, &content);
+                Some((path, content, result))
+            })
+            .await;
+            let Ok(Some((path, content, result))) = tick else {
+                continue;
+            };
+            // A read-only or missing location must not take the event pipeline
+            // down; report each distinct failure once and keep going.
+            match result {
+                Ok(()) => {
+                    written = Some((path, content));
+                    reported_error = None;
+                }
+                Err(e) => {
+                    let message = format!("{}: {}", path.display(), e);
+                    if reported_error.as_deref() != Some(message.as_str()) {
+                        eprintln!("[ProxyPal] Failed to write status file {}", message);
+                        reported_error = Some(message);
+                    }
+                }
+            }
+            tokio::time::sleep(DEBOUNCE).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(running: bool, cost_today: f64, last_error: Option<&str>) -> StatusSnapshot {
+        StatusSnapshot {
+            running,
+            port: 8317,
+            endpoint: "http://127.0.0.1:8317/v1".to_string(),
+            requests_today: 87,
+            cost_today,
+            last_error: last_error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn renders_plain_line() {
+        assert_eq!(snapshot(false, 0.0, None).render("plain"), "stopped");
+        assert_eq!(
+            snapshot(true, 0.0, None).render("plain"),
+            "running :8317 | 87 req"
+        );
+        assert_eq!(
+            snapshot(true, 1.234, Some("14:02 · 429 gpt-5 (rate limit)")).render("plain"),
+            "running :8317 | 87 req | $1.23 | last error: 14:02 · 429 gpt-5 (rate limit)"
+        );
+    }
+
+    #[test]
+    fn renders_json_with_snake_case_fields() {
+        let value: serde_json::Value =
+            serde_json::from_str(&snapshot(true, 1.23, None).render("json")).unwrap();
+        assert_eq!(value["running"], true);
+        assert_eq!(value["requests_today"], 87);
+        assert_eq!(value["cost_today"], 1.23);
+        assert!(value["last_error"].is_null());
+    }
+
+    #[test]
+    fn reports_unwritable_location_as_error() {
+        let dir = std::env::temp_dir().join(format!("proxypal-status-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("status.json");
+        write_atomically(&path, "first").unwrap();
+        write_atomically(&path, "second").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+
+        // A regular file where the parent directory should be
+        assert!(write_atomically(&path.join("status.json"), "x").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,654 @@
+//! System tray: menu, proxy-state icon variants and tooltip.
+//!
+//! Icon variants are derived from the one bundled icon: a badge per proxy
+//! state, drawn over either the color glyph or a monochrome one. On macOS the
+//! monochrome style is a template image the menu bar tints itself; elsewhere
+//! the glyph is dark or light to match the system theme, which the main
+//! window reports. `AppConfig.tray_icon_style` overrides the choice.
+
+use crate::helpers::proxy_errors::error_label;
+use crate::state::AppState;
+use crate::types::ProxyStatus;
+use tauri::{
+    image::Image,
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+    tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Emitter, Listener, Manager, Theme, Wry,
+};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_opener::OpenerExt;
+
+/// Proxy state as shown by the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayProxyState {
+    Stopped,
+    Starting,
+    Running,
+    Error,
+}
+
+/// How the glyph is drawn, resolved from `AppConfig.tray_icon_style`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IconStyle {
+    Color,
+    // macOS template image, tinted by the menu bar
+    Template,
+    // Dark or light glyph picked from the system theme
+    Monochrome,
+}
+
+/// Handles kept after setup so commands can update the tray
+pub struct TrayHandles {
+    pub icon: TrayIcon,
+    // Only re-set on Linux, where libappindicator needs it to pick up changes
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub menu: Menu<Wry>,
+    pub toggle_item: MenuItem<Wry>,
+    pub errors_menu: Submenu<Wry>,
+    pub state: TrayProxyState,
+    base_icon: Image<'static>,
+    // System theme as last reported by the main window
+    theme: Theme,
+    /// `RecentErrors::generation` the errors submenu was last built from
+    errors_generation: u64,
+}
+
+const ERROR_ITEM_PREFIX: &str = "error:";
+
+/// Bounds for `AppConfig.tray_stats_refresh_secs`
+const MIN_STATS_REFRESH_SECS: u64 = 5;
+const MAX_STATS_REFRESH_SECS: u64 = 3600;
+
+// Setup system tray
+pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let toggle_item = MenuItem::with_id(app, "toggle", "Start Proxy", true, None::<&str>)?;
+    let dashboard_item = MenuItem::with_id(app, "dashboard", "Open Dashboard", true, None::<&str>)?;
+    let copy_endpoint_item = MenuItem::with_id(
+        app,
+        "copy_endpoint",
+        "Copy API Endpoint",
+        true,
+        None::<&str>,
+    )?;
+    let open_logs_item =
+        MenuItem::with_id(app, "open_logs", "Open Logs Folder", true, None::<&str>)?;
+    let mini_monitor_item = MenuItem::with_id(
+        app,
+        "mini_monitor",
+        "Toggle Mini Monitor",
+        true,
+        None::<&str>,
+    )?;
+    let no_errors_item =
+        MenuItem::with_id(app, "no_errors", "No recent errors", false, None::<&str>)?;
+    let errors_menu = Submenu::with_id_and_items(
+        app,
+        "recent_errors",
+        "Recent Errors",
+        true,
+        &[&no_errors_item],
+    )?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit ProxyPal", true, None::<&str>)?;
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &toggle_item,
+            &dashboard_item,
+            &mini_monitor_item,
+            &PredefinedMenuItem::separator(app)?,
+            &copy_endpoint_item,
+            &open_logs_item,
+            &errors_menu,
+            &PredefinedMenuItem::separator(app)?,
+            &quit_item,
+        ],
+    )?;
+
+    // Use dedicated tray icon (22x22 @1x, 44x44 @2x for retina)
+    let tray_icon = Image::from_bytes(include_bytes!("../icons/tray-icon@2x.png"))
+        .expect("Failed to load tray icon");
+    let theme = app
+        .get_webview_window("main")
+        .and_then(|window| window.theme().ok())
+        .unwrap_or(Theme::Light);
+    let style = icon_style(&app.state::<AppState>().config.lock().tray_icon_style);
+
+    // icon_as_template is macOS-only (renders icon as template image for dark/light mode).
+    // On Windows/Linux the concept doesn't exist — calling it causes a transparent/invisible tray icon.
+    #[allow(unused_mut)]
+    let mut tray_builder = TrayIconBuilder::new().icon(variant_icon(
+        &tray_icon,
+        style,
+        theme,
+        TrayProxyState::Stopped,
+    ));
+    #[cfg(target_os = "macos")]
+    {
+        tray_builder = tray_builder.icon_as_template(style == IconStyle::Template);
+    }
+    let tray = tray_builder
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .tooltip("ProxyPal - Proxy stopped")
+        .on_menu_event(move |app, event| match event.id.as_ref() {
+            "toggle" => {
+                let app_state = app.state::<AppState>();
+                let is_running = app_state.proxy_status.lock().running;
+
+                // Emit toggle event to frontend
+                let _ = app.emit("tray-toggle-proxy", !is_running);
+            }
+            "dashboard" => show_dashboard(app),
+            "mini_monitor" => crate::helpers::window_state::toggle_mini_monitor(app),
+            "copy_endpoint" => {
+                let app_state = app.state::<AppState>();
+                let (host, port) = crate::proxy::lifecycle::client_address(&app_state);
+                let endpoint = crate::proxy::lifecycle::proxy_endpoint(&host, port);
+                let api_key = app_state.config.lock().proxy_api_key.clone();
+                let text = format!("Endpoint: {}\nAPI Key: {}", endpoint, api_key);
+                if let Err(e) = app.clipboard().write_text(text) {
+                    eprintln!("[ProxyPal] Failed to copy endpoint to clipboard: {}", e);
+                }
+            }
+            "open_logs" => {
+                let logs_dir = crate::config::get_data_dir().join("logs");
+                let _ = std::fs::create_dir_all(&logs_dir);
+                if let Err(e) = app
+                    .opener()
+                    .open_path(logs_dir.to_string_lossy(), None::<&str>)
+                {
+                    eprintln!("[ProxyPal] Failed to open logs folder: {}", e);
+                }
+            }
+            "quit" => crate::commands::shutdown::request_quit(app),
+            id => {
+                if let Some(error_id) = id.strip_prefix(ERROR_ITEM_PREFIX) {
+                    show_dashboard(app);
+                    let _ = app.emit("tray-focus-error", error_id.to_string());
+                }
+            }
+        })
+        .on_tray_icon_event(|tray, event| match event {
+            TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } => show_dashboard(tray.app_handle()),
+            // Rebuild the errors submenu just before the menu can open, and only if
+            // new errors arrived, rather than on every error
+            TrayIconEvent::Enter { .. } | TrayIconEvent::Click { .. } => {
+                refresh_errors_menu(tray.app_handle());
+            }
+            _ => {}
+        })
+        .build(app)?;
+
+    *app.state::<AppState>().tray.lock() = Some(TrayHandles {
+        icon: tray,
+        menu,
+        toggle_item,
+        errors_menu,
+        state: TrayProxyState::Stopped,
+        base_icon: tray_icon,
+        theme,
+        errors_generation: 0,
+    });
+
+    // Keep the tray in sync with proxy status changes from any source
+    let handle = app.handle().clone();
+    app.listen("proxy-status-changed", move |event| {
+        let Ok(status) = serde_json::from_str::<ProxyStatus>(event.payload()) else {
+            return;
+        };
+        let next = match (status.running, tray_state(&handle)) {
+            (true, _) => TrayProxyState::Running,
+            // An unexpected exit already flagged the error; keep showing it
+            (false, Some(TrayProxyState::Error)) => TrayProxyState::Error,
+            (false, _) => TrayProxyState::Stopped,
+        };
+        set_tray_state(&handle, next);
+    });
+
+    // Refresh uptime / today's stats periodically. Reads local aggregate files only,
+    // never the management API, so short intervals stay cheap.
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let refresh_secs = handle
+                .try_state::<AppState>()
+                .map(|s| s.config.lock().tray_stats_refresh_secs)
+                .unwrap_or(60)
+                .clamp(MIN_STATS_REFRESH_SECS, MAX_STATS_REFRESH_SECS);
+            tokio::time::sleep(std::time::Duration::from_secs(refresh_secs)).await;
+            refresh_tray_tooltip(&handle);
+            // Linux trays emit no hover/click events, so catch up on errors here
+            #[cfg(target_os = "linux")]
+            refresh_errors_menu(&handle);
+        }
+    });
+
+    Ok(())
+}
+
+/// Proxy state currently shown in the tray, if the tray is set up.
+pub fn tray_state(app: &AppHandle) -> Option<TrayProxyState> {
+    let state = app.try_state::<AppState>()?;
+    let tray = state.tray.lock();
+    tray.as_ref().map(|t| t.state)
+}
+
+/// Switch the tray icon, toggle label and tooltip to `proxy_state`.
+pub fn set_tray_state(app: &AppHandle, proxy_state: TrayProxyState) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let style = icon_style(&state.config.lock().tray_icon_style);
+    let mut tray = state.tray.lock();
+    let Some(handles) = tray.as_mut() else {
+        return;
+    };
+
+    if handles.state != proxy_state {
+        handles.state = proxy_state;
+        apply_icon(handles, style);
+
+        let label = match proxy_state {
+            TrayProxyState::Running => "Stop Proxy",
+            TrayProxyState::Starting => "Starting Proxy…",
+            TrayProxyState::Stopped | TrayProxyState::Error => "Start Proxy",
+        };
+        let _ = handles.toggle_item.set_text(label);
+        let _ = handles
+            .toggle_item
+            .set_enabled(proxy_state != TrayProxyState::Starting);
+        // libappindicator only picks up item changes when the menu is set again
+        #[cfg(target_os = "linux")]
+        let _ = handles.icon.set_menu(Some(handles.menu.clone()));
+    }
+    drop(tray);
+
+    refresh_tray_tooltip(app);
+}
+
+fn apply_icon(handles: &TrayHandles, style: IconStyle) {
+    let icon = variant_icon(&handles.base_icon, style, handles.theme, handles.state);
+    let _ = handles.icon.set_icon(Some(icon));
+    #[cfg(target_os = "macos")]
+    let _ = handles
+        .icon
+        .set_icon_as_template(style == IconStyle::Template);
+}
+
+/// Redraw the tray icon, after `tray_icon_style` changed.
+pub fn refresh_tray_icon(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let style = icon_style(&state.config.lock().tray_icon_style);
+    let tray = state.tray.lock();
+    if let Some(handles) = tray.as_ref() {
+        apply_icon(handles, style);
+    }
+}
+
+/// Follow a system theme change reported by the main window.
+pub fn set_tray_theme(app: &AppHandle, theme: Theme) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let style = icon_style(&state.config.lock().tray_icon_style);
+    let mut tray = state.tray.lock();
+    if let Some(handles) = tray.as_mut().filter(|h| h.theme != theme) {
+        handles.theme = theme;
+        apply_icon(handles, style);
+    }
+}
+
+/// Rebuild the "Recent Errors" submenu if the error buffer changed since the last build.
+pub fn refresh_errors_menu(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let (generation, entries) = {
+        let errors = state.recent_errors.lock();
+        (errors.generation, errors.entries.clone())
+    };
+    let mut tray = state.tray.lock();
+    let Some(handles) = tray.as_mut() else {
+        return;
+    };
+    if handles.errors_generation == generation {
+        return;
+    }
+
+    let submenu = &handles.errors_menu;
+    while let Ok(Some(_)) = submenu.remove_at(0) {}
+    if entries.is_empty() {
+        if let Ok(item) =
+            MenuItem::with_id(app, "no_errors", "No recent errors", false, None::<&str>)
+        {
+            let _ = submenu.append(&item);
+        }
+    }
+    // Newest first
+    for entry in entries.iter().rev() {
+        let id = format!("{}{}", ERROR_ITEM_PREFIX, entry.id);
+        if let Ok(item) = MenuItem::with_id(app, id, error_label(entry), true, None::<&str>) {
+            let _ = submenu.append(&item);
+        }
+    }
+    handles.errors_generation = generation;
+
+    #[cfg(target_os = "linux")]
+    let _ = handles.icon.set_menu(Some(handles.menu.clone()));
+}
+
+/// Handle a close request on the main window. Returns true when the window was
+/// hidden to the tray and the close should be prevented.
+pub fn hide_to_tray_on_close(app: &AppHandle) -> bool {
+    let Some(state) = app.try_state::<AppState>() else {
+        return false;
+    };
+    let (close_to_tray, notice_shown) = {
+        let config = state.config.lock();
+        (config.close_to_tray, config.close_to_tray_notice_shown)
+    };
+    if !close_to_tray {
+        return false;
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        println!("[ProxyPal] Hiding to system tray...");
+        let _ = window.hide();
+    }
+
+    if !notice_shown {
+        let _ = app
+            .notification()
+            .builder()
+            .title("ProxyPal is still running")
+            .body("The proxy keeps running in the system tray. Use Quit ProxyPal from the tray menu to exit.")
+            .show();
+        let config = {
+            let mut config = state.config.lock();
+            config.close_to_tray_notice_shown = true;
+            config.clone()
+        };
+        if let Err(e) = crate::config::save_config_to_file(&config) {
+            eprintln!("[ProxyPal] Failed to save config: {}", e);
+        }
+    }
+
+    true
+}
+
+fn show_dashboard(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Recompute the tooltip (and, if enabled, the tray title) from the current proxy
+/// status, uptime and today's stats.
+pub fn refresh_tray_tooltip(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let status = state.proxy_status.lock().clone();
+    let uptime = state
+        .proxy_started_at
+        .lock()
+        .map(|started| started.elapsed().as_secs());
+    let (requests_today, cost_today) = crate::helpers::history::today_stats(&state.history);
+    let scheduled_off = crate::scheduler::scheduled_off_until(&state);
+
+    let tray = state.tray.lock();
+    if let Some(handles) = tray.as_ref() {
+        let text = tooltip_text(
+            handles.state,
+            &status,
+            uptime,
+            requests_today,
+            cost_today,
+            scheduled_off.as_deref(),
+        );
+        let _ = handles.icon.set_tooltip(Some(text));
+
+        // Windows trays have no title area
+        #[cfg(not(target_os = "windows"))]
+        {
+            let show_stats = state.config.lock().tray_show_stats;
+            let title = show_stats.then(|| quick_stats_title(requests_today, cost_today));
+            let _ = handles.icon.set_title(title);
+        }
+    }
+}
+
+fn tooltip_text(
+    proxy_state: TrayProxyState,
+    status: &ProxyStatus,
+    uptime_secs: Option<u64>,
+    requests_today: u64,
+    cost_today: f64,
+    scheduled_off_until: Option<&str>,
+) -> String {
+    match proxy_state {
+        TrayProxyState::Stopped => match scheduled_off_until {
+            Some("") => "ProxyPal - Scheduled off".to_string(),
+            Some(until) => format!("ProxyPal - Scheduled off until {}", until),
+            None => "ProxyPal - Proxy stopped".to_string(),
+        },
+        TrayProxyState::Starting => "ProxyPal - Starting proxy…".to_string(),
+        TrayProxyState::Error => "ProxyPal - Proxy error (open dashboard for details)".to_string(),
+        TrayProxyState::Running => format!(
+            "ProxyPal - Running on port {}\nUptime: {}\nToday: {}",
+            status.port,
+            format_uptime(uptime_secs.unwrap_or(0)),
+            quick_stats_title(requests_today, cost_today)
+        ),
+    }
+}
+
+/// "$1.23 • 87 req"; the cost is left out below one cent.
+fn quick_stats_title(requests: u64, cost_usd: f64) -> String {
+    if cost_usd >= 0.01 {
+        format!("${:.2} • {} req", cost_usd, requests)
+    } else {
+        format!("{} req", requests)
+    }
+}
+
+fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, (secs % 86_400) / 3600, (secs % 3600) / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+// "auto" uses a template on macOS, follows the theme on Windows and keeps the
+// color icon on Linux, where panel themes can't be detected
+fn icon_style(setting: &str) -> IconStyle {
+    let monochrome = if cfg!(target_os = "macos") {
+        IconStyle::Template
+    } else {
+        IconStyle::Monochrome
+    };
+    match setting {
+        "color" => IconStyle::Color,
+        "monochrome" => monochrome,
+        _ if cfg!(target_os = "linux") => IconStyle::Color,
+        _ => monochrome,
+    }
+}
+
+// Recolor every visible pixel of the glyph, keeping its alpha: dark on a light
+// theme, light on a dark one
+fn monochrome_icon(base: &Image<'static>, theme: Theme) -> Image<'static> {
+    let glyph: [u8; 3] = match theme {
+        Theme::Dark => [0xFF, 0xFF, 0xFF],
+        _ => [0x1F, 0x1F, 0x1F],
+    };
+    let mut rgba = base.rgba().to_vec();
+    for px in rgba.chunks_mut(4) {
+        if px[3] > 0 {
+            px[..3].copy_from_slice(&glyph);
+        }
+    }
+    Image::new_owned(rgba, base.width(), base.height())
+}
+
+// The icon for a style, theme and proxy state
+fn variant_icon(
+    base: &Image<'static>,
+    style: IconStyle,
+    theme: Theme,
+    proxy_state: TrayProxyState,
+) -> Image<'static> {
+    match style {
+        IconStyle::Monochrome => status_icon(&monochrome_icon(base, theme), proxy_state),
+        IconStyle::Color | IconStyle::Template => status_icon(base, proxy_state),
+    }
+}
+
+// Derive a state variant from the base icon by drawing a badge in the bottom-right
+// corner. Shapes differ per state (dot / ring / square) so they stay distinguishable
+// when macOS renders the icon as a monochrome template.
+fn status_icon(base: &Image<'static>, proxy_state: TrayProxyState) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let color: [u8; 3] = match proxy_state {
+        TrayProxyState::Stopped => return base.clone(),
+        TrayProxyState::Starting => [0xF5, 0xA6, 0x23],
+        TrayProxyState::Running => [0x34, 0xC7, 0x59],
+        TrayProxyState::Error => [0xFF, 0x3B, 0x30],
+    };
+
+    let radius = (width.min(height) as f32) / 5.0;
+    let (cx, cy) = (width as f32 - radius - 1.0, height as f32 - radius - 1.0);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            let dist = (dx * dx + dy * dy).sqrt();
+            let in_badge = match proxy_state {
+                TrayProxyState::Starting => dist <= radius && dist >= radius * 0.55,
+                TrayProxyState::Error => dx.abs() <= radius * 0.85 && dy.abs() <= radius * 0.85,
+                _ => dist <= radius,
+            };
+            let in_gap = dist <= radius + 1.5;
+            let idx = ((y * width + x) * 4) as usize;
+            if in_badge {
+                rgba[idx..idx + 4].copy_from_slice(&[color[0], color[1], color[2], 0xFF]);
+            } else if in_gap {
+                // Clear a thin gap around the badge so it reads against the glyph
+                rgba[idx + 3] = 0;
+            }
+        }
+    }
+
+    Image::new_owned(rgba, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tooltip_includes_port_uptime_and_requests_when_running() {
+        let status = ProxyStatus {
+            running: true,
+            port: 8317,
+            endpoint: "http://localhost:8317/v1".to_string(),
+            ..Default::default()
+        };
+        let text = tooltip_text(
+            TrayProxyState::Running,
+            &status,
+            Some(3 * 3600 + 120),
+            42,
+            1.234,
+            None,
+        );
+        assert!(text.contains("8317"));
+        assert!(text.contains("3h 2m"));
+        assert!(text.contains("Today: $1.23 • 42 req"));
+
+        let stopped = tooltip_text(TrayProxyState::Stopped, &status, None, 0, 0.0, None);
+        assert_eq!(stopped, "ProxyPal - Proxy stopped");
+        let scheduled = tooltip_text(
+            TrayProxyState::Stopped,
+            &status,
+            None,
+            0,
+            0.0,
+            Some("17:00"),
+        );
+        assert_eq!(scheduled, "ProxyPal - Scheduled off until 17:00");
+    }
+
+    #[test]
+    fn quick_stats_omit_cost_below_a_cent() {
+        assert_eq!(quick_stats_title(87, 1.234), "$1.23 • 87 req");
+        assert_eq!(quick_stats_title(3, 0.004), "3 req");
+        assert_eq!(quick_stats_title(0, 0.0), "0 req");
+    }
+
+    #[test]
+    fn monochrome_variants_follow_the_theme() {
+        let mut pixels = vec![0u8; 44 * 44 * 4];
+        pixels[..4].copy_from_slice(&[0x7B, 0x3F, 0xE4, 0xFF]);
+        let base = Image::new_owned(pixels, 44, 44);
+
+        let light = variant_icon(
+            &base,
+            IconStyle::Monochrome,
+            Theme::Light,
+            TrayProxyState::Stopped,
+        );
+        assert_eq!(light.rgba()[..4], [0x1F, 0x1F, 0x1F, 0xFF]);
+        let dark = variant_icon(
+            &base,
+            IconStyle::Monochrome,
+            Theme::Dark,
+            TrayProxyState::Error,
+        );
+        assert_eq!(dark.rgba()[..4], [0xFF, 0xFF, 0xFF, 0xFF]);
+        // Transparent pixels stay transparent, and the state badge keeps its color
+        assert_eq!(dark.rgba()[4..8], [0, 0, 0, 0]);
+        assert!(dark
+            .rgba()
+            .chunks(4)
+            .any(|px| px == [0xFF, 0x3B, 0x30, 0xFF]));
+
+        let color = variant_icon(
+            &base,
+            IconStyle::Color,
+            Theme::Dark,
+            TrayProxyState::Stopped,
+        );
+        assert_eq!(color.rgba(), base.rgba());
+        assert_eq!(icon_style("color"), IconStyle::Color);
+    }
+
+    #[test]
+    fn status_icon_keeps_dimensions_and_marks_badge() {
+        let base = Image::new_owned(vec![0u8; 44 * 44 * 4], 44, 44);
+        let running = status_icon(&base, TrayProxyState::Running);
+        assert_eq!((running.width(), running.height()), (44, 44));
+        assert!(running
+            .rgba()
+            .chunks(4)
+            .any(|px| px == [0x34, 0xC7, 0x59, 0xFF]));
+        assert_eq!(
+            status_icon(&base, TrayProxyState::Stopped).rgba(),
+            base.rgba()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{AgentModelPrefs, AmpModelMapping, FilePreview};
+
+/// Shareable agent setup. Holds no key values or credentials.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentBundle {
+    pub version: u32,
+    pub exported_at: u64,
+    #[serde(default)]
+    pub agents: Vec<String>, // Agents to configure on import
+    #[serde(default)]
+    pub agent_model_preferences: BTreeMap<String, AgentModelPrefs>,
+    #[serde(default)]
+    pub client_keys: Vec<BundleClientKey>,
+    #[serde(default)]
+    pub model_mappings: Vec<AmpModelMapping>,
+}
+
+/// A client key by label only; the importing machine issues its own value
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleClientKey {
+    pub name: String,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+}
+
+/// What importing a bundle changes, or would change in a dry run
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentBundleImport {
+    pub dry_run: bool,
+    pub config_diff: String, // Unified diff of the shared settings, empty when unchanged
+    pub client_keys_created: Vec<String>,
+    pub previews: Vec<FilePreview>, // Agent config files, dry run only
+    pub configured: Vec<String>,
+    pub skipped: Vec<String>, // Agents in the bundle that are not installed here
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+/// A proxy endpoint an agent is set up to use, and where that was found
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentEndpoint {
+    pub agent_id: String,
+    /// Config file or shell profile path, or "environment" for ProxyPal's own env
+    pub source: String,
+    /// Environment variable, for env and shell profile sources
+    #[serde(default)]
+    pub variable: Option<String>,
+    pub url: String,
+    pub host: String, // Loopback names are reported as 127.0.0.1
+    pub port: u16,
+    #[serde(default)]
+    pub key_stale: bool, // Carries a key the proxy no longer accepts
+}
+
+/// Endpoints pointing at the same host and port
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentEndpointGroup {
+    pub host: String,
+    pub port: u16,
+    pub matches_proxy: bool, // The endpoint this ProxyPal serves
+    pub agent_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EndpointConflictKind {
+    /// Local, but another port (an older install, or a port change)
+    Port,
+    /// Another machine or address entirely
+    Host,
+    /// Right endpoint, but a retired or revoked key
+    StaleKey,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointConflict {
+    pub kind: EndpointConflictKind,
+    pub agent_id: String,
+    pub source: String,
+    pub found: String,
+    pub expected: String,
+}
+
+/// Result of `audit_agent_endpoints`, also the `agent-endpoint-conflicts` payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentEndpointAudit {
+    pub expected: String,
+    pub groups: Vec<AgentEndpointGroup>,
+    pub endpoints: Vec<AgentEndpoint>,
+    pub conflicts: Vec<EndpointConflict>,
+    /// Agents `fix_agent_endpoints` would reconfigure
+    pub outliers: Vec<String>,
+}
+
+/// What `fix_agent_endpoints` did for one source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentEndpointFix {
+    pub agent_id: String,
+    pub source: String,
+    /// "updated", "manual" (the user has to change it) or "failed"
+    pub status: String,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+/// An agent env var (ANTHROPIC_*, OPENAI_BASE_URL, ...) set to a URL other
+/// than this proxy's, which takes the agent's traffic elsewhere
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvConflict {
+    pub variable: String,
+    pub value: String, // Redacted
+    /// Shell profile path, or "environment" for ProxyPal's own env
+    pub source: String,
+    #[serde(default)]
+    pub line: Option<u32>, // 1-based, for shell profile sources
+    /// Inside a block ProxyPal wrote (from a `# ProxyPal` header to the next
+    /// blank line); only these are fixed without being asked
+    #[serde(default)]
+    pub managed: bool,
+    pub expected: String,
+}
+
+/// What `fix_environment_conflicts` did for one conflict
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvConflictFix {
+    pub variable: String,
+    pub source: String,
+    #[serde(default)]
+    pub line: Option<u32>,
+    /// "updated", "manual" (the user has to change it) or "failed"
+    pub status: String,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
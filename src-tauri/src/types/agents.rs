@@ -11,6 +11,64 @@ pub struct DetectedTool {
     pub can_auto_configure: bool,
 }
 
+/// Wire protocol a client speaks to the proxy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiProtocol {
+    /// Chat Completions / Responses under `/v1`
+    #[default]
+    OpenAI,
+    /// Messages API (`/v1/messages`)
+    Anthropic,
+    /// Gemini native API under `/v1beta`
+    Gemini,
+}
+
+// Environment variable a client reads its connection settings from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvVar {
+    pub name: String,
+    pub value: String,
+}
+
+// How a client speaking `protocol` connects to the proxy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionInfo {
+    pub protocol: ApiProtocol,
+    pub base_url: String,     // What SDKs expect as their base URL
+    pub example_path: String, // A request path relative to base_url
+    pub auth_header: String,  // e.g. "x-api-key" or "Authorization: Bearer"
+    pub api_key: String,
+    pub env: Vec<EnvVar>,
+    // Shell lines setting `env` (PowerShell on Windows; always POSIX in a container)
+    pub snippet: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docker: Option<DockerHost>, // Set for the Docker target
+}
+
+/// Where the client connecting to the proxy runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionTarget {
+    /// On this machine
+    #[default]
+    Host,
+    /// In a Docker container or devcontainer on this machine
+    Docker,
+}
+
+// How containers on this machine reach the proxy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerHost {
+    pub installed: bool,               // Docker CLI found; only then offer container snippets
+    pub host: String,                  // "host.docker.internal", or the bridge gateway on Linux
+    pub add_host_flag: Option<String>, // `docker run` flag Linux needs for host.docker.internal
+    pub warning: Option<String>,       // e.g. the proxy only listens on 127.0.0.1
+}
+
 // CLI Agent configuration status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -24,6 +82,39 @@ pub struct AgentStatus {
     pub config_path: Option<String>,
     pub logo: String,
     pub docs_url: String,
+    // Configured, but model preferences changed since the config was written
+    #[serde(default)]
+    pub configured_stale: bool,
+    #[serde(default)]
+    pub protocol: ApiProtocol,
+}
+
+// Per-agent model preferences used when generating agent configs.
+// Empty fields fall back to models picked from the proxy's live /v1/models list.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentModelPrefs {
+    #[serde(default)]
+    pub primary_model: Option<String>,
+    #[serde(default)]
+    pub small_model: Option<String>,
+    #[serde(default)]
+    pub reasoning_effort: Option<String>,
+    /// Gemini CLI only; unset means OAuth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gemini_auth_mode: Option<GeminiAuthMode>,
+}
+
+/// How Gemini CLI authenticates against the proxy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GeminiAuthMode {
+    /// CODE_ASSIST_ENDPOINT; the OAuth redirect only works on this machine
+    #[default]
+    #[serde(rename = "oauth")]
+    OAuth,
+    /// GOOGLE_GEMINI_BASE_URL + GEMINI_API_KEY; works from any host
+    #[serde(rename = "api-key")]
+    ApiKey,
 }
 
 // Test agent connection by making a simple API call through the proxy
@@ -47,3 +138,13 @@ pub struct ClaudeCodeSettings {
     pub base_url: Option<String>,
     pub auth_token: Option<String>,
 }
+
+// Would-be change to a config file, returned by configure commands in dry-run mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePreview {
+    pub path: String,
+    pub exists: bool,
+    pub changed: bool,
+    pub diff: String,
+}
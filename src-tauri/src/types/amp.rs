@@ -38,3 +38,48 @@ pub struct AmpOpenAIProvider {
 pub(crate) fn generate_uuid() -> String {
     uuid::Uuid::new_v4().to_string()
 }
+
+/// One provider in an Amp pool. `provider` is the id of an
+/// `AmpOpenAIProvider` or a connected provider ("claude", "gemini", ...);
+/// `model` is the model to ask it for.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AmpPoolMember {
+    pub provider: String,
+    pub model: String,
+    #[serde(default = "default_pool_weight")]
+    pub weight: u32,
+}
+
+fn default_pool_weight() -> u32 {
+    1
+}
+
+/// Amp models (`routes`) sent to an ordered group of providers that share
+/// the requests between them
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AmpPool {
+    #[serde(default = "generate_uuid")]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub routes: Vec<String>,
+    pub members: Vec<AmpPoolMember>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// Requests in history that Amp sent through one pool
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AmpPoolUsage {
+    pub pool_id: String,
+    pub name: String,
+    pub requests: u64,
+    pub success_count: u64,
+    pub tokens_in: u64,
+    pub tokens_out: u64,
+    pub estimated_cost: f64,
+    pub last_used: u64,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// One line of `audit.log`: a mutating command and what it changed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// Unix millis
+    pub timestamp: u64,
+    pub command: String,
+    /// Changed fields, e.g. "routingStrategy: round-robin → fill-first".
+    /// Secret values are never included.
+    pub changes: Vec<String>,
+    /// "ok" or "error"
+    pub outcome: String,
+    #[serde(default)]
+    pub error: Option<String>,
+}
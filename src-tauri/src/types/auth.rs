@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +13,14 @@ pub struct AuthStatus {
     pub kiro: u32,
     pub antigravity: u32,
     pub kimi: u32,
+    // Key-only providers: the number of keys entered in settings
+    #[serde(default)]
+    pub deepseek: u32,
+    #[serde(default)]
+    pub zhipu: u32,
+    // Result of the last post-auth check per provider; missing until one ran
+    #[serde(default)]
+    pub verified: HashMap<String, bool>,
 }
 
 impl Default for AuthStatus {
@@ -25,14 +35,99 @@ impl Default for AuthStatus {
             kiro: 0,
             antigravity: 0,
             kimi: 0,
+            deepseek: 0,
+            zhipu: 0,
+            verified: HashMap::new(),
         }
     }
 }
 
+impl AuthStatus {
+    /// True if at least one provider account is connected
+    pub fn any_connected(&self) -> bool {
+        crate::providers::ids().any(|provider| self.count(provider) > 0)
+    }
+
+    /// Accounts connected for a provider id, 0 for unknown ids
+    pub fn count(&self, provider: &str) -> u32 {
+        match provider {
+            "claude" => self.claude,
+            "openai" => self.openai,
+            "gemini" => self.gemini,
+            "qwen" => self.qwen,
+            "iflow" => self.iflow,
+            "vertex" => self.vertex,
+            "kiro" => self.kiro,
+            "antigravity" => self.antigravity,
+            "kimi" => self.kimi,
+            "deepseek" => self.deepseek,
+            "zhipu" => self.zhipu,
+            _ => 0,
+        }
+    }
+
+    /// Account count field for a provider id, None for unknown ids
+    pub fn count_mut(&mut self, provider: &str) -> Option<&mut u32> {
+        match provider {
+            "claude" => Some(&mut self.claude),
+            "openai" => Some(&mut self.openai),
+            "gemini" => Some(&mut self.gemini),
+            "qwen" => Some(&mut self.qwen),
+            "iflow" => Some(&mut self.iflow),
+            "vertex" => Some(&mut self.vertex),
+            "kiro" => Some(&mut self.kiro),
+            "antigravity" => Some(&mut self.antigravity),
+            "kimi" => Some(&mut self.kimi),
+            "deepseek" => Some(&mut self.deepseek),
+            "zhipu" => Some(&mut self.zhipu),
+            _ => None,
+        }
+    }
+
+    /// Carry over check results for providers that are still connected
+    pub fn keep_verification(&mut self, previous: &AuthStatus) {
+        for (provider, verified) in &previous.verified {
+            if self.count(provider) > 0 {
+                self.verified.insert(provider.clone(), *verified);
+            }
+        }
+    }
+}
+
+/// Outcome of `verify_provider`
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderVerification {
+    pub provider: String,
+    pub verified: bool,
+    pub model: String, // Model the check request was sent to
+    pub status: Option<u16>,
+    pub issue: Option<String>, // e.g. "noProject", "apiDisabled", "billingDisabled"
+    pub guidance: Option<String>,
+    pub link: Option<String>,
+    pub checked_at: u64,
+}
+
+/// An OAuth flow in progress, keyed by the state string CLIProxyAPI issued
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct OAuthState {
     pub provider: String,
     pub state: String,
+    pub created_at: u64, // Unix millis
+    pub expires_at: u64, // Dropped after this unless completed earlier
+    pub status: OAuthFlowStatus,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OAuthFlowStatus {
+    /// Waiting for the user to sign in
+    Pending,
+    /// The deep-link callback arrived; the code still has to be exchanged
+    CallbackReceived,
+    /// The proxy reported the account as added
+    Completed,
 }
 
 // Detailed auth status from CLIProxyAPI's /api/auth/status endpoint (v6.6.72+)
@@ -38,8 +38,26 @@ pub struct AuthFile {
     pub updated_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_refresh: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>, // Token expiry from the credential file (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<String>, // Plan or tier from the credential file
     #[serde(skip_serializing_if = "Option::is_none")]
     pub success_count: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub failure_count: Option<u64>,
+    #[serde(default)]
+    pub refreshable: bool, // Offers a "Refresh" action: refresh_provider_token renews it in place
+}
+
+/// What `refresh_provider_token` did for one credential file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialRefresh {
+    pub file: String,
+    pub provider: String,
+    pub status: String, // "refreshed", "reauth-needed" (see reauth_credential), "reauth-started" or "failed"
+    pub expires_at: Option<String>, // New token expiry (RFC 3339), once refreshed
+    pub oauth_state: Option<String>, // Poll with poll_oauth_status; the file is replaced on completion
+    pub error: Option<String>,       // Why a "failed" or "reauth-needed" refresh failed
 }
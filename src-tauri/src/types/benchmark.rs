@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// One model's reply in a benchmark run
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    pub model: String,
+    pub provider: String,
+    pub status: Option<u16>, // None when the request never got a response
+    pub error: Option<String>,
+    pub ttfb_ms: Option<u64>, // Time to the first streamed byte
+    pub total_ms: Option<u64>,
+    pub tokens_in: Option<u32>,
+    pub tokens_out: Option<u32>,
+    pub estimated_cost: Option<f64>,
+    pub response: Option<String>, // Reply text, truncated
+}
+
+/// The same prompt sent to several models at once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkRun {
+    pub id: String,
+    pub timestamp: u64,
+    pub prompt: String,
+    pub max_tokens: u32,
+    pub results: Vec<BenchmarkResult>,
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// A stored request/response exchange. Ids are either a request log file
+/// name written by the proxy or a replay saved by ProxyPal.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Capture {
+    pub id: String,
+    pub timestamp: u64,
+    pub method: String,
+    pub path: String,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>, // Auth headers are never stored
+    pub body: String,
+    pub response_status: Option<u16>,
+    #[serde(default)]
+    pub response_body: String, // Streamed responses are stored as received, fully accumulated
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub replay_of: Option<String>, // Capture this one replayed
+    #[serde(default)]
+    pub overrides: Option<ReplayOverrides>,
+}
+
+/// Listing entry for `list_captures`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureSummary {
+    pub id: String,
+    pub timestamp: u64,
+    pub method: String,
+    pub path: String,
+    pub response_status: Option<u16>,
+    pub replay_of: Option<String>,
+}
+
+/// Changes applied to a captured request before it is re-sent
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayOverrides {
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+}
+
+/// How the replayed response compares with the captured one
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseDiffSummary {
+    pub old_status: Option<u16>,
+    pub new_status: u16,
+    pub old_bytes: usize,
+    pub new_bytes: usize,
+    pub identical: bool,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub summary: String, // e.g. "Status 500 → 200; 312 → 1840 bytes; +40/-3 lines"
+}
+
+/// Result of `replay_request`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResult {
+    pub capture_id: String, // The new capture
+    pub replay_of: String,
+    pub status: u16,
+    pub latency_ms: u64,
+    pub diff: ResponseDiffSummary,
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of importing a standalone CLIProxyAPI config.yaml, from
+/// `import_cliproxy_config` or on first run (`cliproxy-config-imported`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliproxyConfigImport {
+    pub path: String,
+    /// Keys carried over into the settings, e.g. "request-retry"
+    pub mapped: Vec<String>,
+    /// Keys ProxyPal has no setting for, e.g. "payload" or "gemini-api-key[].headers";
+    /// they can go into proxy-config-custom.yaml
+    pub unmapped: Vec<String>,
+    /// Values that were recognized but not imported, and why
+    pub warnings: Vec<String>,
+}
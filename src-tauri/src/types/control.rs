@@ -0,0 +1,146 @@
+//! Response schema of the local control API (`control_api.rs`).
+//!
+//! Launchers such as Raycast or Alfred script against these field names, so
+//! they are part of the public interface: add fields, don't rename them.
+
+use serde::{Deserialize, Serialize};
+
+use super::{AuthFile, ProxyStatus, UsageStats};
+
+/// `GET /status`, and the reply to `POST /start|stop|toggle`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlStatus {
+    pub running: bool,
+    pub port: u16,
+    pub endpoint: String,
+    pub uptime_secs: Option<u64>,
+}
+
+impl ControlStatus {
+    pub fn new(status: ProxyStatus, uptime_secs: Option<u64>) -> Self {
+        Self {
+            running: status.running,
+            port: status.port,
+            endpoint: status.endpoint,
+            uptime_secs,
+        }
+    }
+}
+
+/// `GET /usage`: totals without the per-model and time series breakdowns
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlUsage {
+    pub total_requests: u64,
+    pub success_count: u64,
+    pub failure_count: u64,
+    pub total_tokens: u64,
+    pub requests_today: u64,
+    pub tokens_today: u64,
+    pub cost_today: f64,
+}
+
+impl ControlUsage {
+    pub fn new(stats: &UsageStats, cost_today: f64) -> Self {
+        Self {
+            total_requests: stats.total_requests,
+            success_count: stats.success_count,
+            failure_count: stats.failure_count,
+            total_tokens: stats.total_tokens,
+            requests_today: stats.requests_today,
+            tokens_today: stats.tokens_today,
+            cost_today: (cost_today * 100.0).round() / 100.0,
+        }
+    }
+}
+
+/// One entry of `GET /credentials`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlCredential {
+    pub id: String,
+    pub provider: String,
+    pub name: String,
+    pub email: Option<String>,
+    pub status: String,
+    pub disabled: bool,
+}
+
+impl From<&AuthFile> for ControlCredential {
+    fn from(file: &AuthFile) -> Self {
+        Self {
+            id: file.id.clone(),
+            provider: file.provider.clone(),
+            name: file.name.clone(),
+            email: file.email.clone(),
+            status: file.status.clone(),
+            disabled: file.disabled,
+        }
+    }
+}
+
+/// Body of every non-2xx response
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ControlError {
+    pub error: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_serializes_with_stable_names() {
+        let status = ControlStatus {
+            running: true,
+            port: 8317,
+            endpoint: "http://localhost:8317/v1".to_string(),
+            uptime_secs: Some(42),
+        };
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#"{"running":true,"port":8317,"endpoint":"http://localhost:8317/v1","uptimeSecs":42}"#
+        );
+    }
+
+    #[test]
+    fn usage_serializes_with_stable_names() {
+        let usage = ControlUsage {
+            total_requests: 10,
+            success_count: 9,
+            failure_count: 1,
+            total_tokens: 1500,
+            requests_today: 3,
+            tokens_today: 400,
+            cost_today: 0.12,
+        };
+        assert_eq!(
+            serde_json::to_string(&usage).unwrap(),
+            r#"{"totalRequests":10,"successCount":9,"failureCount":1,"totalTokens":1500,"requestsToday":3,"tokensToday":400,"costToday":0.12}"#
+        );
+    }
+
+    #[test]
+    fn credential_and_error_serialize_with_stable_names() {
+        let credential = ControlCredential {
+            id: "claude-a.json".to_string(),
+            provider: "claude".to_string(),
+            name: "claude-a.json".to_string(),
+            email: None,
+            status: "active".to_string(),
+            disabled: false,
+        };
+        assert_eq!(
+            serde_json::to_string(&credential).unwrap(),
+            r#"{"id":"claude-a.json","provider":"claude","name":"claude-a.json","email":null,"status":"active","disabled":false}"#
+        );
+        let error = ControlError {
+            error: "Unauthorized".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&error).unwrap(),
+            r#"{"error":"Unauthorized"}"#
+        );
+    }
+}
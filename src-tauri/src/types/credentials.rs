@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+/// Asks the frontend to collect a passphrase, sent as `passphrase-requested`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PassphraseRequest {
+    pub id: String,
+    pub purpose: String, // "export-credentials" or "import-credentials"
+    pub confirm: bool,   // Ask twice, for passphrases that protect new data
+}
+
+/// Auth files written to or read from an encrypted credentials file
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialTransfer {
+    pub files: Vec<String>,
+    pub skipped: Vec<String>, // Already present on import; left untouched
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// Masked diagnostic report for sharing when asking for help
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticReport {
+    pub text: String, // Markdown, ready for the clipboard
+    pub path: Option<String>, // Zip with the report and masked logs, when requested
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// Provider health states a failover rule can react to
+pub const FAILOVER_TRIGGERS: &[&str] = &["rate_limited", "auth_expired", "offline"];
+
+/// Model requested from the unhealthy provider, and what to send instead.
+/// `from` may end in `*` to cover every known model with that prefix.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverModelMapping {
+    pub from: String,
+    pub to: String,
+}
+
+/// Send `from_provider`'s models to `to_provider` while `from_provider` is in
+/// the `trigger` state. Applies to Amp requests only (see `failover`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverRule {
+    pub id: String,
+    pub from_provider: String,
+    pub to_provider: String,
+    pub trigger: String,
+    pub model_map: Vec<FailoverModelMapping>,
+    #[serde(default = "default_failover_enabled")]
+    pub enabled: bool,
+}
+
+fn default_failover_enabled() -> bool {
+    true
+}
+
+/// A rule currently applied to the running proxy; payload of the
+/// `failover-activated` and `failover-cleared` events
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverActivation {
+    pub rule_id: String,
+    pub from_provider: String,
+    pub to_provider: String,
+    pub trigger: String,
+    /// Unix millis
+    pub activated_at: u64,
+    /// Mappings pushed to the proxy, with wildcards expanded
+    pub mappings: Vec<FailoverModelMapping>,
+}
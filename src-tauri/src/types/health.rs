@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 // Provider health status
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProviderHealth {
     pub claude: HealthStatus,
     pub openai: HealthStatus,
@@ -11,6 +11,39 @@ pub struct ProviderHealth {
     pub vertex: HealthStatus,
     pub kiro: HealthStatus,
     pub antigravity: HealthStatus,
+    #[serde(default)]
+    pub kimi: HealthStatus,
+    #[serde(default)]
+    pub deepseek: HealthStatus,
+    #[serde(default)]
+    pub zhipu: HealthStatus,
+}
+
+impl ProviderHealth {
+    /// (provider, status) for every provider, in field order
+    pub fn statuses(&self) -> [(&'static str, &HealthStatus); 11] {
+        [
+            ("claude", &self.claude),
+            ("openai", &self.openai),
+            ("gemini", &self.gemini),
+            ("qwen", &self.qwen),
+            ("iflow", &self.iflow),
+            ("vertex", &self.vertex),
+            ("kiro", &self.kiro),
+            ("antigravity", &self.antigravity),
+            ("kimi", &self.kimi),
+            ("deepseek", &self.deepseek),
+            ("zhipu", &self.zhipu),
+        ]
+    }
+
+    /// Status of one provider, e.g. "rate_limited"
+    pub fn status(&self, provider: &str) -> Option<&str> {
+        self.statuses()
+            .into_iter()
+            .find(|(name, _)| *name == provider)
+            .map(|(_, health)| health.status.as_str())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
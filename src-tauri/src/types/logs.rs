@@ -8,3 +8,29 @@ pub struct LogEntry {
     pub level: String,
     pub message: String,
 }
+
+// Health of the request-log pipeline between the log reader and the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyLogDiagnostics {
+    /// Requests emitted and persisted since launch
+    pub processed_requests: u64,
+    /// Requests dropped because the queue was full
+    pub dropped_requests: u64,
+    pub queue_capacity: usize,
+    pub batch_interval_ms: u64,
+}
+
+// Classified proxy error kept in the in-memory recent-errors buffer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyErrorEntry {
+    pub id: String,
+    pub timestamp: u64,
+    /// "auth", "rate_limit", "upstream", "client" or "process"
+    pub kind: String,
+    pub status: Option<u16>,
+    pub provider: String,
+    pub model: String,
+    pub message: String,
+}
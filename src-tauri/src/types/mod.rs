@@ -1,33 +1,87 @@
+pub mod agent_bundle;
+pub mod agent_endpoints;
 pub mod agents;
 pub mod amp;
 pub mod api_keys;
+pub mod audit;
 pub mod auth;
 pub mod auth_files;
+pub mod benchmark;
+pub mod captures;
+pub mod cliproxy_config;
+pub mod control;
 pub mod copilot;
+pub mod credentials;
+pub mod diagnostics;
+pub mod failover;
 pub mod health;
 pub mod logs;
+pub mod model_caps;
+pub mod model_catalog;
 pub mod models;
+pub mod network;
+pub mod onboarding;
+pub mod permissions;
 pub mod proxy;
+pub mod proxy_keys;
 pub mod quota;
+pub mod rate_limits;
+pub mod request_watches;
+pub mod schedule;
+pub mod self_test;
+pub mod session;
 pub mod settings;
+pub mod startup;
+pub mod storage;
+pub mod updates;
 pub mod usage;
+pub mod vertex;
+pub mod webhooks;
+pub mod window;
 
 pub mod ssh;
 pub mod cloudflare;
 
+pub use agent_bundle::*;
+pub use agent_endpoints::*;
 pub use agents::*;
 pub use amp::*;
 pub use api_keys::*;
+pub use audit::*;
 pub use auth::*;
 pub use auth_files::*;
+pub use benchmark::*;
+pub use captures::*;
+pub use cliproxy_config::*;
+pub use control::*;
 pub use copilot::*;
+pub use credentials::*;
+pub use diagnostics::*;
+pub use failover::*;
 pub use health::*;
 pub use logs::*;
+pub use model_caps::*;
+pub use model_catalog::*;
 pub use models::*;
+pub use network::*;
+pub use onboarding::*;
+pub use permissions::*;
 pub use proxy::*;
+pub use proxy_keys::*;
 pub use quota::*;
+pub use rate_limits::*;
+pub use request_watches::*;
+pub use schedule::*;
+pub use self_test::*;
+pub use session::*;
 pub use settings::*;
+pub use startup::*;
+pub use storage::*;
+pub use updates::*;
 pub use usage::*;
+pub use vertex::*;
+pub use webhooks::*;
+pub use window::*;
 pub use ssh::*;
 #[allow(unused_imports)]
 pub use cloudflare::*;
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// Daily request/token allowance for the models matching `model_pattern`
+/// (an exact id, or a prefix ending in `*`). Limits of 0 or None don't apply.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCap {
+    pub id: String,
+    pub model_pattern: String,
+    #[serde(default)]
+    pub max_requests_per_day: Option<u64>,
+    #[serde(default)]
+    pub max_tokens_per_day: Option<u64>,
+    // Model Amp requests are redirected to once the cap is reached (other
+    // clients keep the capped model); without one the cap is only reported
+    #[serde(default)]
+    pub fallback_model: Option<String>,
+    #[serde(default = "default_cap_enabled")]
+    pub enabled: bool,
+}
+
+fn default_cap_enabled() -> bool {
+    true
+}
+
+/// Today's consumption against one cap; also the `model-cap-reached` payload
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCapStatus {
+    pub cap_id: String,
+    pub model_pattern: String,
+    pub day: String, // Usage day the counts belong to, "YYYY-MM-DD"
+    pub requests_today: u64,
+    pub tokens_today: u64,
+    pub max_requests_per_day: Option<u64>,
+    pub max_tokens_per_day: Option<u64>,
+    pub fallback_model: Option<String>,
+    pub reached: bool,
+    pub reached_at: Option<u64>,
+}
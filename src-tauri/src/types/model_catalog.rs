@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use super::AvailableModel;
+
+/// Rough price band of a model, cheapest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostTier {
+    Low,     // Under $1 per million input tokens
+    Medium,  // $1–3
+    High,    // $3–10
+    Premium, // Above $10
+}
+
+/// What is known about the models matching `pattern` (an exact id, or a
+/// prefix ending in `*`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCatalogEntry {
+    pub pattern: String,
+    #[serde(default)]
+    pub context_window: Option<u32>, // Tokens
+    #[serde(default)]
+    pub supports_vision: bool,
+    #[serde(default)]
+    pub supports_tools: bool,
+    pub cost_tier: CostTier,
+}
+
+/// `model_catalog.json`, built in or in the config dir
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCatalog {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub models: Vec<ModelCatalogEntry>,
+}
+
+/// An available model with its catalog entry, if any pattern matches it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogModel {
+    #[serde(flatten)]
+    pub model: AvailableModel,
+    pub metadata: Option<ModelCatalogEntry>,
+}
@@ -10,6 +10,12 @@ pub struct AvailableModel {
     /// Used to distinguish between different authentication sources for the same provider
     #[serde(default)]
     pub source: String,
+    /// Provider classification from `detect_provider_from_model` ("claude", "openai", ...)
+    #[serde(default)]
+    pub provider: String,
+    /// True when the model was reported by the running proxy, false for the static fallback list
+    #[serde(default)]
+    pub connected: bool,
 }
 
 // Test connection to a custom OpenAI-compatible provider
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Which proxy ProxyPal's own outbound requests go through
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkProxyMode {
+    /// HTTP(S)_PROXY / ALL_PROXY and NO_PROXY from the environment
+    #[default]
+    System,
+    /// Direct connections, whatever the environment says
+    None,
+    /// `NetworkProxy::url`
+    Manual,
+}
+
+/// App-level proxy for ProxyPal's own requests (update checks, sidecar
+/// downloads, quota and provider calls, webhooks). Not to be confused with
+/// `AppConfig.proxy_url`, the upstream proxy the sidecar uses for model
+/// traffic. Requests to the local sidecar never use a proxy.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkProxy {
+    #[serde(default)]
+    pub mode: NetworkProxyMode,
+    #[serde(default)]
+    pub url: String, // Manual mode: http://, https:// or socks5:// URL, credentials allowed
+    #[serde(default)]
+    pub no_proxy: Vec<String>, // Manual mode: hosts, domains (.corp.example) or CIDRs reached directly
+}
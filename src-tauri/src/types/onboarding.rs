@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+/// Proxy has been started at least once
+pub const STEP_PROXY_STARTED: &str = "proxy_started";
+/// At least one provider account is connected
+pub const STEP_PROVIDER_CONNECTED: &str = "provider_connected";
+/// At least one CLI agent points at the proxy
+pub const STEP_AGENT_CONFIGURED: &str = "agent_configured";
+/// A request has gone through the proxy
+pub const STEP_REQUEST_OBSERVED: &str = "request_observed";
+
+pub const ONBOARDING_STEPS: &[&str] = &[
+    STEP_PROXY_STARTED,
+    STEP_PROVIDER_CONNECTED,
+    STEP_AGENT_CONFIGURED,
+    STEP_REQUEST_OBSERVED,
+];
+
+/// First-run setup progress, persisted in config.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OnboardingState {
+    pub completed: bool,
+    pub completed_steps: Vec<String>,
+    pub skipped_steps: Vec<String>,
+}
+
+impl OnboardingState {
+    /// Record a step as done (or skipped). Returns true if anything changed.
+    pub fn mark(&mut self, step: &str, skipped: bool) -> bool {
+        if self.completed_steps.iter().any(|s| s == step) {
+            return false;
+        }
+        let before = self.clone();
+        if skipped {
+            if !self.skipped_steps.iter().any(|s| s == step) {
+                self.skipped_steps.push(step.to_string());
+            }
+        } else {
+            // Doing a step after skipping it counts as done
+            self.skipped_steps.retain(|s| s != step);
+            self.completed_steps.push(step.to_string());
+        }
+        self.completed = ONBOARDING_STEPS.iter().all(|step| {
+            self.completed_steps.iter().any(|s| s == step)
+                || self.skipped_steps.iter().any(|s| s == step)
+        });
+        *self != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_when_every_step_is_done_or_skipped() {
+        let mut state = OnboardingState::default();
+        assert!(state.mark(STEP_PROXY_STARTED, false));
+        assert!(!state.mark(STEP_PROXY_STARTED, false));
+        assert!(state.mark(STEP_PROVIDER_CONNECTED, false));
+        assert!(state.mark(STEP_AGENT_CONFIGURED, true));
+        assert!(!state.completed);
+        assert!(state.mark(STEP_REQUEST_OBSERVED, false));
+        assert!(state.completed);
+
+        // Doing a skipped step moves it to completed
+        assert!(state.mark(STEP_AGENT_CONFIGURED, false));
+        assert!(state.skipped_steps.is_empty());
+        assert_eq!(state.completed_steps.len(), 4);
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// A config or credential file (or directory) other users can access
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePermissionIssue {
+    pub path: String,
+    pub is_dir: bool,
+    /// What is too open: the octal mode on Unix ("644"), the broad groups in
+    /// the ACL on Windows ("Everyone")
+    pub mode: String,
+    /// Set once a repair tightened it
+    pub repaired: bool,
+}
+
+/// Result of `check_file_permissions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePermissionReport {
+    pub checked: usize,
+    pub issues: Vec<FilePermissionIssue>,
+}
@@ -1,18 +1,76 @@
 use serde::{Deserialize, Serialize};
 
+/// Where the CLIProxyAPI binary the proxy runs came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinarySource {
+    /// Shipped with the app
+    #[default]
+    Bundled,
+    /// Found on PATH (e.g. installed by a package manager)
+    System,
+    /// Installed by `update_sidecar`
+    Custom,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyStatus {
     pub running: bool,
     pub port: u16,
+    /// Base URL of the running proxy, set once it answered the readiness
+    /// check; empty while stopped or still starting
     pub endpoint: String,
+    /// Where the proxy answered before it last stopped, for display only
+    #[serde(rename = "lastKnownEndpoint", default)]
+    pub last_known_endpoint: Option<String>,
+    #[serde(rename = "binarySource", default)]
+    pub binary_source: BinarySource,
+    /// Set when the binary in use may be too old for ProxyPal
+    #[serde(rename = "binaryWarning", default)]
+    pub binary_warning: Option<String>,
+    /// CLIProxyAPI version of the running binary, when it reported one
+    #[serde(rename = "sidecarVersion", default)]
+    pub sidecar_version: Option<String>,
+    /// Waiting for in-flight requests before a pause stops the proxy
+    #[serde(default)]
+    pub draining: bool,
+    /// Stopped by `pause_proxy`; `resume_proxy` starts it again
+    #[serde(default)]
+    pub paused: bool,
 }
 
 impl Default for ProxyStatus {
     fn default() -> Self {
         Self {
             running: false,
-            port: 8317,
-            endpoint: "http://localhost:8317/v1".to_string(),
+            port: crate::config::DEFAULT_PORT,
+            endpoint: String::new(),
+            last_known_endpoint: None,
+            binary_source: BinarySource::Bundled,
+            binary_warning: None,
+            sidecar_version: None,
+            draining: false,
+            paused: false,
+        }
+    }
+}
+
+impl ProxyStatus {
+    /// Record the proxy as down; its endpoint is only kept as last known
+    pub fn set_stopped(&mut self) {
+        self.running = false;
+        if !self.endpoint.is_empty() {
+            self.last_known_endpoint = Some(std::mem::take(&mut self.endpoint));
         }
     }
 }
+
+/// `idle-warning` payload: the proxy runs and agents are set up for it, but
+/// no request has come through for a while
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleWarning {
+    pub idle_minutes: u64,
+    pub configured_agents: Vec<String>, // Names of the agents reporting configured
+    pub timestamp: u64,                 // Unix millis
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// A proxy API key replaced by rotation, still accepted until `expires_at`
+/// so running sessions keep working
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetiredProxyKey {
+    pub key: String,
+    /// Unix millis
+    pub expires_at: u64,
+}
+
+/// What happened to one agent, tool or file during a key rotation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotationTarget {
+    pub id: String,
+    pub name: String,
+    /// "updated", "manual" (the user has to change it) or "failed"
+    pub status: String,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+/// Result of `rotate_proxy_api_key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotationReport {
+    pub api_key: String,
+    /// When the previous key stops working (Unix millis); None when it was
+    /// revoked immediately
+    #[serde(default)]
+    pub previous_key_expires_at: Option<u64>,
+    /// "hot" (Management API), "restarted", "notRunning" or "failed"
+    pub proxy: String,
+    pub targets: Vec<KeyRotationTarget>,
+}
+
+/// An extra proxy API key issued to one agent or machine, so it can be
+/// revoked without rotating the main key
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientKey {
+    pub id: String,
+    pub name: String,
+    pub key: String,
+    /// Agent whose config uses this key instead of the main one
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Unix millis
+    pub created_at: u64,
+    /// Last request made with the key (Unix millis)
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+    /// Requests made with the key across proxy restarts
+    #[serde(default)]
+    pub request_count: u64,
+    /// The proxy's own counter at the last sync; it restarts from 0 with the proxy
+    #[serde(default)]
+    pub synced_requests: u64,
+}
+
+/// A client key as listed in the UI, without the full secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientKeyInfo {
+    pub id: String,
+    pub name: String,
+    /// First and last characters of the key
+    pub key_preview: String,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    pub created_at: u64,
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+    pub request_count: u64,
+}
+
+/// Result of `revoke_client_key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientKeyRevocation {
+    pub id: String,
+    /// "hot" (Management API), "restarted", "notRunning" or "failed"
+    pub proxy: String,
+    /// Agents whose config still holds the revoked key, now flagged stale
+    pub stale_agents: Vec<String>,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Soft request rate for one provider. CLIProxyAPI has no setting to
+/// throttle by, so going over it is only reported.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRateLimit {
+    pub provider: String,
+    pub requests_per_minute: f64,
+}
+
+/// `rate-soft-limit-exceeded` payload
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateSoftLimitExceeded {
+    pub provider: String,
+    pub requests_per_minute: f64, // The configured limit
+    pub measured_per_minute: f64, // Requests to the provider in the last minute
+    pub timestamp: u64,           // Unix millis of the request that went over
+}
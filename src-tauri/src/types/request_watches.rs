@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use super::RequestLog;
+
+/// Which requests a watch matches. Unset criteria match every request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestWatchFilter {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model_pattern: Option<String>, // Exact model id, or a prefix ending in *
+    #[serde(default)]
+    pub min_cost_usd: Option<f64>, // Estimated cost of the request
+    #[serde(default)]
+    pub status_class: Option<String>, // "2xx", "3xx", "4xx" or "5xx"
+}
+
+/// A filter over live traffic; matching requests are reported as they arrive
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestWatch {
+    pub id: String,
+    pub filter: RequestWatchFilter,
+    #[serde(default)]
+    pub notify: bool, // Also show a desktop notification for hits
+    #[serde(default)]
+    pub created_at: u64, // Unix millis
+}
+
+/// `request-watch-hit` payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestWatchHit {
+    pub watch_id: String,
+    pub cost_usd: f64,
+    pub request: RequestLog,
+}
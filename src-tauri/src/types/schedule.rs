@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// A weekly period the proxy should be running, in local time.
+/// `start` and `end` are "HH:MM"; `end` may be "24:00". Windows can't cross
+/// midnight, so an overnight period is two windows.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleWindow {
+    pub day: String, // "mon" … "sun"
+    pub start: String,
+    pub end: String,
+}
+
+/// Weekly timetable for the proxy: running inside a window, stopped outside
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySchedule {
+    #[serde(default = "default_schedule_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub windows: Vec<ScheduleWindow>,
+}
+
+fn default_schedule_enabled() -> bool {
+    true
+}
+
+/// Where the schedule stands right now
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleStatus {
+    pub enabled: bool,
+    pub in_window: bool,
+    // A manual start/stop wins until `next_boundary`
+    pub overridden: bool,
+    pub next_boundary: Option<u64>, // ms timestamp of the next window start or end
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of one self-test step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelfTestStatus {
+    Pass,
+    Fail,
+    /// Not run: an earlier step it depends on did not pass, or nothing to check
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestStep {
+    pub id: String, // e.g. "sidecar", "port", "completion"
+    pub label: String,
+    pub status: SelfTestStatus,
+    pub detail: String,
+    pub hint: Option<String>, // What to do about a failed or skipped step
+    pub duration_ms: u64,
+}
+
+/// Result of `run_self_test`, steps in the order they ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    pub steps: Vec<SelfTestStep>,
+    pub passed: bool, // No step failed
+    pub cancelled: bool, // Stopped by `cancel_self_test`; later steps are missing
+}
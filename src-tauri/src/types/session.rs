@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+use super::ProxyErrorEntry;
+
+/// Something that happened to the proxy, or to ProxyPal's files, during a
+/// session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionEventKind {
+    ProxyStart,
+    ProxyStop,
+    ProxyCrash,
+    DataRecovered,
+    DataLost,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEvent {
+    pub timestamp: u64, // Unix millis
+    pub kind: SessionEventKind,
+    #[serde(default)]
+    pub detail: Option<String>, // Restart or crash reason, or the damaged file
+}
+
+/// `session.json` in the config dir, rewritten as the session goes. It stays
+/// `clean_shutdown: false` until ProxyPal exits normally.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionJournal {
+    pub started_at: u64, // Unix millis
+    pub updated_at: u64, // Unix millis, last write
+    #[serde(default)]
+    pub clean_shutdown: bool,
+    #[serde(default)]
+    pub app_version: String,
+    #[serde(default)]
+    pub events: Vec<SessionEvent>, // Latest last, bounded
+    #[serde(default)]
+    pub last_errors: Vec<ProxyErrorEntry>, // Latest last, bounded
+    #[serde(default)]
+    pub requests: u64,
+    #[serde(default)]
+    pub failures: u64,
+}
+
+/// What the previous run of ProxyPal left in its journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastSessionSummary {
+    pub started_at: u64, // Unix millis
+    pub ended_at: u64,   // Unix millis; for an unclean shutdown, the last write
+    pub uptime_secs: u64,
+    pub clean_shutdown: bool,
+    pub crash_reason: Option<String>, // Unclean shutdowns only
+    pub app_version: String,
+    pub proxy_starts: u32,
+    pub proxy_crashes: u32,
+    pub requests: u64,
+    pub failures: u64,
+    pub last_errors: Vec<ProxyErrorEntry>, // Latest first
+}
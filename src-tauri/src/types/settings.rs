@@ -42,3 +42,32 @@ impl Default for ReasoningEffortSettings {
         }
     }
 }
+
+/// Per-category toggles for desktop notifications
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    pub proxy_crash: bool,
+    pub auth_expired: bool,
+    pub budget_threshold: bool,
+    pub rate_limit: bool,
+    pub sidecar_update: bool,
+    pub provider_health: bool,
+    pub idle_warning: bool,
+    pub usage_anomaly: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            proxy_crash: true,
+            auth_expired: true,
+            budget_threshold: true,
+            rate_limit: true,
+            sidecar_update: true,
+            provider_health: true,
+            idle_warning: true,
+            usage_anomaly: true,
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+use super::AuthStatus;
+use crate::config::AppConfig;
+
+/// Settings loaded by the startup task, sent with `state-hydrated`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupState {
+    pub config: AppConfig,
+    pub auth_status: AuthStatus,
+}
@@ -0,0 +1,48 @@
+use serde::Serialize;
+
+/// Payload of `storage-degraded`: files are kept outside the config directory
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDegraded {
+    pub reason: String,
+    pub path: String, // Directory files are written to instead
+}
+
+/// Payload of `data-recovered` and `data-lost`: a file that no longer parsed
+/// was moved aside, and what could be read from it was kept or nothing was
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataRecovery {
+    pub file: String,                   // "config.json" | "auth.json" | "history.json"
+    pub path: String,                   // Where the file was
+    pub quarantined_to: Option<String>, // The damaged file, kept for inspection
+    pub recovered: bool,
+    pub detail: String, // What was kept, or why nothing could be
+    pub timestamp: u64, // Unix millis
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoragePath {
+    pub name: String, // "config" | "auth" | "history" | "aggregate" | "logs" | "captures" | "benchmarks"
+    pub path: String,
+    pub exists: bool,
+    pub size_bytes: u64, // Whole tree for folders
+}
+
+/// Where ProxyPal stores its files, as shown in settings
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageInfo {
+    pub config_dir: String,              // Directory in use
+    pub preferred_dir: String,           // The usual config directory
+    pub degraded_reason: Option<String>, // Why config_dir is not preferred_dir
+    pub portable: bool,                  // Data kept beside the executable
+    pub writable: bool,
+    pub free_bytes: Option<u64>, // Free space on the volume, if known
+    pub config_bytes: u64,       // Everything in config_dir but the data entries
+    pub data_dir: String,        // History, logs, captures, benchmarks; config_dir unless moved
+    pub data_free_bytes: Option<u64>,
+    pub data_bytes: u64,
+    pub paths: Vec<StoragePath>,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// Result of checking for a newer ProxyPal release
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUpdateInfo {
+    pub current: String,
+    pub latest: String,
+    pub available: bool,
+    pub notes: Option<String>, // Release notes (markdown), when the release has any
+    pub url: String,           // Release page, for installs the updater can't replace
+    pub dismissed: bool,       // `latest` is the version the user chose to skip
+    pub can_install: bool,     // The updater can download and install it in place
+    pub checked_at: i64,       // Unix seconds
+}
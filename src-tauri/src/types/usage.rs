@@ -11,9 +11,38 @@ pub struct RequestLog {
     pub path: String,
     pub status: u16,
     pub duration_ms: u64,
+    #[serde(default)]
+    pub ttfb_ms: Option<u64>, // Time to the first streamed chunk; None unless the log shows it
     pub tokens_in: Option<u32>,
     pub tokens_out: Option<u32>,
     pub tokens_cached: Option<u32>,
+    #[serde(default)]
+    pub account: Option<String>, // Email or credential the proxy routed with; None when the log doesn't say
+    #[serde(default)]
+    pub retries: u8, // Extra attempts the proxy made before the final status
+    #[serde(default)]
+    pub attempts: Vec<AttemptInfo>, // Every attempt, in order; empty unless the proxy retried
+    #[serde(default)]
+    pub synthetic: bool, // Generated by demo mode; kept out of aggregates, caps and health
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>, // Id of the first request of its session, see `request_tags`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>, // Set for cost review; counted per tag in the cost ledger
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<String>, // Calling tool: its client key's name, else from its User-Agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_version: Option<String>, // ProxyPal version that recorded the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sidecar_version: Option<String>, // CLIProxyAPI version that served it, from the binary at proxy start
+}
+
+/// One upstream attempt of a retried request
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttemptInfo {
+    pub provider: Option<String>,
+    pub account: Option<String>, // Email or masked key the proxy used, when the log names it
+    pub status: Option<u16>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -22,6 +51,8 @@ pub struct UsageStats {
     pub total_requests: u64,
     pub success_count: u64,
     pub failure_count: u64,
+    #[serde(default)]
+    pub retried_success_count: u64, // Successes that needed more than one attempt
     pub total_tokens: u64,
     pub input_tokens: u64,
     pub output_tokens: u64,
@@ -40,6 +71,8 @@ pub struct UsageStats {
     pub requests_by_hour: Vec<TimeSeriesPoint>,
     #[serde(default)]
     pub tokens_by_hour: Vec<TimeSeriesPoint>,
+    #[serde(default)]
+    pub granularity: SeriesGranularity, // How far back the stored series go per hour and per day
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +82,16 @@ pub struct TimeSeriesPoint {
     pub value: u64,
 }
 
+/// Resolution of the aggregate's stored series after compaction. Hourly
+/// points cover the last `hourly_days` days; day-keyed points up to
+/// `monthly_until` are whole months, labeled "YYYY-MM" instead of a day.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesGranularity {
+    pub hourly_days: u32,
+    pub monthly_until: Option<String>, // Latest month kept as one point; None while every day is its own
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelUsage {
@@ -71,6 +114,96 @@ pub struct ProviderUsage {
     pub tokens: u64,
 }
 
+/// Latency percentiles of one provider/model's successful requests
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyStats {
+    pub provider: String,
+    pub model: String,
+    pub requests: u64,
+    pub duration_p50_ms: u64,
+    pub duration_p90_ms: u64,
+    pub ttfb_samples: u64,        // Requests whose log showed the first chunk
+    pub ttfb_p50_ms: Option<u64>, // None without TTFB samples
+    pub ttfb_p90_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>, // Set when grouped by version; "unknown" for unstamped requests
+}
+
+/// Which version a history query groups or filters by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionDimension {
+    Sidecar,
+    App,
+}
+
+/// Error rate and latency of the requests served under one version
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionStats {
+    pub version: String, // "unknown" for requests recorded before versions were stamped
+    pub requests: u64,
+    pub failure_count: u64,
+    pub error_rate: f64,      // failure_count / requests
+    pub duration_p50_ms: u64, // Successful requests only
+    pub duration_p90_ms: u64,
+    pub ttfb_p50_ms: Option<u64>,
+    pub first_seen: u64,
+    pub last_seen: u64,
+}
+
+/// Requests before and after a version change inside a reporting period
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionChange {
+    pub dimension: VersionDimension,
+    pub changed_at: u64, // Timestamp of the first request under the new version
+    pub before: VersionStats,
+    pub after: VersionStats,
+}
+
+/// Versions that served requests on one usage day
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DayVersions {
+    #[serde(default)]
+    pub app: Vec<String>,
+    #[serde(default)]
+    pub sidecar: Vec<String>,
+}
+
+/// Requests in history served by one account. `account` is None for the
+/// bucket of requests the proxy log did not attribute.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountUsage {
+    pub account: Option<String>,
+    pub label: Option<String>, // Label or email of the matching credential file
+    pub providers: Vec<String>,
+    pub requests: u64,
+    pub success_count: u64,
+    pub tokens_in: u64,
+    pub tokens_out: u64,
+    pub estimated_cost: f64,
+    pub last_used: u64,
+}
+
+/// Requests in history sent by one tool. `client` is None for the bucket
+/// of requests the proxy log did not identify.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientUsage {
+    pub client: Option<String>,
+    pub providers: Vec<String>,
+    pub requests: u64,
+    pub success_count: u64,
+    pub tokens_in: u64,
+    pub tokens_out: u64,
+    pub estimated_cost: f64,
+    pub last_used: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelStats {
@@ -92,6 +225,8 @@ pub struct Aggregate {
     pub total_requests: u64,
     pub total_success_count: u64,
     pub total_failure_count: u64,
+    #[serde(default)]
+    pub total_retried_success_count: u64,
     pub total_tokens_in: u64,
     pub total_tokens_out: u64,
     #[serde(default)]
@@ -109,6 +244,15 @@ pub struct Aggregate {
     pub model_stats: std::collections::HashMap<String, ModelStats>,
     #[serde(default)]
     pub provider_stats: std::collections::HashMap<String, ModelStats>,
+    // Oldest/newest timestamp (ms) imported from standalone CLIProxyAPI usage files
+    #[serde(default)]
+    pub cliproxy_imported_range: Option<(u64, u64)>,
+    #[serde(default)]
+    pub cost_ledger: CostLedger, // Source of total_cost_usd
+    #[serde(default)]
+    pub session_tags: std::collections::HashMap<String, Vec<String>>, // Given to requests joining a tagged session
+    #[serde(default)]
+    pub versions_by_day: std::collections::HashMap<String, DayVersions>, // Keyed by usage day, like requests_by_day
 }
 
 impl Default for Aggregate {
@@ -121,6 +265,7 @@ impl Default for Aggregate {
             total_requests: 0,
             total_success_count: 0,
             total_failure_count: 0,
+            total_retried_success_count: 0,
             total_tokens_in: 0,
             total_tokens_out: 0,
             total_tokens_cached: 0,
@@ -131,24 +276,157 @@ impl Default for Aggregate {
             tokens_by_hour: vec![],
             model_stats: std::collections::HashMap::new(),
             provider_stats: std::collections::HashMap::new(),
+            cliproxy_imported_range: None,
+            cost_ledger: CostLedger::default(),
+            session_tags: std::collections::HashMap::new(),
+            versions_by_day: std::collections::HashMap::new(),
         }
     }
 }
 
+/// Estimated spend of one provider/model on one usage day. Costs are whole
+/// nano-dollars so totals over any range add up exactly.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CostLedgerEntry {
+    pub day: String, // "YYYY-MM-DD" in the usage time zone
+    pub provider: String,
+    pub model: String,
+    pub requests: u64,
+    pub tokens_in: u64,
+    pub tokens_out: u64,
+    pub cost_nano_usd: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CostLedger {
+    pub entries: Vec<CostLedgerEntry>, // Ordered by day, provider, model
+    #[serde(default)]
+    pub untracked_nano_usd: u64, // Spend counted before the ledger existed, not tied to a day
+    #[serde(default)]
+    pub tags: Vec<CostLedgerTagEntry>, // Ordered by day, tag
+    #[serde(default)]
+    pub hours: Vec<CostLedgerHour>, // Ordered by hour; recent days only, for the usage baseline
+}
+
+/// Estimated spend in one usage hour, whatever the provider or model
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CostLedgerHour {
+    pub hour: String, // "YYYY-MM-DDTHH" in the usage time zone
+    pub cost_nano_usd: u64,
+}
+
+/// Estimated spend of the requests carrying one tag on one usage day. Kept
+/// apart from `entries`: a request with two tags counts towards both.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CostLedgerTagEntry {
+    pub day: String,
+    pub tag: String,
+    pub requests: u64,
+    pub tokens_in: u64,
+    pub tokens_out: u64,
+    pub cost_nano_usd: u64,
+}
+
+/// Today's spend against what the previous days had spent by the same hour.
+/// Returned by `get_anomaly_status` and sent as `usage-anomaly`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageAnomalyStatus {
+    pub day: String,
+    pub spent_today_usd: f64,
+    pub baseline_usd: Option<f64>, // Median spend by this hour; None until enough days are known
+    pub deviation_usd: Option<f64>, // Median absolute deviation of the same
+    pub baseline_days: u32,
+    pub multiplier: Option<f64>, // Today's spend over the baseline
+    pub threshold: f64,          // `AppConfig.usage_anomaly_multiplier`; 0 = off
+    pub anomalous: bool,
+    pub alerted_multiplier: Option<f64>, // Last alert of the day, if any
+    pub top_model: Option<String>,       // Costliest model today
+    pub top_model_usd: f64,
+    pub top_session: Option<String>, // Costliest session among today's retained requests
+    pub top_session_usd: f64,
+    pub timestamp: u64, // Unix millis
+}
+
+/// One group of `get_cost_ledger`, e.g. a day or a model
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CostLedgerRow {
+    pub key: String,
+    pub requests: u64,
+    pub tokens_in: u64,
+    pub tokens_out: u64,
+    pub cost_usd: f64,
+    pub cost: f64, // In the display currency
+    pub formatted: String, // e.g. "€1.84"
+}
+
+/// Spend over a range of usage days in the display currency
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CostLedgerView {
+    pub range: String,
+    pub group_by: String,
+    pub from: Option<String>, // First day included; None for "all"
+    pub to: String, // Today
+    pub currency: String,
+    pub rate: f64, // Display currency units per US dollar
+    pub total_usd: f64,
+    pub total: f64,
+    pub formatted_total: String,
+    pub rows: Vec<CostLedgerRow>,
+    #[serde(default)]
+    pub granularity: SeriesGranularity, // Day rows up to monthly_until are whole months
+}
+
+/// Outcome of `import_cliproxy_usage`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CliproxyImportReport {
+    pub imported: u64,
+    pub skipped: u64,
+    // Malformed entries that were left out, e.g. "gpt-5 details[3]: missing timestamp"
+    pub warnings: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct RequestHistory {
     pub requests: Vec<RequestLog>,
+    // Totals and series below come from the aggregate; history.json only
+    // stores `requests` (older files still carry their own totals)
+    #[serde(default)]
     pub total_tokens_in: u64,
+    #[serde(default)]
     pub total_tokens_out: u64,
+    #[serde(default)]
     pub total_tokens_cached: u64,
+    #[serde(default)]
     pub total_cost_usd: f64,
     #[serde(default)]
     pub tokens_by_day: Vec<TimeSeriesPoint>,
     #[serde(default)]
     pub tokens_by_hour: Vec<TimeSeriesPoint>,
     #[serde(default)]
-    pub total_request_count: u64,  // Actual total requests (not capped at 500)
+    pub total_request_count: u64, // Actual total requests (not capped by retention)
     #[serde(default)]
     pub total_success_count: u64,  // Successful requests (status < 400) across all history
+    #[serde(default)]
+    pub quota_events: Vec<QuotaSwitchEvent>, // Quota-exceeded switches seen in the proxy log, oldest first
+}
+
+/// CLIProxyAPI moved to another project or model after hitting a quota
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaSwitchEvent {
+    pub timestamp: u64,
+    pub provider: String,
+    pub account: Option<String>,
+    pub kind: String, // "project" or "model"
+    pub from: Option<String>,
+    pub to: String,
 }
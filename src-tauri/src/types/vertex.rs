@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Whether one publisher model can be used in a region
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexModelAvailability {
+    pub publisher: String, // "google" or "anthropic"
+    pub model: String,
+    pub available: bool,
+    pub detail: Option<String>, // Why a probed model is unavailable
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexRegionCapabilities {
+    pub region: String,
+    pub models: Vec<VertexModelAvailability>,
+    pub error: Option<String>,
+}
+
+/// Regions and models a Vertex service account can reach
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexCapabilities {
+    pub project_id: String,
+    pub client_email: String,
+    pub method: String, // "list", or "probe" when the account may not list
+    pub list_error: Option<String>, // Why listing fell back to probing
+    pub region: Option<String>, // Region the proxy uses for this credential
+    pub regions: Vec<VertexRegionCapabilities>,
+}
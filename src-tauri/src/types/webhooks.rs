@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// Events a webhook can subscribe to
+pub const WEBHOOK_EVENTS: &[&str] = &["proxy-crashed", "rate-limited", "request-failed"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<String>,
+    // Signs payloads with HMAC-SHA256 when set
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default = "default_webhook_enabled")]
+    pub enabled: bool,
+}
+
+fn default_webhook_enabled() -> bool {
+    true
+}
+
+/// Outcome of one webhook delivery (after retries)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event: String,
+    pub timestamp: u64,
+    pub success: bool,
+    pub attempts: u32,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
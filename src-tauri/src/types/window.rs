@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Saved position and size of a window, in physical pixels. `x`/`y` and the
+/// size are those of the window before it was maximized.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+}
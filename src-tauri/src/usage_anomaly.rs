@@ -0,0 +1,366 @@
+//! Alert for a day's spend far above the usual, such as an agent stuck in a
+//! loop.
+//!
+//! The cost ledger keeps each usage hour's spend for the last
+//! `cost_ledger::HOURLY_DAYS` days. The baseline is what each of the
+//! previous [`BASELINE_DAYS`] days had spent by the current hour of the day:
+//! their median, and the median absolute deviation (MAD) as the usual
+//! spread. Days before the first recorded hour aren't counted, so a fresh
+//! install has no baseline rather than a zero one. After each batch of new
+//! requests the log watcher hands the ledger to [`check`]; when today's
+//! running total reaches `AppConfig.usage_anomaly_multiplier` times the
+//! median and lies outside the spread, `usage-anomaly` is emitted along with
+//! a notification naming the costliest model and session. Later batches of
+//! the same day alert again only once the multiplier has doubled.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::helpers::cost_ledger;
+use crate::helpers::usage_day::{self, UsageTimezone};
+use crate::state::AppState;
+use crate::types::{CostLedger, CostLedgerHour, RequestLog, UsageAnomalyStatus};
+use crate::utils::estimate_request_cost;
+
+/// Previous days the baseline looks at
+const BASELINE_DAYS: i64 = 14;
+
+/// Days with a known spend needed before anything is compared
+const MIN_BASELINE_DAYS: u32 = 7;
+
+/// Spend below this never alerts, and a smaller median is taken as this
+/// much, so cents against fractions of a cent don't count as a spike
+const MIN_SPEND_USD: f64 = 0.5;
+
+/// How many deviations above the median today's spend must be
+const DEVIATIONS: f64 = 3.0;
+
+/// Alerts of the current usage day, kept in `AppState`
+#[derive(Default)]
+pub struct UsageAnomalyState {
+    day: String,
+    alerted: Option<f64>,
+}
+
+impl UsageAnomalyState {
+    // Whether `multiplier` is worth an alert on `day`: the first one of the
+    // day, or double the last one
+    fn should_alert(&mut self, day: &str, multiplier: f64) -> bool {
+        if self.day != day {
+            self.day = day.to_string();
+            self.alerted = None;
+        }
+        if self.alerted.is_some_and(|last| multiplier < last * 2.0) {
+            return false;
+        }
+        self.alerted = Some(multiplier);
+        true
+    }
+
+    fn alerted_on(&self, day: &str) -> Option<f64> {
+        self.alerted.filter(|_| self.day == day)
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Median and MAD of what the days before `today` had spent by `hour_of_day`
+/// ("HH"), and how many days that covers. None while fewer than
+/// [`MIN_BASELINE_DAYS`] days are known.
+fn baseline(
+    hours: &[CostLedgerHour],
+    today: NaiveDate,
+    hour_of_day: &str,
+) -> Option<(f64, f64, u32)> {
+    let first_day = hours.first()?.hour.get(..10)?.to_string();
+    let mut by_day = Vec::new();
+    for back in 1..=BASELINE_DAYS {
+        let day = (today - chrono::Duration::days(back))
+            .format("%Y-%m-%d")
+            .to_string();
+        if day < first_day {
+            break;
+        }
+        let nano: u64 = hours
+            .iter()
+            .filter(|h| h.hour.get(..10) == Some(day.as_str()))
+            .filter(|h| h.hour.get(11..).is_some_and(|hh| hh <= hour_of_day))
+            .map(|h| h.cost_nano_usd)
+            .sum();
+        by_day.push(cost_ledger::to_usd(nano));
+    }
+    if (by_day.len() as u32) < MIN_BASELINE_DAYS {
+        return None;
+    }
+    let middle = median(&mut by_day);
+    let mut deviations: Vec<f64> = by_day.iter().map(|v| (v - middle).abs()).collect();
+    Some((middle, median(&mut deviations), by_day.len() as u32))
+}
+
+/// Today's spend, costliest model and baseline as of `now`. The session is
+/// left for [`top_session`], which needs the history.
+fn evaluate(
+    ledger: &CostLedger,
+    threshold: f64,
+    timezone: UsageTimezone,
+    now: DateTime<Utc>,
+) -> UsageAnomalyStatus {
+    let day = usage_day::day_label(timezone, now);
+    let hour = usage_day::hour_label(timezone, now);
+    let by_model =
+        cost_ledger::group(ledger, Some(day.as_str()), &day, "model").unwrap_or_default();
+    let spent_today_usd: f64 = by_model.iter().map(|row| row.cost_usd).sum();
+
+    let today = NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok();
+    let known = today.and_then(|today| baseline(&ledger.hours, today, hour.get(11..)?));
+    let multiplier = known.map(|(median, _, _)| spent_today_usd / median.max(MIN_SPEND_USD));
+    let anomalous = match (known, multiplier) {
+        (Some((median, mad, _)), Some(multiplier)) => {
+            threshold > 0.0
+                && spent_today_usd >= MIN_SPEND_USD
+                && multiplier >= threshold
+                && spent_today_usd > median + DEVIATIONS * mad
+        }
+        _ => false,
+    };
+
+    let top = by_model.first();
+    UsageAnomalyStatus {
+        day,
+        spent_today_usd,
+        baseline_usd: known.map(|(median, _, _)| median),
+        deviation_usd: known.map(|(_, mad, _)| mad),
+        baseline_days: known.map_or(0, |(_, _, days)| days),
+        multiplier,
+        threshold,
+        anomalous,
+        alerted_multiplier: None,
+        top_model: top.map(|row| row.key.clone()),
+        top_model_usd: top.map_or(0.0, |row| row.cost_usd),
+        top_session: None,
+        top_session_usd: 0.0,
+        timestamp: now.timestamp_millis() as u64,
+    }
+}
+
+/// The session that cost the most on `status.day`, among the requests still
+/// in history
+fn top_session(status: &mut UsageAnomalyStatus, requests: &[RequestLog], timezone: UsageTimezone) {
+    let mut by_session: std::collections::HashMap<&str, f64> = std::collections::HashMap::new();
+    for req in requests.iter().filter(|r| !r.synthetic) {
+        let Some(session) = req.session_id.as_deref() else {
+            continue;
+        };
+        if usage_day::day_label_ms(timezone, req.timestamp).as_deref() != Some(status.day.as_str())
+        {
+            continue;
+        }
+        let (tokens_in, tokens_out) = (req.tokens_in.unwrap_or(0), req.tokens_out.unwrap_or(0));
+        *by_session.entry(session).or_default() +=
+            estimate_request_cost(&req.model, tokens_in, tokens_out);
+    }
+    if let Some((session, cost)) = by_session
+        .into_iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(a.0)))
+    {
+        status.top_session = Some(session.to_string());
+        status.top_session_usd = cost;
+    }
+}
+
+/// Current status for the dashboard
+pub(crate) fn status(state: &AppState) -> UsageAnomalyStatus {
+    let threshold = state.config.lock().usage_anomaly_multiplier;
+    let timezone = usage_day::current();
+    let agg = crate::helpers::history::load_aggregate();
+    let mut status = evaluate(&agg.cost_ledger, threshold, timezone, Utc::now());
+    state
+        .history
+        .read(|history| top_session(&mut status, &history.requests, timezone));
+    status.alerted_multiplier = state.usage_anomaly.lock().alerted_on(&status.day);
+    status
+}
+
+/// Compare today's spend with the baseline after a batch of new requests
+pub(crate) fn check(app: &AppHandle, ledger: &CostLedger) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let threshold = state.config.lock().usage_anomaly_multiplier;
+    if threshold <= 0.0 {
+        return;
+    }
+    let timezone = usage_day::current();
+    let mut status = evaluate(ledger, threshold, timezone, Utc::now());
+    let Some(multiplier) = status.multiplier.filter(|_| status.anomalous) else {
+        return;
+    };
+    if !state
+        .usage_anomaly
+        .lock()
+        .should_alert(&status.day, multiplier)
+    {
+        return;
+    }
+    status.alerted_multiplier = Some(multiplier);
+    state
+        .history
+        .read(|history| top_session(&mut status, &history.requests, timezone));
+
+    println!(
+        "[UsageAnomaly] Spent ${:.2} today, {:.1}x the usual ${:.2} by this hour",
+        status.spent_today_usd,
+        multiplier,
+        status.baseline_usd.unwrap_or_default()
+    );
+    let _ = app.emit("usage-anomaly", &status);
+    crate::notifications::notify_usage_anomaly(app, &status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(model: &str, tokens_in: u32) -> RequestLog {
+        RequestLog {
+            id: format!("req_{}_{}", model, tokens_in),
+            timestamp: 0,
+            provider: "claude".to_string(),
+            model: model.to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            status: 200,
+            duration_ms: 500,
+            ttfb_ms: None,
+            tokens_in: Some(tokens_in),
+            tokens_out: Some(0),
+            tokens_cached: None,
+            account: None,
+            retries: 0,
+            attempts: Vec::new(),
+            synthetic: false,
+            session_id: None,
+            tags: Vec::new(),
+            client: None,
+            app_version: None,
+            sidecar_version: None,
+        }
+    }
+
+    // Fourteen days of $3 (a million Sonnet input tokens) at 09:00 each,
+    // ending the day before `today`
+    fn usual_days(today: NaiveDate) -> CostLedger {
+        let mut ledger = CostLedger::default();
+        let req = request("claude-sonnet-4-5", 1_000_000);
+        for back in (1..=BASELINE_DAYS).rev() {
+            let day = (today - chrono::Duration::days(back)).format("%Y-%m-%d");
+            cost_ledger::record_request(&mut ledger, &day.to_string(), &req);
+            cost_ledger::record_hour(&mut ledger, &format!("{}T09", day), &req);
+        }
+        ledger
+    }
+
+    fn at(label: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(label)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn flags_spend_far_above_the_usual_by_this_hour() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 20).unwrap();
+        let mut ledger = usual_days(today);
+        let opus = request("claude-opus-4-1", 1_000_000);
+        for _ in 0..3 {
+            cost_ledger::record_request(&mut ledger, "2026-03-20", &opus);
+            cost_ledger::record_hour(&mut ledger, "2026-03-20T10", &opus);
+        }
+
+        let status = evaluate(&ledger, 4.0, UsageTimezone::Utc, at("2026-03-20T14:00:00Z"));
+        assert_eq!(status.baseline_days, 14);
+        assert_eq!(status.baseline_usd, Some(3.0));
+        assert_eq!(status.deviation_usd, Some(0.0));
+        assert_eq!(status.spent_today_usd, 45.0);
+        assert_eq!(status.multiplier, Some(15.0));
+        assert!(status.anomalous);
+        assert_eq!(status.top_model.as_deref(), Some("claude-opus-4-1"));
+
+        // $45 is 15x the usual, short of a 20x threshold
+        assert!(
+            !evaluate(
+                &ledger,
+                20.0,
+                UsageTimezone::Utc,
+                at("2026-03-20T14:00:00Z")
+            )
+            .anomalous
+        );
+        assert!(!evaluate(&ledger, 0.0, UsageTimezone::Utc, at("2026-03-20T14:00:00Z")).anomalous);
+
+        // Before 09:00 the usual days had spent nothing yet; the median is
+        // taken as the minimum spend instead
+        let early = evaluate(
+            &ledger,
+            20.0,
+            UsageTimezone::Utc,
+            at("2026-03-20T08:00:00Z"),
+        );
+        assert_eq!(early.baseline_usd, Some(0.0));
+        assert_eq!(early.multiplier, Some(45.0 / MIN_SPEND_USD));
+        assert!(early.anomalous);
+    }
+
+    #[test]
+    fn needs_a_week_of_history_before_comparing() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 20).unwrap();
+        let mut ledger = usual_days(today);
+        ledger.hours = ledger.hours.split_off(8);
+        let opus = request("claude-opus-4-1", 10_000_000);
+        cost_ledger::record_request(&mut ledger, "2026-03-20", &opus);
+
+        let status = evaluate(&ledger, 4.0, UsageTimezone::Utc, at("2026-03-20T14:00:00Z"));
+        assert_eq!(status.baseline_usd, None);
+        assert_eq!(status.multiplier, None);
+        assert!(!status.anomalous);
+    }
+
+    #[test]
+    fn alerts_again_only_when_the_multiplier_doubles() {
+        let mut state = UsageAnomalyState::default();
+        assert!(state.should_alert("2026-03-20", 4.2));
+        assert!(!state.should_alert("2026-03-20", 6.0));
+        assert!(state.should_alert("2026-03-20", 8.5));
+        assert_eq!(state.alerted_on("2026-03-20"), Some(8.5));
+        // A new day starts over
+        assert!(state.should_alert("2026-03-21", 4.0));
+        assert_eq!(state.alerted_on("2026-03-20"), None);
+    }
+
+    #[test]
+    fn names_the_costliest_session_of_the_day() {
+        let now = at("2026-03-20T14:00:00Z");
+        let mut status = evaluate(&CostLedger::default(), 4.0, UsageTimezone::Utc, now);
+        let mut requests: Vec<RequestLog> = (0..4)
+            .map(|i| request("claude-opus-4-1", 1000 * (i + 1)))
+            .collect();
+        let today_ms = now.timestamp_millis() as u64;
+        for (req, session) in requests.iter_mut().zip(["s_a", "s_b", "s_b", "s_old"]) {
+            req.timestamp = today_ms;
+            req.session_id = Some(session.to_string());
+        }
+        requests[3].timestamp = today_ms - 2 * 24 * 60 * 60 * 1000;
+        requests[3].tokens_in = Some(1_000_000);
+
+        top_session(&mut status, &requests, UsageTimezone::Utc);
+        assert_eq!(status.top_session.as_deref(), Some("s_b"));
+        assert!(status.top_session_usd > 0.0);
+    }
+}
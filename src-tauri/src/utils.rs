@@ -17,6 +17,10 @@ pub fn estimate_request_cost(model: &str, tokens_in: u32, tokens_out: u32) -> f6
         m if m.contains("gemini") && m.contains("flash") => (0.075, 0.30),
         m if m.contains("gemini-2") => (0.10, 0.40),
         m if m.contains("qwen") => (0.50, 2.0),
+        // DeepSeek and Zhipu GLM models
+        m if m.contains("deepseek") => (0.28, 0.42),
+        m if m.contains("glm") && m.contains("air") => (0.20, 1.10),
+        m if m.contains("glm") => (0.60, 2.20),
         _ => (1.0, 3.0),
     };
 
@@ -34,6 +38,10 @@ pub fn detect_provider_from_model(model: &str) -> String {
     if model_lower.contains("antigravity") {
         return "antigravity".to_string();
     }
+    // Claude served through Antigravity's Gemini backend, e.g. gemini-claude-sonnet-4-5
+    if model_lower.starts_with("gemini-claude-") {
+        return "antigravity".to_string();
+    }
     // Antigravity-exclusive Gemini model variants (no "-preview" suffix)
     if model_lower == "gemini-3-flash"
         || model_lower == "gemini-3-pro-high"
@@ -87,26 +95,19 @@ pub fn detect_provider_from_model(model: &str) -> String {
 /// Auth files follow the pattern `<provider-prefix>-<account>.json` (or `.json.disabled`).
 /// Returns the canonical provider string, or `"unknown"` if no prefix matches.
 ///
-/// This is the single source of truth for filename→provider mapping, used by both
-/// `auth_files.rs` (filesystem scan) and `auth.rs` (credential deletion).
+/// Prefixes come from the provider registry (`providers.rs`), plus a few
+/// credential files that don't belong to a connectable provider.
+/// Used by `auth_files.rs` (filesystem scan) and `auth.rs` (status scan).
 pub fn detect_provider_from_filename(name: &str) -> &'static str {
-    /// (prefix, provider) pairs — order doesn't matter since prefixes are disjoint.
-    const MAPPINGS: &[(&str, &str)] = &[
-        ("claude-", "claude"),
-        ("anthropic-", "claude"),
-        ("codex-", "openai"),
-        ("gemini-", "gemini"),
-        ("qwen-", "qwen"),
-        ("iflow-", "iflow"),
-        ("vertex-", "vertex"),
-        ("kiro-", "kiro"),
-        ("antigravity-", "antigravity"),
-        ("kimi-", "kimi"),
-        ("github-", "github"),
-        ("aws-", "AWS"),
-    ];
-
-    for &(prefix, provider) in MAPPINGS {
+    let registered = crate::providers::PROVIDERS.iter().find(|p| {
+        p.credential_file_prefixes
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+    });
+    if let Some(provider) = registered {
+        return provider.id;
+    }
+    for &(prefix, provider) in OTHER_CREDENTIAL_PREFIXES {
         if name.starts_with(prefix) {
             return provider;
         }
@@ -114,42 +115,49 @@ pub fn detect_provider_from_filename(name: &str) -> &'static str {
     "unknown"
 }
 
+/// (prefix, name) pairs for credential files of services outside the registry
+const OTHER_CREDENTIAL_PREFIXES: &[(&str, &str)] = &[("github-", "github"), ("aws-", "AWS")];
+
 /// Return the filename prefixes associated with a canonical provider name.
 ///
 /// Used by `auth.rs` to match credential files for deletion.
 /// Returns an empty slice for unknown providers.
 pub fn provider_filename_prefixes(provider: &str) -> &'static [&'static str] {
+    if let Some(meta) = crate::providers::get(provider) {
+        return meta.credential_file_prefixes;
+    }
     match provider {
-        "claude" => &["claude-", "anthropic-"],
-        "openai" => &["codex-"],
-        "gemini" => &["gemini-"],
-        "qwen" => &["qwen-"],
-        "iflow" => &["iflow-"],
-        "vertex" => &["vertex-"],
-        "kiro" => &["kiro-"],
-        "antigravity" => &["antigravity-"],
-        "kimi" => &["kimi-"],
         "github" => &["github-"],
         "AWS" => &["aws-"],
         _ => &[],
     }
 }
 
+/// Prefix of the routes Amp sends model requests through, followed by the
+/// upstream provider's name
+pub const AMP_PROVIDER_ROUTE: &str = "/api/provider/";
+
+/// Whether `path` is one of Amp's provider routes
+pub fn is_amp_provider_path(path: &str) -> bool {
+    path.contains(AMP_PROVIDER_ROUTE)
+}
+
 /// Extract provider from Amp-style API path
 /// e.g., "/api/provider/anthropic/v1/messages" -> "claude"
 pub fn detect_provider_from_path(path: &str) -> Option<String> {
     // First try Amp-style path
-    if path.contains("/api/provider/") {
-        let parts: Vec<&str> = path.split('/').collect();
-        if let Some(idx) = parts.iter().position(|&p| p == "provider") {
-            if let Some(provider) = parts.get(idx + 1) {
-                return Some(match *provider {
-                    "anthropic" => "claude".to_string(),
-                    "openai" => "openai".to_string(),
-                    "google" => "gemini".to_string(),
-                    p => p.to_string(),
-                });
-            }
+    if let Some((_, rest)) = path.split_once(AMP_PROVIDER_ROUTE) {
+        let provider = rest
+            .split(['/', '?'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        if !provider.is_empty() {
+            return Some(match provider.as_str() {
+                "anthropic" => "claude".to_string(),
+                "google" | "gemini" => "gemini".to_string(),
+                _ => provider,
+            });
         }
     }
 
@@ -194,6 +202,42 @@ pub fn extract_model_from_path(path: &str) -> Option<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn gemini_claude_models_belong_to_antigravity() {
+        assert_eq!(
+            detect_provider_from_model("gemini-claude-sonnet-4-5"),
+            "antigravity"
+        );
+        assert_eq!(
+            detect_provider_from_model("gemini-claude-opus-4-5-thinking"),
+            "antigravity"
+        );
+        assert_eq!(detect_provider_from_model("claude-sonnet-4-5"), "claude");
+        assert_eq!(detect_provider_from_model("gemini-2.5-pro"), "gemini");
+    }
+
+    #[test]
+    fn amp_routes_name_their_provider() {
+        let provider = |path| detect_provider_from_path(path);
+        assert_eq!(
+            provider("/api/provider/anthropic/v1/messages").as_deref(),
+            Some("claude")
+        );
+        assert_eq!(
+            provider("/api/provider/Google/v1beta/models/gemini-2.5-pro:generateContent")
+                .as_deref(),
+            Some("gemini")
+        );
+        assert_eq!(provider("/api/provider/xai?beta=1").as_deref(), Some("xai"));
+        // An Amp route without a provider falls back to the endpoint
+        assert_eq!(
+            provider("/api/provider//v1/chat/completions").as_deref(),
+            Some("openai-compat")
+        );
+        assert!(is_amp_provider_path("/api/provider/openai/v1/responses"));
+        assert!(!is_amp_provider_path("/api/threads"));
+    }
+
     #[test]
     fn detect_provider_from_filename_known_prefixes() {
         assert_eq!(detect_provider_from_filename("claude-user@example.json"), "claude");
@@ -0,0 +1,340 @@
+//! Webhook delivery for proxy lifecycle and request events.
+//!
+//! Events are queued on a small bounded channel and delivered by a background
+//! worker, so a slow or unreachable endpoint never blocks the log watcher or the
+//! sidecar handlers. When the queue is full the event is dropped and recorded
+//! as a failed delivery.
+//!
+//! `request-failed` fires on every failing request, so it is sent at most once
+//! per provider within its cooldown; a provider stuck on errors would otherwise
+//! send one POST per request.
+//!
+//! Payloads are JSON (`{event, timestamp, text, data}`, where `text` makes them
+//! usable as-is with Slack incoming webhooks). With a secret configured, the body
+//! is signed with HMAC-SHA256 in the `X-ProxyPal-Signature: sha256=<hex>` header.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{mpsc, Semaphore};
+
+use crate::state::AppState;
+use crate::types::{WebhookConfig, WebhookDelivery};
+
+const QUEUE_CAPACITY: usize = 64;
+const MAX_CONCURRENT_DELIVERIES: usize = 4;
+const MAX_ATTEMPTS: u32 = 3;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_DELIVERY_LOG: usize = 100;
+
+// Quiet period per provider for events sent on every failing request
+fn cooldown(event: &str) -> Option<Duration> {
+    match event {
+        "request-failed" => Some(Duration::from_secs(5 * 60)),
+        _ => None,
+    }
+}
+
+struct WebhookJob {
+    webhook: WebhookConfig,
+    event: String,
+    body: String,
+}
+
+/// Queue handle and recent delivery outcomes, kept in `AppState`
+#[derive(Default)]
+pub struct WebhookState {
+    sender: Option<mpsc::Sender<WebhookJob>>,
+    deliveries: VecDeque<WebhookDelivery>,
+    last_sent: HashMap<(String, String), Instant>, // (event, provider)
+}
+
+impl WebhookState {
+    fn should_send(&mut self, event: &str, provider: &str, now: Instant) -> bool {
+        let Some(cooldown) = cooldown(event) else {
+            return true;
+        };
+        let key = (event.to_string(), provider.to_string());
+        if let Some(last) = self.last_sent.get(&key) {
+            if now.duration_since(*last) < cooldown {
+                return false;
+            }
+        }
+        self.last_sent.insert(key, now);
+        true
+    }
+
+    fn record(&mut self, delivery: WebhookDelivery) {
+        if self.deliveries.len() >= MAX_DELIVERY_LOG {
+            self.deliveries.pop_front();
+        }
+        self.deliveries.push_back(delivery);
+    }
+
+    /// Most recent first
+    pub fn deliveries(&self) -> Vec<WebhookDelivery> {
+        self.deliveries.iter().rev().cloned().collect()
+    }
+}
+
+/// Start the delivery worker. Called once from setup.
+pub fn start_worker(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let (sender, mut receiver) = mpsc::channel::<WebhookJob>(QUEUE_CAPACITY);
+    state.webhooks.lock().sender = Some(sender);
+
+    let http = state.http.clone();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_DELIVERIES));
+        while let Some(job) = receiver.recv().await {
+            let Ok(permit) = permits.clone().acquire_owned().await else {
+                break;
+            };
+            let app = app.clone();
+            let http = http.clone();
+            tauri::async_runtime::spawn(async move {
+                let delivery = deliver(&http.external(), &job).await;
+                if let Some(state) = app.try_state::<AppState>() {
+                    state.webhooks.lock().record(delivery);
+                }
+                drop(permit);
+            });
+        }
+    });
+}
+
+fn payload(event: &str, text: &str, data: serde_json::Value) -> String {
+    let body = serde_json::json!({
+        "event": event,
+        "timestamp": chrono::Utc::now().timestamp_millis(),
+        "text": text,
+        "data": data,
+    })
+    .to_string();
+    // Masks only touch key-shaped text inside strings, so the JSON stays valid
+    crate::redact::redact(&body)
+}
+
+/// Queue `event` for every enabled webhook subscribed to it, unless the same
+/// event for the same provider (`data.provider`) is cooling down.
+pub fn emit(app: &AppHandle, event: &str, text: &str, data: serde_json::Value) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let subscribed: Vec<WebhookConfig> = state
+        .config
+        .lock()
+        .webhooks
+        .iter()
+        .filter(|w| w.enabled && w.events.iter().any(|e| e == event))
+        .cloned()
+        .collect();
+    if subscribed.is_empty() {
+        return;
+    }
+
+    let provider = data
+        .get("provider")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    let mut webhooks = state.webhooks.lock();
+    if !webhooks.should_send(event, provider, Instant::now()) {
+        return;
+    }
+    let Some(sender) = webhooks.sender.clone() else {
+        return;
+    };
+    let body = payload(event, text, data);
+    for webhook in subscribed {
+        let job = WebhookJob {
+            webhook,
+            event: event.to_string(),
+            body: body.clone(),
+        };
+        let (job, reason) = match sender.try_send(job) {
+            Ok(()) => continue,
+            Err(mpsc::error::TrySendError::Full(job)) => {
+                (job, "Delivery queue full, event dropped")
+            }
+            Err(mpsc::error::TrySendError::Closed(job)) => (job, "Delivery worker stopped"),
+        };
+        webhooks.record(failed_delivery(&job, 0, None, reason));
+    }
+}
+
+/// Send a test event to one webhook right away and return the outcome.
+pub async fn send_test(app: &AppHandle, webhook: WebhookConfig) -> WebhookDelivery {
+    let job = WebhookJob {
+        body: payload(
+            "test",
+            "Test webhook from ProxyPal",
+            serde_json::json!({ "webhookId": webhook.id }),
+        ),
+        event: "test".to_string(),
+        webhook,
+    };
+    let Some(state) = app.try_state::<AppState>() else {
+        return failed_delivery(&job, 0, None, "App state unavailable");
+    };
+    let delivery = deliver(&state.http.external(), &job).await;
+    state.webhooks.lock().record(delivery.clone());
+    delivery
+}
+
+fn signature(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn failed_delivery(
+    job: &WebhookJob,
+    attempts: u32,
+    status: Option<u16>,
+    error: &str,
+) -> WebhookDelivery {
+    WebhookDelivery {
+        id: uuid::Uuid::new_v4().to_string(),
+        webhook_id: job.webhook.id.clone(),
+        event: job.event.clone(),
+        timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        success: false,
+        attempts,
+        status,
+        error: Some(error.to_string()),
+    }
+}
+
+// POST with retries and exponential backoff (1s, 2s)
+async fn deliver(client: &reqwest::Client, job: &WebhookJob) -> WebhookDelivery {
+    let delivery_id = uuid::Uuid::new_v4().to_string();
+
+    let mut attempts = 0;
+    let mut last_status = None;
+    let mut last_error = String::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        attempts = attempt;
+        let mut request = client
+            .post(&job.webhook.url)
+            .header("Content-Type", "application/json")
+            .header("User-Agent", "ProxyPal-Webhook")
+            .header("X-ProxyPal-Event", &job.event)
+            .header("X-ProxyPal-Delivery", &delivery_id)
+            .timeout(DELIVERY_TIMEOUT)
+            .body(job.body.clone());
+        if let Some(secret) = job.webhook.secret.as_deref().filter(|s| !s.is_empty()) {
+            request = request.header(
+                "X-ProxyPal-Signature",
+                format!("sha256={}", signature(secret, &job.body)),
+            );
+        }
+
+        match request.send().await {
+            Ok(resp) if resp.status().is_success() => {
+                return WebhookDelivery {
+                    id: delivery_id,
+                    webhook_id: job.webhook.id.clone(),
+                    event: job.event.clone(),
+                    timestamp: chrono::Utc::now().timestamp_millis() as u64,
+                    success: true,
+                    attempts: attempt,
+                    status: Some(resp.status().as_u16()),
+                    error: None,
+                };
+            }
+            Ok(resp) => {
+                last_status = Some(resp.status().as_u16());
+                last_error = format!("HTTP {}", resp.status());
+                // Client errors won't succeed on retry (except rate limiting)
+                if resp.status().is_client_error() && resp.status().as_u16() != 429 {
+                    break;
+                }
+            }
+            Err(e) => {
+                last_status = None;
+                last_error = e.to_string();
+            }
+        }
+        if attempt < MAX_ATTEMPTS {
+            tokio::time::sleep(Duration::from_secs(1 << (attempt - 1))).await;
+        }
+    }
+
+    eprintln!(
+        "[ProxyPal] Webhook {} delivery of {} failed: {}",
+        job.webhook.id, job.event, last_error
+    );
+    let mut delivery = failed_delivery(job, attempts, last_status, &last_error);
+    delivery.id = delivery_id;
+    delivery
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_body_with_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            signature("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn failure_events_cool_down_per_provider() {
+        let mut state = WebhookState::default();
+        let now = Instant::now();
+        assert!(state.should_send("request-failed", "claude", now));
+        assert!(!state.should_send("request-failed", "claude", now));
+        assert!(state.should_send("request-failed", "gemini", now));
+        assert!(state.should_send("proxy-crashed", "claude", now));
+        assert!(state.should_send(
+            "request-failed",
+            "claude",
+            now + cooldown("request-failed").unwrap()
+        ));
+
+        // Lifecycle events are rare and always sent
+        assert!(state.should_send("proxy-crashed", "", now));
+        assert!(state.should_send("proxy-crashed", "", now));
+    }
+
+    #[test]
+    fn delivery_log_is_bounded_and_newest_first() {
+        let mut state = WebhookState::default();
+        let job = WebhookJob {
+            webhook: WebhookConfig {
+                id: "w1".to_string(),
+                url: "http://127.0.0.1:9".to_string(),
+                events: vec![],
+                secret: None,
+                enabled: true,
+            },
+            event: "proxy-crashed".to_string(),
+            body: String::new(),
+        };
+        for i in 0..(MAX_DELIVERY_LOG + 5) {
+            state.record(failed_delivery(&job, 1, None, &i.to_string()));
+        }
+        let deliveries = state.deliveries();
+        assert_eq!(deliveries.len(), MAX_DELIVERY_LOG);
+        assert_eq!(
+            deliveries[0].error.as_deref(),
+            Some((MAX_DELIVERY_LOG + 4).to_string().as_str())
+        );
+    }
+}
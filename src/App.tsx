@@ -1,5 +1,6 @@
 import { Match, onCleanup, onMount, Switch } from "solid-js";
 import { CommandPalette } from "./components/CommandPalette";
+import { PassphraseDialog } from "./components/PassphraseDialog";
 import { Sidebar } from "./components/Sidebar";
 import { ToastContainer } from "./components/ui";
 import { useI18n } from "./i18n";
@@ -88,6 +89,7 @@ function App() {
       )}
       <ToastContainer />
       <CommandPalette />
+      <PassphraseDialog />
     </>
   );
 }
@@ -1,13 +1,20 @@
-import { createEffect, createSignal, For, Show } from "solid-js";
+import { createEffect, createSignal, For, onCleanup, onMount, Show } from "solid-js";
 import { useI18n } from "../i18n";
 import {
   type AgentConfigResult,
+  type AgentEndpointAudit,
   type AgentStatus,
   type AvailableModel,
   appendToShellProfile,
+  auditAgentEndpoints,
   configureCliAgent,
   detectCliAgents,
+  type EnvConflict,
+  fixAgentEndpoints,
+  fixEnvironmentConflicts,
   getAvailableModels,
+  onAgentEndpointConflicts,
+  scanEnvironmentConflicts,
   testAgentConnection,
 } from "../lib/tauri";
 import { appStore } from "../stores/app";
@@ -307,6 +314,10 @@ export function AgentSetup() {
   const [loading, setLoading] = createSignal(true);
   const [configuring, setConfiguring] = createSignal<string | null>(null);
   const [testing, setTesting] = createSignal<string | null>(null);
+  const [endpointAudit, setEndpointAudit] = createSignal<AgentEndpointAudit | null>(null);
+  const [fixingEndpoints, setFixingEndpoints] = createSignal(false);
+  const [envConflicts, setEnvConflicts] = createSignal<EnvConflict[]>([]);
+  const [fixingEnv, setFixingEnv] = createSignal(false);
   const [configResult, setConfigResult] = createSignal<{
     agentName: string;
     result: AgentConfigResult;
@@ -317,6 +328,8 @@ export function AgentSetup() {
     try {
       const detected = await detectCliAgents();
       setAgents(detected);
+      setEndpointAudit(await auditAgentEndpoints());
+      setEnvConflicts(await scanEnvironmentConflicts());
     } catch (error) {
       console.error("Failed to detect agents:", error);
       toastStore.error(t("agentSetup.toasts.failedToDetectCliAgents"));
@@ -329,6 +342,11 @@ export function AgentSetup() {
     loadAgents();
   });
 
+  onMount(async () => {
+    const unlisten = await onAgentEndpointConflicts((audit) => setEndpointAudit(audit));
+    onCleanup(() => unlisten());
+  });
+
   const handleConfigure = async (agentId: string) => {
     // Agents that need models from the proxy (they configure with available model list)
     const agentsNeedingModels = ["factory-droid", "opencode"];
@@ -435,6 +453,53 @@ export function AgentSetup() {
     }
   };
 
+  const handleFixEndpoints = async () => {
+    setFixingEndpoints(true);
+    try {
+      const fixes = await fixAgentEndpoints();
+      const manual = fixes.filter((f) => f.status !== "updated");
+      if (manual.length > 0) {
+        toastStore.warning(
+          t("agentSetup.toasts.endpointsNeedManualFix"),
+          manual.map((f) => `${f.agentId}: ${f.detail ?? f.status}`).join("\n"),
+        );
+      } else {
+        toastStore.success(t("agentSetup.toasts.endpointsFixed"));
+      }
+      await loadAgents();
+    } catch (error) {
+      toastStore.error(t("agentSetup.toasts.failedToFixEndpoints"), String(error));
+    } finally {
+      setFixingEndpoints(false);
+    }
+  };
+
+  const handleFixEnv = async (outsideManaged: boolean) => {
+    setFixingEnv(true);
+    try {
+      const fixes = await fixEnvironmentConflicts(outsideManaged);
+      const manual = fixes.filter((f) => f.status !== "updated");
+      if (manual.length > 0) {
+        toastStore.warning(
+          t("agentSetup.toasts.envConflictsNeedManualFix"),
+          manual.map((f) => `${f.variable}: ${f.detail ?? f.status}`).join("\n"),
+        );
+      } else {
+        toastStore.success(t("agentSetup.toasts.envConflictsFixed"));
+      }
+      await loadAgents();
+    } catch (error) {
+      toastStore.error(t("agentSetup.toasts.failedToFixEnvConflicts"), String(error));
+    } finally {
+      setFixingEnv(false);
+    }
+  };
+
+  const hasUnmanagedEnvConflicts = () =>
+    envConflicts().some((c) => c.source !== "environment" && !c.managed);
+
+  const endpointOutliers = () => endpointAudit()?.outliers ?? [];
+
   const installedAgents = () => agents().filter((a) => a.installed);
   const notInstalledAgents = () => agents().filter((a) => !a.installed);
   const configuredCount = () => agents().filter((a) => a.configured).length;
@@ -490,6 +555,85 @@ export function AgentSetup() {
         </div>
       </Show>
 
+      <Show when={endpointOutliers().length > 0}>
+        <div class="flex items-center justify-between gap-3 rounded-lg border border-amber-200 bg-amber-50 p-3 dark:border-amber-800 dark:bg-amber-900/20">
+          <div class="text-amber-700 dark:text-amber-300">
+            <p class="text-sm font-medium">{t("agentSetup.endpointConflicts.title")}</p>
+            <p class="mt-0.5 text-xs">
+              {t("agentSetup.endpointConflicts.summary", {
+                count: endpointOutliers().length,
+                expected: endpointAudit()?.expected ?? "",
+              })}
+            </p>
+            <ul class="mt-1 space-y-0.5 text-xs">
+              <For each={endpointAudit()?.conflicts ?? []}>
+                {(conflict) => (
+                  <li class="font-mono">
+                    {conflict.agentId}: {conflict.found} ({conflict.source})
+                  </li>
+                )}
+              </For>
+            </ul>
+          </div>
+          <Button
+            disabled={fixingEndpoints()}
+            onClick={handleFixEndpoints}
+            size="sm"
+            variant="secondary"
+          >
+            {fixingEndpoints()
+              ? t("agentSetup.endpointConflicts.fixing")
+              : t("agentSetup.endpointConflicts.fix")}
+          </Button>
+        </div>
+      </Show>
+
+      <Show when={envConflicts().length > 0}>
+        <div class="rounded-lg border border-amber-200 bg-amber-50 p-3 dark:border-amber-800 dark:bg-amber-900/20">
+          <div class="flex items-start justify-between gap-3">
+            <div class="text-amber-700 dark:text-amber-300">
+              <p class="text-sm font-medium">{t("agentSetup.envConflicts.title")}</p>
+              <p class="mt-0.5 text-xs">
+                {t("agentSetup.envConflicts.summary", {
+                  expected: envConflicts()[0]?.expected ?? "",
+                })}
+              </p>
+            </div>
+            <Button
+              disabled={fixingEnv()}
+              onClick={() => handleFixEnv(false)}
+              size="sm"
+              variant="secondary"
+            >
+              {fixingEnv() ? t("agentSetup.envConflicts.fixing") : t("agentSetup.envConflicts.fix")}
+            </Button>
+          </div>
+          <ul class="mt-1 space-y-0.5 text-xs text-amber-700 dark:text-amber-300">
+            <For each={envConflicts()}>
+              {(conflict) => (
+                <li class="font-mono">
+                  {conflict.variable}={conflict.value} ({conflict.source}
+                  {conflict.line ? `:${conflict.line}` : ""})
+                  <Show when={conflict.source !== "environment" && !conflict.managed}>
+                    {" — "}
+                    {t("agentSetup.envConflicts.outsideManaged")}
+                  </Show>
+                </li>
+              )}
+            </For>
+          </ul>
+          <Show when={hasUnmanagedEnvConflicts()}>
+            <button
+              class="mt-2 text-xs text-amber-700 underline hover:text-amber-800 disabled:opacity-50 dark:text-amber-300"
+              disabled={fixingEnv()}
+              onClick={() => handleFixEnv(true)}
+            >
+              {t("agentSetup.envConflicts.fixAll")}
+            </button>
+          </Show>
+        </div>
+      </Show>
+
       <Show when={loading()}>
         <div class="flex items-center justify-center py-8">
           <svg class="h-6 w-6 animate-spin text-gray-400" fill="none" viewBox="0 0 24 24">
@@ -3,13 +3,18 @@ import { Button } from "./ui";
 
 interface ApiEndpointProps {
   endpoint: string;
+  lastKnownEndpoint?: string | null;
   running: boolean;
 }
 
 export function ApiEndpoint(props: ApiEndpointProps) {
   const [copied, setCopied] = createSignal(false);
 
+  // A stopped proxy has no endpoint; its last one is shown but not copied
   const copyToClipboard = async () => {
+    if (!props.endpoint) {
+      return;
+    }
     await navigator.clipboard.writeText(props.endpoint);
     setCopied(true);
     setTimeout(() => setCopied(false), 2000);
@@ -32,9 +37,18 @@ export function ApiEndpoint(props: ApiEndpointProps) {
 
       <div class="flex items-center gap-2">
         <code class="flex-1 rounded-lg border border-gray-200 bg-white px-3 py-2 font-mono text-sm text-gray-800 dark:border-gray-700 dark:bg-gray-900 dark:text-gray-200">
-          {props.endpoint}
+          {props.endpoint || (
+            <span class="text-gray-400 dark:text-gray-500">
+              {props.lastKnownEndpoint ? `${props.lastKnownEndpoint} (last known)` : "Not running"}
+            </span>
+          )}
         </code>
-        <Button onClick={copyToClipboard} size="sm" variant="secondary">
+        <Button
+          disabled={!props.endpoint}
+          onClick={copyToClipboard}
+          size="sm"
+          variant="secondary"
+        >
           {copied() ? (
             <svg
               class="h-4 w-4 text-green-600"
@@ -122,8 +122,14 @@ export function CommandPalette() {
     },
     {
       action: () => {
-        navigator.clipboard.writeText(proxyStatus().endpoint);
-        toastStore.success(t("common.copied"));
+        // Only a proxy that answered has an endpoint worth handing out
+        const endpoint = proxyStatus().endpoint;
+        if (endpoint) {
+          navigator.clipboard.writeText(endpoint);
+          toastStore.success(t("common.copied"));
+        } else {
+          toastStore.error(t("apiKeys.proxyNotRunning"));
+        }
         setIsOpen(false);
       },
       category: "proxy",
@@ -1,6 +1,7 @@
 import { createEffect, createSignal, onCleanup, Show } from "solid-js";
 import {
   checkProviderHealth,
+  onProviderHealthChanged,
   type HealthStatus,
   type Provider,
   type ProviderHealth,
@@ -8,6 +9,11 @@ import {
 import { appStore } from "../stores/app";
 
 const statusConfig = {
+  auth_expired: {
+    color: "bg-red-500",
+    label: "Sign-in expired",
+    textColor: "text-red-600 dark:text-red-400",
+  },
   degraded: {
     color: "bg-amber-500",
     label: "Degraded",
@@ -23,6 +29,11 @@ const statusConfig = {
     label: "Offline",
     textColor: "text-red-600 dark:text-red-400",
   },
+  rate_limited: {
+    color: "bg-orange-500",
+    label: "Rate limited",
+    textColor: "text-orange-600 dark:text-orange-400",
+  },
   unconfigured: {
     color: "bg-gray-400",
     label: "Not configured",
@@ -56,21 +67,13 @@ export function HealthIndicator(props: HealthIndicatorProps) {
     }
   };
 
-  // Check health on mount and when proxy status changes
-  // Throttle to once per 60 seconds to avoid spamming /v1/models
+  // Check once when the proxy starts, then follow the background monitor
   createEffect(() => {
     if (proxyStatus().running) {
-      // Delay initial check to stagger requests from multiple instances
-      const initialDelay = Math.random() * 5000; // 0-5 second random delay
-      const timeout = setTimeout(() => {
-        checkHealth();
-      }, initialDelay);
-
-      // Check every 60 seconds (reduced from 30 to cut spam)
-      const interval = setInterval(checkHealth, 60_000);
+      checkHealth();
+      const unlisten = onProviderHealthChanged((result) => setHealth(result[props.provider]));
       onCleanup(() => {
-        clearTimeout(timeout);
-        clearInterval(interval);
+        unlisten.then((fn) => fn());
       });
     } else {
       setHealth({ lastChecked: Date.now() / 1000, status: "offline" });
@@ -119,9 +122,13 @@ export function HealthPanel() {
   createEffect(() => {
     if (proxyStatus().running) {
       checkHealth();
-      // Check every 60 seconds (reduced from 30 to cut spam)
-      const interval = setInterval(checkHealth, 60_000);
-      onCleanup(() => clearInterval(interval));
+      const unlisten = onProviderHealthChanged((result) => {
+        setHealth(result);
+        setLastChecked(new Date());
+      });
+      onCleanup(() => {
+        unlisten.then((fn) => fn());
+      });
     }
   });
 
@@ -0,0 +1,109 @@
+import { createSignal, For, onCleanup, onMount, Show } from "solid-js";
+import { useI18n } from "../i18n";
+import {
+  getProxyStatus,
+  getRequestHistory,
+  onProxyStatusChanged,
+  onRequestLogBatch,
+  snapshotLiveFeed,
+} from "../lib/tauri";
+// Imported for its side effect: applies the saved light/dark theme
+import "../stores/theme";
+
+import type { RequestLog } from "../lib/tauri";
+
+const MAX_DISPLAY = 100;
+
+function statusColor(status: number): string {
+  if (status >= 500 || status === 401) {
+    return "text-red-600 dark:text-red-400";
+  }
+  if (status >= 400) {
+    return "text-amber-600 dark:text-amber-400";
+  }
+  return "text-green-600 dark:text-green-400";
+}
+
+function formatTime(timestamp: number): string {
+  return new Date(timestamp).toLocaleTimeString(undefined, {
+    hour: "2-digit",
+    hour12: false,
+    minute: "2-digit",
+    second: "2-digit",
+  });
+}
+
+function formatDuration(ms: number): string {
+  return ms < 1000 ? `${ms}ms` : `${(ms / 1000).toFixed(1)}s`;
+}
+
+// Live request feed for the always-on-top "mini-monitor" window. Read-only:
+// the main window owns the request history, so nothing is saved from here.
+export function MiniMonitor() {
+  const { t } = useI18n();
+  const [requests, setRequests] = createSignal<RequestLog[]>([]);
+  const [running, setRunning] = createSignal(false);
+
+  onMount(async () => {
+    // The in-memory feed answers at once; the full history follows
+    try {
+      setRequests((await snapshotLiveFeed()).slice(-MAX_DISPLAY).reverse());
+    } catch (error) {
+      console.error("Failed to load the live feed:", error);
+    }
+    try {
+      const [history, status] = await Promise.all([getRequestHistory(), getProxyStatus()]);
+      setRequests(history.requests.slice(-MAX_DISPLAY).reverse());
+      setRunning(status.running);
+    } catch (error) {
+      console.error("Failed to load request history:", error);
+    }
+
+    const unlistenLogs = await onRequestLogBatch((logs) => {
+      setRequests((prev) => [...[...logs].reverse(), ...prev].slice(0, MAX_DISPLAY));
+    });
+    const unlistenStatus = await onProxyStatusChanged((status) => setRunning(status.running));
+    onCleanup(() => {
+      unlistenLogs();
+      unlistenStatus();
+    });
+  });
+
+  return (
+    <div class="flex h-screen flex-col bg-white text-xs dark:bg-gray-900">
+      <div class="flex items-center gap-2 border-b border-gray-200 px-3 py-2 dark:border-gray-700">
+        <div
+          class={`h-2 w-2 rounded-full ${running() ? "animate-pulse bg-green-500" : "bg-gray-400"}`}
+        />
+        <span class="font-medium text-gray-900 dark:text-gray-100">
+          {running() ? t("requestMonitor.requestHistory") : t("requestMonitor.proxyOfflineCompact")}
+        </span>
+      </div>
+      <div class="flex-1 overflow-y-auto">
+        <Show
+          when={requests().length > 0}
+          fallback={
+            <p class="p-4 text-center text-gray-500 dark:text-gray-400">
+              {t("requestMonitor.waitingForRequests")}
+            </p>
+          }
+        >
+          <For each={requests()}>
+            {(request) => (
+              <div class="flex items-center gap-2 border-b border-gray-100 px-3 py-1.5 dark:border-gray-800">
+                <span class="tabular-nums text-gray-400">{formatTime(request.timestamp)}</span>
+                <span class={`font-mono font-medium ${statusColor(request.status)}`}>
+                  {request.status}
+                </span>
+                <span class="flex-1 truncate text-gray-700 dark:text-gray-300">
+                  {request.model || request.path}
+                </span>
+                <span class="tabular-nums text-gray-400">{formatDuration(request.durationMs)}</span>
+              </div>
+            )}
+          </For>
+        </Show>
+      </div>
+    </div>
+  );
+}
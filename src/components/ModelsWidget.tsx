@@ -20,6 +20,7 @@ const getProviderDisplayName = (ownedBy: string): string => {
     anthropic: "Anthropic",
     antigravity: "Antigravity",
     copilot: "GitHub Copilot",
+    deepseek: "DeepSeek",
     "github-copilot": "GitHub Copilot",
     google: "Google",
     iflow: "iFlow",
@@ -27,6 +28,7 @@ const getProviderDisplayName = (ownedBy: string): string => {
     kiro: "Kiro",
     openai: "OpenAI",
     qwen: "Qwen",
+    zhipu: "GLM (Zhipu)",
   };
   return names[ownedBy] || ownedBy.charAt(0).toUpperCase() + ownedBy.slice(1);
 };
@@ -36,6 +38,7 @@ const getProviderColor = (ownedBy: string): string => {
     anthropic: "bg-orange-500",
     antigravity: "bg-indigo-500",
     copilot: "bg-purple-500",
+    deepseek: "bg-sky-500",
     "github-copilot": "bg-purple-500",
     google: "bg-blue-500",
     iflow: "bg-pink-500",
@@ -43,6 +46,7 @@ const getProviderColor = (ownedBy: string): string => {
     kiro: "bg-teal-500",
     openai: "bg-green-500",
     qwen: "bg-cyan-500",
+    zhipu: "bg-blue-600",
   };
   return colors[ownedBy] || "bg-gray-500";
 };
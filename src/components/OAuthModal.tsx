@@ -9,6 +9,7 @@ import type { Provider } from "../lib/tauri";
 const providerLogos: Record<Provider, string> = {
   antigravity: "/logos/antigravity.webp",
   claude: "/logos/claude.svg",
+  deepseek: "/logos/deepseek.svg",
   gemini: "/logos/gemini.svg",
   iflow: "/logos/iflow.svg",
   kimi: "/logos/kimi.png",
@@ -16,6 +17,7 @@ const providerLogos: Record<Provider, string> = {
   openai: "/logos/openai.svg",
   qwen: "/logos/qwen.png",
   vertex: "/logos/vertex.svg",
+  zhipu: "/logos/zhipu.svg",
 };
 
 interface OAuthModalProps {
@@ -0,0 +1,130 @@
+import { createSignal, onCleanup, onMount, Show } from "solid-js";
+import { useI18n } from "../i18n";
+import {
+  answerPassphraseRequest,
+  MIN_PASSPHRASE_LENGTH,
+  onPassphraseRequestClosed,
+  onPassphraseRequested,
+  type PassphraseRequest,
+} from "../lib/tauri";
+import { Button } from "./ui";
+
+// Collects passphrases the backend asks for. The value only lives in the
+// inputs and is cleared as soon as the prompt is answered.
+export function PassphraseDialog() {
+  const { t } = useI18n();
+  const [request, setRequest] = createSignal<PassphraseRequest | null>(null);
+  const [passphrase, setPassphrase] = createSignal("");
+  const [confirmation, setConfirmation] = createSignal("");
+
+  const close = () => {
+    setPassphrase("");
+    setConfirmation("");
+    setRequest(null);
+  };
+
+  const error = () => {
+    const current = request();
+    if (!current?.confirm || !passphrase()) {
+      return null;
+    }
+    if (passphrase().length < MIN_PASSPHRASE_LENGTH) {
+      return t("passphrase.tooShort", { count: MIN_PASSPHRASE_LENGTH });
+    }
+    if (confirmation() && confirmation() !== passphrase()) {
+      return t("passphrase.mismatch");
+    }
+    return null;
+  };
+
+  const canSubmit = () =>
+    passphrase().length > 0 &&
+    !error() &&
+    (!request()?.confirm || confirmation() === passphrase());
+
+  const answer = async (value: string | null) => {
+    const current = request();
+    close();
+    if (current) {
+      // Expired prompts reject; the backend has already given up on them
+      await answerPassphraseRequest(current.id, value).catch(() => {});
+    }
+  };
+
+  onMount(async () => {
+    const unlistenRequested = await onPassphraseRequested((next) => {
+      const previous = request();
+      if (previous) {
+        void answerPassphraseRequest(previous.id, null).catch(() => {});
+      }
+      setPassphrase("");
+      setConfirmation("");
+      setRequest(next);
+    });
+    const unlistenClosed = await onPassphraseRequestClosed((id) => {
+      if (request()?.id === id) {
+        close();
+      }
+    });
+    onCleanup(() => {
+      unlistenRequested();
+      unlistenClosed();
+    });
+  });
+
+  const inputClass =
+    "mt-1 block w-full rounded-lg border border-gray-300 bg-white px-3 py-2 text-sm focus:border-transparent focus:ring-2 focus:ring-brand-500 dark:border-gray-600 dark:bg-gray-900";
+
+  return (
+    <Show when={request()}>
+      {(current) => (
+        <div class="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm">
+          <form
+            class="mx-4 w-full max-w-md rounded-2xl border border-gray-200 bg-white p-6 shadow-xl dark:border-gray-700 dark:bg-gray-800"
+            onSubmit={(e) => {
+              e.preventDefault();
+              if (canSubmit()) {
+                void answer(passphrase());
+              }
+            }}
+          >
+            <h3 class="mb-2 text-lg font-semibold text-gray-900 dark:text-gray-100">
+              {t(`passphrase.purposes.${current().purpose}.title`)}
+            </h3>
+            <p class="mb-4 text-sm text-gray-600 dark:text-gray-400">
+              {t(`passphrase.purposes.${current().purpose}.description`)}
+            </p>
+            <input
+              autocomplete="off"
+              autofocus
+              class={inputClass}
+              onInput={(e) => setPassphrase(e.currentTarget.value)}
+              placeholder={t("passphrase.placeholder")}
+              type="password"
+              value={passphrase()}
+            />
+            <Show when={current().confirm}>
+              <input
+                autocomplete="off"
+                class={`${inputClass} mt-3`}
+                onInput={(e) => setConfirmation(e.currentTarget.value)}
+                placeholder={t("passphrase.confirmPlaceholder")}
+                type="password"
+                value={confirmation()}
+              />
+            </Show>
+            <p class="mt-2 min-h-[1rem] text-xs text-red-500">{error()}</p>
+            <div class="mt-4 flex justify-end gap-3">
+              <Button onClick={() => void answer(null)} type="button" variant="ghost">
+                {t("common.cancel")}
+              </Button>
+              <Button disabled={!canSubmit()} type="submit" variant="primary">
+                {t("passphrase.continue")}
+              </Button>
+            </div>
+          </form>
+        </div>
+      )}
+    </Show>
+  );
+}
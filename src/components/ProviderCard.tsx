@@ -12,6 +12,7 @@ interface ProviderCardProps {
   name: string;
   onConnect: (provider: Provider) => Promise<void>;
   provider: Provider;
+  verified?: boolean; // false when the post-auth check failed
 }
 
 export function ProviderCard(props: ProviderCardProps) {
@@ -63,6 +64,13 @@ export function ProviderCard(props: ProviderCardProps) {
       <h3 class="font-semibold text-gray-900 dark:text-gray-100">{props.name}</h3>
       <p class="mb-4 mt-1 text-sm text-gray-500 dark:text-gray-400">{props.description}</p>
 
+      {/* Failed post-auth check */}
+      {isConnected() && props.verified === false && (
+        <p class="-mt-2 mb-3 text-xs text-amber-600 dark:text-amber-400">
+          Connected, but a test request failed. Check the account's setup.
+        </p>
+      )}
+
       {/* Action */}
       {isConnected() ? (
         <div class="flex items-center justify-between">
@@ -1,8 +1,14 @@
 import { createSignal, For, onCleanup, onMount, Show } from "solid-js";
 import { useI18n } from "../i18n";
-import { clearRequestHistory, onRequestLog } from "../lib/tauri";
+import {
+  clearRequestHistory,
+  onFeedResumed,
+  onRequestLogBatch,
+  setLiveFeedPaused,
+} from "../lib/tauri";
 import { appStore } from "../stores/app";
 import { requestStore } from "../stores/requests";
+import { toastStore } from "../stores/toast";
 
 const MAX_DISPLAY = 50;
 
@@ -84,9 +90,7 @@ export function RequestMonitor() {
   const history = requestStore.history;
   const [expanded, setExpanded] = createSignal(false);
   const [loading, setLoading] = createSignal(true);
-
-  // Debounce timer for high-frequency events
-  let debounceTimer: number | undefined;
+  const [paused, setPaused] = createSignal(false);
 
   // Load history on mount
   onMount(async () => {
@@ -98,31 +102,52 @@ export function RequestMonitor() {
       setLoading(false);
     }
 
-    // Listen for new requests with debouncing
-    const unlisten = await onRequestLog(async (log) => {
-      // Clear previous timer
-      if (debounceTimer) {
-        clearTimeout(debounceTimer);
-      }
-
-      // Debounce: wait 100ms before processing
-      debounceTimer = window.setTimeout(async () => {
-        try {
+    // New requests arrive already batched by the backend
+    const unlisten = await onRequestLogBatch(async (logs) => {
+      try {
+        for (const log of logs) {
           await requestStore.addRequest(log);
-        } catch (error) {
-          console.error("Failed to save request to history:", error);
         }
-      }, 100);
+      } catch (error) {
+        console.error("Failed to save request to history:", error);
+      }
     });
 
-    onCleanup(() => {
-      if (debounceTimer) {
-        clearTimeout(debounceTimer);
+    const unlistenResumed = await onFeedResumed(({ missed }) => {
+      if (missed > 0) {
+        toastStore.info(t("requestMonitor.caughtUp", { count: missed }));
       }
+    });
+
+    onCleanup(() => {
       unlisten();
+      unlistenResumed();
+      // Don't leave the feed paused for the other views
+      if (paused()) {
+        void setLiveFeedPaused(false);
+      }
     });
   });
 
+  const handleTogglePause = async () => {
+    const next = !paused();
+    try {
+      await setLiveFeedPaused(next);
+      setPaused(next);
+    } catch (error) {
+      console.error("Failed to pause the live feed:", error);
+    }
+  };
+
+  const handleClearDemo = async () => {
+    try {
+      await clearRequestHistory(true);
+      requestStore.clearSyntheticHistory();
+    } catch (error) {
+      console.error("Failed to clear demo requests:", error);
+    }
+  };
+
   const handleClear = async () => {
     try {
       await clearRequestHistory();
@@ -138,6 +163,7 @@ export function RequestMonitor() {
   };
 
   const requestCount = () => history().requests.length;
+  const hasSynthetic = () => history().requests.some((r) => r.synthetic);
   const hasRequests = () => requestCount() > 0;
 
   return (
@@ -178,6 +204,26 @@ export function RequestMonitor() {
                 {t("requestMonitor.tokens")}
               </span>
             </div>
+            <Show when={hasSynthetic()}>
+              <button
+                class="rounded px-2 py-1 text-xs text-gray-500 hover:bg-gray-200 hover:text-gray-700 dark:hover:bg-gray-700 dark:hover:text-gray-300"
+                onClick={(e) => {
+                  e.stopPropagation();
+                  handleClearDemo();
+                }}
+              >
+                {t("requestMonitor.clearDemo")}
+              </button>
+            </Show>
+            <button
+              class="rounded px-2 py-1 text-xs text-gray-500 hover:bg-gray-200 hover:text-gray-700 dark:hover:bg-gray-700 dark:hover:text-gray-300"
+              onClick={(e) => {
+                e.stopPropagation();
+                handleTogglePause();
+              }}
+            >
+              {paused() ? t("requestMonitor.resume") : t("requestMonitor.pause")}
+            </button>
             <button
               class="rounded px-2 py-1 text-xs text-gray-500 hover:bg-gray-200 hover:text-gray-700 dark:hover:bg-gray-700 dark:hover:text-gray-300"
               onClick={(e) => {
@@ -338,6 +384,26 @@ export function RequestMonitor() {
                       {log.model || "—"}
                     </span>
 
+                    {/* Tool that sent the request */}
+                    <Show when={log.client}>
+                      <span
+                        class="hidden max-w-28 truncate rounded bg-gray-100 px-1.5 text-xs text-gray-500 dark:bg-gray-800 dark:text-gray-400 md:block"
+                        title={log.client ?? undefined}
+                      >
+                        {log.client}
+                      </span>
+                    </Show>
+
+                    {/* Account that served the request */}
+                    <Show when={log.account}>
+                      <span
+                        class="hidden max-w-32 truncate text-xs text-gray-400 dark:text-gray-500 md:block"
+                        title={log.account ?? undefined}
+                      >
+                        {log.account}
+                      </span>
+                    </Show>
+
                     {/* Tokens */}
                     <Show when={log.tokensIn || log.tokensOut}>
                       <span class="hidden font-mono text-xs text-gray-400 dark:text-gray-500 sm:block">
@@ -345,6 +411,21 @@ export function RequestMonitor() {
                       </span>
                     </Show>
 
+                    {/* Retries, with each attempt's provider/account on hover */}
+                    <Show when={(log.retries ?? 0) > 0}>
+                      <span
+                        class="rounded bg-amber-100 px-1 font-mono text-[10px] text-amber-700 dark:bg-amber-900/40 dark:text-amber-300"
+                        title={(log.attempts ?? [])
+                          .map(
+                            (attempt, i) =>
+                              `#${i + 1} ${attempt.status ?? "?"} ${[attempt.provider, attempt.account].filter(Boolean).join(" · ")}`,
+                          )
+                          .join("\n")}
+                      >
+                        ×{(log.retries ?? 0) + 1}
+                      </span>
+                    </Show>
+
                     {/* Status */}
                     <span
                       class={`font-mono text-xs font-semibold ${statusColors[log.status] || "text-gray-500"}`}
@@ -20,12 +20,13 @@ const toolLogos: Record<string, string> = {
   cline: "/logos/cline.svg",
   continue: "/logos/continue.svg",
   cursor: "/logos/cursor.svg",
+  jetbrains: "/logos/jetbrains.svg",
   windsurf: "/logos/windsurf.svg",
 };
 
 export function SetupWizard(props: SetupWizardProps) {
   const { t } = useI18n();
-  const { proxyStatus } = appStore;
+  const { config, proxyStatus } = appStore;
   const [tools, setTools] = createSignal<DetectedTool[]>([]);
   const [loading, setLoading] = createSignal(true);
   const [selectedTool, setSelectedTool] = createSignal<string | null>(null);
@@ -97,7 +98,7 @@ export function SetupWizard(props: SetupWizardProps) {
   const installedTools = () => tools().filter((t) => t.installed);
   const notInstalledTools = () => tools().filter((t) => !t.installed);
 
-  const endpoint = () => proxyStatus().endpoint || "http://localhost:8317/v1";
+  const endpoint = () => proxyStatus().endpoint || `http://localhost:${config().port}/v1`;
 
   return (
     <div class="flex h-full flex-col">
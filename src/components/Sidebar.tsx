@@ -1,10 +1,19 @@
-import { type Component, createEffect, createSignal, For, onMount, Show } from "solid-js";
+import { type Component, createEffect, createSignal, For, onCleanup, onMount, Show } from "solid-js";
 import { useI18n } from "../i18n";
-import { checkForUpdates, downloadAndInstallUpdate, saveConfig } from "../lib/tauri";
+import {
+  dismissAppUpdate,
+  downloadAndInstallUpdate,
+  getAppUpdate,
+  onAppUpdateAvailable,
+  openAppReleasePage,
+  saveConfig,
+} from "../lib/tauri";
 import { appStore } from "../stores/app";
 import { themeStore } from "../stores/theme";
 import { toastStore } from "../stores/toast";
 
+import type { AppUpdateInfo } from "../lib/tauri";
+
 type PageId = "dashboard" | "analytics" | "logs" | "api-keys" | "auth-files" | "settings";
 
 interface NavItem {
@@ -99,25 +108,38 @@ export const Sidebar: Component = () => {
     }
   });
 
-  const [updateAvailable, setUpdateAvailable] = createSignal(false);
-  const [updateVersion, setUpdateVersion] = createSignal("");
+  const [update, setUpdate] = createSignal<AppUpdateInfo | null>(null);
   const [isUpdating, setIsUpdating] = createSignal(false);
 
-  // Check for updates on mount
+  const updateAvailable = () => {
+    const info = update();
+    return info !== null && info.available && !info.dismissed;
+  };
+  const updateVersion = () => update()?.latest ?? "";
+
+  // The backend checks shortly after launch and then daily (autoCheckAppUpdates)
   onMount(async () => {
+    const unlisten = await onAppUpdateAvailable(setUpdate);
+    onCleanup(unlisten);
     try {
-      const info = await checkForUpdates();
-      if (info.available && info.version) {
-        setUpdateAvailable(true);
-        setUpdateVersion(info.version);
-      }
+      setUpdate(await getAppUpdate());
     } catch {
-      // Silently ignore update check errors
+      // Nothing checked yet
     }
   });
 
   const handleUpdate = async () => {
-    if (isUpdating()) {
+    const info = update();
+    if (isUpdating() || !info) {
+      return;
+    }
+    // Installs the updater can't replace (e.g. .deb) get the release page instead
+    if (!info.canInstall) {
+      try {
+        await openAppReleasePage();
+      } catch (error) {
+        toastStore.error(t("settings.toasts.updateFailed"), String(error));
+      }
       return;
     }
     setIsUpdating(true);
@@ -130,6 +152,19 @@ export const Sidebar: Component = () => {
     }
   };
 
+  const handleSkipUpdate = async () => {
+    const info = update();
+    if (!info) {
+      return;
+    }
+    try {
+      await dismissAppUpdate(info.latest);
+      setUpdate({ ...info, dismissed: true });
+    } catch (error) {
+      console.error("Failed to dismiss update:", error);
+    }
+  };
+
   const isExpanded = () => isPinned() || sidebarExpanded();
 
   const isActive = (id: PageId) => {
@@ -332,6 +367,15 @@ export const Sidebar: Component = () => {
               </span>
             </Show>
           </button>
+          <Show when={isExpanded() && !isUpdating()}>
+            <button
+              class="mt-1 w-full rounded-lg px-2.5 py-1 text-left text-xs text-gray-500 transition-colors hover:bg-gray-100 hover:text-gray-700 dark:text-gray-400 dark:hover:bg-gray-800 dark:hover:text-gray-200"
+              onClick={handleSkipUpdate}
+              type="button"
+            >
+              {t("sidebar.skipVersion")}
+            </button>
+          </Show>
         </div>
       </Show>
 
@@ -1,5 +1,5 @@
-import { createSignal, createEffect, onCleanup, Show, For } from "solid-js";
-import { getUsageStats, type UsageStats } from "../lib/tauri";
+import { createSignal, createEffect, onCleanup, onMount, Show, For } from "solid-js";
+import { getUsageStats, onUsageDayChanged, type UsageStats } from "../lib/tauri";
 import { appStore } from "../stores/app";
 
 function formatUptime(startTime: number | null): string {
@@ -125,6 +125,12 @@ export function UsageSummary() {
     fetchStats();
   });
 
+  // Today's figures start over at the usage day boundary
+  onMount(async () => {
+    const unlisten = await onUsageDayChanged(() => fetchStats());
+    onCleanup(unlisten);
+  });
+
   const successRate = () => {
     const s = stats();
     if (!s || s.totalRequests === 0) {
@@ -0,0 +1,72 @@
+import { createSignal, For, onMount, Show } from "solid-js";
+import { useI18n } from "../../i18n";
+import { getLastSessionSummary, type LastSessionSummary } from "../../lib/tauri";
+import { Button } from "../ui";
+
+const MAX_SHOWN_ERRORS = 3;
+
+function formatUptime(secs: number): string {
+  if (secs < 3600) {
+    return `${Math.max(1, Math.floor(secs / 60))}m`;
+  }
+  return `${Math.floor(secs / 3600)}h ${Math.floor((secs % 3600) / 60)}m`;
+}
+
+// Shown once after a launch that follows an unclean shutdown
+export function LastSessionBanner() {
+  const { t } = useI18n();
+  const [summary, setSummary] = createSignal<LastSessionSummary | null>(null);
+
+  onMount(async () => {
+    try {
+      setSummary(await getLastSessionSummary());
+    } catch (error) {
+      console.error("Failed to load the last session summary:", error);
+    }
+  });
+
+  return (
+    <Show when={summary()?.cleanShutdown === false && summary()}>
+      {(last) => (
+        <div class="rounded-lg border border-amber-200 bg-amber-50 p-3 dark:border-amber-800 dark:bg-amber-900/20">
+          <div class="flex items-start justify-between gap-3">
+            <div class="text-sm text-amber-700 dark:text-amber-300">
+              <p class="font-medium">{t("dashboard.lastSession.title")}</p>
+              <p class="mt-1 text-xs">
+                {t("dashboard.lastSession.details", {
+                  failures: last().failures,
+                  requests: last().requests,
+                  uptime: formatUptime(last().uptimeSecs),
+                })}
+              </p>
+              <Show when={last().crashReason}>
+                {(reason) => (
+                  <p class="mt-1 text-xs">
+                    {t("dashboard.lastSession.reason")}: {reason()}
+                  </p>
+                )}
+              </Show>
+            </div>
+            <Button onClick={() => setSummary(null)} size="sm" variant="secondary">
+              {t("dashboard.lastSession.dismiss")}
+            </Button>
+          </div>
+          <Show when={last().lastErrors.length > 0}>
+            <p class="mt-2 text-xs font-medium text-amber-700 dark:text-amber-300">
+              {t("dashboard.lastSession.lastErrors")}
+            </p>
+            <ul class="mt-1 space-y-0.5">
+              <For each={last().lastErrors.slice(0, MAX_SHOWN_ERRORS)}>
+                {(error) => (
+                  <li class="truncate text-xs text-amber-700 dark:text-amber-300">
+                    {new Date(error.timestamp).toLocaleTimeString()} · {error.message}
+                  </li>
+                )}
+              </For>
+            </ul>
+          </Show>
+        </div>
+      )}
+    </Show>
+  );
+}
@@ -0,0 +1,66 @@
+import { createSignal, For, onCleanup, onMount, Show } from "solid-js";
+import { useI18n } from "../../i18n";
+import { cancelOAuth, listPendingOAuth, type PendingOAuth, type Provider } from "../../lib/tauri";
+import { toastStore } from "../../stores/toast";
+import { Button } from "../ui";
+
+interface PendingOAuthListProps {
+  providerName: (provider: Provider) => string;
+}
+
+// Sign-ins started in the browser that haven't finished yet
+export function PendingOAuthList(props: PendingOAuthListProps) {
+  const { t } = useI18n();
+  const [flows, setFlows] = createSignal<PendingOAuth[]>([]);
+
+  const load = async () => {
+    try {
+      setFlows(await listPendingOAuth());
+    } catch (error) {
+      console.error("Failed to list pending sign-ins:", error);
+    }
+  };
+
+  onMount(() => {
+    void load();
+    const timer = setInterval(load, 3000);
+    onCleanup(() => clearInterval(timer));
+  });
+
+  const handleCancel = async (flow: PendingOAuth) => {
+    try {
+      await cancelOAuth(flow.state);
+    } catch (error) {
+      toastStore.error(t("dashboard.pendingOAuth.failedToCancel"), String(error));
+    }
+    await load();
+  };
+
+  return (
+    <Show when={flows().length > 0}>
+      <div class="rounded-lg border border-blue-200 bg-blue-50 p-3 dark:border-blue-800 dark:bg-blue-900/20">
+        <p class="text-sm font-medium text-blue-700 dark:text-blue-300">
+          {t("dashboard.pendingOAuth.title")}
+        </p>
+        <ul class="mt-2 space-y-1">
+          <For each={flows()}>
+            {(flow) => (
+              <li class="flex items-center justify-between gap-3 text-xs text-blue-700 dark:text-blue-300">
+                <span>
+                  {props.providerName(flow.provider)}
+                  {" — "}
+                  {flow.status === "callback-received"
+                    ? t("dashboard.pendingOAuth.finishing")
+                    : t("dashboard.pendingOAuth.waiting")}
+                </span>
+                <Button onClick={() => handleCancel(flow)} size="sm" variant="secondary">
+                  {t("dashboard.pendingOAuth.cancel")}
+                </Button>
+              </li>
+            )}
+          </For>
+        </ul>
+      </div>
+    </Show>
+  );
+}
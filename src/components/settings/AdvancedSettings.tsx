@@ -1,7 +1,7 @@
 import { createEffect, createSignal, For, Show } from "solid-js";
 import { useI18n } from "../../i18n";
 import {
-  checkForUpdates,
+  checkAppUpdate,
   deleteOAuthExcludedModels,
   downloadAndInstallUpdate,
   getAvailableModels,
@@ -9,6 +9,7 @@ import {
   getOAuthExcludedModels,
   getWebsocketAuth,
   isUpdaterSupported,
+  openAppReleasePage,
   setConfigYaml,
   setOAuthExcludedModels,
   setWebsocketAuth,
@@ -19,9 +20,9 @@ import { toastStore } from "../../stores/toast";
 import { Button, Switch } from "../ui";
 
 import type {
+  AppUpdateInfo,
   AvailableModel,
   OAuthExcludedModels,
-  UpdateInfo,
   UpdateProgress,
   UpdaterSupport,
 } from "../../lib/tauri";
@@ -49,7 +50,7 @@ export function AdvancedSettings(props: AdvancedSettingsProps) {
   const [savingYaml, setSavingYaml] = createSignal(false);
 
   // App Updates state
-  const [updateInfo, setUpdateInfo] = createSignal<UpdateInfo | null>(null);
+  const [updateInfo, setUpdateInfo] = createSignal<AppUpdateInfo | null>(null);
   const [checkingForUpdates, setCheckingForUpdates] = createSignal(false);
   const [installingUpdate, setInstallingUpdate] = createSignal(false);
   const [updateProgress, setUpdateProgress] = createSignal<UpdateProgress | null>(null);
@@ -127,12 +128,12 @@ export function AdvancedSettings(props: AdvancedSettingsProps) {
     setCheckingForUpdates(true);
     setUpdateInfo(null);
     try {
-      const info = await checkForUpdates();
+      const info = await checkAppUpdate();
       setUpdateInfo(info);
       if (info.available) {
         toastStore.success(
           t("settings.toasts.updateAvailable", {
-            version: info.version || "",
+            version: info.latest,
           }),
         );
       } else {
@@ -146,6 +147,14 @@ export function AdvancedSettings(props: AdvancedSettingsProps) {
     }
   };
 
+  const handleOpenReleasePage = async () => {
+    try {
+      await openAppReleasePage();
+    } catch (error) {
+      toastStore.error(t("settings.toasts.updateFailed"), String(error));
+    }
+  };
+
   const handleInstallUpdate = async () => {
     setInstallingUpdate(true);
     setUpdateProgress(null);
@@ -889,6 +898,15 @@ export function AdvancedSettings(props: AdvancedSettingsProps) {
         </h2>
 
         <div class="space-y-4 rounded-xl border border-gray-200 bg-gray-50 p-4 dark:border-gray-700 dark:bg-gray-800/50">
+          <Switch
+            checked={props.config().autoCheckAppUpdates ?? true}
+            description="Look for a new ProxyPal release once a day"
+            label="Check Automatically"
+            onChange={(checked) => props.handleConfigChange("autoCheckAppUpdates", checked)}
+          />
+
+          <div class="border-t border-gray-200 dark:border-gray-700" />
+
           <div class="flex items-center justify-between">
             <div class="flex-1">
               <p class="text-sm font-medium text-gray-700 dark:text-gray-300">Check for Updates</p>
@@ -961,7 +979,7 @@ export function AdvancedSettings(props: AdvancedSettingsProps) {
                 </svg>
                 <div class="min-w-0 flex-1">
                   <p class="text-sm font-medium text-brand-700 dark:text-brand-300">
-                    Update Available: v{updateInfo()?.version}
+                    Update Available: v{updateInfo()?.latest}
                   </p>
                   <p class="mt-1 flex items-center gap-1 text-xs text-amber-600 dark:text-amber-400">
                     <svg class="h-3.5 w-3.5" fill="none" stroke="currentColor" viewBox="0 0 24 24">
@@ -974,14 +992,9 @@ export function AdvancedSettings(props: AdvancedSettingsProps) {
                     </svg>
                     Please stop the proxy before updating to avoid issues
                   </p>
-                  <Show when={updateInfo()?.body}>
-                    <p class="mt-1 line-clamp-3 text-xs text-brand-600 dark:text-brand-400">
-                      {updateInfo()?.body}
-                    </p>
-                  </Show>
-                  <Show when={updateInfo()?.date}>
-                    <p class="mt-1 text-xs text-brand-500 dark:text-brand-500">
-                      Released: {updateInfo()?.date}
+                  <Show when={updateInfo()?.notes}>
+                    <p class="mt-1 max-h-40 overflow-y-auto whitespace-pre-line text-xs text-brand-600 dark:text-brand-400">
+                      {updateInfo()?.notes}
                     </p>
                   </Show>
                 </div>
@@ -991,14 +1004,15 @@ export function AdvancedSettings(props: AdvancedSettingsProps) {
                 <Show
                   fallback={
                     <div class="text-center">
-                      <p class="mb-2 text-xs text-amber-600 dark:text-amber-400">
-                        {updaterSupport()?.reason}
-                      </p>
-                      <a
+                      <Show when={updaterSupport()?.supported === false}>
+                        <p class="mb-2 text-xs text-amber-600 dark:text-amber-400">
+                          {updaterSupport()?.reason}
+                        </p>
+                      </Show>
+                      <button
                         class="inline-flex items-center justify-center rounded-lg bg-brand-500 px-4 py-2 text-sm font-medium text-white transition-colors hover:bg-brand-600"
-                        href="https://github.com/heyhuynhgiabuu/proxypal/releases"
-                        rel="noopener noreferrer"
-                        target="_blank"
+                        onClick={handleOpenReleasePage}
+                        type="button"
                       >
                         <svg
                           class="mr-1.5 h-4 w-4"
@@ -1014,10 +1028,10 @@ export function AdvancedSettings(props: AdvancedSettingsProps) {
                           />
                         </svg>
                         Download from GitHub
-                      </a>
+                      </button>
                     </div>
                   }
-                  when={updaterSupport()?.supported !== false}
+                  when={updateInfo()?.canInstall}
                 >
                   <Button
                     class="w-full"
@@ -1106,7 +1120,7 @@ export function AdvancedSettings(props: AdvancedSettingsProps) {
               </svg>
               <p class="text-sm text-green-700 dark:text-green-300">
                 You're running the latest version (v
-                {updateInfo()?.currentVersion})
+                {updateInfo()?.current})
               </p>
             </div>
           </Show>
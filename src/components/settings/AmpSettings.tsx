@@ -2,11 +2,13 @@ import { createEffect, createMemo, createSignal, For, Show } from "solid-js";
 import { useI18n } from "../../i18n";
 import {
   AMP_MODEL_SLOTS,
+  configureAmp,
   getForceModelMappings,
   saveConfig,
   setForceModelMappings,
   startProxy,
   stopProxy,
+  validateAmpApiKey,
 } from "../../lib/tauri";
 import { appStore } from "../../stores/app";
 import { toastStore } from "../../stores/toast";
@@ -99,6 +101,38 @@ export function AmpSettings(props: AmpSettingsProps) {
   const [forceModelMappings, setForceModelMappingsState] = createSignal<boolean>(false);
   const [savingForceModelMappings, setSavingForceModelMappings] = createSignal(false);
 
+  const [settingUpAmp, setSettingUpAmp] = createSignal(false);
+  const [checkingAmpKey, setCheckingAmpKey] = createSignal(false);
+
+  // Save the key and routing, then write the proxy's Amp routes and Amp's settings.json
+  const handleSetUpAmp = async () => {
+    setSettingUpAmp(true);
+    try {
+      const result = await configureAmp(props.config().ampApiKey, props.config().ampRoutingMode);
+      toastStore.success(t("settings.amp.configured"), result.configPath);
+    } catch (error) {
+      toastStore.error(t("settings.amp.configureFailed"), String(error));
+    } finally {
+      setSettingUpAmp(false);
+    }
+  };
+
+  const handleCheckAmpKey = async () => {
+    setCheckingAmpKey(true);
+    try {
+      const result = await validateAmpApiKey();
+      if (result.success) {
+        toastStore.success(t("settings.amp.keyAccepted"), `${result.latencyMs ?? 0}ms`);
+      } else {
+        toastStore.error(t("settings.amp.keyRejected"), result.message);
+      }
+    } catch (error) {
+      toastStore.error(t("settings.amp.keyCheckFailed"), String(error));
+    } finally {
+      setCheckingAmpKey(false);
+    }
+  };
+
   // Load forceModelMappings state reactively when proxy becomes running
   createEffect(() => {
     if (appStore.proxyStatus().running) {
@@ -464,6 +498,30 @@ export function AmpSettings(props: AmpSettingsProps) {
           </p>
         </label>
 
+        <div class="space-y-2">
+          <p class="text-xs text-gray-500 dark:text-gray-400">{t("settings.amp.setUpDescription")}</p>
+          <div class="flex gap-2">
+            <Button
+              disabled={!props.config().ampApiKey || settingUpAmp()}
+              onClick={handleSetUpAmp}
+              size="sm"
+              variant="secondary"
+            >
+              {settingUpAmp() ? t("settings.amp.configuring") : t("settings.amp.setUp")}
+            </Button>
+            <Button
+              disabled={
+                !props.config().ampApiKey || !appStore.proxyStatus().running || checkingAmpKey()
+              }
+              onClick={handleCheckAmpKey}
+              size="sm"
+              variant="ghost"
+            >
+              {checkingAmpKey() ? t("settings.amp.checkingKey") : t("settings.amp.checkKey")}
+            </Button>
+          </div>
+        </div>
+
         <div class="border-t border-gray-200 dark:border-gray-700" />
 
         {/* Model Mappings */}
@@ -23,7 +23,7 @@ export function CloudflareSettings(props: CloudflareSettingsProps) {
   const [cfId, setCfId] = createSignal("");
   const [cfName, setCfName] = createSignal("");
   const [cfToken, setCfToken] = createSignal("");
-  const [cfLocalPort, setCfLocalPort] = createSignal(8317);
+  const [cfLocalPort, setCfLocalPort] = createSignal(props.config.port);
   const [cfAdding, setCfAdding] = createSignal(false);
 
   // Cloudflare Handlers
@@ -45,7 +45,7 @@ export function CloudflareSettings(props: CloudflareSettingsProps) {
       setCfId("");
       setCfName("");
       setCfToken("");
-      setCfLocalPort(8317);
+      setCfLocalPort(props.config.port);
       setCfAdding(false);
       toastStore.success(t("settings.toasts.cloudflareTunnelSaved"));
     } catch (error) {
@@ -97,7 +97,7 @@ export function CloudflareSettings(props: CloudflareSettingsProps) {
             setCfId("");
             setCfName("");
             setCfToken("");
-            setCfLocalPort(8317);
+            setCfLocalPort(props.config.port);
             setCfAdding(true);
           }}
           variant="primary"
@@ -213,8 +213,10 @@ export function CloudflareSettings(props: CloudflareSettingsProps) {
               </label>
               <input
                 class="w-full rounded-lg border border-gray-200 bg-white px-3 py-2 text-sm outline-none focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-800"
-                onInput={(e) => setCfLocalPort(Number.parseInt(e.currentTarget.value) || 8317)}
-                placeholder="8317"
+                onInput={(e) =>
+                  setCfLocalPort(Number.parseInt(e.currentTarget.value) || props.config.port)
+                }
+                placeholder={String(props.config.port)}
                 type="number"
                 value={cfLocalPort()}
               />
@@ -282,8 +284,10 @@ export function CloudflareSettings(props: CloudflareSettingsProps) {
                 Service Type: <code class="rounded bg-gray-200 px-1 dark:bg-gray-700">HTTP</code>
               </li>
               <li>
-                URL: <code class="rounded bg-gray-200 px-1 dark:bg-gray-700">localhost:8317</code>{" "}
-                (or your proxy port)
+                URL:{" "}
+                <code class="rounded bg-gray-200 px-1 dark:bg-gray-700">
+                  localhost:{props.config.port}
+                </code>
               </li>
             </ul>
           </li>
@@ -1,6 +1,7 @@
 import { createEffect, createSignal, Show, splitProps } from "solid-js";
 import { useI18n } from "../../i18n";
 import {
+  DEFAULT_PORT,
   getLogSize,
   getMaxRetryInterval,
   saveConfig,
@@ -100,13 +101,13 @@ export function ProxySettings(props: ProxySettingsProps) {
             max="65535"
             min="1024"
             onInput={(e) =>
-              local.handleConfigChange("port", Number.parseInt(e.currentTarget.value) || 8317)
+              local.handleConfigChange("port", Number.parseInt(e.currentTarget.value) || DEFAULT_PORT)
             }
             type="number"
             value={local.config().port}
           />
           <p class="mt-1 text-xs text-gray-500 dark:text-gray-400">
-            The port where the proxy server will listen (default: 8317)
+            The port where the proxy server will listen (default: {DEFAULT_PORT})
           </p>
         </label>
 
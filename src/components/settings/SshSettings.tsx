@@ -1,7 +1,7 @@
 import { open } from "@tauri-apps/plugin-dialog";
 import { createMemo, createSignal, For, Show } from "solid-js";
 import { useI18n } from "../../i18n";
-import { deleteSshConfig, saveSshConfig, setSshConnection } from "../../lib/tauri";
+import { DEFAULT_PORT, deleteSshConfig, saveSshConfig, setSshConnection } from "../../lib/tauri";
 import { appStore } from "../../stores/app";
 import { toastStore } from "../../stores/toast";
 import { Button, Switch } from "../ui";
@@ -23,8 +23,8 @@ export function SshSettings(props: SshSettingsProps) {
   const [sshUser, setSshUser] = createSignal("");
   const [sshPass, setSshPass] = createSignal("");
   const [sshKey, setSshKey] = createSignal("");
-  const [sshRemote, setSshRemote] = createSignal(8317);
-  const [sshLocal, setSshLocal] = createSignal(8317);
+  const [sshRemote, setSshRemote] = createSignal(props.config.port);
+  const [sshLocal, setSshLocal] = createSignal(props.config.port);
   const [sshAdding, setSshAdding] = createSignal(false);
 
   // SSH Handlers
@@ -94,8 +94,8 @@ export function SshSettings(props: SshSettingsProps) {
     setSshUser("");
     setSshPass("");
     setSshKey("");
-    setSshRemote(8317);
-    setSshLocal(8317);
+    setSshRemote(props.config.port);
+    setSshLocal(props.config.port);
   };
 
   const handleDeleteSsh = async (id: string) => {
@@ -128,7 +128,7 @@ export function SshSettings(props: SshSettingsProps) {
         SSH API Connections
       </h2>
       <p class="text-sm text-gray-500 dark:text-gray-400">
-        Securely tunnel your local API (port 8317) to a remote server for shared access.
+        Securely tunnel your local API (port {props.config.port}) to a remote server for shared access.
       </p>
 
       {/* List */}
@@ -333,7 +333,7 @@ export function SshSettings(props: SshSettingsProps) {
             <input
               class="w-full rounded-lg border border-gray-200 bg-white px-3 py-2 text-sm outline-none transition-all focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-800"
               onInput={(e) => setSshRemote(Number.parseInt(e.currentTarget.value) || 0)}
-              placeholder="8317"
+              placeholder={String(props.config.port)}
               type="number"
               value={sshRemote()}
             />
@@ -344,11 +344,11 @@ export function SshSettings(props: SshSettingsProps) {
             <input
               class="w-full rounded-lg border border-gray-200 bg-white px-3 py-2 text-sm outline-none transition-all focus:ring-2 focus:ring-blue-500 dark:border-gray-600 dark:bg-gray-800"
               onInput={(e) => setSshLocal(Number.parseInt(e.currentTarget.value) || 0)}
-              placeholder="8317"
+              placeholder={String(props.config.port)}
               type="number"
               value={sshLocal()}
             />
-            <p class="text-[10px] text-gray-400">Port running locally (default 8317)</p>
+            <p class="text-[10px] text-gray-400">Port running locally (default {DEFAULT_PORT})</p>
           </div>
         </div>
         <div class="pt-2">
@@ -0,0 +1,150 @@
+import { open } from "@tauri-apps/plugin-dialog";
+import { createSignal, For, Show } from "solid-js";
+import { useI18n } from "../../i18n";
+import { migrateDataDir } from "../../lib/tauri";
+import { appStore } from "../../stores/app";
+import { toastStore } from "../../stores/toast";
+import { Button } from "../ui";
+
+import type { StorageInfo } from "../../lib/tauri";
+
+interface StorageSettingsProps {
+  proxyRunning: boolean;
+  setStorageInfo: (info: StorageInfo) => void;
+  storageInfo: () => StorageInfo | null;
+}
+
+// Entries that move with the data folder
+const DATA_ENTRIES = ["history", "aggregate", "logs", "captures", "benchmarks"];
+
+const formatSize = (bytes?: number | null) => {
+  if (bytes === null || bytes === undefined) {
+    return "-";
+  }
+  if (bytes < 1024) {
+    return `${bytes} B`;
+  }
+  if (bytes < 1024 * 1024) {
+    return `${(bytes / 1024).toFixed(1)} KB`;
+  }
+  if (bytes < 1024 * 1024 * 1024) {
+    return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+  }
+  return `${(bytes / 1024 / 1024 / 1024).toFixed(1)} GB`;
+};
+
+export function StorageSettings(props: StorageSettingsProps) {
+  const { t } = useI18n();
+  const [moving, setMoving] = createSignal(false);
+
+  const separate = () => {
+    const info = props.storageInfo();
+    return !!info && info.dataDir !== info.configDir;
+  };
+
+  const dataPaths = () =>
+    (props.storageInfo()?.paths ?? []).filter((p) => DATA_ENTRIES.includes(p.name));
+
+  const move = async (newPath?: string) => {
+    setMoving(true);
+    try {
+      const info = await migrateDataDir(newPath);
+      props.setStorageInfo(info);
+      appStore.setConfig((prev) => ({ ...prev, dataDir: newPath ?? null }));
+      toastStore.success(t("settings.storage.moved"), info.dataDir);
+    } catch (error) {
+      toastStore.error(t("settings.storage.moveFailed"), String(error));
+    } finally {
+      setMoving(false);
+    }
+  };
+
+  const handleChoose = async () => {
+    const selected = await open({ directory: true, multiple: false });
+    if (typeof selected === "string" && selected !== props.storageInfo()?.dataDir) {
+      await move(selected);
+    }
+  };
+
+  const handleReset = async () => {
+    if (confirm(t("settings.storage.confirmReset"))) {
+      await move();
+    }
+  };
+
+  return (
+    <div class="space-y-4">
+      <h2 class="text-sm font-semibold uppercase tracking-wider text-gray-600 dark:text-gray-400">
+        {t("settings.storage.title")}
+      </h2>
+
+      <div class="space-y-4 rounded-xl border border-gray-200 bg-gray-50 p-4 dark:border-gray-700 dark:bg-gray-800/50">
+        <p class="text-xs text-gray-500 dark:text-gray-400">{t("settings.storage.description")}</p>
+
+        <div class="space-y-1 text-sm">
+          <div class="flex justify-between gap-4">
+            <span class="text-gray-700 dark:text-gray-300">{t("settings.storage.configDir")}</span>
+            <span class="text-gray-500 dark:text-gray-400">
+              {formatSize(props.storageInfo()?.configBytes)}
+            </span>
+          </div>
+          <code class="block break-all text-xs text-gray-500 dark:text-gray-400">
+            {props.storageInfo()?.configDir}
+          </code>
+          <div class="flex justify-between gap-4 pt-2">
+            <span class="text-gray-700 dark:text-gray-300">{t("settings.storage.dataDir")}</span>
+            <span class="text-gray-500 dark:text-gray-400">
+              {formatSize(props.storageInfo()?.dataBytes)}
+            </span>
+          </div>
+          <code class="block break-all text-xs text-gray-500 dark:text-gray-400">
+            {props.storageInfo()?.dataDir}
+          </code>
+          <p class="text-xs text-gray-500 dark:text-gray-400">
+            {t("settings.storage.free", {
+              size: formatSize(props.storageInfo()?.dataFreeBytes),
+            })}
+          </p>
+        </div>
+
+        <ul class="space-y-1 text-xs text-gray-500 dark:text-gray-400">
+          <For each={dataPaths()}>
+            {(entry) => (
+              <li class="flex justify-between gap-4">
+                <span>{entry.name}</span>
+                <span>{entry.exists ? formatSize(entry.sizeBytes) : "-"}</span>
+              </li>
+            )}
+          </For>
+        </ul>
+
+        <Show when={props.proxyRunning}>
+          <p class="text-xs text-amber-600 dark:text-amber-400">
+            {t("settings.storage.stopProxyFirst")}
+          </p>
+        </Show>
+
+        <div class="flex gap-2">
+          <Button
+            disabled={props.proxyRunning || moving() || !props.storageInfo()}
+            onClick={handleChoose}
+            size="sm"
+            variant="secondary"
+          >
+            {moving() ? t("settings.storage.moving") : t("settings.storage.choose")}
+          </Button>
+          <Show when={separate()}>
+            <Button
+              disabled={props.proxyRunning || moving()}
+              onClick={handleReset}
+              size="sm"
+              variant="ghost"
+            >
+              {t("settings.storage.reset")}
+            </Button>
+          </Show>
+        </div>
+      </div>
+    </div>
+  );
+}
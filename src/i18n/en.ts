@@ -19,6 +19,20 @@ export const en = {
       environmentVariables: "Environment Variables",
     },
     configuredSummary: "{{configured}} of {{total}} configured",
+    endpointConflicts: {
+      fix: "Fix",
+      fixing: "Fixing...",
+      summary: "{{count}} agent(s) send requests somewhere other than {{expected}}",
+      title: "Agents point at different proxies",
+    },
+    envConflicts: {
+      fix: "Comment out",
+      fixAll: "Include lines outside ProxyPal blocks",
+      fixing: "Fixing...",
+      outsideManaged: "outside ProxyPal's block",
+      summary: "These variables send agent traffic somewhere other than {{expected}}",
+      title: "Environment variables override ProxyPal",
+    },
     noCliAgentsDetected: "No CLI agents detected",
     notInstalled: "Not Installed",
     pendingCount: "{{count}} pending",
@@ -38,7 +52,13 @@ export const en = {
       configurationFailed: "Configuration failed",
       connectionSuccessful: "Connection successful{{latency}}",
       connectProviderToConfigureAgents: "Connect at least one provider to configure agents",
+      endpointsFixed: "Agent endpoints updated",
+      endpointsNeedManualFix: "Some endpoints need a manual change",
+      envConflictsFixed: "Conflicting exports commented out",
+      envConflictsNeedManualFix: "Some variables need a manual change",
       failedToDetectCliAgents: "Failed to detect CLI agents",
+      failedToFixEndpoints: "Failed to fix agent endpoints",
+      failedToFixEnvConflicts: "Failed to fix environment variables",
       failedToUpdateShellProfile: "Failed to update shell profile",
       noModelsAvailable: "No models available",
       proxyMustRunToConfigureAgent: "The proxy must be running to configure this agent",
@@ -217,6 +237,9 @@ export const en = {
       disable: "Disable",
       download: "Download",
       enable: "Enable",
+      export: "Export",
+      import: "Import",
+      refresh: "Refresh token",
       testConnection: "Test Connection",
       testing: "Testing...",
       upload: "Upload",
@@ -232,6 +255,8 @@ export const en = {
       deletePrefix: "Delete",
       deleteSingleDescription: "You will need to re-authenticate with this provider.",
       deleteSingleTitle: "Delete Auth File?",
+      reauthConfirm:
+        "This proxy version can't refresh {{file}} in place. Sign in again in your browser to renew it?",
     },
     noAuthFiles: "No Auth Files",
     noAuthFilesDescription:
@@ -249,18 +274,27 @@ export const en = {
       authFileUploadedSuccessfully: "Auth file uploaded successfully",
       connectionFailed: "Connection failed",
       connectionToProviderSuccessful: "Connection to {{provider}} successful! ({{latency}}ms)",
+      credentialRenewed: "Credential renewed",
+      credentialsExported: "Exported {{count}} credentials to {{path}}",
+      credentialsImported: "Imported {{count}} credentials ({{skipped}} already present)",
       downloadedTo: "Downloaded to {{path}}",
       failedToDelete: "Failed to delete",
       failedToDeleteAll: "Failed to delete all",
       failedToDownload: "Failed to download",
+      failedToExportCredentials: "Failed to export credentials",
+      failedToImportCredentials: "Failed to import credentials",
       failedToLoadAuthFiles: "Failed to load auth files",
+      failedToRefreshToken: "Failed to refresh token",
       failedToToggleFile: "Failed to toggle file",
       failedToUploadFile: "Failed to upload file",
       kiroConnectionOk: "Kiro connection OK{{latency}}",
       kiroTestFailed: "Kiro test failed",
+      signInToRenew: "Sign in again in your browser to renew {{file}}",
       testFailed: "Test failed",
+      tokenRefreshed: "Token refreshed",
       unknownProviderCannotDetermineTestModel:
         "Unknown provider: {{provider}}. Cannot determine test model.",
+      validUntil: "Valid until {{date}}",
     },
   },
   commandPalette: {
@@ -414,6 +448,13 @@ export const en = {
       tokensCount: "{{count}} tokens",
       totalRequests: "Total Requests",
     },
+    lastSession: {
+      details: "Up {{uptime}} · {{requests}} requests, {{failures}} failed",
+      dismiss: "Dismiss",
+      lastErrors: "Last errors",
+      reason: "Reason",
+      title: "ProxyPal did not shut down cleanly last time",
+    },
     onboarding: {
       completeSteps: "Complete these steps to start saving",
       enableLocalProxy: "Enable the local proxy server",
@@ -422,6 +463,13 @@ export const en = {
       starting: "Starting...",
       startProxy: "Start the proxy",
     },
+    pendingOAuth: {
+      cancel: "Cancel",
+      failedToCancel: "Failed to cancel sign-in",
+      finishing: "finishing…",
+      title: "Sign-ins in progress",
+      waiting: "waiting for browser",
+    },
     providers: {
       addAnotherAccount: "Add another account",
       connectedCount: "{{count}} connected",
@@ -541,17 +589,39 @@ export const en = {
     manualCodeSubmit: "Submit Code",
     startOAuth: "Start OAuth",
   },
+  passphrase: {
+    confirmPlaceholder: "Repeat passphrase",
+    continue: "Continue",
+    mismatch: "Passphrases don't match",
+    placeholder: "Passphrase",
+    purposes: {
+      "export-credentials": {
+        description:
+          "Choose a passphrase to encrypt the exported credentials. You will need it to import them, and it can't be recovered.",
+        title: "Encrypt Credentials",
+      },
+      "import-credentials": {
+        description: "Enter the passphrase these credentials were exported with.",
+        title: "Decrypt Credentials",
+      },
+    },
+    tooShort: "Use at least {{count}} characters",
+  },
   requestMonitor: {
+    caughtUp: "{{count}} requests arrived while paused",
     clear: "Clear",
+    clearDemo: "Clear demo",
     costSaved: "{{cost}} saved",
     estimatedSavings: "Est. savings",
     last: "Last:",
     loadingHistory: "Loading history...",
+    pause: "Pause",
     proxyListeningOnPort: "Proxy listening on port {{port}}",
     proxyOffline: "Proxy is offline",
     proxyOfflineCompact: "Proxy offline",
     requestHistory: "Request History",
     requestsCount: "{{count}} requests",
+    resume: "Resume",
     saved: "saved",
     startProxyToTrack: "Start the proxy to begin tracking requests",
     today: "Today",
@@ -562,6 +632,19 @@ export const en = {
     yesterday: "Yesterday",
   },
   settings: {
+    amp: {
+      checkingKey: "Checking...",
+      checkKey: "Check key",
+      configured: "Amp configured",
+      configureFailed: "Failed to configure Amp",
+      configuring: "Setting up...",
+      keyAccepted: "Amp accepted the API key",
+      keyCheckFailed: "Failed to check the Amp API key",
+      keyRejected: "Amp API key not accepted",
+      setUp: "Set up Amp",
+      setUpDescription:
+        "Saves the key, writes the proxy's Amp routes and points Amp's settings.json at the proxy.",
+    },
     autoStartProxy: {
       description: "Start the proxy server when ProxyPal launches",
       label: "Auto-start proxy",
@@ -676,6 +759,21 @@ export const en = {
       title: "Reasoning Effort (GPT/Codex Models)",
       useModelSuffix: "Use",
     },
+    storage: {
+      choose: "Move data folder…",
+      configDir: "Config folder",
+      confirmReset: "Move history, logs, captures and benchmarks back into the config folder?",
+      dataDir: "Data folder",
+      description:
+        "Settings and auth files stay in the config folder. History, logs, captures and benchmarks can live elsewhere, such as a larger drive.",
+      free: "{{size}} free",
+      moveFailed: "Failed to move data folder",
+      moved: "Data folder moved",
+      moving: "Moving…",
+      reset: "Use config folder",
+      stopProxyFirst: "Stop the proxy to move the data folder.",
+      title: "Storage",
+    },
     tabs: {
       advanced: "Advanced",
       cloudflare: "Cloudflare",
@@ -880,6 +978,7 @@ export const en = {
     proxyRunning: "Proxy Running",
     proxyStopped: "Proxy Stopped",
     settings: "Settings",
+    skipVersion: "Skip this version",
     toggleTheme: "Toggle Theme",
     unpin: "Unpin",
     unpinSidebar: "Unpin sidebar",
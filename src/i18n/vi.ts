@@ -19,6 +19,20 @@ export const vi = {
       environmentVariables: "Biến môi trường",
     },
     configuredSummary: "Đã cấu hình {{configured}} / {{total}}",
+    endpointConflicts: {
+      fix: "Sửa",
+      fixing: "Đang sửa...",
+      summary: "{{count}} agent gửi request tới nơi khác ngoài {{expected}}",
+      title: "Các agent trỏ tới các proxy khác nhau",
+    },
+    envConflicts: {
+      fix: "Vô hiệu hóa",
+      fixAll: "Gồm cả các dòng ngoài khối ProxyPal",
+      fixing: "Đang sửa...",
+      outsideManaged: "ngoài khối của ProxyPal",
+      summary: "Các biến này gửi lưu lượng agent tới nơi khác ngoài {{expected}}",
+      title: "Biến môi trường đang ghi đè ProxyPal",
+    },
     noCliAgentsDetected: "Không phát hiện CLI agents nào",
     notInstalled: "Chưa cài đặt",
     pendingCount: "{{count}} đang chờ",
@@ -38,7 +52,13 @@ export const vi = {
       configurationFailed: "Cấu hình thất bại",
       connectionSuccessful: "Kết nối thành công{{latency}}",
       connectProviderToConfigureAgents: "Kết nối ít nhất một nhà cung cấp để cấu hình agents",
+      endpointsFixed: "Đã cập nhật endpoint của agents",
+      endpointsNeedManualFix: "Một số endpoint cần sửa thủ công",
+      envConflictsFixed: "Đã vô hiệu hóa các dòng export xung đột",
+      envConflictsNeedManualFix: "Một số biến cần sửa thủ công",
       failedToDetectCliAgents: "Không thể phát hiện CLI agents",
+      failedToFixEndpoints: "Không thể sửa endpoint của agents",
+      failedToFixEnvConflicts: "Không thể sửa biến môi trường",
       failedToUpdateShellProfile: "Cập nhật Shell profile thất bại",
       noModelsAvailable: "Không có model khả dụng",
       proxyMustRunToConfigureAgent: "Proxy phải đang chạy để cấu hình agent này",
@@ -216,6 +236,9 @@ export const vi = {
       disable: "Tắt",
       download: "Tải xuống",
       enable: "Bật",
+      export: "Xuất",
+      import: "Nhập",
+      refresh: "Làm mới token",
       testConnection: "Kiểm tra kết nối",
       testing: "Đang kiểm tra...",
       upload: "Tải lên",
@@ -231,6 +254,8 @@ export const vi = {
       deletePrefix: "Xóa",
       deleteSingleDescription: "Bạn sẽ cần xác thực lại với nhà cung cấp này.",
       deleteSingleTitle: "Xóa tệp xác thực?",
+      reauthConfirm:
+        "Phiên bản proxy này không thể làm mới {{file}} tại chỗ. Đăng nhập lại trong trình duyệt để gia hạn?",
     },
     noAuthFiles: "Chưa có tệp xác thực",
     noAuthFilesDescription:
@@ -248,18 +273,27 @@ export const vi = {
       authFileUploadedSuccessfully: "Tải lên tệp xác thực thành công",
       connectionFailed: "Kết nối thất bại",
       connectionToProviderSuccessful: "Kết nối {{provider}} thành công! ({{latency}}ms)",
+      credentialRenewed: "Đã gia hạn thông tin xác thực",
+      credentialsExported: "Đã xuất {{count}} thông tin xác thực vào {{path}}",
+      credentialsImported: "Đã nhập {{count}} thông tin xác thực ({{skipped}} đã có sẵn)",
       downloadedTo: "Đã tải xuống {{path}}",
       failedToDelete: "Xóa thất bại",
       failedToDeleteAll: "Xóa tất cả thất bại",
       failedToDownload: "Tải xuống thất bại",
+      failedToExportCredentials: "Xuất thông tin xác thực thất bại",
+      failedToImportCredentials: "Nhập thông tin xác thực thất bại",
       failedToLoadAuthFiles: "Tải tệp xác thực thất bại",
+      failedToRefreshToken: "Làm mới token thất bại",
       failedToToggleFile: "Chuyển đổi trạng thái tệp thất bại",
       failedToUploadFile: "Tải lên tệp thất bại",
       kiroConnectionOk: "Kết nối Kiro OK{{latency}}",
       kiroTestFailed: "Kiểm tra Kiro thất bại",
+      signInToRenew: "Đăng nhập lại trong trình duyệt để gia hạn {{file}}",
       testFailed: "Kiểm tra thất bại",
+      tokenRefreshed: "Đã làm mới token",
       unknownProviderCannotDetermineTestModel:
         "Nhà cung cấp không xác định: {{provider}}. Không thể xác định model kiểm tra.",
+      validUntil: "Có hiệu lực đến {{date}}",
     },
   },
   commandPalette: {
@@ -411,6 +445,13 @@ export const vi = {
       tokensCount: "{{count}} tokens",
       totalRequests: "Tổng yêu cầu",
     },
+    lastSession: {
+      details: "Chạy {{uptime}} · {{requests}} yêu cầu, {{failures}} thất bại",
+      dismiss: "Bỏ qua",
+      lastErrors: "Lỗi gần nhất",
+      reason: "Lý do",
+      title: "Lần trước ProxyPal không tắt đúng cách",
+    },
     onboarding: {
       completeSteps: "Hoàn thành các bước để bắt đầu tiết kiệm",
       enableLocalProxy: "Bật proxy server cục bộ",
@@ -419,6 +460,13 @@ export const vi = {
       starting: "Đang khởi động...",
       startProxy: "Khởi động proxy",
     },
+    pendingOAuth: {
+      cancel: "Hủy",
+      failedToCancel: "Không thể hủy đăng nhập",
+      finishing: "đang hoàn tất…",
+      title: "Đang đăng nhập",
+      waiting: "đang chờ trình duyệt",
+    },
     providers: {
       addAnotherAccount: "Thêm tài khoản khác",
       connectedCount: "{{count}} đã kết nối",
@@ -538,17 +586,39 @@ export const vi = {
     manualCodeSubmit: "Gửi mã",
     startOAuth: "Bắt đầu OAuth",
   },
+  passphrase: {
+    confirmPlaceholder: "Nhập lại cụm mật khẩu",
+    continue: "Tiếp tục",
+    mismatch: "Cụm mật khẩu không khớp",
+    placeholder: "Cụm mật khẩu",
+    purposes: {
+      "export-credentials": {
+        description:
+          "Chọn cụm mật khẩu để mã hóa thông tin xác thực được xuất. Bạn sẽ cần nó để nhập lại và không thể khôi phục nếu quên.",
+        title: "Mã hóa thông tin xác thực",
+      },
+      "import-credentials": {
+        description: "Nhập cụm mật khẩu đã dùng khi xuất thông tin xác thực này.",
+        title: "Giải mã thông tin xác thực",
+      },
+    },
+    tooShort: "Dùng ít nhất {{count}} ký tự",
+  },
   requestMonitor: {
+    caughtUp: "{{count}} yêu cầu đã đến khi tạm dừng",
     clear: "Xóa",
+    clearDemo: "Xóa dữ liệu demo",
     costSaved: "Tiết kiệm {{cost}}",
     estimatedSavings: "Tiết kiệm ước tính",
     last: "Gần nhất:",
     loadingHistory: "Đang tải lịch sử...",
+    pause: "Tạm dừng",
     proxyListeningOnPort: "Proxy đang lắng nghe cổng {{port}}",
     proxyOffline: "Proxy ngoại tuyến",
     proxyOfflineCompact: "Proxy ngoại tuyến",
     requestHistory: "Lịch sử yêu cầu",
     requestsCount: "{{count}} yêu cầu",
+    resume: "Tiếp tục",
     saved: "tiết kiệm",
     startProxyToTrack: "Khởi động proxy để bắt đầu theo dõi yêu cầu",
     today: "Hôm nay",
@@ -559,6 +629,19 @@ export const vi = {
     yesterday: "Hôm qua",
   },
   settings: {
+    amp: {
+      checkingKey: "Đang kiểm tra...",
+      checkKey: "Kiểm tra khóa",
+      configured: "Đã cấu hình Amp",
+      configureFailed: "Không thể cấu hình Amp",
+      configuring: "Đang thiết lập...",
+      keyAccepted: "Amp đã chấp nhận khóa API",
+      keyCheckFailed: "Không thể kiểm tra khóa API Amp",
+      keyRejected: "Khóa API Amp không được chấp nhận",
+      setUp: "Thiết lập Amp",
+      setUpDescription:
+        "Lưu khóa, ghi các tuyến Amp của proxy và trỏ settings.json của Amp tới proxy.",
+    },
     autoStartProxy: {
       description: "Tự động khởi động proxy khi mở ProxyPal",
       label: "Tự động khởi động proxy",
@@ -673,6 +756,21 @@ export const vi = {
       title: "Mức độ suy luận (Model GPT/Codex)",
       useModelSuffix: "Sử dụng",
     },
+    storage: {
+      choose: "Di chuyển thư mục dữ liệu…",
+      configDir: "Thư mục cấu hình",
+      confirmReset: "Chuyển lịch sử, log, bản ghi và benchmark về thư mục cấu hình?",
+      dataDir: "Thư mục dữ liệu",
+      description:
+        "Cài đặt và tệp xác thực luôn nằm trong thư mục cấu hình. Lịch sử, log, bản ghi và benchmark có thể đặt ở nơi khác, ví dụ ổ đĩa lớn hơn.",
+      free: "Còn trống {{size}}",
+      moveFailed: "Không thể di chuyển thư mục dữ liệu",
+      moved: "Đã di chuyển thư mục dữ liệu",
+      moving: "Đang di chuyển…",
+      reset: "Dùng thư mục cấu hình",
+      stopProxyFirst: "Hãy dừng proxy trước khi di chuyển thư mục dữ liệu.",
+      title: "Lưu trữ",
+    },
     tabs: {
       advanced: "Nâng cao",
       cloudflare: "Cloudflare",
@@ -877,6 +975,7 @@ export const vi = {
     proxyRunning: "Proxy đang chạy",
     proxyStopped: "Proxy đã dừng",
     settings: "Cài đặt",
+    skipVersion: "Bỏ qua phiên bản này",
     toggleTheme: "Đổi giao diện",
     unpin: "Bỏ ghim",
     unpinSidebar: "Bỏ ghim thanh bên",
@@ -19,6 +19,20 @@ export const zhCN = {
       environmentVariables: "环境变量",
     },
     configuredSummary: "已配置 {{configured}} / {{total}}",
+    endpointConflicts: {
+      fix: "修复",
+      fixing: "修复中...",
+      summary: "{{count}} 个 agent 的请求未发往 {{expected}}",
+      title: "Agents 指向了不同的代理",
+    },
+    envConflicts: {
+      fix: "注释掉",
+      fixAll: "包括 ProxyPal 区块之外的行",
+      fixing: "修复中...",
+      outsideManaged: "位于 ProxyPal 区块之外",
+      summary: "这些变量会把 agent 流量发往 {{expected}} 以外的地址",
+      title: "环境变量覆盖了 ProxyPal",
+    },
     noCliAgentsDetected: "未检测到 CLI agents",
     notInstalled: "未安装",
     pendingCount: "待配置 {{count}}",
@@ -38,7 +52,13 @@ export const zhCN = {
       configurationFailed: "配置失败",
       connectionSuccessful: "连接成功{{latency}}",
       connectProviderToConfigureAgents: "请至少连接一个提供商后再配置 agents",
+      endpointsFixed: "已更新 agent 端点",
+      endpointsNeedManualFix: "部分端点需要手动修改",
+      envConflictsFixed: "已注释掉冲突的导出",
+      envConflictsNeedManualFix: "部分变量需要手动修改",
       failedToDetectCliAgents: "检测 CLI agents 失败",
+      failedToFixEndpoints: "修复 agent 端点失败",
+      failedToFixEnvConflicts: "修复环境变量失败",
       failedToUpdateShellProfile: "更新 Shell 配置文件失败",
       noModelsAvailable: "无可用模型",
       proxyMustRunToConfigureAgent: "配置该 agent 需要代理处于运行状态",
@@ -215,6 +235,9 @@ export const zhCN = {
       disable: "禁用",
       download: "下载",
       enable: "启用",
+      export: "导出",
+      import: "导入",
+      refresh: "刷新令牌",
       testConnection: "测试连接",
       testing: "测试中...",
       upload: "上传",
@@ -230,6 +253,7 @@ export const zhCN = {
       deletePrefix: "删除",
       deleteSingleDescription: "你需要重新认证该提供商。",
       deleteSingleTitle: "删除认证文件？",
+      reauthConfirm: "此代理版本无法原地刷新 {{file}}。要在浏览器中重新登录以更新吗？",
     },
     noAuthFiles: "暂无认证文件",
     noAuthFilesDescription: "连接提供商后 OAuth 凭据会显示在这里，或可手动上传凭据文件。",
@@ -246,17 +270,26 @@ export const zhCN = {
       authFileUploadedSuccessfully: "认证文件上传成功",
       connectionFailed: "连接失败",
       connectionToProviderSuccessful: "连接 {{provider}} 成功！（{{latency}}ms）",
+      credentialRenewed: "凭据已更新",
+      credentialsExported: "已将 {{count}} 个凭据导出到 {{path}}",
+      credentialsImported: "已导入 {{count}} 个凭据（{{skipped}} 个已存在）",
       downloadedTo: "已下载到 {{path}}",
       failedToDelete: "删除失败",
       failedToDeleteAll: "删除全部失败",
       failedToDownload: "下载失败",
+      failedToExportCredentials: "导出凭据失败",
+      failedToImportCredentials: "导入凭据失败",
       failedToLoadAuthFiles: "加载认证文件失败",
+      failedToRefreshToken: "刷新令牌失败",
       failedToToggleFile: "切换文件状态失败",
       failedToUploadFile: "上传文件失败",
       kiroConnectionOk: "Kiro 连接正常{{latency}}",
       kiroTestFailed: "Kiro 测试失败",
+      signInToRenew: "请在浏览器中重新登录以更新 {{file}}",
       testFailed: "测试失败",
+      tokenRefreshed: "令牌已刷新",
       unknownProviderCannotDetermineTestModel: "未知提供商：{{provider}}。无法确定测试模型。",
+      validUntil: "有效期至 {{date}}",
     },
   },
   commandPalette: {
@@ -406,6 +439,13 @@ export const zhCN = {
       tokensCount: "{{count}} tokens",
       totalRequests: "总请求数",
     },
+    lastSession: {
+      details: "运行 {{uptime}} · {{requests}} 个请求，{{failures}} 个失败",
+      dismiss: "关闭",
+      lastErrors: "最近的错误",
+      reason: "原因",
+      title: "ProxyPal 上次未正常退出",
+    },
     onboarding: {
       completeSteps: "完成以下步骤即可开始节省成本",
       enableLocalProxy: "启用本地代理服务",
@@ -414,6 +454,13 @@ export const zhCN = {
       starting: "启动中...",
       startProxy: "启动代理",
     },
+    pendingOAuth: {
+      cancel: "取消",
+      failedToCancel: "无法取消登录",
+      finishing: "正在完成…",
+      title: "进行中的登录",
+      waiting: "等待浏览器",
+    },
     providers: {
       addAnotherAccount: "添加另一个账号",
       connectedCount: "已连接 {{count}} 个",
@@ -532,17 +579,38 @@ export const zhCN = {
     manualCodeSubmit: "提交授权码",
     startOAuth: "开始 OAuth",
   },
+  passphrase: {
+    confirmPlaceholder: "再次输入密码短语",
+    continue: "继续",
+    mismatch: "密码短语不一致",
+    placeholder: "密码短语",
+    purposes: {
+      "export-credentials": {
+        description: "设置用于加密导出凭据的密码短语。导入时需要它，且遗忘后无法恢复。",
+        title: "加密凭据",
+      },
+      "import-credentials": {
+        description: "输入导出这些凭据时使用的密码短语。",
+        title: "解密凭据",
+      },
+    },
+    tooShort: "至少使用 {{count}} 个字符",
+  },
   requestMonitor: {
+    caughtUp: "暂停期间收到 {{count}} 个请求",
     clear: "清空",
+    clearDemo: "清除演示数据",
     costSaved: "已节省 {{cost}}",
     estimatedSavings: "预计节省",
     last: "最近：",
     loadingHistory: "正在加载历史...",
+    pause: "暂停",
     proxyListeningOnPort: "代理正在监听端口 {{port}}",
     proxyOffline: "代理离线",
     proxyOfflineCompact: "代理离线",
     requestHistory: "请求历史",
     requestsCount: "{{count}} 个请求",
+    resume: "继续",
     saved: "节省",
     startProxyToTrack: "启动代理后开始追踪请求",
     today: "今天",
@@ -553,6 +621,18 @@ export const zhCN = {
     yesterday: "昨天",
   },
   settings: {
+    amp: {
+      checkingKey: "检查中...",
+      checkKey: "检查密钥",
+      configured: "Amp 已配置",
+      configureFailed: "配置 Amp 失败",
+      configuring: "设置中...",
+      keyAccepted: "Amp 已接受 API 密钥",
+      keyCheckFailed: "检查 Amp API 密钥失败",
+      keyRejected: "Amp API 密钥未被接受",
+      setUp: "设置 Amp",
+      setUpDescription: "保存密钥，写入代理的 Amp 路由，并将 Amp 的 settings.json 指向代理。",
+    },
     autoStartProxy: {
       description: "启动 ProxyPal 时自动启动代理服务",
       label: "自动启动代理",
@@ -665,6 +745,21 @@ export const zhCN = {
       title: "推理强度（GPT/Codex 模型）",
       useModelSuffix: "使用",
     },
+    storage: {
+      choose: "移动数据文件夹…",
+      configDir: "配置文件夹",
+      confirmReset: "将历史记录、日志、抓包和基准测试移回配置文件夹？",
+      dataDir: "数据文件夹",
+      description:
+        "设置和认证文件始终保存在配置文件夹中。历史记录、日志、抓包和基准测试可以放在其他位置，例如更大的磁盘。",
+      free: "可用 {{size}}",
+      moveFailed: "移动数据文件夹失败",
+      moved: "数据文件夹已移动",
+      moving: "正在移动…",
+      reset: "使用配置文件夹",
+      stopProxyFirst: "请先停止代理再移动数据文件夹。",
+      title: "存储",
+    },
     tabs: {
       advanced: "高级",
       cloudflare: "Cloudflare",
@@ -868,6 +963,7 @@ export const zhCN = {
     proxyRunning: "代理运行中",
     proxyStopped: "代理已停止",
     settings: "设置",
+    skipVersion: "跳过此版本",
     toggleTheme: "切换主题",
     unpin: "取消固定",
     unpinSidebar: "取消固定侧边栏",
@@ -1,6 +1,8 @@
 /* @refresh reload */
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import { render } from "solid-js/web";
 import App from "./App";
+import { MiniMonitor } from "./components/MiniMonitor";
 import { I18nProvider, type Locale } from "./i18n";
 import { appStore } from "./stores/app";
 import "./styles/index.css";
@@ -11,7 +13,7 @@ render(
       locale={() => appStore.config().locale}
       setLocale={(locale: Locale) => appStore.setLocale(locale)}
     >
-      <App />
+      {getCurrentWindow().label === "mini-monitor" ? <MiniMonitor /> : <App />}
     </I18nProvider>
   ),
   document.getElementById("root") as HTMLElement,
@@ -0,0 +1,45 @@
+import type { AgentModelPrefs, FilePreview } from "./agents";
+import { invoke } from "./invoke";
+import type { AmpModelMapping } from "./models";
+
+// ============================================
+// Agent setup bundles ("team setup")
+// ============================================
+
+// A client key by label only; importing issues a fresh local key
+export interface BundleClientKey {
+  agentId?: string | null;
+  name: string;
+}
+
+// Shareable agent setup. Never holds key values or credentials.
+export interface AgentBundle {
+  agentModelPreferences: Record<string, AgentModelPrefs>;
+  agents: string[]; // Agents to configure on import
+  clientKeys: BundleClientKey[];
+  exportedAt: number;
+  modelMappings: AmpModelMapping[];
+  version: number;
+}
+
+export interface AgentBundleImport {
+  clientKeysCreated: string[];
+  configDiff: string; // Unified diff of the shared settings, empty when unchanged
+  configured: string[];
+  dryRun: boolean;
+  errors: string[];
+  previews: FilePreview[]; // Agent config files, dry run only
+  skipped: string[]; // Agents in the bundle that are not installed here
+}
+
+export async function exportAgentBundle(path: string): Promise<AgentBundle> {
+  return invoke("export_agent_bundle", { path });
+}
+
+// Dry run unless `dryRun` is false; show the result before applying
+export async function importAgentBundle(
+  path: string,
+  dryRun = true,
+): Promise<AgentBundleImport> {
+  return invoke("import_agent_bundle", { dryRun, path });
+}
@@ -0,0 +1,94 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+import { invoke } from "./invoke";
+
+// ============================================
+// Agent endpoint audit
+// ============================================
+
+export interface AgentEndpoint {
+  agentId: string;
+  host: string; // Loopback names are reported as 127.0.0.1
+  keyStale: boolean; // Carries a key the proxy no longer accepts
+  port: number;
+  source: string; // Config file or shell profile path, or "environment"
+  url: string;
+  variable?: string; // Env var, for environment and shell profile sources
+}
+
+export interface AgentEndpointGroup {
+  agentIds: string[];
+  host: string;
+  matchesProxy: boolean; // The endpoint this ProxyPal serves
+  port: number;
+}
+
+export type EndpointConflictKind = "port" | "host" | "stale-key";
+
+export interface EndpointConflict {
+  agentId: string;
+  expected: string;
+  found: string;
+  kind: EndpointConflictKind;
+  source: string;
+}
+
+export interface AgentEndpointAudit {
+  conflicts: EndpointConflict[];
+  endpoints: AgentEndpoint[];
+  expected: string;
+  groups: AgentEndpointGroup[];
+  outliers: string[]; // Agents fixAgentEndpoints would reconfigure
+}
+
+export interface AgentEndpointFix {
+  agentId: string;
+  detail?: string;
+  source: string;
+  status: "updated" | "manual" | "failed";
+}
+
+export async function auditAgentEndpoints(): Promise<AgentEndpointAudit> {
+  return invoke("audit_agent_endpoints");
+}
+
+// Reconfigure the outliers of a fresh audit, or only `agentIds`
+export async function fixAgentEndpoints(agentIds?: string[]): Promise<AgentEndpointFix[]> {
+  return invoke("fix_agent_endpoints", { agentIds });
+}
+
+// An agent env var (ANTHROPIC_*, OPENAI_BASE_URL, ...) set to a URL other than this proxy's
+export interface EnvConflict {
+  expected: string;
+  line?: number | null; // 1-based, for shell profile sources
+  managed: boolean; // Inside a block ProxyPal wrote; only these are fixed by default
+  source: string; // Shell profile path, or "environment"
+  value: string; // Redacted
+  variable: string;
+}
+
+export interface EnvConflictFix {
+  detail?: string;
+  line?: number | null;
+  source: string;
+  status: "updated" | "manual" | "failed";
+  variable: string;
+}
+
+export async function scanEnvironmentConflicts(): Promise<EnvConflict[]> {
+  return invoke("scan_environment_conflicts");
+}
+
+// Comments out conflicting exports; lines outside ProxyPal blocks only with outsideManaged
+export async function fixEnvironmentConflicts(outsideManaged?: boolean): Promise<EnvConflictFix[]> {
+  return invoke("fix_environment_conflicts", { outsideManaged });
+}
+
+// Emitted after the port changes when some agents still point elsewhere
+export async function onAgentEndpointConflicts(
+  callback: (audit: AgentEndpointAudit) => void,
+): Promise<UnlistenFn> {
+  return listen<AgentEndpointAudit>("agent-endpoint-conflicts", (event) => {
+    callback(event.payload);
+  });
+}
@@ -1,30 +1,85 @@
-import { invoke } from "@tauri-apps/api/core";
-
+import { invoke } from "./invoke";
 import type { AvailableModel } from "./models";
 
+// Wire protocol a client speaks to the proxy
+export type ApiProtocol = "openai" | "anthropic" | "gemini";
+
+// Where the client connecting to the proxy runs
+export type ConnectionTarget = "host" | "docker";
+
+// How containers on this machine reach the proxy
+export interface DockerHost {
+  addHostFlag?: string; // `docker run` flag Linux needs for host.docker.internal
+  host: string; // "host.docker.internal", or the bridge gateway on Linux
+  installed: boolean; // Only offer container snippets when true
+  warning?: string; // e.g. the proxy only listens on 127.0.0.1
+}
+
+export interface ConnectionInfo {
+  apiKey: string;
+  authHeader: string; // "Authorization: Bearer", "x-api-key" or "x-goog-api-key"
+  baseUrl: string; // What SDKs expect as their base URL
+  docker?: DockerHost; // Set for the Docker target
+  env: { name: string; value: string }[];
+  examplePath: string; // A request path relative to baseUrl
+  protocol: ApiProtocol;
+  snippet: string; // Shell lines setting env (PowerShell on Windows, POSIX in a container)
+}
+
+export async function getConnectionInfo(
+  protocol?: ApiProtocol,
+  target?: ConnectionTarget,
+): Promise<ConnectionInfo[]> {
+  return invoke("get_connection_info", { protocol, target });
+}
+
+export async function getDockerHost(): Promise<DockerHost> {
+  return invoke("get_docker_host");
+}
+
+// devcontainer.json fragment (remoteEnv, plus runArgs on Linux)
+export async function getDevcontainerSnippet(agentId?: string): Promise<string> {
+  return invoke("get_devcontainer_snippet", { agentId });
+}
+
 // CLI Agent Types and Functions
 export interface AgentStatus {
   configPath?: string;
   configType: "env" | "file" | "both" | "config";
   configured: boolean;
+  configuredStale?: boolean; // Model preferences changed since the config was written
   description: string;
   docsUrl: string;
   id: string;
   installed: boolean;
   logo: string;
   name: string;
+  protocol: ApiProtocol;
 }
 
 export interface AgentConfigResult {
   authPath?: string;
+  backupPath?: string;
   configPath?: string;
   configType: "env" | "file" | "both" | "config";
+  docker?: DockerHost; // Set for the Docker target; previews go inside the container
+  dryRun?: boolean;
   instructions: string;
   modelsConfigured?: number;
+  previews?: FilePreview[];
+  proxyConfigPath?: string; // configureAmp only
   shellConfig?: string;
   success: boolean;
 }
 
+// Would-be change to a config file (dry-run mode)
+export interface FilePreview {
+  changed: boolean;
+  diff: string; // unified diff against the current contents
+  exists: boolean;
+  path: string;
+}
+
 export async function detectCliAgents(): Promise<AgentStatus[]> {
   return invoke("detect_cli_agents");
 }
@@ -32,16 +87,41 @@ export async function detectCliAgents(): Promise<AgentStatus[]> {
 export async function configureCliAgent(
   agentId: string,
   models: AvailableModel[],
+  dryRun?: boolean,
+  target?: ConnectionTarget,
 ): Promise<AgentConfigResult> {
-  return invoke("configure_cli_agent", { agentId, models });
+  return invoke("configure_cli_agent", { agentId, dryRun, models, target });
+}
+
+// Per-agent model preferences (empty fields fall back to live proxy models)
+// How Gemini CLI authenticates against the proxy; API key mode works from any host
+export type GeminiAuthMode = "oauth" | "api-key";
+
+export interface AgentModelPrefs {
+  geminiAuthMode?: GeminiAuthMode; // Gemini CLI only; unset means OAuth
+  primaryModel?: string;
+  reasoningEffort?: string;
+  smallModel?: string;
+}
+
+export async function getAgentModelPreferences(): Promise<Record<string, AgentModelPrefs>> {
+  return invoke("get_agent_model_preferences");
+}
+
+export async function setAgentModelPreferences(
+  agentId: string,
+  prefs: AgentModelPrefs,
+): Promise<void> {
+  return invoke("set_agent_model_preferences", { agentId, prefs });
 }
 
 export async function getShellProfilePath(): Promise<string> {
   return invoke("get_shell_profile_path");
 }
 
-export async function appendToShellProfile(content: string): Promise<string> {
-  return invoke("append_to_shell_profile", { content });
+// Returns the profile path, or a unified diff when dryRun is set
+export async function appendToShellProfile(content: string, dryRun?: boolean): Promise<string> {
+  return invoke("append_to_shell_profile", { content, dryRun });
 }
 
 // Test agent connection
@@ -0,0 +1,68 @@
+import { invoke } from "./invoke";
+import type { AgentConfigResult } from "./agents";
+import type { ProviderTestResult } from "./health";
+
+// ============================================
+// Amp provider pools
+// ============================================
+
+export interface AmpPoolMember {
+  model: string; // Model the provider serves the pool's requests with
+  provider: string; // Custom provider id, or a connected provider ("claude", "gemini", ...)
+  weight: number; // 1-10; turns per round, custom providers only
+}
+
+export interface AmpPool {
+  enabled: boolean;
+  id: string; // Empty when creating; the backend assigns one
+  members: AmpPoolMember[];
+  name: string;
+  routes: string[]; // Amp models sent to this pool instead of their flat mapping
+}
+
+export interface AmpPoolUsage {
+  estimatedCost: number;
+  lastUsed: number; // ms timestamp
+  name: string;
+  poolId: string;
+  requests: number;
+  successCount: number;
+  tokensIn: number;
+  tokensOut: number;
+}
+
+export async function getAmpPools(): Promise<AmpPool[]> {
+  return invoke("get_amp_pools");
+}
+
+// Rejected when a member's provider is missing or not connected, or when an
+// Amp model is already routed by another pool. Takes effect on proxy restart.
+export async function saveAmpPool(pool: AmpPool): Promise<AmpPool[]> {
+  return invoke("save_amp_pool", { pool });
+}
+
+export async function deleteAmpPool(id: string): Promise<AmpPool[]> {
+  return invoke("delete_amp_pool", { id });
+}
+
+export async function getAmpPoolUsage(): Promise<AmpPoolUsage[]> {
+  return invoke("get_amp_pool_usage");
+}
+
+// ============================================
+// Amp onboarding
+// ============================================
+
+// Asks Amp, through the running proxy, whether it accepts the saved Amp API key
+export async function validateAmpApiKey(): Promise<ProviderTestResult> {
+  return invoke("validate_amp_api_key");
+}
+
+// Saves the key and routing mode, writes the proxy config and Amp's
+// settings.json. Rejected when the routing mode leaves Amp's models without a route.
+export async function configureAmp(
+  ampApiKey?: string,
+  routingMode?: string,
+): Promise<AgentConfigResult> {
+  return invoke("configure_amp", { ampApiKey, routingMode });
+}
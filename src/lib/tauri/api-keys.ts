@@ -1,4 +1,5 @@
-import { invoke } from "@tauri-apps/api/core";
+import type { Provider } from "./auth";
+import { invoke } from "./invoke";
 
 // Model mapping with alias and name (used by Claude and OpenAI-compatible providers)
 export interface ModelMapping {
@@ -36,12 +37,15 @@ export interface CodexApiKey {
   proxyUrl?: string;
 }
 
+// One key of an OpenAI-compatible provider
+export interface OpenAICompatibleApiKeyEntry {
+  apiKey: string;
+  proxyUrl?: string;
+}
+
 // OpenAI-Compatible Provider structure
 export interface OpenAICompatibleProvider {
-  apiKeyEntries: Array<{
-    apiKey: string;
-    proxyUrl?: string;
-  }>;
+  apiKeyEntries: OpenAICompatibleApiKeyEntry[];
   baseUrl: string;
   headers?: Record<string, string>;
   models?: ModelMapping[];
@@ -150,3 +154,17 @@ export async function addOpenAICompatibleProvider(
 export async function deleteOpenAICompatibleProvider(index: number): Promise<void> {
   return invoke("delete_openai_compatible_provider", { index });
 }
+
+// Keys of a first-class API-key provider (DeepSeek, GLM)
+export async function getProviderApiKeys(
+  provider: Provider,
+): Promise<OpenAICompatibleApiKeyEntry[]> {
+  return invoke("get_provider_api_keys", { provider });
+}
+
+export async function setProviderApiKeys(
+  provider: Provider,
+  keys: OpenAICompatibleApiKeyEntry[],
+): Promise<void> {
+  return invoke("set_provider_api_keys", { keys, provider });
+}
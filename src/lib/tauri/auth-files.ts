@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "./invoke";
 
 // Auth file entry from Management API
 export interface AuthFile {
@@ -7,6 +7,7 @@ export interface AuthFile {
   createdAt?: string;
   disabled: boolean;
   email?: string;
+  expiresAt?: string; // Token expiry read from the credential file
   failureCount?: number;
   id: string;
   label?: string;
@@ -14,7 +15,9 @@ export interface AuthFile {
   modtime?: string;
   name: string;
   path?: string;
+  plan?: string; // Plan or tier read from the credential file
   provider: string;
+  refreshable?: boolean; // refreshProviderToken can renew it in place
   runtimeOnly: boolean;
   size?: number;
   source?: "file" | "memory";
@@ -48,3 +51,24 @@ export async function downloadAuthFile(fileId: string, filename: string): Promis
 export async function deleteAllAuthFiles(): Promise<void> {
   return invoke("delete_all_auth_files");
 }
+
+// What refreshProviderToken did for one credential file
+export interface CredentialRefresh {
+  error: string | null; // Why a "failed" or "reauth-needed" refresh failed
+  expiresAt: string | null; // New token expiry, once refreshed
+  file: string;
+  oauthState: string | null; // Poll with pollOAuthStatus; the file is replaced on completion
+  provider: string;
+  status: "failed" | "reauth-needed" | "reauth-started" | "refreshed";
+}
+
+// Renew a credential file (or every file of a provider) without disconnecting it.
+// Files the proxy has no way to refresh come back as "reauth-needed".
+export async function refreshProviderToken(target: string): Promise<CredentialRefresh[]> {
+  return invoke("refresh_provider_token", { target });
+}
+
+// Sign a "reauth-needed" credential file in again; a browser sign-in replaces it
+export async function reauthCredential(file: string): Promise<CredentialRefresh> {
+  return invoke("reauth_credential", { file });
+}
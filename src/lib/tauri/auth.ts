@@ -1,6 +1,7 @@
-import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
+import { invoke } from "./invoke";
+
 // OAuth management
 export type Provider =
   | "claude"
@@ -11,7 +12,28 @@ export type Provider =
   | "vertex"
   | "kiro"
   | "antigravity"
-  | "kimi";
+  | "kimi"
+  | "deepseek"
+  | "zhipu";
+
+export type ProviderAuthKind = "oauth" | "device" | "service-account" | "api-key";
+
+export interface ProviderMeta {
+  apiBaseUrl: string | null; // OpenAI-compatible endpoint, for API-key providers
+  authKind: ProviderAuthKind;
+  credentialFilePrefixes: string[];
+  deviceCode: boolean; // Also offers a device-code flow
+  displayName: string;
+  docsUrl: string;
+  id: Provider;
+  loginPage: string | null; // Login page served by the proxy itself (Kiro)
+  logo: string;
+  managementAuthEndpoint: string | null;
+}
+
+export async function getProviders(): Promise<ProviderMeta[]> {
+  return invoke("get_providers");
+}
 
 export async function openOAuth(provider: Provider): Promise<string> {
   return invoke("open_oauth", { provider });
@@ -42,6 +64,25 @@ export async function pollOAuthStatus(oauthState: string): Promise<boolean> {
   return invoke("poll_oauth_status", { oauthState });
 }
 
+// A sign-in started with openOAuth, getOAuthUrl or getDeviceCode
+export interface PendingOAuth {
+  createdAt: number; // Unix millis
+  expiresAt: number; // Dropped after this
+  provider: Provider;
+  state: string;
+  status: "pending" | "callback-received" | "completed";
+}
+
+// Sign-ins still in progress, oldest first
+export async function listPendingOAuth(): Promise<PendingOAuth[]> {
+  return invoke("list_pending_oauth");
+}
+
+// Cancels one sign-in; the others carry on
+export async function cancelOAuth(oauthState: string): Promise<void> {
+  return invoke("cancel_oauth", { oauthState });
+}
+
 export async function completeOAuth(provider: Provider, code: string): Promise<AuthStatus> {
   return invoke("complete_oauth", { code, provider });
 }
@@ -57,13 +98,16 @@ export async function importVertexCredential(filePath: string): Promise<AuthStat
 export interface AuthStatus {
   antigravity: number;
   claude: number;
+  deepseek: number;
   gemini: number;
   iflow: number;
   kimi: number;
   kiro: number;
   openai: number;
   qwen: number;
+  verified?: Record<string, boolean>; // Last post-auth check per provider; missing until one ran
   vertex: number;
+  zhipu: number;
 }
 
 export async function getAuthStatus(): Promise<AuthStatus> {
@@ -74,10 +118,28 @@ export async function refreshAuthStatus(): Promise<AuthStatus> {
   return invoke("refresh_auth_status");
 }
 
+// Result of a post-auth check request sent through the proxy
+export interface ProviderVerification {
+  checkedAt: number;
+  guidance?: string | null;
+  issue?: "noProject" | "apiDisabled" | "billingDisabled" | "permissionDenied" | "unauthorized" | "unknown" | null;
+  link?: string | null; // Where to fix the issue
+  model: string; // Model the check was sent to
+  provider: string;
+  status?: number | null;
+  verified: boolean;
+}
+
+// Runs automatically after an account is added; call again after fixing setup
+export async function verifyProvider(provider: Provider): Promise<ProviderVerification> {
+  return invoke("verify_provider", { provider });
+}
+
 // Event listeners
 export interface OAuthCallback {
   code: string;
   provider: Provider;
+  state: string;
 }
 
 export async function onAuthStatusChanged(
@@ -0,0 +1,49 @@
+import { invoke } from "./invoke";
+
+// ============================================
+// Provider benchmark
+// ============================================
+
+export interface BenchmarkResult {
+  error?: string | null;
+  estimatedCost?: number | null;
+  model: string;
+  provider: string;
+  response?: string | null; // Reply text, truncated
+  status?: number | null; // Missing when the request never got a response
+  tokensIn?: number | null;
+  tokensOut?: number | null;
+  totalMs?: number | null;
+  ttfbMs?: number | null; // Time to the first streamed byte
+}
+
+export interface BenchmarkRun {
+  id: string;
+  maxTokens: number;
+  prompt: string;
+  results: BenchmarkResult[];
+  timestamp: number;
+}
+
+export interface BenchmarkOptions {
+  confirmed?: boolean; // Required above 5 models or 256 max tokens
+  maxTokens?: number; // Default 64
+  prompt?: string; // Default: a fixed one-sentence task
+}
+
+export async function runProviderBenchmark(
+  models: string[],
+  options: BenchmarkOptions = {},
+): Promise<BenchmarkRun> {
+  return invoke("run_provider_benchmark", {
+    confirmed: options.confirmed,
+    maxTokens: options.maxTokens,
+    models,
+    prompt: options.prompt,
+  });
+}
+
+// Saved runs, newest first
+export async function getBenchmarkHistory(limit?: number): Promise<BenchmarkRun[]> {
+  return invoke("get_benchmark_history", { limit });
+}
@@ -0,0 +1,69 @@
+import { invoke } from "./invoke";
+
+// ============================================
+// Request replay
+// ============================================
+
+// Captures are the proxy's request log files (request logging on) plus
+// replays saved by ProxyPal
+export interface Capture {
+  body: string;
+  durationMs?: number | null;
+  headers: [string, string][]; // Auth headers are never stored
+  id: string;
+  method: string;
+  overrides?: ReplayOverrides | null;
+  path: string;
+  replayOf?: string | null;
+  responseBody: string;
+  responseStatus?: number | null;
+  timestamp: number;
+}
+
+export interface CaptureSummary {
+  id: string;
+  method: string;
+  path: string;
+  replayOf?: string | null;
+  responseStatus?: number | null;
+  timestamp: number;
+}
+
+export interface ReplayOverrides {
+  maxTokens?: number | null;
+  model?: string | null;
+}
+
+export interface ResponseDiffSummary {
+  identical: boolean;
+  linesAdded: number;
+  linesRemoved: number;
+  newBytes: number;
+  newStatus: number;
+  oldBytes: number;
+  oldStatus?: number | null;
+  summary: string;
+}
+
+export interface ReplayResult {
+  captureId: string; // The new capture
+  diff: ResponseDiffSummary;
+  latencyMs: number;
+  replayOf: string;
+  status: number;
+}
+
+export async function listCaptures(limit?: number): Promise<CaptureSummary[]> {
+  return invoke("list_captures", { limit });
+}
+
+export async function getCapture(id: string): Promise<Capture> {
+  return invoke("get_capture", { id });
+}
+
+export async function replayRequest(
+  captureId: string,
+  overrides?: ReplayOverrides,
+): Promise<ReplayResult> {
+  return invoke("replay_request", { captureId, overrides });
+}
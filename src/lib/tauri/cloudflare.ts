@@ -1,6 +1,7 @@
-import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
+import { invoke } from "./invoke";
+
 // ============================================================================
 // Cloudflare Tunnel
 // ============================================================================
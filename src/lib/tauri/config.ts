@@ -1,50 +1,167 @@
-import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
+import type { AgentModelPrefs } from "./agents";
+import type { AmpPool } from "./amp-pools";
+import type { AuthStatus } from "./auth";
 import type { CloudflareConfig } from "./cloudflare";
+import type { FailoverRule } from "./failover";
+import { invoke } from "./invoke";
+import type { ModelCap } from "./model-caps";
 import type { AmpModelMapping, AmpOpenAIProvider, CopilotConfig } from "./models";
+import type { OnboardingState } from "./onboarding";
+import type { ProviderRateLimit } from "./rate-limits";
+import type { RequestWatch } from "./request-watches";
+import type { ProxySchedule } from "./schedule";
+import type { NotificationSettings } from "./settings";
 import type { SshConfig } from "./ssh";
+import type { WebhookConfig } from "./webhooks";
+
+// Window position and size in physical pixels, from before it was maximized
+export interface WindowGeometry {
+  height: number;
+  maximized?: boolean;
+  width: number;
+  x: number;
+  y: number;
+}
 
 // Config
+// App-level proxy for ProxyPal's own outbound requests; the sidecar's upstream proxy is AppConfig.proxyUrl
+export interface NetworkProxy {
+  mode: "manual" | "none" | "system"; // system: HTTP(S)_PROXY / NO_PROXY from the environment
+  noProxy?: string[]; // Manual mode: hosts, domains (.corp.example) or CIDRs reached directly
+  url?: string; // Manual mode: http://, https:// or socks5:// URL
+}
+
+// Port the proxy listens on unless configured otherwise; mirrors DEFAULT_PORT in config.rs
+export const DEFAULT_PORT = 8317;
+
 export interface AppConfig {
+  agentModelPreferences?: Record<string, AgentModelPrefs>;
   ampApiKey: string;
   ampModelMappings: AmpModelMapping[];
   ampOpenaiProvider?: AmpOpenAIProvider; // Deprecated: for migration only
   ampOpenaiProviders: AmpOpenAIProvider[]; // Array of custom providers
+  ampPools?: AmpPool[]; // Edited with saveAmpPool; save_config keeps the stored list
   ampRoutingMode: string; // "mappings" or "openai"
+  autoCheckAppUpdates?: boolean; // Look for a new ProxyPal release once a day (default true)
   autoStart: boolean;
+  clientKeys?: ClientKey[]; // Extra proxy API keys issued per agent or machine
+  closeToTrayNoticeShown?: boolean; // One-time "still running in the tray" notice
   cloudflareConfigs?: CloudflareConfig[];
   commercialMode?: boolean; // Disable request logging for lower memory usage
+  controlApiPort?: number | null; // JSON control API for launchers at http://127.0.0.1:<port>/
+  controlSocketEnabled?: boolean; // JSON-RPC control socket for scripts: <config dir>/control.sock, or the proxypal-control named pipe on Windows
   copilot: CopilotConfig;
+  costCurrency?: string; // ISO code costs are shown in (default USD)
+  costCurrencyRate?: number; // Units of costCurrency per US dollar, fixed (default 1)
+  dataDir?: string | null; // History, logs, captures and benchmarks; config dir when unset. Changed with migrateDataDir
   debug: boolean;
+  demoModeEnabled?: boolean; // Allow synthetic demo traffic in release builds
   disableControlPanel?: boolean; // Hide CLIProxyAPI's web management UI
+  dismissedAppVersion?: string | null; // Release not announced again; set with dismissAppUpdate
+  eventStreamPort?: number | null; // ws://127.0.0.1:<port>/?token=<managementKey>
+  failoverRules?: FailoverRule[]; // Switch Amp's models to a backup provider while one is unhealthy (Amp routes only)
   forceModelMappings: boolean; // Force model mappings to take precedence over local API keys
   geminiThinkingInjection?: boolean; // Inject thinking config for Gemini 3 models
+  healthCheckIntervalSecs?: number; // Background provider health checks while the proxy runs (0 = off)
+  historyMaxAgeDays?: number; // Drop history entries older than this (0 = no age limit)
+  historyMaxEntries?: number; // Newest requests kept in history (0 = no limit)
+  hourlySeriesDays?: number; // Days of per-hour usage kept; older usage is kept per day, then per month
+  idleAlertMinutes?: number; // Warn when agents are configured but no request arrives for this long (0 = off)
+  lastAppUpdateCheck?: number | null; // Unix seconds of the last successful update check
   launchAtLogin: boolean;
+  legacyRequestLogEvents?: boolean; // Also emit one request-log event per request besides request-log-batch
   locale?: string;
   loggingToFile: boolean;
   logsMaxTotalSizeMb: number;
+  mainWindow?: WindowGeometry | null; // Saved by the backend as the window moves; kept on save
+  managementAllowRemote?: boolean; // Accept Management API calls from other hosts (needs a rotated key)
+  managementBind?: string; // Address the Management API listens on when it has its own port (default 127.0.0.1)
   managementKey?: string; // Management API key for internal proxy calls
+  managementPort?: number | null; // Serve the Management API on its own port; never shown to agents
+  mcpServerEnabled?: boolean; // Start the MCP introspection server at launch
+  metricsAllowRemote?: boolean; // Bind the metrics listener on all interfaces
+  metricsPort?: number | null; // Serve Prometheus metrics at http://127.0.0.1:<port>/metrics
+  miniMonitorWindow?: WindowGeometry | null; // Same for the mini monitor window
+  modelCaps?: ModelCap[]; // Daily request/token limits per model, with an optional fallback model for Amp requests
+  modelCatalogUrl?: string | null; // Where refreshModelCatalog downloads model metadata from (checked against <url>.sha256)
+  networkProxy?: NetworkProxy; // Proxy for ProxyPal's own requests (updates, quota, webhooks); never used for 127.0.0.1
+  notifications?: NotificationSettings; // Per-category desktop notification toggles
+  onboarding?: OnboardingState;
+  pauseOnSleep?: boolean; // Drain and pause the proxy before system sleep, resume on wake (Linux)
   port: number;
+  probeMode?: "active" | "passive"; // Health from 1-token probes per provider, or from recent requests (default)
+  providerRateLimits?: ProviderRateLimit[]; // Edited with saveProviderRateLimit; save_config keeps the stored list
   proxyApiKey?: string; // API key for client authentication
+  proxyBind?: string; // Address the proxy listens on; empty for all interfaces
+  proxyKeyGraceMinutes?: number; // How long a rotated-out proxy key keeps working (0 = revoke at once)
   proxyPassword?: string;
-  proxyUrl: string;
+  proxyUrl: string; // Upstream proxy the sidecar sends model traffic through (not ProxyPal's own requests)
   proxyUsername?: string;
   quotaSwitchPreviewModel: boolean;
   quotaSwitchProject: boolean;
   requestLogging: boolean;
   requestRetry: number;
+  requestWatches?: RequestWatch[]; // Edited with createRequestWatch; save_config keeps the stored list
+  retiredProxyApiKeys?: RetiredProxyKey[]; // Rotated-out keys still accepted until they expire
   routingStrategy: string; // "round-robin", "fill-first", "sequential"
+  schedule?: ProxySchedule | null; // Weekly windows the proxy runs in; stopped outside them
   sidebarPinned?: boolean;
   sshConfigs?: SshConfig[];
+  staleAgentConfigs?: string[];
+  statusFileEnabled?: boolean; // Keep a status file updated for shell prompts / status bars
+  statusFileFormat?: "json" | "plain";
+  statusFilePath?: string | null; // Defaults to <config dir>/status.json
+  trayIconStyle?: "auto" | "color" | "monochrome"; // Tray glyph; monochrome is a template image on macOS
+  trayShowStats?: boolean; // Show today's spend/requests next to the tray icon (not on Windows)
+  trayStatsRefreshSecs?: number;
+  usageAnomalyMultiplier?: number; // Alert when today's spend reaches this many times the usual by the same hour (0 = off, default 4)
   usageStatsEnabled: boolean;
+  usageTimezone?: "local" | "utc"; // Zone the proxy's per-day usage keys and "today" use (default local)
   useSystemProxy?: boolean;
+  vertexRegions?: Record<string, string>; // Project id -> region for imported service accounts
+  webhooks?: WebhookConfig[];
   wsAuth?: boolean; // Require authentication for WebSocket connections
 }
 
+export interface RetiredProxyKey {
+  expiresAt: number;
+  key: string;
+}
+
 export async function getConfig(): Promise<AppConfig> {
   return invoke("get_config");
 }
 
+// Config and auth as loaded by the backend at startup
+export interface StartupState {
+  authStatus: AuthStatus;
+  config: AppConfig;
+}
+
+// null while the backend is still reading settings from disk
+export async function getStartupState(): Promise<StartupState | null> {
+  return invoke("get_startup_state");
+}
+
+// Resolves once startup has loaded the saved config and auth
+export async function waitForStartupState(): Promise<StartupState> {
+  let resolveHydrated: (state: StartupState) => void = () => {};
+  const hydrated = new Promise<StartupState>((resolve) => {
+    resolveHydrated = resolve;
+  });
+  // Subscribe first so the event can't slip between the check and the listen
+  const unlisten = await listen<StartupState>("state-hydrated", (event) =>
+    resolveHydrated(event.payload),
+  );
+  try {
+    return (await getStartupState()) ?? (await hydrated);
+  } finally {
+    unlisten();
+  }
+}
+
 export async function saveConfig(config: AppConfig): Promise<void> {
   return invoke("save_config", { config });
 }
@@ -53,6 +170,40 @@ export async function reloadConfig(): Promise<AppConfig> {
   return invoke("reload_config");
 }
 
+// Warnings about a config (the current one if omitted) that don't block saving
+export async function validateConfig(config?: AppConfig): Promise<string[]> {
+  return invoke("validate_config", { config });
+}
+
+// Settings copied from a standalone CLIProxyAPI config.yaml
+export interface CliproxyConfigImport {
+  mapped: string[]; // e.g. "request-retry"
+  path: string;
+  unmapped: string[]; // No ProxyPal setting; these can go into proxy-config-custom.yaml
+  warnings: string[]; // Recognized but not imported, e.g. a hashed management key
+}
+
+// Restart the proxy to use the imported settings
+export async function importCliproxyConfig(path: string): Promise<CliproxyConfigImport> {
+  return invoke("import_cliproxy_config", { path });
+}
+
+// Emitted once on first run when a standalone config.yaml was found and imported
+export async function onCliproxyConfigImported(
+  callback: (report: CliproxyConfigImport) => void,
+): Promise<UnlistenFn> {
+  return listen<CliproxyConfigImport>("cliproxy-config-imported", (event) => {
+    callback(event.payload);
+  });
+}
+
+// Emitted once on first run when another program held DEFAULT_PORT; the proxy uses port instead
+export async function onDefaultPortMoved(callback: (port: number) => void): Promise<UnlistenFn> {
+  return listen<number>("default-port-moved", (event) => {
+    callback(event.payload);
+  });
+}
+
 // Raw Config YAML - for power users
 export async function getConfigYaml(): Promise<string> {
   return invoke("get_config_yaml");
@@ -61,3 +212,65 @@ export async function getConfigYaml(): Promise<string> {
 export async function setConfigYaml(yaml: string): Promise<void> {
   return invoke("save_config_yaml", { yaml });
 }
+
+// Proxy API key rotation
+export interface KeyRotationTarget {
+  detail?: string;
+  id: string;
+  name: string;
+  status: "updated" | "manual" | "failed";
+}
+
+export interface KeyRotationReport {
+  apiKey: string;
+  previousKeyExpiresAt?: number | null;
+  proxy: "hot" | "restarted" | "notRunning" | "failed";
+  targets: KeyRotationTarget[];
+}
+
+// New random proxy API key, pushed to the proxy and every configured agent.
+// The old key keeps working for graceMinutes (proxyKeyGraceMinutes if omitted).
+export async function rotateProxyApiKey(graceMinutes?: number): Promise<KeyRotationReport> {
+  return invoke("rotate_proxy_api_key", { graceMinutes });
+}
+
+// Per-client proxy API keys
+export interface ClientKey {
+  agentId?: string | null; // Agent whose config uses this key
+  createdAt: number;
+  id: string;
+  key: string;
+  lastUsedAt?: number | null;
+  name: string;
+  requestCount: number;
+  syncedRequests: number;
+}
+
+export interface ClientKeyInfo {
+  agentId?: string | null;
+  createdAt: number;
+  id: string;
+  keyPreview: string;
+  lastUsedAt?: number | null;
+  name: string;
+  requestCount: number;
+}
+
+export interface ClientKeyRevocation {
+  id: string;
+  proxy: "hot" | "restarted" | "notRunning" | "failed";
+  staleAgents: string[]; // Agents still configured with the revoked key
+}
+
+export async function listClientKeys(): Promise<ClientKeyInfo[]> {
+  return invoke("list_client_keys");
+}
+
+// The full key is only returned here
+export async function createClientKey(name: string, agentId?: string): Promise<ClientKey> {
+  return invoke("create_client_key", { name, agentId });
+}
+
+export async function revokeClientKey(id: string): Promise<ClientKeyRevocation> {
+  return invoke("revoke_client_key", { id });
+}
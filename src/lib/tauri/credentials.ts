@@ -0,0 +1,59 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+import { invoke } from "./invoke";
+
+// ============================================
+// Passphrase prompts and encrypted credential export
+// ============================================
+
+export type PassphrasePurpose = "export-credentials" | "import-credentials";
+
+export interface PassphraseRequest {
+  confirm: boolean; // Ask twice, for passphrases that protect new data
+  id: string;
+  purpose: PassphrasePurpose;
+}
+
+export interface CredentialTransfer {
+  files: string[];
+  skipped: string[]; // Already present on import; left untouched
+}
+
+// Message of the "cancelled" CommandError when the user dismisses the dialog
+export const PASSPHRASE_CANCELLED = "Cancelled";
+
+export const MIN_PASSPHRASE_LENGTH = 8;
+
+// Pass null to cancel the prompt
+export async function answerPassphraseRequest(
+  id: string,
+  passphrase: string | null,
+): Promise<void> {
+  return invoke("answer_passphrase_request", { id, passphrase });
+}
+
+// Asks for a passphrase through onPassphraseRequested before writing `path`
+export async function exportCredentials(path: string): Promise<CredentialTransfer> {
+  return invoke("export_credentials", { path });
+}
+
+export async function importCredentials(path: string): Promise<CredentialTransfer> {
+  return invoke("import_credentials", { path });
+}
+
+export async function onPassphraseRequested(
+  callback: (request: PassphraseRequest) => void,
+): Promise<UnlistenFn> {
+  return listen<PassphraseRequest>("passphrase-requested", (event) => {
+    callback(event.payload);
+  });
+}
+
+// The backend stopped waiting (timed out), so the dialog should close
+export async function onPassphraseRequestClosed(
+  callback: (id: string) => void,
+): Promise<UnlistenFn> {
+  return listen<string>("passphrase-request-closed", (event) => {
+    callback(event.payload);
+  });
+}
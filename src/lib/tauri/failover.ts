@@ -0,0 +1,68 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+import { invoke } from "./invoke";
+
+// ============================================
+// Provider failover
+// ============================================
+
+// Rules rewrite the proxy's Amp model mappings, so they only redirect requests
+// made through Amp; other clients keep reaching the unhealthy provider.
+
+export type FailoverTrigger = "rate_limited" | "auth_expired" | "offline";
+
+export interface FailoverModelMapping {
+  from: string; // May end in * to cover every model with that prefix
+  to: string;
+}
+
+export interface FailoverRule {
+  enabled: boolean;
+  fromProvider: string;
+  id: string; // Empty when creating; the backend assigns one
+  modelMap: FailoverModelMapping[];
+  toProvider: string;
+  trigger: FailoverTrigger;
+}
+
+export interface FailoverActivation {
+  activatedAt: number;
+  fromProvider: string;
+  mappings: FailoverModelMapping[]; // Wildcards expanded
+  ruleId: string;
+  toProvider: string;
+  trigger: FailoverTrigger;
+}
+
+export async function getFailoverRules(): Promise<FailoverRule[]> {
+  return invoke("get_failover_rules");
+}
+
+// Rejected when the enabled rules would loop (e.g. claude → vertex → claude)
+export async function saveFailoverRule(rule: FailoverRule): Promise<FailoverRule[]> {
+  return invoke("save_failover_rule", { rule });
+}
+
+export async function deleteFailoverRule(id: string): Promise<FailoverRule[]> {
+  return invoke("delete_failover_rule", { id });
+}
+
+export async function getActiveFailovers(): Promise<FailoverActivation[]> {
+  return invoke("get_active_failovers");
+}
+
+export async function onFailoverActivated(
+  callback: (activation: FailoverActivation) => void,
+): Promise<UnlistenFn> {
+  return listen<FailoverActivation>("failover-activated", (event) => {
+    callback(event.payload);
+  });
+}
+
+export async function onFailoverCleared(
+  callback: (activation: FailoverActivation) => void,
+): Promise<UnlistenFn> {
+  return listen<FailoverActivation>("failover-cleared", (event) => {
+    callback(event.payload);
+  });
+}
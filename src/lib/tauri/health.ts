@@ -1,15 +1,18 @@
-import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+import { invoke } from "./invoke";
 
 // Provider health check
 export interface HealthStatus {
   lastChecked: number;
   latencyMs?: number;
-  status: "healthy" | "degraded" | "offline" | "unconfigured";
+  status: "auth_expired" | "degraded" | "healthy" | "offline" | "rate_limited" | "unconfigured";
 }
 
 export interface ProviderHealth {
   antigravity: HealthStatus;
   claude: HealthStatus;
+  deepseek: HealthStatus;
   gemini: HealthStatus;
   iflow: HealthStatus;
   kimi: HealthStatus;
@@ -17,12 +20,22 @@ export interface ProviderHealth {
   openai: HealthStatus;
   qwen: HealthStatus;
   vertex: HealthStatus;
+  zhipu: HealthStatus;
 }
 
 export async function checkProviderHealth(): Promise<ProviderHealth> {
   return invoke("check_provider_health");
 }
 
+// Fired by the background monitor when any provider's status changes
+export async function onProviderHealthChanged(
+  callback: (health: ProviderHealth) => void,
+): Promise<UnlistenFn> {
+  return listen<ProviderHealth>("provider-health-changed", (event) => {
+    callback(event.payload);
+  });
+}
+
 // Test OpenAI-compatible provider connection
 export interface ProviderTestResult {
   latencyMs?: number;
@@ -46,3 +59,35 @@ export async function testProviderConnection(modelId: string): Promise<ProviderT
 export async function testKiroConnection(): Promise<ProviderTestResult> {
   return invoke("test_kiro_connection");
 }
+
+// End-to-end self-test, rendered as a checklist
+export interface SelfTestStep {
+  detail: string;
+  durationMs: number;
+  hint?: string | null; // What to do about a failed or skipped step
+  id: string;
+  label: string;
+  status: "fail" | "pass" | "skip";
+}
+
+export interface SelfTestReport {
+  cancelled: boolean;
+  passed: boolean;
+  steps: SelfTestStep[];
+}
+
+// Starts the proxy if it is stopped
+export async function runSelfTest(): Promise<SelfTestReport> {
+  return invoke("run_self_test");
+}
+
+export async function cancelSelfTest(): Promise<void> {
+  return invoke("cancel_self_test");
+}
+
+// Fired after each step of a running self-test
+export async function onSelfTestStep(callback: (step: SelfTestStep) => void): Promise<UnlistenFn> {
+  return listen<SelfTestStep>("self-test-step", (event) => {
+    callback(event.payload);
+  });
+}
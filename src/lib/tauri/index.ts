@@ -13,7 +13,24 @@ export * from "./health";
 export * from "./auth-files";
 export * from "./tools";
 export * from "./notifications";
+export * from "./onboarding";
 export * from "./updates";
 export * from "./ssh";
 export * from "./cloudflare";
 export * from "./utils";
+export * from "./webhooks";
+export * from "./failover";
+export * from "./schedule";
+export * from "./model-caps";
+export * from "./model-catalog";
+export * from "./captures";
+export * from "./benchmark";
+export * from "./mcp";
+export * from "./agent-bundle";
+export * from "./agent-endpoints";
+export * from "./vertex";
+export * from "./amp-pools";
+export * from "./credentials";
+export * from "./rate-limits";
+export * from "./request-watches";
+export * from "./invoke";
@@ -0,0 +1,63 @@
+import { invoke as tauriInvoke, type InvokeArgs } from "@tauri-apps/api/core";
+
+// ============================================
+// Command errors
+// ============================================
+
+export type CommandErrorCode =
+  | "cancelled"
+  | "internal"
+  | "invalid_input"
+  | "io"
+  | "loading"
+  | "not_found"
+  | "proxy_not_running"
+  | "upstream";
+
+// Commands converted to typed errors reject with { code, message, details? }.
+// The rest still reject with a plain string, which is passed through as-is.
+export class CommandError extends Error {
+  code: CommandErrorCode;
+  details?: Record<string, unknown>;
+
+  constructor(code: CommandErrorCode, message: string, details?: Record<string, unknown>) {
+    super(message);
+    this.name = "CommandError";
+    this.code = code;
+    this.details = details;
+  }
+
+  // String(error) shows the message alone, as with plain string errors
+  override toString(): string {
+    return this.message;
+  }
+}
+
+export function isCommandError(error: unknown, code?: CommandErrorCode): error is CommandError {
+  return error instanceof CommandError && (code === undefined || error.code === code);
+}
+
+function toCommandError(error: unknown): unknown {
+  if (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as { code?: unknown }).code === "string" &&
+    typeof (error as { message?: unknown }).message === "string"
+  ) {
+    const { code, message, details } = error as {
+      code: CommandErrorCode;
+      details?: Record<string, unknown>;
+      message: string;
+    };
+    return new CommandError(code, message, details);
+  }
+  return error;
+}
+
+export async function invoke<T>(command: string, args?: InvokeArgs): Promise<T> {
+  try {
+    return await tauriInvoke<T>(command, args);
+  } catch (error) {
+    throw toCommandError(error);
+  }
+}
@@ -1,26 +1,82 @@
-import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
+import { invoke } from "./invoke";
+
+// One upstream attempt of a request the proxy retried
+export interface AttemptInfo {
+  account?: string | null; // Email or masked key, when the log names it
+  provider?: string | null;
+  status?: number | null;
+}
+
 // Request log for live monitoring
 export interface RequestLog {
+  account?: string | null; // Email or credential that served the request, when the log says
+  appVersion?: string | null; // ProxyPal version that recorded it; absent on older entries
+  attempts?: AttemptInfo[]; // Every attempt in order; empty unless the proxy retried
+  client?: string | null; // Calling tool: its client key's name, else from its User-Agent
   durationMs: number;
   id: string;
   method: string;
   model: string;
   path: string;
   provider: string;
+  retries?: number; // Extra attempts before the final status
+  sessionId?: string; // Id of the first request of its session
+  sidecarVersion?: string | null; // CLIProxyAPI version that served it; absent on older entries
   status: number;
+  synthetic?: boolean; // Generated by demo mode; not counted in totals, caps or health
+  tags?: string[]; // Set for cost review
   timestamp: number;
   tokensIn?: number;
   tokensOut?: number;
+  ttfbMs?: number | null; // Time to the first streamed chunk, when the log shows it
+}
+
+// Requests arrive in batches, at most one every 100ms
+export async function onRequestLogBatch(
+  callback: (logs: RequestLog[]) => void,
+): Promise<UnlistenFn> {
+  return listen<RequestLog[]>("request-log-batch", (event) => {
+    callback(event.payload);
+  });
 }
 
 export async function onRequestLog(callback: (log: RequestLog) => void): Promise<UnlistenFn> {
-  return listen<RequestLog>("request-log", (event) => {
+  return onRequestLogBatch((logs) => logs.forEach(callback));
+}
+
+// Pausing stops live events but not history; resuming sends the held-back
+// requests as one batch, then feed-resumed
+export async function setLiveFeedPaused(paused: boolean): Promise<void> {
+  return invoke("set_live_feed_paused", { paused });
+}
+
+// Recent requests kept in memory, oldest first, paused or not
+export async function snapshotLiveFeed(): Promise<RequestLog[]> {
+  return invoke("snapshot_live_feed");
+}
+
+export async function onFeedResumed(
+  callback: (data: { missed: number }) => void,
+): Promise<UnlistenFn> {
+  return listen<{ missed: number }>("feed-resumed", (event) => {
     callback(event.payload);
   });
 }
 
+// Health of the request-log pipeline; dropped requests mean the UI fell behind
+export interface ProxyLogDiagnostics {
+  batchIntervalMs: number;
+  droppedRequests: number;
+  processedRequests: number;
+  queueCapacity: number;
+}
+
+export async function getProxyLogDiagnostics(): Promise<ProxyLogDiagnostics> {
+  return invoke("get_proxy_log_diagnostics");
+}
+
 // ==========================================================================
 // Log Viewer
 // ==========================================================================
@@ -39,6 +95,28 @@ export async function clearLogs(): Promise<void> {
   return invoke("clear_logs");
 }
 
+// Classified proxy errors backing the tray's "Recent Errors" submenu
+export interface ProxyErrorEntry {
+  id: string;
+  kind: "auth" | "client" | "process" | "rate_limit" | "upstream";
+  message: string;
+  model: string;
+  provider: string;
+  status: number | null;
+  timestamp: number;
+}
+
+export async function getRecentErrors(): Promise<ProxyErrorEntry[]> {
+  return invoke("get_recent_errors");
+}
+
+// Fired when an entry in the tray's "Recent Errors" submenu is clicked
+export async function onTrayFocusError(callback: (errorId: string) => void): Promise<UnlistenFn> {
+  return listen<string>("tray-focus-error", (event) => {
+    callback(event.payload);
+  });
+}
+
 // Request Error Logs - view error-specific logs
 export async function getRequestErrorLogs(): Promise<string[]> {
   return invoke("get_request_error_logs");
@@ -47,3 +125,33 @@ export async function getRequestErrorLogs(): Promise<string[]> {
 export async function getRequestErrorLogContent(filename: string): Promise<string> {
   return invoke("get_request_error_log_content", { filename });
 }
+
+// Masked report to paste when asking for help; `path` is set when a zip was written
+export interface DiagnosticReport {
+  path: string | null;
+  text: string;
+}
+
+export async function generateDiagnosticReport(writeZip?: boolean): Promise<DiagnosticReport> {
+  return invoke("generate_diagnostic_report", { writeZip });
+}
+
+// What the previous run of ProxyPal left in its session journal
+export interface LastSessionSummary {
+  appVersion: string;
+  cleanShutdown: boolean;
+  crashReason: string | null; // Unclean shutdowns only
+  endedAt: number; // Unix millis; for an unclean shutdown, the last journal write
+  failures: number;
+  lastErrors: ProxyErrorEntry[]; // Latest first
+  proxyCrashes: number;
+  proxyStarts: number;
+  requests: number;
+  startedAt: number; // Unix millis
+  uptimeSecs: number;
+}
+
+// null on the first launch
+export async function getLastSessionSummary(): Promise<LastSessionSummary | null> {
+  return invoke("get_last_session_summary");
+}
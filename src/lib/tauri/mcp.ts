@@ -0,0 +1,15 @@
+import { invoke } from "./invoke";
+
+// ============================================
+// MCP Server
+// ============================================
+
+// Returns the socket path (named pipe on Windows) agents connect to;
+// `proxypal mcp` bridges it to stdio
+export async function startMcpServer(): Promise<string> {
+  return invoke("start_mcp_server");
+}
+
+export async function stopMcpServer(): Promise<void> {
+  return invoke("stop_mcp_server");
+}
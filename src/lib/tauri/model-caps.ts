@@ -0,0 +1,54 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+import { invoke } from "./invoke";
+
+// ============================================
+// Per-model daily caps
+// ============================================
+
+export interface ModelCap {
+  enabled: boolean;
+  fallbackModel?: string | null; // Amp requests are redirected here once the cap is reached; other clients are not
+  id: string; // Empty when creating; the backend assigns one
+  maxRequestsPerDay?: number | null;
+  maxTokensPerDay?: number | null;
+  modelPattern: string; // Exact model id, or a prefix ending in *
+}
+
+export interface ModelCapStatus {
+  capId: string;
+  day: string; // Usage day the counts belong to, "YYYY-MM-DD"
+  fallbackModel?: string | null;
+  maxRequestsPerDay?: number | null;
+  maxTokensPerDay?: number | null;
+  modelPattern: string;
+  reached: boolean;
+  reachedAt?: number | null;
+  requestsToday: number;
+  tokensToday: number;
+}
+
+export async function getModelCaps(): Promise<ModelCap[]> {
+  return invoke("get_model_caps");
+}
+
+// Rejected without a limit, or when the fallback is covered by the same cap
+export async function saveModelCap(cap: ModelCap): Promise<ModelCap[]> {
+  return invoke("save_model_cap", { cap });
+}
+
+export async function deleteModelCap(id: string): Promise<ModelCap[]> {
+  return invoke("delete_model_cap", { id });
+}
+
+export async function getModelCapStatus(): Promise<ModelCapStatus[]> {
+  return invoke("get_model_cap_status");
+}
+
+export async function onModelCapReached(
+  callback: (status: ModelCapStatus) => void,
+): Promise<UnlistenFn> {
+  return listen<ModelCapStatus>("model-cap-reached", (event) => {
+    callback(event.payload);
+  });
+}
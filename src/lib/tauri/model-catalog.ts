@@ -0,0 +1,35 @@
+import type { AvailableModel } from "./models";
+import { invoke } from "./invoke";
+
+// ============================================
+// Model catalog
+// ============================================
+
+export type CostTier = "high" | "low" | "medium" | "premium";
+
+export interface ModelCatalogEntry {
+  contextWindow?: number | null; // Tokens
+  costTier: CostTier;
+  pattern: string; // Exact model id, or a prefix ending in *
+  supportsTools: boolean;
+  supportsVision: boolean;
+}
+
+export interface ModelCatalog {
+  models: ModelCatalogEntry[];
+  version: number;
+}
+
+export interface CatalogModel extends AvailableModel {
+  metadata?: ModelCatalogEntry | null; // null when no catalog pattern matches the model
+}
+
+// Every word of the query must match the id, provider, a cost tier, "vision" or "tools"
+export async function getModelCatalog(query?: string): Promise<CatalogModel[]> {
+  return invoke("get_model_catalog", { query });
+}
+
+// Downloads url (default: config.modelCatalogUrl), checked against <url>.sha256
+export async function refreshModelCatalog(url?: string): Promise<ModelCatalog> {
+  return invoke("refresh_model_catalog", { url });
+}
@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "./invoke";
 
 // GPT Reasoning Models (single source of truth from backend)
 export async function getGptReasoningModels(): Promise<string[]> {
@@ -225,8 +225,10 @@ export interface CopilotConfig {
 }
 
 export interface AvailableModel {
+  connected?: boolean; // false when served from the offline fallback list
   id: string;
   ownedBy: string; // "google", "openai", "qwen", "anthropic", etc.
+  provider?: string; // "claude", "openai", "gemini", etc.
   source: string; // "vertex", "gemini-api", "copilot", "oauth", "api-key", etc.
 }
 
@@ -253,6 +255,14 @@ export async function getAvailableModels(): Promise<AvailableModel[]> {
   return invoke("get_available_models");
 }
 
+// Export alias -> model map as LiteLLM config.yaml or plain JSON (optionally written to path)
+export async function exportModelAliases(
+  format: "litellm" | "json",
+  path?: string,
+): Promise<string> {
+  return invoke("export_model_aliases", { format, path });
+}
+
 export async function fetchOpenaiCompatibleModels(): Promise<OpenAICompatibleProviderModels[]> {
   return invoke("fetch_openai_compatible_models");
 }
@@ -1,3 +1,4 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import {
   isPermissionGranted,
   requestPermission,
@@ -17,3 +18,25 @@ export async function showSystemNotification(title: string, body?: string): Prom
     sendNotification({ body, title });
   }
 }
+
+// Emitted for proxypal://navigate/<page> links, e.g. after clicking a notification
+export async function onNavigate(callback: (page: string) => void): Promise<UnlistenFn> {
+  return listen<string>("navigate", (event) => {
+    callback(event.payload);
+  });
+}
+
+// Outcome of a proxypal:// action link (proxy/start, auth/connect, ...)
+export interface DeepLinkActionResult {
+  action: string;
+  message: string;
+  success: boolean;
+}
+
+export async function onDeepLinkAction(
+  callback: (result: DeepLinkActionResult) => void,
+): Promise<UnlistenFn> {
+  return listen<DeepLinkActionResult>("deep-link-action", (event) => {
+    callback(event.payload);
+  });
+}
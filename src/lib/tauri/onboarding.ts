@@ -0,0 +1,43 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+import { invoke } from "./invoke";
+
+// ============================================
+// First-run Onboarding
+// ============================================
+
+export type OnboardingStep =
+  | "proxy_started"
+  | "provider_connected"
+  | "agent_configured"
+  | "request_observed";
+
+export interface OnboardingState {
+  completed: boolean;
+  completedSteps: OnboardingStep[];
+  skippedSteps: OnboardingStep[];
+}
+
+// Also picks up steps the user completed outside the wizard
+export async function getOnboardingState(): Promise<OnboardingState> {
+  return invoke("get_onboarding_state");
+}
+
+export async function completeOnboardingStep(
+  step: OnboardingStep,
+  skipped = false,
+): Promise<OnboardingState> {
+  return invoke("complete_onboarding_step", { step, skipped });
+}
+
+export async function resetOnboarding(): Promise<OnboardingState> {
+  return invoke("reset_onboarding");
+}
+
+export async function onOnboardingChanged(
+  callback: (state: OnboardingState) => void,
+): Promise<UnlistenFn> {
+  return listen<OnboardingState>("onboarding-changed", (event) => {
+    callback(event.payload);
+  });
+}
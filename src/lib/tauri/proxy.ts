@@ -1,25 +1,53 @@
-import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
+import { invoke } from "./invoke";
+
 // Proxy management
+export type BinarySource = "bundled" | "system" | "custom";
+
 export interface ProxyStatus {
-  endpoint: string;
+  binarySource?: BinarySource;
+  // Set when the CLIProxyAPI found on PATH is older than ProxyPal supports
+  binaryWarning?: string | null;
+  draining?: boolean; // Waiting for in-flight requests before pausing
+  endpoint: string; // Set once the running proxy answered; empty while stopped or starting
+  lastKnownEndpoint?: string | null; // Where the proxy answered before it last stopped
+  paused?: boolean; // Stopped by pauseProxy; resumeProxy starts it again
   port: number;
   running: boolean;
+  sidecarVersion?: string | null; // CLIProxyAPI version of the running binary, when it reported one
 }
 
-export async function startProxy(): Promise<ProxyStatus> {
-  return invoke("start_proxy");
+// Outside a scheduled window, starting needs overrideSchedule; the proxy then
+// stays up until the next schedule boundary
+export async function startProxy(overrideSchedule?: boolean): Promise<ProxyStatus> {
+  return invoke("start_proxy", { overrideSchedule });
 }
 
 export async function stopProxy(): Promise<ProxyStatus> {
   return invoke("stop_proxy");
 }
 
+// Wait for in-flight requests (up to drainTimeoutSecs, default 30), then stop
+export async function pauseProxy(drainTimeoutSecs?: number): Promise<ProxyStatus> {
+  return invoke("pause_proxy", { drainTimeoutSecs });
+}
+
+// Start a paused proxy with the settings it was paused with
+export async function resumeProxy(): Promise<ProxyStatus> {
+  return invoke("resume_proxy");
+}
+
 export async function getProxyStatus(): Promise<ProxyStatus> {
   return invoke("get_proxy_status");
 }
 
+// Download, verify and install a CLIProxyAPI release (latest unless a version
+// is given); used from the next proxy start. Resolves to the installed version.
+export async function updateSidecar(version?: string): Promise<string> {
+  return invoke("update_sidecar", { version });
+}
+
 export async function onProxyStatusChanged(
   callback: (status: ProxyStatus) => void,
 ): Promise<UnlistenFn> {
@@ -28,6 +56,13 @@ export async function onProxyStatusChanged(
   });
 }
 
+// Emitted when the proxy fails to auto-start on launch; payload is the error message
+export async function onProxyStartFailed(callback: (error: string) => void): Promise<UnlistenFn> {
+  return listen<string>("proxy-start-failed", (event) => {
+    callback(event.payload);
+  });
+}
+
 export async function onTrayToggleProxy(
   callback: (shouldStart: boolean) => void,
 ): Promise<UnlistenFn> {
@@ -35,3 +70,33 @@ export async function onTrayToggleProxy(
     callback(event.payload);
   });
 }
+
+// Quit confirmation: emitted with the in-flight request count when quit is requested
+export async function onConfirmQuit(callback: (inFlight: number) => void): Promise<UnlistenFn> {
+  return listen<number>("confirm-quit", (event) => {
+    callback(event.payload);
+  });
+}
+
+export async function forceQuit(): Promise<void> {
+  return invoke("force_quit");
+}
+
+// Quit once in-flight requests finish (the backend caps the wait at 30s)
+export async function quitWhenIdle(): Promise<void> {
+  return invoke("quit_when_idle");
+}
+
+// Agents are configured but no request arrived for idleAlertMinutes while the proxy ran
+export interface IdleWarning {
+  configuredAgents: string[];
+  idleMinutes: number;
+  timestamp: number;
+}
+
+// Once per idle stretch; run auditAgentEndpoints to find the agent going around the proxy
+export async function onIdleWarning(callback: (warning: IdleWarning) => void): Promise<UnlistenFn> {
+  return listen<IdleWarning>("idle-warning", (event) => {
+    callback(event.payload);
+  });
+}
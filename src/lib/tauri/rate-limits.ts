@@ -0,0 +1,42 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+import { invoke } from "./invoke";
+
+// ============================================
+// Provider soft rate limits
+// ============================================
+
+// Only warned about: CLIProxyAPI has no setting to throttle by
+export interface ProviderRateLimit {
+  provider: string;
+  requestsPerMinute: number; // Must be positive
+}
+
+export interface RateSoftLimitExceeded {
+  measuredPerMinute: number; // Requests to the provider in the last minute
+  provider: string;
+  requestsPerMinute: number; // The configured limit
+  timestamp: number; // Unix millis of the request that went over
+}
+
+export async function getProviderRateLimits(): Promise<ProviderRateLimit[]> {
+  return invoke("get_provider_rate_limits");
+}
+
+// Replaces the provider's existing limit, if any
+export async function saveProviderRateLimit(limit: ProviderRateLimit): Promise<ProviderRateLimit[]> {
+  return invoke("save_provider_rate_limit", { limit });
+}
+
+export async function deleteProviderRateLimit(provider: string): Promise<ProviderRateLimit[]> {
+  return invoke("delete_provider_rate_limit", { provider });
+}
+
+// At most one a minute per provider
+export async function onRateSoftLimitExceeded(
+  callback: (warning: RateSoftLimitExceeded) => void,
+): Promise<UnlistenFn> {
+  return listen<RateSoftLimitExceeded>("rate-soft-limit-exceeded", (event) => {
+    callback(event.payload);
+  });
+}
@@ -0,0 +1,54 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+import { invoke } from "./invoke";
+import type { RequestLog } from "./logs";
+
+// ============================================
+// Request watches
+// ============================================
+
+export type StatusClass = "2xx" | "3xx" | "4xx" | "5xx";
+
+// Unset criteria match every request; at least one is required
+export interface RequestWatchFilter {
+  minCostUsd?: number | null; // Estimated cost of the request
+  modelPattern?: string | null; // Exact model id, or a prefix ending in *
+  provider?: string | null;
+  statusClass?: StatusClass | null;
+}
+
+export interface RequestWatch {
+  createdAt: number; // Unix millis
+  filter: RequestWatchFilter;
+  id: string;
+  notify: boolean; // Also show a desktop notification for hits (at most one a minute)
+}
+
+export interface RequestWatchHit {
+  costUsd: number;
+  request: RequestLog;
+  watchId: string;
+}
+
+export async function createRequestWatch(
+  filter: RequestWatchFilter,
+  notify: boolean,
+): Promise<RequestWatch> {
+  return invoke("create_request_watch", { filter, notify });
+}
+
+export async function listRequestWatches(): Promise<RequestWatch[]> {
+  return invoke("list_request_watches");
+}
+
+export async function deleteRequestWatch(id: string): Promise<RequestWatch[]> {
+  return invoke("delete_request_watch", { id });
+}
+
+export async function onRequestWatchHit(
+  callback: (hit: RequestWatchHit) => void,
+): Promise<UnlistenFn> {
+  return listen<RequestWatchHit>("request-watch-hit", (event) => {
+    callback(event.payload);
+  });
+}
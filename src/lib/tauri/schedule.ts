@@ -0,0 +1,41 @@
+import { invoke } from "./invoke";
+
+// ============================================
+// Proxy schedule
+// ============================================
+
+export type ScheduleDay = "mon" | "tue" | "wed" | "thu" | "fri" | "sat" | "sun";
+
+// Local time; windows can't cross midnight, so split overnight periods in two
+export interface ScheduleWindow {
+  day: ScheduleDay;
+  end: string; // "HH:MM", or "24:00" for end of day
+  start: string; // "HH:MM"
+}
+
+export interface ProxySchedule {
+  enabled: boolean;
+  windows: ScheduleWindow[];
+}
+
+export interface ScheduleStatus {
+  enabled: boolean;
+  inWindow: boolean;
+  nextBoundary: number | null; // ms timestamp of the next window start or end
+  overridden: boolean; // A manual start/stop holds until nextBoundary
+}
+
+export async function getProxySchedule(): Promise<ProxySchedule | null> {
+  return invoke("get_proxy_schedule");
+}
+
+// Pass null to remove the schedule; rejects overlapping windows
+export async function setProxySchedule(
+  schedule: ProxySchedule | null,
+): Promise<ProxySchedule | null> {
+  return invoke("set_proxy_schedule", { schedule });
+}
+
+export async function getScheduleStatus(): Promise<ScheduleStatus> {
+  return invoke("get_schedule_status");
+}
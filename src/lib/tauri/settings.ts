@@ -1,4 +1,6 @@
-import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+import { invoke } from "./invoke";
 
 // ============================================
 // Thinking Budget Settings
@@ -47,6 +49,174 @@ export async function setCloseToTray(enabled: boolean): Promise<void> {
   return invoke("set_close_to_tray", { enabled });
 }
 
+// ============================================
+// Mini Monitor
+// ============================================
+
+// Small always-on-top window with the live request feed; also in the tray menu
+export async function openMiniMonitor(): Promise<void> {
+  return invoke("open_mini_monitor");
+}
+
+// ============================================
+// Notifications
+// ============================================
+
+export interface NotificationSettings {
+  authExpired: boolean;
+  budgetThreshold: boolean;
+  idleWarning: boolean; // Agents are configured but no requests reach the proxy
+  providerHealth: boolean; // A provider's background health check turned offline or degraded
+  proxyCrash: boolean;
+  rateLimit: boolean;
+  sidecarUpdate: boolean;
+  usageAnomaly: boolean; // Today's spend is far above the usual by this hour
+}
+
+export type NotificationCategory = keyof NotificationSettings;
+
+export async function sendTestNotification(category: NotificationCategory): Promise<void> {
+  return invoke("send_test_notification", { category });
+}
+
+// ============================================
+// File Permissions
+// ============================================
+
+export interface FilePermissionIssue {
+  isDir: boolean;
+  // Octal mode on Unix ("644"), broad ACL groups on Windows ("Everyone")
+  mode: string;
+  path: string;
+  repaired: boolean;
+}
+
+export interface FilePermissionReport {
+  checked: number;
+  issues: FilePermissionIssue[];
+}
+
+// Audit config/credential files; with repair, tighten them to owner-only
+export async function checkFilePermissions(repair = false): Promise<FilePermissionReport> {
+  return invoke("check_file_permissions", { repair });
+}
+
+// Emitted at startup when config or credential files are readable by other users
+export async function onFilePermissionsWarning(
+  callback: (issues: FilePermissionIssue[]) => void,
+): Promise<UnlistenFn> {
+  return listen<FilePermissionIssue[]>("file-permissions-warning", (event) => {
+    callback(event.payload);
+  });
+}
+
+// ============================================
+// Storage
+// ============================================
+
+export interface StoragePath {
+  exists: boolean;
+  name: string; // "config" | "auth" | "history" | "aggregate" | "logs" | "captures" | "benchmarks"
+  path: string;
+  sizeBytes: number;
+}
+
+export interface StorageInfo {
+  configBytes: number; // Size of the config dir, data dir excluded
+  configDir: string; // Directory in use
+  dataBytes: number; // Size of history, logs, captures and benchmarks
+  dataDir: string; // Where history, logs, captures and benchmarks are kept
+  dataFreeBytes: number | null;
+  degradedReason: string | null; // Why configDir is not preferredDir
+  freeBytes: number | null;
+  paths: StoragePath[];
+  portable: boolean; // Data kept beside the executable
+  preferredDir: string; // The usual config directory
+  writable: boolean;
+}
+
+export interface StorageDegraded {
+  path: string;
+  reason: string;
+}
+
+export async function getStorageInfo(): Promise<StorageInfo> {
+  return invoke("get_storage_info");
+}
+
+// Move history, logs, captures and benchmarks to newPath (the config dir when omitted).
+// The proxy must be stopped; nothing is deleted until the copy is verified.
+export async function migrateDataDir(newPath?: string): Promise<StorageInfo> {
+  return invoke("migrate_data_dir", { newPath });
+}
+
+// Emitted at startup when the config directory is unusable and a fallback is in use
+export async function onStorageDegraded(
+  callback: (status: StorageDegraded) => void,
+): Promise<UnlistenFn> {
+  return listen<StorageDegraded>("storage-degraded", (event) => {
+    callback(event.payload);
+  });
+}
+
+export interface DataRecovery {
+  detail: string; // What was kept, or why nothing could be read
+  file: string; // "config.json" | "auth.json" | "history.json"
+  path: string;
+  quarantinedTo: string | null; // Where the damaged file was moved
+  recovered: boolean;
+  timestamp: number;
+}
+
+// Emitted when a damaged config, auth or history file was moved aside and partly recovered
+export async function onDataRecovered(
+  callback: (report: DataRecovery) => void,
+): Promise<UnlistenFn> {
+  return listen<DataRecovery>("data-recovered", (event) => {
+    callback(event.payload);
+  });
+}
+
+// Emitted when a damaged file was moved aside and nothing in it could be used
+export async function onDataLost(callback: (report: DataRecovery) => void): Promise<UnlistenFn> {
+  return listen<DataRecovery>("data-lost", (event) => {
+    callback(event.payload);
+  });
+}
+
+// ============================================
+// Audit Log
+// ============================================
+
+export interface AuditEntry {
+  changes: string[];
+  command: string;
+  error?: string;
+  outcome: "ok" | "error";
+  timestamp: number;
+}
+
+// Recorded config and credential changes, newest first
+export async function getAuditLog(limit?: number, filter?: string): Promise<AuditEntry[]> {
+  return invoke("get_audit_log", { limit, filter });
+}
+
+// ============================================
+// Status Bar Plugin
+// ============================================
+
+export type StatusbarPluginKind = "swiftbar" | "xbar";
+
+// Writes a menu bar script reading the JSON status file; returns its path.
+// pluginsDir defaults to xbar's plugins folder, refreshSecs to 30.
+export async function generateStatusbarPlugin(
+  kind: StatusbarPluginKind,
+  pluginsDir?: string,
+  refreshSecs?: number,
+): Promise<string> {
+  return invoke("generate_statusbar_plugin", { kind, pluginsDir, refreshSecs });
+}
+
 // ============================================
 // Management API Settings (Runtime Updates)
 // ============================================
@@ -1,4 +1,6 @@
-import { invoke } from "@tauri-apps/api/core";
+import type { ApiProtocol, ConnectionInfo } from "./agents";
+import { invoke } from "./invoke";
+import type { AvailableModel } from "./models";
 
 // AI Tool Detection & Setup
 export interface DetectedTool {
@@ -13,8 +15,12 @@ export async function detectAiTools(): Promise<DetectedTool[]> {
   return invoke("detect_ai_tools");
 }
 
-export async function configureContinue(): Promise<string> {
-  return invoke("configure_continue");
+// Returns the config path, or a unified diff when dryRun is set
+export async function configureContinue(
+  models?: AvailableModel[],
+  dryRun?: boolean,
+): Promise<string> {
+  return invoke("configure_continue", { dryRun, models });
 }
 
 export interface ToolSetupStep {
@@ -25,11 +31,16 @@ export interface ToolSetupStep {
 
 export interface ToolSetupInfo {
   canAutoConfigure: boolean;
+  connection: ConnectionInfo; // Snippet for the tool's protocol
   endpoint?: string;
+  ide?: string | null; // JetBrains: newest IDE that has been run
   logo: string;
   manualConfig?: string;
+  models?: string[]; // JetBrains: models ProxyPal serves
   name: string;
   note?: string;
+  protocol: ApiProtocol;
+  settingsPath?: string | null; // JetBrains: that IDE's config folder
   steps: ToolSetupStep[];
 }
 
@@ -1,8 +1,10 @@
 import { getVersion } from "@tauri-apps/api/app";
-import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { relaunch } from "@tauri-apps/plugin-process";
 import { check } from "@tauri-apps/plugin-updater";
 
+import { invoke } from "./invoke";
+
 // ============================================================================
 // App Updates (OTA via Tauri Updater Plugin)
 // ============================================================================
@@ -96,3 +98,47 @@ export interface UpdaterSupport {
 export async function isUpdaterSupported(): Promise<UpdaterSupport> {
   return invoke<UpdaterSupport>("is_updater_supported");
 }
+
+// ============================================================================
+// Release checks (backend; also run daily in the background)
+// ============================================================================
+
+export interface AppUpdateInfo {
+  available: boolean;
+  canInstall: boolean; // The updater can install it in place; otherwise open the release page
+  checkedAt: number; // Unix seconds
+  current: string;
+  dismissed: boolean; // `latest` is the version the user chose to skip
+  latest: string;
+  notes?: string | null; // Release notes (markdown)
+  url: string;
+}
+
+// Ask for the newest release now; rejects when offline or rate limited
+export async function checkAppUpdate(): Promise<AppUpdateInfo> {
+  return invoke("check_app_update");
+}
+
+// Last successful check this session, without touching the network
+export async function getAppUpdate(): Promise<AppUpdateInfo | null> {
+  return invoke("get_app_update");
+}
+
+// Stop announcing this version; later releases are announced as usual
+export async function dismissAppUpdate(version: string): Promise<void> {
+  return invoke("dismiss_app_update", { version });
+}
+
+// Open the release page in the browser, for installs the updater can't replace
+export async function openAppReleasePage(): Promise<void> {
+  return invoke("open_app_release_page");
+}
+
+// A release the user hasn't dismissed was found by the background check
+export async function onAppUpdateAvailable(
+  callback: (info: AppUpdateInfo) => void,
+): Promise<UnlistenFn> {
+  return listen<AppUpdateInfo>("app-update-available", (event) => {
+    callback(event.payload);
+  });
+}
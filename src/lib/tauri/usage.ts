@@ -1,5 +1,6 @@
-import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
+import { invoke } from "./invoke";
 import type { RequestLog } from "./logs";
 
 // Usage Statistics
@@ -23,9 +24,17 @@ export interface ProviderUsage {
   tokens: number;
 }
 
+// Resolution of the stored series: hourly points cover hourlyDays days; day-keyed
+// points up to monthlyUntil are whole months labeled "YYYY-MM"
+export interface SeriesGranularity {
+  hourlyDays: number;
+  monthlyUntil: string | null;
+}
+
 export interface UsageStats {
   cachedTokens: number;
   failureCount: number;
+  granularity?: SeriesGranularity;
   inputTokens: number;
   models: ModelUsage[];
   outputTokens: number;
@@ -33,6 +42,7 @@ export interface UsageStats {
   requestsByDay: TimeSeriesPoint[];
   requestsByHour: TimeSeriesPoint[];
   requestsToday: number;
+  retriedSuccessCount?: number; // Successes that needed more than one attempt
   successCount: number;
   tokensByDay: TimeSeriesPoint[];
   tokensByHour: TimeSeriesPoint[];
@@ -46,8 +56,15 @@ export async function getUsageStats(): Promise<UsageStats> {
   return invoke("get_usage_stats");
 }
 
+// Fired with the new day label ("YYYY-MM-DD") when the usage day rolls over;
+// "today" figures fetched earlier are stale from then on
+export async function onUsageDayChanged(callback: (day: string) => void): Promise<UnlistenFn> {
+  return listen<string>("usage-day-changed", (event) => callback(event.payload));
+}
+
 // Request History (persisted)
 export interface RequestHistory {
+  quotaEvents?: QuotaSwitchEvent[];
   requests: RequestLog[];
   totalCostUsd: number;
   totalTokensCached: number;
@@ -55,16 +72,238 @@ export interface RequestHistory {
   totalTokensOut: number;
 }
 
-export async function getRequestHistory(): Promise<RequestHistory> {
-  return invoke("get_request_history");
+// With `tag`, only the requests carrying it
+// Client names match case-insensitively; "unknown" matches entries recorded without a version
+export async function getRequestHistory(
+  tag?: string,
+  client?: string,
+  versions?: { appVersion?: string; sidecarVersion?: string },
+): Promise<RequestHistory> {
+  return invoke("get_request_history", { client, tag, ...versions });
+}
+
+// Tags are trimmed and lowercased; an empty list clears them. Fails with
+// "not_found" once the request has been trimmed from history.
+export async function setRequestTag(requestId: string, tags: string[]): Promise<RequestLog> {
+  return invoke("set_request_tag", { requestId, tags });
+}
+
+// Tags every request of the session, including later ones; returns how many
+// requests in history were retagged
+export async function setSessionTag(sessionId: string, tags: string[]): Promise<number> {
+  return invoke("set_session_tag", { sessionId, tags });
+}
+
+// Requests in history per account; `account` is null for the unattributed bucket
+export interface AccountUsage {
+  account: string | null;
+  estimatedCost: number;
+  label: string | null; // Credential label or email, "Unattributed" for the null bucket
+  lastUsed: number;
+  providers: string[];
+  requests: number;
+  successCount: number;
+  tokensIn: number;
+  tokensOut: number;
+}
+
+export async function getUsageByAccount(): Promise<AccountUsage[]> {
+  return invoke("get_usage_by_account");
+}
+
+// Requests per calling tool; the null bucket holds requests no tool was identified for
+export interface ClientUsage {
+  client: string | null;
+  estimatedCost: number;
+  lastUsed: number;
+  providers: string[];
+  requests: number;
+  successCount: number;
+  tokensIn: number;
+  tokensOut: number;
+}
+
+export async function getUsageByClient(): Promise<ClientUsage[]> {
+  return invoke("get_usage_by_client");
+}
+
+// Percentiles of successful requests per provider and model. TTFB (time to
+// first token) is null where no request's log showed the first chunk.
+export interface LatencyStats {
+  durationP50Ms: number;
+  durationP90Ms: number;
+  model: string;
+  provider: string;
+  requests: number;
+  ttfbP50Ms: number | null;
+  ttfbP90Ms: number | null;
+  ttfbSamples: number;
+  version?: string; // Set when grouped by version; "unknown" for entries recorded without one
+}
+
+export type VersionDimension = "app" | "sidecar";
+
+// `since` is a ms timestamp; all of history when omitted
+export async function getLatencyStats(
+  since?: number,
+  groupBy?: VersionDimension,
+): Promise<LatencyStats[]> {
+  return invoke("get_latency_stats", { groupBy, since });
+}
+
+// Error rate and latency of the requests served under one version
+export interface VersionStats {
+  durationP50Ms: number; // Successful requests only
+  durationP90Ms: number;
+  errorRate: number; // failureCount / requests
+  failureCount: number;
+  firstSeen: number;
+  lastSeen: number;
+  requests: number;
+  ttfbP50Ms: number | null;
+  version: string; // "unknown" for entries recorded without one
+}
+
+export interface VersionChange {
+  after: VersionStats;
+  before: VersionStats;
+  changedAt: number; // Timestamp of the first request under the new version
+  dimension: VersionDimension;
+}
+
+export async function getVersionStats(
+  dimension: VersionDimension,
+  since?: number,
+): Promise<VersionStats[]> {
+  return invoke("get_version_stats", { dimension, since });
+}
+
+// One entry per version change between since and until; empty if the version didn't change
+export async function getVersionComparison(
+  dimension: VersionDimension,
+  since?: number,
+  until?: number,
+): Promise<VersionChange[]> {
+  return invoke("get_version_comparison", { dimension, since, until });
+}
+
+// Estimated spend per usage day, provider or model from the cost ledger
+export interface CostLedgerRow {
+  cost: number; // In the display currency
+  costUsd: number;
+  formatted: string; // e.g. "€1.84"
+  key: string; // Day, provider, model or tag; "untracked" for spend from before the ledger
+  requests: number;
+  tokensIn: number;
+  tokensOut: number;
+}
+
+export interface CostLedgerView {
+  currency: string;
+  formattedTotal: string;
+  from: string | null; // First day included; null for "all"
+  granularity?: SeriesGranularity; // Day rows up to monthlyUntil are whole months
+  groupBy: "day" | "model" | "provider" | "tag";
+  range: CostRange;
+  rate: number; // Display currency units per US dollar
+  rows: CostLedgerRow[];
+  to: string;
+  total: number;
+  totalUsd: number;
+}
+
+export type CostRange = "30d" | "7d" | "all" | "month" | "today";
+
+// Defaults to month to date, grouped by day
+export async function getCostLedger(
+  range?: CostRange,
+  groupBy?: "day" | "model" | "provider",
+): Promise<CostLedgerView> {
+  return invoke("get_cost_ledger", { groupBy, range });
+}
+
+// Spend per tag, most expensive first; a request with two tags counts twice
+export async function getCostByTag(range?: CostRange): Promise<CostLedgerView> {
+  return invoke("get_cost_by_tag", { range });
+}
+
+// Today's spend against the median of what the previous 14 days had spent by this hour
+export interface UsageAnomalyStatus {
+  alertedMultiplier: number | null; // Last alert of the day, if any
+  anomalous: boolean;
+  baselineDays: number;
+  baselineUsd: number | null; // null until a week of spend is known
+  day: string;
+  deviationUsd: number | null; // Median absolute deviation of the baseline days
+  multiplier: number | null; // spentTodayUsd over baselineUsd
+  spentTodayUsd: number;
+  threshold: number; // usageAnomalyMultiplier from the config; 0 = off
+  timestamp: number;
+  topModel: string | null;
+  topModelUsd: number;
+  topSession: string | null; // Among today's requests still in history
+  topSessionUsd: number;
+}
+
+export async function getAnomalyStatus(): Promise<UsageAnomalyStatus> {
+  return invoke("get_anomaly_status");
+}
+
+// Once per day, and again whenever the multiplier doubles
+export async function onUsageAnomaly(
+  callback: (status: UsageAnomalyStatus) => void,
+): Promise<UnlistenFn> {
+  return listen<UsageAnomalyStatus>("usage-anomaly", (event) => callback(event.payload));
+}
+
+// CLIProxyAPI moved to another project or model after a quota error
+export interface QuotaSwitchEvent {
+  account: string | null;
+  from: string | null;
+  kind: "project" | "model";
+  provider: string;
+  timestamp: number;
+  to: string;
+}
+
+// Quota switches in a time range (ms timestamps, either end open), oldest first
+export async function getQuotaEvents(since?: number, until?: number): Promise<QuotaSwitchEvent[]> {
+  return invoke("get_quota_events", { since, until });
+}
+
+export async function onQuotaSwitch(
+  callback: (event: QuotaSwitchEvent) => void,
+): Promise<UnlistenFn> {
+  return listen<QuotaSwitchEvent>("quota-switch", (event) => callback(event.payload));
 }
 
 export async function addRequestToHistory(request: RequestLog): Promise<RequestLog> {
   return invoke("add_request_to_history", { request });
 }
 
-export async function clearRequestHistory(): Promise<void> {
-  return invoke("clear_request_history");
+// With syntheticOnly, removes only requests generated by demo mode
+export async function clearRequestHistory(syntheticOnly?: boolean): Promise<void> {
+  return invoke("clear_request_history", { syntheticOnly });
+}
+
+// Demo mode: synthetic request traffic for UI work. Available in debug builds,
+// or with demoModeEnabled in the config. rate is requests per second.
+export async function startDemoMode(rate?: number, durationSecs?: number): Promise<void> {
+  return invoke("start_demo_mode", { durationSecs, rate });
+}
+
+export async function stopDemoMode(): Promise<void> {
+  return invoke("stop_demo_mode");
+}
+
+export async function isDemoModeRunning(): Promise<boolean> {
+  return invoke("is_demo_mode_running");
+}
+
+export async function onDemoModeChanged(callback: (running: boolean) => void): Promise<UnlistenFn> {
+  return listen<boolean>("demo-mode-changed", (event) => {
+    callback(event.payload);
+  });
 }
 
 // Sync usage statistics from CLIProxyAPI (fetches real token counts)
@@ -88,3 +327,14 @@ export interface ImportUsageResult {
 export async function importUsageStats(data: unknown): Promise<ImportUsageResult> {
   return invoke("import_usage_stats", { data });
 }
+
+// Import request history from a standalone CLIProxyAPI usage file
+export interface CliproxyImportReport {
+  imported: number;
+  skipped: number; // Already in history or imported before
+  warnings: string[]; // Malformed entries that were left out
+}
+
+export async function importCliproxyUsage(path: string): Promise<CliproxyImportReport> {
+  return invoke("import_cliproxy_usage", { path });
+}
@@ -1,5 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
-
+import { invoke } from "./invoke";
 import type { ThinkingBudgetSettings } from "./settings";
 
 export async function openUrlInBrowser(url: string): Promise<void> {
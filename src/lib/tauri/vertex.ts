@@ -0,0 +1,36 @@
+import { invoke } from "./invoke";
+
+// ============================================
+// Vertex regions and model availability
+// ============================================
+
+export interface VertexModelAvailability {
+  available: boolean;
+  detail?: string | null; // Why a probed model is unavailable
+  model: string;
+  publisher: string; // "google" or "anthropic"
+}
+
+export interface VertexRegionCapabilities {
+  error?: string | null;
+  models: VertexModelAvailability[];
+  region: string;
+}
+
+export interface VertexCapabilities {
+  clientEmail: string;
+  listError?: string | null; // Why listing fell back to probing
+  method: "list" | "probe";
+  projectId: string;
+  region?: string | null; // Region the proxy uses for this credential
+  regions: VertexRegionCapabilities[];
+}
+
+export async function getVertexCapabilities(projectId: string): Promise<VertexCapabilities> {
+  return invoke("get_vertex_capabilities", { projectId });
+}
+
+// Pass null to go back to the proxy default (us-central1)
+export async function setVertexRegion(projectId: string, region: string | null): Promise<void> {
+  return invoke("set_vertex_region", { projectId, region });
+}
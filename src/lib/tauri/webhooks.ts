@@ -0,0 +1,50 @@
+import { invoke } from "./invoke";
+
+// ============================================
+// Webhooks
+// ============================================
+
+export type WebhookEvent =
+  | "proxy-crashed"
+  | "rate-limited"
+  | "request-failed";
+
+export interface WebhookConfig {
+  enabled: boolean;
+  events: WebhookEvent[];
+  id: string; // Empty when creating; the backend assigns one
+  secret?: string | null; // Signs payloads (X-ProxyPal-Signature: sha256=<hex>)
+  url: string;
+}
+
+export interface WebhookDelivery {
+  attempts: number;
+  error: string | null;
+  event: string;
+  id: string;
+  status: number | null;
+  success: boolean;
+  timestamp: number;
+  webhookId: string;
+}
+
+export async function getWebhooks(): Promise<WebhookConfig[]> {
+  return invoke("get_webhooks");
+}
+
+export async function saveWebhook(webhook: WebhookConfig): Promise<WebhookConfig[]> {
+  return invoke("save_webhook", { webhook });
+}
+
+export async function deleteWebhook(id: string): Promise<WebhookConfig[]> {
+  return invoke("delete_webhook", { id });
+}
+
+export async function testWebhook(id: string): Promise<WebhookDelivery> {
+  return invoke("test_webhook", { id });
+}
+
+// Newest first
+export async function getWebhookDeliveryLog(): Promise<WebhookDelivery[]> {
+  return invoke("get_webhook_delivery_log");
+}
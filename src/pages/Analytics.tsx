@@ -10,7 +10,14 @@ import {
   type HeatmapData,
 } from "../components/charts";
 import { useI18n } from "../i18n";
-import { exportUsageStats, getUsageStats, importUsageStats, type UsageStats } from "../lib/tauri";
+import {
+  exportUsageStats,
+  getUsageStats,
+  importUsageStats,
+  onUsageDayChanged,
+  type UsageStats,
+} from "../lib/tauri";
+import { appStore } from "../stores/app";
 import { toastStore } from "../stores/toast";
 
 // Register Chart.js components
@@ -292,6 +299,7 @@ function StatCard(props: {
 
 export function Analytics() {
   const { t } = useI18n();
+  const { config } = appStore;
   const [stats, setStats] = createSignal<UsageStats | null>(null);
   const [loading, setLoading] = createSignal(true);
   const [timeRange, setTimeRange] = createSignal<TimeRange>("day");
@@ -375,9 +383,11 @@ export function Analytics() {
     }
   };
 
-  // Fetch on mount
-  onMount(() => {
+  // Fetch on mount, and again when "today" moves on
+  onMount(async () => {
     fetchStats();
+    const unlisten = await onUsageDayChanged(() => fetchStats());
+    onCleanup(unlisten);
   });
 
   // Filter data by date preset and fill in missing days/hours with zeros
@@ -776,7 +786,7 @@ export function Analytics() {
                   <span>
                     {t("analytics.troubleshooting.toolConfigured")}{" "}
                     <code class="rounded bg-gray-200 px-1 py-0.5 text-xs dark:bg-gray-700">
-                      http://localhost:8317/v1
+                      http://localhost:{config().port}/v1
                     </code>
                   </span>
                 </li>
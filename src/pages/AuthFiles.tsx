@@ -1,4 +1,4 @@
-import { open } from "@tauri-apps/plugin-dialog";
+import { open, save } from "@tauri-apps/plugin-dialog";
 import { createEffect, createSignal, For, Show } from "solid-js";
 import { EmptyState } from "../components/EmptyState";
 import { Button } from "../components/ui";
@@ -8,8 +8,14 @@ import {
   deleteAllAuthFiles,
   deleteAuthFile,
   downloadAuthFile,
+  exportCredentials,
   getAuthFiles,
+  importCredentials,
+  PASSPHRASE_CANCELLED,
+  pollOAuthStatus,
+  reauthCredential,
   refreshAuthStatus,
+  refreshProviderToken,
   toggleAuthFile,
   uploadAuthFile,
 } from "../lib/tauri";
@@ -72,6 +78,52 @@ export function AuthFilesPage() {
     }
   };
 
+  // Encrypted copies of every auth file, to move accounts to another machine
+  const handleExport = async () => {
+    const path = await save({
+      defaultPath: `proxypal-credentials-${new Date().toISOString().split("T")[0]}.json`,
+      filters: [{ extensions: ["json"], name: "JSON" }],
+    });
+    if (!path) {
+      return;
+    }
+    try {
+      const result = await exportCredentials(path);
+      toastStore.success(
+        t("authFiles.toasts.credentialsExported", { count: result.files.length, path }),
+      );
+    } catch (error) {
+      if (String(error) !== PASSPHRASE_CANCELLED) {
+        toastStore.error(t("authFiles.toasts.failedToExportCredentials"), String(error));
+      }
+    }
+  };
+
+  const handleImport = async () => {
+    const selected = await open({
+      filters: [{ extensions: ["json"], name: "JSON" }],
+      multiple: false,
+    });
+    if (!selected) {
+      return;
+    }
+    try {
+      const result = await importCredentials(selected);
+      toastStore.success(
+        t("authFiles.toasts.credentialsImported", {
+          count: result.files.length,
+          skipped: result.skipped.length,
+        }),
+      );
+      await refreshAuthStatus();
+      loadFiles();
+    } catch (error) {
+      if (String(error) !== PASSPHRASE_CANCELLED) {
+        toastStore.error(t("authFiles.toasts.failedToImportCredentials"), String(error));
+      }
+    }
+  };
+
   const handleUpload = async () => {
     try {
       const selected = await open({
@@ -243,6 +295,55 @@ export function AuthFilesPage() {
     }
   };
 
+  const [refreshingFile, setRefreshingFile] = createSignal<string | null>(null);
+
+  // Wait for a browser sign-in that replaces the credential, for up to 10 minutes
+  const waitForReauth = async (oauthState: string) => {
+    const deadline = Date.now() + 10 * 60 * 1000;
+    while (Date.now() < deadline) {
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      if (await pollOAuthStatus(oauthState)) {
+        return true;
+      }
+    }
+    return false;
+  };
+
+  const handleRefreshToken = async (file: AuthFile) => {
+    setRefreshingFile(file.id);
+    try {
+      let [result] = await refreshProviderToken(file.name);
+      if (result?.status === "reauth-needed") {
+        if (!confirm(t("authFiles.modals.reauthConfirm", { file: file.name }))) {
+          return;
+        }
+        result = await reauthCredential(file.name);
+      }
+      if (result?.status === "failed") {
+        toastStore.error(t("authFiles.toasts.failedToRefreshToken"), result.error ?? undefined);
+      } else if (result?.status === "reauth-started" && result.oauthState) {
+        toastStore.info(t("authFiles.toasts.signInToRenew", { file: file.name }));
+        if (await waitForReauth(result.oauthState)) {
+          toastStore.success(t("authFiles.toasts.credentialRenewed"));
+        }
+      } else if (result) {
+        toastStore.success(
+          t("authFiles.toasts.tokenRefreshed"),
+          result.expiresAt
+            ? t("authFiles.toasts.validUntil", {
+                date: new Date(result.expiresAt).toLocaleString(),
+              })
+            : undefined,
+        );
+      }
+      loadFiles();
+    } catch (error) {
+      toastStore.error(t("authFiles.toasts.failedToRefreshToken"), String(error));
+    } finally {
+      setRefreshingFile(null);
+    }
+  };
+
   const handleDownload = async (file: AuthFile) => {
     try {
       const path = await downloadAuthFile(file.id, file.name);
@@ -352,6 +453,14 @@ export function AuthFilesPage() {
                 {t("authFiles.actions.deleteAll")}
               </Button>
             </Show>
+            <Show when={files().length > 0}>
+              <Button onClick={handleExport} size="sm" variant="ghost">
+                {t("authFiles.actions.export")}
+              </Button>
+            </Show>
+            <Button onClick={handleImport} size="sm" variant="ghost">
+              {t("authFiles.actions.import")}
+            </Button>
             <Button onClick={handleUpload} size="sm" variant="primary">
               <svg class="mr-1.5 h-4 w-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                 <path
@@ -623,6 +732,29 @@ export function AuthFilesPage() {
 
                         {/* Right: Actions */}
                         <div class="flex shrink-0 items-center gap-1">
+                          <Show when={file.refreshable}>
+                            <button
+                              class="rounded-lg p-2 text-gray-500 transition-colors hover:bg-gray-100 hover:text-gray-700 disabled:opacity-50 dark:text-gray-400 dark:hover:bg-gray-700 dark:hover:text-gray-200"
+                              disabled={refreshingFile() === file.id}
+                              onClick={() => handleRefreshToken(file)}
+                              title={t("authFiles.actions.refresh")}
+                            >
+                              <svg
+                                class="h-5 w-5"
+                                classList={{ "animate-spin": refreshingFile() === file.id }}
+                                fill="none"
+                                stroke="currentColor"
+                                viewBox="0 0 24 24"
+                              >
+                                <path
+                                  d="M4 4v5h.582m15.356 2A8.001 8.001 0 004.582 9m0 0H9m11 11v-5h-.581m0 0a8.003 8.003 0 01-15.357-2m15.357 2H15"
+                                  stroke-linecap="round"
+                                  stroke-linejoin="round"
+                                  stroke-width="2"
+                                />
+                              </svg>
+                            </button>
+                          </Show>
                           <button
                             class="rounded-lg p-2 text-gray-500 transition-colors hover:bg-gray-100 hover:text-gray-700 dark:text-gray-400 dark:hover:bg-gray-700 dark:hover:text-gray-200"
                             onClick={() => handleDownload(file)}
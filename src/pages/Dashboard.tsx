@@ -3,7 +3,9 @@ import { createSignal, For, onCleanup, onMount, Show } from "solid-js";
 import { ApiEndpoint } from "../components/ApiEndpoint";
 import { openCommandPalette } from "../components/CommandPalette";
 import { CopilotCard } from "../components/CopilotCard";
+import { LastSessionBanner } from "../components/dashboard/LastSessionBanner";
 import { OnboardingChecklist } from "../components/dashboard/OnboardingChecklist";
+import { PendingOAuthList } from "../components/dashboard/PendingOAuthList";
 import { ProviderSection } from "../components/dashboard/ProviderSection";
 import {
   ClaudeQuotaWidget,
@@ -23,6 +25,7 @@ import {
   type AgentConfigResult,
   type AvailableModel,
   appendToShellProfile,
+  cancelOAuth,
   completeOAuth,
   type CopilotConfig,
   type DeviceCodeResponse,
@@ -34,7 +37,8 @@ import {
   importVertexCredential,
   type OAuthUrlResponse,
   onOAuthCallback,
-  onRequestLog,
+  onRequestLogBatch,
+  onUsageDayChanged,
   openUrlInBrowser,
   type Provider,
   pollOAuthStatus,
@@ -244,7 +248,7 @@ export function DashboardPage() {
 
     // Listen for new requests and refresh stats only
     // History is handled by RequestMonitor via centralized store
-    const unlisten = await onRequestLog(async () => {
+    const unlisten = await onRequestLogBatch(async () => {
       // Debounce: wait 1 second after request to allow backend to process
       setTimeout(async () => {
         try {
@@ -260,7 +264,8 @@ export function DashboardPage() {
     // Listen for deep-link OAuth callback (faster than polling)
     const unlistenOAuth = await onOAuthCallback(async (data) => {
       const provider = oauthModalProvider();
-      if (!provider || data.provider !== provider || oauthCompleted()) {
+      // Callbacks for other sign-ins belong to their own flows
+      if (!provider || data.state !== oauthUrlData()?.state || oauthCompleted()) {
         return;
       }
       setOauthCompleted(true);
@@ -291,10 +296,20 @@ export function DashboardPage() {
       }
     });
 
+    // Today's figures start over at the usage day boundary
+    const unlistenDay = await onUsageDayChanged(async () => {
+      try {
+        setStats(await getUsageStats());
+      } catch (error) {
+        console.error("Failed to refresh stats after day change:", error);
+      }
+    });
+
     // Cleanup listener on unmount
     onCleanup(() => {
       unlisten();
       unlistenOAuth();
+      unlistenDay();
     });
   });
 
@@ -592,6 +607,10 @@ export function DashboardPage() {
   };
 
   const handleCancelOAuth = () => {
+    const urlData = oauthUrlData();
+    if (urlData?.state && !oauthCompleted()) {
+      cancelOAuth(urlData.state).catch(() => {}); // Already expired is fine
+    }
     setOauthModalProvider(null);
     setOauthUrlData(null);
     setOauthLoading(false);
@@ -781,6 +800,9 @@ export function DashboardPage() {
           {/* === PikaAI Banner === */}
           <PikaAiBanner />
 
+          {/* === Last session ended uncleanly === */}
+          <LastSessionBanner />
+
           {/* === ZONE 1: Onboarding (shows until setup complete) === */}
           <OnboardingChecklist
             firstDisconnectedProvider={disconnectedProviders()[0]?.provider}
@@ -848,6 +870,7 @@ export function DashboardPage() {
             proxyRunning={proxyStatus().running}
             recentlyConnected={recentlyConnected()}
           />
+          <PendingOAuthList providerName={getProviderName} />
 
           {/* === ZONE 3.5: Antigravity Quota === */}
           <QuotaWidget authStatus={authStatus()} />
@@ -872,7 +895,11 @@ export function DashboardPage() {
           />
 
           {/* === ZONE 4: API Endpoint === */}
-          <ApiEndpoint endpoint={proxyStatus().endpoint} running={proxyStatus().running} />
+          <ApiEndpoint
+            endpoint={proxyStatus().endpoint}
+            lastKnownEndpoint={proxyStatus().lastKnownEndpoint}
+            running={proxyStatus().running}
+          />
 
           {/* Config Modal */}
           <Show when={configResult()}>
@@ -9,6 +9,7 @@ import { OpenAIProviderSettings } from "../components/settings/OpenAIProviderSet
 import { ProvidersSettings } from "../components/settings/ProvidersSettings";
 import { ProxySettings } from "../components/settings/ProxySettings";
 import { SshSettings } from "../components/settings/SshSettings";
+import { StorageSettings } from "../components/settings/StorageSettings";
 import { ThinkingReasoningSettings } from "../components/settings/ThinkingReasoningSettings";
 import { Button, Switch } from "../components/ui";
 import { LOCALE_LABELS, LOCALE_OPTIONS, useI18n } from "../i18n";
@@ -18,10 +19,12 @@ type SettingsTab = "general" | "providers" | "models" | "advanced" | "ssh" | "cl
 import {
   type AgentConfigResult,
   type AvailableModel,
+  type StorageInfo,
   appendToShellProfile,
   getAvailableModels,
   getCloseToTray,
   getGptReasoningModels,
+  getStorageInfo,
   saveConfig,
   setCloseToTray,
   startProxy,
@@ -59,6 +62,9 @@ export function SettingsPage() {
     }
   });
 
+  // Shown as a warning when settings can't be saved to the config directory, and in Storage
+  const [storageInfo, setStorageInfo] = createSignal<StorageInfo | null>(null);
+
   // Fetch app version on mount
   onMount(async () => {
     try {
@@ -68,6 +74,12 @@ export function SettingsPage() {
       console.error("Failed to get app version:", error);
     }
 
+    try {
+      setStorageInfo(await getStorageInfo());
+    } catch (error) {
+      console.error("Failed to get storage info:", error);
+    }
+
     // Load GPT reasoning models from backend (single source of truth)
     try {
       const gptModels = await getGptReasoningModels();
@@ -425,6 +437,28 @@ export function SettingsPage() {
       {/* Main content */}
       <main class="flex-1 overflow-y-auto p-4 sm:p-6">
         <div class="animate-stagger mx-auto max-w-xl space-y-4 sm:space-y-6">
+          <Show when={storageInfo()?.degradedReason || storageInfo()?.writable === false}>
+            <div class="rounded-lg border border-amber-200 bg-amber-50 p-3 text-sm text-amber-800 dark:border-amber-800 dark:bg-amber-900/20 dark:text-amber-300">
+              <p class="font-medium">Settings may not be saved</p>
+              <p class="mt-1">
+                {storageInfo()?.degradedReason ?? "The storage directory is not writable."}
+              </p>
+              <Show when={storageInfo()?.configDir !== storageInfo()?.preferredDir}>
+                <p class="mt-1">
+                  Files are stored in <code class="break-all">{storageInfo()?.configDir}</code>{" "}
+                  instead of <code class="break-all">{storageInfo()?.preferredDir}</code>.
+                </p>
+              </Show>
+            </div>
+          </Show>
+
+          <Show when={storageInfo()?.portable}>
+            <div class="rounded-lg border border-blue-200 bg-blue-50 p-3 text-sm text-blue-700 dark:border-blue-800 dark:bg-blue-900/20 dark:text-blue-300">
+              Portable mode: all data is kept in{" "}
+              <code class="break-all">{storageInfo()?.configDir}</code>.
+            </div>
+          </Show>
+
           {/* General settings */}
           <div class="space-y-4" classList={{ hidden: activeTab() !== "general" }}>
             <h2 class="text-sm font-semibold uppercase tracking-wider text-gray-600 dark:text-gray-400">
@@ -555,6 +589,15 @@ export function SettingsPage() {
             />
           </div>
 
+          {/* Storage */}
+          <div classList={{ hidden: activeTab() !== "advanced" }}>
+            <StorageSettings
+              proxyRunning={appStore.proxyStatus().running}
+              setStorageInfo={setStorageInfo}
+              storageInfo={storageInfo}
+            />
+          </div>
+
           {/* Copilot Detection */}
           <div classList={{ hidden: activeTab() !== "providers" }}>
             <ProvidersSettings config={config()} setConfig={setConfig} />
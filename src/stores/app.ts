@@ -1,22 +1,33 @@
+import { message } from "@tauri-apps/plugin-dialog";
 import { createRoot, createSignal, onCleanup } from "solid-js";
 import { detectSystemLocale, normalizeLocale, resolveInitialLocale } from "../i18n/locale";
 import {
+  DEFAULT_PORT,
+  forceQuit,
   getAuthStatus,
-  getConfig,
   getProxyStatus,
   migrateAmpModelMappings,
   onAuthStatusChanged,
   onCloudflareStatusChanged,
+  onConfirmQuit,
+  onDeepLinkAction,
+  onIdleWarning,
+  onNavigate,
+  onProxyStartFailed,
   onProxyStatusChanged,
   onSshStatusChanged,
   onTrayToggleProxy,
+  onUsageAnomaly,
+  quitWhenIdle,
   refreshAuthStatus,
   saveConfig,
   showSystemNotification,
   startProxy,
   stopProxy,
   syncUsageFromProxy,
+  waitForStartupState,
 } from "../lib/tauri";
+import { toastStore } from "./toast";
 
 import type {
   AppConfig,
@@ -29,8 +40,8 @@ import type {
 function createAppStore() {
   // Proxy state
   const [proxyStatus, setProxyStatus] = createSignal<ProxyStatus>({
-    endpoint: "http://localhost:8317/v1",
-    port: 8317,
+    endpoint: "",
+    port: DEFAULT_PORT,
     running: false,
   });
 
@@ -38,6 +49,7 @@ function createAppStore() {
   const [authStatus, setAuthStatus] = createSignal<AuthStatus>({
     antigravity: 0,
     claude: 0,
+    deepseek: 0,
     gemini: 0,
     iflow: 0,
     kimi: 0,
@@ -45,6 +57,7 @@ function createAppStore() {
     openai: 0,
     qwen: 0,
     vertex: 0,
+    zhipu: 0,
   });
 
   // Config
@@ -69,7 +82,7 @@ function createAppStore() {
     locale: "en",
     loggingToFile: false,
     logsMaxTotalSizeMb: 100,
-    port: 8317,
+    port: DEFAULT_PORT,
     proxyUrl: "",
     quotaSwitchPreviewModel: false,
     quotaSwitchProject: false,
@@ -126,7 +139,12 @@ function createAppStore() {
       setIsLoading(true);
 
       // Load initial state from backend
-      const [proxyState, configState] = await Promise.all([getProxyStatus(), getConfig()]);
+      // Waits for the backend to finish loading settings from disk
+      const [proxyState, startupState] = await Promise.all([
+        getProxyStatus(),
+        waitForStartupState(),
+      ]);
+      const configState = startupState.config;
 
       updateProxyStatus(proxyState);
 
@@ -186,10 +204,68 @@ function createAppStore() {
             updateProxyStatus(status, true); // Show notification
           }
         } catch (error) {
+          // Outside a scheduled window: offer to override until the next boundary
+          if (shouldStart && String(error).includes("scheduled off")) {
+            const choice = await message(String(error), {
+              buttons: { cancel: "Cancel", ok: "Start anyway" },
+              title: "Proxy is scheduled off",
+            });
+            if (choice === "Start anyway") {
+              await startProxy(true)
+                .then((status) => updateProxyStatus(status, true))
+                .catch((e) => console.error("Failed to start proxy:", e));
+            }
+            return;
+          }
           console.error("Failed to toggle proxy:", error);
         }
       });
 
+      const unlistenConfirmQuit = await onConfirmQuit(async (inFlight) => {
+        const choice = await message(
+          `${inFlight} request${inFlight === 1 ? " is" : "s are"} still in progress. Quitting now will cut off the response.`,
+          {
+            buttons: { cancel: "Cancel", no: "Wait for requests", yes: "Quit anyway" },
+            title: "Quit ProxyPal?",
+          },
+        );
+        if (choice === "Quit anyway") {
+          await forceQuit();
+        } else if (choice === "Wait for requests") {
+          await quitWhenIdle();
+        }
+      });
+
+      const unlistenDeepLink = await onDeepLinkAction((result) => {
+        if (result.success) {
+          toastStore.success(result.message);
+        } else {
+          toastStore.error("Link action failed", result.message);
+        }
+      });
+
+      const unlistenIdle = await onIdleWarning((warning) => {
+        toastStore.warning(
+          `No requests for ${warning.idleMinutes} minutes`,
+          `${warning.configuredAgents.join(", ")} may be calling the provider directly. Run the endpoint audit in Agent Setup.`,
+        );
+      });
+
+      const unlistenAnomaly = await onUsageAnomaly((status) => {
+        const culprit = status.topSession ?? status.topModel;
+        toastStore.warning(
+          `Spent ${(status.multiplier ?? 0).toFixed(1)}x your usual today`,
+          `$${status.spentTodayUsd.toFixed(2)} against $${(status.baselineUsd ?? 0).toFixed(2)} by this time${culprit ? `, mostly ${culprit}` : ""}. An agent may be stuck in a loop.`,
+        );
+      });
+
+      const unlistenNavigate = await onNavigate((page) => {
+        const pages = ["dashboard", "settings", "api-keys", "auth-files", "logs", "analytics"];
+        if (pages.includes(page)) {
+          setCurrentPage(page as ReturnType<typeof currentPage>);
+        }
+      });
+
       const unlistenSsh = await onSshStatusChanged((status) => {
         setSshStatus((prev) => ({ ...prev, [status.id]: status }));
       });
@@ -203,15 +279,12 @@ function createAppStore() {
         unlistenCf();
       });
 
-      // Auto-start proxy if configured
-      if (nextConfig.autoStart) {
-        try {
-          const status = await startProxy();
-          updateProxyStatus(status);
-        } catch (error) {
-          console.error("Failed to auto-start proxy:", error);
-        }
-      }
+      // The backend auto-starts the proxy when autoStart is set; re-sync in case it
+      // came up before the status listener was registered
+      const unlistenStartFailed = await onProxyStartFailed((error) => {
+        console.error("Failed to auto-start proxy:", error);
+      });
+      updateProxyStatus(await getProxyStatus());
 
       // Sync usage data from CLIProxyAPI on startup
       try {
@@ -226,6 +299,12 @@ function createAppStore() {
         unlistenAuth();
         unlistenTray();
         unlistenSsh();
+        unlistenStartFailed();
+        unlistenNavigate();
+        unlistenConfirmQuit();
+        unlistenDeepLink();
+        unlistenIdle();
+        unlistenAnomaly();
       });
     } catch (error) {
       console.error("Failed to initialize app:", error);
@@ -103,10 +103,19 @@ export function clearHistory(): void {
   });
 }
 
+// Drop demo-mode requests, keeping real ones
+export function clearSyntheticHistory(): void {
+  setRequestHistory((prev) => ({
+    ...prev,
+    requests: prev.requests.filter((r) => !r.synthetic),
+  }));
+}
+
 // Export the raw signal for components that need full access
 export const requestStore = {
   addRequest,
   clearHistory,
+  clearSyntheticHistory,
   history: requestHistory,
   isLoading,
   loadHistory,